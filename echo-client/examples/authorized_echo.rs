@@ -0,0 +1,65 @@
+//! Writes to an authorized buffer: `cargo run --example authorized_echo -- <ECHO_PROGRAM_ID>`
+
+use echo::{instruction::EchoInstruction, state::AUTH_BUFF_HEADER_SIZE};
+use echo_client::{find_authorized_echo_pda, EchoClient};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use std::{env, error::Error, str::FromStr};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let program_id = Pubkey::from_str(&env::args().nth(1).ok_or("missing program id")?)?;
+    let client = EchoClient::new(
+        RpcClient::new_with_commitment(
+            "http://localhost:8899".to_string(),
+            CommitmentConfig::confirmed(),
+        ),
+        program_id,
+    );
+
+    // the authority pays for its own buffer
+    let authority = Keypair::new();
+    let airdrop = client
+        .rpc_client()
+        .request_airdrop(&authority.pubkey(), LAMPORTS_PER_SOL)?;
+    client.rpc_client().poll_for_signature(&airdrop)?;
+
+    let (buffer, _) = find_authorized_echo_pda(&program_id, &authority.pubkey(), 0);
+    client.send_instructions(
+        &authority,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::InitializeAuthorizedEcho {
+                buffer_seed: 0,
+                buffer_size: AUTH_BUFF_HEADER_SIZE + 16,
+                label: "example".to_string(),
+                permanent_delegate: None,
+                write_fee_lamports: 0,
+                expiry_slot: 0,
+                crank_bounty_bps: 0,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )?;
+
+    let signature =
+        client.authorized_echo_write(&authority, &authority, &buffer, b"hello echo".to_vec())?;
+    println!("Authorized echo: {}", signature);
+    println!(
+        "Payload: {:?}",
+        &client.rpc_client().get_account_data(&buffer)?[AUTH_BUFF_HEADER_SIZE..]
+    );
+
+    Ok(())
+}
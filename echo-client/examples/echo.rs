@@ -0,0 +1,55 @@
+//! Writes to a plain echo buffer: `cargo run --example echo -- <ECHO_PROGRAM_ID>`
+
+use echo_client::EchoClient;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+use std::{env, error::Error, str::FromStr};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let program_id = Pubkey::from_str(&env::args().nth(1).ok_or("missing program id")?)?;
+    let client = EchoClient::new(
+        RpcClient::new_with_commitment(
+            "http://localhost:8899".to_string(),
+            CommitmentConfig::confirmed(),
+        ),
+        program_id,
+    );
+
+    let payer = Keypair::new();
+    let airdrop = client
+        .rpc_client()
+        .request_airdrop(&payer.pubkey(), LAMPORTS_PER_SOL)?;
+    client.rpc_client().poll_for_signature(&airdrop)?;
+
+    // the echo buffer is a zeroed account owned by the Echo Program
+    let buffer = Keypair::new();
+    let rent = client
+        .rpc_client()
+        .get_minimum_balance_for_rent_exemption(16)?;
+    client.send_instructions(
+        &payer,
+        &[system_instruction::create_account(
+            &payer.pubkey(),
+            &buffer.pubkey(),
+            rent,
+            16,
+            &program_id,
+        )],
+        &[&buffer],
+    )?;
+
+    let signature = client.echo_write(&payer, &buffer.pubkey(), b"hello echo".to_vec())?;
+    println!("Echo: {}", signature);
+    println!(
+        "Buffer: {:?}",
+        client.rpc_client().get_account_data(&buffer.pubkey())?
+    );
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+//! Pays tokens to write to a vending machine buffer:
+//! `cargo run --example vending_machine_echo -- <ECHO_PROGRAM_ID>`
+
+use echo::{instruction::EchoInstruction, state::VENDING_MACHINE_BUFF_HEADER_SIZE};
+use echo_client::{find_vending_machine_echo_pda, EchoClient};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+};
+use spl_token::state::{Account as TokenAccount, Mint};
+use std::{env, error::Error, str::FromStr};
+
+const PRICE: u64 = 5;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let program_id = Pubkey::from_str(&env::args().nth(1).ok_or("missing program id")?)?;
+    let client = EchoClient::new(
+        RpcClient::new_with_commitment(
+            "http://localhost:8899".to_string(),
+            CommitmentConfig::confirmed(),
+        ),
+        program_id,
+    );
+    let rpc_client = client.rpc_client();
+
+    // the payer is also the mint authority, creating the vending machine requires its signature
+    let payer = Keypair::new();
+    let airdrop = rpc_client.request_airdrop(&payer.pubkey(), LAMPORTS_PER_SOL)?;
+    rpc_client.poll_for_signature(&airdrop)?;
+
+    let mint = Keypair::new();
+    let user = Keypair::new();
+    let user_token_account = Keypair::new();
+    client.send_instructions(
+        &payer,
+        &[
+            system_instruction::create_account(
+                &payer.pubkey(),
+                &mint.pubkey(),
+                rpc_client.get_minimum_balance_for_rent_exemption(Mint::LEN)?,
+                Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &payer.pubkey(),
+                None,
+                0,
+            )?,
+            system_instruction::create_account(
+                &payer.pubkey(),
+                &user_token_account.pubkey(),
+                rpc_client.get_minimum_balance_for_rent_exemption(TokenAccount::LEN)?,
+                TokenAccount::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account(
+                &spl_token::id(),
+                &user_token_account.pubkey(),
+                &mint.pubkey(),
+                &user.pubkey(),
+            )?,
+            spl_token::instruction::mint_to(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &user_token_account.pubkey(),
+                &payer.pubkey(),
+                &[],
+                PRICE,
+            )?,
+        ],
+        &[&mint, &user_token_account],
+    )?;
+
+    let (buffer, _) = find_vending_machine_echo_pda(&program_id, &mint.pubkey(), PRICE);
+    client.send_instructions(
+        &payer,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::InitializeVendingMachineEcho {
+                price: PRICE,
+                buffer_size: VENDING_MACHINE_BUFF_HEADER_SIZE + 16,
+                payment_mode: 0,
+                price_per_byte: 0,
+                require_ata: false,
+                max_uses: 0,
+                tiers: vec![],
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(mint.pubkey(), false),
+                AccountMeta::new_readonly(payer.pubkey(), true),
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )?;
+
+    // the user holds the tokens, the payer only pays the transaction fee
+    let signature = client.vending_machine_echo_write(
+        &payer,
+        &user,
+        &user_token_account.pubkey(),
+        &mint.pubkey(),
+        &buffer,
+        b"hello echo".to_vec(),
+    )?;
+    println!("Vending machine echo: {}", signature);
+    println!(
+        "Payload: {:?}",
+        &rpc_client.get_account_data(&buffer)?[VENDING_MACHINE_BUFF_HEADER_SIZE..]
+    );
+
+    Ok(())
+}
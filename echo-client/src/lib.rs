@@ -0,0 +1,191 @@
+use borsh::BorshDeserialize;
+use echo::{
+    instruction::EchoInstruction,
+    pda,
+    state::{VendingMachineBufferHeader, VendingMachineStats, VENDING_MACHINE_BUFF_HEADER_SIZE},
+};
+use solana_client::{
+    client_error::{ClientError, Result as ClientResult},
+    rpc_client::RpcClient,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+
+/// Address and bump seed of the authorized buffer of `authority` created with `buffer_seed`
+pub fn find_authorized_echo_pda(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    buffer_seed: u64,
+) -> (Pubkey, u8) {
+    pda::find_authorized_echo_address(program_id, authority, buffer_seed)
+}
+
+/// Address and bump seed of the vending machine buffer for `mint` at `price`
+pub fn find_vending_machine_echo_pda(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    price: u64,
+) -> (Pubkey, u8) {
+    pda::find_vending_machine_echo_address(program_id, mint, price)
+}
+
+/// `Echo` instruction copying `data` into `buffer`
+pub fn echo_instruction(program_id: &Pubkey, buffer: &Pubkey, data: Vec<u8>) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::Echo { data },
+        vec![AccountMeta::new(*buffer, false)],
+    )
+}
+
+/// `AuthorizedEcho` instruction for a buffer without write fee
+pub fn authorized_echo_instruction(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    buffer: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::AuthorizedEcho { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// `VendingMachineEcho` instruction for a vending machine burning its payments
+pub fn vending_machine_echo_instruction(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    user_token_account: &Pubkey,
+    mint: &Pubkey,
+    buffer: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::VendingMachineEcho { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(*user_token_account, false),
+            AccountMeta::new(*mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+/// Builds, signs and sends the instructions of the Echo Program deployed at `program_id`
+pub struct EchoClient {
+    rpc_client: RpcClient,
+    program_id: Pubkey,
+}
+
+impl EchoClient {
+    pub fn new(rpc_client: RpcClient, program_id: Pubkey) -> Self {
+        Self {
+            rpc_client,
+            program_id,
+        }
+    }
+
+    pub fn rpc_client(&self) -> &RpcClient {
+        &self.rpc_client
+    }
+
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    /// Sends `instructions` in a single transaction paid by `payer`, `signers` are the other keys that must sign.
+    pub fn send_instructions(
+        &self,
+        payer: &Keypair,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> ClientResult<Signature> {
+        let mut all_signers = vec![payer];
+        all_signers.extend_from_slice(signers);
+
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            self.rpc_client.get_latest_blockhash()?,
+        );
+
+        self.rpc_client.send_and_confirm_transaction(&transaction)
+    }
+
+    pub fn echo_write(
+        &self,
+        payer: &Keypair,
+        buffer: &Pubkey,
+        data: Vec<u8>,
+    ) -> ClientResult<Signature> {
+        self.send_instructions(
+            payer,
+            &[echo_instruction(&self.program_id, buffer, data)],
+            &[],
+        )
+    }
+
+    pub fn authorized_echo_write(
+        &self,
+        payer: &Keypair,
+        authority: &Keypair,
+        buffer: &Pubkey,
+        data: Vec<u8>,
+    ) -> ClientResult<Signature> {
+        self.send_instructions(
+            payer,
+            &[authorized_echo_instruction(
+                &self.program_id,
+                &authority.pubkey(),
+                buffer,
+                data,
+            )],
+            &[authority],
+        )
+    }
+
+    /// Reads the totals of the vending machine at `buffer` with a single `getAccountInfo`.
+    pub fn vending_machine_stats(&self, buffer: &Pubkey) -> ClientResult<VendingMachineStats> {
+        let data = self.rpc_client.get_account_data(buffer)?;
+        let header = data
+            .get(..VENDING_MACHINE_BUFF_HEADER_SIZE)
+            .ok_or_else(|| {
+                ClientError::from(std::io::Error::from(std::io::ErrorKind::UnexpectedEof))
+            })?;
+        Ok(VendingMachineBufferHeader::try_from_slice(header)?.stats())
+    }
+
+    pub fn vending_machine_echo_write(
+        &self,
+        payer: &Keypair,
+        user: &Keypair,
+        user_token_account: &Pubkey,
+        mint: &Pubkey,
+        buffer: &Pubkey,
+        data: Vec<u8>,
+    ) -> ClientResult<Signature> {
+        self.send_instructions(
+            payer,
+            &[vending_machine_echo_instruction(
+                &self.program_id,
+                &user.pubkey(),
+                user_token_account,
+                mint,
+                buffer,
+                data,
+            )],
+            &[user],
+        )
+    }
+}
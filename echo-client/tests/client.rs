@@ -0,0 +1,147 @@
+#![cfg(feature = "test-bpf")]
+
+use {
+    echo::{instruction::EchoInstruction, state::AUTH_BUFF_HEADER_SIZE},
+    echo_client::{
+        echo_instruction, find_authorized_echo_pda, find_vending_machine_echo_pda, EchoClient,
+    },
+    solana_client::rpc_client::RpcClient,
+    solana_sdk::{
+        commitment_config::CommitmentConfig,
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        system_instruction, system_program,
+    },
+    solana_validator::test_validator::{TestValidator, TestValidatorGenesis},
+};
+
+/// Starts a local test validator running the Echo Program built by `cargo build-bpf` in `BPF_OUT_DIR`.
+fn start() -> (TestValidator, EchoClient, Keypair) {
+    let program_id = Pubkey::new_unique();
+    let (test_validator, payer) = TestValidatorGenesis::default()
+        .add_program("echo", program_id)
+        .start();
+    let rpc_client =
+        RpcClient::new_with_commitment(test_validator.rpc_url(), CommitmentConfig::confirmed());
+    (
+        test_validator,
+        EchoClient::new(rpc_client, program_id),
+        payer,
+    )
+}
+
+#[test]
+fn test_echo_write() {
+    let (_test_validator, client, payer) = start();
+
+    let buffer = Keypair::new();
+    let rent = client
+        .rpc_client()
+        .get_minimum_balance_for_rent_exemption(8)
+        .unwrap();
+    client
+        .send_instructions(
+            &payer,
+            &[system_instruction::create_account(
+                &payer.pubkey(),
+                &buffer.pubkey(),
+                rent,
+                8,
+                client.program_id(),
+            )],
+            &[&buffer],
+        )
+        .unwrap();
+
+    client
+        .echo_write(&payer, &buffer.pubkey(), vec![1, 2, 3])
+        .unwrap();
+    assert_eq!(
+        client
+            .rpc_client()
+            .get_account_data(&buffer.pubkey())
+            .unwrap(),
+        vec![1, 2, 3, 0, 0, 0, 0, 0]
+    );
+
+    // the buffer is no longer zeroed
+    assert!(client
+        .send_instructions(
+            &payer,
+            &[echo_instruction(
+                client.program_id(),
+                &buffer.pubkey(),
+                vec![4]
+            )],
+            &[],
+        )
+        .is_err());
+}
+
+#[test]
+fn test_authorized_echo_write() {
+    let (_test_validator, client, payer) = start();
+
+    let authority = Keypair::new();
+    let (buffer, _) = find_authorized_echo_pda(client.program_id(), &authority.pubkey(), 7);
+    client
+        .send_instructions(
+            &payer,
+            &[
+                system_instruction::transfer(&payer.pubkey(), &authority.pubkey(), 100_000_000),
+                Instruction::new_with_borsh(
+                    *client.program_id(),
+                    &EchoInstruction::InitializeAuthorizedEcho {
+                        buffer_seed: 7,
+                        buffer_size: AUTH_BUFF_HEADER_SIZE + 4,
+                        label: String::new(),
+                        permanent_delegate: None,
+                        write_fee_lamports: 0,
+                        expiry_slot: 0,
+                        crank_bounty_bps: 0,
+                    },
+                    vec![
+                        AccountMeta::new(buffer, false),
+                        AccountMeta::new(authority.pubkey(), true),
+                        AccountMeta::new_readonly(system_program::id(), false),
+                    ],
+                ),
+            ],
+            &[&authority],
+        )
+        .unwrap();
+
+    client
+        .authorized_echo_write(&payer, &authority, &buffer, vec![9; 8])
+        .unwrap();
+    let data = client.rpc_client().get_account_data(&buffer).unwrap();
+    assert_eq!(&data[AUTH_BUFF_HEADER_SIZE..], &[9; 4]);
+
+    // only the authority can write
+    let intruder = Keypair::new();
+    assert!(client
+        .authorized_echo_write(&payer, &intruder, &buffer, vec![1; 4])
+        .is_err());
+}
+
+#[test]
+fn test_find_vending_machine_echo_pda() {
+    let program_id = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let (pda, bump_seed) = find_vending_machine_echo_pda(&program_id, &mint, 5);
+    assert_eq!(
+        Pubkey::create_program_address(
+            &[
+                b"vending_machine",
+                mint.as_ref(),
+                &5u64.to_le_bytes(),
+                &[bump_seed]
+            ],
+            &program_id,
+        )
+        .unwrap(),
+        pda
+    );
+    assert_ne!(find_vending_machine_echo_pda(&program_id, &mint, 6).0, pda);
+}
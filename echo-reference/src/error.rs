@@ -26,6 +26,112 @@ pub enum EchoError {
     NotImplemented,
     #[error("Insufficient funds.")]
     InsufficientFunds,
+    #[error("Invalid account owner.")]
+    InvalidAccountOwner,
+    #[error("Unauthorized caller.")]
+    UnauthorizedCaller,
+    #[error("Condition not met.")]
+    ConditionNotMet,
+    #[error("Arithmetic overflow.")]
+    Overflow,
+    #[error("Arithmetic underflow.")]
+    Underflow,
+    #[error("Buffer full.")]
+    BufferFull,
+    #[error("Set full.")]
+    SetFull,
+    #[error("Duplicate member.")]
+    DuplicateMember,
+    #[error("Member not found.")]
+    MemberNotFound,
+    #[error("Commitment mismatch.")]
+    CommitmentMismatch,
+    #[error("Reveal too early.")]
+    RevealTooEarly,
+    #[error("Map full.")]
+    MapFull,
+    #[error("Registry full.")]
+    RegistryFull,
+    #[error("Snapshot already exists.")]
+    SnapshotAlreadyExists,
+    #[error("Sequence overflow.")]
+    SequenceOverflow,
+    #[error("Max writes exceeded.")]
+    MaxWritesExceeded,
+    #[error("Buffer not expired.")]
+    BufferNotExpired,
+    #[error("Hash mismatch.")]
+    HashMismatch,
+    #[error("Write in progress.")]
+    WriteInProgress,
+    #[error("Segment out of range.")]
+    SegmentOutOfRange,
+    #[error("Invalid mint authority.")]
+    InvalidMintAuthority,
+    #[error("Multi slot buffer already committed.")]
+    MultiSlotCommitted,
+    #[error("Multi slot buffer incomplete.")]
+    MultiSlotIncomplete,
+    #[error("Already voted.")]
+    AlreadyVoted,
+    #[error("No pending write.")]
+    NoPendingWrite,
+    #[error("Vending machine paused.")]
+    VendingMachinePaused,
+    #[error("Invalid signature.")]
+    InvalidSignature,
+    #[error("Invalid mint.")]
+    InvalidMint,
+    #[error("Token account frozen.")]
+    TokenAccountFrozen,
+    #[error("Invalid schema descriptor.")]
+    InvalidSchemaDescriptor,
+    #[error("Not an associated token account.")]
+    NotAssociatedTokenAccount,
+    #[error("Invalid Merkle proof.")]
+    InvalidMerkleProof,
+    #[error("Bid too low.")]
+    BidTooLow,
+    #[error("Auction ended.")]
+    AuctionEnded,
+    #[error("Auction not ended.")]
+    AuctionNotEnded,
+    #[error("Vault not empty.")]
+    VaultNotEmpty,
+    #[error("Mint mismatch.")]
+    MintMismatch,
+    #[error("Buffer frozen.")]
+    BufferFrozen,
+    #[error("Vending machine sold out.")]
+    VendingMachineSoldOut,
+    #[error("Fee treasury not initialized.")]
+    FeeTreasuryNotInitialized,
+    #[error("Data too large.")]
+    DataTooLarge,
+    #[error("Subscription expired.")]
+    SubscriptionExpired,
+    #[error("Mint decimals mismatch.")]
+    MintDecimalsMismatch,
+    #[error("Buffer expired.")]
+    BufferExpired,
+    #[error("Invalid collection.")]
+    InvalidCollection,
+    #[error("Time lock not expired.")]
+    TimeLockNotExpired,
+    #[error("Account already initialized.")]
+    AccountAlreadyInitialized,
+    #[error("Invalid nonce.")]
+    InvalidNonce,
+    #[error("Vending machine not started.")]
+    VendingMachineNotStarted,
+    #[error("Vending machine ended.")]
+    VendingMachineEnded,
+    #[error("Invalid VAA.")]
+    InvalidVAA,
+    #[error("Cooldown active.")]
+    CooldownActive,
+    #[error("Not whitelisted.")]
+    NotWhitelisted,
 }
 
 impl From<EchoError> for ProgramError {
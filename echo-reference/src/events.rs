@@ -0,0 +1,77 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{msg, pubkey::Pubkey};
+
+/// Prefix of the log lines carrying a base64 encoded, Borsh serialized `EchoEvent`
+pub const EVENT_LOG_PREFIX: &str = "ECHO_EVENT:";
+
+/// Emitted when data is written to an authorized buffer, or with a `len` of 0 when it is cleared
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct WriteEvent {
+    pub buffer: Pubkey,
+    pub authority: Pubkey,
+    pub len: u64,
+    pub slot: u64,
+    /// Sequence of the buffer after the write, always 0 for named buffers which don't keep one
+    pub sequence: u64,
+}
+
+/// Emitted when a user pays to write data to a vending machine buffer
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct VendingMachineWriteEvent {
+    pub buffer: Pubkey,
+    pub user: Pubkey,
+    pub len: u64,
+    pub price: u64,
+    pub slot: u64,
+}
+
+/// Emitted by `AuthorizedEchoHashOnly` with the full payload, of which the buffer only keeps the hash
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct HashOnlyWriteEvent {
+    pub buffer: Pubkey,
+    pub authority: Pubkey,
+    pub hash: [u8; 32],
+    pub data: Vec<u8>,
+    pub slot: u64,
+    pub sequence: u64,
+}
+
+/// Emitted after every paid write to a vending machine with its totals
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct VendingMachineStatsEvent {
+    pub buffer: Pubkey,
+    pub total_writes: u64,
+    pub total_burned: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum EchoEvent {
+    Write(WriteEvent),
+    VendingMachineWrite(VendingMachineWriteEvent),
+    HashOnlyWrite(HashOnlyWriteEvent),
+    VendingMachineStats(VendingMachineStatsEvent),
+}
+
+impl EchoEvent {
+    /// Logs the event so indexers can pick it up from the transaction logs
+    pub fn emit(&self) {
+        msg!(
+            "{}{}",
+            EVENT_LOG_PREFIX,
+            base64::encode(self.try_to_vec().unwrap())
+        );
+    }
+}
+
+/// Decodes every `EchoEvent` found in a transaction's log messages, skipping lines that aren't events.
+#[cfg(feature = "client")]
+pub fn parse_events(logs: &[String]) -> Vec<EchoEvent> {
+    logs.iter()
+        .filter_map(|line| {
+            let line = line.strip_prefix("Program log: ").unwrap_or(line);
+            let encoded = line.strip_prefix(EVENT_LOG_PREFIX)?;
+            let bytes = base64::decode(encoded).ok()?;
+            EchoEvent::try_from_slice(&bytes).ok()
+        })
+        .collect()
+}
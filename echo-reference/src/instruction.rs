@@ -1,5 +1,12 @@
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
 
+use crate::state::{
+    BatchInitConfig, MetadataUri, PriceTier, METADATA_DESCRIPTION_LEN, METADATA_NAME_LEN,
+};
+
+// instructions are deserialized once per transaction, boxing the metadata fields buys nothing
+#[allow(clippy::large_enum_variant)]
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub enum EchoInstruction {
     /// The contents of the data vector that is provided to the instruction will be copied into the echo_buffer account.
@@ -16,9 +23,30 @@ pub enum EchoInstruction {
     Echo { data: Vec<u8> },
     /// This instruction will allocate `buffer_size` bytes to the `authorized_buffer` account and assign it the Echo Program.
     ///
-    /// The first 9 bytes of authorized_buffer will be set with the following data:
+    /// The first 146 bytes of authorized_buffer will be set with the following data:
     ///     byte 0: bump_seed
     ///     bytes 1-8: buffer_seed
+    ///     bytes 9-12: cursor
+    ///     bytes 13-44: commitment
+    ///     bytes 45-52: commit_slot
+    ///     byte 53: has_commitment
+    ///     bytes 54-85: label, zero-padded UTF-8 of at most 32 bytes
+    ///     byte 86: has_permanent_delegate
+    ///     bytes 87-118: permanent_delegate
+    ///     bytes 119-126: sequence
+    ///     bytes 127-134: write_fee_lamports
+    ///     bytes 135-142: expiry_slot
+    ///     bytes 143-144: crank_bounty_bps
+    ///     byte 145: write_in_progress
+    ///
+    /// The `sequence` is incremented by every write, append or clear of the data past the header. Those instructions
+    /// also return the new `sequence` as little-endian return data, so callers invoking them through CPI learn it.
+    ///
+    /// The `permanent_delegate` can only be set here. It is meant for an emergency key that can still clear or close
+    /// the buffer if the `authority` key is lost, it can never write to the buffer.
+    ///
+    /// `buffer_size` must be greater than the header size, and the `authority` pays the rent exempt minimum for it,
+    /// failing with `InsufficientFunds` if it holds less.
     ///
     /// Accounts:
     /// | index | writable | signer | description                                                              |
@@ -26,69 +54,2423 @@ pub enum EchoInstruction {
     /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
     /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
     /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                               |
+    /// | 3     | ✅       | ❌     | registry: (optional) Registry of `authority` the buffer is added to       |
     InitializeAuthorizedEcho {
         buffer_seed: u64,
         buffer_size: usize,
+        label: String,
+        permanent_delegate: Option<Pubkey>,
+        /// Lamports the `authority` pays into the fee vault on every `AuthorizedEcho`, 0 for no fee
+        write_fee_lamports: u64,
+        /// Slot after which anyone can close the buffer with `CrankCloseExpired`, 0 for a buffer that never expires
+        expiry_slot: u64,
+        /// Share of the lamports paid to the cranker of an expired buffer, in basis points (at most 10000)
+        crank_bounty_bps: u16,
     },
     /// The contents of the data vector that is provided to the instruction will be copied into the `authorized_buffer` account
-    /// starting from index 9 (will NOT override the bump_seed and buffer_seed).
+    /// starting from index 146 (will NOT override the header).
     ///
     /// If the remaining `authorized_buffer` account length ( N ) is smaller than the length of `data`, copy the first N bytes
     /// of data into `authorized_buffer`.
     ///
-    /// Initially, if `authorized_buffer` has any non-zero data past index 9, you should should zero out all of the data outside
-    /// of the first 9 bytes.
+    /// Initially, if `authorized_buffer` has any non-zero data past index 146, you should should zero out all of the data outside
+    /// of the first 146 bytes.
+    ///
+    /// The cursor is reset to the number of bytes written, so a following `AppendAuthorizedEcho` continues after them.
     ///
     /// If any account besides the `authority` attempts to write to the `authorized_buffer`, the instruction will fail.
     ///
+    /// If the buffer has a non-zero `write_fee_lamports`, the `authority` first transfers it to the fee vault, so the
+    /// `authority` must be writable and the fee vault and system program must be passed.
+    ///
     /// Accounts:
     /// | index | writable | signer | description                                                              |
     /// |-------|----------|--------|--------------------------------------------------------------------------|
     /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
     /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
+    /// | 2     | ✅       | ❌     | fee_vault: (optional) PDA of Echo Program seeded by `"fee_vault"`         |
+    /// | 3     | ❌       | ❌     | system_program: (optional) Used to transfer the write fee                 |
     AuthorizedEcho { data: Vec<u8> },
     /// This instruction will allocate `buffer_size` bytes to the `vending_machine_buffer` account and assign it the Echo Program.
     ///
-    /// The first 9 bytes of `vending_machine_buffer` will be set with the following data:
+    /// With a `payment_mode` of 0 the tokens paid for writes are burned, with 1 they are transferred to the `treasury`
+    /// token account, which must hold the `vending_machine_mint`. A `treasury` owned by the vault authority PDA seeded by
+    /// `[b"vending_vault", vending_machine_buffer]` lets the admin take the proceeds out with
+    /// `WithdrawVendingMachineProceeds`.
+    ///
+    /// Fails with `InvalidMint` unless `vending_machine_mint` is an initialized mint owned by the Token Program or by
+    /// Token-2022. The owner is recorded as the token program of the vending machine, which every write has to use.
+    ///
+    /// Up to 4 `tiers` can replace the `price` depending on the length of the data, they must be sorted by strictly
+    /// increasing `max_len`, failing with `InvalidInstructionInput` otherwise.
+    ///
+    /// Fails with `AccountAlreadyInitialized` if `vending_machine_buffer` already holds a vending machine.
+    ///
+    /// The first 317 bytes of `vending_machine_buffer` will be set with the following data:
     ///     byte 0: bump_seed
     ///     bytes 1-8: price
+    ///     byte 9: free (0 for these paid vending machines)
+    ///     byte 10: payment_mode
+    ///     bytes 11-42: treasury, zeroed in burn mode
+    ///     bytes 43-74: admin, the `mint_authority`
+    ///     byte 75: paused (initially 0)
+    ///     bytes 76-83: price_per_byte
+    ///     byte 84: require_ata
+    ///     bytes 85-116: token_program, the owner of `vending_machine_mint`
+    ///     bytes 117-148: mint, the `vending_machine_mint`
+    ///     bytes 149-156: uses (initially 0)
+    ///     bytes 157-164: max_uses
+    ///     bytes 165-172: sol_fee (always 0, see `InitializePriorityFeeEcho`)
+    ///     byte 173: tier_count
+    ///     bytes 174-221: tiers, 4 times max_len (4 bytes) and price (8 bytes), zeroed past `tier_count`
+    ///     bytes 222-253: last_writer (initially zeroed)
+    ///     bytes 254-261: last_write_slot (initially 0)
+    ///     bytes 262-269: expiry_slot (always 0, see `InitializeExpiringVendingMachine`)
+    ///     bytes 270-277: total_writes (initially 0)
+    ///     bytes 278-285: total_burned (initially 0)
+    ///     bytes 286-289: data_len (initially 0)
+    ///     byte 290: is_initialized (always 1)
+    ///     bytes 291-298: start_slot (always 0, see `InitializeWindowedVendingMachine`)
+    ///     bytes 299-306: end_slot (always 0, see `InitializeWindowedVendingMachine`)
+    ///     bytes 307-314: cooldown_slots
+    ///     byte 315: whitelist_enabled (always false, see `SetWhitelistEnabled`)
+    ///     byte 316: free_authority_writes
     ///
     /// Accounts:
     /// | index | writable | signer | description                                                                                         |
     /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
     /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program that only holders of a particular token can write to |
     /// | 1     | ❌       | ❌     | vending_machine_mint: Pubkey with sole write access to `authorized_buffer`                           |
-    /// | 2     | ❌       | ✅     | payer: Pubkey that allocates the `vending_machine_buffer`                                            |
-    /// | 3     | ❌       | ❌     | system_program: Used to allocate the buffer                                                          |
+    /// | 2     | ❌       | ✅     | mint_authority: Mint authority of `vending_machine_mint`, fails with `InvalidMintAuthority` otherwise |
+    /// | 3     | ❌       | ✅     | payer: Pubkey that allocates the `vending_machine_buffer`                                            |
+    /// | 4     | ❌       | ❌     | system_program: Used to allocate the buffer                                                          |
+    /// | 5     | ❌       | ❌     | treasury: (treasury mode only) Token account of `vending_machine_mint` receiving the payments        |
     InitializeVendingMachineEcho {
         // Number of tokens required change the buffer
         price: u64,
         buffer_size: usize,
+        /// 0 to burn the payments, 1 to transfer them to the `treasury`
+        payment_mode: u8,
+        /// Tokens charged for every byte of data on top of the `price`, 0 for a flat price
+        price_per_byte: u64,
+        /// Only accept payments from the associated token account of the token account owner
+        require_ata: bool,
+        /// Paid writes accepted before the vending machine is sold out, 0 for unlimited
+        max_uses: u64,
+        /// Up to 4 price tiers with strictly increasing `max_len`, empty to always charge the `price`
+        tiers: Vec<PriceTier>,
+        /// Slots a user must wait between two paid writes, 0 for no cooldown
+        cooldown_slots: u64,
+        /// Let the mint authority of `vending_machine_mint` write without paying the tokens
+        free_authority_writes: bool,
     },
     /// The contents of the data vector that is provided to the instruction should be copied into the account starting from
-    /// index 9 (you do NOT want to override the bump_seed and price).
+    /// index 317 (you do NOT want to override the header).
     ///
     /// If the remaining account length ( N ) is smaller than the length of data, copy the first N bytes of data into
     /// `vending_machine_buffer`.
     ///
-    /// Initially, if `vending_machine_buffer` has any non-zero data past index 9, you should should zero out all of the
-    /// data outside of the first 9 bytes.
+    /// The number of bytes copied is stored as the `data_len` of the header. Only the bytes of the previous `data_len`
+    /// that the new data doesn't cover are zeroed, the rest of the payload is already zero padding.
     ///
-    /// Before any data is copied over, the user must burn a `price` amount of tokens from the `user_token_account`.
+    /// Before any data is copied over, the user must burn a `price + price_per_byte * data.len()` amount of tokens from
+    /// the `user_token_account`, failing with `Overflow` if it doesn't fit in a u64.
     /// This will require a cross program invocation to the Token Program. If this instruction succeed (verifies that the
-    /// user in fact has sufficient tokens), then the copy can occur.
+    /// user in fact has sufficient tokens), then the copy can occur. The burn is checked against the decimals of
+    /// `vending_machine_mint`, a mismatch fails with `MintDecimalsMismatch`.
+    ///
+    /// Writes to a vending machine with a non-zero `expiry_slot` fail with `BufferExpired` from that slot on.
+    ///
+    /// Every paid write increments `total_writes` and adds the burned tokens to `total_burned`, both are also logged in a
+    /// `VendingMachineStats` event.
+    ///
+    /// The `user` can also be the delegate of the `user_token_account`, the burn is then checked against and taken from
+    /// its `delegated_amount`.
+    ///
+    /// If the vending machine was initialized with `require_ata`, the `user_token_account` must be the associated token
+    /// account of its owner for `vending_machine_mint`, failing with `NotAssociatedTokenAccount` otherwise.
+    ///
+    /// If the vending machine was initialized with `free_authority_writes`, a `user` that is the mint authority of
+    /// `vending_machine_mint` writes without paying, nothing is burned or transferred. Such writes aren't sales, they
+    /// don't increment `uses` or `total_writes` and still go through once the vending machine is sold out. Every other
+    /// check still applies.
     ///
     /// This instruction should fail in the case that the mint of the `vending_machine_buffer` does not match the mint
     /// used to seed the PDA.  You can verify this by comparing the output of `Pubkey::create_program_address` with the correct
     /// seeds to the value of `vending_machine_buffer.key`.
     ///
+    /// The `vending_machine_mint` must be the mint recorded at initialization, failing with `MintMismatch` otherwise.
+    /// A vending machine with price tiers charges the price of the first tier whose `max_len` covers the length of
+    /// `data` instead of its `price`, failing with `DataTooLarge` if `data` is longer than the last tier.
+    ///
+    /// Every paid write increments `uses`, once it reaches a non-zero `max_uses` writes fail with
+    /// `VendingMachineSoldOut` before anything is paid.
+    ///
+    /// A vending machine created by `InitializePriorityFeeEcho` also charges its `sol_fee`, transferred from the `user`
+    /// to the fee treasury before the tokens are burned, so the `user` must be writable and the fee treasury and
+    /// system program must be passed.
+    ///
+    /// Fails with `VendingMachinePaused`, before anything is paid, while the vending machine is paused, and with
+    /// `InvalidMint` unless `vending_machine_mint` is an initialized mint owned by the token program of the vending
+    /// machine. The `user_token_account` must be an initialized token account of that token program too, a frozen one
+    /// fails with `TokenAccountFrozen`. A `vending_machine_buffer` that was never initialized fails with
+    /// `AccountNotInitialized`.
+    ///
+    /// A vending machine with `cooldown_slots` records the last paid write of every user in a user record, created on
+    /// their first write with the `user` paying its rent, so the `user` must be writable. A write less than
+    /// `cooldown_slots` after the previous one of the same user fails with `CooldownActive` before anything is paid.
+    ///
+    /// When the whitelist of the vending machine is enabled, a `user` that isn't a member of it fails with
+    /// `NotWhitelisted` before anything is paid.
+    ///
     /// Accounts:
+    /// | index  | writable | signer | description                                                                                            |
+    /// |--------|----------|--------|--------------------------------------------------------------------------------------------------------|
+    /// | 0      | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program that only holders of a particular token can write to   |
+    /// | 1      | ❌       | ✅     | user: Owner of the token account, or its delegate paying from the delegated amount                     |
+    /// | 2      | ✅       | ❌     | user_token_account: This is the token account that will pay for the use of the vending machine         |
+    /// | 3      | ❌       | ❌     | vending_machine_mint: This is the token mint that is accepted by the `vending_machine_buffer`          |
+    /// | 3      | ❌       | ❌     | token_program: Token Program or Token-2022, the one recorded at initialization                         |
+    /// | 5      | ✅       | ❌     | treasury: (treasury mode only) Token account receiving the payment instead of the burn                 |
+    /// | 5 / 6  | ✅       | ❌     | fee_treasury: (SOL fee only) PDA of Echo Program seeded by `"treasury"` and the program id             |
+    /// | 6 / 7  | ❌       | ❌     | system_program: (SOL fee only) Used to transfer the SOL fee                                            |
+    /// | 5 - 8  | ✅       | ❌     | user_record: (cooldown only) PDA of Echo Program seeded by `"vending_user"`, the buffer and `user`     |
+    /// | 6 - 9  | ❌       | ❌     | system_program: (cooldown only) Used to allocate the `user_record`                                     |
+    /// | 5 - 10 | ❌       | ❌     | vending_whitelist: (whitelist only) PDA of Echo Program seeded by `"vending_whitelist"` and the buffer |
+    ///
+    /// When the `vending_machine_buffer` was created by `InitializePermissionlessVendingMachine`, nothing is burned and
+    /// the token accounts are replaced by the creator of the buffer, any further accounts are ignored:
     /// | index | writable | signer | description                                                                                         |
     /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
-    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program that only holders of a particular token can write to |
-    /// | 1     | ❌       | ✅     | user: This is authority of the token account that is using the vending machine                       |
-    /// | 2     | ✅       | ❌     | user_token_account: This is the token account that will pay for the use of the vending machine       |
-    /// | 3     | ❌       | ❌     | vending_machine_mint: This is the token mint that is accepted by the `vending_machine_buffer`        |
-    /// | 3     | ❌       | ❌     | token_program: Used to burn the vending machine tokens                                               |
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program seeded by `"free_vm"` and `creator`                  |
+    /// | 1     | ❌       | ✅     | user: Pubkey writing to the vending machine                                                          |
+    /// | 2     | ❌       | ❌     | creator: Pubkey that paid for the `vending_machine_buffer`                                           |
     VendingMachineEcho { data: Vec<u8> },
+    /// This instruction will allocate `buffer_size` bytes to the `program_controlled_buffer` account and assign it the
+    /// Echo Program. The buffer can only be written through a cross program invocation from `allowed_program`.
+    ///
+    /// The first 33 bytes of `program_controlled_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: allowed_program
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                       |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | program_controlled_buffer: PDA of Echo Program seeded by `allowed_program`        |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `program_controlled_buffer`                      |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                                       |
+    InitializeProgramControlled {
+        buffer_size: usize,
+        allowed_program: Pubkey,
+    },
+    /// The contents of the data vector that is provided to the instruction will be copied into the
+    /// `program_controlled_buffer` account starting from index 33 (will NOT override the header).
+    ///
+    /// The instruction fails with `UnauthorizedCaller` unless it is executed as a cross program invocation whose
+    /// top-level instruction belongs to `allowed_program`. Direct invocations always fail.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                       |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | program_controlled_buffer: PDA of Echo Program seeded by `allowed_program`        |
+    /// | 1     | ❌       | ❌     | instructions: Instructions sysvar used to identify the calling program            |
+    ProgramControlledEcho { data: Vec<u8> },
+    /// Writes `writes[i]` into `authorized_buffer_i` exactly as `AuthorizedEcho` would, for up to 10 buffers of the same
+    /// `authority` in a single instruction.
+    ///
+    /// Every buffer is validated against `authority` and the buffer seed stored in its own header before any data is
+    /// written. The number of `writes` must match the number of buffer accounts, and if any buffer fails validation
+    /// the whole instruction fails. Buffers with a non-zero `write_fee_lamports` fail with `InvalidInstructionInput`, as
+    /// there is no room for the fee accounts, they are written with `AuthorizedEcho`.
+    ///
+    /// The return data holds the new `sequence` of every buffer, as little-endian u64s in account order.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ❌       | ✅     | authority: Pubkey with sole write access to every `authorized_buffer`     |
+    /// | 1..   | ✅       | ❌     | authorized_buffer_i: PDA of Echo Program that only `authority` can write to |
+    BatchAuthorizedEcho { writes: Vec<Vec<u8>> },
+    /// This instruction will allocate `buffer_size` bytes to the `conditional_buffer` account and assign it the Echo Program.
+    ///
+    /// The first 100 bytes of `conditional_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: authority
+    ///     bytes 33-64: condition_account
+    ///     bytes 65-66: condition_offset
+    ///     byte 67: length of condition_value
+    ///     bytes 68-99: condition_value (zero padded)
+    ///
+    /// `condition_value` must be between 1 and 32 bytes long.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                     |
+    /// |-------|----------|--------|---------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | conditional_buffer: PDA of Echo Program seeded by `authority` and `condition_account` |
+    /// | 1     | ❌       | ❌     | condition_account: Account whose data gates writes to `conditional_buffer`       |
+    /// | 2     | ✅       | ✅     | payer: Pubkey that allocates the `conditional_buffer`                            |
+    /// | 3     | ❌       | ❌     | system_program: Used to allocate the buffer                                      |
+    InitializeConditionalEcho {
+        buffer_size: usize,
+        authority: Pubkey,
+        condition_offset: u16,
+        condition_value: Vec<u8>,
+    },
+    /// The contents of the data vector that is provided to the instruction will be copied into the `conditional_buffer`
+    /// account starting from index 100 (will NOT override the header), zero padding the rest of the buffer.
+    ///
+    /// The write only happens if `condition_account.data[condition_offset..condition_offset + condition_value.len()]`
+    /// equals the stored `condition_value`, otherwise the instruction fails with `ConditionNotMet`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                     |
+    /// |-------|----------|--------|---------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | conditional_buffer: PDA of Echo Program seeded by `authority` and `condition_account` |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `conditional_buffer`                 |
+    /// | 2     | ❌       | ❌     | condition_account: Account whose data gates writes to `conditional_buffer`       |
+    ConditionalWrite { data: Vec<u8> },
+    /// This instruction will allocate `buffer_size` bytes to the `authorized_buffer` account and assign it the Echo Program.
+    /// The buffer address is derived from `name` instead of a numeric buffer seed.
+    ///
+    /// `name` must be at most 32 bytes and must contain a non-whitespace character. It must not be exactly 8 bytes long,
+    /// failing with `InvalidInstructionInput`, as it would derive the same address as the numeric buffer seed with the
+    /// same bytes.
+    ///
+    /// The first 34 bytes of authorized_buffer will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     byte 1: length of name
+    ///     bytes 2-33: name (zero padded)
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                               |
+    InitializeNamedAuthorizedEcho { name: String, buffer_size: usize },
+    /// The contents of the data vector that is provided to the instruction will be copied into the named
+    /// `authorized_buffer` account starting from index 34 (will NOT override the header), exactly like `AuthorizedEcho`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
+    NamedAuthorizedEcho { data: Vec<u8> },
+    /// This instruction will allocate `buffer_size` bytes to the `aggregator_buffer` account and assign it the Echo Program.
+    ///
+    /// The first 33 bytes of `aggregator_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: authority
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | aggregator_buffer: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `aggregator_buffer`                |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeAggregatorEcho {
+        buffer_size: usize,
+        authority: Pubkey,
+    },
+    /// Each byte of `data` is XORed into the `aggregator_buffer` data region (starting from index 33) instead of
+    /// overwriting it. `data[i]` is applied to `buffer_data[i % buffer_data.len()]`.
+    ///
+    /// Any signer can contribute. XOR is commutative and self-inverse, so the order of writes doesn't matter and
+    /// writing the same value twice restores the previous contents.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | aggregator_buffer: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ❌       | ✅     | contributor: Any signer                                             |
+    AggregateWrite { data: Vec<u8> },
+    /// Zeroes the `aggregator_buffer` data region.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | aggregator_buffer: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `aggregator_buffer` was created for           |
+    ResetAggregator,
+    /// This instruction will allocate a `sum_account` holding a single u64 accumulator set to `initial_value`.
+    ///
+    /// The 9 bytes of `sum_account` will be set with the following data:
+    ///     bytes 0-7: value
+    ///     byte 8: bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | sum_account: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `sum_account`                |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the account                  |
+    InitializeSumEcho {
+        authority: Pubkey,
+        initial_value: u64,
+    },
+    /// Adds `amount` to the sum, failing with `Overflow` if the result doesn't fit in a u64. Any signer can add.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | sum_account: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ❌       | ✅     | signer: Any signer                                            |
+    /// | 2     | ❌       | ❌     | authority: Pubkey the `sum_account` was created for           |
+    AddToSum { amount: u64 },
+    /// Subtracts `amount` from the sum, failing with `Underflow` if the result would be negative.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | sum_account: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `sum_account` was created for           |
+    SubtractFromSum { amount: u64 },
+    /// Sets the sum to `new_value`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | sum_account: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `sum_account` was created for           |
+    ResetSum { new_value: u64 },
+    /// Copies `data` into `authorized_buffer` right after the bytes written so far, then advances the cursor past them.
+    ///
+    /// Fails with `BufferFull` if the remaining space after the cursor is smaller than `data`, nothing is written in that case.
+    ///
+    /// If the buffer has a non-zero `write_fee_lamports` it is charged like in `AuthorizedEcho`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
+    /// | 2     | ✅       | ❌     | fee_vault: (optional) PDA of Echo Program seeded by `"fee_vault"`         |
+    /// | 3     | ❌       | ❌     | system_program: (optional) Used to transfer the write fee                 |
+    AppendAuthorizedEcho { data: Vec<u8> },
+    /// This instruction will allocate the `max_account` and assign it the Echo Program, starting at `0`.
+    ///
+    /// The 17 bytes of `max_account` will be set with the following data:
+    ///     bytes 0-7: current_max
+    ///     bytes 8-15: update_count
+    ///     byte 16: bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | max_account: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `max_account`                |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the account                  |
+    InitializeMaxEcho { authority: Pubkey },
+    /// Sets `current_max` to `candidate` if it is greater, and always increments `update_count`.
+    /// Anyone can call this instruction, no signature is required.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | max_account: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ❌       | ❌     | authority: Pubkey the `max_account` was created for           |
+    UpdateMax { candidate: u64 },
+    /// Sets `current_max` to `new_value`, `update_count` is left as is.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | max_account: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `max_account` was created for           |
+    ResetMax { new_value: u64 },
+    /// This instruction will allocate the `min_account` and assign it the Echo Program, starting at `u64::MAX`.
+    ///
+    /// The 17 bytes of `min_account` will be set with the following data:
+    ///     bytes 0-7: current_min
+    ///     bytes 8-15: update_count
+    ///     byte 16: bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | min_account: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `min_account`                |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the account                  |
+    InitializeMinEcho { authority: Pubkey },
+    /// Sets `current_min` to `candidate` if it is lower, and always increments `update_count`.
+    /// Anyone can call this instruction, no signature is required.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | min_account: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ❌       | ❌     | authority: Pubkey the `min_account` was created for           |
+    UpdateMin { candidate: u64 },
+    /// Sets `current_min` to `new_value`, `update_count` is left as is.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | min_account: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `min_account` was created for           |
+    ResetMin { new_value: u64 },
+    /// Zero-fills `authorized_buffer` past the header and resets the cursor, without closing the account. A chunked
+    /// write in progress is abandoned, `write_in_progress` is reset.
+    ///
+    /// Either the `authority` or the `permanent_delegate` of the buffer must sign.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access, unless the delegate signs       |
+    /// | 2     | ❌       | ✅     | permanent_delegate: (optional) Signs in place of the `authority`          |
+    ClearAuthorizedBuffer,
+    /// This instruction will allocate a `histogram_buffer` with `bucket_count` u64 counters after the header and assign
+    /// it the Echo Program. Bucket `i` counts values in `[i * bucket_width, (i + 1) * bucket_width)`.
+    ///
+    /// The first 43 bytes of `histogram_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: authority
+    ///     bytes 33-34: bucket_count
+    ///     bytes 35-42: bucket_width
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | histogram_buffer: PDA of Echo Program seeded by `authority`         |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `histogram_buffer`                 |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeHistogramEcho {
+        bucket_count: u16,
+        bucket_width: u64,
+        authority: Pubkey,
+    },
+    /// Increments the counter of bucket `value / bucket_width`, values past the last bucket are counted in the last one.
+    /// Fails with `Overflow` if the counter is already at `u64::MAX`. Anyone can record a value.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | histogram_buffer: PDA of Echo Program seeded by `authority`         |
+    RecordHistogramValue { value: u64 },
+    /// Logs the counter of every bucket.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | histogram_buffer: PDA of Echo Program seeded by `authority`         |
+    ReadHistogram,
+    /// Zeroes every bucket of the `histogram_buffer`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | histogram_buffer: PDA of Echo Program seeded by `authority`         |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `histogram_buffer` was created for            |
+    ResetHistogram,
+    /// Same as `InitializeAuthorizedEcho`, but the `authority` doesn't have to sign and a separate `payer` funds the
+    /// account. This lets a PDA of another program be the authority, it can then write to the `authorized_buffer`
+    /// by signing `AuthorizedEcho` with `invoke_signed`.
+    ///
+    /// The `authorized_buffer` address is still derived from the `authority` key, so nobody but the `authority` can
+    /// write to it afterwards.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
+    /// | 1     | ❌       | ❌     | authority: Pubkey with sole write access to `authorized_buffer`           |
+    /// | 2     | ✅       | ✅     | payer: Pubkey that allocates the `authorized_buffer`                      |
+    /// | 3     | ❌       | ❌     | system_program: Used to allocate the buffer                               |
+    InitializeAuthorizedEchoForPda {
+        buffer_seed: u64,
+        buffer_size: usize,
+    },
+    /// This instruction will allocate a `set_buffer` with room for `capacity` unique pubkeys after the header and
+    /// assign it the Echo Program. Members are kept sorted so they can be looked up with a binary search.
+    ///
+    /// The first 5 bytes of `set_buffer` will be set with the following data:
+    ///     bytes 0-1: count
+    ///     bytes 2-3: capacity
+    ///     byte 4: bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | set_buffer: PDA of Echo Program seeded by `authority`               |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `set_buffer`                       |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeSetEcho { capacity: u16, authority: Pubkey },
+    /// Inserts `member` into the set. Fails with `SetFull` if the set is at capacity, or `DuplicateMember` if
+    /// `member` is already in it.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | set_buffer: PDA of Echo Program seeded by `authority`               |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `set_buffer` was created for                  |
+    AddToSet { member: Pubkey },
+    /// Removes `member` from the set, fails with `MemberNotFound` if it isn't in it.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | set_buffer: PDA of Echo Program seeded by `authority`               |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `set_buffer` was created for                  |
+    RemoveFromSet { member: Pubkey },
+    /// Logs whether `member` is in the set, without modifying it.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | set_buffer: PDA of Echo Program seeded by `authority`               |
+    /// | 1     | ❌       | ❌     | authority: Pubkey the `set_buffer` was created for                  |
+    ContainsInSet { member: Pubkey },
+    /// Stores the keccak `hash` of data to be written later with `RevealAuthorizedEcho`, along with the current slot.
+    /// A commitment that hasn't been revealed yet is replaced.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
+    CommitAuthorizedEcho { hash: [u8; 32] },
+    /// Writes `data` like `AuthorizedEcho` if its keccak hash matches the pending commitment, then clears the
+    /// commitment. Fails with `CommitmentMismatch` if there is no pending commitment or the hash differs, and with
+    /// `RevealTooEarly` if less than `MIN_REVEAL_DELAY_SLOTS` slots passed since the commit.
+    ///
+    /// If the buffer has a non-zero `write_fee_lamports` it is charged like in `AuthorizedEcho`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
+    /// | 2     | ✅       | ❌     | fee_vault: (optional) PDA of Echo Program seeded by `"fee_vault"`         |
+    /// | 3     | ❌       | ❌     | system_program: (optional) Used to transfer the write fee                 |
+    RevealAuthorizedEcho { data: Vec<u8> },
+    /// This instruction will allocate a `map_buffer` with room for `capacity` pubkey to u64 entries after the header
+    /// and assign it the Echo Program. Entries are kept sorted by key so they can be looked up with a binary search.
+    ///
+    /// The first 5 bytes of `map_buffer` will be set with the following data:
+    ///     bytes 0-1: count
+    ///     bytes 2-3: capacity
+    ///     byte 4: bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | map_buffer: PDA of Echo Program seeded by `authority`               |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `map_buffer`                       |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeMapEcho { capacity: u16, authority: Pubkey },
+    /// Sets the value of `key`, inserting a new entry if it isn't in the map yet. Fails with `MapFull` if a new
+    /// entry is needed and the map is at capacity.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | map_buffer: PDA of Echo Program seeded by `authority`               |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `map_buffer` was created for                  |
+    PutMapEntry { key: Pubkey, value: u64 },
+    /// Removes the entry of `key`, fails with `MemberNotFound` if it isn't in the map.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | map_buffer: PDA of Echo Program seeded by `authority`               |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `map_buffer` was created for                  |
+    DeleteMapEntry { key: Pubkey },
+    /// Logs the value of `key`, fails with `MemberNotFound` if it isn't in the map.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | map_buffer: PDA of Echo Program seeded by `authority`               |
+    /// | 1     | ❌       | ❌     | authority: Pubkey the `map_buffer` was created for                  |
+    GetMapEntry { key: Pubkey },
+    /// Replaces the label of `authorized_buffer`, it must be at most 32 bytes.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
+    SetLabel { label: String },
+    /// This instruction will allocate a `bitmap_buffer` with `(bit_count + 7) / 8` bytes after the header and assign it
+    /// the Echo Program. Bit `index` is stored in byte `index / 8` of the data region, at bit `index % 8`.
+    ///
+    /// The first 5 bytes of `bitmap_buffer` will be set with the following data:
+    ///     bytes 0-3: bit_count
+    ///     byte 4: bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | bitmap_buffer: PDA of Echo Program seeded by `authority`            |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `bitmap_buffer`                    |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeBitmapEcho { bit_count: u32, authority: Pubkey },
+    /// Sets bit `index`, fails with `InvalidInstructionInput` if `index >= bit_count`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | bitmap_buffer: PDA of Echo Program seeded by `authority`            |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `bitmap_buffer` was created for               |
+    SetBit { index: u32 },
+    /// Clears bit `index`, fails with `InvalidInstructionInput` if `index >= bit_count`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | bitmap_buffer: PDA of Echo Program seeded by `authority`            |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `bitmap_buffer` was created for               |
+    ClearBit { index: u32 },
+    /// Logs `bit N = 1` or `bit N = 0`, without modifying the bitmap.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | bitmap_buffer: PDA of Echo Program seeded by `authority`            |
+    /// | 1     | ❌       | ❌     | authority: Pubkey the `bitmap_buffer` was created for               |
+    QueryBit { index: u32 },
+    /// This instruction will allocate the `counter` account of `authority`, used by `InitializeAuthorizedEchoAuto` to
+    /// pick buffer seeds. Seeds start at 0.
+    ///
+    /// The 9 bytes of `counter` will be set with the following data:
+    ///     bytes 0-7: next_seed
+    ///     byte 8: bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | counter: PDA of Echo Program seeded by `authority`            |
+    /// | 1     | ✅       | ✅     | authority: Pubkey that allocates the `counter`                |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the account                  |
+    InitializeAuthorityCounter,
+    /// Same as `InitializeAuthorizedEcho`, but `buffer_seed` is the `next_seed` of the authority's `counter`, which is
+    /// then incremented. The new buffer has an empty label.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program for `authority` and `next_seed`    |
+    /// | 1     | ✅       | ❌     | counter: PDA of Echo Program seeded by `authority`                        |
+    /// | 2     | ✅       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
+    /// | 3     | ❌       | ❌     | system_program: Used to allocate the buffer                               |
+    InitializeAuthorizedEchoAuto { buffer_size: usize },
+    /// This instruction will allocate `buffer_size` bytes to the `linked_buffer` account, the head of a chain of
+    /// buffers, and assign it the Echo Program.
+    ///
+    /// The first 66 bytes of every buffer of the chain will be set with the following data:
+    ///     byte 0: bump_seed (0 for every buffer but the head)
+    ///     bytes 1-32: authority
+    ///     bytes 33-64: next_buffer
+    ///     byte 65: has_next
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | linked_buffer: PDA of Echo Program seeded by `authority`            |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `linked_buffer`                    |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeLinkedEcho {
+        buffer_size: usize,
+        authority: Pubkey,
+    },
+    /// Points the last buffer of the chain to `new_buffer` and initializes `new_buffer` as the new last buffer.
+    ///
+    /// `new_buffer` must already be allocated and assigned to the Echo Program with all of its data set to zero, and
+    /// it can't be the `tail_buffer` itself.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | tail_buffer: Last buffer of the chain                               |
+    /// | 1     | ✅       | ❌     | new_buffer: Zeroed account owned by the Echo Program                |
+    /// | 2     | ❌       | ✅     | authority: Pubkey the chain was created for                         |
+    AppendLinkedEcho { new_buffer: Pubkey },
+    /// Logs the data of every buffer of the chain, stopping at the buffer with `has_next == false`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | linked_buffer: PDA of Echo Program seeded by `authority`            |
+    /// | 1..N  | ❌       | ❌     | The following buffers of the chain, in order                        |
+    TraverseLinkedEcho,
+    /// This instruction will allocate the `registry` of `authority` with room for `capacity` buffers, to list the
+    /// buffers of `authority` without scanning every account of the Echo Program. Buffers are added by passing the
+    /// `registry` to `InitializeAuthorizedEcho` and removed by passing it to `CloseAuthorizedEcho`.
+    ///
+    /// The first 5 bytes of `registry` will be set with the following data:
+    ///     bytes 0-1: count
+    ///     bytes 2-3: capacity
+    ///     byte 4: bump_seed
+    ///
+    /// followed by `count` entries of 40 bytes:
+    ///     bytes 0-7: buffer_seed
+    ///     bytes 8-39: buffer
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | registry: PDA of Echo Program seeded by `authority`           |
+    /// | 1     | ✅       | ✅     | authority: Pubkey that allocates the `registry`               |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the account                  |
+    InitializeRegistry { capacity: u16 },
+    /// Closes `authorized_buffer`, sending its lamports to the `authority`. If the `registry` is passed the buffer
+    /// is removed from it, failing with `MemberNotFound` if it isn't listed.
+    ///
+    /// Either the `authority` or the `permanent_delegate` of the buffer must sign, the lamports always go to the
+    /// `authority`. The `registry` is always at index 2, when the `permanent_delegate` signs without a `registry` the
+    /// Echo Program id is passed in its place.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
+    /// | 1     | ✅       | ✅     | authority: Pubkey with sole write access, unless the delegate signs       |
+    /// | 2     | ✅       | ❌     | registry: (optional) Registry of `authority` the buffer is removed from   |
+    /// | 3     | ❌       | ✅     | permanent_delegate: (optional) Signs in place of the `authority`          |
+    CloseAuthorizedEcho,
+    /// This instruction will allocate an `indexed_buffer` with `slot_count` records of `key_size + value_size` bytes
+    /// after the header and assign it the Echo Program. A record whose key is all zeros is a free slot.
+    ///
+    /// The first 6 bytes of `indexed_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-2: slot_count
+    ///     byte 3: key_size
+    ///     bytes 4-5: value_size
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | indexed_buffer: PDA of Echo Program seeded by `authority`           |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `indexed_buffer`                   |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeIndexedEcho {
+        slot_count: u16,
+        key_size: u8,
+        value_size: u16,
+        authority: Pubkey,
+    },
+    /// Updates the value of `key` if it is stored, otherwise stores it in the first free slot, failing with `SetFull`
+    /// if there is none. `key` and `value` must be exactly `key_size` and `value_size` bytes, and `key` can't be all
+    /// zeros.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | indexed_buffer: PDA of Echo Program seeded by `authority`           |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `indexed_buffer` was created for              |
+    PutIndexedValue { key: Vec<u8>, value: Vec<u8> },
+    /// Zeroes the record of `key`, freeing its slot. Fails with `MemberNotFound` if `key` isn't stored.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | indexed_buffer: PDA of Echo Program seeded by `authority`           |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `indexed_buffer` was created for              |
+    DeleteIndexedValue { key: Vec<u8> },
+    /// Logs the value stored for `key`, failing with `MemberNotFound` if `key` isn't stored.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | indexed_buffer: PDA of Echo Program seeded by `authority`           |
+    /// | 1     | ❌       | ❌     | authority: Pubkey the `indexed_buffer` was created for              |
+    GetIndexedValue { key: Vec<u8> },
+    /// This instruction will allocate `buffer_size` bytes to the `epoch_buffer` account and assign it the Echo Program.
+    /// Its data is only valid for the epoch it was written in.
+    ///
+    /// The first 9 bytes of `epoch_buffer` will be set with the following data:
+    ///     bytes 0-7: last_write_epoch, the current epoch
+    ///     byte 8: bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | epoch_buffer: PDA of Echo Program seeded by `authority`             |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `epoch_buffer`                     |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeEpochEcho {
+        buffer_size: usize,
+        authority: Pubkey,
+    },
+    /// The contents of `data` will be copied into `epoch_buffer` starting from index 9, keeping the bytes past them.
+    ///
+    /// If `epoch_buffer` was last written in a previous epoch, all of its data past the header is zeroed and
+    /// `last_write_epoch` is set to the current epoch before copying.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | epoch_buffer: PDA of Echo Program seeded by `authority`             |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `epoch_buffer`          |
+    EpochEcho { data: Vec<u8> },
+    /// Logs the data of `epoch_buffer`, or that it is empty if it was last written in a previous epoch.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | epoch_buffer: PDA of Echo Program seeded by `authority`             |
+    /// | 1     | ❌       | ❌     | authority: Pubkey the `epoch_buffer` was created for                |
+    ReadEpochEcho,
+    /// Copies the payload of `authorized_buffer` (everything past its header) into a new `snapshot` account, to keep
+    /// the previous contents on-chain before overwriting them. Fails with `SnapshotAlreadyExists` if the snapshot for
+    /// `snapshot_index` was already taken.
+    ///
+    /// The first 49 bytes of `snapshot` will be set with the following data, followed by the copied payload:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: source_buffer
+    ///     bytes 33-40: slot
+    ///     bytes 41-48: snapshot_index
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
+    /// | 2     | ✅       | ❌     | snapshot: PDA of Echo Program seeded by `authorized_buffer` and the index |
+    /// | 3     | ✅       | ✅     | payer: Pubkey that allocates the `snapshot`                               |
+    /// | 4     | ❌       | ❌     | system_program: Used to allocate the snapshot                             |
+    SnapshotAuthorizedEcho { snapshot_index: u64 },
+    /// This instruction will allocate `buffer_size` bytes to the `slot_buffer` account and assign it the Echo Program.
+    /// Its data is only valid for the slot it was written in.
+    ///
+    /// The first 9 bytes of `slot_buffer` will be set with the following data:
+    ///     bytes 0-7: last_write_slot, the current slot
+    ///     byte 8: bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | slot_buffer: PDA of Echo Program seeded by `authority`              |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `slot_buffer`                      |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeSlotEcho {
+        buffer_size: usize,
+        authority: Pubkey,
+    },
+    /// The contents of `data` will be copied into `slot_buffer` starting from index 9, keeping the bytes past them.
+    ///
+    /// If `slot_buffer` was last written in a previous slot, all of its data past the header is zeroed and
+    /// `last_write_slot` is set to the current slot before copying.
+    ///
+    /// Data is only zeroed by this instruction, so if a slot passes without a write the account still holds stale
+    /// data. Readers must check `last_write_slot` against the current slot.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | slot_buffer: PDA of Echo Program seeded by `authority`              |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `slot_buffer`           |
+    SlotEcho { data: Vec<u8> },
+    /// This instruction will allocate `buffer_size` bytes to the `co_authorized_buffer` account and assign it the Echo
+    /// Program. Its address is derived from both authorities in ascending order, so they can be passed in any order.
+    ///
+    /// The first 73 bytes of `co_authorized_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-8: buffer_seed
+    ///     bytes 9-40: authority_a, the lower of the two authorities
+    ///     bytes 41-72: authority_b
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                 |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | co_authorized_buffer: PDA of Echo Program seeded by both authorities         |
+    /// | 1     | ✅       | ✅     | first_authority: One of the authorities, pays for the buffer                 |
+    /// | 2     | ❌       | ❌     | second_authority: The other authority                                        |
+    /// | 3     | ❌       | ❌     | system_program: Used to allocate the buffer                                  |
+    InitializeCoAuthorizedEcho {
+        buffer_seed: u64,
+        buffer_size: usize,
+    },
+    /// The contents of `data` will be copied into `co_authorized_buffer` starting from index 73, zero-padding the
+    /// rest of the buffer. Both authorities must sign, in any order.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                 |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | co_authorized_buffer: PDA of Echo Program seeded by both authorities         |
+    /// | 1     | ❌       | ✅     | first_authority: One of the authorities                                      |
+    /// | 2     | ❌       | ✅     | second_authority: The other authority                                        |
+    CoAuthorizedEcho { data: Vec<u8> },
+    /// This instruction will allocate `buffer_size` bytes to the `decay_buffer` account and assign it the Echo Program.
+    /// The bytes of its data decay toward zero, each one is multiplied by `1 - decay_rate_per_1000_slots / 1000` for
+    /// every slot that passes.
+    ///
+    /// The first 10 bytes of `decay_buffer` will be set with the following data:
+    ///     bytes 0-7: last_decay_slot, the current slot
+    ///     byte 8: decay_rate
+    ///     byte 9: bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | decay_buffer: PDA of Echo Program seeded by `authority`             |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `decay_buffer`                     |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeDecayEcho {
+        buffer_size: usize,
+        decay_rate_per_1000_slots: u8,
+        authority: Pubkey,
+    },
+    /// Applies the decay of the slots elapsed since `last_decay_slot` to the data of `decay_buffer`, rounding each
+    /// byte down, and sets `last_decay_slot` to the current slot. Anyone can refresh the decay.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | decay_buffer: PDA of Echo Program seeded by `authority`             |
+    /// | 1     | ❌       | ❌     | authority: Pubkey the `decay_buffer` was created for                |
+    RefreshDecay,
+    /// Refreshes the decay like `RefreshDecay`, then copies `data` into `decay_buffer` starting from index 10,
+    /// zero-padding the rest of the buffer.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | decay_buffer: PDA of Echo Program seeded by `authority`             |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `decay_buffer`          |
+    WriteDecayEcho { data: Vec<u8> },
+    /// This instruction will allocate the `average_account` and assign it the Echo Program, with no samples.
+    ///
+    /// The 29 bytes of `average_account` will be set with the following data:
+    ///     bytes 0-15: sum
+    ///     bytes 16-23: count
+    ///     bytes 24-27: max_samples
+    ///     byte 28: bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | average_account: PDA of Echo Program seeded by `authority`    |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `average_account`            |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the account                  |
+    InitializeAverageEcho { max_samples: u32, authority: Pubkey },
+    /// Adds `value` to `sum` and increments `count`, failing with `MaxWritesExceeded` once `count` reaches
+    /// `max_samples`. Anyone can call this instruction, no signature is required.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | average_account: PDA of Echo Program seeded by `authority`    |
+    /// | 1     | ❌       | ❌     | authority: Pubkey the `average_account` was created for       |
+    SubmitSample { value: u64 },
+    /// Logs `sum / count`, rounded down, or zero if no sample was submitted.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | average_account: PDA of Echo Program seeded by `authority`    |
+    /// | 1     | ❌       | ❌     | authority: Pubkey the `average_account` was created for       |
+    ReadAverage,
+    /// Sets `sum` and `count` to zero, `max_samples` is left as is.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | average_account: PDA of Echo Program seeded by `authority`    |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `average_account` was created for       |
+    ResetAverage,
+    /// This instruction will allocate the `fee_config` and the `fee_vault` of the program and assign them the Echo
+    /// Program. Both addresses are unique for the program, so it can only run once, right after deployment.
+    ///
+    /// The 34 bytes of `fee_config` will be set with the following data:
+    ///     bytes 0-31: admin
+    ///     byte 32: bump_seed
+    ///     byte 33: vault_bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | fee_config: PDA of Echo Program seeded by `"fee_config"`      |
+    /// | 1     | ✅       | ❌     | fee_vault: PDA of Echo Program seeded by `"fee_vault"`        |
+    /// | 2     | ✅       | ✅     | payer: Pubkey that allocates both accounts                    |
+    /// | 3     | ❌       | ❌     | system_program: Used to allocate the accounts                 |
+    InitializeFeeConfig { admin: Pubkey },
+    /// Moves `amount` lamports of collected write fees from the `fee_vault` to `destination`. Only the `admin` of the
+    /// `fee_config` can withdraw, and the `fee_vault` always keeps its rent exempt minimum.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                  |
+    /// |-------|----------|--------|--------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | fee_config: PDA of Echo Program seeded by `"fee_config"`      |
+    /// | 1     | ✅       | ❌     | fee_vault: PDA of Echo Program seeded by `"fee_vault"`        |
+    /// | 2     | ❌       | ✅     | admin: Pubkey stored in the `fee_config`                      |
+    /// | 3     | ✅       | ❌     | destination: Account receiving the lamports                   |
+    WithdrawFees { amount: u64 },
+    /// This instruction will allocate `buffer_size` bytes to a vending machine buffer that anyone can write to for free,
+    /// the write through `VendingMachineEcho` skips the burn and needs no mint or token accounts.
+    ///
+    /// The first 317 bytes of `vending_machine_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-8: price (always 0)
+    ///     byte 9: free (always 1)
+    ///     byte 10: payment_mode (always 0)
+    ///     bytes 11-42: treasury (always zeroed)
+    ///     bytes 43-74: admin, the `payer`
+    ///     byte 75: paused (initially 0)
+    ///     bytes 76-83: price_per_byte (always 0)
+    ///     byte 84: require_ata (always 0)
+    ///     bytes 85-116: token_program (always zeroed)
+    ///     bytes 117-148: mint (always zeroed)
+    ///     bytes 149-156: uses (always 0)
+    ///     bytes 157-164: max_uses (always 0)
+    ///     bytes 165-172: sol_fee (always 0)
+    ///     byte 173: tier_count (always 0)
+    ///     bytes 174-221: tiers (always zeroed)
+    ///     bytes 222-253: last_writer (always zeroed)
+    ///     bytes 254-261: last_write_slot (always 0)
+    ///     bytes 262-269: expiry_slot (always 0)
+    ///     bytes 270-277: total_writes (always 0)
+    ///     bytes 278-285: total_burned (always 0)
+    ///     bytes 286-289: data_len (initially 0)
+    ///     byte 290: is_initialized (always 1)
+    ///     bytes 291-298: start_slot (always 0)
+    ///     bytes 299-306: end_slot (always 0)
+    ///     bytes 307-314: cooldown_slots (always 0)
+    ///     byte 315: whitelist_enabled (always false)
+    ///     byte 316: free_authority_writes (always false)
+    ///
+    /// Fails with `AccountAlreadyInitialized` if the `payer` already created a free vending machine.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                          |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program seeded by `"free_vm"` and `payer`     |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `vending_machine_buffer`                             |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                                           |
+    InitializePermissionlessVendingMachine { buffer_size: usize },
+    /// Closes an expired `authorized_buffer`, callable by anyone once the current slot is past its `expiry_slot`.
+    /// The `cranker` receives `crank_bounty_bps` of the buffer lamports as a bounty, rounded down, and the `authority`
+    /// receives the rest. Buffers with an `expiry_slot` of 0 never expire. If the `registry` is passed the buffer is
+    /// removed from it, like `CloseAuthorizedEcho`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer: Expired PDA of Echo Program                            |
+    /// | 1     | ✅       | ❌     | authority: Pubkey the buffer was created for, receives the remainder      |
+    /// | 2     | ✅       | ✅     | cranker: Pubkey closing the buffer, receives the bounty                   |
+    /// | 3     | ✅       | ❌     | registry: (optional) Registry of `authority` the buffer is removed from   |
+    CrankCloseExpired,
+    /// This instruction will allocate `buffer_size` bytes to the authorized buffer of `authority` at buffer seed 0,
+    /// like `InitializeAuthorizedEcho` without label, delegate, fee or expiry. The rent is paid by the `sponsor` PDA
+    /// of `authority` through `invoke_signed`, so no signer of the transaction pays for the buffer. The `sponsor` must
+    /// have been funded with `FundSponsor` first.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                  |
+    /// |-------|----------|--------|------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program seeded by `authority` and buffer seed 0 |
+    /// | 1     | ✅       | ❌     | sponsor: PDA of Echo Program seeded by `"sponsor"` and `authority`             |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                                   |
+    InitializeSponsoredEcho {
+        buffer_size: usize,
+        authority: Pubkey,
+    },
+    /// Transfers `lamports` from the `payer` to the `sponsor` of `authority`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                          |
+    /// |-------|----------|--------|----------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | sponsor: PDA of Echo Program seeded by `"sponsor"` and `authority`     |
+    /// | 1     | ❌       | ❌     | authority: Pubkey the `sponsor` pays rent for                          |
+    /// | 2     | ✅       | ✅     | payer: Pubkey funding the `sponsor`                                    |
+    /// | 3     | ❌       | ❌     | system_program: Used to transfer the lamports                          |
+    FundSponsor { lamports: u64 },
+    /// Transfers every lamport of the `sponsor` of `authority` to `destination`, only `authority` can drain it.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                          |
+    /// |-------|----------|--------|----------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | sponsor: PDA of Echo Program seeded by `"sponsor"` and `authority`     |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the `sponsor` pays rent for                          |
+    /// | 2     | ✅       | ❌     | destination: Account receiving the lamports                            |
+    /// | 3     | ❌       | ❌     | system_program: Used to transfer the lamports                          |
+    DrainSponsor { authority: Pubkey },
+    /// Validates the `authority` and the `authorized_buffer` and charges the write fee exactly like `AuthorizedEcho`,
+    /// but only stores `data`'s keccak hash and length, so large messages stay verifiable without occupying the
+    /// buffer. The full `data` is logged in a `HashOnlyWrite` event for indexers.
+    ///
+    /// The payload of `authorized_buffer`, starting from index 146, will be set with the following data:
+    ///     bytes 0-31: keccak hash of `data`
+    ///     bytes 32-35: length of `data`
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
+    /// | 2     | ✅       | ❌     | fee_vault: (optional) PDA of Echo Program seeded by `"fee_vault"`         |
+    /// | 3     | ❌       | ❌     | system_program: (optional) Used to transfer the write fee                 |
+    AuthorizedEchoHashOnly { data: Vec<u8> },
+    /// Recomputes the hash and length of `data` and compares them with the ones stored by `AuthorizedEchoHashOnly`,
+    /// failing with `HashMismatch` if they differ.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | authorized_buffer: PDA of Echo Program holding the hash                   |
+    /// | 1     | ❌       | ❌     | authority: Pubkey the buffer was created for                              |
+    VerifyAuthorizedEcho { data: Vec<u8> },
+    /// This instruction will allocate `buffer_size` bytes to the `random_buffer` account and assign it the Echo
+    /// Program. Its data starts zeroed and is mixed with a slot hash by every `RefreshRandom`.
+    ///
+    /// The first 9 bytes of `random_buffer` will be set with the following data:
+    ///     bytes 0-7: last_refresh_slot, 0 until the first refresh
+    ///     byte 8: bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | random_buffer: PDA of Echo Program seeded by `authority`            |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `random_buffer`                    |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeRandomEcho {
+        buffer_size: usize,
+        authority: Pubkey,
+    },
+    /// XORs the data of `random_buffer` past index 9 with the most recent hash of the `SlotHashes` sysvar, repeated
+    /// every 32 bytes, and sets `last_refresh_slot` to the current slot. A second refresh in the same slot leaves the
+    /// buffer unchanged instead of replaying the hash.
+    ///
+    /// This is not cryptographically secure randomness. The slot hash is known before the refresh is processed and
+    /// the leader can influence it, so the data must not be used for high-stakes lotteries.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | random_buffer: PDA of Echo Program seeded by `authority`            |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `random_buffer`         |
+    /// | 2     | ❌       | ❌     | slot_hashes: The `SlotHashes` sysvar                                |
+    RefreshRandom,
+    /// Copies `data` into the payload of `authorized_buffer` at `offset`, keeping the bytes around it, so payloads
+    /// larger than a transaction can be uploaded over several transactions. The buffer is marked as
+    /// `write_in_progress` until `AuthorizedEchoFinalize`, no event is emitted and the sequence is left as is.
+    ///
+    /// If the buffer has a non-zero `write_fee_lamports` it is charged like in `AuthorizedEcho`, on every chunk.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
+    /// | 2     | ✅       | ❌     | fee_vault: (optional) PDA of Echo Program seeded by `"fee_vault"`         |
+    /// | 3     | ❌       | ❌     | system_program: (optional) Used to transfer the write fee                 |
+    AuthorizedEchoChunk { offset: u32, data: Vec<u8> },
+    /// Completes a chunked write: checks that the keccak hash of the first `total_len` bytes of the payload is `hash`,
+    /// failing with `HashMismatch` otherwise, zeroes the payload past them and clears `write_in_progress`. The
+    /// cursor is set to `total_len` and the sequence is incremented like any other write.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
+    AuthorizedEchoFinalize { total_len: u32, hash: [u8; 32] },
+    /// Closes a paid `vending_machine_buffer`, sending its lamports to `destination` and zeroing its data. The buffer
+    /// stores no authority, so the mint authority of `vending_machine_mint` must sign instead.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                  |
+    /// |-------|----------|--------|------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program seeded by the mint and price  |
+    /// | 1     | ❌       | ❌     | vending_machine_mint: The token mint accepted by the `vending_machine_buffer` |
+    /// | 2     | ❌       | ✅     | mint_authority: Mint authority of `vending_machine_mint`                      |
+    /// | 3     | ✅       | ❌     | destination: Account receiving the lamports                                   |
+    CloseVendingMachineBuffer,
+    /// This instruction will allocate `segment_count` segments of `segment_size` bytes to the `segmented_buffer`
+    /// account and assign it the Echo Program. Each segment is an independent record, written with
+    /// `AuthorizedEchoSegment` and tracked by a bitmap of the written segments.
+    ///
+    /// The first 38 bytes of `segmented_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-4: segment_size
+    ///     byte 5: segment_count
+    ///     bytes 6-37: bitmap, bit `i % 8` of byte `i / 8` is set once segment `i` is written
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | segmented_buffer: PDA of Echo Program seeded by `authority`         |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `segmented_buffer`                 |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeSegmentedEcho {
+        segment_size: u32,
+        segment_count: u8,
+        authority: Pubkey,
+    },
+    /// Copies `data` into segment `index`, truncating or zero-padding it to `segment_size`, and sets its bit in the
+    /// bitmap. Fails with `SegmentOutOfRange` if `index` is not below `segment_count`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | segmented_buffer: PDA of Echo Program seeded by `authority`         |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `segmented_buffer`      |
+    AuthorizedEchoSegment { index: u8, data: Vec<u8> },
+    /// Zeroes segment `index` and clears its bit in the bitmap. Fails with `SegmentOutOfRange` if `index` is not below
+    /// `segment_count`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | segmented_buffer: PDA of Echo Program seeded by `authority`         |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `segmented_buffer`      |
+    ClearSegment { index: u8 },
+    /// Attaches a name, description and URI to `authorized_buffer`, each zero-padded UTF-8. They are stored in the
+    /// `metadata` PDA, created on first use and paid by the `authority`, later calls overwrite all three fields.
+    ///
+    /// The `metadata` account will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: name
+    ///     bytes 33-160: description
+    ///     bytes 161-360: uri
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                |
+    /// |-------|----------|--------|----------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | metadata: PDA of Echo Program seeded by `authorized_buffer`                 |
+    /// | 1     | ❌       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to   |
+    /// | 2     | ✅       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`             |
+    /// | 3     | ❌       | ❌     | system_program: (first call only) Used to allocate the `metadata` account   |
+    SetBufferMetadata {
+        name: [u8; METADATA_NAME_LEN],
+        description: [u8; METADATA_DESCRIPTION_LEN],
+        uri: MetadataUri,
+    },
+    /// Logs the name, description and URI attached to `authorized_buffer`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                |
+    /// |-------|----------|--------|----------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | metadata: PDA of Echo Program seeded by `authorized_buffer`                 |
+    /// | 1     | ❌       | ❌     | authorized_buffer: The buffer the metadata is attached to                   |
+    GetBufferMetadata,
+    /// Closes the `metadata` of `authorized_buffer`, sending its lamports to the `authority`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                |
+    /// |-------|----------|--------|----------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | metadata: PDA of Echo Program seeded by `authorized_buffer`                 |
+    /// | 1     | ❌       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to   |
+    /// | 2     | ✅       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`             |
+    CloseBufferMetadata,
+    /// Like `InitializeVendingMachineEcho`, but writes are paid in lamports instead of tokens. The `admin` pays for the
+    /// `vending_machine_buffer` and is the only key allowed to withdraw the proceeds from the vault PDA seeded by
+    /// `[b"sol_vending_machine_vault", vending_machine_buffer]`.
+    ///
+    /// The first 42 bytes of `vending_machine_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     byte 1: vault_bump_seed
+    ///     bytes 2-9: price_lamports
+    ///     bytes 10-41: admin
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program seeded by the admin and price   |
+    /// | 1     | ✅       | ✅     | admin: Pubkey that allocates the buffer and withdraws its proceeds              |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                                     |
+    InitializeSolVendingMachine {
+        price_lamports: u64,
+        buffer_size: usize,
+    },
+    /// Transfers `price_lamports` from the `user` to the vault, then copies `data` into `vending_machine_buffer` past
+    /// the header, zero padding the rest. Fails with `InsufficientFunds` before writing anything if the `user` can't
+    /// pay.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program seeded by the admin and price   |
+    /// | 1     | ✅       | ✅     | user: Pubkey paying for the write                                               |
+    /// | 2     | ✅       | ❌     | vault: PDA of the Echo Program collecting the payments                          |
+    /// | 3     | ❌       | ❌     | system_program: Used to transfer the payment                                    |
+    SolVendingMachineEcho { data: Vec<u8> },
+    /// Moves every lamport of the vault of `vending_machine_buffer` to `destination`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | vending_machine_buffer: PDA of the Echo Program seeded by the admin and price   |
+    /// | 1     | ✅       | ❌     | vault: PDA of the Echo Program collecting the payments                          |
+    /// | 2     | ❌       | ✅     | admin: Pubkey that initialized the `vending_machine_buffer`                     |
+    /// | 3     | ✅       | ❌     | destination: Account receiving the proceeds                                     |
+    /// | 4     | ❌       | ❌     | system_program: Used to transfer the proceeds                                   |
+    WithdrawSolVendingProceeds,
+    /// Transfers `amount` tokens, or the whole balance when `amount` is 0, from a `vault` token account owned by the
+    /// vault authority PDA of `vending_machine_buffer` to `destination`. Only the admin stored in the header can
+    /// withdraw, and the `vault` must hold the mint of the vending machine.
+    ///
+    /// The tokens are moved with `TransferChecked` through the token program the vending machine was initialized
+    /// with, the Token Program or Token-2022, failing with `InvalidProgramAddress` for the other one and with
+    /// `MintMismatch` if `vending_machine_mint` isn't the mint stored in the header.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                  |
+    /// |-------|----------|--------|------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | vending_machine_buffer: PDA of the Echo Program seeded by the mint and price  |
+    /// | 1     | ✅       | ❌     | vault: Token account owned by `vault_authority`                               |
+    /// | 2     | ❌       | ❌     | vault_authority: PDA of the Echo Program seeded by `"vending_vault"`          |
+    /// | 3     | ✅       | ❌     | destination: Token account of the same mint receiving the tokens              |
+    /// | 4     | ❌       | ✅     | admin: Admin stored in the header of `vending_machine_buffer`                 |
+    /// | 5     | ❌       | ❌     | token_program: Token program stored in the header                             |
+    /// | 6     | ❌       | ❌     | vending_machine_mint: Mint stored in the header of `vending_machine_buffer`   |
+    WithdrawVendingMachineProceeds { amount: u64 },
+    /// This instruction will allocate `total_size` bytes after the header to the `multi_slot_buffer` account and assign
+    /// it the Echo Program. The data is uploaded in `ceil(total_size / chunk_size)` chunks, at most 64, with
+    /// `WriteMultiSlotChunk` and then sealed with `CommitMultiSlot`.
+    ///
+    /// The first 20 bytes of `multi_slot_buffer` will be set with the following data:
+    ///     bytes 0-3: total_size
+    ///     bytes 4-5: chunk_size
+    ///     bytes 6-7: chunks_written
+    ///     bytes 8-9: total_chunks
+    ///     byte 10: committed
+    ///     byte 11: bump_seed
+    ///     bytes 12-19: written_chunks, bit `i` is set once chunk `i` is written
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | multi_slot_buffer: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `multi_slot_buffer`                |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeMultiSlotEcho {
+        total_size: u32,
+        chunk_size: u16,
+        authority: Pubkey,
+    },
+    /// Copies `data` to `chunk_index * chunk_size` past the header, truncating or zero-padding it to the chunk, and
+    /// sets the bit of the chunk. Chunks can be written in any order and rewritten until the buffer is committed, then
+    /// fails with `MultiSlotCommitted`. Fails with `InvalidInstructionInput` if `chunk_index >= total_chunks`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | multi_slot_buffer: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `multi_slot_buffer`     |
+    WriteMultiSlotChunk { chunk_index: u16, data: Vec<u8> },
+    /// Sets `committed` once every chunk is written, fails with `MultiSlotIncomplete` otherwise.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | multi_slot_buffer: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `multi_slot_buffer`     |
+    CommitMultiSlot,
+    /// Zeroes the data past the header and resets `chunks_written`, `written_chunks` and `committed`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | multi_slot_buffer: PDA of Echo Program seeded by `authority`        |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `multi_slot_buffer`     |
+    AbortMultiSlot,
+    /// This instruction will allocate a `voted_buffer` holding a payload of `buffer_size` bytes followed by a staging
+    /// region of the same size and assign it the Echo Program. Writes are staged with `ProposeVotedWrite` and only
+    /// reach the payload once `quorum` of the `voters` (at most 8, no duplicates) voted for them with `CastVote`.
+    ///
+    /// The first 335 bytes of `voted_buffer` will be set with the following data:
+    ///     bytes 0-255: voters, zero-padded to 8 keys
+    ///     byte 256: voter_count
+    ///     byte 257: quorum
+    ///     bytes 258-265: vote_bitmap, bit `i` is set once `voters[i]` voted
+    ///     bytes 266-297: pending_data_hash, keccak hash of the staged data, zeroed while no write is pending
+    ///     bytes 298-301: pending_len
+    ///     bytes 302-333: creator, the `payer`
+    ///     byte 334: bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | voted_buffer: PDA of Echo Program seeded by `payer`                 |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `voted_buffer`                     |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeVotedEcho {
+        buffer_size: usize,
+        voters: Vec<Pubkey>,
+        quorum: u8,
+    },
+    /// Copies `data` into the staging region and stores its hash, replacing any pending write and resetting the votes.
+    /// `data` must fit in the payload. Only voters can propose.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | voted_buffer: PDA of Echo Program seeded by its creator             |
+    /// | 1     | ❌       | ✅     | voter: One of the voters of `voted_buffer`                          |
+    ProposeVotedWrite { data: Vec<u8> },
+    /// Sets the bit of the `voter` in `vote_bitmap`, failing with `AlreadyVoted` if it is already set. Once `quorum`
+    /// bits are set, the staged data replaces the payload, zero padded, and the pending write is cleared.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | voted_buffer: PDA of Echo Program seeded by its creator             |
+    /// | 1     | ❌       | ✅     | voter: One of the voters of `voted_buffer`                          |
+    CastVote,
+    /// Zeroes the staging region and clears the pending write and its votes.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | voted_buffer: PDA of Echo Program seeded by its creator             |
+    /// | 1     | ❌       | ✅     | voter: One of the voters of `voted_buffer`                          |
+    AbortVotedWrite,
+    /// Pauses `vending_machine_buffer`, `VendingMachineEcho` fails with `VendingMachinePaused` until it is resumed.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                       |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program                                    |
+    /// | 1     | ❌       | ❌     | seed: `vending_machine_mint` of a paid buffer, `creator` of a permissionless one   |
+    /// | 2     | ❌       | ✅     | admin: Admin stored in the header of `vending_machine_buffer`                      |
+    PauseVendingMachine,
+    /// Resumes a paused `vending_machine_buffer`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                       |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program                                    |
+    /// | 1     | ❌       | ❌     | seed: `vending_machine_mint` of a paid buffer, `creator` of a permissionless one   |
+    /// | 2     | ❌       | ✅     | admin: Admin stored in the header of `vending_machine_buffer`                      |
+    ResumeVendingMachine,
+    /// This instruction will allocate `buffer_size` bytes to the `gasless_buffer` account and assign it the Echo
+    /// Program. The `authority` signs its writes off-chain and the `relayer` submits them, paying the fees.
+    ///
+    /// The first 73 bytes of `gasless_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: authority
+    ///     bytes 33-64: relayer
+    ///     bytes 65-72: nonce
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | gasless_buffer: PDA of Echo Program seeded by `authority`           |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `gasless_buffer`                   |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeGaslessEcho {
+        buffer_size: usize,
+        authority: Pubkey,
+        relayer: Pubkey,
+    },
+    /// Copies `data` into `gasless_buffer` past the header like `AuthorizedEcho`, without the `authority` signing the
+    /// transaction. Instead, the instruction right before this one must be an Ed25519 Program instruction verifying
+    /// `user_signature` by the `authority` over `gasless_buffer || nonce (little-endian u64) || data`, with the key,
+    /// signature and message stored in the Ed25519 instruction itself. Fails with `InvalidSignature` otherwise. The
+    /// `nonce` is then incremented, so a signature can't be replayed.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | gasless_buffer: PDA of Echo Program seeded by `authority`           |
+    /// | 1     | ❌       | ✅     | relayer: Relayer stored in the header of `gasless_buffer`           |
+    /// | 2     | ❌       | ❌     | instructions: Instructions sysvar                                   |
+    GaslessEcho {
+        data: Vec<u8>,
+        user_signature: [u8; 64],
+    },
+    /// Replaces the relayer of `gasless_buffer`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | gasless_buffer: PDA of Echo Program seeded by `authority`           |
+    /// | 1     | ❌       | ✅     | authority: Authority stored in the header of `gasless_buffer`       |
+    UpdateRelayer { new_relayer: Pubkey },
+    /// This instruction will allocate `buffer_size` bytes to the `oracle_buffer` account and assign it the Echo
+    /// Program. Writes through `OracleGatedWrite` only succeed while the price read from `oracle_pubkey` is within
+    /// `[min_price, max_price]`, fails with `InvalidInstructionInput` if `min_price > max_price`.
+    ///
+    /// The first 81 bytes of `oracle_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: authority
+    ///     bytes 33-64: oracle
+    ///     bytes 65-72: min_price
+    ///     bytes 73-80: max_price
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | oracle_buffer: PDA of Echo Program seeded by `authority`            |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `oracle_buffer`                    |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeOracleEcho {
+        buffer_size: usize,
+        authority: Pubkey,
+        oracle_pubkey: Pubkey,
+        min_price: i64,
+        max_price: i64,
+    },
+    /// Copies `data` into `oracle_buffer` past the header like `AuthorizedEcho`, if the little-endian `i64` price at
+    /// `oracle_offset` in the data of the `oracle` is within the range of the header. Fails with `ConditionNotMet`
+    /// otherwise, and with `InvalidInstructionInput` if the price doesn't fit in the `oracle` data.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | oracle_buffer: PDA of Echo Program seeded by `authority`            |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `oracle_buffer`         |
+    /// | 2     | ❌       | ❌     | oracle: Price feed stored in the header of `oracle_buffer`          |
+    OracleGatedWrite { data: Vec<u8>, oracle_offset: u16 },
+    /// Replaces the price range of `oracle_buffer` with `[new_min, new_max]`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | oracle_buffer: PDA of Echo Program seeded by `authority`            |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `oracle_buffer`         |
+    UpdateOracleGate { new_min: i64, new_max: i64 },
+    /// This instruction will allocate `buffer_size` bytes to the `memo_buffer` account and assign it the Echo Program.
+    /// Writes through `MemoEcho` also log their data with the SPL Memo program.
+    ///
+    /// The first 33 bytes of `memo_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: authority
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | memo_buffer: PDA of Echo Program seeded by `"memo"` and `authority` |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `memo_buffer`                      |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeMemoEcho {
+        buffer_size: usize,
+        authority: Pubkey,
+    },
+    /// Copies `data` into `memo_buffer` past the header like `AuthorizedEcho`, then invokes the SPL Memo program with
+    /// the lowercase hex encoding of the full `data` as the memo. The memo is part of the inner instructions of the
+    /// transaction, an audit trail of every write even once the buffer is overwritten.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | memo_buffer: PDA of Echo Program seeded by `"memo"` and `authority` |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `memo_buffer`           |
+    /// | 2     | ❌       | ❌     | memo_program: The SPL Memo program                                  |
+    MemoEcho { data: Vec<u8> },
+    /// This instruction will allocate `buffer_size` bytes to the `schema_buffer` account and assign it the Echo Program.
+    /// `schema_hash` is the SHA-256 of the canonical Borsh schema descriptor string of the data, which every
+    /// `SchemaWrite` has to provide. Off-chain tools can read the hash from the header to check the data independently.
+    ///
+    /// The first 65 bytes of `schema_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: authority
+    ///     bytes 33-64: schema_hash
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | schema_buffer: PDA of Echo Program seeded by `authority`            |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `schema_buffer`                    |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeSchemaEcho {
+        schema_hash: [u8; 32],
+        buffer_size: usize,
+        authority: Pubkey,
+    },
+    /// Copies `data` into `schema_buffer` past the header like `AuthorizedEcho`, if the SHA-256 of
+    /// `schema_descriptor` is the `schema_hash` of the header. Fails with `InvalidSchemaDescriptor` otherwise.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | schema_buffer: PDA of Echo Program seeded by `authority`            |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `schema_buffer`         |
+    SchemaWrite {
+        data: Vec<u8>,
+        schema_descriptor: Vec<u8>,
+    },
+    /// This instruction will allocate the `compressed_buffer` account and assign it the Echo Program. Instead of raw
+    /// data it stores the hash of a leaf of the off-chain Merkle tree identified by `tree_id`, with the root it was
+    /// proven against.
+    ///
+    /// The 133 bytes of `compressed_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: authority
+    ///     bytes 33-64: tree_id
+    ///     bytes 65-96: leaf (initially zeroed)
+    ///     bytes 97-128: root (initially zeroed)
+    ///     bytes 129-132: leaf_index
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                |
+    /// |-------|----------|--------|----------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | compressed_buffer: PDA of Echo Program seeded by `tree_id` and `authority`  |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `compressed_buffer`                        |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                                 |
+    InitializeCompressedEcho { tree_id: Pubkey, authority: Pubkey },
+    /// Replaces the leaf of `compressed_buffer` with `new_leaf` and logs it, once `proof` shows it is at `proof_index`
+    /// of the SHA-256 Merkle tree with `root`. The `proof` holds the sibling hashes from the leaf up, a node on the
+    /// left of its sibling is hashed first. Fails with `InvalidMerkleProof` otherwise.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                |
+    /// |-------|----------|--------|----------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | compressed_buffer: PDA of Echo Program seeded by `tree_id` and `authority`  |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `compressed_buffer`             |
+    UpdateCompressedEcho {
+        new_leaf: [u8; 32],
+        proof: Vec<[u8; 32]>,
+        proof_index: u32,
+        root: [u8; 32],
+    },
+    /// This instruction will allocate `buffer_size` bytes to the `auction_buffer` account and assign it the Echo
+    /// Program. Instead of paying per write, bidders compete with `BidOnVendingSlot` until `auction_duration_slots`
+    /// from now, and the highest bidder gets write access once the auction is settled.
+    ///
+    /// The first 91 bytes of `auction_buffer` will be set with the following data:
+    ///     bytes 0-7: end_slot
+    ///     bytes 8-15: highest_bid (initially 0)
+    ///     bytes 16-47: highest_bidder (initially zeroed)
+    ///     byte 48: settled (initially 0)
+    ///     byte 49: bump_seed
+    ///     byte 50: vault_bump_seed
+    ///     bytes 51-58: reserve_price
+    ///     bytes 59-90: creator
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | auction_buffer: PDA of Echo Program seeded by `creator`             |
+    /// | 1     | ✅       | ✅     | creator: Pays for the buffer and receives the highest bid           |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeAuctionVendingMachine {
+        buffer_size: usize,
+        auction_duration_slots: u64,
+        reserve_price: u64,
+    },
+    /// Transfers `bid_lamports` from the `bidder` to the vault and refunds the previous highest bid from it. Fails
+    /// with `BidTooLow` unless the bid is at least the reserve price and above the highest bid, and with
+    /// `AuctionEnded` past the end slot.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | auction_buffer: PDA of Echo Program seeded by `creator`             |
+    /// | 1     | ✅       | ❌     | vault: PDA of Echo Program seeded by `"auction_vault"` and buffer   |
+    /// | 2     | ✅       | ✅     | bidder: Pubkey paying the bid                                       |
+    /// | 3     | ❌       | ❌     | system_program: Used to transfer the bids                           |
+    /// | 4     | ✅       | ❌     | previous_bidder: (after the first bid) Current highest bidder       |
+    BidOnVendingSlot { bid_lamports: u64 },
+    /// Transfers the highest bid from the vault to the `creator` once the end slot has passed, failing with
+    /// `AuctionNotEnded` before. Anyone can settle the auction, the highest bidder can then write with `AuctionEcho`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | auction_buffer: PDA of Echo Program seeded by `creator`             |
+    /// | 1     | ✅       | ❌     | vault: PDA of Echo Program seeded by `"auction_vault"` and buffer   |
+    /// | 2     | ✅       | ❌     | creator: Creator stored in the header                               |
+    /// | 3     | ❌       | ❌     | system_program: Used to transfer the highest bid                    |
+    SettleAuction,
+    /// Copies `data` into `auction_buffer` past the header, only the winner of the settled auction can.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | auction_buffer: PDA of Echo Program seeded by `creator`             |
+    /// | 1     | ❌       | ✅     | winner: Highest bidder of the auction                               |
+    AuctionEcho { data: Vec<u8> },
+    /// Closes `vending_machine_buffer`, sending its lamports to `recipient` and zeroing its data. Only the admin stored
+    /// in the header can close it, failing with `UnauthorizedCaller` otherwise. Permissionless machines are checked
+    /// against the admin seeds, paid ones against the mint stored in the header, failing with `MintMismatch` if another
+    /// `vending_machine_mint` is passed. In treasury mode the treasury has to be empty, failing with `VaultNotEmpty`
+    /// otherwise, its tokens can be taken out first with `WithdrawVendingMachineProceeds`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                  |
+    /// |-------|----------|--------|------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program                               |
+    /// | 1     | ❌       | ✅     | admin: Admin stored in the header of `vending_machine_buffer`                 |
+    /// | 2     | ✅       | ❌     | recipient: Account receiving the lamports                                     |
+    /// | 3     | ❌       | ❌     | vending_machine_mint: (paid machines only) Mint accepted by the machine       |
+    /// | 4     | ❌       | ❌     | treasury: (treasury mode only) Treasury stored in the header                  |
+    CloseVendingMachine,
+    /// This instruction will allocate `buffer_size` bytes to the `stake_gated_buffer` account and assign it the Echo
+    /// Program. Writes through `StakeGatedWrite` need a stake account delegating at least `min_stake` lamports to
+    /// `vote_account`, so a validator can open its buffer to its delegators.
+    ///
+    /// The first 73 bytes of `stake_gated_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: creator
+    ///     bytes 33-64: vote_account
+    ///     bytes 65-72: min_stake
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | stake_gated_buffer: PDA of Echo Program seeded by `creator` and `vote_account` |
+    /// | 1     | ✅       | ✅     | creator: Pubkey that allocates the `stake_gated_buffer`                        |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                                    |
+    InitializeStakeGatedEcho {
+        buffer_size: usize,
+        vote_account: Pubkey,
+        min_stake: u64,
+    },
+    /// Copies `data` into `stake_gated_buffer` past the header. The `stake_account` must be owned by the Stake Program,
+    /// have `staker` as its staker authority and delegate to the vote account of the buffer. Fails with
+    /// `InsufficientFunds` if the delegated stake is below `min_stake` or deactivating.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | stake_gated_buffer: PDA of Echo Program seeded by `creator` and `vote_account` |
+    /// | 1     | ❌       | ✅     | staker: Staker authority of `stake_account`                                    |
+    /// | 2     | ❌       | ❌     | stake_account: Stake account delegated to `vote_account`                       |
+    StakeGatedWrite { data: Vec<u8> },
+    /// This instruction will allocate `buffer_size` bytes to both the `primary_buffer` and the `replica_buffer` accounts
+    /// and assign them the Echo Program. `ReplicatedWrite` writes the same data to both buffers of the pair, which stay
+    /// identical as a write fails entirely if either buffer can't take it.
+    ///
+    /// The first 67 bytes of each buffer will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     byte 1: role, 0 for the primary buffer and 1 for the replica buffer
+    ///     bytes 2-33: primary_authority
+    ///     bytes 34-65: replica_authority
+    ///     byte 66: frozen (initially 0)
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | primary_buffer: PDA of Echo Program seeded by both authorities and role 0      |
+    /// | 1     | ✅       | ❌     | replica_buffer: PDA of Echo Program seeded by both authorities and role 1      |
+    /// | 2     | ✅       | ✅     | payer: Pubkey that allocates both buffers                                      |
+    /// | 3     | ❌       | ❌     | system_program: Used to allocate the buffers                                   |
+    InitializeReplicatedEcho {
+        primary_authority: Pubkey,
+        replica_authority: Pubkey,
+        buffer_size: usize,
+    },
+    /// Copies `data` into both buffers of a pair past the header, zeroing the rest of the payloads. Nothing is written if
+    /// either buffer is frozen, failing with `BufferFrozen`, or too small for `data`, failing with `BufferFull`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | primary_buffer: Primary buffer of the pair                                     |
+    /// | 1     | ✅       | ❌     | replica_buffer: Replica buffer of the pair                                     |
+    /// | 2     | ❌       | ✅     | primary_authority: Primary authority stored in both headers                    |
+    /// | 3     | ❌       | ✅     | replica_authority: Replica authority stored in both headers                    |
+    ReplicatedWrite { data: Vec<u8> },
+    /// Sets the `frozen` flag of a buffer of a replicated pair, replicated writes fail while either buffer is frozen.
+    /// Only the authority of the buffer's role can freeze it.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | replicated_buffer: Primary or replica buffer of a pair                         |
+    /// | 1     | ❌       | ✅     | authority: Authority of the role of `replicated_buffer`                        |
+    SetReplicaFrozen { frozen: bool },
+    /// Initializes a vending machine like `InitializeVendingMachineEcho` in burn mode, which also charges `sol_fee`
+    /// lamports on every write. The fee is transferred to the fee treasury before the tokens are burned, and a failed
+    /// transfer fails the write without burning anything.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program seeded by the mint and price   |
+    /// | 1     | ❌       | ❌     | vending_machine_mint: The token mint accepted by the `vending_machine_buffer`  |
+    /// | 2     | ❌       | ✅     | mint_authority: Mint authority of `vending_machine_mint`                       |
+    /// | 3     | ❌       | ✅     | payer: Pubkey that allocates the `vending_machine_buffer`                      |
+    /// | 4     | ❌       | ❌     | system_program: Used to allocate the buffer                                    |
+    InitializePriorityFeeEcho {
+        price: u64,
+        buffer_size: usize,
+        /// Lamports paid to the fee treasury on every write
+        sol_fee: u64,
+    },
+    /// Creates the fee treasury collecting the SOL fees of vending machines, unique for the program so this can only
+    /// succeed once. The fees are held in the lamports of the account, above its rent exempt minimum.
+    ///
+    /// The 33 bytes of `treasury` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: authority
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | treasury: PDA of Echo Program seeded by `"treasury"` and the program id        |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `treasury`                                    |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the treasury                                  |
+    InitializeTreasury { authority: Pubkey },
+    /// Moves every lamport above the rent exempt minimum of the fee treasury to `destination`, only the treasury
+    /// authority can drain it.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | treasury: PDA of Echo Program seeded by `"treasury"` and the program id        |
+    /// | 1     | ❌       | ✅     | authority: Authority stored in the `treasury`                                  |
+    /// | 2     | ✅       | ❌     | destination: Account receiving the lamports, must be `destination`             |
+    DrainTreasury { destination: Pubkey },
+    /// This instruction will allocate `buffer_size` bytes to the `ownership_buffer` account and assign it the Echo
+    /// Program, recording the signing `owner` as the current owner of `asset`.
+    ///
+    /// `asset` is a mint of the Token Program, and as there is a single record per asset the `owner` must prove they
+    /// hold it with `asset_token_account`. It fails with `InvalidAccountData` if the token account holds another mint,
+    /// and with `UnauthorizedCaller` unless it belongs to the `owner` and holds at least one token.
+    ///
+    /// The first 73 bytes of `ownership_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: asset
+    ///     bytes 33-64: current_owner
+    ///     bytes 65-72: transfer_count
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | ownership_buffer: PDA of Echo Program seeded by `"ownership"` and `asset`      |
+    /// | 1     | ✅       | ✅     | owner: First owner of `asset`, pays for the `ownership_buffer`                 |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                                    |
+    /// | 3     | ❌       | ❌     | asset_token_account: Token account of the `owner` holding `asset`              |
+    InitializeOwnershipEcho { buffer_size: usize, asset: Pubkey },
+    /// Transfers the ownership recorded in `ownership_buffer` to `new_owner` and copies `data` past the header like
+    /// `AuthorizedEcho`. The instruction right before this one must be an Ed25519 Program instruction verifying a
+    /// signature by the `current_owner` over `ownership_buffer || transfer_count (little-endian u64) || new_owner ||
+    /// data`, with the key, signature and message stored in the Ed25519 instruction itself. Fails with
+    /// `InvalidSignature` otherwise. The `transfer_count` is then incremented, so an approval can't be replayed.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | ownership_buffer: PDA of Echo Program seeded by `"ownership"` and `asset`      |
+    /// | 1     | ❌       | ✅     | new_owner: Becomes the `current_owner`                                         |
+    /// | 2     | ❌       | ❌     | instructions: Instructions sysvar                                              |
+    ClaimOwnership { data: Vec<u8> },
+    /// This instruction will allocate `buffer_size` bytes to the `subscription_buffer` account and assign it the Echo
+    /// Program. The `authority` can write through `SubscriptionWrite` until `expires_at_slot`, which anyone extends by
+    /// burning `price_per_period` tokens of `mint` per period through `RenewSubscription`. Fails with
+    /// `InvalidInstructionInput` if `period_slots` is 0.
+    ///
+    /// The first 89 bytes of `subscription_buffer` will be set with the following data:
+    ///     bytes 0-7: expires_at_slot (initially 0, expired)
+    ///     bytes 8-15: price_per_period
+    ///     bytes 16-23: period_slots
+    ///     bytes 24-55: mint
+    ///     byte 56: bump_seed
+    ///     bytes 57-88: authority
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | subscription_buffer: PDA of Echo Program seeded by `authority` and `mint`      |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `subscription_buffer`                         |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                                    |
+    InitializeSubscriptionEcho {
+        period_slots: u64,
+        price_per_period: u64,
+        buffer_size: usize,
+        authority: Pubkey,
+        mint: Pubkey,
+    },
+    /// Burns `price_per_period * periods` tokens from the `user_token_account` and extends `expires_at_slot` by
+    /// `period_slots * periods`, from the current slot if the subscription already expired. Fails with
+    /// `InvalidInstructionInput` if `periods` is 0 and with `Overflow` if the price or expiry doesn't fit in a u64.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | subscription_buffer: PDA of Echo Program seeded by `authority` and `mint`      |
+    /// | 1     | ❌       | ✅     | user: Owner or delegate of `user_token_account`                                |
+    /// | 2     | ✅       | ❌     | user_token_account: Token account of `mint` paying the renewal                 |
+    /// | 3     | ✅       | ❌     | mint: Mint stored in the header, fails with `MintMismatch` otherwise           |
+    /// | 4     | ❌       | ❌     | token_program: Used to burn the tokens                                         |
+    RenewSubscription { periods: u8 },
+    /// Copies `data` past the header like `AuthorizedEcho`, failing with `SubscriptionExpired` once the current slot
+    /// reaches `expires_at_slot`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | subscription_buffer: PDA of Echo Program seeded by `authority` and `mint`      |
+    /// | 1     | ❌       | ✅     | authority: Authority stored in the header of `subscription_buffer`             |
+    SubscriptionWrite { data: Vec<u8> },
+    /// Initializes a vending machine like `InitializeVendingMachineEcho` in burn mode, that expires at `expiry_slot`.
+    /// `mint` must be the `vending_machine_mint` passed, failing with `MintMismatch` otherwise, and `expiry_slot` must be
+    /// after the current slot, failing with `InvalidInstructionInput` otherwise. It is stored at bytes 262-269 of the
+    /// header.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program seeded by `mint` and `price`   |
+    /// | 1     | ❌       | ❌     | vending_machine_mint: Mint paying for the writes, must be `mint`               |
+    /// | 2     | ❌       | ✅     | mint_authority: Mint authority of `vending_machine_mint`                       |
+    /// | 3     | ❌       | ✅     | payer: Pubkey that allocates the `vending_machine_buffer`                      |
+    /// | 4     | ❌       | ❌     | system_program: Used to allocate the buffer                                    |
+    InitializeExpiringVendingMachine {
+        buffer_size: usize,
+        price: u64,
+        mint: Pubkey,
+        expiry_slot: u64,
+    },
+    /// Closes an expiring vending machine once the current slot reaches its `expiry_slot`, failing with
+    /// `BufferNotExpired` before. Callable by anyone, the mint authority doesn't sign. The data of the buffer is zeroed
+    /// and all its lamports are transferred to `destination`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program seeded by `mint` and `price`   |
+    /// | 1     | ✅       | ❌     | destination: Account receiving the lamports, must be `destination`             |
+    ClaimExpiredVendingMachine { destination: Pubkey },
+    /// Replaces the admin of a paid vending machine, the key allowed to pause, resume, close it and withdraw its
+    /// proceeds. Both the current and the new admin sign. Permissionless vending machines derive their address from
+    /// their admin, so rotating it fails with `InvalidInstructionInput`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program seeded by `mint` and `price`   |
+    /// | 1     | ❌       | ✅     | admin: Admin stored in the header of `vending_machine_buffer`                  |
+    /// | 2     | ❌       | ✅     | new_admin: Becomes the admin                                                   |
+    TransferVendingMachineAdmin {},
+    /// This instruction will allocate `buffer_size` bytes to the `nft_vending_machine_buffer` account and assign it the
+    /// Echo Program. Writes through `NftVendingMachineEcho` burn an NFT of the verified Metaplex `collection`.
+    ///
+    /// The first 33 bytes of `nft_vending_machine_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: collection
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | nft_vending_machine_buffer: PDA of Echo Program seeded by `collection`         |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `nft_vending_machine_buffer`                  |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                                    |
+    InitializeNftVendingMachine {
+        buffer_size: usize,
+        collection: Pubkey,
+    },
+    /// Burns the NFT held by `user_token_account` and copies `data` past the header like `AuthorizedEcho`. The mint
+    /// must have a supply of 1 and no decimals, failing with `InvalidMint` otherwise, and the token account must hold
+    /// it. The Metaplex metadata of the mint must have `collection` as its verified collection, failing with
+    /// `InvalidCollection` otherwise. A burned NFT can't pay for another write, so the size of the collection caps the
+    /// number of writes.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | nft_vending_machine_buffer: PDA of Echo Program seeded by `collection`         |
+    /// | 1     | ❌       | ✅     | user: Owner of `user_token_account`                                            |
+    /// | 2     | ✅       | ❌     | user_token_account: Token account holding the NFT                              |
+    /// | 3     | ✅       | ❌     | nft_mint: Mint of the NFT                                                      |
+    /// | 4     | ❌       | ❌     | metadata: Metaplex metadata account of `nft_mint`                              |
+    /// | 5     | ❌       | ❌     | token_program: Used to burn the NFT                                            |
+    NftVendingMachineEcho { data: Vec<u8> },
+    /// Pays like `VendingMachineEcho` for `data`, then copies it at `offset` of the payload (starting at index 317)
+    /// without touching the other bytes: the data past it is not zeroed. Fails before anything is paid with
+    /// `InvalidInstructionInput` if `offset` is past the payload and with `DataTooLarge` if `data` doesn't fit after it.
+    /// The `data_len` is extended to the end of `data` if it was shorter.
+    ///
+    /// Takes the same accounts as `VendingMachineEcho`, for paid and permissionless vending machines.
+    VendingMachineEchoWriteAt { offset: u64, data: Vec<u8> },
+    /// This instruction will allocate `buffer_size` bytes to the `time_lock_buffer` account and assign it the Echo
+    /// Program. Its data can't be read before `unlock_slot`, unlike an expiring buffer it stays readable afterwards.
+    ///
+    /// The first 9 bytes of `time_lock_buffer` will be set with the following data:
+    ///     bytes 0-7: unlock_slot
+    ///     byte 8: bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | time_lock_buffer: PDA of Echo Program seeded by `authority`         |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `time_lock_buffer`                 |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeTimeLockedEcho {
+        buffer_size: usize,
+        authority: Pubkey,
+        unlock_slot: u64,
+    },
+    /// The contents of `data` will be copied into `time_lock_buffer` starting from index 9, zeroing the rest of it.
+    ///
+    /// The authority can write before the `unlock_slot`, only reads are locked.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | time_lock_buffer: PDA of Echo Program seeded by `authority`         |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `time_lock_buffer`      |
+    TimeLockedWrite { data: Vec<u8> },
+    /// Logs the data of `time_lock_buffer`, failing with `TimeLockNotExpired` before its `unlock_slot`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | time_lock_buffer: PDA of Echo Program seeded by `authority`         |
+    /// | 1     | ❌       | ❌     | authority: Pubkey the `time_lock_buffer` was created for            |
+    TimeLockedRead,
+    /// Pushes the `unlock_slot` of `time_lock_buffer` to `new_unlock_slot`, failing with `InvalidInstructionInput`
+    /// unless it is later than the current one or if the buffer is already unlocked.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | time_lock_buffer: PDA of Echo Program seeded by `authority`         |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `time_lock_buffer`      |
+    ExtendTimeLock { new_unlock_slot: u64 },
+    /// Returns the `len` bytes at `offset` of the data of `vending_machine_buffer` as return data, for programs reading
+    /// it through CPI. Nothing is paid and no signer is needed, the address is checked against the seeds stored in the
+    /// header.
+    ///
+    /// Fails with `DataTooLarge` if `len` is over 1024 bytes and with `InvalidInstructionInput` if the read goes past
+    /// the `data_len` of the header.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | vending_machine_buffer: Paid or permissionless vending machine      |
+    ReadVendingMachineEcho { offset: u64, len: u64 },
+    /// This instruction will allocate `buffer_size` bytes to the `shared_buffer` account and assign it the Echo Program.
+    /// The buffer belongs to a group of programs identified by `group_seed` rather than to an authority, and is written
+    /// through cross program invocations from `allowed_program`.
+    ///
+    /// The first 65 bytes of `shared_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: group_seed
+    ///     bytes 33-64: allowed_program
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                       |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | shared_buffer: PDA of Echo Program seeded by `"shared"` and `group_seed`          |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `shared_buffer`                                  |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                                       |
+    InitializeSharedEcho {
+        buffer_size: usize,
+        group_seed: [u8; 32],
+        allowed_program: Pubkey,
+    },
+    /// The contents of the data vector that is provided to the instruction will be copied into the `shared_buffer`
+    /// account starting from index 65, zeroing the rest of it.
+    ///
+    /// No signer is needed, but like `ProgramControlledEcho` the instruction fails with `UnauthorizedCaller` unless it
+    /// is executed as a cross program invocation whose top-level instruction belongs to `allowed_program`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                       |
+    /// |-------|----------|--------|-----------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | shared_buffer: PDA of Echo Program seeded by `"shared"` and `group_seed`          |
+    /// | 1     | ❌       | ❌     | instructions: Instructions sysvar used to identify the calling program            |
+    WriteShared { data: Vec<u8> },
+    /// This instruction will allocate `buffer_size` bytes to the `durable_nonce_buffer` account and assign it the Echo
+    /// Program. Writes are bound to the current value of `nonce_account`, a System Program nonce account, instead of
+    /// a recent blockhash, so a write can be signed long before it is sent but only be applied once.
+    ///
+    /// The first 65 bytes of `durable_nonce_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: authority
+    ///     bytes 33-64: nonce_account
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                           |
+    /// |-------|----------|--------|---------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | durable_nonce_buffer: PDA of Echo Program seeded by `"durable_nonce"` and `authority` |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `durable_nonce_buffer`                               |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                                           |
+    InitializeDurableNonceEcho {
+        buffer_size: usize,
+        authority: Pubkey,
+        nonce_account: Pubkey,
+    },
+    /// The contents of `data` will be copied into `durable_nonce_buffer` starting from index 65, zeroing the rest of it.
+    ///
+    /// Fails with `InvalidNonce` unless `nonce_value` is the current value of the `nonce_account` of the buffer. The
+    /// nonce is advanced after the write, the `authority` must be the nonce authority of the `nonce_account`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                           |
+    /// |-------|----------|--------|---------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | durable_nonce_buffer: PDA of Echo Program seeded by `"durable_nonce"` and `authority` |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `durable_nonce_buffer`                    |
+    /// | 2     | ✅       | ❌     | nonce_account: Nonce account stored in the header                                     |
+    /// | 3     | ❌       | ❌     | recent_blockhashes: RecentBlockhashes sysvar used to advance the nonce                |
+    /// | 4     | ❌       | ❌     | system_program: Used to advance the nonce                                             |
+    DurableNonceWrite {
+        data: Vec<u8>,
+        nonce_value: [u8; 32],
+    },
+    /// Reallocates `vending_machine_buffer` to `new_size` bytes, only the admin of the vending machine can resize it.
+    /// The grown region is zeroed and the `payer` transfers the lamports missing for the new rent exempt minimum.
+    ///
+    /// Fails with `InvalidInstructionInput` if `new_size` is smaller than the header and the `data_len` bytes written so
+    /// far, and with `DataTooLarge` if the buffer grows by more than 10KB, the limit of a single instruction.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: Paid or permissionless vending machine                 |
+    /// | 1     | ❌       | ✅     | admin: Admin stored in the header of `vending_machine_buffer`                  |
+    /// | 2     | ✅       | ✅     | payer: Pubkey that tops up the rent of `vending_machine_buffer`                |
+    /// | 3     | ❌       | ❌     | system_program: Used to transfer the rent                                      |
+    ResizeVendingMachine { new_size: u64 },
+    /// This instruction will allocate `buffer_size` bytes to the `governance_buffer` account and assign it the Echo
+    /// Program. Anyone holding at least `min_tokens` of `governance_mint` can write to it, without spending them.
+    ///
+    /// The first 41 bytes of `governance_buffer` will be set with the following data:
+    ///     bytes 0-31: governance_mint
+    ///     bytes 32-39: min_tokens
+    ///     byte 40: bump_seed
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                           |
+    /// |-------|----------|--------|---------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | governance_buffer: PDA of Echo Program seeded by `"governance"` and `governance_mint` |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `governance_buffer`                                  |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                                           |
+    InitializeGovernanceEcho {
+        buffer_size: usize,
+        governance_mint: Pubkey,
+        min_tokens: u64,
+    },
+    /// The contents of `data` will be copied into `governance_buffer` starting from index 41, zeroing the rest of it.
+    ///
+    /// The `holder_token_account` must be a token account of the `holder` for the governance mint, failing with
+    /// `MintMismatch` for another mint and with `InsufficientFunds` if it holds less than `min_tokens`. No tokens are
+    /// burned.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                           |
+    /// |-------|----------|--------|---------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | governance_buffer: PDA of Echo Program seeded by `"governance"` and `governance_mint` |
+    /// | 1     | ❌       | ✅     | holder: Owner of the `holder_token_account`                                           |
+    /// | 2     | ❌       | ❌     | holder_token_account: Token account of the governance mint                            |
+    GovernanceWrite { data: Vec<u8> },
+    /// Replaces the `min_tokens` of `governance_buffer` with `new_min`. Fails with `InvalidMintAuthority` unless signed
+    /// by the mint authority of the governance mint.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                           |
+    /// |-------|----------|--------|---------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | governance_buffer: PDA of Echo Program seeded by `"governance"` and `governance_mint` |
+    /// | 1     | ❌       | ❌     | governance_mint: Mint stored in the header                                            |
+    /// | 2     | ❌       | ✅     | mint_authority: Mint authority of the `governance_mint`                               |
+    UpdateGovernanceThreshold { new_min: u64 },
+    /// Initializes a vending machine like `InitializeVendingMachineEcho` in burn mode, that only accepts paid writes from
+    /// `start_slot` until `end_slot`, stored at bytes 291-306 of the header. A bound of 0 leaves the window open on that
+    /// side, otherwise `end_slot` must be after `start_slot`, failing with `InvalidInstructionInput`.
+    ///
+    /// Writes fail with `VendingMachineNotStarted` before `start_slot` and with `VendingMachineEnded` from `end_slot` on,
+    /// before anything is paid.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program seeded by `mint` and `price`   |
+    /// | 1     | ❌       | ❌     | vending_machine_mint: Mint paying for the writes                               |
+    /// | 2     | ❌       | ✅     | mint_authority: Mint authority of `vending_machine_mint`                       |
+    /// | 3     | ❌       | ✅     | payer: Pubkey that allocates the `vending_machine_buffer`                      |
+    /// | 4     | ❌       | ❌     | system_program: Used to allocate the buffer                                    |
+    InitializeWindowedVendingMachine {
+        buffer_size: usize,
+        price: u64,
+        start_slot: u64,
+        end_slot: u64,
+    },
+    /// Replaces the window of paid writes of `vending_machine_buffer`, only its admin can change it. The window can only
+    /// be extended: an earlier `start_slot` and a later `end_slot`, 0 opening it on that side. Fails with
+    /// `InvalidInstructionInput` if the new window doesn't contain the current one.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: Paid or permissionless vending machine                 |
+    /// | 1     | ❌       | ✅     | admin: Admin stored in the header of `vending_machine_buffer`                  |
+    SetVendingWindow { start_slot: u64, end_slot: u64 },
+    /// This instruction will allocate `buffer_size` bytes to the `cross_chain_buffer` account and assign it the Echo
+    /// Program. The buffer is written with the payloads of the Wormhole messages of a single emitter, identified by its
+    /// `emitter_chain` and `emitter_address`.
+    ///
+    /// The first 44 bytes of `cross_chain_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-2: emitter_chain
+    ///     bytes 3-34: emitter_address
+    ///     byte 35: has_sequence (initially 0)
+    ///     bytes 36-43: last_sequence (initially 0)
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                              |
+    /// |-------|----------|--------|------------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | cross_chain_buffer: PDA of Echo Program seeded by `"cross_chain"` and the emitter        |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `cross_chain_buffer`                                    |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                                              |
+    InitializeCrossChainEcho {
+        buffer_size: usize,
+        emitter_chain: u16,
+        emitter_address: [u8; 32],
+    },
+    /// The payload of `posted_vaa` will be copied into `cross_chain_buffer` starting from index 44, zeroing the rest of
+    /// it. No signer is needed, the guardians signed the message.
+    ///
+    /// Fails with `InvalidVAA` unless `posted_vaa` is owned by the Wormhole bridge, was emitted by the emitter of the
+    /// buffer and carries `data` as its payload. It also fails with `InvalidVAA` unless the sequence of the message is
+    /// greater than the `last_sequence` written, so an older VAA can't be replayed to restore a stale payload.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                              |
+    /// |-------|----------|--------|------------------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | cross_chain_buffer: PDA of Echo Program seeded by `"cross_chain"` and the emitter        |
+    /// | 1     | ❌       | ❌     | posted_vaa: Posted VAA account of the Wormhole bridge                                    |
+    CrossChainWrite { data: Vec<u8> },
+    /// Creates one `authorized_buffer_i` per config exactly as `InitializeAuthorizedEcho` would with an empty label, no
+    /// permanent delegate, no write fee and no expiry, for up to 5 buffers of the same `authority` in a single
+    /// instruction.
+    ///
+    /// The buffer accounts must be passed in the order of `configs`, each at the PDA of `authority` and its
+    /// `buffer_seed`. The `authority` pays the rent of every buffer, and if any buffer can't be created, for instance
+    /// because it already exists, the whole instruction fails.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                  |
+    /// |-------|----------|--------|------------------------------------------------------------------------------|
+    /// | 0     | ✅       | ✅     | authority: Pubkey with sole write access to every `authorized_buffer`         |
+    /// | 1     | ❌       | ❌     | system_program: Used to allocate the buffers                                  |
+    /// | 2..   | ✅       | ❌     | authorized_buffer_i: PDA of Echo Program that only `authority` can write to   |
+    BatchInitialize { configs: Vec<BatchInitConfig> },
+    /// Adds `member` to the whitelist of the vending machine, only the admin can manage it. The `vending_whitelist` is
+    /// created on the first call with the `admin` paying its rent, and holds up to 32 members kept sorted.
+    ///
+    /// Fails with `SetFull` if the whitelist is at capacity, or `DuplicateMember` if `member` is already in it. The
+    /// members are ignored until `SetWhitelistEnabled` turns the check on.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | vending_machine_buffer: The vending machine the whitelist belongs to            |
+    /// | 1     | ✅       | ❌     | vending_whitelist: PDA of Echo Program seeded by `"vending_whitelist"` and it   |
+    /// | 2     | ✅       | ✅     | admin: Admin of the vending machine, pays for the whitelist on the first call   |
+    /// | 3     | ❌       | ❌     | system_program: Used to allocate the whitelist                                  |
+    AddToWhitelist { member: Pubkey },
+    /// Removes `member` from the whitelist of the vending machine, fails with `MemberNotFound` if it isn't in it.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                    |
+    /// |-------|----------|--------|--------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | vending_machine_buffer: The vending machine the whitelist belongs to            |
+    /// | 1     | ✅       | ❌     | vending_whitelist: PDA of Echo Program seeded by `"vending_whitelist"` and it   |
+    /// | 2     | ❌       | ✅     | admin: Admin of the vending machine                                             |
+    RemoveFromWhitelist { member: Pubkey },
+    /// Sets the `whitelist_enabled` flag of the vending machine, only paid writes check the whitelist. A machine whose
+    /// whitelist was never created rejects every paid write while it is enabled.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                         |
+    /// |-------|----------|--------|-----------------------------------------------------|
+    /// | 0     | ✅       | ❌     | vending_machine_buffer: The vending machine to set   |
+    /// | 1     | ❌       | ✅     | admin: Admin of the vending machine                  |
+    SetWhitelistEnabled { enabled: bool },
+    /// Appends the bytes written so far to `source_buffer` right after the bytes written so far to
+    /// `destination_buffer`, exactly as `AppendAuthorizedEcho` would, then closes `source_buffer` and sends its
+    /// lamports to `destination_buffer`. Both buffers must belong to `authority`.
+    ///
+    /// Fails with `InvalidInstructionInput` if the destination can't hold both, or if both accounts are the same
+    /// buffer. Passing the `registry` removes `source_buffer` from it.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | source_buffer: Authorized buffer of `authority` closed by the merge       |
+    /// | 1     | ✅       | ❌     | destination_buffer: Authorized buffer of `authority` receiving the data   |
+    /// | 2     | ❌       | ✅     | authority: Pubkey with sole write access to both buffers                  |
+    /// | 3     | ✅       | ❌     | registry: (optional) Registry of `authority` listing `source_buffer`      |
+    MergeBuffers,
+    /// Closes the `user_record` tracking the cooldown of `user` on a vending machine and sends its lamports to `user`.
+    /// Either the `user` signs, or the admin of the vending machine passed after it. The next paid write of `user`
+    /// creates a new record.
+    ///
+    /// Fails with `CooldownActive` until the cooldown of the last write of `user` is over, so closing the record can't
+    /// be used to skip it.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                                        |
+    /// |-------|----------|--------|------------------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | vending_machine_buffer: The vending machine the record belongs to                  |
+    /// | 1     | ✅       | ❌     | user_record: PDA of Echo Program seeded by `"vending_user"`, the buffer and `user` |
+    /// | 2     | ✅       | ✅     | user: Pubkey the record tracks, receives its lamports, signs unless the admin does |
+    /// | 3     | ❌       | ✅     | admin: (optional) Admin of the vending machine, closing the record for `user`      |
+    CloseVendingUserRecord,
+    /// Exchanges the data past the header of two authorized buffers of `authority`, along with their cursors, in a
+    /// single instruction. Both buffers must have data regions of the same size, of at most 1024 bytes.
+    ///
+    /// Fails with `InvalidInstructionInput` if the data regions differ in size or both accounts are the same buffer,
+    /// and with `DataTooLarge` if they are larger than 1024 bytes.
+    ///
+    /// The return data holds the new `sequence` of both buffers, as little-endian u64s in account order.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | authorized_buffer_a: Authorized buffer of `authority`                     |
+    /// | 1     | ✅       | ❌     | authorized_buffer_b: Authorized buffer of `authority`                     |
+    /// | 2     | ❌       | ✅     | authority: Pubkey with sole write access to both buffers                  |
+    SwapBuffers,
+    /// Same as `SnapshotAuthorizedEcho` with the current slot as the `snapshot_index`, so the `snapshot` is the PDA of
+    /// Echo Program seeded by `"slot_snapshot"`, `authorized_buffer` and the slot as little-endian bytes. The prefix
+    /// keeps it apart from the snapshot a `SnapshotAuthorizedEcho` takes at the index with the same value. Fails with
+    /// `SnapshotAlreadyExists` if a snapshot was already taken in the same slot.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
+    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
+    /// | 2     | ✅       | ❌     | snapshot: PDA of Echo Program seeded by `authorized_buffer` and the slot  |
+    /// | 3     | ✅       | ✅     | payer: Pubkey that allocates the `snapshot`                               |
+    /// | 4     | ❌       | ❌     | system_program: Used to allocate the snapshot                             |
+    SnapshotBuffer,
+    /// Logs the kind, address and slot of every `snapshot_i`, either taken at an index by `SnapshotAuthorizedEcho` or
+    /// at a slot by `SnapshotBuffer`, failing with `InvalidAccountAddress` if one of them isn't a snapshot of
+    /// `authorized_buffer`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                              |
+    /// |-------|----------|--------|--------------------------------------------------------------------------|
+    /// | 0     | ❌       | ❌     | authorized_buffer: Authorized buffer the snapshots were taken of          |
+    /// | 1..   | ❌       | ❌     | snapshot_i: Snapshots of `authorized_buffer`                              |
+    ListSnapshots,
+    /// This instruction will allocate the `gradual_release_buffer` account with room for `data` twice and assign it the
+    /// Echo Program. `data` is stored masked, and `RevealGradual` unmasks it into the second region a bit more every
+    /// slot, until all of it is revealed `total_slots` after the initialization.
+    ///
+    /// The mask is a XOR with a keystream of keccak hashes of the buffer address and the initialization slot. It only
+    /// depends on public values and `data` is visible in this instruction, so it keeps the data from being read off
+    /// the account, it is not encryption.
+    ///
+    /// The first 57 bytes of `gradual_release_buffer` will be set with the following data:
+    ///     byte 0: bump_seed
+    ///     bytes 1-32: authority
+    ///     bytes 33-40: reveal_start_slot
+    ///     bytes 41-48: reveal_end_slot
+    ///     bytes 49-52: total_bytes
+    ///     bytes 53-56: revealed_bytes
+    ///
+    /// They are followed by the `total_bytes` of masked data, then the `total_bytes` of revealed data.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | gradual_release_buffer: PDA of Echo Program seeded by `authority`   |
+    /// | 1     | ✅       | ✅     | payer: Pubkey that allocates the `gradual_release_buffer`           |
+    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                         |
+    InitializeGradualReleaseEcho {
+        total_slots: u64,
+        data: Vec<u8>,
+        authority: Pubkey,
+    },
+    /// Reveals the first `(current_slot - reveal_start_slot) * total_bytes / total_slots` bytes of the data, all of
+    /// them from the `reveal_end_slot` on, by writing them unmasked to the revealed region. Bytes already revealed
+    /// stay revealed. Fails with `InvalidInstructionInput` if `current_slot` is past the slot of the transaction.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                                        |
+    /// |-------|----------|--------|--------------------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | gradual_release_buffer: PDA of Echo Program seeded by `authority`   |
+    /// | 1     | ❌       | ✅     | authority: Pubkey the buffer was created for                        |
+    RevealGradual { current_slot: u64 },
 }
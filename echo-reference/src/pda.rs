@@ -0,0 +1,60 @@
+use solana_program::pubkey::Pubkey;
+
+/// Address and bump seed of the authorized buffer of `authority` created with `buffer_seed`
+pub fn find_authorized_echo_address(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    buffer_seed: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"authority", authority.as_ref(), &buffer_seed.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Address and bump seed of the authorized buffer of `authority` created with `name`
+pub fn find_named_authorized_echo_address(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    name: &str,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"authority", authority.as_ref(), name.as_bytes()],
+        program_id,
+    )
+}
+
+/// Address and bump seed of the vending machine buffer for `mint` at `price`
+pub fn find_vending_machine_echo_address(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    price: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"vending_machine", mint.as_ref(), &price.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// The SPL Associated Token Account program
+pub mod spl_associated_token_account {
+    solana_program::declare_id!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+}
+
+/// Associated token account of `wallet` for `mint`, as derived by the SPL Associated Token Account program
+pub fn get_associated_token_address(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(wallet, mint, &spl_token::id())
+}
+
+/// Associated token account of `wallet` for a `mint` of `token_program`, the Token Program or Token-2022
+pub fn get_associated_token_address_with_program_id(
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &spl_associated_token_account::id(),
+    )
+    .0
+}
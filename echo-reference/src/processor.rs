@@ -4,13 +4,118 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
-use crate::instruction::EchoInstruction;
+use crate::{instruction::EchoInstruction, state::PAYMENT_MODE_BURN};
 
+pub mod aggregator_echo;
+pub mod auction_vending_machine;
 pub mod authorized_echo;
+pub mod average_echo;
+pub mod batch_authorized_echo;
+pub mod batch_initialize;
+pub mod bitmap_echo;
+pub mod claim_expired_vending_machine;
+pub mod close_vending_machine;
+pub mod close_vending_machine_buffer;
+pub mod co_authorized_echo;
+pub mod commit_reveal_echo;
+pub mod compressed_echo;
+pub mod conditional_echo;
+pub mod cross_chain_echo;
+pub mod decay_echo;
+pub mod drain_treasury;
+pub mod durable_nonce_echo;
 pub mod echo;
+pub mod epoch_echo;
+pub mod gasless_echo;
+pub mod governance_echo;
+pub mod gradual_release_echo;
+pub mod histogram_echo;
+pub mod indexed_echo;
+pub mod initialize_aggregator_echo;
+pub mod initialize_auction_vending_machine;
+pub mod initialize_authority_counter;
 pub mod initialize_authorized_echo;
+pub mod initialize_authorized_echo_auto;
+pub mod initialize_authorized_echo_for_pda;
+pub mod initialize_average_echo;
+pub mod initialize_bitmap_echo;
+pub mod initialize_co_authorized_echo;
+pub mod initialize_compressed_echo;
+pub mod initialize_conditional_echo;
+pub mod initialize_cross_chain_echo;
+pub mod initialize_decay_echo;
+pub mod initialize_durable_nonce_echo;
+pub mod initialize_epoch_echo;
+pub mod initialize_fee_config;
+pub mod initialize_gasless_echo;
+pub mod initialize_governance_echo;
+pub mod initialize_gradual_release_echo;
+pub mod initialize_histogram_echo;
+pub mod initialize_indexed_echo;
+pub mod initialize_linked_echo;
+pub mod initialize_map_echo;
+pub mod initialize_max_echo;
+pub mod initialize_memo_echo;
+pub mod initialize_min_echo;
+pub mod initialize_multi_slot_echo;
+pub mod initialize_named_authorized_echo;
+pub mod initialize_nft_vending_machine;
+pub mod initialize_oracle_echo;
+pub mod initialize_ownership_echo;
+pub mod initialize_permissionless_vending_machine;
+pub mod initialize_program_controlled_echo;
+pub mod initialize_random_echo;
+pub mod initialize_registry;
+pub mod initialize_replicated_echo;
+pub mod initialize_schema_echo;
+pub mod initialize_segmented_echo;
+pub mod initialize_set_echo;
+pub mod initialize_shared_echo;
+pub mod initialize_slot_echo;
+pub mod initialize_sol_vending_machine;
+pub mod initialize_sponsored_echo;
+pub mod initialize_stake_gated_echo;
+pub mod initialize_subscription_echo;
+pub mod initialize_sum_echo;
+pub mod initialize_time_locked_echo;
+pub mod initialize_treasury;
 pub mod initialize_vending_machine_echo;
+pub mod initialize_voted_echo;
+pub mod linked_echo;
+pub mod map_echo;
+pub mod max_echo;
+pub mod memo_echo;
+pub mod min_echo;
+pub mod multi_slot_echo;
+pub mod named_authorized_echo;
+pub mod nft_vending_machine_echo;
+pub mod oracle_echo;
+pub mod ownership_echo;
+pub mod program_controlled_echo;
+pub mod random_echo;
+pub mod registry;
+pub mod replicated_echo;
+pub mod resize_vending_machine;
+pub mod schema_echo;
+pub mod segmented_echo;
+pub mod set_echo;
+pub mod set_metadata;
+pub mod shared_echo;
+pub mod slot_echo;
+pub mod snapshot_authorized_echo;
+pub mod sol_vending_machine_echo;
+pub mod sponsor;
+pub mod stake_gated_echo;
+pub mod subscription_echo;
+pub mod sum_echo;
+pub mod swap_buffers;
+pub mod time_locked_echo;
+pub mod transfer_vending_machine_admin;
 pub mod vending_machine_echo;
+pub mod vending_whitelist;
+pub mod voted_echo;
+pub mod withdraw_fees;
+pub mod withdraw_vending_machine_proceeds;
 
 pub struct Processor {}
 
@@ -31,6 +136,11 @@ impl Processor {
             EchoInstruction::InitializeAuthorizedEcho {
                 buffer_seed,
                 buffer_size,
+                label,
+                permanent_delegate,
+                write_fee_lamports,
+                expiry_slot,
+                crank_bounty_bps,
             } => {
                 msg!("Instruction: InitializeAuthorizedEcho");
                 initialize_authorized_echo::process(
@@ -38,20 +148,1059 @@ impl Processor {
                     accounts,
                     buffer_seed,
                     buffer_size,
+                    label,
+                    permanent_delegate,
+                    write_fee_lamports,
+                    expiry_slot,
+                    crank_bounty_bps,
                 )?;
             }
             EchoInstruction::AuthorizedEcho { data } => {
                 msg!("Instruction: AuthorizedEcho");
                 authorized_echo::process(program_id, accounts, data)?;
             }
-            EchoInstruction::InitializeVendingMachineEcho { price, buffer_size } => {
+            EchoInstruction::InitializeVendingMachineEcho {
+                price,
+                buffer_size,
+                payment_mode,
+                price_per_byte,
+                require_ata,
+                max_uses,
+                tiers,
+                cooldown_slots,
+                free_authority_writes,
+            } => {
                 msg!("Instruction: InitializeVendingMachineEcho");
-                initialize_vending_machine_echo::process(program_id, accounts, price, buffer_size)?;
+                initialize_vending_machine_echo::process(
+                    program_id,
+                    accounts,
+                    price,
+                    buffer_size,
+                    payment_mode,
+                    price_per_byte,
+                    require_ata,
+                    max_uses,
+                    0,
+                    tiers,
+                    0,
+                    0,
+                    0,
+                    cooldown_slots,
+                    free_authority_writes,
+                )?;
             }
             EchoInstruction::VendingMachineEcho { data } => {
                 msg!("Instruction: VendingMachineEcho");
                 vending_machine_echo::process(program_id, accounts, data)?;
             }
+            EchoInstruction::InitializeProgramControlled {
+                buffer_size,
+                allowed_program,
+            } => {
+                msg!("Instruction: InitializeProgramControlled");
+                initialize_program_controlled_echo::process(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                    allowed_program,
+                )?;
+            }
+            EchoInstruction::ProgramControlledEcho { data } => {
+                msg!("Instruction: ProgramControlledEcho");
+                program_controlled_echo::process(program_id, accounts, data)?;
+            }
+            EchoInstruction::BatchAuthorizedEcho { writes } => {
+                msg!("Instruction: BatchAuthorizedEcho");
+                batch_authorized_echo::process(program_id, accounts, writes)?;
+            }
+            EchoInstruction::InitializeConditionalEcho {
+                buffer_size,
+                authority,
+                condition_offset,
+                condition_value,
+            } => {
+                msg!("Instruction: InitializeConditionalEcho");
+                initialize_conditional_echo::process(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                    authority,
+                    condition_offset,
+                    condition_value,
+                )?;
+            }
+            EchoInstruction::ConditionalWrite { data } => {
+                msg!("Instruction: ConditionalWrite");
+                conditional_echo::process(program_id, accounts, data)?;
+            }
+            EchoInstruction::InitializeNamedAuthorizedEcho { name, buffer_size } => {
+                msg!("Instruction: InitializeNamedAuthorizedEcho");
+                initialize_named_authorized_echo::process(program_id, accounts, name, buffer_size)?;
+            }
+            EchoInstruction::NamedAuthorizedEcho { data } => {
+                msg!("Instruction: NamedAuthorizedEcho");
+                named_authorized_echo::process(program_id, accounts, data)?;
+            }
+            EchoInstruction::InitializeAggregatorEcho {
+                buffer_size,
+                authority,
+            } => {
+                msg!("Instruction: InitializeAggregatorEcho");
+                initialize_aggregator_echo::process(program_id, accounts, buffer_size, authority)?;
+            }
+            EchoInstruction::AggregateWrite { data } => {
+                msg!("Instruction: AggregateWrite");
+                aggregator_echo::process_aggregate_write(program_id, accounts, data)?;
+            }
+            EchoInstruction::ResetAggregator => {
+                msg!("Instruction: ResetAggregator");
+                aggregator_echo::process_reset(program_id, accounts)?;
+            }
+            EchoInstruction::InitializeSumEcho {
+                authority,
+                initial_value,
+            } => {
+                msg!("Instruction: InitializeSumEcho");
+                initialize_sum_echo::process(program_id, accounts, authority, initial_value)?;
+            }
+            EchoInstruction::AddToSum { amount } => {
+                msg!("Instruction: AddToSum");
+                sum_echo::process_add(program_id, accounts, amount)?;
+            }
+            EchoInstruction::SubtractFromSum { amount } => {
+                msg!("Instruction: SubtractFromSum");
+                sum_echo::process_subtract(program_id, accounts, amount)?;
+            }
+            EchoInstruction::ResetSum { new_value } => {
+                msg!("Instruction: ResetSum");
+                sum_echo::process_reset(program_id, accounts, new_value)?;
+            }
+            EchoInstruction::AppendAuthorizedEcho { data } => {
+                msg!("Instruction: AppendAuthorizedEcho");
+                authorized_echo::process_append(program_id, accounts, data)?;
+            }
+            EchoInstruction::InitializeMaxEcho { authority } => {
+                msg!("Instruction: InitializeMaxEcho");
+                initialize_max_echo::process(program_id, accounts, authority)?;
+            }
+            EchoInstruction::UpdateMax { candidate } => {
+                msg!("Instruction: UpdateMax");
+                max_echo::process_update(program_id, accounts, candidate)?;
+            }
+            EchoInstruction::ResetMax { new_value } => {
+                msg!("Instruction: ResetMax");
+                max_echo::process_reset(program_id, accounts, new_value)?;
+            }
+            EchoInstruction::InitializeMinEcho { authority } => {
+                msg!("Instruction: InitializeMinEcho");
+                initialize_min_echo::process(program_id, accounts, authority)?;
+            }
+            EchoInstruction::UpdateMin { candidate } => {
+                msg!("Instruction: UpdateMin");
+                min_echo::process_update(program_id, accounts, candidate)?;
+            }
+            EchoInstruction::ResetMin { new_value } => {
+                msg!("Instruction: ResetMin");
+                min_echo::process_reset(program_id, accounts, new_value)?;
+            }
+            EchoInstruction::ClearAuthorizedBuffer => {
+                msg!("Instruction: ClearAuthorizedBuffer");
+                authorized_echo::process_clear(program_id, accounts)?;
+            }
+            EchoInstruction::InitializeHistogramEcho {
+                bucket_count,
+                bucket_width,
+                authority,
+            } => {
+                msg!("Instruction: InitializeHistogramEcho");
+                initialize_histogram_echo::process(
+                    program_id,
+                    accounts,
+                    bucket_count,
+                    bucket_width,
+                    authority,
+                )?;
+            }
+            EchoInstruction::RecordHistogramValue { value } => {
+                msg!("Instruction: RecordHistogramValue");
+                histogram_echo::process_record(program_id, accounts, value)?;
+            }
+            EchoInstruction::ReadHistogram => {
+                msg!("Instruction: ReadHistogram");
+                histogram_echo::process_read(program_id, accounts)?;
+            }
+            EchoInstruction::ResetHistogram => {
+                msg!("Instruction: ResetHistogram");
+                histogram_echo::process_reset(program_id, accounts)?;
+            }
+            EchoInstruction::InitializeAuthorizedEchoForPda {
+                buffer_seed,
+                buffer_size,
+            } => {
+                msg!("Instruction: InitializeAuthorizedEchoForPda");
+                initialize_authorized_echo_for_pda::process(
+                    program_id,
+                    accounts,
+                    buffer_seed,
+                    buffer_size,
+                )?;
+            }
+            EchoInstruction::InitializeSetEcho {
+                capacity,
+                authority,
+            } => {
+                msg!("Instruction: InitializeSetEcho");
+                initialize_set_echo::process(program_id, accounts, capacity, authority)?;
+            }
+            EchoInstruction::AddToSet { member } => {
+                msg!("Instruction: AddToSet");
+                set_echo::process_add(program_id, accounts, member)?;
+            }
+            EchoInstruction::RemoveFromSet { member } => {
+                msg!("Instruction: RemoveFromSet");
+                set_echo::process_remove(program_id, accounts, member)?;
+            }
+            EchoInstruction::ContainsInSet { member } => {
+                msg!("Instruction: ContainsInSet");
+                set_echo::process_contains(program_id, accounts, member)?;
+            }
+            EchoInstruction::CommitAuthorizedEcho { hash } => {
+                msg!("Instruction: CommitAuthorizedEcho");
+                commit_reveal_echo::process_commit(program_id, accounts, hash)?;
+            }
+            EchoInstruction::RevealAuthorizedEcho { data } => {
+                msg!("Instruction: RevealAuthorizedEcho");
+                commit_reveal_echo::process_reveal(program_id, accounts, data)?;
+            }
+            EchoInstruction::InitializeMapEcho {
+                capacity,
+                authority,
+            } => {
+                msg!("Instruction: InitializeMapEcho");
+                initialize_map_echo::process(program_id, accounts, capacity, authority)?;
+            }
+            EchoInstruction::PutMapEntry { key, value } => {
+                msg!("Instruction: PutMapEntry");
+                map_echo::process_put(program_id, accounts, key, value)?;
+            }
+            EchoInstruction::DeleteMapEntry { key } => {
+                msg!("Instruction: DeleteMapEntry");
+                map_echo::process_delete(program_id, accounts, key)?;
+            }
+            EchoInstruction::GetMapEntry { key } => {
+                msg!("Instruction: GetMapEntry");
+                map_echo::process_get(program_id, accounts, key)?;
+            }
+            EchoInstruction::SetLabel { label } => {
+                msg!("Instruction: SetLabel");
+                authorized_echo::process_set_label(program_id, accounts, label)?;
+            }
+            EchoInstruction::InitializeBitmapEcho {
+                bit_count,
+                authority,
+            } => {
+                msg!("Instruction: InitializeBitmapEcho");
+                initialize_bitmap_echo::process(program_id, accounts, bit_count, authority)?;
+            }
+            EchoInstruction::SetBit { index } => {
+                msg!("Instruction: SetBit");
+                bitmap_echo::process_set(program_id, accounts, index)?;
+            }
+            EchoInstruction::ClearBit { index } => {
+                msg!("Instruction: ClearBit");
+                bitmap_echo::process_clear(program_id, accounts, index)?;
+            }
+            EchoInstruction::QueryBit { index } => {
+                msg!("Instruction: QueryBit");
+                bitmap_echo::process_query(program_id, accounts, index)?;
+            }
+            EchoInstruction::InitializeAuthorityCounter => {
+                msg!("Instruction: InitializeAuthorityCounter");
+                initialize_authority_counter::process(program_id, accounts)?;
+            }
+            EchoInstruction::InitializeAuthorizedEchoAuto { buffer_size } => {
+                msg!("Instruction: InitializeAuthorizedEchoAuto");
+                initialize_authorized_echo_auto::process(program_id, accounts, buffer_size)?;
+            }
+            EchoInstruction::InitializeLinkedEcho {
+                buffer_size,
+                authority,
+            } => {
+                msg!("Instruction: InitializeLinkedEcho");
+                initialize_linked_echo::process(program_id, accounts, buffer_size, authority)?;
+            }
+            EchoInstruction::AppendLinkedEcho { new_buffer } => {
+                msg!("Instruction: AppendLinkedEcho");
+                linked_echo::process_append(program_id, accounts, new_buffer)?;
+            }
+            EchoInstruction::TraverseLinkedEcho => {
+                msg!("Instruction: TraverseLinkedEcho");
+                linked_echo::process_traverse(program_id, accounts)?;
+            }
+            EchoInstruction::InitializeRegistry { capacity } => {
+                msg!("Instruction: InitializeRegistry");
+                initialize_registry::process(program_id, accounts, capacity)?;
+            }
+            EchoInstruction::CloseAuthorizedEcho => {
+                msg!("Instruction: CloseAuthorizedEcho");
+                authorized_echo::process_close(program_id, accounts)?;
+            }
+            EchoInstruction::InitializeIndexedEcho {
+                slot_count,
+                key_size,
+                value_size,
+                authority,
+            } => {
+                msg!("Instruction: InitializeIndexedEcho");
+                initialize_indexed_echo::process(
+                    program_id, accounts, slot_count, key_size, value_size, authority,
+                )?;
+            }
+            EchoInstruction::PutIndexedValue { key, value } => {
+                msg!("Instruction: PutIndexedValue");
+                indexed_echo::process_put(program_id, accounts, key, value)?;
+            }
+            EchoInstruction::DeleteIndexedValue { key } => {
+                msg!("Instruction: DeleteIndexedValue");
+                indexed_echo::process_delete(program_id, accounts, key)?;
+            }
+            EchoInstruction::GetIndexedValue { key } => {
+                msg!("Instruction: GetIndexedValue");
+                indexed_echo::process_get(program_id, accounts, key)?;
+            }
+            EchoInstruction::InitializeEpochEcho {
+                buffer_size,
+                authority,
+            } => {
+                msg!("Instruction: InitializeEpochEcho");
+                initialize_epoch_echo::process(program_id, accounts, buffer_size, authority)?;
+            }
+            EchoInstruction::EpochEcho { data } => {
+                msg!("Instruction: EpochEcho");
+                epoch_echo::process_write(program_id, accounts, data)?;
+            }
+            EchoInstruction::ReadEpochEcho => {
+                msg!("Instruction: ReadEpochEcho");
+                epoch_echo::process_read(program_id, accounts)?;
+            }
+            EchoInstruction::SnapshotAuthorizedEcho { snapshot_index } => {
+                msg!("Instruction: SnapshotAuthorizedEcho");
+                snapshot_authorized_echo::process(program_id, accounts, snapshot_index)?;
+            }
+            EchoInstruction::InitializeSlotEcho {
+                buffer_size,
+                authority,
+            } => {
+                msg!("Instruction: InitializeSlotEcho");
+                initialize_slot_echo::process(program_id, accounts, buffer_size, authority)?;
+            }
+            EchoInstruction::SlotEcho { data } => {
+                msg!("Instruction: SlotEcho");
+                slot_echo::process_write(program_id, accounts, data)?;
+            }
+            EchoInstruction::InitializeCoAuthorizedEcho {
+                buffer_seed,
+                buffer_size,
+            } => {
+                msg!("Instruction: InitializeCoAuthorizedEcho");
+                initialize_co_authorized_echo::process(
+                    program_id,
+                    accounts,
+                    buffer_seed,
+                    buffer_size,
+                )?;
+            }
+            EchoInstruction::CoAuthorizedEcho { data } => {
+                msg!("Instruction: CoAuthorizedEcho");
+                co_authorized_echo::process(program_id, accounts, data)?;
+            }
+            EchoInstruction::InitializeDecayEcho {
+                buffer_size,
+                decay_rate_per_1000_slots,
+                authority,
+            } => {
+                msg!("Instruction: InitializeDecayEcho");
+                initialize_decay_echo::process(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                    decay_rate_per_1000_slots,
+                    authority,
+                )?;
+            }
+            EchoInstruction::RefreshDecay => {
+                msg!("Instruction: RefreshDecay");
+                decay_echo::process_refresh(program_id, accounts)?;
+            }
+            EchoInstruction::WriteDecayEcho { data } => {
+                msg!("Instruction: WriteDecayEcho");
+                decay_echo::process_write(program_id, accounts, data)?;
+            }
+            EchoInstruction::InitializeAverageEcho {
+                max_samples,
+                authority,
+            } => {
+                msg!("Instruction: InitializeAverageEcho");
+                initialize_average_echo::process(program_id, accounts, max_samples, authority)?;
+            }
+            EchoInstruction::SubmitSample { value } => {
+                msg!("Instruction: SubmitSample");
+                average_echo::process_submit(program_id, accounts, value)?;
+            }
+            EchoInstruction::ReadAverage => {
+                msg!("Instruction: ReadAverage");
+                average_echo::process_read(program_id, accounts)?;
+            }
+            EchoInstruction::ResetAverage => {
+                msg!("Instruction: ResetAverage");
+                average_echo::process_reset(program_id, accounts)?;
+            }
+            EchoInstruction::InitializeFeeConfig { admin } => {
+                msg!("Instruction: InitializeFeeConfig");
+                initialize_fee_config::process(program_id, accounts, admin)?;
+            }
+            EchoInstruction::WithdrawFees { amount } => {
+                msg!("Instruction: WithdrawFees");
+                withdraw_fees::process(program_id, accounts, amount)?;
+            }
+            EchoInstruction::InitializePermissionlessVendingMachine { buffer_size } => {
+                msg!("Instruction: InitializePermissionlessVendingMachine");
+                initialize_permissionless_vending_machine::process(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                )?;
+            }
+            EchoInstruction::CrankCloseExpired => {
+                msg!("Instruction: CrankCloseExpired");
+                authorized_echo::process_crank_close_expired(program_id, accounts)?;
+            }
+            EchoInstruction::InitializeSponsoredEcho {
+                buffer_size,
+                authority,
+            } => {
+                msg!("Instruction: InitializeSponsoredEcho");
+                initialize_sponsored_echo::process(program_id, accounts, buffer_size, authority)?;
+            }
+            EchoInstruction::FundSponsor { lamports } => {
+                msg!("Instruction: FundSponsor");
+                sponsor::process_fund(program_id, accounts, lamports)?;
+            }
+            EchoInstruction::DrainSponsor { authority } => {
+                msg!("Instruction: DrainSponsor");
+                sponsor::process_drain(program_id, accounts, authority)?;
+            }
+            EchoInstruction::AuthorizedEchoHashOnly { data } => {
+                msg!("Instruction: AuthorizedEchoHashOnly");
+                authorized_echo::process_hash_only(program_id, accounts, data)?;
+            }
+            EchoInstruction::VerifyAuthorizedEcho { data } => {
+                msg!("Instruction: VerifyAuthorizedEcho");
+                authorized_echo::process_verify(program_id, accounts, data)?;
+            }
+            EchoInstruction::InitializeRandomEcho {
+                buffer_size,
+                authority,
+            } => {
+                msg!("Instruction: InitializeRandomEcho");
+                initialize_random_echo::process(program_id, accounts, buffer_size, authority)?;
+            }
+            EchoInstruction::RefreshRandom => {
+                msg!("Instruction: RefreshRandom");
+                random_echo::process_refresh(program_id, accounts)?;
+            }
+            EchoInstruction::AuthorizedEchoChunk { offset, data } => {
+                msg!("Instruction: AuthorizedEchoChunk");
+                authorized_echo::process_chunk(program_id, accounts, offset, data)?;
+            }
+            EchoInstruction::AuthorizedEchoFinalize { total_len, hash } => {
+                msg!("Instruction: AuthorizedEchoFinalize");
+                authorized_echo::process_finalize(program_id, accounts, total_len, hash)?;
+            }
+            EchoInstruction::CloseVendingMachineBuffer => {
+                msg!("Instruction: CloseVendingMachineBuffer");
+                close_vending_machine_buffer::process(program_id, accounts)?;
+            }
+            EchoInstruction::InitializeSegmentedEcho {
+                segment_size,
+                segment_count,
+                authority,
+            } => {
+                msg!("Instruction: InitializeSegmentedEcho");
+                initialize_segmented_echo::process(
+                    program_id,
+                    accounts,
+                    segment_size,
+                    segment_count,
+                    authority,
+                )?;
+            }
+            EchoInstruction::AuthorizedEchoSegment { index, data } => {
+                msg!("Instruction: AuthorizedEchoSegment");
+                segmented_echo::process_write(program_id, accounts, index, data)?;
+            }
+            EchoInstruction::ClearSegment { index } => {
+                msg!("Instruction: ClearSegment");
+                segmented_echo::process_clear(program_id, accounts, index)?;
+            }
+            EchoInstruction::SetBufferMetadata {
+                name,
+                description,
+                uri,
+            } => {
+                msg!("Instruction: SetBufferMetadata");
+                set_metadata::process_set(program_id, accounts, name, description, uri)?;
+            }
+            EchoInstruction::GetBufferMetadata => {
+                msg!("Instruction: GetBufferMetadata");
+                set_metadata::process_get(program_id, accounts)?;
+            }
+            EchoInstruction::CloseBufferMetadata => {
+                msg!("Instruction: CloseBufferMetadata");
+                set_metadata::process_close(program_id, accounts)?;
+            }
+            EchoInstruction::InitializeSolVendingMachine {
+                price_lamports,
+                buffer_size,
+            } => {
+                msg!("Instruction: InitializeSolVendingMachine");
+                initialize_sol_vending_machine::process(
+                    program_id,
+                    accounts,
+                    price_lamports,
+                    buffer_size,
+                )?;
+            }
+            EchoInstruction::SolVendingMachineEcho { data } => {
+                msg!("Instruction: SolVendingMachineEcho");
+                sol_vending_machine_echo::process(program_id, accounts, data)?;
+            }
+            EchoInstruction::WithdrawSolVendingProceeds => {
+                msg!("Instruction: WithdrawSolVendingProceeds");
+                sol_vending_machine_echo::process_withdraw(program_id, accounts)?;
+            }
+            EchoInstruction::WithdrawVendingMachineProceeds { amount } => {
+                msg!("Instruction: WithdrawVendingMachineProceeds");
+                withdraw_vending_machine_proceeds::process(program_id, accounts, amount)?;
+            }
+            EchoInstruction::InitializeMultiSlotEcho {
+                total_size,
+                chunk_size,
+                authority,
+            } => {
+                msg!("Instruction: InitializeMultiSlotEcho");
+                initialize_multi_slot_echo::process(
+                    program_id, accounts, total_size, chunk_size, authority,
+                )?;
+            }
+            EchoInstruction::WriteMultiSlotChunk { chunk_index, data } => {
+                msg!("Instruction: WriteMultiSlotChunk");
+                multi_slot_echo::process_write_chunk(program_id, accounts, chunk_index, data)?;
+            }
+            EchoInstruction::CommitMultiSlot => {
+                msg!("Instruction: CommitMultiSlot");
+                multi_slot_echo::process_commit(program_id, accounts)?;
+            }
+            EchoInstruction::AbortMultiSlot => {
+                msg!("Instruction: AbortMultiSlot");
+                multi_slot_echo::process_abort(program_id, accounts)?;
+            }
+            EchoInstruction::InitializeVotedEcho {
+                buffer_size,
+                voters,
+                quorum,
+            } => {
+                msg!("Instruction: InitializeVotedEcho");
+                initialize_voted_echo::process(program_id, accounts, buffer_size, voters, quorum)?;
+            }
+            EchoInstruction::ProposeVotedWrite { data } => {
+                msg!("Instruction: ProposeVotedWrite");
+                voted_echo::process_propose(program_id, accounts, data)?;
+            }
+            EchoInstruction::CastVote => {
+                msg!("Instruction: CastVote");
+                voted_echo::process_cast_vote(program_id, accounts)?;
+            }
+            EchoInstruction::AbortVotedWrite => {
+                msg!("Instruction: AbortVotedWrite");
+                voted_echo::process_abort(program_id, accounts)?;
+            }
+            EchoInstruction::PauseVendingMachine => {
+                msg!("Instruction: PauseVendingMachine");
+                vending_machine_echo::process_set_paused(program_id, accounts, true)?;
+            }
+            EchoInstruction::ResumeVendingMachine => {
+                msg!("Instruction: ResumeVendingMachine");
+                vending_machine_echo::process_set_paused(program_id, accounts, false)?;
+            }
+            EchoInstruction::InitializeGaslessEcho {
+                buffer_size,
+                authority,
+                relayer,
+            } => {
+                msg!("Instruction: InitializeGaslessEcho");
+                initialize_gasless_echo::process(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                    authority,
+                    relayer,
+                )?;
+            }
+            EchoInstruction::GaslessEcho {
+                data,
+                user_signature,
+            } => {
+                msg!("Instruction: GaslessEcho");
+                gasless_echo::process(program_id, accounts, data, user_signature)?;
+            }
+            EchoInstruction::UpdateRelayer { new_relayer } => {
+                msg!("Instruction: UpdateRelayer");
+                gasless_echo::process_update_relayer(program_id, accounts, new_relayer)?;
+            }
+            EchoInstruction::InitializeOracleEcho {
+                buffer_size,
+                authority,
+                oracle_pubkey,
+                min_price,
+                max_price,
+            } => {
+                msg!("Instruction: InitializeOracleEcho");
+                initialize_oracle_echo::process(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                    authority,
+                    oracle_pubkey,
+                    min_price,
+                    max_price,
+                )?;
+            }
+            EchoInstruction::OracleGatedWrite {
+                data,
+                oracle_offset,
+            } => {
+                msg!("Instruction: OracleGatedWrite");
+                oracle_echo::process(program_id, accounts, data, oracle_offset)?;
+            }
+            EchoInstruction::UpdateOracleGate { new_min, new_max } => {
+                msg!("Instruction: UpdateOracleGate");
+                oracle_echo::process_update_gate(program_id, accounts, new_min, new_max)?;
+            }
+            EchoInstruction::InitializeMemoEcho {
+                buffer_size,
+                authority,
+            } => {
+                msg!("Instruction: InitializeMemoEcho");
+                initialize_memo_echo::process(program_id, accounts, buffer_size, authority)?;
+            }
+            EchoInstruction::MemoEcho { data } => {
+                msg!("Instruction: MemoEcho");
+                memo_echo::process(program_id, accounts, data)?;
+            }
+            EchoInstruction::InitializeSchemaEcho {
+                schema_hash,
+                buffer_size,
+                authority,
+            } => {
+                msg!("Instruction: InitializeSchemaEcho");
+                initialize_schema_echo::process(
+                    program_id,
+                    accounts,
+                    schema_hash,
+                    buffer_size,
+                    authority,
+                )?;
+            }
+            EchoInstruction::SchemaWrite {
+                data,
+                schema_descriptor,
+            } => {
+                msg!("Instruction: SchemaWrite");
+                schema_echo::process(program_id, accounts, data, schema_descriptor)?;
+            }
+            EchoInstruction::InitializeCompressedEcho { tree_id, authority } => {
+                msg!("Instruction: InitializeCompressedEcho");
+                initialize_compressed_echo::process(program_id, accounts, tree_id, authority)?;
+            }
+            EchoInstruction::UpdateCompressedEcho {
+                new_leaf,
+                proof,
+                proof_index,
+                root,
+            } => {
+                msg!("Instruction: UpdateCompressedEcho");
+                compressed_echo::process(program_id, accounts, new_leaf, proof, proof_index, root)?;
+            }
+            EchoInstruction::InitializeAuctionVendingMachine {
+                buffer_size,
+                auction_duration_slots,
+                reserve_price,
+            } => {
+                msg!("Instruction: InitializeAuctionVendingMachine");
+                initialize_auction_vending_machine::process(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                    auction_duration_slots,
+                    reserve_price,
+                )?;
+            }
+            EchoInstruction::BidOnVendingSlot { bid_lamports } => {
+                msg!("Instruction: BidOnVendingSlot");
+                auction_vending_machine::process_bid(program_id, accounts, bid_lamports)?;
+            }
+            EchoInstruction::SettleAuction => {
+                msg!("Instruction: SettleAuction");
+                auction_vending_machine::process_settle(program_id, accounts)?;
+            }
+            EchoInstruction::AuctionEcho { data } => {
+                msg!("Instruction: AuctionEcho");
+                auction_vending_machine::process_write(program_id, accounts, data)?;
+            }
+            EchoInstruction::CloseVendingMachine => {
+                msg!("Instruction: CloseVendingMachine");
+                close_vending_machine::process(program_id, accounts)?;
+            }
+            EchoInstruction::InitializeStakeGatedEcho {
+                buffer_size,
+                vote_account,
+                min_stake,
+            } => {
+                msg!("Instruction: InitializeStakeGatedEcho");
+                initialize_stake_gated_echo::process(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                    vote_account,
+                    min_stake,
+                )?;
+            }
+            EchoInstruction::StakeGatedWrite { data } => {
+                msg!("Instruction: StakeGatedWrite");
+                stake_gated_echo::process(program_id, accounts, data)?;
+            }
+            EchoInstruction::InitializeReplicatedEcho {
+                primary_authority,
+                replica_authority,
+                buffer_size,
+            } => {
+                msg!("Instruction: InitializeReplicatedEcho");
+                initialize_replicated_echo::process(
+                    program_id,
+                    accounts,
+                    primary_authority,
+                    replica_authority,
+                    buffer_size,
+                )?;
+            }
+            EchoInstruction::ReplicatedWrite { data } => {
+                msg!("Instruction: ReplicatedWrite");
+                replicated_echo::process(program_id, accounts, data)?;
+            }
+            EchoInstruction::SetReplicaFrozen { frozen } => {
+                msg!("Instruction: SetReplicaFrozen");
+                replicated_echo::process_set_frozen(program_id, accounts, frozen)?;
+            }
+            EchoInstruction::InitializePriorityFeeEcho {
+                price,
+                buffer_size,
+                sol_fee,
+            } => {
+                msg!("Instruction: InitializePriorityFeeEcho");
+                initialize_vending_machine_echo::process(
+                    program_id,
+                    accounts,
+                    price,
+                    buffer_size,
+                    PAYMENT_MODE_BURN,
+                    0,
+                    false,
+                    0,
+                    sol_fee,
+                    vec![],
+                    0,
+                    0,
+                    0,
+                    0,
+                    false,
+                )?;
+            }
+            EchoInstruction::InitializeTreasury { authority } => {
+                msg!("Instruction: InitializeTreasury");
+                initialize_treasury::process(program_id, accounts, authority)?;
+            }
+            EchoInstruction::DrainTreasury { destination } => {
+                msg!("Instruction: DrainTreasury");
+                drain_treasury::process(program_id, accounts, destination)?;
+            }
+            EchoInstruction::InitializeOwnershipEcho { buffer_size, asset } => {
+                msg!("Instruction: InitializeOwnershipEcho");
+                initialize_ownership_echo::process(program_id, accounts, buffer_size, asset)?;
+            }
+            EchoInstruction::ClaimOwnership { data } => {
+                msg!("Instruction: ClaimOwnership");
+                ownership_echo::process(program_id, accounts, data)?;
+            }
+            EchoInstruction::InitializeSubscriptionEcho {
+                period_slots,
+                price_per_period,
+                buffer_size,
+                authority,
+                mint,
+            } => {
+                msg!("Instruction: InitializeSubscriptionEcho");
+                initialize_subscription_echo::process(
+                    program_id,
+                    accounts,
+                    period_slots,
+                    price_per_period,
+                    buffer_size,
+                    authority,
+                    mint,
+                )?;
+            }
+            EchoInstruction::RenewSubscription { periods } => {
+                msg!("Instruction: RenewSubscription");
+                subscription_echo::process_renew(program_id, accounts, periods)?;
+            }
+            EchoInstruction::SubscriptionWrite { data } => {
+                msg!("Instruction: SubscriptionWrite");
+                subscription_echo::process_write(program_id, accounts, data)?;
+            }
+            EchoInstruction::InitializeExpiringVendingMachine {
+                buffer_size,
+                price,
+                mint,
+                expiry_slot,
+            } => {
+                msg!("Instruction: InitializeExpiringVendingMachine");
+                initialize_vending_machine_echo::process_expiring(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                    price,
+                    mint,
+                    expiry_slot,
+                )?;
+            }
+            EchoInstruction::ClaimExpiredVendingMachine { destination } => {
+                msg!("Instruction: ClaimExpiredVendingMachine");
+                claim_expired_vending_machine::process(program_id, accounts, destination)?;
+            }
+            EchoInstruction::TransferVendingMachineAdmin {} => {
+                msg!("Instruction: TransferVendingMachineAdmin");
+                transfer_vending_machine_admin::process(program_id, accounts)?;
+            }
+            EchoInstruction::InitializeNftVendingMachine {
+                buffer_size,
+                collection,
+            } => {
+                msg!("Instruction: InitializeNftVendingMachine");
+                initialize_nft_vending_machine::process(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                    collection,
+                )?;
+            }
+            EchoInstruction::NftVendingMachineEcho { data } => {
+                msg!("Instruction: NftVendingMachineEcho");
+                nft_vending_machine_echo::process(program_id, accounts, data)?;
+            }
+            EchoInstruction::VendingMachineEchoWriteAt { offset, data } => {
+                msg!("Instruction: VendingMachineEchoWriteAt");
+                vending_machine_echo::process_write_at(program_id, accounts, offset, data)?;
+            }
+            EchoInstruction::InitializeTimeLockedEcho {
+                buffer_size,
+                authority,
+                unlock_slot,
+            } => {
+                msg!("Instruction: InitializeTimeLockedEcho");
+                initialize_time_locked_echo::process(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                    authority,
+                    unlock_slot,
+                )?;
+            }
+            EchoInstruction::TimeLockedWrite { data } => {
+                msg!("Instruction: TimeLockedWrite");
+                time_locked_echo::process_write(program_id, accounts, data)?;
+            }
+            EchoInstruction::TimeLockedRead => {
+                msg!("Instruction: TimeLockedRead");
+                time_locked_echo::process_read(program_id, accounts)?;
+            }
+            EchoInstruction::ExtendTimeLock { new_unlock_slot } => {
+                msg!("Instruction: ExtendTimeLock");
+                time_locked_echo::process_extend(program_id, accounts, new_unlock_slot)?;
+            }
+            EchoInstruction::ReadVendingMachineEcho { offset, len } => {
+                msg!("Instruction: ReadVendingMachineEcho");
+                vending_machine_echo::process_read(program_id, accounts, offset, len)?;
+            }
+            EchoInstruction::InitializeSharedEcho {
+                buffer_size,
+                group_seed,
+                allowed_program,
+            } => {
+                msg!("Instruction: InitializeSharedEcho");
+                initialize_shared_echo::process(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                    group_seed,
+                    allowed_program,
+                )?;
+            }
+            EchoInstruction::WriteShared { data } => {
+                msg!("Instruction: WriteShared");
+                shared_echo::process(program_id, accounts, data)?;
+            }
+            EchoInstruction::InitializeDurableNonceEcho {
+                buffer_size,
+                authority,
+                nonce_account,
+            } => {
+                msg!("Instruction: InitializeDurableNonceEcho");
+                initialize_durable_nonce_echo::process(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                    authority,
+                    nonce_account,
+                )?;
+            }
+            EchoInstruction::DurableNonceWrite { data, nonce_value } => {
+                msg!("Instruction: DurableNonceWrite");
+                durable_nonce_echo::process(program_id, accounts, data, nonce_value)?;
+            }
+            EchoInstruction::ResizeVendingMachine { new_size } => {
+                msg!("Instruction: ResizeVendingMachine");
+                resize_vending_machine::process(program_id, accounts, new_size)?;
+            }
+            EchoInstruction::InitializeGovernanceEcho {
+                buffer_size,
+                governance_mint,
+                min_tokens,
+            } => {
+                msg!("Instruction: InitializeGovernanceEcho");
+                initialize_governance_echo::process(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                    governance_mint,
+                    min_tokens,
+                )?;
+            }
+            EchoInstruction::GovernanceWrite { data } => {
+                msg!("Instruction: GovernanceWrite");
+                governance_echo::process_write(program_id, accounts, data)?;
+            }
+            EchoInstruction::UpdateGovernanceThreshold { new_min } => {
+                msg!("Instruction: UpdateGovernanceThreshold");
+                governance_echo::process_update_threshold(program_id, accounts, new_min)?;
+            }
+            EchoInstruction::InitializeWindowedVendingMachine {
+                buffer_size,
+                price,
+                start_slot,
+                end_slot,
+            } => {
+                msg!("Instruction: InitializeWindowedVendingMachine");
+                initialize_vending_machine_echo::process_windowed(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                    price,
+                    start_slot,
+                    end_slot,
+                )?;
+            }
+            EchoInstruction::SetVendingWindow {
+                start_slot,
+                end_slot,
+            } => {
+                msg!("Instruction: SetVendingWindow");
+                vending_machine_echo::process_set_window(
+                    program_id, accounts, start_slot, end_slot,
+                )?;
+            }
+            EchoInstruction::InitializeCrossChainEcho {
+                buffer_size,
+                emitter_chain,
+                emitter_address,
+            } => {
+                msg!("Instruction: InitializeCrossChainEcho");
+                initialize_cross_chain_echo::process(
+                    program_id,
+                    accounts,
+                    buffer_size,
+                    emitter_chain,
+                    emitter_address,
+                )?;
+            }
+            EchoInstruction::CrossChainWrite { data } => {
+                msg!("Instruction: CrossChainWrite");
+                cross_chain_echo::process(program_id, accounts, data)?;
+            }
+            EchoInstruction::BatchInitialize { configs } => {
+                msg!("Instruction: BatchInitialize");
+                batch_initialize::process(program_id, accounts, configs)?;
+            }
+            EchoInstruction::AddToWhitelist { member } => {
+                msg!("Instruction: AddToWhitelist");
+                vending_whitelist::process_add(program_id, accounts, member)?;
+            }
+            EchoInstruction::RemoveFromWhitelist { member } => {
+                msg!("Instruction: RemoveFromWhitelist");
+                vending_whitelist::process_remove(program_id, accounts, member)?;
+            }
+            EchoInstruction::SetWhitelistEnabled { enabled } => {
+                msg!("Instruction: SetWhitelistEnabled");
+                vending_whitelist::process_set_enabled(program_id, accounts, enabled)?;
+            }
+            EchoInstruction::MergeBuffers => {
+                msg!("Instruction: MergeBuffers");
+                authorized_echo::process_merge(program_id, accounts)?;
+            }
+            EchoInstruction::CloseVendingUserRecord => {
+                msg!("Instruction: CloseVendingUserRecord");
+                vending_machine_echo::process_close_user_record(program_id, accounts)?;
+            }
+            EchoInstruction::SwapBuffers => {
+                msg!("Instruction: SwapBuffers");
+                swap_buffers::process(program_id, accounts)?;
+            }
+            EchoInstruction::SnapshotBuffer => {
+                msg!("Instruction: SnapshotBuffer");
+                snapshot_authorized_echo::process_at_slot(program_id, accounts)?;
+            }
+            EchoInstruction::ListSnapshots => {
+                msg!("Instruction: ListSnapshots");
+                snapshot_authorized_echo::process_list(program_id, accounts)?;
+            }
+            EchoInstruction::InitializeGradualReleaseEcho {
+                total_slots,
+                data,
+                authority,
+            } => {
+                msg!("Instruction: InitializeGradualReleaseEcho");
+                initialize_gradual_release_echo::process(
+                    program_id,
+                    accounts,
+                    total_slots,
+                    data,
+                    authority,
+                )?;
+            }
+            EchoInstruction::RevealGradual { current_slot } => {
+                msg!("Instruction: RevealGradual");
+                gradual_release_echo::process_reveal(program_id, accounts, current_slot)?;
+            }
         }
 
         Ok(())
@@ -0,0 +1,120 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    state::{AggregatorBufferHeader, AGGREGATOR_BUFF_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    aggregator_buffer: &'a AccountInfo<'b>,
+    signer: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            aggregator_buffer: next_account_info(accounts_iter)?,
+            signer: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.aggregator_buffer.is_writable {
+            msg!("Aggregator buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.signer.is_signer {
+            msg!("Signer account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Reads the header of `aggregator_buffer` after checking that it is an aggregator buffer of the Echo Program.
+fn validate_aggregator_buffer(
+    program_id: &Pubkey,
+    aggregator_buffer: &AccountInfo,
+) -> Result<AggregatorBufferHeader, ProgramError> {
+    if aggregator_buffer.owner != program_id {
+        msg!("Aggregator buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = aggregator_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < AGGREGATOR_BUFF_HEADER_SIZE {
+        msg!("Invalid aggregator buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header =
+        AggregatorBufferHeader::try_from_slice(&buffer[..AGGREGATOR_BUFF_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(
+        &[b"aggregator", buffer_header.authority.as_ref()],
+        program_id,
+    );
+
+    if pda != *aggregator_buffer.key {
+        msg!("Invalid aggregator buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// XORs `data` into the buffer, so any signer can contribute and contributing the same value twice cancels out.
+pub fn process_aggregate_write(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    validate_aggregator_buffer(program_id, ctx.aggregator_buffer)?;
+
+    let buffer = &mut (*ctx.aggregator_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[AGGREGATOR_BUFF_HEADER_SIZE..];
+    let buffer_len = buffer_data.len();
+
+    // bytes past the end of the buffer wrap around to the start
+    for (index, byte) in data.iter().enumerate() {
+        buffer_data[index % buffer_len] ^= byte;
+    }
+
+    msg!("Aggregated {} bytes from {}", data.len(), ctx.signer.key);
+
+    Ok(())
+}
+
+/// Zeroes the buffer, only the authority the buffer was created for can reset it.
+pub fn process_reset(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let buffer_header = validate_aggregator_buffer(program_id, ctx.aggregator_buffer)?;
+
+    if buffer_header.authority != *ctx.signer.key {
+        msg!("Only the aggregator authority can reset the buffer");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let buffer = &mut (*ctx.aggregator_buffer.data).borrow_mut();
+    buffer[AGGREGATOR_BUFF_HEADER_SIZE..].fill(0);
+
+    Ok(())
+}
@@ -0,0 +1,269 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction::transfer,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{AuctionHeader, AUCTION_HEADER_SIZE},
+};
+
+/// Address and bump seed of the vault escrowing the highest bid of `auction_buffer`. It is never allocated, staying a
+/// system account so its lamports can be moved with a signed system transfer.
+pub(crate) fn vault_address(program_id: &Pubkey, auction_buffer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"auction_vault", auction_buffer.as_ref()], program_id)
+}
+
+/// Reads the header of `auction_buffer` after checking its PDA.
+fn validate_auction_buffer(
+    program_id: &Pubkey,
+    auction_buffer: &AccountInfo,
+) -> Result<AuctionHeader, ProgramError> {
+    if !auction_buffer.is_writable {
+        msg!("Auction buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if auction_buffer.owner != program_id {
+        msg!("Auction buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = auction_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < AUCTION_HEADER_SIZE {
+        msg!("Invalid auction buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = AuctionHeader::try_from_slice(&buffer[..AUCTION_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"auction", buffer_header.creator.as_ref()], program_id);
+
+    if pda != *auction_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or creator");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Checks that `vault` is the writable vault of `auction_buffer`.
+fn validate_vault(
+    program_id: &Pubkey,
+    auction_buffer: &AccountInfo,
+    vault: &AccountInfo,
+    buffer_header: &AuctionHeader,
+) -> ProgramResult {
+    if vault_address(program_id, auction_buffer.key) != (*vault.key, buffer_header.vault_bump_seed)
+    {
+        msg!("Invalid vault address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    if !vault.is_writable {
+        msg!("Vault account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    Ok(())
+}
+
+fn check_system_program(system_program: &AccountInfo) -> ProgramResult {
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        msg!("Invalid system program");
+        return Err(EchoError::InvalidProgramAddress.into());
+    }
+
+    Ok(())
+}
+
+fn write_header(auction_buffer: &AccountInfo, buffer_header: &AuctionHeader) {
+    auction_buffer.data.borrow_mut()[..AUCTION_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+}
+
+/// Escrows `bid_lamports` in the vault and refunds the previous highest bid, the bid has to be at least the reserve
+/// price and more than the highest bid.
+pub fn process_bid(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    bid_lamports: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let auction_buffer = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let bidder = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !bidder.is_signer {
+        msg!("Bidder must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    check_system_program(system_program)?;
+
+    let mut buffer_header = validate_auction_buffer(program_id, auction_buffer)?;
+    validate_vault(program_id, auction_buffer, vault, &buffer_header)?;
+
+    if buffer_header.settled || Clock::get()?.slot > buffer_header.end_slot {
+        msg!("The auction ended at slot {}", buffer_header.end_slot);
+        return Err(EchoError::AuctionEnded.into());
+    }
+
+    if bid_lamports < buffer_header.reserve_price || bid_lamports <= buffer_header.highest_bid {
+        msg!(
+            "Bid {} must be at least {} and above {}",
+            bid_lamports,
+            buffer_header.reserve_price,
+            buffer_header.highest_bid
+        );
+        return Err(EchoError::BidTooLow.into());
+    }
+
+    invoke(
+        &transfer(bidder.key, vault.key, bid_lamports),
+        &[bidder.clone(), vault.clone(), system_program.clone()],
+    )?;
+
+    // there is no previous bidder to refund before the first bid
+    if buffer_header.highest_bid > 0 {
+        let previous_bidder = next_account_info(accounts_iter)?;
+
+        if *previous_bidder.key != buffer_header.highest_bidder {
+            msg!(
+                "The previous bidder {} must be passed",
+                buffer_header.highest_bidder
+            );
+            return Err(EchoError::InvalidAccountAddress.into());
+        }
+
+        invoke_signed(
+            &transfer(vault.key, previous_bidder.key, buffer_header.highest_bid),
+            &[
+                vault.clone(),
+                previous_bidder.clone(),
+                system_program.clone(),
+            ],
+            &[&[
+                b"auction_vault",
+                auction_buffer.key.as_ref(),
+                &[buffer_header.vault_bump_seed],
+            ]],
+        )?;
+    }
+
+    buffer_header.highest_bid = bid_lamports;
+    buffer_header.highest_bidder = *bidder.key;
+    write_header(auction_buffer, &buffer_header);
+
+    msg!("Highest bid: {} by {}", bid_lamports, bidder.key);
+
+    Ok(())
+}
+
+/// Pays the highest bid to the creator once the auction ended, the highest bidder can write from then on.
+pub fn process_settle(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let auction_buffer = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let creator = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    check_system_program(system_program)?;
+
+    let mut buffer_header = validate_auction_buffer(program_id, auction_buffer)?;
+    validate_vault(program_id, auction_buffer, vault, &buffer_header)?;
+
+    if buffer_header.settled {
+        msg!("The auction is already settled");
+        return Err(EchoError::AuctionEnded.into());
+    }
+
+    if Clock::get()?.slot <= buffer_header.end_slot {
+        msg!("The auction ends at slot {}", buffer_header.end_slot);
+        return Err(EchoError::AuctionNotEnded.into());
+    }
+
+    if *creator.key != buffer_header.creator {
+        msg!("The creator {} must be passed", buffer_header.creator);
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    if buffer_header.highest_bid > 0 {
+        invoke_signed(
+            &transfer(vault.key, creator.key, buffer_header.highest_bid),
+            &[vault.clone(), creator.clone(), system_program.clone()],
+            &[&[
+                b"auction_vault",
+                auction_buffer.key.as_ref(),
+                &[buffer_header.vault_bump_seed],
+            ]],
+        )?;
+    }
+
+    buffer_header.settled = true;
+    write_header(auction_buffer, &buffer_header);
+
+    msg!(
+        "Settled for {} lamports, winner {}",
+        buffer_header.highest_bid,
+        buffer_header.highest_bidder
+    );
+
+    Ok(())
+}
+
+/// Copies `data` past the header, only the winner of a settled auction can.
+pub fn process_write(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let auction_buffer = next_account_info(accounts_iter)?;
+    let winner = next_account_info(accounts_iter)?;
+
+    if !winner.is_signer {
+        msg!("Winner must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let buffer_header = validate_auction_buffer(program_id, auction_buffer)?;
+
+    if !buffer_header.settled {
+        msg!("The auction must be settled before writing");
+        return Err(EchoError::AuctionNotEnded.into());
+    }
+
+    if buffer_header.highest_bid == 0 || buffer_header.highest_bidder != *winner.key {
+        msg!("Only the winner of the auction can write");
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    let buffer = &mut (*auction_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[AUCTION_HEADER_SIZE..];
+    let len = buffer_data.len().min(data.len());
+    buffer_data[..len].copy_from_slice(&data[..len]);
+    buffer_data[len..].fill(0);
+
+    msg!("Wrote {} bytes", len);
+
+    Ok(())
+}
@@ -1,16 +1,26 @@
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
-    msg,
+    keccak, msg,
+    program::{invoke, set_return_data},
     program_error::ProgramError,
     pubkey::Pubkey,
+    system_instruction::transfer,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
 };
 
-use borsh::BorshDeserialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 
 use crate::{
     error::EchoError,
-    state::{AuthorizedBufferHeader, AUTH_BUFF_HEADER_SIZE},
+    events::{EchoEvent, HashOnlyWriteEvent, WriteEvent},
+    processor::registry::unregister_buffer,
+    state::{
+        AuthorizedBufferHeader, AUTH_BUFF_HEADER_SIZE, HASH_ONLY_RECORD_SIZE, MAX_BPS,
+        MAX_BUFFER_LABEL_LEN,
+    },
 };
 
 struct Context<'a, 'b: 'a> {
@@ -20,6 +30,19 @@ struct Context<'a, 'b: 'a> {
 
 impl<'a, 'b: 'a> Context<'a, 'b> {
     pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let ctx = Self::parse_unsigned(accounts)?;
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+
+    /// Same as `parse` without requiring the authority signature, for the instructions that also accept the
+    /// permanent delegate through `check_authority_or_delegate`.
+    fn parse_unsigned(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
         let accounts_iter = &mut accounts.iter();
 
         let ctx = Self {
@@ -32,47 +55,233 @@ impl<'a, 'b: 'a> Context<'a, 'b> {
             return Err(EchoError::AccountMustBeWritable.into());
         }
 
-        if !ctx.authority.is_signer {
-            msg!("Authority account must be signer");
-            return Err(EchoError::MissingRequiredSignature.into());
-        }
-
         Ok(ctx)
     }
 }
 
+/// Checks that the authority signed, or that the permanent delegate of the buffer is among `others` and signed.
+///
+/// The permanent delegate is an emergency key, e.g. a security council, that can still clear or close a buffer when
+/// the authority key is lost. It can only destroy data, never author it, so it is accepted by `ClearAuthorizedBuffer`
+/// and `CloseAuthorizedEcho` only, while writes, appends, labels and commit/reveal keep requiring the authority.
+/// Closing still pays the lamports to the authority, so the delegate gains nothing from it. The delegate is set at
+/// initialization and no instruction can change it.
+fn check_authority_or_delegate(
+    authority: &AccountInfo,
+    buffer_header: &AuthorizedBufferHeader,
+    others: &[AccountInfo],
+) -> ProgramResult {
+    if authority.is_signer {
+        return Ok(());
+    }
+
+    let delegate_signed = buffer_header.permanent_delegate().is_some_and(|delegate| {
+        others
+            .iter()
+            .any(|account| *account.key == delegate && account.is_signer)
+    });
+
+    if !delegate_signed {
+        msg!("Authority or permanent delegate account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    msg!("Signed by the permanent delegate");
+
+    Ok(())
+}
+
 pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
     let ctx = Context::parse(accounts)?;
 
-    let buffer = &mut (*ctx.authorized_buffer.data).borrow_mut();
+    let buffer_header =
+        validate_authorized_buffer(program_id, ctx.authorized_buffer, ctx.authority.key)?;
 
-    // check the size of the account before trying to read it
-    if buffer.len() < AUTH_BUFF_HEADER_SIZE {
-        msg!("Invalid authorized buffer size, {}", buffer.len());
-        return Err(EchoError::AccountNotInitialized.into());
-    }
+    charge_write_fee(
+        program_id,
+        ctx.authority,
+        &buffer_header,
+        accounts.get(2..).unwrap_or_default(),
+    )?;
 
-    // in order to validate the PDA address, we first read it to access the buffer seed
-    let buffer_header = AuthorizedBufferHeader::try_from_slice(&buffer[..AUTH_BUFF_HEADER_SIZE])?;
+    overwrite_authorized_buffer(
+        ctx.authorized_buffer,
+        ctx.authority.key,
+        buffer_header,
+        &data,
+    )?;
 
-    // verify that the PDA account is the correct address
-    let pda = Pubkey::create_program_address(
-        &[
-            b"authority",
-            ctx.authority.key.as_ref(),
-            &buffer_header.buffer_seed.to_le_bytes(),
-            &[buffer_header.bump_seed],
-        ],
+    Ok(())
+}
+
+/// Keccak hash of `data` followed by its length, the payload kept by a hash-only write.
+fn hash_only_record(data: &[u8]) -> Result<Vec<u8>, ProgramError> {
+    let len = u32::try_from(data.len()).map_err(|_| EchoError::Overflow)?;
+
+    Ok([&keccak::hash(data).to_bytes()[..], &len.to_le_bytes()].concat())
+}
+
+/// Validates and charges like `process`, but only stores the hash and length of `data` and logs `data` itself.
+pub fn process_hash_only(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let buffer_header =
+        validate_authorized_buffer(program_id, ctx.authorized_buffer, ctx.authority.key)?;
+
+    if ctx.authorized_buffer.data_len() < AUTH_BUFF_HEADER_SIZE + HASH_ONLY_RECORD_SIZE {
+        msg!(
+            "Buffer payload must hold at least {} bytes for the hash and length",
+            HASH_ONLY_RECORD_SIZE
+        );
+        return Err(EchoError::BufferFull.into());
+    }
+
+    charge_write_fee(
         program_id,
+        ctx.authority,
+        &buffer_header,
+        accounts.get(2..).unwrap_or_default(),
     )?;
 
-    if pda != *ctx.authorized_buffer.key {
-        msg!("Invalid account address or authority");
+    let record = hash_only_record(&data)?;
+    let sequence = overwrite_authorized_buffer(
+        ctx.authorized_buffer,
+        ctx.authority.key,
+        buffer_header,
+        &record,
+    )?;
+
+    let mut hash = [0; 32];
+    hash.copy_from_slice(&record[..32]);
+
+    EchoEvent::HashOnlyWrite(HashOnlyWriteEvent {
+        buffer: *ctx.authorized_buffer.key,
+        authority: *ctx.authority.key,
+        hash,
+        data,
+        slot: Clock::get()?.slot,
+        sequence,
+    })
+    .emit();
+
+    Ok(())
+}
+
+/// Checks that `data` is the preimage stored by the last hash-only write, no signature is needed.
+pub fn process_verify(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authorized_buffer = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+
+    let buffer_header = validate_authorized_buffer(program_id, authorized_buffer, authority.key)?;
+    check_complete(&buffer_header)?;
+
+    let buffer = authorized_buffer.data.borrow();
+    let stored = buffer
+        .get(AUTH_BUFF_HEADER_SIZE..AUTH_BUFF_HEADER_SIZE + HASH_ONLY_RECORD_SIZE)
+        .ok_or(EchoError::InvalidAccountData)?;
+
+    if stored != hash_only_record(&data)?.as_slice() {
+        msg!("Data does not match the stored hash");
+        return Err(EchoError::HashMismatch.into());
+    }
+
+    msg!("Verified {} bytes", data.len());
+
+    Ok(())
+}
+
+/// Charges the `write_fee_lamports` of the buffer, if any, on every instruction writing data to it. `fee_accounts` are
+/// the accounts after the buffer and the authority.
+pub(crate) fn charge_write_fee<'a>(
+    program_id: &Pubkey,
+    authority: &AccountInfo<'a>,
+    buffer_header: &AuthorizedBufferHeader,
+    fee_accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    if buffer_header.write_fee_lamports == 0 {
+        return Ok(());
+    }
+
+    pay_write_fee(
+        program_id,
+        authority,
+        fee_accounts,
+        buffer_header.write_fee_lamports,
+    )
+}
+
+/// Transfers `write_fee_lamports` from the authority to the fee vault, `fee_accounts` being the fee vault and the
+/// system program.
+fn pay_write_fee<'a>(
+    program_id: &Pubkey,
+    authority: &AccountInfo<'a>,
+    fee_accounts: &[AccountInfo<'a>],
+    write_fee_lamports: u64,
+) -> ProgramResult {
+    let (fee_vault, system_program) = match fee_accounts {
+        [fee_vault, system_program, ..] => (fee_vault, system_program),
+        _ => {
+            msg!("The fee vault and system program must be passed to pay the write fee");
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+    };
+
+    let (vault_pda, _) = Pubkey::find_program_address(&[b"fee_vault"], program_id);
+
+    if *fee_vault.key != vault_pda {
+        msg!("Invalid fee vault address");
         return Err(EchoError::InvalidAccountAddress.into());
     }
 
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        msg!("Invalid system program");
+        return Err(EchoError::InvalidProgramAddress.into());
+    }
+
+    invoke(
+        &transfer(authority.key, fee_vault.key, write_fee_lamports),
+        &[authority.clone(), fee_vault.clone(), system_program.clone()],
+    )?;
+
+    msg!("Paid write fee of {} lamports", write_fee_lamports);
+
+    Ok(())
+}
+
+/// Increments the sequence of the buffer and sets it as the return data, for every change to the payload.
+pub(crate) fn bump_sequence(buffer_header: &mut AuthorizedBufferHeader) -> ProgramResult {
+    buffer_header.sequence = buffer_header
+        .sequence
+        .checked_add(1)
+        .ok_or(EchoError::SequenceOverflow)?;
+
+    set_return_data(&buffer_header.sequence.to_le_bytes());
+
+    Ok(())
+}
+
+/// Overwrites the payload of an already validated `authorized_buffer` with `data`, zero-padding the rest, and
+/// stores `buffer_header` with the cursor moved to the end of the written bytes. Returns the new sequence.
+pub(crate) fn overwrite_authorized_buffer(
+    authorized_buffer: &AccountInfo,
+    authority: &Pubkey,
+    mut buffer_header: AuthorizedBufferHeader,
+    data: &[u8],
+) -> Result<u64, ProgramError> {
+    let buffer = &mut (*authorized_buffer.data).borrow_mut();
+
     // this is the 'rest' of the account's data (beyond the header info)
     let buffer_data = &mut buffer[AUTH_BUFF_HEADER_SIZE..];
+    let bytes_written = buffer_data.len().min(data.len());
 
     // loop over each byte in the rest of account's data
     for index in 0..buffer_data.len() {
@@ -82,7 +291,507 @@ pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) ->
         };
     }
 
+    // later appends continue right after the overwritten payload, and a pending chunked write is abandoned
+    buffer_header.cursor = bytes_written as u32;
+    buffer_header.write_in_progress = false;
+    bump_sequence(&mut buffer_header)?;
+    buffer[..AUTH_BUFF_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    EchoEvent::Write(WriteEvent {
+        buffer: *authorized_buffer.key,
+        authority: *authority,
+        len: bytes_written as u64,
+        slot: Clock::get()?.slot,
+        sequence: buffer_header.sequence,
+    })
+    .emit();
+
+    Ok(buffer_header.sequence)
+}
+
+/// Closes the buffer, sending its lamports to the authority and removing it from the registry if one is passed.
+pub fn process_close(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse_unsigned(accounts)?;
+
+    let buffer_header =
+        validate_authorized_buffer(program_id, ctx.authorized_buffer, ctx.authority.key)?;
+    // the registry keeps its index whoever signs, the Echo Program id holds its place when there is none
+    let registry = accounts.get(2).filter(|account| account.key != program_id);
+    let others = accounts.get(3..).unwrap_or_default();
+    check_authority_or_delegate(ctx.authority, &buffer_header, others)?;
+
+    if !ctx.authority.is_writable {
+        msg!("Authority account must be writable to receive the buffer lamports");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if let Some(registry) = registry {
+        unregister_buffer(
+            program_id,
+            registry,
+            ctx.authority.key,
+            ctx.authorized_buffer.key,
+        )?;
+    }
+
+    let lamports = ctx.authorized_buffer.lamports();
+    **ctx.authority.lamports.borrow_mut() = ctx
+        .authority
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(EchoError::Overflow)?;
+    **ctx.authorized_buffer.lamports.borrow_mut() = 0;
+
+    // the runtime deletes the account once it has no lamports left, clear it in case it is funded again
+    ctx.authorized_buffer.data.borrow_mut().fill(0);
+
+    msg!("Closed authorized buffer, reclaimed {} lamports", lamports);
+
+    Ok(())
+}
+
+/// Closes an expired buffer on behalf of its authority, paying the cranker a share of the lamports as a bounty.
+pub fn process_crank_close_expired(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authorized_buffer = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+    let cranker = next_account_info(accounts_iter)?;
+    let registry = accounts_iter.next();
+
+    if !authorized_buffer.is_writable || !authority.is_writable || !cranker.is_writable {
+        msg!("Authorized buffer, authority and cranker accounts must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !cranker.is_signer {
+        msg!("Cranker account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    // the PDA check ties the buffer to `authority`, so the remainder can't be redirected
+    let buffer_header = validate_authorized_buffer(program_id, authorized_buffer, authority.key)?;
+
+    let slot = Clock::get()?.slot;
+
+    if buffer_header.expiry_slot == 0 || slot <= buffer_header.expiry_slot {
+        msg!(
+            "Buffer expires at slot {}, current slot {}",
+            buffer_header.expiry_slot,
+            slot
+        );
+        return Err(EchoError::BufferNotExpired.into());
+    }
+
+    if let Some(registry) = registry {
+        unregister_buffer(program_id, registry, authority.key, authorized_buffer.key)?;
+    }
+
+    let lamports = authorized_buffer.lamports();
+    let bounty =
+        (lamports as u128 * buffer_header.crank_bounty_bps as u128 / MAX_BPS as u128) as u64;
+
+    **cranker.lamports.borrow_mut() = cranker
+        .lamports()
+        .checked_add(bounty)
+        .ok_or(EchoError::Overflow)?;
+    **authority.lamports.borrow_mut() = authority
+        .lamports()
+        .checked_add(lamports - bounty)
+        .ok_or(EchoError::Overflow)?;
+    **authorized_buffer.lamports.borrow_mut() = 0;
+
+    // the runtime deletes the account once it has no lamports left, clear it in case it is funded again
+    authorized_buffer.data.borrow_mut().fill(0);
+
+    msg!(
+        "Closed expired buffer, {} lamports to the cranker, {} to the authority",
+        bounty,
+        lamports - bounty
+    );
+
+    Ok(())
+}
+
+/// Zero-fills the payload of the buffer, keeping the header and the account itself.
+pub fn process_clear(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse_unsigned(accounts)?;
+
+    let mut buffer_header =
+        validate_authorized_buffer(program_id, ctx.authorized_buffer, ctx.authority.key)?;
+    check_authority_or_delegate(
+        ctx.authority,
+        &buffer_header,
+        accounts.get(2..).unwrap_or_default(),
+    )?;
+
+    let buffer = &mut (*ctx.authorized_buffer.data).borrow_mut();
+    buffer[AUTH_BUFF_HEADER_SIZE..].fill(0);
+
+    buffer_header.cursor = 0;
+    // the chunks written so far are gone, so a chunked write in progress is abandoned
+    buffer_header.write_in_progress = false;
+    bump_sequence(&mut buffer_header)?;
+    buffer[..AUTH_BUFF_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Cleared {} bytes", buffer.len() - AUTH_BUFF_HEADER_SIZE);
+
+    EchoEvent::Write(WriteEvent {
+        buffer: *ctx.authorized_buffer.key,
+        authority: *ctx.authority.key,
+        len: 0,
+        slot: Clock::get()?.slot,
+        sequence: buffer_header.sequence,
+    })
+    .emit();
+
+    Ok(())
+}
+
+/// Replaces the label of the buffer, the payload is left as is.
+pub fn process_set_label(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    label: String,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let mut buffer_header =
+        validate_authorized_buffer(program_id, ctx.authorized_buffer, ctx.authority.key)?;
+    buffer_header.label = encode_label(&label)?;
+
+    ctx.authorized_buffer.data.borrow_mut()[..AUTH_BUFF_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Label: {}", label);
+
+    Ok(())
+}
+
+/// Zero-pads `label` to the fixed size stored in the header.
+pub(crate) fn encode_label(label: &str) -> Result<[u8; MAX_BUFFER_LABEL_LEN], ProgramError> {
+    if label.len() > MAX_BUFFER_LABEL_LEN {
+        msg!(
+            "Invalid label length {}, must be at most {} bytes",
+            label.len(),
+            MAX_BUFFER_LABEL_LEN
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    let mut encoded = [0; MAX_BUFFER_LABEL_LEN];
+    encoded[..label.len()].copy_from_slice(label.as_bytes());
+
+    Ok(encoded)
+}
+
+/// Writes `data` right after the bytes written so far instead of overwriting the buffer.
+pub fn process_append(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let mut buffer_header =
+        validate_authorized_buffer(program_id, ctx.authorized_buffer, ctx.authority.key)?;
+    check_complete(&buffer_header)?;
+
+    // the fee moves lamports of the authority, it is paid before the buffer data is borrowed
+    charge_write_fee(
+        program_id,
+        ctx.authority,
+        &buffer_header,
+        accounts.get(2..).unwrap_or_default(),
+    )?;
+
+    let buffer = &mut (*ctx.authorized_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[AUTH_BUFF_HEADER_SIZE..];
+
+    let start = buffer_header.cursor as usize;
+    let end = start.checked_add(data.len()).ok_or(EchoError::Overflow)?;
+
+    if end > buffer_data.len() {
+        msg!(
+            "Cannot append {} bytes at {}, buffer only holds {}",
+            data.len(),
+            start,
+            buffer_data.len()
+        );
+        return Err(EchoError::BufferFull.into());
+    }
+
+    buffer_data[start..end].copy_from_slice(&data);
+
+    buffer_header.cursor = u32::try_from(end).map_err(|_| EchoError::Overflow)?;
+    bump_sequence(&mut buffer_header)?;
+    buffer[..AUTH_BUFF_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    EchoEvent::Write(WriteEvent {
+        buffer: *ctx.authorized_buffer.key,
+        authority: *ctx.authority.key,
+        len: data.len() as u64,
+        slot: Clock::get()?.slot,
+        sequence: buffer_header.sequence,
+    })
+    .emit();
+
+    Ok(())
+}
+
+/// Appends the bytes written to `source_buffer` after the bytes written to `destination_buffer`, then closes the
+/// source, sending its lamports to the destination and removing it from the registry if one is passed.
+pub fn process_merge(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let source_buffer = next_account_info(accounts_iter)?;
+    let destination_buffer = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+    let registry = accounts_iter.next();
+
+    if !source_buffer.is_writable || !destination_buffer.is_writable {
+        msg!("Source and destination buffer accounts must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !authority.is_signer {
+        msg!("Authority account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    if source_buffer.key == destination_buffer.key {
+        msg!("Cannot merge a buffer into itself");
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    let source_header = validate_authorized_buffer(program_id, source_buffer, authority.key)?;
+    let mut destination_header =
+        validate_authorized_buffer(program_id, destination_buffer, authority.key)?;
+    check_complete(&source_header)?;
+    check_complete(&destination_header)?;
+
+    {
+        let source = source_buffer.data.borrow();
+        let destination = &mut (*destination_buffer.data).borrow_mut();
+
+        let source_data = source[AUTH_BUFF_HEADER_SIZE..]
+            .get(..source_header.cursor as usize)
+            .ok_or(EchoError::InvalidAccountData)?;
+        let destination_data = &mut destination[AUTH_BUFF_HEADER_SIZE..];
+
+        let start = destination_header.cursor as usize;
+        let end = start
+            .checked_add(source_data.len())
+            .ok_or(EchoError::Overflow)?;
+
+        if end > destination_data.len() {
+            msg!(
+                "Cannot merge {} bytes at {}, destination buffer only holds {}",
+                source_data.len(),
+                start,
+                destination_data.len()
+            );
+            return Err(EchoError::InvalidInstructionInput.into());
+        }
+
+        destination_data[start..end].copy_from_slice(source_data);
+
+        destination_header.cursor = u32::try_from(end).map_err(|_| EchoError::Overflow)?;
+        bump_sequence(&mut destination_header)?;
+        destination[..AUTH_BUFF_HEADER_SIZE]
+            .copy_from_slice(&destination_header.try_to_vec().unwrap());
+    }
+
+    if let Some(registry) = registry {
+        unregister_buffer(program_id, registry, authority.key, source_buffer.key)?;
+    }
+
+    let lamports = source_buffer.lamports();
+    **destination_buffer.lamports.borrow_mut() = destination_buffer
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(EchoError::Overflow)?;
+    **source_buffer.lamports.borrow_mut() = 0;
+
+    // the runtime deletes the account once it has no lamports left, clear it in case it is funded again
+    source_buffer.data.borrow_mut().fill(0);
+
+    EchoEvent::Write(WriteEvent {
+        buffer: *destination_buffer.key,
+        authority: *authority.key,
+        len: source_header.cursor as u64,
+        slot: Clock::get()?.slot,
+        sequence: destination_header.sequence,
+    })
+    .emit();
+
+    msg!(
+        "Merged {} bytes, closed source buffer with {} lamports",
+        source_header.cursor,
+        lamports
+    );
+
+    Ok(())
+}
+
+/// Fails while a chunked write is in progress, as the payload is only partially uploaded.
+pub(crate) fn check_complete(buffer_header: &AuthorizedBufferHeader) -> ProgramResult {
+    if buffer_header.write_in_progress {
+        msg!("A chunked write is in progress, finalize it first");
+        return Err(EchoError::WriteInProgress.into());
+    }
+
     Ok(())
 }
 
+/// Writes one chunk of a payload too large for a single transaction at `offset`.
+pub fn process_chunk(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    offset: u32,
+    data: Vec<u8>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let mut buffer_header =
+        validate_authorized_buffer(program_id, ctx.authorized_buffer, ctx.authority.key)?;
+
+    // every chunk is a write of its own
+    charge_write_fee(
+        program_id,
+        ctx.authority,
+        &buffer_header,
+        accounts.get(2..).unwrap_or_default(),
+    )?;
+
+    let buffer = &mut (*ctx.authorized_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[AUTH_BUFF_HEADER_SIZE..];
+
+    let start = offset as usize;
+    let end = start.checked_add(data.len()).ok_or(EchoError::Overflow)?;
+
+    if end > buffer_data.len() {
+        msg!(
+            "Cannot write {} bytes at {}, buffer only holds {}",
+            data.len(),
+            start,
+            buffer_data.len()
+        );
+        return Err(EchoError::BufferFull.into());
+    }
+
+    buffer_data[start..end].copy_from_slice(&data);
+
+    if !buffer_header.write_in_progress {
+        buffer_header.write_in_progress = true;
+        buffer[..AUTH_BUFF_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+    }
+
+    msg!("Wrote chunk of {} bytes at {}", data.len(), start);
+
+    Ok(())
+}
+
+/// Checks the hash of the uploaded chunks and makes the payload readable again.
+pub fn process_finalize(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    total_len: u32,
+    hash: [u8; 32],
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let mut buffer_header =
+        validate_authorized_buffer(program_id, ctx.authorized_buffer, ctx.authority.key)?;
+
+    if !buffer_header.write_in_progress {
+        msg!("No chunked write to finalize");
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    let buffer = &mut (*ctx.authorized_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[AUTH_BUFF_HEADER_SIZE..];
+    let total_len = total_len as usize;
+
+    if total_len > buffer_data.len() {
+        msg!(
+            "Invalid total length {}, buffer only holds {}",
+            total_len,
+            buffer_data.len()
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    if keccak::hash(&buffer_data[..total_len]).to_bytes() != hash {
+        msg!("Uploaded data does not match the hash, a chunk may be missing");
+        return Err(EchoError::HashMismatch.into());
+    }
+
+    // same layout as a single `AuthorizedEcho` of the whole payload
+    buffer_data[total_len..].fill(0);
+
+    buffer_header.cursor = total_len as u32;
+    buffer_header.write_in_progress = false;
+    bump_sequence(&mut buffer_header)?;
+    buffer[..AUTH_BUFF_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    EchoEvent::Write(WriteEvent {
+        buffer: *ctx.authorized_buffer.key,
+        authority: *ctx.authority.key,
+        len: total_len as u64,
+        slot: Clock::get()?.slot,
+        sequence: buffer_header.sequence,
+    })
+    .emit();
+
+    Ok(())
+}
+
+/// Checks that `authorized_buffer` is an initialized authorized buffer owned by the Echo Program whose
+/// address is the canonical PDA of `authority` and the buffer seed stored in its header.
+pub(crate) fn validate_authorized_buffer(
+    program_id: &Pubkey,
+    authorized_buffer: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<AuthorizedBufferHeader, ProgramError> {
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if authorized_buffer.owner != program_id {
+        msg!("Authorized buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = authorized_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < AUTH_BUFF_HEADER_SIZE {
+        msg!("Invalid authorized buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    // in order to validate the PDA address, we first read it to access the buffer seed
+    let buffer_header = AuthorizedBufferHeader::try_from_slice(&buffer[..AUTH_BUFF_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address, re-deriving the canonical bump
+    // instead of trusting the one stored in the header
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"authority",
+            authority.as_ref(),
+            &buffer_header.buffer_seed.to_le_bytes(),
+        ],
+        program_id,
+    );
+
+    if pda != *authorized_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
 // test cases:
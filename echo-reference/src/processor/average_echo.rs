@@ -0,0 +1,138 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{AverageHeader, AVERAGE_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    average_account: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            average_account: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        Ok(ctx)
+    }
+
+    /// Additional check for instructions that modify the average account.
+    fn check_writable(&self) -> ProgramResult {
+        if !self.average_account.is_writable {
+            msg!("Average account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the header of `average_account` after checking that it is the average account of `authority`.
+fn read_average_header(
+    program_id: &Pubkey,
+    average_account: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<AverageHeader, ProgramError> {
+    if average_account.owner != program_id {
+        msg!("Average account must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let data = average_account.data.borrow();
+
+    if data.len() != AVERAGE_HEADER_SIZE {
+        msg!("Invalid average account size, {}", data.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let header = AverageHeader::try_from_slice(&data)?;
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(&[b"average", authority.as_ref()], program_id);
+
+    if pda != *average_account.key {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(header)
+}
+
+fn write_average_header(average_account: &AccountInfo, header: &AverageHeader) {
+    average_account.data.borrow_mut()[..AVERAGE_HEADER_SIZE]
+        .copy_from_slice(&header.try_to_vec().unwrap());
+    msg!("Sum: {}", header.sum);
+    msg!("Count: {}", header.count);
+}
+
+/// Anyone can submit a sample without signing, the authority is passed along only to validate the
+/// average account address.
+pub fn process_submit(program_id: &Pubkey, accounts: &[AccountInfo], value: u64) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+    ctx.check_writable()?;
+
+    let mut header = read_average_header(program_id, ctx.average_account, ctx.authority.key)?;
+
+    if header.count >= header.max_samples as u64 {
+        msg!("Average already holds {} samples", header.max_samples);
+        return Err(EchoError::MaxWritesExceeded.into());
+    }
+
+    header.sum = header
+        .sum
+        .checked_add(value as u128)
+        .ok_or(EchoError::Overflow)?;
+    header.count = header.count.checked_add(1).ok_or(EchoError::Overflow)?;
+
+    write_average_header(ctx.average_account, &header);
+
+    Ok(())
+}
+
+/// Logs the mean of the submitted samples, rounded down, or zero if there are none.
+pub fn process_read(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let header = read_average_header(program_id, ctx.average_account, ctx.authority.key)?;
+
+    let average = match header.count {
+        0 => 0,
+        count => header.sum / count as u128,
+    };
+
+    msg!("Average: {}", average);
+
+    Ok(())
+}
+
+pub fn process_reset(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+    ctx.check_writable()?;
+
+    if !ctx.authority.is_signer {
+        msg!("Authority account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let mut header = read_average_header(program_id, ctx.average_account, ctx.authority.key)?;
+    header.sum = 0;
+    header.count = 0;
+
+    write_average_header(ctx.average_account, &header);
+
+    Ok(())
+}
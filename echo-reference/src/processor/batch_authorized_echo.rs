@@ -0,0 +1,113 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use std::mem::size_of;
+
+use crate::{
+    error::EchoError,
+    processor::authorized_echo::{overwrite_authorized_buffer, validate_authorized_buffer},
+};
+
+/// Maximum number of buffers that can be written by a single `BatchAuthorizedEcho`
+pub const MAX_BATCH_WRITES: usize = 10;
+
+struct Context<'a, 'b: 'a> {
+    authority: &'a AccountInfo<'b>,
+    authorized_buffers: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            authority: next_account_info(accounts_iter)?,
+            authorized_buffers: &accounts[1..],
+        };
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        for authorized_buffer in ctx.authorized_buffers {
+            if !authorized_buffer.is_writable {
+                msg!(
+                    "Authorized buffer {} must be writable",
+                    authorized_buffer.key
+                );
+                return Err(EchoError::AccountMustBeWritable.into());
+            }
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    writes: Vec<Vec<u8>>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if writes.len() != ctx.authorized_buffers.len() {
+        msg!(
+            "Got {} writes for {} authorized buffers",
+            writes.len(),
+            ctx.authorized_buffers.len()
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    if writes.is_empty() || writes.len() > MAX_BATCH_WRITES {
+        msg!(
+            "Invalid batch size {}, must be between 1 and {}",
+            writes.len(),
+            MAX_BATCH_WRITES
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // validate every buffer before writing any of them
+    let mut buffer_headers = Vec::with_capacity(writes.len());
+    for authorized_buffer in ctx.authorized_buffers {
+        let buffer_header =
+            validate_authorized_buffer(program_id, authorized_buffer, ctx.authority.key)?;
+
+        // every account is a buffer, there is no room for the fee accounts
+        if buffer_header.write_fee_lamports > 0 {
+            msg!(
+                "Authorized buffer {} charges a write fee, use AuthorizedEcho",
+                authorized_buffer.key
+            );
+            return Err(EchoError::InvalidInstructionInput.into());
+        }
+
+        buffer_headers.push(buffer_header);
+    }
+
+    // each write sets its own sequence as return data, replace it with the sequences of the whole batch
+    let mut sequences = Vec::with_capacity(writes.len() * size_of::<u64>());
+    for ((authorized_buffer, buffer_header), data) in ctx
+        .authorized_buffers
+        .iter()
+        .zip(buffer_headers)
+        .zip(writes.iter())
+    {
+        let sequence =
+            overwrite_authorized_buffer(authorized_buffer, ctx.authority.key, buffer_header, data)?;
+        sequences.extend_from_slice(&sequence.to_le_bytes());
+    }
+    set_return_data(&sequences);
+
+    msg!("Wrote {} authorized buffers", writes.len());
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+};
+
+use crate::{
+    error::EchoError,
+    processor::initialize_authorized_echo::create_authorized_buffer,
+    state::{AuthorizedBufferHeader, BatchInitConfig},
+};
+
+/// Maximum number of buffers that can be created by a single `BatchInitialize`, each one costs a `create_account` CPI
+pub const MAX_BATCH_INITS: usize = 5;
+
+struct Context<'a, 'b: 'a> {
+    authority: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    authorized_buffers: &'a [AccountInfo<'b>],
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            authority: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+            authorized_buffers: accounts_iter.as_slice(),
+        };
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        for authorized_buffer in ctx.authorized_buffers {
+            if !authorized_buffer.is_writable {
+                msg!(
+                    "Authorized buffer {} must be writable",
+                    authorized_buffer.key
+                );
+                return Err(EchoError::AccountMustBeWritable.into());
+            }
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    configs: Vec<BatchInitConfig>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if configs.len() != ctx.authorized_buffers.len() {
+        msg!(
+            "Got {} configs for {} authorized buffers",
+            configs.len(),
+            ctx.authorized_buffers.len()
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    if configs.is_empty() || configs.len() > MAX_BATCH_INITS {
+        msg!(
+            "Invalid batch size {}, must be between 1 and {}",
+            configs.len(),
+            MAX_BATCH_INITS
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // a buffer that already exists fails its `create_account`, which aborts the whole batch
+    for (authorized_buffer, config) in ctx.authorized_buffers.iter().zip(configs.iter()) {
+        create_authorized_buffer(
+            program_id,
+            authorized_buffer,
+            ctx.authority.key,
+            ctx.authority,
+            ctx.system_program,
+            config.buffer_size,
+            AuthorizedBufferHeader {
+                buffer_seed: config.buffer_seed,
+                ..AuthorizedBufferHeader::default()
+            },
+        )?;
+    }
+
+    msg!("Initialized {} authorized buffers", configs.len());
+
+    Ok(())
+}
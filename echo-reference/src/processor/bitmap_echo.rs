@@ -0,0 +1,144 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    state::{bitmap_len, BitmapHeader, BITMAP_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    bitmap_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            bitmap_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        Ok(ctx)
+    }
+
+    /// Additional checks for instructions that modify the bitmap.
+    fn check_mutable(&self) -> ProgramResult {
+        if !self.bitmap_buffer.is_writable {
+            msg!("Bitmap buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !self.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the header of `bitmap_buffer` after checking that it is the bitmap buffer of `authority`.
+fn validate_bitmap_buffer(
+    program_id: &Pubkey,
+    bitmap_buffer: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<BitmapHeader, ProgramError> {
+    if bitmap_buffer.owner != program_id {
+        msg!("Bitmap buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = bitmap_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < BITMAP_HEADER_SIZE {
+        msg!("Invalid bitmap buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = BitmapHeader::try_from_slice(&buffer[..BITMAP_HEADER_SIZE])?;
+
+    if buffer.len() != BITMAP_HEADER_SIZE + bitmap_len(buffer_header.bit_count) {
+        msg!("Invalid bitmap buffer size, {}", buffer.len());
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(&[b"bitmap", authority.as_ref()], program_id);
+
+    if pda != *bitmap_buffer.key {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Byte offset within the account data and mask of bit `index`, fails if `index` is past the declared bits so the
+/// padding bits of the last byte are never touched.
+fn locate_bit(buffer_header: &BitmapHeader, index: u32) -> Result<(usize, u8), ProgramError> {
+    if index >= buffer_header.bit_count {
+        msg!(
+            "Invalid bit index {}, bitmap has {} bits",
+            index,
+            buffer_header.bit_count
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    let byte_index = BITMAP_HEADER_SIZE + (index / 8) as usize;
+    let bit_mask = 1 << (index % 8);
+
+    Ok((byte_index, bit_mask))
+}
+
+pub fn process_set(program_id: &Pubkey, accounts: &[AccountInfo], index: u32) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+    ctx.check_mutable()?;
+
+    let buffer_header = validate_bitmap_buffer(program_id, ctx.bitmap_buffer, ctx.authority.key)?;
+    let (byte_index, bit_mask) = locate_bit(&buffer_header, index)?;
+
+    ctx.bitmap_buffer.data.borrow_mut()[byte_index] |= bit_mask;
+
+    msg!("bit {} = 1", index);
+
+    Ok(())
+}
+
+pub fn process_clear(program_id: &Pubkey, accounts: &[AccountInfo], index: u32) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+    ctx.check_mutable()?;
+
+    let buffer_header = validate_bitmap_buffer(program_id, ctx.bitmap_buffer, ctx.authority.key)?;
+    let (byte_index, bit_mask) = locate_bit(&buffer_header, index)?;
+
+    ctx.bitmap_buffer.data.borrow_mut()[byte_index] &= !bit_mask;
+
+    msg!("bit {} = 0", index);
+
+    Ok(())
+}
+
+/// Logs the value of bit `index`, the authority is passed along only to validate the bitmap buffer address.
+pub fn process_query(program_id: &Pubkey, accounts: &[AccountInfo], index: u32) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let buffer_header = validate_bitmap_buffer(program_id, ctx.bitmap_buffer, ctx.authority.key)?;
+    let (byte_index, bit_mask) = locate_bit(&buffer_header, index)?;
+
+    let bit = ctx.bitmap_buffer.data.borrow()[byte_index] & bit_mask != 0;
+
+    msg!("bit {} = {}", index, bit as u8);
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{error::EchoError, processor::vending_machine_echo::read_vending_machine_header};
+
+/// Closes an expired vending machine, callable by anyone once the current slot reaches its `expiry_slot`. The data is
+/// zeroed and every lamport of the buffer goes to `destination`.
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    destination: Pubkey,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vending_machine_buffer = next_account_info(accounts_iter)?;
+    let destination_account = next_account_info(accounts_iter)?;
+
+    if !vending_machine_buffer.is_writable || !destination_account.is_writable {
+        msg!("Vending machine buffer and destination accounts must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if *destination_account.key != destination {
+        msg!("The destination {} must be passed", destination);
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let buffer_header = read_vending_machine_header(program_id, vending_machine_buffer)?;
+
+    // only paid machines can expire, their address is derived from the mint and price of the header
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"vending_machine",
+            buffer_header.mint.as_ref(),
+            &buffer_header.price.to_le_bytes(),
+        ],
+        program_id,
+    );
+
+    if buffer_header.free
+        || pda != *vending_machine_buffer.key
+        || bump_seed != buffer_header.bump_seed
+    {
+        msg!("Invalid account address or mint");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let slot = Clock::get()?.slot;
+
+    if buffer_header.expiry_slot == 0 || slot < buffer_header.expiry_slot {
+        msg!(
+            "The vending machine expires at slot {}, current slot {}",
+            buffer_header.expiry_slot,
+            slot
+        );
+        return Err(EchoError::BufferNotExpired.into());
+    }
+
+    let lamports = vending_machine_buffer.lamports();
+    **destination_account.lamports.borrow_mut() = destination_account
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(EchoError::Overflow)?;
+    **vending_machine_buffer.lamports.borrow_mut() = 0;
+
+    // the runtime deletes the account once it has no lamports left, clear it in case it is funded again
+    vending_machine_buffer.data.borrow_mut().fill(0);
+
+    msg!(
+        "Claimed expired vending machine, {} lamports to {}",
+        lamports,
+        destination
+    );
+
+    Ok(())
+}
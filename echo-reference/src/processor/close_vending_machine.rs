@@ -0,0 +1,114 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::EchoError,
+    processor::vending_machine_echo::{
+        assert_admin, assert_vending_machine_address, read_vending_machine_header,
+        unpack_token_account,
+    },
+    state::PAYMENT_MODE_TREASURY,
+};
+
+struct Context<'a, 'b: 'a> {
+    vending_machine_buffer: &'a AccountInfo<'b>,
+    admin: &'a AccountInfo<'b>,
+    recipient: &'a AccountInfo<'b>,
+    vending_machine_mint: Option<&'a AccountInfo<'b>>,
+    treasury: Option<&'a AccountInfo<'b>>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            vending_machine_buffer: next_account_info(accounts_iter)?,
+            admin: next_account_info(accounts_iter)?,
+            recipient: next_account_info(accounts_iter)?,
+            vending_machine_mint: accounts_iter.next(),
+            treasury: accounts_iter.next(),
+        };
+
+        if !ctx.vending_machine_buffer.is_writable || !ctx.recipient.is_writable {
+            msg!("Vending machine buffer and recipient accounts must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.admin.is_signer {
+            msg!("Admin account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Closes a vending machine buffer, paid or permissionless, sending its lamports to `recipient`. Only the admin
+/// stored in the header can close it, and the treasury of a machine in treasury mode must have been emptied first.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let buffer_header = read_vending_machine_header(program_id, ctx.vending_machine_buffer)?;
+
+    assert_admin(&buffer_header, ctx.admin)?;
+
+    // the seeds come from the header, the mint passed by the caller is only checked against it
+    assert_vending_machine_address(program_id, ctx.vending_machine_buffer, &buffer_header)?;
+
+    if !buffer_header.free {
+        let vending_machine_mint = ctx.vending_machine_mint.ok_or_else(|| {
+            msg!("The mint must be passed to close a paid vending machine");
+            ProgramError::NotEnoughAccountKeys
+        })?;
+
+        if *vending_machine_mint.key != buffer_header.mint {
+            msg!(
+                "Invalid mint {}, expected {}",
+                vending_machine_mint.key,
+                buffer_header.mint
+            );
+            return Err(EchoError::MintMismatch.into());
+        }
+    }
+
+    if !buffer_header.free && buffer_header.payment_mode == PAYMENT_MODE_TREASURY {
+        let treasury = ctx
+            .treasury
+            .filter(|treasury| *treasury.key == buffer_header.treasury)
+            .ok_or_else(|| {
+                msg!("The treasury {} must be passed", buffer_header.treasury);
+                EchoError::InvalidAccountAddress
+            })?;
+
+        let treasury_account = unpack_token_account(treasury, &buffer_header.token_program)?;
+
+        if treasury_account.amount > 0 {
+            msg!(
+                "The treasury still holds {} tokens",
+                treasury_account.amount
+            );
+            return Err(EchoError::VaultNotEmpty.into());
+        }
+    }
+
+    let lamports = ctx.vending_machine_buffer.lamports();
+    **ctx.recipient.lamports.borrow_mut() = ctx
+        .recipient
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(EchoError::Overflow)?;
+    **ctx.vending_machine_buffer.lamports.borrow_mut() = 0;
+
+    // the runtime deletes the account once it has no lamports left, clear it in case it is funded again
+    ctx.vending_machine_buffer.data.borrow_mut().fill(0);
+
+    msg!("Closed vending machine, reclaimed {} lamports", lamports);
+
+    Ok(())
+}
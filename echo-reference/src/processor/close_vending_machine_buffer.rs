@@ -0,0 +1,120 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use spl_token::state::Mint;
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    state::{VendingMachineBufferHeader, VENDING_MACHINE_BUFF_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    vending_machine_buffer: &'a AccountInfo<'b>,
+    vending_machine_mint: &'a AccountInfo<'b>,
+    mint_authority: &'a AccountInfo<'b>,
+    destination: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            vending_machine_buffer: next_account_info(accounts_iter)?,
+            vending_machine_mint: next_account_info(accounts_iter)?,
+            mint_authority: next_account_info(accounts_iter)?,
+            destination: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.vending_machine_buffer.is_writable || !ctx.destination.is_writable {
+            msg!("Vending machine buffer and destination accounts must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.mint_authority.is_signer {
+            msg!("Mint authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Closes a vending machine buffer, sending its lamports to `destination`. The buffer stores no authority, so the
+/// mint authority of its mint stands in for one.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if *ctx.vending_machine_mint.owner != spl_token::id() {
+        msg!("Vending machine mint must be owned by the Token Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let mint = Mint::unpack(&ctx.vending_machine_mint.data.borrow()).inspect_err(|_| {
+        msg!("Invalid mint account");
+    })?;
+
+    if mint.mint_authority != COption::Some(*ctx.mint_authority.key) {
+        msg!("Only the mint authority can close the vending machine buffer");
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if ctx.vending_machine_buffer.owner != program_id {
+        msg!("Vending machine buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer_header = {
+        let buffer = ctx.vending_machine_buffer.data.borrow();
+
+        // check the size of the account before trying to read it
+        if buffer.len() < VENDING_MACHINE_BUFF_HEADER_SIZE {
+            msg!("Invalid vending machine buffer size, {}", buffer.len());
+            return Err(EchoError::AccountNotInitialized.into());
+        }
+
+        VendingMachineBufferHeader::try_from_slice(&buffer[..VENDING_MACHINE_BUFF_HEADER_SIZE])?
+    };
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"vending_machine",
+            ctx.vending_machine_mint.key.as_ref(),
+            &buffer_header.price.to_le_bytes(),
+        ],
+        program_id,
+    );
+
+    if pda != *ctx.vending_machine_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or mint");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let lamports = ctx.vending_machine_buffer.lamports();
+    **ctx.destination.lamports.borrow_mut() = ctx
+        .destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(EchoError::Overflow)?;
+    **ctx.vending_machine_buffer.lamports.borrow_mut() = 0;
+
+    // the runtime deletes the account once it has no lamports left, clear it in case it is funded again
+    ctx.vending_machine_buffer.data.borrow_mut().fill(0);
+
+    msg!(
+        "Closed vending machine buffer, reclaimed {} lamports",
+        lamports
+    );
+
+    Ok(())
+}
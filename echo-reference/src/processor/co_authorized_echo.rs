@@ -0,0 +1,127 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    state::{CoAuthorizedBufferHeader, CO_AUTH_BUFF_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    co_authorized_buffer: &'a AccountInfo<'b>,
+    first_authority: &'a AccountInfo<'b>,
+    second_authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            co_authorized_buffer: next_account_info(accounts_iter)?,
+            first_authority: next_account_info(accounts_iter)?,
+            second_authority: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.co_authorized_buffer.is_writable {
+            msg!("Co-authorized buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.first_authority.is_signer || !ctx.second_authority.is_signer {
+            msg!("Both authority accounts must be signers");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// The two authorities in ascending order, as used for the PDA seeds and stored in the header.
+pub(crate) fn sort_authorities(first: &Pubkey, second: &Pubkey) -> (Pubkey, Pubkey) {
+    match first <= second {
+        true => (*first, *second),
+        false => (*second, *first),
+    }
+}
+
+/// Overwrites the payload of the buffer with `data`, zero-padding the rest, once both authorities signed.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    validate_co_authorized_buffer(
+        program_id,
+        ctx.co_authorized_buffer,
+        ctx.first_authority.key,
+        ctx.second_authority.key,
+    )?;
+
+    let buffer = &mut (*ctx.co_authorized_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[CO_AUTH_BUFF_HEADER_SIZE..];
+    let bytes_written = buffer_data.len().min(data.len());
+
+    buffer_data[..bytes_written].copy_from_slice(&data[..bytes_written]);
+    buffer_data[bytes_written..].fill(0);
+
+    msg!("Wrote {} bytes", bytes_written);
+
+    Ok(())
+}
+
+/// Checks that `co_authorized_buffer` is an initialized co-authorized buffer owned by the Echo Program whose
+/// authorities are `first_authority` and `second_authority`, in any order.
+fn validate_co_authorized_buffer(
+    program_id: &Pubkey,
+    co_authorized_buffer: &AccountInfo,
+    first_authority: &Pubkey,
+    second_authority: &Pubkey,
+) -> Result<CoAuthorizedBufferHeader, ProgramError> {
+    if co_authorized_buffer.owner != program_id {
+        msg!("Co-authorized buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = co_authorized_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < CO_AUTH_BUFF_HEADER_SIZE {
+        msg!("Invalid co-authorized buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header =
+        CoAuthorizedBufferHeader::try_from_slice(&buffer[..CO_AUTH_BUFF_HEADER_SIZE])?;
+
+    // the same key passed twice would otherwise only need a single signature
+    let (authority_a, authority_b) = sort_authorities(first_authority, second_authority);
+    if authority_a != buffer_header.authority_a || authority_b != buffer_header.authority_b {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(
+        &[
+            b"co_authority",
+            authority_a.as_ref(),
+            authority_b.as_ref(),
+            &buffer_header.buffer_seed.to_le_bytes(),
+        ],
+        program_id,
+    );
+
+    if pda != *co_authorized_buffer.key {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
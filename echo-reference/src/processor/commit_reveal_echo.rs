@@ -0,0 +1,137 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    keccak, msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use borsh::BorshSerialize;
+
+use crate::{
+    error::EchoError,
+    processor::authorized_echo::{
+        charge_write_fee, overwrite_authorized_buffer, validate_authorized_buffer,
+    },
+    state::AUTH_BUFF_HEADER_SIZE,
+};
+
+/// Minimum number of slots between `CommitAuthorizedEcho` and `RevealAuthorizedEcho`, so a commitment can't be
+/// revealed in the same slot it was made in
+pub const MIN_REVEAL_DELAY_SLOTS: u64 = 1;
+
+struct Context<'a, 'b: 'a> {
+    authorized_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            authorized_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.authorized_buffer.is_writable {
+            msg!("Authorized Echo Buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process_commit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    hash: [u8; 32],
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let mut buffer_header =
+        validate_authorized_buffer(program_id, ctx.authorized_buffer, ctx.authority.key)?;
+
+    if buffer_header.has_commitment {
+        msg!(
+            "Replacing unrevealed commitment from slot {}",
+            buffer_header.commit_slot
+        );
+    }
+
+    buffer_header.commitment = hash;
+    buffer_header.commit_slot = Clock::get()?.slot;
+    buffer_header.has_commitment = true;
+
+    ctx.authorized_buffer.data.borrow_mut()[..AUTH_BUFF_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Commit slot: {}", buffer_header.commit_slot);
+
+    Ok(())
+}
+
+pub fn process_reveal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let mut buffer_header =
+        validate_authorized_buffer(program_id, ctx.authorized_buffer, ctx.authority.key)?;
+
+    if !buffer_header.has_commitment {
+        msg!("No pending commitment to reveal");
+        return Err(EchoError::CommitmentMismatch.into());
+    }
+
+    let slot = Clock::get()?.slot;
+    if slot
+        < buffer_header
+            .commit_slot
+            .saturating_add(MIN_REVEAL_DELAY_SLOTS)
+    {
+        msg!(
+            "Commitment from slot {} can't be revealed before slot {}",
+            buffer_header.commit_slot,
+            buffer_header
+                .commit_slot
+                .saturating_add(MIN_REVEAL_DELAY_SLOTS)
+        );
+        return Err(EchoError::RevealTooEarly.into());
+    }
+
+    if keccak::hash(&data).to_bytes() != buffer_header.commitment {
+        msg!("Revealed data doesn't match the commitment");
+        return Err(EchoError::CommitmentMismatch.into());
+    }
+
+    charge_write_fee(
+        program_id,
+        ctx.authority,
+        &buffer_header,
+        accounts.get(2..).unwrap_or_default(),
+    )?;
+
+    // the commitment is used up, the next commit starts a new round
+    buffer_header.commitment = [0; 32];
+    buffer_header.has_commitment = false;
+
+    overwrite_authorized_buffer(
+        ctx.authorized_buffer,
+        ctx.authority.key,
+        buffer_header,
+        &data,
+    )?;
+
+    Ok(())
+}
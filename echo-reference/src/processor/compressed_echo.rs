@@ -0,0 +1,105 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    hash::{hashv, Hash},
+    msg,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{CompressedEchoHeader, COMPRESSED_ECHO_HEADER_SIZE, MAX_MERKLE_PROOF_LEN},
+};
+
+/// Root of the SHA-256 Merkle tree in which `leaf` is at `index` with the sibling hashes of `proof`, from the leaf up.
+/// A node on the left of its sibling is hashed first. `None` if `index` doesn't fit in a tree of that depth.
+pub fn merkle_root(leaf: [u8; 32], proof: &[[u8; 32]], index: u32) -> Option<[u8; 32]> {
+    if proof.len() > MAX_MERKLE_PROOF_LEN || (proof.len() < 32 && index >> proof.len() != 0) {
+        return None;
+    }
+
+    let root = proof
+        .iter()
+        .enumerate()
+        .fold(leaf, |node, (depth, sibling)| match (index >> depth) & 1 {
+            0 => hashv(&[&node, sibling]).to_bytes(),
+            _ => hashv(&[sibling, &node]).to_bytes(),
+        });
+
+    Some(root)
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_leaf: [u8; 32],
+    proof: Vec<[u8; 32]>,
+    proof_index: u32,
+    root: [u8; 32],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let compressed_buffer = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+
+    if !compressed_buffer.is_writable {
+        msg!("Compressed buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !authority.is_signer {
+        msg!("Authority account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if compressed_buffer.owner != program_id {
+        msg!("Compressed buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = &mut (*compressed_buffer.data).borrow_mut();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < COMPRESSED_ECHO_HEADER_SIZE {
+        msg!("Invalid compressed buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let mut buffer_header =
+        CompressedEchoHeader::try_from_slice(&buffer[..COMPRESSED_ECHO_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address, which also checks the authority
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"compressed",
+            buffer_header.tree_id.as_ref(),
+            authority.key.as_ref(),
+        ],
+        program_id,
+    );
+
+    if pda != *compressed_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    if merkle_root(new_leaf, &proof, proof_index) != Some(root) {
+        msg!(
+            "The proof doesn't lead from the leaf {} to the root",
+            proof_index
+        );
+        return Err(EchoError::InvalidMerkleProof.into());
+    }
+
+    buffer_header.leaf = new_leaf;
+    buffer_header.root = root;
+    buffer_header.leaf_index = proof_index;
+
+    buffer[..COMPRESSED_ECHO_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Leaf {}: {}", proof_index, Hash::new_from_array(new_leaf));
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    state::{ConditionalBufferHeader, CONDITIONAL_BUFF_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    conditional_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    condition_account: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            conditional_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+            condition_account: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.conditional_buffer.is_writable {
+            msg!("Conditional buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if ctx.conditional_buffer.owner != program_id {
+        msg!("Conditional buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = &mut (*ctx.conditional_buffer.data).borrow_mut();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < CONDITIONAL_BUFF_HEADER_SIZE {
+        msg!("Invalid conditional buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header =
+        ConditionalBufferHeader::try_from_slice(&buffer[..CONDITIONAL_BUFF_HEADER_SIZE])?;
+
+    if buffer_header.authority != *ctx.authority.key {
+        msg!("Invalid authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    if buffer_header.condition_account != *ctx.condition_account.key {
+        msg!("Invalid condition account");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(
+        &[
+            b"conditional",
+            buffer_header.authority.as_ref(),
+            buffer_header.condition_account.as_ref(),
+        ],
+        program_id,
+    );
+
+    if pda != *ctx.conditional_buffer.key {
+        msg!("Invalid conditional buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // compare the watched bytes of the condition account against the stored value
+    let condition_value = buffer_header.condition_value();
+    let start = buffer_header.condition_offset as usize;
+    let end = start + condition_value.len();
+    let condition_data = ctx.condition_account.data.borrow();
+
+    if condition_data.len() < end || &condition_data[start..end] != condition_value {
+        msg!("Condition account does not hold the expected value");
+        return Err(EchoError::ConditionNotMet.into());
+    }
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[CONDITIONAL_BUFF_HEADER_SIZE..];
+
+    // loop over each byte in the rest of account's data
+    for index in 0..buffer_data.len() {
+        buffer_data[index] = match index < data.len() {
+            true => data[index],
+            false => 0,
+        };
+    }
+
+    Ok(())
+}
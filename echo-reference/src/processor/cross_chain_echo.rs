@@ -0,0 +1,166 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{CrossChainHeader, CROSS_CHAIN_HEADER_SIZE},
+};
+
+/// The Wormhole core bridge, only the fields of its posted VAA accounts the Echo Program filters on are read
+pub mod wormhole {
+    solana_program::declare_id!("worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth");
+
+    /// First bytes of a posted VAA account
+    pub const POSTED_VAA_MAGIC: &[u8] = b"vaa";
+    /// The magic is followed by the version, consistency level, VAA time, signature set, submission time and nonce
+    pub const SEQUENCE_OFFSET: usize = 49;
+    pub const EMITTER_CHAIN_OFFSET: usize = SEQUENCE_OFFSET + 8;
+    pub const EMITTER_ADDRESS_OFFSET: usize = EMITTER_CHAIN_OFFSET + 2;
+    /// Length prefix of the payload, written by Borsh as an `u32`
+    pub const PAYLOAD_OFFSET: usize = EMITTER_ADDRESS_OFFSET + 32;
+
+    /// The fields of a posted VAA the Echo Program reads
+    pub struct PostedVaa<'a> {
+        pub sequence: u64,
+        pub emitter_chain: u16,
+        pub emitter_address: [u8; 32],
+        pub payload: &'a [u8],
+    }
+
+    impl<'a> PostedVaa<'a> {
+        /// Reads the posted VAA at the known offsets, `None` if the data is too short or doesn't start with the magic.
+        pub fn unpack(data: &'a [u8]) -> Option<Self> {
+            if !data.starts_with(POSTED_VAA_MAGIC) || data.len() < PAYLOAD_OFFSET + 4 {
+                return None;
+            }
+
+            let mut sequence = [0; 8];
+            sequence.copy_from_slice(&data[SEQUENCE_OFFSET..EMITTER_CHAIN_OFFSET]);
+            let mut emitter_chain = [0; 2];
+            emitter_chain.copy_from_slice(&data[EMITTER_CHAIN_OFFSET..EMITTER_ADDRESS_OFFSET]);
+            let mut emitter_address = [0; 32];
+            emitter_address.copy_from_slice(&data[EMITTER_ADDRESS_OFFSET..PAYLOAD_OFFSET]);
+            let mut payload_len = [0; 4];
+            payload_len.copy_from_slice(&data[PAYLOAD_OFFSET..PAYLOAD_OFFSET + 4]);
+
+            let payload = data
+                .get(PAYLOAD_OFFSET + 4..)?
+                .get(..u32::from_le_bytes(payload_len) as usize)?;
+
+            Some(Self {
+                sequence: u64::from_le_bytes(sequence),
+                emitter_chain: u16::from_le_bytes(emitter_chain),
+                emitter_address,
+                payload,
+            })
+        }
+    }
+}
+
+/// Copies the payload of a VAA posted by the Wormhole bridge past the header, zeroing the rest of it, when the message
+/// comes from the emitter of the buffer. `data` must be the payload, so the caller knows what is written.
+///
+/// Anyone can submit a VAA, so only messages with a sequence past the last one written are accepted, older ones would
+/// roll the buffer back to a stale payload.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let cross_chain_buffer = next_account_info(accounts_iter)?;
+    let posted_vaa = next_account_info(accounts_iter)?;
+
+    if !cross_chain_buffer.is_writable {
+        msg!("Cross chain buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if cross_chain_buffer.owner != program_id {
+        msg!("Cross chain buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let mut buffer_header = {
+        let buffer = cross_chain_buffer.data.borrow();
+
+        // check the size of the account before trying to read it
+        if buffer.len() <= CROSS_CHAIN_HEADER_SIZE {
+            msg!("Invalid cross chain buffer size, {}", buffer.len());
+            return Err(EchoError::AccountNotInitialized.into());
+        }
+
+        CrossChainHeader::try_from_slice(&buffer[..CROSS_CHAIN_HEADER_SIZE])?
+    };
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"cross_chain",
+            &buffer_header.emitter_chain.to_le_bytes(),
+            buffer_header.emitter_address.as_ref(),
+        ],
+        program_id,
+    );
+
+    if pda != *cross_chain_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid cross chain buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // the bridge only posts VAAs whose guardian signatures it verified, any other owner could forge one
+    if *posted_vaa.owner != wormhole::id() {
+        msg!("Posted VAA must be owned by the Wormhole bridge");
+        return Err(EchoError::InvalidVAA.into());
+    }
+
+    let vaa_data = posted_vaa.data.borrow();
+    let vaa = wormhole::PostedVaa::unpack(&vaa_data).ok_or_else(|| {
+        msg!("Invalid posted VAA");
+        EchoError::InvalidVAA
+    })?;
+
+    if vaa.emitter_chain != buffer_header.emitter_chain
+        || vaa.emitter_address != buffer_header.emitter_address
+    {
+        msg!(
+            "VAA emitted by chain {} {:?}",
+            vaa.emitter_chain,
+            vaa.emitter_address
+        );
+        return Err(EchoError::InvalidVAA.into());
+    }
+
+    if vaa.payload != data.as_slice() {
+        msg!("Data is not the payload of the VAA");
+        return Err(EchoError::InvalidVAA.into());
+    }
+
+    if buffer_header.has_sequence && vaa.sequence <= buffer_header.last_sequence {
+        msg!(
+            "VAA sequence {} is not after the last sequence {}",
+            vaa.sequence,
+            buffer_header.last_sequence
+        );
+        return Err(EchoError::InvalidVAA.into());
+    }
+
+    let buffer = &mut (*cross_chain_buffer.data).borrow_mut();
+
+    buffer_header.has_sequence = true;
+    buffer_header.last_sequence = vaa.sequence;
+    buffer[..CROSS_CHAIN_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[CROSS_CHAIN_HEADER_SIZE..];
+    let len = buffer_data.len().min(vaa.payload.len());
+    buffer_data[..len].copy_from_slice(&vaa.payload[..len]);
+    buffer_data[len..].fill(0);
+
+    msg!("Wrote {} bytes", len);
+
+    Ok(())
+}
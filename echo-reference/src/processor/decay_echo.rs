@@ -0,0 +1,155 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{DecayHeader, DECAY_HEADER_SIZE},
+};
+
+/// Fixed point scale of the decay factor, 1.0 is `DECAY_SCALE`
+const DECAY_SCALE: u128 = 1_000_000_000;
+
+struct Context<'a, 'b: 'a> {
+    decay_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            decay_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.decay_buffer.is_writable {
+            msg!("Decay buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Reads the header of `decay_buffer` after checking that it is the decay buffer of `authority`.
+fn read_decay_header(
+    program_id: &Pubkey,
+    decay_buffer: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<DecayHeader, ProgramError> {
+    if decay_buffer.owner != program_id {
+        msg!("Decay buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = decay_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() <= DECAY_HEADER_SIZE {
+        msg!("Invalid decay buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = DecayHeader::try_from_slice(&buffer[..DECAY_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(&[b"decay", authority.as_ref()], program_id);
+
+    if pda != *decay_buffer.key {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// `(1 - decay_rate / 1000) ^ elapsed_slots` scaled by `DECAY_SCALE`, using exponentiation by squaring.
+fn decay_factor(decay_rate: u8, elapsed_slots: u64) -> u128 {
+    let mut base = DECAY_SCALE * (1000 - decay_rate as u128) / 1000;
+    let mut exponent = elapsed_slots;
+    let mut factor = DECAY_SCALE;
+
+    // stop as soon as the factor rounds down to zero, there is nothing left to decay
+    while exponent > 0 && factor > 0 {
+        if exponent & 1 == 1 {
+            factor = factor * base / DECAY_SCALE;
+        }
+        base = base * base / DECAY_SCALE;
+        exponent >>= 1;
+    }
+
+    factor
+}
+
+/// Applies the decay of the slots elapsed since `last_decay_slot` to every byte of the buffer.
+fn apply_decay(decay_buffer: &AccountInfo, mut buffer_header: DecayHeader) -> ProgramResult {
+    let slot = Clock::get()?.slot;
+    let elapsed_slots = slot.saturating_sub(buffer_header.last_decay_slot);
+    let factor = decay_factor(buffer_header.decay_rate, elapsed_slots);
+
+    let buffer = &mut (*decay_buffer.data).borrow_mut();
+
+    // a factor of exactly 1.0 leaves every byte as is
+    if factor != DECAY_SCALE {
+        for byte in buffer[DECAY_HEADER_SIZE..].iter_mut() {
+            *byte = (*byte as u128 * factor / DECAY_SCALE) as u8;
+        }
+    }
+
+    buffer_header.last_decay_slot = slot;
+    buffer[..DECAY_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Decayed over {} slots", elapsed_slots);
+
+    Ok(())
+}
+
+/// Anyone can refresh the decay without signing, the authority is passed along only to validate the decay
+/// buffer address.
+pub fn process_refresh(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let buffer_header = read_decay_header(program_id, ctx.decay_buffer, ctx.authority.key)?;
+
+    apply_decay(ctx.decay_buffer, buffer_header)
+}
+
+/// Refreshes the decay, then overwrites the payload of the buffer with `data`, zero-padding the rest.
+pub fn process_write(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if !ctx.authority.is_signer {
+        msg!("Authority account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let buffer_header = read_decay_header(program_id, ctx.decay_buffer, ctx.authority.key)?;
+    apply_decay(ctx.decay_buffer, buffer_header)?;
+
+    let buffer = &mut (*ctx.decay_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[DECAY_HEADER_SIZE..];
+    let bytes_written = buffer_data.len().min(data.len());
+
+    buffer_data[..bytes_written].copy_from_slice(&data[..bytes_written]);
+    buffer_data[bytes_written..].fill(0);
+
+    msg!("Wrote {} bytes", bytes_written);
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    processor::initialize_treasury::treasury_address,
+    state::{FeeTreasury, FEE_TREASURY_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    treasury: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    destination: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            treasury: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+            destination: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.treasury.is_writable || !ctx.destination.is_writable {
+            msg!("Treasury and destination accounts must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.authority.is_signer {
+            msg!("Treasury authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Checks that `treasury` is the initialized fee treasury of the Echo Program.
+pub(crate) fn validate_treasury(program_id: &Pubkey, treasury: &AccountInfo) -> ProgramResult {
+    if *treasury.key != treasury_address(program_id).0 {
+        msg!("Invalid treasury address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    if treasury.owner != program_id || treasury.data_len() != FEE_TREASURY_SIZE {
+        msg!("The treasury must be initialized with InitializeTreasury");
+        return Err(EchoError::FeeTreasuryNotInitialized.into());
+    }
+
+    Ok(())
+}
+
+/// Moves every lamport above the rent exempt minimum from the fee treasury to `destination`, only the treasury
+/// authority can drain it.
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    destination: Pubkey,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    validate_treasury(program_id, ctx.treasury)?;
+
+    let treasury = FeeTreasury::try_from_slice(&ctx.treasury.data.borrow())?;
+
+    if treasury.authority != *ctx.authority.key {
+        msg!("Only the treasury authority can drain the treasury");
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    if *ctx.destination.key != destination {
+        msg!("The destination {} must be passed", destination);
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let amount = ctx
+        .treasury
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(FEE_TREASURY_SIZE));
+
+    **ctx.treasury.lamports.borrow_mut() -= amount;
+    **ctx.destination.lamports.borrow_mut() = ctx
+        .destination
+        .lamports()
+        .checked_add(amount)
+        .ok_or(EchoError::Overflow)?;
+
+    msg!("Drained {} lamports", amount);
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    hash::Hash,
+    msg,
+    nonce::state::{State, Versions},
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction::advance_nonce_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    state::{DurableNonceHeader, DURABLE_NONCE_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    durable_nonce_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    nonce_account: &'a AccountInfo<'b>,
+    recent_blockhashes: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            durable_nonce_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+            nonce_account: next_account_info(accounts_iter)?,
+            recent_blockhashes: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.durable_nonce_buffer.is_writable {
+            msg!("Durable nonce buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if !ctx.nonce_account.is_writable {
+            msg!("Nonce account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Reads the current value of a nonce account, the blockhash stored by its last advance.
+fn durable_nonce(nonce_account: &AccountInfo) -> Result<Hash, ProgramError> {
+    // the state of an account owned by any other program could be spoofed
+    if *nonce_account.owner != SYSTEM_PROGRAM_ID {
+        msg!("Nonce account must be owned by the System Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let versions = nonce_account
+        .deserialize_data::<Versions>()
+        .map_err(|_| EchoError::InvalidAccountData)?;
+
+    match versions.convert_to_current() {
+        State::Initialized(data) => Ok(data.blockhash),
+        State::Uninitialized => {
+            msg!("Nonce account is not initialized");
+            Err(EchoError::InvalidAccountData.into())
+        }
+    }
+}
+
+/// Copies `data` past the header when `nonce_value` is the current value of the nonce account of the buffer, then
+/// advances the nonce so that the same write can't be replayed.
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+    nonce_value: [u8; 32],
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if ctx.durable_nonce_buffer.owner != program_id {
+        msg!("Durable nonce buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer_header = {
+        let buffer = ctx.durable_nonce_buffer.data.borrow();
+
+        // check the size of the account before trying to read it
+        if buffer.len() <= DURABLE_NONCE_HEADER_SIZE {
+            msg!("Invalid durable nonce buffer size, {}", buffer.len());
+            return Err(EchoError::AccountNotInitialized.into());
+        }
+
+        DurableNonceHeader::try_from_slice(&buffer[..DURABLE_NONCE_HEADER_SIZE])?
+    };
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"durable_nonce", ctx.authority.key.as_ref()], program_id);
+
+    if pda != *ctx.durable_nonce_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    if *ctx.nonce_account.key != buffer_header.nonce_account {
+        msg!("Invalid nonce account");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let nonce = durable_nonce(ctx.nonce_account)?;
+    if nonce != Hash::new_from_array(nonce_value) {
+        msg!("Nonce value {} expected", nonce);
+        return Err(EchoError::InvalidNonce.into());
+    }
+
+    {
+        let buffer = &mut (*ctx.durable_nonce_buffer.data).borrow_mut();
+
+        // this is the 'rest' of the account's data (beyond the header info)
+        let buffer_data = &mut buffer[DURABLE_NONCE_HEADER_SIZE..];
+        let len = buffer_data.len().min(data.len());
+        buffer_data[..len].copy_from_slice(&data[..len]);
+        buffer_data[len..].fill(0);
+
+        msg!("Wrote {} bytes", len);
+    }
+
+    // the authority of the nonce account must sign, the System Program checks it
+    invoke(
+        &advance_nonce_account(ctx.nonce_account.key, ctx.authority.key),
+        &[
+            ctx.nonce_account.clone(),
+            ctx.recent_blockhashes.clone(),
+            ctx.authority.clone(),
+            ctx.system_program.clone(),
+        ],
+    )?;
+
+    Ok(())
+}
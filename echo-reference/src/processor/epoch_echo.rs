@@ -0,0 +1,136 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{EpochHeader, EPOCH_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    epoch_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            epoch_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        Ok(ctx)
+    }
+}
+
+/// Reads the header of `epoch_buffer` after checking that it is the epoch buffer of `authority`.
+fn read_epoch_header(
+    program_id: &Pubkey,
+    epoch_buffer: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<EpochHeader, ProgramError> {
+    if epoch_buffer.owner != program_id {
+        msg!("Epoch buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = epoch_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() <= EPOCH_HEADER_SIZE {
+        msg!("Invalid epoch buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = EpochHeader::try_from_slice(&buffer[..EPOCH_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(&[b"epoch", authority.as_ref()], program_id);
+
+    if pda != *epoch_buffer.key {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Copies `data` to the start of the buffer, the bytes past it are kept. The first write of a new epoch zeroes the
+/// whole buffer beforehand, so nothing written in a previous epoch survives it.
+pub fn process_write(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if !ctx.epoch_buffer.is_writable {
+        msg!("Epoch buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !ctx.authority.is_signer {
+        msg!("Authority account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let mut buffer_header = read_epoch_header(program_id, ctx.epoch_buffer, ctx.authority.key)?;
+    let epoch = Clock::get()?.epoch;
+
+    let buffer = &mut (*ctx.epoch_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[EPOCH_HEADER_SIZE..];
+
+    if epoch != buffer_header.last_write_epoch {
+        msg!(
+            "Resetting data of epoch {}, now {}",
+            buffer_header.last_write_epoch,
+            epoch
+        );
+        buffer_data.fill(0);
+        buffer_header.last_write_epoch = epoch;
+    }
+
+    let bytes_written = buffer_data.len().min(data.len());
+    buffer_data[..bytes_written].copy_from_slice(&data[..bytes_written]);
+
+    buffer[..EPOCH_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Wrote {} bytes in epoch {}", bytes_written, epoch);
+
+    Ok(())
+}
+
+/// Logs the data of the buffer, or that it is empty if it was last written in a previous epoch. The authority is
+/// passed along only to validate the epoch buffer address.
+pub fn process_read(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let buffer_header = read_epoch_header(program_id, ctx.epoch_buffer, ctx.authority.key)?;
+    let epoch = Clock::get()?.epoch;
+
+    // stale data is only zeroed by the next write, reading doesn't require the buffer to be writable
+    if epoch != buffer_header.last_write_epoch {
+        msg!(
+            "Epoch buffer is empty, last written in epoch {}",
+            buffer_header.last_write_epoch
+        );
+        return Ok(());
+    }
+
+    let buffer = ctx.epoch_buffer.data.borrow();
+    msg!("{:?}", &buffer[EPOCH_HEADER_SIZE..]);
+
+    Ok(())
+}
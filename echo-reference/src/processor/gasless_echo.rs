@@ -0,0 +1,220 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    ed25519_program,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::instructions::{self, load_current_index_checked, load_instruction_at_checked},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{GaslessHeader, GASLESS_HEADER_SIZE},
+};
+
+/// Offsets of a single signature in the data of an Ed25519 Program instruction, following the count and padding
+/// bytes. An instruction index of `u16::MAX` refers to the Ed25519 instruction itself.
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_OFFSETS_SIZE: usize = 14;
+
+/// Reads the header of `gasless_buffer` after checking its PDA.
+fn validate_gasless_buffer(
+    program_id: &Pubkey,
+    gasless_buffer: &AccountInfo,
+) -> Result<GaslessHeader, ProgramError> {
+    if !gasless_buffer.is_writable {
+        msg!("Gasless buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if gasless_buffer.owner != program_id {
+        msg!("Gasless buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = gasless_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < GASLESS_HEADER_SIZE {
+        msg!("Invalid gasless buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = GaslessHeader::try_from_slice(&buffer[..GASLESS_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"gasless", buffer_header.authority.as_ref()], program_id);
+
+    if pda != *gasless_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Message the authority signs off-chain for a write of `data` to `gasless_buffer` at `nonce`.
+pub fn gasless_message(gasless_buffer: &Pubkey, nonce: u64, data: &[u8]) -> Vec<u8> {
+    [gasless_buffer.as_ref(), &nonce.to_le_bytes(), data].concat()
+}
+
+/// Checks that `instruction` makes the Ed25519 Program verify exactly one signature of `message` by `authority`,
+/// with all three stored in the instruction itself. The signature must also be `signature` when given.
+pub(crate) fn check_ed25519_instruction(
+    instruction: &Instruction,
+    authority: &Pubkey,
+    signature: Option<&[u8; 64]>,
+    message: &[u8],
+) -> ProgramResult {
+    if instruction.program_id != ed25519_program::id() {
+        msg!("The instruction before this one must verify the signature with the Ed25519 Program");
+        return Err(EchoError::InvalidSignature.into());
+    }
+
+    let data = &instruction.data;
+
+    if data.len() < ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE || data[0] != 1 {
+        msg!("The Ed25519 instruction must verify exactly one signature");
+        return Err(EchoError::InvalidSignature.into());
+    }
+
+    let offsets = &data[ED25519_OFFSETS_START..ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE];
+    let read_u16 = |index: usize| u16::from_le_bytes([offsets[2 * index], offsets[2 * index + 1]]);
+    let (signature_offset, signature_instruction_index) = (read_u16(0), read_u16(1));
+    let (public_key_offset, public_key_instruction_index) = (read_u16(2), read_u16(3));
+    let (message_offset, message_size, message_instruction_index) =
+        (read_u16(4), read_u16(5), read_u16(6));
+
+    // data stored in other instructions could be changed without invalidating the signature check
+    if [
+        signature_instruction_index,
+        public_key_instruction_index,
+        message_instruction_index,
+    ]
+    .iter()
+    .any(|index| *index != u16::MAX)
+    {
+        msg!("The Ed25519 instruction must hold the signature, key and message itself");
+        return Err(EchoError::InvalidSignature.into());
+    }
+
+    let field = |offset: u16, len: usize| data.get(offset as usize..offset as usize + len);
+
+    if field(public_key_offset, 32) != Some(authority.as_ref())
+        || field(signature_offset, 64).is_none()
+        || signature.is_some() && field(signature_offset, 64) != signature.map(AsRef::as_ref)
+        || field(message_offset, message_size as usize) != Some(message)
+    {
+        msg!("The Ed25519 instruction doesn't verify the authority signature of this instruction");
+        return Err(EchoError::InvalidSignature.into());
+    }
+
+    Ok(())
+}
+
+/// Writes `data` signed off-chain by the authority, submitted and paid for by the relayer. The signature is verified
+/// by the Ed25519 Program in the instruction right before this one, which fails the whole transaction if it is
+/// invalid, so only its inputs are checked here.
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+    user_signature: [u8; 64],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let gasless_buffer = next_account_info(accounts_iter)?;
+    let relayer = next_account_info(accounts_iter)?;
+    let instructions_sysvar = next_account_info(accounts_iter)?;
+
+    if !relayer.is_signer {
+        msg!("Relayer account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    if !instructions::check_id(instructions_sysvar.key) {
+        msg!("Invalid instructions sysvar");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let mut buffer_header = validate_gasless_buffer(program_id, gasless_buffer)?;
+
+    if buffer_header.relayer != *relayer.key {
+        msg!(
+            "Only the relayer {} can submit writes",
+            buffer_header.relayer
+        );
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+
+    if current_index == 0 {
+        msg!("The gasless write must follow an Ed25519 instruction");
+        return Err(EchoError::InvalidSignature.into());
+    }
+
+    let ed25519_instruction =
+        load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+    check_ed25519_instruction(
+        &ed25519_instruction,
+        &buffer_header.authority,
+        Some(&user_signature),
+        &gasless_message(gasless_buffer.key, buffer_header.nonce, &data),
+    )?;
+
+    buffer_header.nonce = buffer_header
+        .nonce
+        .checked_add(1)
+        .ok_or(EchoError::Overflow)?;
+
+    let buffer = &mut (*gasless_buffer.data).borrow_mut();
+    buffer[..GASLESS_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[GASLESS_HEADER_SIZE..];
+    let len = buffer_data.len().min(data.len());
+    buffer_data[..len].copy_from_slice(&data[..len]);
+    buffer_data[len..].fill(0);
+
+    msg!("Relayed {} bytes, nonce {}", len, buffer_header.nonce);
+
+    Ok(())
+}
+
+/// Replaces the relayer, only the authority can.
+pub fn process_update_relayer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_relayer: Pubkey,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let gasless_buffer = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        msg!("Authority account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let mut buffer_header = validate_gasless_buffer(program_id, gasless_buffer)?;
+
+    if buffer_header.authority != *authority.key {
+        msg!("Only the authority can update the relayer");
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    buffer_header.relayer = new_relayer;
+
+    gasless_buffer.data.borrow_mut()[..GASLESS_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Relayer: {}", new_relayer);
+
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_option::COption,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    processor::vending_machine_echo::{unpack_mint, unpack_token_account},
+    state::{GovernanceHeader, GOVERNANCE_HEADER_SIZE},
+};
+
+/// Reads the header of `governance_buffer` after checking that it is the buffer of its governance mint.
+fn read_governance_header(
+    program_id: &Pubkey,
+    governance_buffer: &AccountInfo,
+) -> Result<GovernanceHeader, ProgramError> {
+    if !governance_buffer.is_writable {
+        msg!("Governance buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if governance_buffer.owner != program_id {
+        msg!("Governance buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = governance_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() <= GOVERNANCE_HEADER_SIZE {
+        msg!("Invalid governance buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = GovernanceHeader::try_from_slice(&buffer[..GOVERNANCE_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[b"governance", buffer_header.governance_mint.as_ref()],
+        program_id,
+    );
+
+    if pda != *governance_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or governance mint");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Copies `data` past the header, zeroing the rest of it, when the `holder` owns a token account with at least
+/// `min_tokens` of the governance mint. Nothing is burned, the holder can write again as long as they keep the tokens.
+pub fn process_write(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let governance_buffer = next_account_info(accounts_iter)?;
+    let holder = next_account_info(accounts_iter)?;
+    let holder_token_account = next_account_info(accounts_iter)?;
+
+    if !holder.is_signer {
+        msg!("Holder must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let buffer_header = read_governance_header(program_id, governance_buffer)?;
+
+    let token_account = unpack_token_account(holder_token_account, &spl_token::id())?;
+
+    // anyone could pass the token account of a large holder otherwise
+    if token_account.owner != *holder.key {
+        msg!("Token account is not owned by the holder");
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    if token_account.mint != buffer_header.governance_mint {
+        msg!(
+            "Token account holds {}, not the governance mint {}",
+            token_account.mint,
+            buffer_header.governance_mint
+        );
+        return Err(EchoError::MintMismatch.into());
+    }
+
+    if token_account.amount < buffer_header.min_tokens {
+        msg!(
+            "{} governance tokens required, {} held",
+            buffer_header.min_tokens,
+            token_account.amount
+        );
+        return Err(EchoError::InsufficientFunds.into());
+    }
+
+    let buffer = &mut (*governance_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[GOVERNANCE_HEADER_SIZE..];
+    let len = buffer_data.len().min(data.len());
+    buffer_data[..len].copy_from_slice(&data[..len]);
+    buffer_data[len..].fill(0);
+
+    msg!("Wrote {} bytes", len);
+
+    Ok(())
+}
+
+/// Replaces the `min_tokens` a writer must hold, only the mint authority of the governance mint can change it.
+pub fn process_update_threshold(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_min: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let governance_buffer = next_account_info(accounts_iter)?;
+    let governance_mint = next_account_info(accounts_iter)?;
+    let mint_authority = next_account_info(accounts_iter)?;
+
+    if !mint_authority.is_signer {
+        msg!("Mint authority must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let mut buffer_header = read_governance_header(program_id, governance_buffer)?;
+
+    if *governance_mint.key != buffer_header.governance_mint {
+        msg!("Invalid governance mint");
+        return Err(EchoError::MintMismatch.into());
+    }
+
+    let mint = unpack_mint(governance_mint, &spl_token::id())?;
+
+    if mint.mint_authority != COption::Some(*mint_authority.key) {
+        msg!("Invalid mint authority");
+        return Err(EchoError::InvalidMintAuthority.into());
+    }
+
+    buffer_header.min_tokens = new_min;
+    governance_buffer.data.borrow_mut()[..GOVERNANCE_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Min tokens: {}", new_min);
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    keccak, msg,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{GradualReleaseHeader, GRADUAL_RELEASE_HEADER_SIZE},
+};
+
+/// XORs `bytes`, found at `offset` of the data, with a keystream of keccak hashes of the buffer address, the start
+/// slot and the block index. Applying it twice gives back the original bytes.
+///
+/// The keystream only depends on public values, so this hides the data from a casual read of the account, not from
+/// anyone computing the keystream, nor from the instruction data of the initialization.
+pub(crate) fn apply_mask(buffer: &Pubkey, reveal_start_slot: u64, offset: usize, bytes: &mut [u8]) {
+    let block_len = keccak::HASH_BYTES;
+
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        let position = offset + index;
+        let block = keccak::hashv(&[
+            buffer.as_ref(),
+            &reveal_start_slot.to_le_bytes(),
+            &((position / block_len) as u64).to_le_bytes(),
+        ]);
+        *byte ^= block.0[position % block_len];
+    }
+}
+
+/// Bytes revealed at `slot`, growing linearly from none at the start slot to all of them at the end slot.
+pub fn revealed_len(buffer_header: &GradualReleaseHeader, slot: u64) -> u32 {
+    let total_slots = buffer_header.reveal_end_slot - buffer_header.reveal_start_slot;
+    let elapsed = slot
+        .saturating_sub(buffer_header.reveal_start_slot)
+        .min(total_slots);
+
+    (elapsed as u128 * buffer_header.total_bytes as u128 / total_slots as u128) as u32
+}
+
+/// Unmasks the bytes due by `current_slot` into the revealed region, which only ever grows.
+pub fn process_reveal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    current_slot: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let gradual_release_buffer = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+
+    if !gradual_release_buffer.is_writable {
+        msg!("Gradual release buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !authority.is_signer {
+        msg!("Authority account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if gradual_release_buffer.owner != program_id {
+        msg!("Gradual release buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    // a later slot would reveal bytes before they are due
+    let slot = Clock::get()?.slot;
+    if current_slot > slot {
+        msg!(
+            "Slot {} is in the future, current slot {}",
+            current_slot,
+            slot
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    let buffer = &mut (*gradual_release_buffer.data).borrow_mut();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < GRADUAL_RELEASE_HEADER_SIZE {
+        msg!("Invalid gradual release buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let mut buffer_header =
+        GradualReleaseHeader::try_from_slice(&buffer[..GRADUAL_RELEASE_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[b"gradual_release", buffer_header.authority.as_ref()],
+        program_id,
+    );
+
+    if pda != *gradual_release_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid gradual release buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    if buffer_header.authority != *authority.key {
+        msg!("Only the authority can reveal the data");
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    let total_bytes = buffer_header.total_bytes as usize;
+    if buffer.len() != GRADUAL_RELEASE_HEADER_SIZE + 2 * total_bytes {
+        msg!("Invalid gradual release buffer size, {}", buffer.len());
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    let start = buffer_header.revealed_bytes as usize;
+    let end = revealed_len(&buffer_header, current_slot) as usize;
+
+    if end > start {
+        let (masked, revealed) = buffer[GRADUAL_RELEASE_HEADER_SIZE..].split_at_mut(total_bytes);
+        revealed[start..end].copy_from_slice(&masked[start..end]);
+        apply_mask(
+            &pda,
+            buffer_header.reveal_start_slot,
+            start,
+            &mut revealed[start..end],
+        );
+
+        buffer_header.revealed_bytes = end as u32;
+        buffer[..GRADUAL_RELEASE_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+    }
+
+    msg!(
+        "Revealed {} of {} bytes",
+        buffer_header.revealed_bytes,
+        total_bytes
+    );
+
+    Ok(())
+}
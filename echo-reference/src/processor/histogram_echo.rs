@@ -0,0 +1,150 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    state::{HistogramHeader, HISTOGRAM_BUCKET_SIZE, HISTOGRAM_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    histogram_buffer: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            histogram_buffer: next_account_info(accounts_iter)?,
+        };
+
+        Ok(ctx)
+    }
+}
+
+/// Reads the header of `histogram_buffer` after checking that it is a histogram buffer of the Echo Program.
+fn validate_histogram_buffer(
+    program_id: &Pubkey,
+    histogram_buffer: &AccountInfo,
+) -> Result<HistogramHeader, ProgramError> {
+    if histogram_buffer.owner != program_id {
+        msg!("Histogram buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = histogram_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < HISTOGRAM_HEADER_SIZE {
+        msg!("Invalid histogram buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = HistogramHeader::try_from_slice(&buffer[..HISTOGRAM_HEADER_SIZE])?;
+
+    if buffer.len()
+        != HISTOGRAM_HEADER_SIZE + buffer_header.bucket_count as usize * HISTOGRAM_BUCKET_SIZE
+    {
+        msg!("Invalid histogram buffer size, {}", buffer.len());
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(
+        &[b"histogram", buffer_header.authority.as_ref()],
+        program_id,
+    );
+
+    if pda != *histogram_buffer.key {
+        msg!("Invalid histogram buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Byte range of the counter of bucket `index` within the account data.
+fn bucket_range(index: usize) -> std::ops::Range<usize> {
+    let start = HISTOGRAM_HEADER_SIZE + index * HISTOGRAM_BUCKET_SIZE;
+    start..start + HISTOGRAM_BUCKET_SIZE
+}
+
+/// Counts `value` in bucket `value / bucket_width`, values beyond the last bucket are counted in the last one.
+/// Anyone can record a value.
+pub fn process_record(program_id: &Pubkey, accounts: &[AccountInfo], value: u64) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if !ctx.histogram_buffer.is_writable {
+        msg!("Histogram buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    let buffer_header = validate_histogram_buffer(program_id, ctx.histogram_buffer)?;
+
+    let last_bucket = buffer_header.bucket_count as u64 - 1;
+    let bucket_index = (value / buffer_header.bucket_width).min(last_bucket) as usize;
+
+    let buffer = &mut (*ctx.histogram_buffer.data).borrow_mut();
+    let bucket = &mut buffer[bucket_range(bucket_index)];
+
+    let count = u64::from_le_bytes(bucket.try_into().unwrap());
+    let count = count.checked_add(1).ok_or_else(|| {
+        msg!("Bucket {} overflows", bucket_index);
+        EchoError::Overflow
+    })?;
+    bucket.copy_from_slice(&count.to_le_bytes());
+
+    msg!("Bucket {}: {}", bucket_index, count);
+
+    Ok(())
+}
+
+/// Logs the count of every bucket.
+pub fn process_read(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let buffer_header = validate_histogram_buffer(program_id, ctx.histogram_buffer)?;
+
+    let buffer = ctx.histogram_buffer.data.borrow();
+    for index in 0..buffer_header.bucket_count as usize {
+        let count = u64::from_le_bytes(buffer[bucket_range(index)].try_into().unwrap());
+        msg!("Bucket {}: {}", index, count);
+    }
+
+    Ok(())
+}
+
+/// Zeroes every bucket, only the authority the histogram was created for can reset it.
+pub fn process_reset(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+    let authority = accounts.get(1).ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    if !ctx.histogram_buffer.is_writable {
+        msg!("Histogram buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !authority.is_signer {
+        msg!("Authority account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let buffer_header = validate_histogram_buffer(program_id, ctx.histogram_buffer)?;
+
+    if buffer_header.authority != *authority.key {
+        msg!("Only the histogram authority can reset the buckets");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let buffer = &mut (*ctx.histogram_buffer.data).borrow_mut();
+    buffer[HISTOGRAM_HEADER_SIZE..].fill(0);
+
+    Ok(())
+}
@@ -0,0 +1,208 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    state::{IndexedHeader, INDEXED_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    indexed_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            indexed_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        Ok(ctx)
+    }
+
+    /// Additional checks for instructions that modify the buffer.
+    fn check_mutable(&self) -> ProgramResult {
+        if !self.indexed_buffer.is_writable {
+            msg!("Indexed buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !self.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the header of `indexed_buffer` after checking that it is the indexed buffer of `authority`.
+fn validate_indexed_buffer(
+    program_id: &Pubkey,
+    indexed_buffer: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<IndexedHeader, ProgramError> {
+    if indexed_buffer.owner != program_id {
+        msg!("Indexed buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = indexed_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < INDEXED_HEADER_SIZE {
+        msg!("Invalid indexed buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = IndexedHeader::try_from_slice(&buffer[..INDEXED_HEADER_SIZE])?;
+
+    if buffer.len()
+        != INDEXED_HEADER_SIZE + buffer_header.slot_count as usize * buffer_header.record_size()
+    {
+        msg!("Invalid indexed buffer size, {}", buffer.len());
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(&[b"indexed", authority.as_ref()], program_id);
+
+    if pda != *indexed_buffer.key {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Checks the size of `key`, an all-zero key is rejected as zeroed records mark free slots.
+fn check_key(buffer_header: &IndexedHeader, key: &[u8]) -> ProgramResult {
+    if key.len() != buffer_header.key_size as usize {
+        msg!(
+            "Invalid key length {}, must be {}",
+            key.len(),
+            buffer_header.key_size
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    if key.iter().all(|byte| *byte == 0) {
+        msg!("Key must not be all zeros");
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    Ok(())
+}
+
+/// Index of the record holding `key`, using a linear scan.
+fn find_record(buffer_header: &IndexedHeader, records: &[u8], key: &[u8]) -> Option<usize> {
+    records
+        .chunks(buffer_header.record_size())
+        .position(|record| &record[..key.len()] == key)
+}
+
+/// Updates the value of `key` if it is already stored, otherwise stores it in the first free slot.
+pub fn process_put(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    key: Vec<u8>,
+    value: Vec<u8>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+    ctx.check_mutable()?;
+
+    let buffer_header = validate_indexed_buffer(program_id, ctx.indexed_buffer, ctx.authority.key)?;
+    check_key(&buffer_header, &key)?;
+
+    if value.len() != buffer_header.value_size as usize {
+        msg!(
+            "Invalid value length {}, must be {}",
+            value.len(),
+            buffer_header.value_size
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    let buffer = &mut (*ctx.indexed_buffer.data).borrow_mut();
+    let records = &mut buffer[INDEXED_HEADER_SIZE..];
+
+    let index = match find_record(&buffer_header, records, &key) {
+        Some(index) => index,
+        None => records
+            .chunks(buffer_header.record_size())
+            .position(|record| record[..key.len()].iter().all(|byte| *byte == 0))
+            .ok_or_else(|| {
+                msg!("No free slot, slot count {}", buffer_header.slot_count);
+                EchoError::SetFull
+            })?,
+    };
+
+    let start = index * buffer_header.record_size();
+    records[start..start + key.len()].copy_from_slice(&key);
+    records[start + key.len()..start + buffer_header.record_size()].copy_from_slice(&value);
+
+    msg!("Stored value in slot {}", index);
+
+    Ok(())
+}
+
+pub fn process_delete(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    key: Vec<u8>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+    ctx.check_mutable()?;
+
+    let buffer_header = validate_indexed_buffer(program_id, ctx.indexed_buffer, ctx.authority.key)?;
+    check_key(&buffer_header, &key)?;
+
+    let buffer = &mut (*ctx.indexed_buffer.data).borrow_mut();
+    let records = &mut buffer[INDEXED_HEADER_SIZE..];
+
+    let index = find_record(&buffer_header, records, &key).ok_or_else(|| {
+        msg!("Key not found");
+        EchoError::MemberNotFound
+    })?;
+
+    let start = index * buffer_header.record_size();
+    records[start..start + buffer_header.record_size()].fill(0);
+
+    msg!("Cleared slot {}", index);
+
+    Ok(())
+}
+
+/// Logs the value stored for `key`, the authority is passed along only to validate the indexed buffer address.
+pub fn process_get(program_id: &Pubkey, accounts: &[AccountInfo], key: Vec<u8>) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let buffer_header = validate_indexed_buffer(program_id, ctx.indexed_buffer, ctx.authority.key)?;
+    check_key(&buffer_header, &key)?;
+
+    let buffer = ctx.indexed_buffer.data.borrow();
+    let records = &buffer[INDEXED_HEADER_SIZE..];
+
+    let index = find_record(&buffer_header, records, &key).ok_or_else(|| {
+        msg!("Key not found");
+        EchoError::MemberNotFound
+    })?;
+
+    let start = index * buffer_header.record_size();
+    msg!(
+        "{:?}",
+        &records[start + key.len()..start + buffer_header.record_size()]
+    );
+
+    Ok(())
+}
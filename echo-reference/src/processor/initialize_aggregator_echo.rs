@@ -0,0 +1,118 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    state::{AggregatorBufferHeader, AGGREGATOR_BUFF_HEADER_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    aggregator_buffer: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            aggregator_buffer: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.aggregator_buffer.is_writable {
+            msg!("Aggregator buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    buffer_size: usize,
+    authority: Pubkey,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // need at least enough for the buffer header
+    if buffer_size <= AGGREGATOR_BUFF_HEADER_SIZE {
+        msg!(
+            "Invalid buffer length {}, must be greater than header size {}",
+            buffer_size,
+            AGGREGATOR_BUFF_HEADER_SIZE
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"aggregator", authority.as_ref()], program_id);
+
+    if *ctx.aggregator_buffer.key != pda {
+        msg!("Invalid aggregator buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.payer.key,
+        ctx.aggregator_buffer.key,
+        Rent::get()?.minimum_balance(buffer_size),
+        buffer_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.aggregator_buffer.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"aggregator", authority.as_ref(), &[bump_seed]]],
+    )?;
+
+    // the full data buffer
+    let buffer = &mut (*ctx.aggregator_buffer.data).borrow_mut();
+
+    // slice of the buffer used for the header
+    let buffer_header = AggregatorBufferHeader {
+        bump_seed,
+        authority,
+    };
+
+    buffer[0..AGGREGATOR_BUFF_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Aggregator buffer len: {}", buffer_size);
+    msg!("Bump seed: {}", bump_seed);
+    msg!("Authority: {}", authority);
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    processor::auction_vending_machine::vault_address,
+    state::{AuctionHeader, AUCTION_HEADER_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    auction_buffer: &'a AccountInfo<'b>,
+    creator: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            auction_buffer: next_account_info(accounts_iter)?,
+            creator: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.auction_buffer.is_writable {
+            msg!("Auction buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.creator.is_signer {
+            msg!("Creator must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    buffer_size: usize,
+    auction_duration_slots: u64,
+    reserve_price: u64,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // need at least enough for the buffer header
+    if buffer_size <= AUCTION_HEADER_SIZE {
+        msg!(
+            "Invalid buffer length {}, must be greater than header size {}",
+            buffer_size,
+            AUCTION_HEADER_SIZE
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    let end_slot = Clock::get()?
+        .slot
+        .checked_add(auction_duration_slots)
+        .ok_or(EchoError::Overflow)?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"auction", ctx.creator.key.as_ref()], program_id);
+
+    if *ctx.auction_buffer.key != pda {
+        msg!("Invalid auction buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.creator.key,
+        ctx.auction_buffer.key,
+        Rent::get()?.minimum_balance(buffer_size),
+        buffer_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.auction_buffer.clone(),
+            ctx.creator.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"auction", ctx.creator.key.as_ref(), &[bump_seed]]],
+    )?;
+
+    let (vault, vault_bump_seed) = vault_address(program_id, ctx.auction_buffer.key);
+
+    // slice of the buffer used for the header
+    let buffer_header = AuctionHeader {
+        end_slot,
+        highest_bid: 0,
+        highest_bidder: Pubkey::default(),
+        settled: false,
+        bump_seed,
+        vault_bump_seed,
+        reserve_price,
+        creator: *ctx.creator.key,
+    };
+
+    ctx.auction_buffer.data.borrow_mut()[..AUCTION_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Auction buffer len: {}", buffer_size);
+    msg!("Bump seed: {}", bump_seed);
+    msg!("End slot: {}", end_slot);
+    msg!("Reserve price: {}", reserve_price);
+    msg!("Vault: {}", vault);
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    state::{AuthorityCounter, AUTHORITY_COUNTER_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    counter: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            counter: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.counter.is_writable {
+            msg!("Counter account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"counter", ctx.authority.key.as_ref()], program_id);
+
+    if *ctx.counter.key != pda {
+        msg!("Invalid counter address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.authority.key,
+        ctx.counter.key,
+        Rent::get()?.minimum_balance(AUTHORITY_COUNTER_SIZE),
+        AUTHORITY_COUNTER_SIZE as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.counter.clone(),
+            ctx.authority.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"counter", ctx.authority.key.as_ref(), &[bump_seed]]],
+    )?;
+
+    let header = AuthorityCounter {
+        next_seed: 0,
+        bump_seed,
+    };
+
+    ctx.counter.data.borrow_mut()[..AUTHORITY_COUNTER_SIZE]
+        .copy_from_slice(&header.try_to_vec().unwrap());
+
+    msg!("Bump seed: {}", bump_seed);
+
+    Ok(())
+}
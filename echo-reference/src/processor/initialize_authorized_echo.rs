@@ -13,7 +13,8 @@ use solana_program::{
 
 use crate::{
     error::EchoError,
-    state::{AuthorizedBufferHeader, AUTH_BUFF_HEADER_SIZE},
+    processor::{authorized_echo::encode_label, registry::register_buffer},
+    state::{AuthorizedBufferHeader, AUTH_BUFF_HEADER_SIZE, MAX_BPS},
 };
 
 use borsh::BorshSerialize;
@@ -22,6 +23,7 @@ struct Context<'a, 'b: 'a> {
     authorized_buffer: &'a AccountInfo<'b>,
     authority: &'a AccountInfo<'b>,
     system_program: &'a AccountInfo<'b>,
+    registry: Option<&'a AccountInfo<'b>>,
 }
 
 impl<'a, 'b: 'a> Context<'a, 'b> {
@@ -32,6 +34,7 @@ impl<'a, 'b: 'a> Context<'a, 'b> {
             authorized_buffer: next_account_info(accounts_iter)?,
             authority: next_account_info(accounts_iter)?,
             system_program: next_account_info(accounts_iter)?,
+            registry: accounts_iter.next(),
         };
 
         if !ctx.authorized_buffer.is_writable {
@@ -53,14 +56,111 @@ impl<'a, 'b: 'a> Context<'a, 'b> {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn process(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     buffer_seed: u64,
     buffer_size: usize,
+    label: String,
+    permanent_delegate: Option<Pubkey>,
+    write_fee_lamports: u64,
+    expiry_slot: u64,
+    crank_bounty_bps: u16,
 ) -> ProgramResult {
     let ctx = Context::parse(accounts)?;
 
+    if crank_bounty_bps > MAX_BPS {
+        msg!(
+            "Invalid crank bounty {} bps, must be at most {}",
+            crank_bounty_bps,
+            MAX_BPS
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // there is no instruction to change the delegate, so it is fixed for the lifetime of the buffer
+    let buffer_header = AuthorizedBufferHeader {
+        buffer_seed,
+        label: encode_label(&label)?,
+        has_permanent_delegate: permanent_delegate.is_some(),
+        permanent_delegate: permanent_delegate.unwrap_or_default(),
+        write_fee_lamports,
+        expiry_slot,
+        crank_bounty_bps,
+        ..AuthorizedBufferHeader::default()
+    };
+
+    create_authorized_buffer(
+        program_id,
+        ctx.authorized_buffer,
+        ctx.authority.key,
+        ctx.authority,
+        ctx.system_program,
+        buffer_size,
+        buffer_header,
+    )?;
+
+    msg!("Label: {}", label);
+    if let Some(permanent_delegate) = permanent_delegate {
+        msg!("Permanent delegate: {}", permanent_delegate);
+    }
+    msg!("Write fee: {} lamports", write_fee_lamports);
+    if expiry_slot > 0 {
+        msg!("Expiry slot: {}", expiry_slot);
+        msg!("Crank bounty: {} bps", crank_bounty_bps);
+    }
+
+    if let Some(registry) = ctx.registry {
+        register_buffer(
+            program_id,
+            registry,
+            ctx.authority.key,
+            buffer_seed,
+            ctx.authorized_buffer.key,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Allocates `authorized_buffer` at the PDA of `authority` and `buffer_header.buffer_seed`, funded by `payer`, and
+/// stores `buffer_header` with its bump seed filled in.
+pub(crate) fn create_authorized_buffer<'a>(
+    program_id: &Pubkey,
+    authorized_buffer: &AccountInfo<'a>,
+    authority: &Pubkey,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    buffer_size: usize,
+    buffer_header: AuthorizedBufferHeader,
+) -> ProgramResult {
+    create_authorized_buffer_signed(
+        program_id,
+        authorized_buffer,
+        authority,
+        payer,
+        system_program,
+        buffer_size,
+        buffer_header,
+        &[],
+    )
+}
+
+/// Same as `create_authorized_buffer`, with `payer_seeds` signing for a `payer` that is a PDA of the Echo Program.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_authorized_buffer_signed<'a>(
+    program_id: &Pubkey,
+    authorized_buffer: &AccountInfo<'a>,
+    authority: &Pubkey,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    buffer_size: usize,
+    mut buffer_header: AuthorizedBufferHeader,
+    payer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let buffer_seed = buffer_header.buffer_seed;
+
     // need at least enough for the buffer header
     if buffer_size <= AUTH_BUFF_HEADER_SIZE {
         msg!(
@@ -73,51 +173,59 @@ pub fn process(
 
     // verify that the PDA account is the correct address
     let (pda, bump_seed) = Pubkey::find_program_address(
-        &[
-            b"authority",
-            ctx.authority.key.as_ref(),
-            &buffer_seed.to_le_bytes(),
-        ],
+        &[b"authority", authority.as_ref(), &buffer_seed.to_le_bytes()],
         program_id,
     );
 
-    if *ctx.authorized_buffer.key != pda {
+    if *authorized_buffer.key != pda {
         msg!("Invalid authorized buffer address");
         return Err(EchoError::InvalidAccountAddress.into());
     }
 
+    // the buffer is funded with exactly the rent exempt minimum, a payer short of it can't create the buffer
+    let rent_lamports = Rent::get()?.minimum_balance(buffer_size);
+
+    if payer.lamports() < rent_lamports {
+        msg!(
+            "Payer has {} lamports, {} are needed for a rent exempt buffer of {} bytes",
+            payer.lamports(),
+            rent_lamports,
+            buffer_size
+        );
+        return Err(EchoError::InsufficientFunds.into());
+    }
+
     // call the system program to create the account
     let create_account_ix = create_account(
-        &ctx.authority.key,
-        &ctx.authorized_buffer.key,
-        Rent::get()?.minimum_balance(buffer_size),
+        payer.key,
+        authorized_buffer.key,
+        rent_lamports,
         buffer_size as u64,
         program_id,
     );
 
+    let buffer_seeds: &[&[u8]] = &[
+        b"authority",
+        authority.as_ref(),
+        &buffer_seed.to_le_bytes(),
+        &[bump_seed],
+    ];
+
     invoke_signed(
         &create_account_ix,
         &[
-            ctx.authorized_buffer.clone(),
-            ctx.authority.clone(),
-            ctx.system_program.clone(),
+            authorized_buffer.clone(),
+            payer.clone(),
+            system_program.clone(),
         ],
-        &[&[
-            b"authority",
-            ctx.authority.key.as_ref(),
-            &buffer_seed.to_le_bytes(),
-            &[bump_seed],
-        ]],
+        &[&[buffer_seeds], payer_seeds].concat(),
     )?;
 
     // the full data buffer
-    let buffer = &mut (*ctx.authorized_buffer.data).borrow_mut();
+    let buffer = &mut (*authorized_buffer.data).borrow_mut();
 
     // slice of the buffer used for the header
-    let buffer_header = AuthorizedBufferHeader {
-        bump_seed,
-        buffer_seed,
-    };
+    buffer_header.bump_seed = bump_seed;
 
     buffer[0..AUTH_BUFF_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
 
@@ -0,0 +1,114 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    processor::initialize_authorized_echo::create_authorized_buffer,
+    state::{AuthorityCounter, AuthorizedBufferHeader, AUTHORITY_COUNTER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    authorized_buffer: &'a AccountInfo<'b>,
+    counter: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            authorized_buffer: next_account_info(accounts_iter)?,
+            counter: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.authorized_buffer.is_writable {
+            msg!("Authorized Echo Buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.counter.is_writable {
+            msg!("Counter account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Creates an authorized buffer using the authority's counter as `buffer_seed`, then moves the counter on.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], buffer_size: usize) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if ctx.counter.owner != program_id {
+        msg!("Counter account must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let mut counter = {
+        let data = ctx.counter.data.borrow();
+
+        if data.len() != AUTHORITY_COUNTER_SIZE {
+            msg!("Invalid counter account size, {}", data.len());
+            return Err(EchoError::AccountNotInitialized.into());
+        }
+
+        AuthorityCounter::try_from_slice(&data)?
+    };
+
+    // verify that the PDA account is the correct address
+    let (pda, _) =
+        Pubkey::find_program_address(&[b"counter", ctx.authority.key.as_ref()], program_id);
+
+    if pda != *ctx.counter.key {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let buffer_seed = counter.next_seed;
+
+    // store the incremented counter first, if creating the buffer fails the whole instruction is rolled back
+    counter.next_seed = buffer_seed.checked_add(1).ok_or(EchoError::Overflow)?;
+    ctx.counter.data.borrow_mut()[..AUTHORITY_COUNTER_SIZE]
+        .copy_from_slice(&counter.try_to_vec().unwrap());
+
+    let buffer_header = AuthorizedBufferHeader {
+        buffer_seed,
+        ..AuthorizedBufferHeader::default()
+    };
+
+    create_authorized_buffer(
+        program_id,
+        ctx.authorized_buffer,
+        ctx.authority.key,
+        ctx.authority,
+        ctx.system_program,
+        buffer_size,
+        buffer_header,
+    )?;
+
+    msg!("Next seed: {}", counter.next_seed);
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+};
+
+use crate::{
+    error::EchoError, processor::initialize_authorized_echo::create_authorized_buffer,
+    state::AuthorizedBufferHeader,
+};
+
+struct Context<'a, 'b: 'a> {
+    authorized_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            authorized_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.authorized_buffer.is_writable {
+            msg!("Authorized Echo Buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        // the authority may be a PDA of another program, so only the payer has to sign
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    buffer_seed: u64,
+    buffer_size: usize,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let buffer_header = AuthorizedBufferHeader {
+        buffer_seed,
+        ..AuthorizedBufferHeader::default()
+    };
+
+    create_authorized_buffer(
+        program_id,
+        ctx.authorized_buffer,
+        ctx.authority.key,
+        ctx.payer,
+        ctx.system_program,
+        buffer_size,
+        buffer_header,
+    )?;
+
+    msg!("Authority: {}", ctx.authority.key);
+
+    Ok(())
+}
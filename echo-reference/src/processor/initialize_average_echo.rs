@@ -0,0 +1,111 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    state::{AverageHeader, AVERAGE_HEADER_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    average_account: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            average_account: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.average_account.is_writable {
+            msg!("Average account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_samples: u32,
+    authority: Pubkey,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if max_samples == 0 {
+        msg!("Invalid max samples, must be non-zero");
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"average", authority.as_ref()], program_id);
+
+    if *ctx.average_account.key != pda {
+        msg!("Invalid average account address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.payer.key,
+        ctx.average_account.key,
+        Rent::get()?.minimum_balance(AVERAGE_HEADER_SIZE),
+        AVERAGE_HEADER_SIZE as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.average_account.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"average", authority.as_ref(), &[bump_seed]]],
+    )?;
+
+    let header = AverageHeader {
+        sum: 0,
+        count: 0,
+        max_samples,
+        bump_seed,
+    };
+
+    ctx.average_account.data.borrow_mut()[..AVERAGE_HEADER_SIZE]
+        .copy_from_slice(&header.try_to_vec().unwrap());
+
+    msg!("Max samples: {}", max_samples);
+    msg!("Bump seed: {}", bump_seed);
+
+    Ok(())
+}
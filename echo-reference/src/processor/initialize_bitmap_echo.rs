@@ -0,0 +1,116 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    state::{bitmap_len, BitmapHeader, BITMAP_HEADER_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    bitmap_buffer: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            bitmap_buffer: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.bitmap_buffer.is_writable {
+            msg!("Bitmap buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    bit_count: u32,
+    authority: Pubkey,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if bit_count == 0 {
+        msg!("Invalid bit count, must be non-zero");
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // one bit per index after the header, rounded up to whole bytes
+    let buffer_size = BITMAP_HEADER_SIZE + bitmap_len(bit_count);
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"bitmap", authority.as_ref()], program_id);
+
+    if *ctx.bitmap_buffer.key != pda {
+        msg!("Invalid bitmap buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.payer.key,
+        ctx.bitmap_buffer.key,
+        Rent::get()?.minimum_balance(buffer_size),
+        buffer_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.bitmap_buffer.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"bitmap", authority.as_ref(), &[bump_seed]]],
+    )?;
+
+    // the full data buffer
+    let buffer = &mut (*ctx.bitmap_buffer.data).borrow_mut();
+
+    // slice of the buffer used for the header
+    let buffer_header = BitmapHeader {
+        bit_count,
+        bump_seed,
+    };
+
+    buffer[0..BITMAP_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Bit count: {}", bit_count);
+    msg!("Bump seed: {}", bump_seed);
+    msg!("Authority: {}", authority);
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    processor::co_authorized_echo::sort_authorities,
+    state::{CoAuthorizedBufferHeader, CO_AUTH_BUFF_HEADER_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    co_authorized_buffer: &'a AccountInfo<'b>,
+    first_authority: &'a AccountInfo<'b>,
+    second_authority: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            co_authorized_buffer: next_account_info(accounts_iter)?,
+            first_authority: next_account_info(accounts_iter)?,
+            second_authority: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.co_authorized_buffer.is_writable {
+            msg!("Co-authorized buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        // the first authority pays for the buffer, the second only has to sign its writes
+        if !ctx.first_authority.is_signer {
+            msg!("First authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    buffer_seed: u64,
+    buffer_size: usize,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // need at least enough for the buffer header
+    if buffer_size <= CO_AUTH_BUFF_HEADER_SIZE {
+        msg!(
+            "Invalid buffer length {}, must be greater than header size {}",
+            buffer_size,
+            CO_AUTH_BUFF_HEADER_SIZE
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    if ctx.first_authority.key == ctx.second_authority.key {
+        msg!("The two authorities must be different");
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // sorting makes the address independent of the order the authorities are passed in
+    let (authority_a, authority_b) =
+        sort_authorities(ctx.first_authority.key, ctx.second_authority.key);
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"co_authority",
+            authority_a.as_ref(),
+            authority_b.as_ref(),
+            &buffer_seed.to_le_bytes(),
+        ],
+        program_id,
+    );
+
+    if *ctx.co_authorized_buffer.key != pda {
+        msg!("Invalid co-authorized buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.first_authority.key,
+        ctx.co_authorized_buffer.key,
+        Rent::get()?.minimum_balance(buffer_size),
+        buffer_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.co_authorized_buffer.clone(),
+            ctx.first_authority.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[
+            b"co_authority",
+            authority_a.as_ref(),
+            authority_b.as_ref(),
+            &buffer_seed.to_le_bytes(),
+            &[bump_seed],
+        ]],
+    )?;
+
+    // the full data buffer
+    let buffer = &mut (*ctx.co_authorized_buffer.data).borrow_mut();
+
+    // slice of the buffer used for the header
+    let buffer_header = CoAuthorizedBufferHeader {
+        bump_seed,
+        buffer_seed,
+        authority_a,
+        authority_b,
+    };
+
+    buffer[0..CO_AUTH_BUFF_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Co-authorized buffer len: {}", buffer_size);
+    msg!("Bump seed: {}", bump_seed);
+    msg!("Buffer seed: {}", buffer_seed);
+    msg!("Authorities: {} {}", authority_a, authority_b);
+
+    Ok(())
+}
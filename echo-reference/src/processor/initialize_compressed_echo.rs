@@ -0,0 +1,118 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    state::{CompressedEchoHeader, COMPRESSED_ECHO_HEADER_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    compressed_buffer: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            compressed_buffer: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.compressed_buffer.is_writable {
+            msg!("Compressed buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    tree_id: Pubkey,
+    authority: Pubkey,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[b"compressed", tree_id.as_ref(), authority.as_ref()],
+        program_id,
+    );
+
+    if *ctx.compressed_buffer.key != pda {
+        msg!("Invalid compressed buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // the buffer only holds the header, the data itself stays off-chain
+    let create_account_ix = create_account(
+        ctx.payer.key,
+        ctx.compressed_buffer.key,
+        Rent::get()?.minimum_balance(COMPRESSED_ECHO_HEADER_SIZE),
+        COMPRESSED_ECHO_HEADER_SIZE as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.compressed_buffer.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[
+            b"compressed",
+            tree_id.as_ref(),
+            authority.as_ref(),
+            &[bump_seed],
+        ]],
+    )?;
+
+    let buffer_header = CompressedEchoHeader {
+        bump_seed,
+        authority,
+        tree_id,
+        leaf: [0; 32],
+        root: [0; 32],
+        leaf_index: 0,
+    };
+
+    ctx.compressed_buffer
+        .data
+        .borrow_mut()
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Bump seed: {}", bump_seed);
+    msg!("Tree: {}", tree_id);
+    msg!("Authority: {}", authority);
+
+    Ok(())
+}
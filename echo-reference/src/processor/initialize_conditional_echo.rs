@@ -0,0 +1,147 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    state::{ConditionalBufferHeader, CONDITIONAL_BUFF_HEADER_SIZE, MAX_CONDITION_VALUE_LEN},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    conditional_buffer: &'a AccountInfo<'b>,
+    condition_account: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            conditional_buffer: next_account_info(accounts_iter)?,
+            condition_account: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.conditional_buffer.is_writable {
+            msg!("Conditional buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    buffer_size: usize,
+    authority: Pubkey,
+    condition_offset: u16,
+    condition_value: Vec<u8>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // need at least enough for the buffer header
+    if buffer_size <= CONDITIONAL_BUFF_HEADER_SIZE {
+        msg!(
+            "Invalid buffer length {}, must be greater than header size {}",
+            buffer_size,
+            CONDITIONAL_BUFF_HEADER_SIZE
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    if condition_value.is_empty() || condition_value.len() > MAX_CONDITION_VALUE_LEN {
+        msg!(
+            "Invalid condition value length {}, must be between 1 and {}",
+            condition_value.len(),
+            MAX_CONDITION_VALUE_LEN
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"conditional",
+            authority.as_ref(),
+            ctx.condition_account.key.as_ref(),
+        ],
+        program_id,
+    );
+
+    if *ctx.conditional_buffer.key != pda {
+        msg!("Invalid conditional buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.payer.key,
+        ctx.conditional_buffer.key,
+        Rent::get()?.minimum_balance(buffer_size),
+        buffer_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.conditional_buffer.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[
+            b"conditional",
+            authority.as_ref(),
+            ctx.condition_account.key.as_ref(),
+            &[bump_seed],
+        ]],
+    )?;
+
+    // the full data buffer
+    let buffer = &mut (*ctx.conditional_buffer.data).borrow_mut();
+
+    // slice of the buffer used for the header
+    let mut buffer_header = ConditionalBufferHeader {
+        bump_seed,
+        authority,
+        condition_account: *ctx.condition_account.key,
+        condition_offset,
+        condition_value_len: condition_value.len() as u8,
+        condition_value: [0; MAX_CONDITION_VALUE_LEN],
+    };
+    buffer_header.condition_value[..condition_value.len()].copy_from_slice(&condition_value);
+
+    buffer[0..CONDITIONAL_BUFF_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Conditional buffer len: {}", buffer_size);
+    msg!("Bump seed: {}", bump_seed);
+    msg!("Condition account: {}", ctx.condition_account.key);
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    state::{FeeConfig, FEE_CONFIG_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    fee_config: &'a AccountInfo<'b>,
+    fee_vault: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            fee_config: next_account_info(accounts_iter)?,
+            fee_vault: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.fee_config.is_writable || !ctx.fee_vault.is_writable {
+            msg!("Fee config and fee vault accounts must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Creates the fee config and the fee vault, both unique for the program so this can only succeed once. It is
+/// meant to be run right after deploying the program.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], admin: Pubkey) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // verify that the PDA accounts are the correct addresses
+    let (config_pda, bump_seed) = Pubkey::find_program_address(&[b"fee_config"], program_id);
+    let (vault_pda, vault_bump_seed) = Pubkey::find_program_address(&[b"fee_vault"], program_id);
+
+    if *ctx.fee_config.key != config_pda || *ctx.fee_vault.key != vault_pda {
+        msg!("Invalid fee config or fee vault address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &create_account(
+            ctx.payer.key,
+            ctx.fee_config.key,
+            rent.minimum_balance(FEE_CONFIG_SIZE),
+            FEE_CONFIG_SIZE as u64,
+            program_id,
+        ),
+        &[
+            ctx.fee_config.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"fee_config", &[bump_seed]]],
+    )?;
+
+    // the vault holds no data, owning it lets the program move its lamports on withdrawal
+    invoke_signed(
+        &create_account(
+            ctx.payer.key,
+            ctx.fee_vault.key,
+            rent.minimum_balance(0),
+            0,
+            program_id,
+        ),
+        &[
+            ctx.fee_vault.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"fee_vault", &[vault_bump_seed]]],
+    )?;
+
+    let fee_config = FeeConfig {
+        admin,
+        bump_seed,
+        vault_bump_seed,
+    };
+
+    ctx.fee_config.data.borrow_mut()[..FEE_CONFIG_SIZE]
+        .copy_from_slice(&fee_config.try_to_vec().unwrap());
+
+    msg!("Fee admin: {}", admin);
+
+    Ok(())
+}
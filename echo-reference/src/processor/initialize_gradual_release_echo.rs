@@ -0,0 +1,139 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    processor::gradual_release_echo::apply_mask,
+    state::{GradualReleaseHeader, GRADUAL_RELEASE_HEADER_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    gradual_release_buffer: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            gradual_release_buffer: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.gradual_release_buffer.is_writable {
+            msg!("Gradual release buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    total_slots: u64,
+    data: Vec<u8>,
+    authority: Pubkey,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if total_slots == 0 || data.is_empty() {
+        msg!(
+            "Invalid release of {} bytes over {} slots, both must be positive",
+            data.len(),
+            total_slots
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    let total_bytes = u32::try_from(data.len()).map_err(|_| EchoError::Overflow)?;
+    let reveal_start_slot = Clock::get()?.slot;
+    let reveal_end_slot = reveal_start_slot
+        .checked_add(total_slots)
+        .ok_or(EchoError::Overflow)?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"gradual_release", authority.as_ref()], program_id);
+
+    if *ctx.gradual_release_buffer.key != pda {
+        msg!("Invalid gradual release buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // the masked data is followed by a region of the same size the reveals write to
+    let buffer_size = GRADUAL_RELEASE_HEADER_SIZE + 2 * data.len();
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.payer.key,
+        ctx.gradual_release_buffer.key,
+        Rent::get()?.minimum_balance(buffer_size),
+        buffer_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.gradual_release_buffer.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"gradual_release", authority.as_ref(), &[bump_seed]]],
+    )?;
+
+    // the full data buffer
+    let buffer = &mut (*ctx.gradual_release_buffer.data).borrow_mut();
+
+    // slice of the buffer used for the header
+    let buffer_header = GradualReleaseHeader {
+        bump_seed,
+        authority,
+        reveal_start_slot,
+        reveal_end_slot,
+        total_bytes,
+        revealed_bytes: 0,
+    };
+
+    buffer[0..GRADUAL_RELEASE_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    let masked = &mut buffer[GRADUAL_RELEASE_HEADER_SIZE..GRADUAL_RELEASE_HEADER_SIZE + data.len()];
+    masked.copy_from_slice(&data);
+    apply_mask(&pda, reveal_start_slot, 0, masked);
+
+    msg!("Gradual release buffer len: {}", buffer_size);
+    msg!("Total bytes: {}", total_bytes);
+    msg!("Reveal slots: {}-{}", reveal_start_slot, reveal_end_slot);
+    msg!("Bump seed: {}", bump_seed);
+    msg!("Authority: {}", authority);
+
+    Ok(())
+}
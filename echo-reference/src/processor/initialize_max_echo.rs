@@ -0,0 +1,98 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    state::{MaxHeader, MAX_ECHO_HEADER_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    max_account: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            max_account: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.max_account.is_writable {
+            msg!("Max account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], authority: Pubkey) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(&[b"max", authority.as_ref()], program_id);
+
+    if *ctx.max_account.key != pda {
+        msg!("Invalid max account address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.payer.key,
+        ctx.max_account.key,
+        Rent::get()?.minimum_balance(MAX_ECHO_HEADER_SIZE),
+        MAX_ECHO_HEADER_SIZE as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.max_account.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"max", authority.as_ref(), &[bump_seed]]],
+    )?;
+
+    let header = MaxHeader {
+        current_max: 0,
+        update_count: 0,
+        bump_seed,
+    };
+
+    ctx.max_account.data.borrow_mut()[..MAX_ECHO_HEADER_SIZE]
+        .copy_from_slice(&header.try_to_vec().unwrap());
+
+    msg!("Bump seed: {}", bump_seed);
+
+    Ok(())
+}
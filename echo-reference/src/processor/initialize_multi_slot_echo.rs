@@ -0,0 +1,138 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    state::{MultiSlotHeader, MAX_MULTI_SLOT_CHUNKS, MULTI_SLOT_HEADER_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    multi_slot_buffer: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            multi_slot_buffer: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.multi_slot_buffer.is_writable {
+            msg!("Multi slot buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    total_size: u32,
+    chunk_size: u16,
+    authority: Pubkey,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if total_size == 0 || chunk_size == 0 {
+        msg!(
+            "Invalid multi slot buffer of {} bytes in chunks of {} bytes, both must be non-zero",
+            total_size,
+            chunk_size
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    let total_chunks = (total_size as u64).div_ceil(chunk_size as u64);
+
+    if total_chunks > MAX_MULTI_SLOT_CHUNKS as u64 {
+        msg!(
+            "Invalid multi slot buffer of {} chunks, must be at most {}",
+            total_chunks,
+            MAX_MULTI_SLOT_CHUNKS
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    let buffer_size = MULTI_SLOT_HEADER_SIZE + total_size as usize;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"multi_slot", authority.as_ref()], program_id);
+
+    if *ctx.multi_slot_buffer.key != pda {
+        msg!("Invalid multi slot buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.payer.key,
+        ctx.multi_slot_buffer.key,
+        Rent::get()?.minimum_balance(buffer_size),
+        buffer_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.multi_slot_buffer.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"multi_slot", authority.as_ref(), &[bump_seed]]],
+    )?;
+
+    // the full data buffer
+    let buffer = &mut (*ctx.multi_slot_buffer.data).borrow_mut();
+
+    // slice of the buffer used for the header
+    let buffer_header = MultiSlotHeader {
+        total_size,
+        chunk_size,
+        chunks_written: 0,
+        total_chunks: total_chunks as u16,
+        committed: false,
+        bump_seed,
+        written_chunks: 0,
+    };
+
+    buffer[0..MULTI_SLOT_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Total size: {}", total_size);
+    msg!("Chunk size: {}", chunk_size);
+    msg!("Total chunks: {}", total_chunks);
+    msg!("Bump seed: {}", bump_seed);
+    msg!("Authority: {}", authority);
+
+    Ok(())
+}
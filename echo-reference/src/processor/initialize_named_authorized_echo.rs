@@ -0,0 +1,148 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    state::{NamedAuthorizedBufferHeader, MAX_BUFFER_NAME_LEN, NAMED_AUTH_BUFF_HEADER_SIZE},
+};
+
+use borsh::BorshSerialize;
+use std::mem::size_of;
+
+struct Context<'a, 'b: 'a> {
+    authorized_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            authorized_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.authorized_buffer.is_writable {
+            msg!("Authorized Echo Buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    name: String,
+    buffer_size: usize,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // need at least enough for the buffer header
+    if buffer_size <= NAMED_AUTH_BUFF_HEADER_SIZE {
+        msg!(
+            "Invalid buffer length {}, must be greater than header size {}",
+            buffer_size,
+            NAMED_AUTH_BUFF_HEADER_SIZE
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    if name.len() > MAX_BUFFER_NAME_LEN {
+        msg!(
+            "Invalid name length {}, must be at most {} bytes",
+            name.len(),
+            MAX_BUFFER_NAME_LEN
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    if name.trim().is_empty() {
+        msg!("Buffer name must not be empty or whitespace");
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // the seeds of an 8 byte name are the seeds of the numeric buffer seed with the same bytes
+    if name.len() == size_of::<u64>() {
+        msg!("Buffer name must not be 8 bytes long, it would share the address of a buffer seed");
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[b"authority", ctx.authority.key.as_ref(), name.as_bytes()],
+        program_id,
+    );
+
+    if *ctx.authorized_buffer.key != pda {
+        msg!("Invalid authorized buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.authority.key,
+        ctx.authorized_buffer.key,
+        Rent::get()?.minimum_balance(buffer_size),
+        buffer_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.authorized_buffer.clone(),
+            ctx.authority.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[
+            b"authority",
+            ctx.authority.key.as_ref(),
+            name.as_bytes(),
+            &[bump_seed],
+        ]],
+    )?;
+
+    // the full data buffer
+    let buffer = &mut (*ctx.authorized_buffer.data).borrow_mut();
+
+    // slice of the buffer used for the header
+    let mut buffer_header = NamedAuthorizedBufferHeader {
+        bump_seed,
+        name_len: name.len() as u8,
+        name: [0; MAX_BUFFER_NAME_LEN],
+    };
+    buffer_header.name[..name.len()].copy_from_slice(name.as_bytes());
+
+    buffer[0..NAMED_AUTH_BUFF_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Authorized buffer len: {}", buffer_size);
+    msg!("Bump seed: {}", bump_seed);
+    msg!("Buffer name: {}", name);
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    processor::vending_machine_echo::unpack_token_account,
+    state::{OwnershipEchoHeader, OWNERSHIP_ECHO_HEADER_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    ownership_buffer: &'a AccountInfo<'b>,
+    owner: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    asset_token_account: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            ownership_buffer: next_account_info(accounts_iter)?,
+            owner: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+            asset_token_account: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.ownership_buffer.is_writable {
+            msg!("Ownership buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.owner.is_writable {
+            msg!("Owner account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.owner.is_signer {
+            msg!("Owner must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    buffer_size: usize,
+    asset: Pubkey,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // need at least enough for the buffer header
+    if buffer_size <= OWNERSHIP_ECHO_HEADER_SIZE {
+        msg!(
+            "Invalid buffer length {}, must be greater than header size {}",
+            buffer_size,
+            OWNERSHIP_ECHO_HEADER_SIZE
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // there is a single record per asset, only a holder of the asset can create it and name themselves its owner
+    let asset_token_account = unpack_token_account(ctx.asset_token_account, &spl_token::id())?;
+
+    if asset_token_account.mint != asset {
+        msg!("Token account must hold the asset {}", asset);
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    if asset_token_account.owner != *ctx.owner.key || asset_token_account.amount == 0 {
+        msg!("Owner {} doesn't hold the asset {}", ctx.owner.key, asset);
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"ownership", asset.as_ref()], program_id);
+
+    if *ctx.ownership_buffer.key != pda {
+        msg!("Invalid ownership buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.owner.key,
+        ctx.ownership_buffer.key,
+        Rent::get()?.minimum_balance(buffer_size),
+        buffer_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.ownership_buffer.clone(),
+            ctx.owner.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"ownership", asset.as_ref(), &[bump_seed]]],
+    )?;
+
+    // slice of the buffer used for the header
+    let buffer_header = OwnershipEchoHeader {
+        bump_seed,
+        asset,
+        current_owner: *ctx.owner.key,
+        transfer_count: 0,
+    };
+
+    ctx.ownership_buffer.data.borrow_mut()[..OWNERSHIP_ECHO_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Ownership buffer len: {}", buffer_size);
+    msg!("Bump seed: {}", bump_seed);
+    msg!("Asset: {}", asset);
+    msg!("Owner: {}", ctx.owner.key);
+
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    processor::vending_machine_echo::assert_uninitialized,
+    state::{
+        PriceTier, VendingMachineBufferHeader, MAX_PRICE_TIERS, PAYMENT_MODE_BURN,
+        VENDING_MACHINE_BUFF_HEADER_SIZE,
+    },
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    vending_machine_buffer: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            vending_machine_buffer: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.vending_machine_buffer.is_writable {
+            msg!("Vending machine buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], buffer_size: usize) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // need at least enough for the buffer header
+    if buffer_size <= VENDING_MACHINE_BUFF_HEADER_SIZE {
+        msg!(
+            "Invalid buffer length {}, must be greater than header size {}",
+            buffer_size,
+            VENDING_MACHINE_BUFF_HEADER_SIZE
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"free_vm", ctx.payer.key.as_ref()], program_id);
+
+    if *ctx.vending_machine_buffer.key != pda {
+        msg!("Invalid vending machine buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    assert_uninitialized(program_id, ctx.vending_machine_buffer)?;
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.payer.key,
+        ctx.vending_machine_buffer.key,
+        Rent::get()?.minimum_balance(buffer_size),
+        buffer_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.vending_machine_buffer.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"free_vm", ctx.payer.key.as_ref(), &[bump_seed]]],
+    )?;
+
+    // the full data buffer
+    let buffer = &mut (*ctx.vending_machine_buffer.data).borrow_mut();
+
+    // slice of the buffer used for the header
+    let buffer_header = VendingMachineBufferHeader {
+        bump_seed,
+        price: 0,
+        free: true,
+        payment_mode: PAYMENT_MODE_BURN,
+        treasury: Pubkey::default(),
+        admin: *ctx.payer.key,
+        paused: false,
+        price_per_byte: 0,
+        require_ata: false,
+        token_program: Pubkey::default(),
+        mint: Pubkey::default(),
+        uses: 0,
+        max_uses: 0,
+        sol_fee: 0,
+        tier_count: 0,
+        tiers: [PriceTier::default(); MAX_PRICE_TIERS],
+        last_writer: Pubkey::default(),
+        last_write_slot: 0,
+        expiry_slot: 0,
+        total_writes: 0,
+        total_burned: 0,
+        data_len: 0,
+        is_initialized: true,
+        start_slot: 0,
+        end_slot: 0,
+        cooldown_slots: 0,
+        whitelist_enabled: false,
+        free_authority_writes: false,
+    };
+
+    buffer[0..VENDING_MACHINE_BUFF_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Vending machine buffer len: {}", buffer_size);
+    msg!("Bump seed: {}", bump_seed);
+    msg!("Creator: {}", ctx.payer.key);
+
+    Ok(())
+}
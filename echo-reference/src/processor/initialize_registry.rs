@@ -0,0 +1,108 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    state::{RegistryHeader, REGISTRY_ENTRY_SIZE, REGISTRY_HEADER_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    registry: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            registry: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.registry.is_writable {
+            msg!("Registry account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], capacity: u16) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if capacity == 0 {
+        msg!("Invalid registry capacity, must be non-zero");
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // room for `capacity` entries after the header
+    let registry_size = REGISTRY_HEADER_SIZE + capacity as usize * REGISTRY_ENTRY_SIZE;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"registry", ctx.authority.key.as_ref()], program_id);
+
+    if *ctx.registry.key != pda {
+        msg!("Invalid registry address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.authority.key,
+        ctx.registry.key,
+        Rent::get()?.minimum_balance(registry_size),
+        registry_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.registry.clone(),
+            ctx.authority.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"registry", ctx.authority.key.as_ref(), &[bump_seed]]],
+    )?;
+
+    let header = RegistryHeader {
+        count: 0,
+        capacity,
+        bump_seed,
+    };
+
+    ctx.registry.data.borrow_mut()[..REGISTRY_HEADER_SIZE]
+        .copy_from_slice(&header.try_to_vec().unwrap());
+
+    msg!("Registry capacity: {}", capacity);
+    msg!("Bump seed: {}", bump_seed);
+
+    Ok(())
+}
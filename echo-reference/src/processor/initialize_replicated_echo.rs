@@ -0,0 +1,166 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    state::{
+        ReplicatedEchoHeader, REPLICATED_ECHO_HEADER_SIZE, REPLICA_ROLE_PRIMARY,
+        REPLICA_ROLE_REPLICA,
+    },
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    primary_buffer: &'a AccountInfo<'b>,
+    replica_buffer: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            primary_buffer: next_account_info(accounts_iter)?,
+            replica_buffer: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.primary_buffer.is_writable || !ctx.replica_buffer.is_writable {
+            msg!("Primary and replica buffer accounts must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Allocates the buffer of `role` in the pair and writes its header.
+fn create_replicated_buffer<'b>(
+    program_id: &Pubkey,
+    ctx: &Context<'_, 'b>,
+    buffer: &AccountInfo<'b>,
+    role: u8,
+    primary_authority: Pubkey,
+    replica_authority: Pubkey,
+    buffer_size: usize,
+) -> ProgramResult {
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"replicated",
+            primary_authority.as_ref(),
+            replica_authority.as_ref(),
+            &[role],
+        ],
+        program_id,
+    );
+
+    if *buffer.key != pda {
+        msg!("Invalid replicated buffer address for role {}", role);
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.payer.key,
+        buffer.key,
+        Rent::get()?.minimum_balance(buffer_size),
+        buffer_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            buffer.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[
+            b"replicated",
+            primary_authority.as_ref(),
+            replica_authority.as_ref(),
+            &[role],
+            &[bump_seed],
+        ]],
+    )?;
+
+    // slice of the buffer used for the header
+    let buffer_header = ReplicatedEchoHeader {
+        bump_seed,
+        role,
+        primary_authority,
+        replica_authority,
+        frozen: false,
+    };
+
+    buffer.data.borrow_mut()[..REPLICATED_ECHO_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Bump seed of role {}: {}", role, bump_seed);
+
+    Ok(())
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    primary_authority: Pubkey,
+    replica_authority: Pubkey,
+    buffer_size: usize,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // need at least enough for the buffer header
+    if buffer_size <= REPLICATED_ECHO_HEADER_SIZE {
+        msg!(
+            "Invalid buffer length {}, must be greater than header size {}",
+            buffer_size,
+            REPLICATED_ECHO_HEADER_SIZE
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    for (buffer, role) in [
+        (ctx.primary_buffer, REPLICA_ROLE_PRIMARY),
+        (ctx.replica_buffer, REPLICA_ROLE_REPLICA),
+    ] {
+        create_replicated_buffer(
+            program_id,
+            &ctx,
+            buffer,
+            role,
+            primary_authority,
+            replica_authority,
+            buffer_size,
+        )?;
+    }
+
+    msg!("Replicated buffer len: {}", buffer_size);
+
+    Ok(())
+}
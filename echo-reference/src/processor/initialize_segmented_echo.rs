@@ -0,0 +1,129 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    state::{SegmentedHeader, SEGMENTED_HEADER_SIZE, SEGMENT_BITMAP_LEN},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    segmented_buffer: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            segmented_buffer: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.segmented_buffer.is_writable {
+            msg!("Segmented buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    segment_size: u32,
+    segment_count: u8,
+    authority: Pubkey,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if segment_size == 0 || segment_count == 0 {
+        msg!(
+            "Invalid segmented buffer with {} segments of {} bytes, both must be non-zero",
+            segment_count,
+            segment_size
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    let buffer_header = SegmentedHeader {
+        bump_seed: 0,
+        segment_size,
+        segment_count,
+        bitmap: [0; SEGMENT_BITMAP_LEN],
+    };
+
+    // `segment_count` segments of `segment_size` bytes after the header
+    let buffer_size = SEGMENTED_HEADER_SIZE + segment_count as usize * segment_size as usize;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"segmented", authority.as_ref()], program_id);
+
+    if *ctx.segmented_buffer.key != pda {
+        msg!("Invalid segmented buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.payer.key,
+        ctx.segmented_buffer.key,
+        Rent::get()?.minimum_balance(buffer_size),
+        buffer_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.segmented_buffer.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"segmented", authority.as_ref(), &[bump_seed]]],
+    )?;
+
+    // the full data buffer
+    let buffer = &mut (*ctx.segmented_buffer.data).borrow_mut();
+
+    // slice of the buffer used for the header
+    let buffer_header = SegmentedHeader {
+        bump_seed,
+        ..buffer_header
+    };
+
+    buffer[0..SEGMENTED_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Segment count: {}", segment_count);
+    msg!("Segment size: {}", segment_size);
+    msg!("Bump seed: {}", bump_seed);
+    msg!("Authority: {}", authority);
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    processor::sol_vending_machine_echo::vault_address,
+    state::{SolVendingMachineHeader, SOL_VENDING_MACHINE_HEADER_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    vending_machine_buffer: &'a AccountInfo<'b>,
+    admin: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            vending_machine_buffer: next_account_info(accounts_iter)?,
+            admin: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.vending_machine_buffer.is_writable {
+            msg!("Vending machine buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.admin.is_signer {
+            msg!("Admin must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    price_lamports: u64,
+    buffer_size: usize,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // need at least enough for the buffer header
+    if buffer_size <= SOL_VENDING_MACHINE_HEADER_SIZE {
+        msg!(
+            "Invalid buffer length {}, must be greater than header size {}",
+            buffer_size,
+            SOL_VENDING_MACHINE_HEADER_SIZE
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"sol_vending_machine",
+            ctx.admin.key.as_ref(),
+            &price_lamports.to_le_bytes(),
+        ],
+        program_id,
+    );
+
+    if *ctx.vending_machine_buffer.key != pda {
+        msg!("Invalid vending machine buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.admin.key,
+        ctx.vending_machine_buffer.key,
+        Rent::get()?.minimum_balance(buffer_size),
+        buffer_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.vending_machine_buffer.clone(),
+            ctx.admin.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[
+            b"sol_vending_machine",
+            ctx.admin.key.as_ref(),
+            &price_lamports.to_le_bytes(),
+            &[bump_seed],
+        ]],
+    )?;
+
+    let (vault, vault_bump_seed) = vault_address(program_id, ctx.vending_machine_buffer.key);
+
+    // the full data buffer
+    let buffer = &mut (*ctx.vending_machine_buffer.data).borrow_mut();
+
+    // slice of the buffer used for the header
+    let buffer_header = SolVendingMachineHeader {
+        bump_seed,
+        vault_bump_seed,
+        price_lamports,
+        admin: *ctx.admin.key,
+    };
+
+    buffer[0..SOL_VENDING_MACHINE_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Vending machine buffer len: {}", buffer_size);
+    msg!("Bump seed: {}", bump_seed);
+    msg!("Price: {} lamports", price_lamports);
+    msg!("Vault: {}", vault);
+
+    Ok(())
+}
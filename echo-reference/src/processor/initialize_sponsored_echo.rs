@@ -0,0 +1,75 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+};
+
+use crate::{
+    error::EchoError,
+    processor::{
+        initialize_authorized_echo::create_authorized_buffer_signed, sponsor::check_sponsor,
+    },
+    state::AuthorizedBufferHeader,
+};
+
+struct Context<'a, 'b: 'a> {
+    authorized_buffer: &'a AccountInfo<'b>,
+    sponsor: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            authorized_buffer: next_account_info(accounts_iter)?,
+            sponsor: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.authorized_buffer.is_writable {
+            msg!("Authorized Echo Buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Allocates the authorized buffer of `authority` at buffer seed 0, with the rent paid by its sponsor PDA instead
+/// of a signer of the transaction.
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    buffer_size: usize,
+    authority: Pubkey,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let sponsor_bump_seed = check_sponsor(program_id, ctx.sponsor, &authority)?;
+
+    create_authorized_buffer_signed(
+        program_id,
+        ctx.authorized_buffer,
+        &authority,
+        ctx.sponsor,
+        ctx.system_program,
+        buffer_size,
+        AuthorizedBufferHeader::default(),
+        &[&[b"sponsor", authority.as_ref(), &[sponsor_bump_seed]]],
+    )?;
+
+    msg!("Authority: {}", authority);
+    msg!("Sponsor: {}", ctx.sponsor.key);
+
+    Ok(())
+}
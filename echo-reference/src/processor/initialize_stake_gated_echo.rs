@@ -0,0 +1,131 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    state::{StakeGatedHeader, STAKE_GATED_HEADER_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    stake_gated_buffer: &'a AccountInfo<'b>,
+    creator: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            stake_gated_buffer: next_account_info(accounts_iter)?,
+            creator: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.stake_gated_buffer.is_writable {
+            msg!("Stake gated buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.creator.is_signer {
+            msg!("Creator must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    buffer_size: usize,
+    vote_account: Pubkey,
+    min_stake: u64,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // need at least enough for the buffer header
+    if buffer_size <= STAKE_GATED_HEADER_SIZE {
+        msg!(
+            "Invalid buffer length {}, must be greater than header size {}",
+            buffer_size,
+            STAKE_GATED_HEADER_SIZE
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"stake_gated",
+            ctx.creator.key.as_ref(),
+            vote_account.as_ref(),
+        ],
+        program_id,
+    );
+
+    if *ctx.stake_gated_buffer.key != pda {
+        msg!("Invalid stake gated buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.creator.key,
+        ctx.stake_gated_buffer.key,
+        Rent::get()?.minimum_balance(buffer_size),
+        buffer_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.stake_gated_buffer.clone(),
+            ctx.creator.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[
+            b"stake_gated",
+            ctx.creator.key.as_ref(),
+            vote_account.as_ref(),
+            &[bump_seed],
+        ]],
+    )?;
+
+    // slice of the buffer used for the header
+    let buffer_header = StakeGatedHeader {
+        bump_seed,
+        creator: *ctx.creator.key,
+        vote_account,
+        min_stake,
+    };
+
+    ctx.stake_gated_buffer.data.borrow_mut()[..STAKE_GATED_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Stake gated buffer len: {}", buffer_size);
+    msg!("Bump seed: {}", bump_seed);
+    msg!("Vote account: {}", vote_account);
+    msg!("Min stake: {}", min_stake);
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use borsh::BorshSerialize;
+
+use crate::{
+    error::EchoError,
+    state::{FeeTreasury, FEE_TREASURY_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    treasury: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            treasury: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.treasury.is_writable {
+            msg!("Treasury account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Address and bump seed of the fee treasury collecting the SOL fees of vending machines, unique for the program.
+pub(crate) fn treasury_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"treasury", program_id.as_ref()], program_id)
+}
+
+/// Creates the fee treasury, unique for the program so this can only succeed once. The fees are collected in the
+/// lamports of the account itself, owning it lets the program move them when draining.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], authority: Pubkey) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = treasury_address(program_id);
+
+    if *ctx.treasury.key != pda {
+        msg!("Invalid treasury address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    invoke_signed(
+        &create_account(
+            ctx.payer.key,
+            ctx.treasury.key,
+            Rent::get()?.minimum_balance(FEE_TREASURY_SIZE),
+            FEE_TREASURY_SIZE as u64,
+            program_id,
+        ),
+        &[
+            ctx.treasury.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"treasury", program_id.as_ref(), &[bump_seed]]],
+    )?;
+
+    let treasury = FeeTreasury {
+        bump_seed,
+        authority,
+    };
+
+    ctx.treasury.data.borrow_mut()[..FEE_TREASURY_SIZE]
+        .copy_from_slice(&treasury.try_to_vec().unwrap());
+
+    msg!("Treasury authority: {}", authority);
+
+    Ok(())
+}
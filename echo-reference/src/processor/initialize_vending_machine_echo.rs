@@ -1,21 +1,27 @@
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
     msg,
     program::invoke_signed,
     program_error::ProgramError,
-    program_pack::Pack,
+    program_option::COption,
     pubkey::Pubkey,
     rent::Rent,
     system_instruction::create_account,
     system_program::ID as SYSTEM_PROGRAM_ID,
     sysvar::Sysvar,
 };
-use spl_token::state::Mint;
 
 use crate::{
     error::EchoError,
-    state::{VendingMachineBufferHeader, VENDING_MACHINE_BUFF_HEADER_SIZE},
+    processor::vending_machine_echo::{
+        assert_uninitialized, is_token_program, unpack_mint, unpack_token_account,
+    },
+    state::{
+        PriceTier, VendingMachineBufferHeader, MAX_PRICE_TIERS, PAYMENT_MODE_BURN,
+        PAYMENT_MODE_TREASURY, VENDING_MACHINE_BUFF_HEADER_SIZE,
+    },
 };
 
 use borsh::BorshSerialize;
@@ -23,8 +29,10 @@ use borsh::BorshSerialize;
 struct Context<'a, 'b: 'a> {
     vending_machine_buffer: &'a AccountInfo<'b>,
     vending_machine_mint: &'a AccountInfo<'b>,
+    mint_authority: &'a AccountInfo<'b>,
     payer: &'a AccountInfo<'b>,
     system_program: &'a AccountInfo<'b>,
+    treasury: Option<&'a AccountInfo<'b>>,
 }
 
 impl<'a, 'b: 'a> Context<'a, 'b> {
@@ -34,8 +42,10 @@ impl<'a, 'b: 'a> Context<'a, 'b> {
         let ctx = Self {
             vending_machine_buffer: next_account_info(accounts_iter)?,
             vending_machine_mint: next_account_info(accounts_iter)?,
+            mint_authority: next_account_info(accounts_iter)?,
             payer: next_account_info(accounts_iter)?,
             system_program: next_account_info(accounts_iter)?,
+            treasury: accounts_iter.next(),
         };
 
         if !ctx.vending_machine_buffer.is_writable {
@@ -43,6 +53,11 @@ impl<'a, 'b: 'a> Context<'a, 'b> {
             return Err(EchoError::AccountMustBeWritable.into());
         }
 
+        if !ctx.mint_authority.is_signer {
+            msg!("Mint authority must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
         if !ctx.payer.is_signer {
             msg!("Payer must be signer");
             return Err(EchoError::MissingRequiredSignature.into());
@@ -57,11 +72,65 @@ impl<'a, 'b: 'a> Context<'a, 'b> {
     }
 }
 
+/// Checks that `treasury` is a token account of `mint`, so payments can be transferred to it.
+fn validate_treasury(
+    treasury: &AccountInfo,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> ProgramResult {
+    let treasury_account = unpack_token_account(treasury, token_program)?;
+
+    if treasury_account.mint != *mint {
+        msg!("Invalid treasury mint");
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    Ok(())
+}
+
+/// Checks that there are at most `MAX_PRICE_TIERS` tiers sorted by strictly increasing `max_len`, and returns them
+/// padded with empty tiers.
+fn validate_tiers(tiers: &[PriceTier]) -> Result<[PriceTier; MAX_PRICE_TIERS], ProgramError> {
+    if tiers.len() > MAX_PRICE_TIERS {
+        msg!(
+            "At most {} price tiers, got {}",
+            MAX_PRICE_TIERS,
+            tiers.len()
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    if tiers
+        .windows(2)
+        .any(|pair| pair[0].max_len >= pair[1].max_len)
+    {
+        msg!("Price tiers must have strictly increasing max_len");
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    let mut price_tiers = [PriceTier::default(); MAX_PRICE_TIERS];
+    price_tiers[..tiers.len()].copy_from_slice(tiers);
+
+    Ok(price_tiers)
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn process(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     price: u64,
     buffer_size: usize,
+    payment_mode: u8,
+    price_per_byte: u64,
+    require_ata: bool,
+    max_uses: u64,
+    sol_fee: u64,
+    tiers: Vec<PriceTier>,
+    expiry_slot: u64,
+    start_slot: u64,
+    end_slot: u64,
+    cooldown_slots: u64,
+    free_authority_writes: bool,
 ) -> ProgramResult {
     let ctx = Context::parse(accounts)?;
 
@@ -75,10 +144,43 @@ pub fn process(
         return Err(EchoError::InvalidInstructionInput.into());
     }
 
-    let _mint = Mint::unpack_unchecked(&ctx.vending_machine_mint.data.borrow()).map_err(|e| {
-        msg!("Invalid mint account");
-        return e;
-    })?;
+    let price_tiers = validate_tiers(&tiers)?;
+
+    // the token program of the machine is the one owning its mint
+    let token_program = *ctx.vending_machine_mint.owner;
+
+    if !is_token_program(&token_program) {
+        msg!(
+            "Invalid mint {}, not owned by a token program",
+            ctx.vending_machine_mint.key
+        );
+        return Err(EchoError::InvalidMint.into());
+    }
+
+    let mint = unpack_mint(ctx.vending_machine_mint, &token_program)?;
+
+    // only the mint authority decides the price of writes paid with its tokens
+    if mint.mint_authority != COption::Some(*ctx.mint_authority.key) {
+        msg!("Invalid mint authority");
+        return Err(EchoError::InvalidMintAuthority.into());
+    }
+
+    let treasury = match payment_mode {
+        PAYMENT_MODE_BURN => Pubkey::default(),
+        PAYMENT_MODE_TREASURY => {
+            let treasury = ctx.treasury.ok_or_else(|| {
+                msg!("The treasury token account must be passed in treasury mode");
+                ProgramError::NotEnoughAccountKeys
+            })?;
+            validate_treasury(treasury, ctx.vending_machine_mint.key, &token_program)?;
+            *treasury.key
+        }
+        _ => {
+            msg!("Invalid payment mode {}", payment_mode);
+            return Err(EchoError::InvalidInstructionInput.into());
+        }
+    };
+
     // verify that the PDA account is the correct address
     let (pda, bump_seed) = Pubkey::find_program_address(
         &[
@@ -94,6 +196,8 @@ pub fn process(
         return Err(EchoError::InvalidAccountAddress.into());
     }
 
+    assert_uninitialized(program_id, ctx.vending_machine_buffer)?;
+
     // call the system program to create the account
     let create_account_ix = create_account(
         &ctx.payer.key,
@@ -122,7 +226,36 @@ pub fn process(
     let buffer = &mut (*ctx.vending_machine_buffer.data).borrow_mut();
 
     // slice of the buffer used for the header
-    let buffer_header = VendingMachineBufferHeader { bump_seed, price };
+    let buffer_header = VendingMachineBufferHeader {
+        bump_seed,
+        price,
+        free: false,
+        payment_mode,
+        treasury,
+        admin: *ctx.mint_authority.key,
+        paused: false,
+        price_per_byte,
+        require_ata,
+        token_program,
+        mint: *ctx.vending_machine_mint.key,
+        uses: 0,
+        max_uses,
+        sol_fee,
+        tier_count: tiers.len() as u8,
+        tiers: price_tiers,
+        last_writer: Pubkey::default(),
+        last_write_slot: 0,
+        expiry_slot,
+        total_writes: 0,
+        total_burned: 0,
+        data_len: 0,
+        is_initialized: true,
+        start_slot,
+        end_slot,
+        cooldown_slots,
+        whitelist_enabled: false,
+        free_authority_writes,
+    };
 
     buffer[0..VENDING_MACHINE_BUFF_HEADER_SIZE]
         .copy_from_slice(&buffer_header.try_to_vec().unwrap());
@@ -130,6 +263,120 @@ pub fn process(
     msg!("Vending machine buffer len: {}", buffer_size);
     msg!("Bump seed: {}", bump_seed);
     msg!("Price: {}", price);
+    if price_per_byte > 0 {
+        msg!("Price per byte: {}", price_per_byte);
+    }
+    if require_ata {
+        msg!("Requires associated token accounts");
+    }
+    if max_uses > 0 {
+        msg!("Max uses: {}", max_uses);
+    }
+    if sol_fee > 0 {
+        msg!("SOL fee: {} lamports", sol_fee);
+    }
+    for tier in &tiers {
+        msg!("Tier up to {} bytes: {}", tier.max_len, tier.price);
+    }
+    if expiry_slot > 0 {
+        msg!("Expiry slot: {}", expiry_slot);
+    }
+    if start_slot > 0 {
+        msg!("Start slot: {}", start_slot);
+    }
+    if end_slot > 0 {
+        msg!("End slot: {}", end_slot);
+    }
+    if cooldown_slots > 0 {
+        msg!("Cooldown: {} slots", cooldown_slots);
+    }
+    if payment_mode == PAYMENT_MODE_TREASURY {
+        msg!("Treasury: {}", treasury);
+    }
 
     Ok(())
 }
+
+/// Initializes a vending machine in burn mode that expires at `expiry_slot`, `mint` must be the mint account passed.
+pub fn process_expiring(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    buffer_size: usize,
+    price: u64,
+    mint: Pubkey,
+    expiry_slot: u64,
+) -> ProgramResult {
+    let vending_machine_mint = accounts.get(1).ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    if *vending_machine_mint.key != mint {
+        msg!("The mint {} must be passed", mint);
+        return Err(EchoError::MintMismatch.into());
+    }
+
+    // a machine that is already expired could be claimed right away
+    let slot = Clock::get()?.slot;
+
+    if expiry_slot <= slot {
+        msg!(
+            "Expiry slot {} must be after the current slot {}",
+            expiry_slot,
+            slot
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    process(
+        program_id,
+        accounts,
+        price,
+        buffer_size,
+        PAYMENT_MODE_BURN,
+        0,
+        false,
+        0,
+        0,
+        vec![],
+        expiry_slot,
+        0,
+        0,
+        0,
+        false,
+    )
+}
+
+/// Initializes a vending machine in burn mode that only accepts paid writes from `start_slot` until `end_slot`.
+pub fn process_windowed(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    buffer_size: usize,
+    price: u64,
+    start_slot: u64,
+    end_slot: u64,
+) -> ProgramResult {
+    if end_slot > 0 && end_slot <= start_slot {
+        msg!(
+            "End slot {} must be after the start slot {}",
+            end_slot,
+            start_slot
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    process(
+        program_id,
+        accounts,
+        price,
+        buffer_size,
+        PAYMENT_MODE_BURN,
+        0,
+        false,
+        0,
+        0,
+        vec![],
+        0,
+        start_slot,
+        end_slot,
+        0,
+        false,
+    )
+}
@@ -0,0 +1,147 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    state::{VotedEchoHeader, MAX_VOTERS, VOTED_ECHO_HEADER_SIZE},
+};
+
+use borsh::BorshSerialize;
+
+struct Context<'a, 'b: 'a> {
+    voted_buffer: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            voted_buffer: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.voted_buffer.is_writable {
+            msg!("Voted buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    buffer_size: usize,
+    voters: Vec<Pubkey>,
+    quorum: u8,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if buffer_size == 0 {
+        msg!("Invalid buffer length 0, must be non-zero");
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    if voters.is_empty() || voters.len() > MAX_VOTERS {
+        msg!(
+            "Invalid number of voters {}, must be between 1 and {}",
+            voters.len(),
+            MAX_VOTERS
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    if (1..voters.len()).any(|i| voters[..i].contains(&voters[i])) {
+        msg!("Voters must be unique");
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    if quorum == 0 || quorum as usize > voters.len() {
+        msg!(
+            "Invalid quorum {}, must be between 1 and the {} voters",
+            quorum,
+            voters.len()
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // the payload followed by the staging region of the proposed write
+    let account_size = VOTED_ECHO_HEADER_SIZE + 2 * buffer_size;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"voted", ctx.payer.key.as_ref()], program_id);
+
+    if *ctx.voted_buffer.key != pda {
+        msg!("Invalid voted buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.payer.key,
+        ctx.voted_buffer.key,
+        Rent::get()?.minimum_balance(account_size),
+        account_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.voted_buffer.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[b"voted", ctx.payer.key.as_ref(), &[bump_seed]]],
+    )?;
+
+    let mut voter_list = [Pubkey::default(); MAX_VOTERS];
+    voter_list[..voters.len()].copy_from_slice(&voters);
+
+    // slice of the buffer used for the header
+    let buffer_header = VotedEchoHeader {
+        voters: voter_list,
+        voter_count: voters.len() as u8,
+        quorum,
+        vote_bitmap: 0,
+        pending_data_hash: [0; 32],
+        pending_len: 0,
+        creator: *ctx.payer.key,
+        bump_seed,
+    };
+
+    ctx.voted_buffer.data.borrow_mut()[..VOTED_ECHO_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Voted buffer len: {}", buffer_size);
+    msg!("Quorum: {} of {}", quorum, voters.len());
+    msg!("Bump seed: {}", bump_seed);
+
+    Ok(())
+}
@@ -0,0 +1,209 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{LinkedBufferHeader, LINKED_BUFF_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    tail_buffer: &'a AccountInfo<'b>,
+    new_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            tail_buffer: next_account_info(accounts_iter)?,
+            new_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.tail_buffer.is_writable {
+            msg!("Tail buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.new_buffer.is_writable {
+            msg!("New buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Reads the header of a node of a linked buffer chain.
+///
+/// Only the head of a chain is a PDA, the other nodes are plain accounts that were handed to the Echo Program
+/// while still zeroed, so they are recognized by their owner and the authority stored in their header.
+fn read_linked_header(
+    program_id: &Pubkey,
+    linked_buffer: &AccountInfo,
+) -> Result<LinkedBufferHeader, ProgramError> {
+    if linked_buffer.owner != program_id {
+        msg!("Linked buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = linked_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() <= LINKED_BUFF_HEADER_SIZE {
+        msg!("Invalid linked buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = LinkedBufferHeader::try_from_slice(&buffer[..LINKED_BUFF_HEADER_SIZE])?;
+
+    if buffer_header.authority == Pubkey::default() {
+        msg!("Linked buffer {} is not initialized", linked_buffer.key);
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Links `new_buffer` after `tail_buffer` and initializes it as the new tail of the chain.
+pub fn process_append(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_buffer: Pubkey,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if *ctx.new_buffer.key != new_buffer {
+        msg!("New buffer account doesn't match {}", new_buffer);
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    if ctx.new_buffer.key == ctx.tail_buffer.key {
+        msg!("Linked buffer can't point to itself");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let mut tail_header = read_linked_header(program_id, ctx.tail_buffer)?;
+
+    if tail_header.authority != *ctx.authority.key {
+        msg!("Only the linked buffer authority can extend the chain");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    if tail_header.has_next {
+        msg!(
+            "Linked buffer already points to {}",
+            tail_header.next_buffer
+        );
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    if ctx.new_buffer.owner != program_id {
+        msg!("New buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    {
+        let buffer = &mut (*ctx.new_buffer.data).borrow_mut();
+
+        if buffer.len() <= LINKED_BUFF_HEADER_SIZE {
+            msg!(
+                "Invalid buffer length {}, must be greater than header size {}",
+                buffer.len(),
+                LINKED_BUFF_HEADER_SIZE
+            );
+            return Err(EchoError::InvalidAccountData.into());
+        }
+
+        // every node of a chain has a non-zero header, so this also keeps existing nodes from being linked again
+        if let Some(index) = buffer.iter().position(|byte| *byte != 0) {
+            msg!("New buffer has non-zero data at index {}", index);
+            return Err(EchoError::AccountHasNonZeroData.into());
+        }
+
+        let new_header = LinkedBufferHeader {
+            bump_seed: 0,
+            authority: tail_header.authority,
+            next_buffer: Pubkey::default(),
+            has_next: false,
+        };
+        buffer[..LINKED_BUFF_HEADER_SIZE].copy_from_slice(&new_header.try_to_vec().unwrap());
+    }
+
+    tail_header.next_buffer = new_buffer;
+    tail_header.has_next = true;
+    ctx.tail_buffer.data.borrow_mut()[..LINKED_BUFF_HEADER_SIZE]
+        .copy_from_slice(&tail_header.try_to_vec().unwrap());
+
+    msg!("Linked {} after {}", new_buffer, ctx.tail_buffer.key);
+
+    Ok(())
+}
+
+/// Logs the data of every node, starting from the head and following the links in the order the accounts are
+/// passed. Fails if the accounts don't match the chain or run out before its end.
+pub fn process_traverse(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let head = next_account_info(accounts_iter)?;
+
+    let head_header = read_linked_header(program_id, head)?;
+
+    // verify that the PDA account is the correct address
+    let (pda, _) =
+        Pubkey::find_program_address(&[b"linked", head_header.authority.as_ref()], program_id);
+
+    if pda != *head.key {
+        msg!("Invalid linked buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let mut node = head;
+    let mut header = head_header;
+    let mut index = 0;
+
+    loop {
+        msg!(
+            "Node {} {}: {:?}",
+            index,
+            node.key,
+            &node.data.borrow()[LINKED_BUFF_HEADER_SIZE..]
+        );
+
+        if !header.has_next {
+            break;
+        }
+
+        node = next_account_info(accounts_iter)?;
+        if *node.key != header.next_buffer {
+            msg!("Expected node {}, got {}", header.next_buffer, node.key);
+            return Err(EchoError::InvalidAccountAddress.into());
+        }
+
+        let authority = header.authority;
+        header = read_linked_header(program_id, node)?;
+        if header.authority != authority {
+            msg!("Node {} belongs to another authority", node.key);
+            return Err(EchoError::InvalidAccountData.into());
+        }
+
+        index += 1;
+    }
+
+    msg!("Traversed {} linked buffers", index + 1);
+
+    Ok(())
+}
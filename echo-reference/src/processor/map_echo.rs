@@ -0,0 +1,213 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{MapHeader, MAP_ENTRY_SIZE, MAP_HEADER_SIZE, MAP_KEY_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    map_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            map_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        Ok(ctx)
+    }
+
+    /// Additional checks for instructions that modify the map.
+    fn check_mutable(&self) -> ProgramResult {
+        if !self.map_buffer.is_writable {
+            msg!("Map buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !self.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the header of `map_buffer` after checking that it is the map buffer of `authority`.
+fn validate_map_buffer(
+    program_id: &Pubkey,
+    map_buffer: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<MapHeader, ProgramError> {
+    if map_buffer.owner != program_id {
+        msg!("Map buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = map_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < MAP_HEADER_SIZE {
+        msg!("Invalid map buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = MapHeader::try_from_slice(&buffer[..MAP_HEADER_SIZE])?;
+
+    if buffer.len() != MAP_HEADER_SIZE + buffer_header.capacity as usize * MAP_ENTRY_SIZE
+        || buffer_header.count > buffer_header.capacity
+    {
+        msg!("Invalid map buffer size, {}", buffer.len());
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(&[b"map", authority.as_ref()], program_id);
+
+    if pda != *map_buffer.key {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Binary search over the entries sorted by key, returns the index of `key` or where it would be inserted.
+fn search(entries: &[u8], key: &Pubkey) -> Result<usize, usize> {
+    let count = entries.len() / MAP_ENTRY_SIZE;
+    let (mut low, mut high) = (0, count);
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let current = &entries[mid * MAP_ENTRY_SIZE..mid * MAP_ENTRY_SIZE + MAP_KEY_SIZE];
+
+        match current.cmp(key.as_ref()) {
+            std::cmp::Ordering::Less => low = mid + 1,
+            std::cmp::Ordering::Greater => high = mid,
+            std::cmp::Ordering::Equal => return Ok(mid),
+        }
+    }
+
+    Err(low)
+}
+
+/// Byte range of the value of entry `index` within the entries.
+fn value_range(index: usize) -> std::ops::Range<usize> {
+    let start = index * MAP_ENTRY_SIZE + MAP_KEY_SIZE;
+    start..(index + 1) * MAP_ENTRY_SIZE
+}
+
+/// Updates the value of `key` if it's in the map, otherwise inserts it keeping the entries sorted by key.
+pub fn process_put(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    key: Pubkey,
+    value: u64,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+    ctx.check_mutable()?;
+
+    let mut buffer_header = validate_map_buffer(program_id, ctx.map_buffer, ctx.authority.key)?;
+
+    let buffer = &mut (*ctx.map_buffer.data).borrow_mut();
+    let count = buffer_header.count as usize;
+
+    let entries = &mut buffer[MAP_HEADER_SIZE..];
+    match search(&entries[..count * MAP_ENTRY_SIZE], &key) {
+        Ok(index) => {
+            entries[value_range(index)].copy_from_slice(&value.to_le_bytes());
+            msg!("Updated {}: {}", key, value);
+        }
+        Err(index) => {
+            if buffer_header.count == buffer_header.capacity {
+                msg!("Map is full, capacity {}", buffer_header.capacity);
+                return Err(EchoError::MapFull.into());
+            }
+
+            // shift every entry after the insertion point one slot to the right
+            entries.copy_within(
+                index * MAP_ENTRY_SIZE..count * MAP_ENTRY_SIZE,
+                (index + 1) * MAP_ENTRY_SIZE,
+            );
+            entries[index * MAP_ENTRY_SIZE..index * MAP_ENTRY_SIZE + MAP_KEY_SIZE]
+                .copy_from_slice(key.as_ref());
+            entries[value_range(index)].copy_from_slice(&value.to_le_bytes());
+
+            buffer_header.count += 1;
+            buffer[..MAP_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+            msg!(
+                "Inserted {}: {}, map count: {}",
+                key,
+                value,
+                buffer_header.count
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub fn process_delete(program_id: &Pubkey, accounts: &[AccountInfo], key: Pubkey) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+    ctx.check_mutable()?;
+
+    let mut buffer_header = validate_map_buffer(program_id, ctx.map_buffer, ctx.authority.key)?;
+
+    let buffer = &mut (*ctx.map_buffer.data).borrow_mut();
+    let count = buffer_header.count as usize;
+
+    let entries = &mut buffer[MAP_HEADER_SIZE..];
+    let index = search(&entries[..count * MAP_ENTRY_SIZE], &key).map_err(|_| {
+        msg!("{} is not in the map", key);
+        EchoError::MemberNotFound
+    })?;
+
+    // shift every entry after the removed one a slot to the left and clear the freed slot
+    entries.copy_within(
+        (index + 1) * MAP_ENTRY_SIZE..count * MAP_ENTRY_SIZE,
+        index * MAP_ENTRY_SIZE,
+    );
+    entries[(count - 1) * MAP_ENTRY_SIZE..count * MAP_ENTRY_SIZE].fill(0);
+
+    buffer_header.count -= 1;
+    buffer[..MAP_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Deleted {}, map count: {}", key, buffer_header.count);
+
+    Ok(())
+}
+
+/// Logs the value of `key`, the authority is passed along only to validate the map buffer address.
+pub fn process_get(program_id: &Pubkey, accounts: &[AccountInfo], key: Pubkey) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let buffer_header = validate_map_buffer(program_id, ctx.map_buffer, ctx.authority.key)?;
+
+    let buffer = ctx.map_buffer.data.borrow();
+    let entries =
+        &buffer[MAP_HEADER_SIZE..MAP_HEADER_SIZE + buffer_header.count as usize * MAP_ENTRY_SIZE];
+
+    let index = search(entries, &key).map_err(|_| {
+        msg!("{} is not in the map", key);
+        EchoError::MemberNotFound
+    })?;
+    let value = u64::from_le_bytes(entries[value_range(index)].try_into().unwrap());
+
+    msg!("{}: {}", key, value);
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{MaxHeader, MAX_ECHO_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    max_account: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            max_account: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.max_account.is_writable {
+            msg!("Max account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Reads the header of `max_account` after checking that it is the max account of `authority`.
+fn read_max_header(
+    program_id: &Pubkey,
+    max_account: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<MaxHeader, ProgramError> {
+    if max_account.owner != program_id {
+        msg!("Max account must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let data = max_account.data.borrow();
+
+    if data.len() != MAX_ECHO_HEADER_SIZE {
+        msg!("Invalid max account size, {}", data.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let header = MaxHeader::try_from_slice(&data)?;
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(&[b"max", authority.as_ref()], program_id);
+
+    if pda != *max_account.key {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(header)
+}
+
+fn write_max_header(max_account: &AccountInfo, header: &MaxHeader) {
+    max_account.data.borrow_mut()[..MAX_ECHO_HEADER_SIZE]
+        .copy_from_slice(&header.try_to_vec().unwrap());
+    msg!("Current max: {}", header.current_max);
+    msg!("Update count: {}", header.update_count);
+}
+
+/// Anyone can submit a candidate without signing, the authority is passed along only to validate the
+/// max account address.
+pub fn process_update(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    candidate: u64,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let mut header = read_max_header(program_id, ctx.max_account, ctx.authority.key)?;
+    if candidate > header.current_max {
+        header.current_max = candidate;
+    }
+    header.update_count = header
+        .update_count
+        .checked_add(1)
+        .ok_or(EchoError::Overflow)?;
+
+    write_max_header(ctx.max_account, &header);
+
+    Ok(())
+}
+
+pub fn process_reset(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_value: u64,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if !ctx.authority.is_signer {
+        msg!("Authority account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let mut header = read_max_header(program_id, ctx.max_account, ctx.authority.key)?;
+    header.current_max = new_value;
+
+    write_max_header(ctx.max_account, &header);
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    state::{MemoEchoHeader, MEMO_ECHO_HEADER_SIZE},
+};
+
+/// The SPL Memo program, v2 and later
+pub mod spl_memo {
+    solana_program::declare_id!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+}
+
+/// Lowercase hex encoding of `data`, the memo logged for a write.
+fn hex_encode(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let memo_buffer = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+    let memo_program = next_account_info(accounts_iter)?;
+
+    if !memo_buffer.is_writable {
+        msg!("Memo buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !authority.is_signer {
+        msg!("Authority account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    if !spl_memo::check_id(memo_program.key) {
+        msg!("Invalid memo program");
+        return Err(EchoError::InvalidProgramAddress.into());
+    }
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if memo_buffer.owner != program_id {
+        msg!("Memo buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    {
+        let buffer = &mut (*memo_buffer.data).borrow_mut();
+
+        // check the size of the account before trying to read it
+        if buffer.len() < MEMO_ECHO_HEADER_SIZE {
+            msg!("Invalid memo buffer size, {}", buffer.len());
+            return Err(EchoError::AccountNotInitialized.into());
+        }
+
+        let buffer_header = MemoEchoHeader::try_from_slice(&buffer[..MEMO_ECHO_HEADER_SIZE])?;
+
+        // verify that the PDA account is the correct address, which also checks the authority
+        let (pda, bump_seed) =
+            Pubkey::find_program_address(&[b"memo", authority.key.as_ref()], program_id);
+
+        if pda != *memo_buffer.key || bump_seed != buffer_header.bump_seed {
+            msg!("Invalid account address or authority");
+            return Err(EchoError::InvalidAccountAddress.into());
+        }
+
+        // this is the 'rest' of the account's data (beyond the header info)
+        let buffer_data = &mut buffer[MEMO_ECHO_HEADER_SIZE..];
+        let len = buffer_data.len().min(data.len());
+        buffer_data[..len].copy_from_slice(&data[..len]);
+        buffer_data[len..].fill(0);
+    }
+
+    // the memo goes in the inner instructions of the transaction, a permanent log of the full data
+    invoke(
+        &Instruction {
+            program_id: spl_memo::id(),
+            accounts: vec![],
+            data: hex_encode(&data).into_bytes(),
+        },
+        std::slice::from_ref(memo_program),
+    )?;
+
+    Ok(())
+}
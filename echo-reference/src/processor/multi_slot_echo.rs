@@ -0,0 +1,182 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{MultiSlotHeader, MULTI_SLOT_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    multi_slot_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            multi_slot_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.multi_slot_buffer.is_writable {
+            msg!("Multi slot buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Reads the header of `multi_slot_buffer` after checking that it is the multi slot buffer of `authority`.
+fn validate_multi_slot_buffer(
+    program_id: &Pubkey,
+    multi_slot_buffer: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<MultiSlotHeader, ProgramError> {
+    if multi_slot_buffer.owner != program_id {
+        msg!("Multi slot buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = multi_slot_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < MULTI_SLOT_HEADER_SIZE {
+        msg!("Invalid multi slot buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = MultiSlotHeader::try_from_slice(&buffer[..MULTI_SLOT_HEADER_SIZE])?;
+
+    if buffer.len() != MULTI_SLOT_HEADER_SIZE + buffer_header.total_size as usize {
+        msg!("Invalid multi slot buffer size, {}", buffer.len());
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"multi_slot", authority.as_ref()], program_id);
+
+    if pda != *multi_slot_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+fn check_not_committed(buffer_header: &MultiSlotHeader) -> ProgramResult {
+    if buffer_header.committed {
+        msg!("The multi slot buffer is committed, abort it to write again");
+        return Err(EchoError::MultiSlotCommitted.into());
+    }
+
+    Ok(())
+}
+
+/// Copies `data` into chunk `chunk_index`, truncated or zero-padded to the chunk, and marks it as written. Writing
+/// the same chunk again replaces it without counting it twice.
+pub fn process_write_chunk(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    chunk_index: u16,
+    data: Vec<u8>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let mut buffer_header =
+        validate_multi_slot_buffer(program_id, ctx.multi_slot_buffer, ctx.authority.key)?;
+    check_not_committed(&buffer_header)?;
+
+    if chunk_index >= buffer_header.total_chunks {
+        msg!(
+            "Invalid chunk {}, the buffer has {} chunks",
+            chunk_index,
+            buffer_header.total_chunks
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    let buffer = &mut (*ctx.multi_slot_buffer.data).borrow_mut();
+    let chunk = &mut buffer[MULTI_SLOT_HEADER_SIZE..][buffer_header.chunk_range(chunk_index)];
+
+    let bytes_written = chunk.len().min(data.len());
+    chunk[..bytes_written].copy_from_slice(&data[..bytes_written]);
+    chunk[bytes_written..].fill(0);
+
+    if !buffer_header.is_written(chunk_index) {
+        buffer_header.written_chunks |= 1 << chunk_index;
+        buffer_header.chunks_written += 1;
+    }
+
+    buffer[..MULTI_SLOT_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!(
+        "Wrote {} bytes to chunk {}, {}/{} chunks written",
+        bytes_written,
+        chunk_index,
+        buffer_header.chunks_written,
+        buffer_header.total_chunks
+    );
+
+    Ok(())
+}
+
+/// Marks the buffer as committed, failing with `MultiSlotIncomplete` until every chunk is written.
+pub fn process_commit(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let mut buffer_header =
+        validate_multi_slot_buffer(program_id, ctx.multi_slot_buffer, ctx.authority.key)?;
+    check_not_committed(&buffer_header)?;
+
+    if let Some(missing) =
+        (0..buffer_header.total_chunks).find(|index| !buffer_header.is_written(*index))
+    {
+        msg!("Chunk {} hasn't been written", missing);
+        return Err(EchoError::MultiSlotIncomplete.into());
+    }
+
+    buffer_header.committed = true;
+
+    ctx.multi_slot_buffer.data.borrow_mut()[..MULTI_SLOT_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Committed {} chunks", buffer_header.total_chunks);
+
+    Ok(())
+}
+
+/// Zeroes the data and forgets every written chunk, committed or not, so the buffer can be written from scratch.
+pub fn process_abort(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let mut buffer_header =
+        validate_multi_slot_buffer(program_id, ctx.multi_slot_buffer, ctx.authority.key)?;
+
+    let buffer = &mut (*ctx.multi_slot_buffer.data).borrow_mut();
+    buffer[MULTI_SLOT_HEADER_SIZE..].fill(0);
+
+    buffer_header.chunks_written = 0;
+    buffer_header.written_chunks = 0;
+    buffer_header.committed = false;
+    buffer[..MULTI_SLOT_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Aborted multi slot buffer");
+
+    Ok(())
+}
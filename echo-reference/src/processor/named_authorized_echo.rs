@@ -0,0 +1,103 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    events::{EchoEvent, WriteEvent},
+    state::{NamedAuthorizedBufferHeader, NAMED_AUTH_BUFF_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    authorized_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            authorized_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.authorized_buffer.is_writable {
+            msg!("Authorized Echo Buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if ctx.authorized_buffer.owner != program_id {
+        msg!("Authorized buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = &mut (*ctx.authorized_buffer.data).borrow_mut();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < NAMED_AUTH_BUFF_HEADER_SIZE {
+        msg!("Invalid authorized buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    // in order to validate the PDA address, we first read it to access the buffer name
+    let buffer_header =
+        NamedAuthorizedBufferHeader::try_from_slice(&buffer[..NAMED_AUTH_BUFF_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"authority",
+            ctx.authority.key.as_ref(),
+            buffer_header.name(),
+        ],
+        program_id,
+    );
+
+    if pda != *ctx.authorized_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[NAMED_AUTH_BUFF_HEADER_SIZE..];
+
+    // loop over each byte in the rest of account's data
+    for index in 0..buffer_data.len() {
+        buffer_data[index] = match index < data.len() {
+            true => data[index],
+            false => 0,
+        };
+    }
+
+    EchoEvent::Write(WriteEvent {
+        buffer: *ctx.authorized_buffer.key,
+        authority: *ctx.authority.key,
+        len: buffer_data.len().min(data.len()) as u64,
+        slot: Clock::get()?.slot,
+        sequence: 0,
+    })
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,247 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use spl_token::state::{Account as TokenAccount, Mint};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    state::{NftVendingMachineHeader, NFT_VENDING_MACHINE_HEADER_SIZE},
+};
+
+/// The Metaplex Token Metadata program, only the start of its metadata accounts is read, up to the collection
+pub mod mpl_token_metadata {
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use solana_program::pubkey::Pubkey;
+
+    solana_program::declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+    /// First byte of a metadata account
+    pub const KEY_METADATA_V1: u8 = 4;
+
+    /// Address of the metadata account of `mint`
+    pub fn find_metadata_address(mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"metadata", id().as_ref(), mint.as_ref()], &id())
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+    pub struct Creator {
+        pub address: Pubkey,
+        pub verified: bool,
+        pub share: u8,
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+    pub struct Data {
+        pub name: String,
+        pub symbol: String,
+        pub uri: String,
+        pub seller_fee_basis_points: u16,
+        pub creators: Option<Vec<Creator>>,
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+    pub struct Collection {
+        /// Set by the collection authority, anyone can claim an unverified collection
+        pub verified: bool,
+        pub key: Pubkey,
+    }
+
+    /// The fields of a metadata account up to its collection, the ones following it are left unread
+    #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+    pub struct Metadata {
+        pub key: u8,
+        pub update_authority: Pubkey,
+        pub mint: Pubkey,
+        pub data: Data,
+        pub primary_sale_happened: bool,
+        pub is_mutable: bool,
+        pub edition_nonce: Option<u8>,
+        pub token_standard: Option<u8>,
+        pub collection: Option<Collection>,
+    }
+}
+
+struct Context<'a, 'b: 'a> {
+    nft_vending_machine_buffer: &'a AccountInfo<'b>,
+    user: &'a AccountInfo<'b>,
+    user_token_account: &'a AccountInfo<'b>,
+    nft_mint: &'a AccountInfo<'b>,
+    metadata: &'a AccountInfo<'b>,
+    token_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            nft_vending_machine_buffer: next_account_info(accounts_iter)?,
+            user: next_account_info(accounts_iter)?,
+            user_token_account: next_account_info(accounts_iter)?,
+            nft_mint: next_account_info(accounts_iter)?,
+            metadata: next_account_info(accounts_iter)?,
+            token_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.nft_vending_machine_buffer.is_writable
+            || !ctx.user_token_account.is_writable
+            || !ctx.nft_mint.is_writable
+        {
+            msg!("NFT vending machine buffer, user token account and NFT mint must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.user.is_signer {
+            msg!("User account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.token_program.key != spl_token::id() {
+            msg!(
+                "Invalid token program {}, expected {}",
+                ctx.token_program.key,
+                spl_token::id()
+            );
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Reads the metadata of `nft_mint`, checking it is the metadata account of the mint.
+fn read_metadata(
+    metadata: &AccountInfo,
+    nft_mint: &Pubkey,
+) -> Result<mpl_token_metadata::Metadata, ProgramError> {
+    // the collection of an account owned by any other program could be spoofed
+    if *metadata.owner != mpl_token_metadata::id() {
+        msg!("Metadata account must be owned by the Token Metadata Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let (metadata_address, _) = mpl_token_metadata::find_metadata_address(nft_mint);
+
+    if metadata_address != *metadata.key {
+        msg!("Invalid metadata account for mint {}", nft_mint);
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // metadata accounts hold more fields after the collection, they are not read
+    let metadata = mpl_token_metadata::Metadata::deserialize(&mut &metadata.data.borrow()[..])?;
+
+    if metadata.key != mpl_token_metadata::KEY_METADATA_V1 || metadata.mint != *nft_mint {
+        msg!("Invalid metadata account");
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    Ok(metadata)
+}
+
+/// Burns an NFT of the verified collection of the buffer to copy `data` past the header. The burned NFT can't be used
+/// again, so the collection size caps the number of writes.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if ctx.nft_vending_machine_buffer.owner != program_id {
+        msg!("NFT vending machine buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer_header = {
+        let buffer = ctx.nft_vending_machine_buffer.data.borrow();
+
+        // check the size of the account before trying to read it
+        if buffer.len() < NFT_VENDING_MACHINE_HEADER_SIZE {
+            msg!("Invalid NFT vending machine buffer size, {}", buffer.len());
+            return Err(EchoError::AccountNotInitialized.into());
+        }
+
+        NftVendingMachineHeader::try_from_slice(&buffer[..NFT_VENDING_MACHINE_HEADER_SIZE])?
+    };
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"nft_vm", buffer_header.collection.as_ref()], program_id);
+
+    if pda != *ctx.nft_vending_machine_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or collection");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    if *ctx.user_token_account.owner != spl_token::id() || *ctx.nft_mint.owner != spl_token::id() {
+        msg!("NFT mint and token account must be owned by the Token Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let nft_mint = Mint::unpack(&ctx.nft_mint.data.borrow())?;
+
+    // an NFT is the only token of a mint without decimals
+    if nft_mint.supply != 1 || nft_mint.decimals != 0 {
+        msg!("Mint {} is not an NFT", ctx.nft_mint.key);
+        return Err(EchoError::InvalidMint.into());
+    }
+
+    let user_token_account = TokenAccount::unpack(&ctx.user_token_account.data.borrow())?;
+
+    if user_token_account.mint != *ctx.nft_mint.key
+        || user_token_account.owner != *ctx.user.key
+        || user_token_account.amount != 1
+    {
+        msg!("User token account must hold the NFT");
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    let metadata = read_metadata(ctx.metadata, ctx.nft_mint.key)?;
+
+    match metadata.collection {
+        Some(collection) if collection.verified && collection.key == buffer_header.collection => {}
+        _ => {
+            msg!(
+                "NFT must be a verified member of the collection {}",
+                buffer_header.collection
+            );
+            return Err(EchoError::InvalidCollection.into());
+        }
+    }
+
+    // Burn the NFT to authorize the echo
+    invoke(
+        &spl_token::instruction::burn_checked(
+            &spl_token::id(),
+            ctx.user_token_account.key,
+            ctx.nft_mint.key,
+            ctx.user.key,
+            &[],
+            1,
+            0,
+        )?,
+        &[
+            ctx.token_program.clone(),
+            ctx.user_token_account.clone(),
+            ctx.nft_mint.clone(),
+            ctx.user.clone(),
+        ],
+    )?;
+
+    let buffer = &mut (*ctx.nft_vending_machine_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[NFT_VENDING_MACHINE_HEADER_SIZE..];
+    let len = buffer_data.len().min(data.len());
+    buffer_data[..len].copy_from_slice(&data[..len]);
+    buffer_data[len..].fill(0);
+
+    msg!("Burned NFT {}, wrote {} bytes", ctx.nft_mint.key, len);
+
+    Ok(())
+}
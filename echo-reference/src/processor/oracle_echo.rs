@@ -0,0 +1,147 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{OracleEchoHeader, ORACLE_ECHO_HEADER_SIZE},
+};
+
+/// Reads the header of `oracle_buffer` after checking its PDA and that `authority` signed for it.
+fn validate_oracle_buffer(
+    program_id: &Pubkey,
+    oracle_buffer: &AccountInfo,
+    authority: &AccountInfo,
+) -> Result<OracleEchoHeader, ProgramError> {
+    if !oracle_buffer.is_writable {
+        msg!("Oracle buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !authority.is_signer {
+        msg!("Authority account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if oracle_buffer.owner != program_id {
+        msg!("Oracle buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = oracle_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < ORACLE_ECHO_HEADER_SIZE {
+        msg!("Invalid oracle buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = OracleEchoHeader::try_from_slice(&buffer[..ORACLE_ECHO_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address, which also checks the authority
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"oracle", authority.key.as_ref()], program_id);
+
+    if pda != *oracle_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Reads the little-endian `i64` price stored at `offset` in the data of `oracle`.
+fn read_price(oracle: &AccountInfo, offset: u16) -> Result<i64, ProgramError> {
+    let data = oracle.data.borrow();
+    let offset = offset as usize;
+
+    let price_bytes = data.get(offset..offset + 8).ok_or_else(|| {
+        msg!(
+            "Price offset {} out of the oracle data of {} bytes",
+            offset,
+            data.len()
+        );
+        EchoError::InvalidInstructionInput
+    })?;
+
+    Ok(i64::from_le_bytes(price_bytes.try_into().unwrap()))
+}
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+    oracle_offset: u16,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let oracle_buffer = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+    let oracle = next_account_info(accounts_iter)?;
+
+    let buffer_header = validate_oracle_buffer(program_id, oracle_buffer, authority)?;
+
+    if *oracle.key != buffer_header.oracle {
+        msg!("The oracle {} must be passed", buffer_header.oracle);
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let price = read_price(oracle, oracle_offset)?;
+
+    if price < buffer_header.min_price || price > buffer_header.max_price {
+        msg!(
+            "Oracle price {} out of [{}, {}]",
+            price,
+            buffer_header.min_price,
+            buffer_header.max_price
+        );
+        return Err(EchoError::ConditionNotMet.into());
+    }
+
+    let buffer = &mut (*oracle_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[ORACLE_ECHO_HEADER_SIZE..];
+    let len = buffer_data.len().min(data.len());
+    buffer_data[..len].copy_from_slice(&data[..len]);
+    buffer_data[len..].fill(0);
+
+    msg!("Wrote {} bytes at oracle price {}", len, price);
+
+    Ok(())
+}
+
+/// Replaces the price range of the gate, only the authority can.
+pub fn process_update_gate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_min: i64,
+    new_max: i64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let oracle_buffer = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+
+    let mut buffer_header = validate_oracle_buffer(program_id, oracle_buffer, authority)?;
+
+    if new_min > new_max {
+        msg!("Invalid price range [{}, {}]", new_min, new_max);
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    buffer_header.min_price = new_min;
+    buffer_header.max_price = new_max;
+
+    oracle_buffer.data.borrow_mut()[..ORACLE_ECHO_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Price range: [{}, {}]", new_min, new_max);
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    sysvar::instructions::{self, load_current_index_checked, load_instruction_at_checked},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    processor::gasless_echo::check_ed25519_instruction,
+    state::{OwnershipEchoHeader, OWNERSHIP_ECHO_HEADER_SIZE},
+};
+
+/// Message the current owner signs off-chain to approve the transfer of `ownership_buffer` to `new_owner` with
+/// `data`, at `transfer_count`.
+pub fn ownership_message(
+    ownership_buffer: &Pubkey,
+    transfer_count: u64,
+    new_owner: &Pubkey,
+    data: &[u8],
+) -> Vec<u8> {
+    [
+        ownership_buffer.as_ref(),
+        &transfer_count.to_le_bytes(),
+        new_owner.as_ref(),
+        data,
+    ]
+    .concat()
+}
+
+/// Transfers the ownership recorded in `ownership_buffer` to the signing new owner and writes `data`. The approval of
+/// the current owner is verified by the Ed25519 Program in the instruction right before this one, which fails the
+/// whole transaction if it is invalid, so only its inputs are checked here.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let ownership_buffer = next_account_info(accounts_iter)?;
+    let new_owner = next_account_info(accounts_iter)?;
+    let instructions_sysvar = next_account_info(accounts_iter)?;
+
+    if !ownership_buffer.is_writable {
+        msg!("Ownership buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !new_owner.is_signer {
+        msg!("New owner account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    if !instructions::check_id(instructions_sysvar.key) {
+        msg!("Invalid instructions sysvar");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if ownership_buffer.owner != program_id {
+        msg!("Ownership buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = &mut (*ownership_buffer.data).borrow_mut();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < OWNERSHIP_ECHO_HEADER_SIZE {
+        msg!("Invalid ownership buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let mut buffer_header =
+        OwnershipEchoHeader::try_from_slice(&buffer[..OWNERSHIP_ECHO_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"ownership", buffer_header.asset.as_ref()], program_id);
+
+    if pda != *ownership_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or asset");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+
+    if current_index == 0 {
+        msg!("The ownership claim must follow an Ed25519 instruction");
+        return Err(EchoError::InvalidSignature.into());
+    }
+
+    let ed25519_instruction =
+        load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+    check_ed25519_instruction(
+        &ed25519_instruction,
+        &buffer_header.current_owner,
+        None,
+        &ownership_message(
+            ownership_buffer.key,
+            buffer_header.transfer_count,
+            new_owner.key,
+            &data,
+        ),
+    )?;
+
+    let previous_owner = buffer_header.current_owner;
+    buffer_header.current_owner = *new_owner.key;
+    buffer_header.transfer_count = buffer_header
+        .transfer_count
+        .checked_add(1)
+        .ok_or(EchoError::Overflow)?;
+
+    buffer[..OWNERSHIP_ECHO_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[OWNERSHIP_ECHO_HEADER_SIZE..];
+    let len = buffer_data.len().min(data.len());
+    buffer_data[..len].copy_from_slice(&data[..len]);
+    buffer_data[len..].fill(0);
+
+    msg!(
+        "Ownership of {} transferred from {} to {}",
+        buffer_header.asset,
+        previous_owner,
+        new_owner.key
+    );
+    msg!("Wrote {} bytes", len);
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::instructions::{self, load_current_index_checked, load_instruction_at_checked},
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    state::{ProgramControlledBufferHeader, PROGRAM_CONTROLLED_BUFF_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    program_controlled_buffer: &'a AccountInfo<'b>,
+    instructions_sysvar: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            program_controlled_buffer: next_account_info(accounts_iter)?,
+            instructions_sysvar: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.program_controlled_buffer.is_writable {
+            msg!("Program controlled buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !instructions::check_id(ctx.instructions_sysvar.key) {
+            msg!("Invalid instructions sysvar");
+            return Err(EchoError::InvalidAccountAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Checks that the Echo Program was reached through a cross program invocation from `allowed_program`.
+pub(crate) fn check_caller(
+    program_id: &Pubkey,
+    instructions_sysvar: &AccountInfo,
+    allowed_program: &Pubkey,
+) -> ProgramResult {
+    // The runtime doesn't expose the CPI call depth to programs, so the caller is identified through the
+    // Instructions sysvar instead: it only records top-level transaction instructions, so the instruction at
+    // the current index belongs to whichever program the transaction invoked directly. When the Echo Program
+    // is invoked directly that is our own program id; when it is reached via CPI it is the calling program.
+    //
+    // Return data (`get_return_data`) or a flag set by the caller can't be used for this, since any program
+    // (or the transaction itself) can set them.
+    //
+    // This identifies the outermost caller, not necessarily the immediate one, so `allowed_program` must not
+    // itself let arbitrary programs route CPIs through it.
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    let current_instruction =
+        load_instruction_at_checked(current_index as usize, instructions_sysvar)?;
+
+    if current_instruction.program_id == *program_id
+        || current_instruction.program_id != *allowed_program
+    {
+        msg!(
+            "Caller {} is not the allowed program {}",
+            current_instruction.program_id,
+            allowed_program
+        );
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    Ok(())
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if ctx.program_controlled_buffer.owner != program_id {
+        msg!("Program controlled buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = &mut (*ctx.program_controlled_buffer.data).borrow_mut();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < PROGRAM_CONTROLLED_BUFF_HEADER_SIZE {
+        msg!("Invalid program controlled buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = ProgramControlledBufferHeader::try_from_slice(
+        &buffer[..PROGRAM_CONTROLLED_BUFF_HEADER_SIZE],
+    )?;
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(
+        &[
+            b"program_controlled",
+            buffer_header.allowed_program.as_ref(),
+        ],
+        program_id,
+    );
+
+    if pda != *ctx.program_controlled_buffer.key {
+        msg!("Invalid program controlled buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    check_caller(
+        program_id,
+        ctx.instructions_sysvar,
+        &buffer_header.allowed_program,
+    )?;
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[PROGRAM_CONTROLLED_BUFF_HEADER_SIZE..];
+
+    // loop over each byte in the rest of account's data
+    for index in 0..buffer_data.len() {
+        buffer_data[index] = match index < data.len() {
+            true => data[index],
+            false => 0,
+        };
+    }
+
+    Ok(())
+}
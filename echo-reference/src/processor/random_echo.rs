@@ -0,0 +1,139 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{slot_hashes, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{RandomHeader, RANDOM_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    random_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    slot_hashes: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            random_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+            slot_hashes: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.random_buffer.is_writable {
+            msg!("Random buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if !slot_hashes::check_id(ctx.slot_hashes.key) {
+            msg!("Invalid slot hashes sysvar");
+            return Err(EchoError::InvalidAccountAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Reads the header of `random_buffer` after checking that it is the random buffer of `authority`.
+fn read_random_header(
+    program_id: &Pubkey,
+    random_buffer: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<RandomHeader, ProgramError> {
+    if random_buffer.owner != program_id {
+        msg!("Random buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = random_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() <= RANDOM_HEADER_SIZE {
+        msg!("Invalid random buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = RandomHeader::try_from_slice(&buffer[..RANDOM_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(&[b"random", authority.as_ref()], program_id);
+
+    if pda != *random_buffer.key {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Most recent hash of the `SlotHashes` sysvar, read from the raw account data as the whole sysvar is too large to
+/// deserialize. The data is a u64 length followed by `(slot, hash)` entries, newest first.
+fn most_recent_slot_hash(slot_hashes: &AccountInfo) -> Result<[u8; 32], ProgramError> {
+    let data = slot_hashes.data.borrow();
+
+    let len = data
+        .get(..8)
+        .map(|len| u64::from_le_bytes(len.try_into().unwrap()))
+        .ok_or(EchoError::InvalidAccountData)?;
+
+    if len == 0 {
+        msg!("No slot hashes available yet");
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    let mut hash = [0; 32];
+    hash.copy_from_slice(data.get(16..48).ok_or(EchoError::InvalidAccountData)?);
+
+    Ok(hash)
+}
+
+/// XORs the buffer data with the most recent slot hash, repeated over the whole data.
+///
+/// This is NOT cryptographically secure randomness: the slot hash is known to the leader producing the block and
+/// to anyone watching the chain before the refresh lands, so it must not decide lotteries or anything of value.
+pub fn process_refresh(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let mut buffer_header = read_random_header(program_id, ctx.random_buffer, ctx.authority.key)?;
+    let slot = Clock::get()?.slot;
+
+    // the slot hash only changes between slots, refreshing again would replay it
+    if buffer_header.last_refresh_slot == slot {
+        msg!("Already refreshed in slot {}", slot);
+        return Ok(());
+    }
+
+    let hash = most_recent_slot_hash(ctx.slot_hashes)?;
+
+    let buffer = &mut (*ctx.random_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[RANDOM_HEADER_SIZE..];
+
+    for (byte, hash_byte) in buffer_data.iter_mut().zip(hash.iter().cycle()) {
+        *byte ^= hash_byte;
+    }
+
+    buffer_header.last_refresh_slot = slot;
+    buffer[..RANDOM_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Refreshed in slot {}", slot);
+
+    Ok(())
+}
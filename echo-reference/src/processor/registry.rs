@@ -0,0 +1,126 @@
+use solana_program::{account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{RegistryEntry, RegistryHeader, REGISTRY_ENTRY_SIZE, REGISTRY_HEADER_SIZE},
+};
+
+/// Reads the header of `registry` after checking that it is the registry of `authority`.
+fn validate_registry(
+    program_id: &Pubkey,
+    registry: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<RegistryHeader, ProgramError> {
+    if !registry.is_writable {
+        msg!("Registry account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if registry.owner != program_id {
+        msg!("Registry must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let data = registry.data.borrow();
+
+    // check the size of the account before trying to read it
+    if data.len() < REGISTRY_HEADER_SIZE {
+        msg!("Invalid registry size, {}", data.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let header = RegistryHeader::try_from_slice(&data[..REGISTRY_HEADER_SIZE])?;
+
+    if data.len() != REGISTRY_HEADER_SIZE + header.capacity as usize * REGISTRY_ENTRY_SIZE
+        || header.count > header.capacity
+    {
+        msg!("Invalid registry size, {}", data.len());
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(&[b"registry", authority.as_ref()], program_id);
+
+    if pda != *registry.key {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(header)
+}
+
+/// Byte range of entry `index` within the registry data.
+fn entry_range(index: usize) -> std::ops::Range<usize> {
+    let start = REGISTRY_HEADER_SIZE + index * REGISTRY_ENTRY_SIZE;
+    start..start + REGISTRY_ENTRY_SIZE
+}
+
+/// Appends `buffer` to the registry of `authority`, fails with `RegistryFull` if it is at capacity.
+pub(crate) fn register_buffer(
+    program_id: &Pubkey,
+    registry: &AccountInfo,
+    authority: &Pubkey,
+    buffer_seed: u64,
+    buffer: &Pubkey,
+) -> Result<(), ProgramError> {
+    let mut header = validate_registry(program_id, registry, authority)?;
+
+    if header.count == header.capacity {
+        msg!("Registry is full, capacity {}", header.capacity);
+        return Err(EchoError::RegistryFull.into());
+    }
+
+    let entry = RegistryEntry {
+        buffer_seed,
+        buffer: *buffer,
+    };
+
+    let data = &mut (*registry.data).borrow_mut();
+    data[entry_range(header.count as usize)].copy_from_slice(&entry.try_to_vec().unwrap());
+
+    header.count += 1;
+    data[..REGISTRY_HEADER_SIZE].copy_from_slice(&header.try_to_vec().unwrap());
+
+    msg!("Registered {}, registry count: {}", buffer, header.count);
+
+    Ok(())
+}
+
+/// Removes `buffer` from the registry of `authority`, keeping the order of the other entries.
+pub(crate) fn unregister_buffer(
+    program_id: &Pubkey,
+    registry: &AccountInfo,
+    authority: &Pubkey,
+    buffer: &Pubkey,
+) -> Result<(), ProgramError> {
+    let mut header = validate_registry(program_id, registry, authority)?;
+
+    let data = &mut (*registry.data).borrow_mut();
+    let count = header.count as usize;
+
+    let index = (0..count)
+        .find(|index| {
+            RegistryEntry::try_from_slice(&data[entry_range(*index)])
+                .is_ok_and(|entry| entry.buffer == *buffer)
+        })
+        .ok_or_else(|| {
+            msg!("{} is not in the registry", buffer);
+            EchoError::MemberNotFound
+        })?;
+
+    // shift every entry after the removed one a slot to the left and clear the freed slot
+    data.copy_within(
+        entry_range(index + 1).start..entry_range(count).start,
+        entry_range(index).start,
+    );
+    data[entry_range(count - 1)].fill(0);
+
+    header.count -= 1;
+    data[..REGISTRY_HEADER_SIZE].copy_from_slice(&header.try_to_vec().unwrap());
+
+    msg!("Unregistered {}, registry count: {}", buffer, header.count);
+
+    Ok(())
+}
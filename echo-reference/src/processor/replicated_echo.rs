@@ -0,0 +1,171 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{
+        ReplicatedEchoHeader, REPLICATED_ECHO_HEADER_SIZE, REPLICA_ROLE_PRIMARY,
+        REPLICA_ROLE_REPLICA,
+    },
+};
+
+/// Reads the header of `replicated_buffer` after checking its PDA, which proves its role.
+fn validate_replicated_buffer(
+    program_id: &Pubkey,
+    replicated_buffer: &AccountInfo,
+) -> Result<ReplicatedEchoHeader, ProgramError> {
+    if !replicated_buffer.is_writable {
+        msg!("Replicated buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if replicated_buffer.owner != program_id {
+        msg!("Replicated buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = replicated_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < REPLICATED_ECHO_HEADER_SIZE {
+        msg!("Invalid replicated buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header =
+        ReplicatedEchoHeader::try_from_slice(&buffer[..REPLICATED_ECHO_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"replicated",
+            buffer_header.primary_authority.as_ref(),
+            buffer_header.replica_authority.as_ref(),
+            &[buffer_header.role],
+        ],
+        program_id,
+    );
+
+    if pda != *replicated_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Writes `data` to both buffers of a pair, which both authorities sign for. Either buffer being frozen or too small
+/// for `data` fails the whole write, so the pair never diverges.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let primary_buffer = next_account_info(accounts_iter)?;
+    let replica_buffer = next_account_info(accounts_iter)?;
+    let primary_authority = next_account_info(accounts_iter)?;
+    let replica_authority = next_account_info(accounts_iter)?;
+
+    if !primary_authority.is_signer || !replica_authority.is_signer {
+        msg!("Primary and replica authorities must be signers");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let primary_header = validate_replicated_buffer(program_id, primary_buffer)?;
+    let replica_header = validate_replicated_buffer(program_id, replica_buffer)?;
+
+    if primary_header.role != REPLICA_ROLE_PRIMARY || replica_header.role != REPLICA_ROLE_REPLICA {
+        msg!("The primary buffer must be passed before the replica buffer");
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    // both headers are derived from the same authorities, so the buffers belong to the same pair
+    if primary_header.primary_authority != *primary_authority.key
+        || primary_header.replica_authority != *replica_authority.key
+        || replica_header.primary_authority != *primary_authority.key
+        || replica_header.replica_authority != *replica_authority.key
+    {
+        msg!("The authorities must match both buffers of the pair");
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    // everything is checked up front, a partial write would leave the replica diverging from the primary
+    for (buffer, buffer_header) in [
+        (primary_buffer, &primary_header),
+        (replica_buffer, &replica_header),
+    ] {
+        if buffer_header.frozen {
+            msg!("Replicated buffer {} is frozen", buffer.key);
+            return Err(EchoError::BufferFrozen.into());
+        }
+
+        if buffer.data_len() - REPLICATED_ECHO_HEADER_SIZE < data.len() {
+            msg!(
+                "Replicated buffer {} can't hold {} bytes",
+                buffer.key,
+                data.len()
+            );
+            return Err(EchoError::BufferFull.into());
+        }
+    }
+
+    for buffer in [primary_buffer, replica_buffer] {
+        let buffer_data = &mut buffer.data.borrow_mut()[REPLICATED_ECHO_HEADER_SIZE..];
+        buffer_data[..data.len()].copy_from_slice(&data);
+        buffer_data[data.len()..].fill(0);
+    }
+
+    // read back both payloads before returning
+    for buffer in [primary_buffer, replica_buffer] {
+        if buffer.data.borrow()[REPLICATED_ECHO_HEADER_SIZE..][..data.len()] != data[..] {
+            msg!("Replicated write to {} failed", buffer.key);
+            return Err(EchoError::InvalidAccountData.into());
+        }
+    }
+
+    msg!("Wrote {} bytes to both buffers", data.len());
+
+    Ok(())
+}
+
+/// Sets the `frozen` flag of a buffer of the pair, only its own authority can.
+pub fn process_set_frozen(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    frozen: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let replicated_buffer = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        msg!("Authority must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let mut buffer_header = validate_replicated_buffer(program_id, replicated_buffer)?;
+
+    let buffer_authority = if buffer_header.role == REPLICA_ROLE_PRIMARY {
+        buffer_header.primary_authority
+    } else {
+        buffer_header.replica_authority
+    };
+
+    if buffer_authority != *authority.key {
+        msg!("Only the authority of the buffer can freeze it");
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    buffer_header.frozen = frozen;
+    replicated_buffer.data.borrow_mut()[..REPLICATED_ECHO_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Frozen: {}", frozen);
+
+    Ok(())
+}
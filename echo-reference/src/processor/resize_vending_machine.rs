@@ -0,0 +1,114 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE},
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::transfer,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::EchoError,
+    processor::vending_machine_echo::{
+        assert_admin, assert_vending_machine_address, read_vending_machine_header,
+    },
+    state::VENDING_MACHINE_BUFF_HEADER_SIZE,
+};
+
+struct Context<'a, 'b: 'a> {
+    vending_machine_buffer: &'a AccountInfo<'b>,
+    admin: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            vending_machine_buffer: next_account_info(accounts_iter)?,
+            admin: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.vending_machine_buffer.is_writable {
+            msg!("Vending machine buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Reallocates the vending machine buffer to `new_size` bytes, the `payer` tops up the lamports when the rent exempt
+/// minimum grows. The lamports in excess after shrinking stay in the buffer.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], new_size: u64) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let buffer_header = read_vending_machine_header(program_id, ctx.vending_machine_buffer)?;
+    assert_vending_machine_address(program_id, ctx.vending_machine_buffer, &buffer_header)?;
+    assert_admin(&buffer_header, ctx.admin)?;
+
+    // the data written so far must still fit
+    let min_size = VENDING_MACHINE_BUFF_HEADER_SIZE as u64 + buffer_header.data_len as u64;
+    if new_size < min_size {
+        msg!(
+            "Invalid buffer length {}, the header and data need {}",
+            new_size,
+            min_size
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // the runtime only lets an instruction grow an account by a limited amount
+    let old_size = ctx.vending_machine_buffer.data_len();
+    let new_size = new_size as usize;
+    if new_size.saturating_sub(old_size) > MAX_PERMITTED_DATA_INCREASE {
+        msg!(
+            "Can't grow the buffer by {} bytes, at most {} per instruction",
+            new_size - old_size,
+            MAX_PERMITTED_DATA_INCREASE
+        );
+        return Err(EchoError::DataTooLarge.into());
+    }
+
+    let rent_lamports = Rent::get()?.minimum_balance(new_size);
+    let lamports = ctx.vending_machine_buffer.lamports();
+    if lamports < rent_lamports {
+        invoke(
+            &transfer(
+                ctx.payer.key,
+                ctx.vending_machine_buffer.key,
+                rent_lamports - lamports,
+            ),
+            &[
+                ctx.payer.clone(),
+                ctx.vending_machine_buffer.clone(),
+                ctx.system_program.clone(),
+            ],
+        )?;
+    }
+
+    // the grown region is zeroed rather than trusting the memory the runtime reserved for it
+    ctx.vending_machine_buffer.realloc(new_size, true)?;
+
+    msg!("Vending machine buffer len: {} -> {}", old_size, new_size);
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    hash::hash,
+    msg,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    state::{SchemaEchoHeader, SCHEMA_ECHO_HEADER_SIZE},
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+    schema_descriptor: Vec<u8>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let schema_buffer = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+
+    if !schema_buffer.is_writable {
+        msg!("Schema buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !authority.is_signer {
+        msg!("Authority account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if schema_buffer.owner != program_id {
+        msg!("Schema buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = &mut (*schema_buffer.data).borrow_mut();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < SCHEMA_ECHO_HEADER_SIZE {
+        msg!("Invalid schema buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = SchemaEchoHeader::try_from_slice(&buffer[..SCHEMA_ECHO_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address, which also checks the authority
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"schema", authority.key.as_ref()], program_id);
+
+    if pda != *schema_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // the data itself isn't parsed, the writer declares the schema it was serialized with
+    if hash(&schema_descriptor).to_bytes() != buffer_header.schema_hash {
+        msg!("The schema descriptor doesn't match the schema hash of the buffer");
+        return Err(EchoError::InvalidSchemaDescriptor.into());
+    }
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[SCHEMA_ECHO_HEADER_SIZE..];
+    let len = buffer_data.len().min(data.len());
+    buffer_data[..len].copy_from_slice(&data[..len]);
+    buffer_data[len..].fill(0);
+
+    msg!("Wrote {} bytes", len);
+
+    Ok(())
+}
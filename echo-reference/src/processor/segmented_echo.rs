@@ -0,0 +1,142 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{SegmentedHeader, SEGMENTED_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    segmented_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            segmented_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.segmented_buffer.is_writable {
+            msg!("Segmented buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Reads the header of `segmented_buffer` after checking that it is the segmented buffer of `authority`.
+fn validate_segmented_buffer(
+    program_id: &Pubkey,
+    segmented_buffer: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<SegmentedHeader, ProgramError> {
+    if segmented_buffer.owner != program_id {
+        msg!("Segmented buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = segmented_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < SEGMENTED_HEADER_SIZE {
+        msg!("Invalid segmented buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = SegmentedHeader::try_from_slice(&buffer[..SEGMENTED_HEADER_SIZE])?;
+
+    if buffer.len()
+        != SEGMENTED_HEADER_SIZE
+            + buffer_header.segment_count as usize * buffer_header.segment_size as usize
+    {
+        msg!("Invalid segmented buffer size, {}", buffer.len());
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(&[b"segmented", authority.as_ref()], program_id);
+
+    if pda != *segmented_buffer.key {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+fn check_index(buffer_header: &SegmentedHeader, index: u8) -> ProgramResult {
+    if index >= buffer_header.segment_count {
+        msg!(
+            "Invalid segment {}, the buffer has {} segments",
+            index,
+            buffer_header.segment_count
+        );
+        return Err(EchoError::SegmentOutOfRange.into());
+    }
+
+    Ok(())
+}
+
+/// Replaces segment `index` with `data`, truncated or zero-padded to the segment size, and marks it as written.
+pub fn process_write(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index: u8,
+    data: Vec<u8>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let mut buffer_header =
+        validate_segmented_buffer(program_id, ctx.segmented_buffer, ctx.authority.key)?;
+    check_index(&buffer_header, index)?;
+
+    let buffer = &mut (*ctx.segmented_buffer.data).borrow_mut();
+    let segment = &mut buffer[SEGMENTED_HEADER_SIZE..][buffer_header.segment_range(index)];
+
+    let bytes_written = segment.len().min(data.len());
+    segment[..bytes_written].copy_from_slice(&data[..bytes_written]);
+    segment[bytes_written..].fill(0);
+
+    buffer_header.set_populated(index, true);
+    buffer[..SEGMENTED_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Wrote {} bytes to segment {}", bytes_written, index);
+
+    Ok(())
+}
+
+/// Zeroes segment `index` and marks it as unwritten.
+pub fn process_clear(program_id: &Pubkey, accounts: &[AccountInfo], index: u8) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let mut buffer_header =
+        validate_segmented_buffer(program_id, ctx.segmented_buffer, ctx.authority.key)?;
+    check_index(&buffer_header, index)?;
+
+    let buffer = &mut (*ctx.segmented_buffer.data).borrow_mut();
+    buffer[SEGMENTED_HEADER_SIZE..][buffer_header.segment_range(index)].fill(0);
+
+    buffer_header.set_populated(index, false);
+    buffer[..SEGMENTED_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Cleared segment {}", index);
+
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{SetHeader, SET_HEADER_SIZE, SET_MEMBER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    set_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            set_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        Ok(ctx)
+    }
+
+    /// Additional checks for instructions that modify the set.
+    fn check_mutable(&self) -> ProgramResult {
+        if !self.set_buffer.is_writable {
+            msg!("Set buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !self.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the header of `set_buffer` after checking that it is the set buffer of `authority`.
+fn validate_set_buffer(
+    program_id: &Pubkey,
+    set_buffer: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<SetHeader, ProgramError> {
+    if set_buffer.owner != program_id {
+        msg!("Set buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = set_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < SET_HEADER_SIZE {
+        msg!("Invalid set buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = SetHeader::try_from_slice(&buffer[..SET_HEADER_SIZE])?;
+
+    if buffer.len() != SET_HEADER_SIZE + buffer_header.capacity as usize * SET_MEMBER_SIZE
+        || buffer_header.count > buffer_header.capacity
+    {
+        msg!("Invalid set buffer size, {}", buffer.len());
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(&[b"set", authority.as_ref()], program_id);
+
+    if pda != *set_buffer.key {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Binary search over the sorted members, returns the index of `member` or where it would be inserted.
+pub(crate) fn search(members: &[u8], member: &Pubkey) -> Result<usize, usize> {
+    let count = members.len() / SET_MEMBER_SIZE;
+    let (mut low, mut high) = (0, count);
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let current = &members[mid * SET_MEMBER_SIZE..(mid + 1) * SET_MEMBER_SIZE];
+
+        match current.cmp(member.as_ref()) {
+            std::cmp::Ordering::Less => low = mid + 1,
+            std::cmp::Ordering::Greater => high = mid,
+            std::cmp::Ordering::Equal => return Ok(mid),
+        }
+    }
+
+    Err(low)
+}
+
+/// Inserts `member` keeping the members sorted, so lookups can use a binary search.
+pub fn process_add(program_id: &Pubkey, accounts: &[AccountInfo], member: Pubkey) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+    ctx.check_mutable()?;
+
+    let mut buffer_header = validate_set_buffer(program_id, ctx.set_buffer, ctx.authority.key)?;
+
+    if buffer_header.count == buffer_header.capacity {
+        msg!("Set is full, capacity {}", buffer_header.capacity);
+        return Err(EchoError::SetFull.into());
+    }
+
+    let buffer = &mut (*ctx.set_buffer.data).borrow_mut();
+    let count = buffer_header.count as usize;
+
+    let members = &mut buffer[SET_HEADER_SIZE..];
+    let index = match search(&members[..count * SET_MEMBER_SIZE], &member) {
+        Ok(_) => {
+            msg!("{} is already a member", member);
+            return Err(EchoError::DuplicateMember.into());
+        }
+        Err(index) => index,
+    };
+
+    // shift every member after the insertion point one slot to the right
+    members.copy_within(
+        index * SET_MEMBER_SIZE..count * SET_MEMBER_SIZE,
+        (index + 1) * SET_MEMBER_SIZE,
+    );
+    members[index * SET_MEMBER_SIZE..(index + 1) * SET_MEMBER_SIZE]
+        .copy_from_slice(member.as_ref());
+
+    buffer_header.count += 1;
+    buffer[..SET_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Added {}, set count: {}", member, buffer_header.count);
+
+    Ok(())
+}
+
+pub fn process_remove(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    member: Pubkey,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+    ctx.check_mutable()?;
+
+    let mut buffer_header = validate_set_buffer(program_id, ctx.set_buffer, ctx.authority.key)?;
+
+    let buffer = &mut (*ctx.set_buffer.data).borrow_mut();
+    let count = buffer_header.count as usize;
+
+    let members = &mut buffer[SET_HEADER_SIZE..];
+    let index = search(&members[..count * SET_MEMBER_SIZE], &member).map_err(|_| {
+        msg!("{} is not a member", member);
+        EchoError::MemberNotFound
+    })?;
+
+    // shift every member after the removed one a slot to the left and clear the freed slot
+    members.copy_within(
+        (index + 1) * SET_MEMBER_SIZE..count * SET_MEMBER_SIZE,
+        index * SET_MEMBER_SIZE,
+    );
+    members[(count - 1) * SET_MEMBER_SIZE..count * SET_MEMBER_SIZE].fill(0);
+
+    buffer_header.count -= 1;
+    buffer[..SET_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Removed {}, set count: {}", member, buffer_header.count);
+
+    Ok(())
+}
+
+/// Logs whether `member` is in the set, the authority is passed along only to validate the set buffer address.
+pub fn process_contains(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    member: Pubkey,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let buffer_header = validate_set_buffer(program_id, ctx.set_buffer, ctx.authority.key)?;
+
+    let buffer = ctx.set_buffer.data.borrow();
+    let members =
+        &buffer[SET_HEADER_SIZE..SET_HEADER_SIZE + buffer_header.count as usize * SET_MEMBER_SIZE];
+
+    match search(members, &member) {
+        Ok(_) => msg!("{} is a member", member),
+        Err(_) => msg!("{} is not a member", member),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    processor::authorized_echo::validate_authorized_buffer,
+    state::{
+        BufferMetadata, MetadataUri, BUFFER_METADATA_SIZE, METADATA_DESCRIPTION_LEN,
+        METADATA_NAME_LEN,
+    },
+};
+
+struct Context<'a, 'b: 'a> {
+    metadata: &'a AccountInfo<'b>,
+    authorized_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    /// Parses the accounts shared by `SetBufferMetadata` and `CloseBufferMetadata`, the authority signs and receives
+    /// or pays the lamports of the metadata account.
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+    ) -> Result<Self, ProgramError> {
+        let ctx = Self {
+            metadata: next_account_info(accounts_iter)?,
+            authorized_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.metadata.is_writable || !ctx.authority.is_writable {
+            msg!("Metadata and authority accounts must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        validate_authorized_buffer(program_id, ctx.authorized_buffer, ctx.authority.key)?;
+
+        Ok(ctx)
+    }
+}
+
+/// Address and bump seed of the metadata PDA of `authorized_buffer`.
+fn metadata_address(program_id: &Pubkey, authorized_buffer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"metadata", authorized_buffer.as_ref()], program_id)
+}
+
+/// Checks that `metadata` is the initialized metadata PDA of `authorized_buffer`.
+fn read_metadata(
+    program_id: &Pubkey,
+    metadata: &AccountInfo,
+    authorized_buffer: &Pubkey,
+) -> Result<BufferMetadata, ProgramError> {
+    // only accounts owned by the Echo Program can have been written by `SetBufferMetadata`
+    if metadata.owner != program_id {
+        msg!("Metadata account must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let data = metadata.data.borrow();
+
+    // check the size of the account before trying to read it
+    if data.len() < BUFFER_METADATA_SIZE {
+        msg!("Invalid metadata account size, {}", data.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_metadata = BufferMetadata::try_from_slice(&data[..BUFFER_METADATA_SIZE])?;
+
+    let (pda, bump_seed) = metadata_address(program_id, authorized_buffer);
+
+    if pda != *metadata.key || bump_seed != buffer_metadata.bump_seed {
+        msg!("Invalid account address or buffer");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_metadata)
+}
+
+/// Decodes a zero-padded UTF-8 field for logging.
+fn trim_padding(field: &[u8]) -> String {
+    let len = field
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |i| i + 1);
+    String::from_utf8_lossy(&field[..len]).into_owned()
+}
+
+/// Creates the metadata PDA of the buffer on first use, paid by the authority, then replaces all three fields.
+pub fn process_set(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    name: [u8; METADATA_NAME_LEN],
+    description: [u8; METADATA_DESCRIPTION_LEN],
+    uri: MetadataUri,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let ctx = Context::parse(program_id, accounts_iter)?;
+
+    let bump_seed = if ctx.metadata.data_is_empty() {
+        let system_program = next_account_info(accounts_iter)?;
+
+        if *system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        let (pda, bump_seed) = metadata_address(program_id, ctx.authorized_buffer.key);
+
+        if pda != *ctx.metadata.key {
+            msg!("Invalid metadata address");
+            return Err(EchoError::InvalidAccountAddress.into());
+        }
+
+        invoke_signed(
+            &create_account(
+                ctx.authority.key,
+                ctx.metadata.key,
+                Rent::get()?.minimum_balance(BUFFER_METADATA_SIZE),
+                BUFFER_METADATA_SIZE as u64,
+                program_id,
+            ),
+            &[
+                ctx.metadata.clone(),
+                ctx.authority.clone(),
+                system_program.clone(),
+            ],
+            &[&[
+                b"metadata",
+                ctx.authorized_buffer.key.as_ref(),
+                &[bump_seed],
+            ]],
+        )?;
+
+        bump_seed
+    } else {
+        read_metadata(program_id, ctx.metadata, ctx.authorized_buffer.key)?.bump_seed
+    };
+
+    let buffer_metadata = BufferMetadata {
+        bump_seed,
+        name,
+        description,
+        uri,
+    };
+
+    ctx.metadata.data.borrow_mut()[..BUFFER_METADATA_SIZE]
+        .copy_from_slice(&buffer_metadata.try_to_vec().unwrap());
+
+    msg!("Name: {}", trim_padding(&name));
+
+    Ok(())
+}
+
+/// Logs the name, description and URI of the buffer, anyone can read them.
+pub fn process_get(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let metadata = next_account_info(accounts_iter)?;
+    let authorized_buffer = next_account_info(accounts_iter)?;
+
+    let buffer_metadata = read_metadata(program_id, metadata, authorized_buffer.key)?;
+
+    msg!("Name: {}", trim_padding(&buffer_metadata.name));
+    msg!(
+        "Description: {}",
+        trim_padding(&buffer_metadata.description)
+    );
+    msg!("URI: {}", trim_padding(&buffer_metadata.uri.0));
+
+    Ok(())
+}
+
+/// Closes the metadata PDA of the buffer, sending its lamports to the authority.
+pub fn process_close(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(program_id, &mut accounts.iter())?;
+
+    read_metadata(program_id, ctx.metadata, ctx.authorized_buffer.key)?;
+
+    let lamports = ctx.metadata.lamports();
+    **ctx.authority.lamports.borrow_mut() = ctx
+        .authority
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(EchoError::Overflow)?;
+    **ctx.metadata.lamports.borrow_mut() = 0;
+
+    // the runtime deletes the account once it has no lamports left, clear it in case it is funded again
+    ctx.metadata.data.borrow_mut().fill(0);
+
+    msg!("Closed buffer metadata, reclaimed {} lamports", lamports);
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::instructions,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    processor::program_controlled_echo::check_caller,
+    state::{SharedBufferHeader, SHARED_BUFF_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    shared_buffer: &'a AccountInfo<'b>,
+    instructions_sysvar: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            shared_buffer: next_account_info(accounts_iter)?,
+            instructions_sysvar: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.shared_buffer.is_writable {
+            msg!("Shared buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !instructions::check_id(ctx.instructions_sysvar.key) {
+            msg!("Invalid instructions sysvar");
+            return Err(EchoError::InvalidAccountAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Copies `data` past the header, zeroing the rest of it. Nobody signs for the write, it is only accepted through a
+/// cross program invocation from the `allowed_program` of the group.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if ctx.shared_buffer.owner != program_id {
+        msg!("Shared buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = &mut (*ctx.shared_buffer.data).borrow_mut();
+
+    // check the size of the account before trying to read it
+    if buffer.len() <= SHARED_BUFF_HEADER_SIZE {
+        msg!("Invalid shared buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = SharedBufferHeader::try_from_slice(&buffer[..SHARED_BUFF_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"shared", buffer_header.group_seed.as_ref()], program_id);
+
+    if pda != *ctx.shared_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid shared buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    check_caller(
+        program_id,
+        ctx.instructions_sysvar,
+        &buffer_header.allowed_program,
+    )?;
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[SHARED_BUFF_HEADER_SIZE..];
+    let len = buffer_data.len().min(data.len());
+    buffer_data[..len].copy_from_slice(&data[..len]);
+    buffer_data[len..].fill(0);
+
+    msg!("Wrote {} bytes", len);
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{SlotHeader, SLOT_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    slot_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            slot_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        Ok(ctx)
+    }
+}
+
+/// Reads the header of `slot_buffer` after checking that it is the slot buffer of `authority`.
+fn read_slot_header(
+    program_id: &Pubkey,
+    slot_buffer: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<SlotHeader, ProgramError> {
+    if slot_buffer.owner != program_id {
+        msg!("Slot buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = slot_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() <= SLOT_HEADER_SIZE {
+        msg!("Invalid slot buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = SlotHeader::try_from_slice(&buffer[..SLOT_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(&[b"slot", authority.as_ref()], program_id);
+
+    if pda != *slot_buffer.key {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Copies `data` to the start of the buffer, the bytes past it are kept. The first write of a new slot zeroes the
+/// whole buffer beforehand, so nothing written in a previous slot survives it.
+///
+/// The data is only zeroed by a write, if a slot passes without one the account still holds the data of
+/// `last_write_slot`. Readers must compare it with the current slot instead of trusting the data as is.
+pub fn process_write(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    if !ctx.slot_buffer.is_writable {
+        msg!("Slot buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !ctx.authority.is_signer {
+        msg!("Authority account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let mut buffer_header = read_slot_header(program_id, ctx.slot_buffer, ctx.authority.key)?;
+    let slot = Clock::get()?.slot;
+
+    let buffer = &mut (*ctx.slot_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[SLOT_HEADER_SIZE..];
+
+    if slot != buffer_header.last_write_slot {
+        msg!(
+            "Resetting data of slot {}, now {}",
+            buffer_header.last_write_slot,
+            slot
+        );
+        buffer_data.fill(0);
+        buffer_header.last_write_slot = slot;
+    }
+
+    let bytes_written = buffer_data.len().min(data.len());
+    buffer_data[..bytes_written].copy_from_slice(&data[..bytes_written]);
+
+    buffer[..SLOT_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Wrote {} bytes in slot {}", bytes_written, slot);
+
+    Ok(())
+}
@@ -0,0 +1,252 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    processor::authorized_echo::{check_complete, validate_authorized_buffer},
+    state::{SnapshotHeader, AUTH_BUFF_HEADER_SIZE, SNAPSHOT_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    authorized_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    snapshot: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            authorized_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+            snapshot: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if !ctx.snapshot.is_writable {
+            msg!("Snapshot account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.payer.is_signer {
+            msg!("Payer must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        if *ctx.system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Seeds prefix of the snapshots taken at a chosen index
+const SNAPSHOT_PREFIX: &[u8] = b"snapshot";
+/// Seeds prefix of the snapshots indexed by the slot they were taken in, kept apart from the chosen indexes
+const SLOT_SNAPSHOT_PREFIX: &[u8] = b"slot_snapshot";
+
+/// Copies the payload of the authorized buffer into a new snapshot account, which is never written again.
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    snapshot_index: u64,
+) -> ProgramResult {
+    create_snapshot(program_id, accounts, SNAPSHOT_PREFIX, snapshot_index)
+}
+
+/// Same as `process` at the PDA seeded by `"slot_snapshot"` with the current slot as the snapshot index, so the
+/// snapshot address tells when it was taken.
+pub fn process_at_slot(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    create_snapshot(
+        program_id,
+        accounts,
+        SLOT_SNAPSHOT_PREFIX,
+        Clock::get()?.slot,
+    )
+}
+
+fn create_snapshot(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    prefix: &[u8],
+    snapshot_index: u64,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let buffer_header =
+        validate_authorized_buffer(program_id, ctx.authorized_buffer, ctx.authority.key)?;
+    check_complete(&buffer_header)?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            prefix,
+            ctx.authorized_buffer.key.as_ref(),
+            &snapshot_index.to_le_bytes(),
+        ],
+        program_id,
+    );
+
+    if *ctx.snapshot.key != pda {
+        msg!("Invalid snapshot address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // snapshots are immutable, an index can only be used once
+    if ctx.snapshot.lamports() > 0 || !ctx.snapshot.data_is_empty() {
+        msg!("Snapshot {} already exists", snapshot_index);
+        return Err(EchoError::SnapshotAlreadyExists.into());
+    }
+
+    let payload_len = ctx.authorized_buffer.data_len() - AUTH_BUFF_HEADER_SIZE;
+    let snapshot_size = SNAPSHOT_HEADER_SIZE + payload_len;
+
+    // call the system program to create the account
+    let create_account_ix = create_account(
+        ctx.payer.key,
+        ctx.snapshot.key,
+        Rent::get()?.minimum_balance(snapshot_size),
+        snapshot_size as u64,
+        program_id,
+    );
+
+    invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.snapshot.clone(),
+            ctx.payer.clone(),
+            ctx.system_program.clone(),
+        ],
+        &[&[
+            prefix,
+            ctx.authorized_buffer.key.as_ref(),
+            &snapshot_index.to_le_bytes(),
+            &[bump_seed],
+        ]],
+    )?;
+
+    let snapshot_header = SnapshotHeader {
+        bump_seed,
+        source_buffer: *ctx.authorized_buffer.key,
+        slot: Clock::get()?.slot,
+        snapshot_index,
+    };
+
+    let snapshot = &mut (*ctx.snapshot.data).borrow_mut();
+    snapshot[..SNAPSHOT_HEADER_SIZE].copy_from_slice(&snapshot_header.try_to_vec().unwrap());
+    snapshot[SNAPSHOT_HEADER_SIZE..]
+        .copy_from_slice(&ctx.authorized_buffer.data.borrow()[AUTH_BUFF_HEADER_SIZE..]);
+
+    msg!("Snapshot index: {}", snapshot_index);
+    msg!("Slot: {}", snapshot_header.slot);
+    msg!("Copied {} bytes", payload_len);
+
+    Ok(())
+}
+
+/// Logs the address and slot of every snapshot passed after the authorized buffer, failing on any account that isn't
+/// a snapshot of it. Snapshots can't be enumerated on-chain, the caller finds them with `getProgramAccounts`.
+pub fn process_list(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authorized_buffer = next_account_info(accounts_iter)?;
+
+    for snapshot in accounts_iter {
+        if snapshot.owner != program_id {
+            msg!(
+                "Snapshot {} must be owned by the Echo Program",
+                snapshot.key
+            );
+            return Err(EchoError::InvalidAccountOwner.into());
+        }
+
+        let snapshot_header = {
+            let data = snapshot.data.borrow();
+
+            // check the size of the account before trying to read it
+            if data.len() < SNAPSHOT_HEADER_SIZE {
+                msg!("Invalid snapshot size, {}", data.len());
+                return Err(EchoError::AccountNotInitialized.into());
+            }
+
+            SnapshotHeader::try_from_slice(&data[..SNAPSHOT_HEADER_SIZE])?
+        };
+
+        if snapshot_header.source_buffer != *authorized_buffer.key {
+            msg!(
+                "{} is not a snapshot of {}",
+                snapshot.key,
+                authorized_buffer.key
+            );
+            return Err(EchoError::InvalidAccountAddress.into());
+        }
+
+        // the index of a slot snapshot is the slot it was taken in
+        let kind =
+            if is_snapshot_address(program_id, snapshot.key, &snapshot_header, SNAPSHOT_PREFIX) {
+                "Snapshot"
+            } else if snapshot_header.snapshot_index == snapshot_header.slot
+                && is_snapshot_address(
+                    program_id,
+                    snapshot.key,
+                    &snapshot_header,
+                    SLOT_SNAPSHOT_PREFIX,
+                )
+            {
+                "Slot snapshot"
+            } else {
+                msg!(
+                    "{} is not a snapshot of {}",
+                    snapshot.key,
+                    authorized_buffer.key
+                );
+                return Err(EchoError::InvalidAccountAddress.into());
+            };
+
+        msg!("{} {} at slot {}", kind, snapshot.key, snapshot_header.slot);
+    }
+
+    Ok(())
+}
+
+/// Whether `snapshot` is the PDA seeded by `prefix`, the source buffer and the index of `snapshot_header`.
+fn is_snapshot_address(
+    program_id: &Pubkey,
+    snapshot: &Pubkey,
+    snapshot_header: &SnapshotHeader,
+    prefix: &[u8],
+) -> bool {
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            prefix,
+            snapshot_header.source_buffer.as_ref(),
+            &snapshot_header.snapshot_index.to_le_bytes(),
+        ],
+        program_id,
+    );
+
+    pda == *snapshot && bump_seed == snapshot_header.bump_seed
+}
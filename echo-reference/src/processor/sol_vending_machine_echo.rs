@@ -0,0 +1,189 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction::transfer,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    events::{EchoEvent, VendingMachineWriteEvent},
+    state::{SolVendingMachineHeader, SOL_VENDING_MACHINE_HEADER_SIZE},
+};
+
+/// Address and bump seed of the vault collecting the payments of `vending_machine_buffer`. It is never allocated,
+/// staying a system account so its lamports can be moved with a signed system transfer.
+pub(crate) fn vault_address(program_id: &Pubkey, vending_machine_buffer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"sol_vending_machine_vault",
+            vending_machine_buffer.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Checks that `vending_machine_buffer` is an initialized SOL vending machine at its canonical PDA and that `vault`
+/// is its vault.
+fn validate_sol_vending_machine(
+    program_id: &Pubkey,
+    vending_machine_buffer: &AccountInfo,
+    vault: &AccountInfo,
+) -> Result<SolVendingMachineHeader, ProgramError> {
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if vending_machine_buffer.owner != program_id {
+        msg!("Vending machine buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer_header = {
+        let buffer = vending_machine_buffer.data.borrow();
+
+        // check the size of the account before trying to read it
+        if buffer.len() < SOL_VENDING_MACHINE_HEADER_SIZE {
+            msg!("Invalid vending machine buffer size, {}", buffer.len());
+            return Err(EchoError::AccountNotInitialized.into());
+        }
+
+        SolVendingMachineHeader::try_from_slice(&buffer[..SOL_VENDING_MACHINE_HEADER_SIZE])?
+    };
+
+    // verify that the PDA accounts are the correct addresses
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"sol_vending_machine",
+            buffer_header.admin.as_ref(),
+            &buffer_header.price_lamports.to_le_bytes(),
+        ],
+        program_id,
+    );
+
+    if pda != *vending_machine_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or admin");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    if vault_address(program_id, vending_machine_buffer.key)
+        != (*vault.key, buffer_header.vault_bump_seed)
+    {
+        msg!("Invalid vault address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    if !vault.is_writable {
+        msg!("Vault account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    Ok(buffer_header)
+}
+
+fn check_system_program(system_program: &AccountInfo) -> ProgramResult {
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        msg!("Invalid system program");
+        return Err(EchoError::InvalidProgramAddress.into());
+    }
+
+    Ok(())
+}
+
+/// Transfers `price_lamports` from the user to the vault, then copies `data` past the header, zeroing the rest of
+/// the payload.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vending_machine_buffer = next_account_info(accounts_iter)?;
+    let user = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !vending_machine_buffer.is_writable || !user.is_writable {
+        msg!("Vending machine buffer and user accounts must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !user.is_signer {
+        msg!("User account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    check_system_program(system_program)?;
+
+    let buffer_header = validate_sol_vending_machine(program_id, vending_machine_buffer, vault)?;
+
+    if user.lamports() < buffer_header.price_lamports {
+        msg!("User has insufficient lamports");
+        return Err(EchoError::InsufficientFunds.into());
+    }
+
+    invoke(
+        &transfer(user.key, vault.key, buffer_header.price_lamports),
+        &[user.clone(), vault.clone(), system_program.clone()],
+    )?;
+
+    let buffer = &mut (*vending_machine_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[SOL_VENDING_MACHINE_HEADER_SIZE..];
+    let len = buffer_data.len().min(data.len());
+    buffer_data[..len].copy_from_slice(&data[..len]);
+    buffer_data[len..].fill(0);
+
+    EchoEvent::VendingMachineWrite(VendingMachineWriteEvent {
+        buffer: *vending_machine_buffer.key,
+        user: *user.key,
+        len: len as u64,
+        price: buffer_header.price_lamports,
+        slot: Clock::get()?.slot,
+    })
+    .emit();
+
+    Ok(())
+}
+
+/// Moves every lamport of the vault to `destination`, only the admin can withdraw.
+pub fn process_withdraw(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vending_machine_buffer = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let admin = next_account_info(accounts_iter)?;
+    let destination = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !admin.is_signer {
+        msg!("Admin must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    check_system_program(system_program)?;
+
+    let buffer_header = validate_sol_vending_machine(program_id, vending_machine_buffer, vault)?;
+
+    if buffer_header.admin != *admin.key {
+        msg!("Only the admin can withdraw the proceeds");
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    let lamports = vault.lamports();
+
+    invoke_signed(
+        &transfer(vault.key, destination.key, lamports),
+        &[vault.clone(), destination.clone(), system_program.clone()],
+        &[&[
+            b"sol_vending_machine_vault",
+            vending_machine_buffer.key.as_ref(),
+            &[buffer_header.vault_bump_seed],
+        ]],
+    )?;
+
+    msg!("Withdrew {} lamports", lamports);
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction::transfer,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+};
+
+use crate::error::EchoError;
+
+/// Address and bump seed of the sponsor PDA paying rent for the sponsored buffers of `authority`. It is never
+/// allocated, staying a system account so it can fund `create_account` through `invoke_signed`.
+pub(crate) fn sponsor_address(program_id: &Pubkey, authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"sponsor", authority.as_ref()], program_id)
+}
+
+/// Checks that `sponsor` is the sponsor PDA of `authority`, returning its bump seed.
+pub(crate) fn check_sponsor(
+    program_id: &Pubkey,
+    sponsor: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<u8, ProgramError> {
+    let (pda, bump_seed) = sponsor_address(program_id, authority);
+
+    if *sponsor.key != pda {
+        msg!("Invalid sponsor address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    if !sponsor.is_writable {
+        msg!("Sponsor account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    Ok(bump_seed)
+}
+
+fn check_system_program(system_program: &AccountInfo) -> ProgramResult {
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        msg!("Invalid system program");
+        return Err(EchoError::InvalidProgramAddress.into());
+    }
+
+    Ok(())
+}
+
+/// Transfers `lamports` from the payer to the sponsor PDA of `authority`, anyone can fund a sponsor.
+pub fn process_fund(program_id: &Pubkey, accounts: &[AccountInfo], lamports: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let sponsor = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    check_sponsor(program_id, sponsor, authority.key)?;
+    check_system_program(system_program)?;
+
+    if !payer.is_signer {
+        msg!("Payer must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    invoke(
+        &transfer(payer.key, sponsor.key, lamports),
+        &[payer.clone(), sponsor.clone(), system_program.clone()],
+    )?;
+
+    msg!("Funded sponsor with {} lamports", lamports);
+
+    Ok(())
+}
+
+/// Moves every lamport of the sponsor PDA of `authority` to `destination`.
+pub fn process_drain(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    authority: Pubkey,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let sponsor = next_account_info(accounts_iter)?;
+    let authority_info = next_account_info(accounts_iter)?;
+    let destination = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if *authority_info.key != authority || !authority_info.is_signer {
+        msg!("Only the authority can drain its sponsor");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let bump_seed = check_sponsor(program_id, sponsor, &authority)?;
+    check_system_program(system_program)?;
+
+    let lamports = sponsor.lamports();
+
+    invoke_signed(
+        &transfer(sponsor.key, destination.key, lamports),
+        &[sponsor.clone(), destination.clone(), system_program.clone()],
+        &[&[b"sponsor", authority.as_ref(), &[bump_seed]]],
+    )?;
+
+    msg!("Drained {} lamports from the sponsor", lamports);
+
+    Ok(())
+}
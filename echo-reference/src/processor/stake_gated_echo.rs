@@ -0,0 +1,122 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Epoch,
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    stake::{self, state::StakeState},
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    state::{StakeGatedHeader, STAKE_GATED_HEADER_SIZE},
+};
+
+/// Copies `data` past the header when the `staker` authority of `stake_account` signs and the account delegates at
+/// least `min_stake` lamports to the vote account of the buffer.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let stake_gated_buffer = next_account_info(accounts_iter)?;
+    let staker = next_account_info(accounts_iter)?;
+    let stake_account = next_account_info(accounts_iter)?;
+
+    if !stake_gated_buffer.is_writable {
+        msg!("Stake gated buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !staker.is_signer {
+        msg!("Staker must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if stake_gated_buffer.owner != program_id {
+        msg!("Stake gated buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer_header = {
+        let buffer = stake_gated_buffer.data.borrow();
+
+        // check the size of the account before trying to read it
+        if buffer.len() < STAKE_GATED_HEADER_SIZE {
+            msg!("Invalid stake gated buffer size, {}", buffer.len());
+            return Err(EchoError::AccountNotInitialized.into());
+        }
+
+        StakeGatedHeader::try_from_slice(&buffer[..STAKE_GATED_HEADER_SIZE])?
+    };
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"stake_gated",
+            buffer_header.creator.as_ref(),
+            buffer_header.vote_account.as_ref(),
+        ],
+        program_id,
+    );
+
+    if pda != *stake_gated_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or creator");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    // the delegation of an account owned by any other program could be spoofed
+    if *stake_account.owner != stake::program::id() {
+        msg!("Stake account must be owned by the Stake Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    // stake accounts are allocated larger than the state, so the trailing bytes are not read
+    let stake_state = StakeState::deserialize(&mut &stake_account.data.borrow()[..])?;
+
+    let (meta, stake) = match stake_state {
+        StakeState::Stake(meta, stake) => (meta, stake),
+        _ => {
+            msg!("Stake account is not delegated");
+            return Err(EchoError::InvalidAccountData.into());
+        }
+    };
+
+    if meta.authorized.staker != *staker.key {
+        msg!("Only the staker of the stake account can write");
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    if stake.delegation.voter_pubkey != buffer_header.vote_account {
+        msg!(
+            "Stake is delegated to {}, not {}",
+            stake.delegation.voter_pubkey,
+            buffer_header.vote_account
+        );
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    // a deactivating stake no longer counts towards the threshold
+    if stake.delegation.deactivation_epoch != Epoch::MAX
+        || stake.delegation.stake < buffer_header.min_stake
+    {
+        msg!(
+            "Active stake of {} lamports required, {} delegated",
+            buffer_header.min_stake,
+            stake.delegation.stake
+        );
+        return Err(EchoError::InsufficientFunds.into());
+    }
+
+    let buffer = &mut (*stake_gated_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[STAKE_GATED_HEADER_SIZE..];
+    let len = buffer_data.len().min(data.len());
+    buffer_data[..len].copy_from_slice(&data[..len]);
+    buffer_data[len..].fill(0);
+
+    msg!("Wrote {} bytes", len);
+
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token::state::Account as TokenAccount;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{SubscriptionHeader, SUBSCRIPTION_HEADER_SIZE},
+};
+
+/// Reads the header of `subscription_buffer` after checking its PDA.
+fn validate_subscription_buffer(
+    program_id: &Pubkey,
+    subscription_buffer: &AccountInfo,
+) -> Result<SubscriptionHeader, ProgramError> {
+    if !subscription_buffer.is_writable {
+        msg!("Subscription buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if subscription_buffer.owner != program_id {
+        msg!("Subscription buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = subscription_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < SUBSCRIPTION_HEADER_SIZE {
+        msg!("Invalid subscription buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = SubscriptionHeader::try_from_slice(&buffer[..SUBSCRIPTION_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"subscription",
+            buffer_header.authority.as_ref(),
+            buffer_header.mint.as_ref(),
+        ],
+        program_id,
+    );
+
+    if pda != *subscription_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Burns `price_per_period * periods` tokens of the subscription mint from the user and extends the subscription by
+/// `period_slots * periods`. An expired subscription is extended from the current slot, so the lapsed slots are not
+/// paid for.
+pub fn process_renew(program_id: &Pubkey, accounts: &[AccountInfo], periods: u8) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let subscription_buffer = next_account_info(accounts_iter)?;
+    let user = next_account_info(accounts_iter)?;
+    let user_token_account = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !user_token_account.is_writable || !mint.is_writable {
+        msg!("User token account and mint must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !user.is_signer {
+        msg!("User account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        msg!("Invalid token program");
+        return Err(EchoError::InvalidProgramAddress.into());
+    }
+
+    if periods == 0 {
+        msg!("A renewal must pay for at least one period");
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    let mut buffer_header = validate_subscription_buffer(program_id, subscription_buffer)?;
+
+    if *mint.key != buffer_header.mint {
+        msg!("Subscriptions are paid with {}", buffer_header.mint);
+        return Err(EchoError::MintMismatch.into());
+    }
+
+    // the burn also fails on a wrong mint, checking first gives a clearer error
+    if *user_token_account.owner != spl_token::id()
+        || TokenAccount::unpack(&user_token_account.data.borrow())?.mint != buffer_header.mint
+    {
+        msg!("Invalid user token account");
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    let price = buffer_header
+        .price_per_period
+        .checked_mul(periods as u64)
+        .ok_or(EchoError::Overflow)?;
+    let extension = buffer_header
+        .period_slots
+        .checked_mul(periods as u64)
+        .ok_or(EchoError::Overflow)?;
+
+    let slot = Clock::get()?.slot;
+    buffer_header.expires_at_slot = buffer_header
+        .expires_at_slot
+        .max(slot)
+        .checked_add(extension)
+        .ok_or(EchoError::Overflow)?;
+
+    invoke(
+        &spl_token::instruction::burn(
+            &spl_token::id(),
+            user_token_account.key,
+            mint.key,
+            user.key,
+            &[],
+            price,
+        )?,
+        &[
+            token_program.clone(),
+            user_token_account.clone(),
+            mint.clone(),
+            user.clone(),
+        ],
+    )?;
+
+    subscription_buffer.data.borrow_mut()[..SUBSCRIPTION_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!(
+        "Renewed {} periods for {} tokens, expires at slot {}",
+        periods,
+        price,
+        buffer_header.expires_at_slot
+    );
+
+    Ok(())
+}
+
+/// Copies `data` past the header while the subscription is active, only the authority can.
+pub fn process_write(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let subscription_buffer = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        msg!("Authority account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let buffer_header = validate_subscription_buffer(program_id, subscription_buffer)?;
+
+    if buffer_header.authority != *authority.key {
+        msg!("Only the authority {} can write", buffer_header.authority);
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    let slot = Clock::get()?.slot;
+
+    if slot >= buffer_header.expires_at_slot {
+        msg!(
+            "Subscription expired at slot {}, current slot {}",
+            buffer_header.expires_at_slot,
+            slot
+        );
+        return Err(EchoError::SubscriptionExpired.into());
+    }
+
+    let buffer = &mut (*subscription_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[SUBSCRIPTION_HEADER_SIZE..];
+    let len = buffer_data.len().min(data.len());
+    buffer_data[..len].copy_from_slice(&data[..len]);
+    buffer_data[len..].fill(0);
+
+    msg!("Wrote {} bytes", len);
+
+    Ok(())
+}
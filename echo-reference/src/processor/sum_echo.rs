@@ -0,0 +1,127 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{SumHeader, SUM_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    sum_account: &'a AccountInfo<'b>,
+    signer: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            sum_account: next_account_info(accounts_iter)?,
+            signer: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.sum_account.is_writable {
+            msg!("Sum account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.signer.is_signer {
+            msg!("Signer account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Reads the header of `sum_account` after checking that it is the sum account of `authority`.
+fn read_sum_header(
+    program_id: &Pubkey,
+    sum_account: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<SumHeader, ProgramError> {
+    if sum_account.owner != program_id {
+        msg!("Sum account must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let data = sum_account.data.borrow();
+
+    if data.len() != SUM_HEADER_SIZE {
+        msg!("Invalid sum account size, {}", data.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let header = SumHeader::try_from_slice(&data)?;
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(&[b"sum", authority.as_ref()], program_id);
+
+    if pda != *sum_account.key {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(header)
+}
+
+fn write_sum_header(sum_account: &AccountInfo, header: &SumHeader) {
+    sum_account.data.borrow_mut()[..SUM_HEADER_SIZE].copy_from_slice(&header.try_to_vec().unwrap());
+    msg!("Sum value: {}", header.value);
+}
+
+/// Any signer can add to the sum, the authority is passed along only to validate the sum account address.
+pub fn process_add(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+    let authority = accounts.get(2).ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    let mut header = read_sum_header(program_id, ctx.sum_account, authority.key)?;
+    header.value = header.value.checked_add(amount).ok_or_else(|| {
+        msg!("Adding {} to {} overflows", amount, header.value);
+        EchoError::Overflow
+    })?;
+
+    write_sum_header(ctx.sum_account, &header);
+
+    Ok(())
+}
+
+pub fn process_subtract(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let mut header = read_sum_header(program_id, ctx.sum_account, ctx.signer.key)?;
+    header.value = header.value.checked_sub(amount).ok_or_else(|| {
+        msg!("Subtracting {} from {} underflows", amount, header.value);
+        EchoError::Underflow
+    })?;
+
+    write_sum_header(ctx.sum_account, &header);
+
+    Ok(())
+}
+
+pub fn process_reset(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_value: u64,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let mut header = read_sum_header(program_id, ctx.sum_account, ctx.signer.key)?;
+    header.value = new_value;
+
+    write_sum_header(ctx.sum_account, &header);
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::set_return_data,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshSerialize;
+
+use std::mem::{size_of, swap};
+
+use crate::{
+    error::EchoError,
+    processor::authorized_echo::{bump_sequence, check_complete, validate_authorized_buffer},
+    state::AUTH_BUFF_HEADER_SIZE,
+};
+
+/// Largest data region `SwapBuffers` exchanges, it is copied through a temporary on the stack
+pub const MAX_SWAP_SIZE: usize = 1024;
+
+/// Exchanges the data regions and cursors of two authorized buffers of the same `authority`, returning both new
+/// sequences as return data.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let buffer_a = next_account_info(accounts_iter)?;
+    let buffer_b = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+
+    if !buffer_a.is_writable || !buffer_b.is_writable {
+        msg!("Both authorized buffer accounts must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !authority.is_signer {
+        msg!("Authority account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    if buffer_a.key == buffer_b.key {
+        msg!("Cannot swap a buffer with itself");
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // both PDAs are derived from the same authority, so it has sole write access to both
+    let mut header_a = validate_authorized_buffer(program_id, buffer_a, authority.key)?;
+    let mut header_b = validate_authorized_buffer(program_id, buffer_b, authority.key)?;
+    check_complete(&header_a)?;
+    check_complete(&header_b)?;
+
+    let data_a = &mut (*buffer_a.data).borrow_mut();
+    let data_b = &mut (*buffer_b.data).borrow_mut();
+    let len = data_a.len() - AUTH_BUFF_HEADER_SIZE;
+
+    if data_b.len() - AUTH_BUFF_HEADER_SIZE != len {
+        msg!(
+            "Cannot swap data regions of {} and {} bytes",
+            len,
+            data_b.len() - AUTH_BUFF_HEADER_SIZE
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    if len > MAX_SWAP_SIZE {
+        msg!(
+            "Cannot swap {} bytes, at most {} are supported",
+            len,
+            MAX_SWAP_SIZE
+        );
+        return Err(EchoError::DataTooLarge.into());
+    }
+
+    let mut temporary = [0; MAX_SWAP_SIZE];
+    temporary[..len].copy_from_slice(&data_a[AUTH_BUFF_HEADER_SIZE..]);
+    data_a[AUTH_BUFF_HEADER_SIZE..].copy_from_slice(&data_b[AUTH_BUFF_HEADER_SIZE..]);
+    data_b[AUTH_BUFF_HEADER_SIZE..].copy_from_slice(&temporary[..len]);
+
+    // the cursors follow the data, appends continue after the bytes each buffer now holds
+    swap(&mut header_a.cursor, &mut header_b.cursor);
+    bump_sequence(&mut header_a)?;
+    bump_sequence(&mut header_b)?;
+    data_a[..AUTH_BUFF_HEADER_SIZE].copy_from_slice(&header_a.try_to_vec().unwrap());
+    data_b[..AUTH_BUFF_HEADER_SIZE].copy_from_slice(&header_b.try_to_vec().unwrap());
+
+    let mut sequences = Vec::with_capacity(2 * size_of::<u64>());
+    sequences.extend_from_slice(&header_a.sequence.to_le_bytes());
+    sequences.extend_from_slice(&header_b.sequence.to_le_bytes());
+    set_return_data(&sequences);
+
+    msg!("Swapped {} bytes", len);
+
+    Ok(())
+}
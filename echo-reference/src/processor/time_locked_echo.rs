@@ -0,0 +1,172 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{TimeLockHeader, TIME_LOCK_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    time_lock_buffer: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            time_lock_buffer: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+        };
+
+        Ok(ctx)
+    }
+
+    /// Checks the accounts of the instructions only the authority can send.
+    fn check_authority(&self) -> ProgramResult {
+        if !self.time_lock_buffer.is_writable {
+            msg!("Time lock buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !self.authority.is_signer {
+            msg!("Authority account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the header of `time_lock_buffer` after checking that it is the time lock buffer of `authority`.
+fn read_time_lock_header(
+    program_id: &Pubkey,
+    time_lock_buffer: &AccountInfo,
+    authority: &Pubkey,
+) -> Result<TimeLockHeader, ProgramError> {
+    if time_lock_buffer.owner != program_id {
+        msg!("Time lock buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = time_lock_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() <= TIME_LOCK_HEADER_SIZE {
+        msg!("Invalid time lock buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = TimeLockHeader::try_from_slice(&buffer[..TIME_LOCK_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"time_lock", authority.as_ref()], program_id);
+
+    if pda != *time_lock_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or authority");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Copies `data` past the header, zeroing the rest of it. The lock only applies to reads, the authority can write at
+/// any time.
+pub fn process_write(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+    ctx.check_authority()?;
+
+    read_time_lock_header(program_id, ctx.time_lock_buffer, ctx.authority.key)?;
+
+    let buffer = &mut (*ctx.time_lock_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    let buffer_data = &mut buffer[TIME_LOCK_HEADER_SIZE..];
+    let len = buffer_data.len().min(data.len());
+    buffer_data[..len].copy_from_slice(&data[..len]);
+    buffer_data[len..].fill(0);
+
+    msg!("Wrote {} bytes", len);
+
+    Ok(())
+}
+
+/// Logs the data of the buffer once its unlock slot is reached, it stays readable from then on. The authority is
+/// passed along only to validate the time lock buffer address.
+pub fn process_read(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let buffer_header = read_time_lock_header(program_id, ctx.time_lock_buffer, ctx.authority.key)?;
+    let slot = Clock::get()?.slot;
+
+    if slot < buffer_header.unlock_slot {
+        msg!(
+            "Time lock buffer unlocks at slot {}, current slot {}",
+            buffer_header.unlock_slot,
+            slot
+        );
+        return Err(EchoError::TimeLockNotExpired.into());
+    }
+
+    let buffer = ctx.time_lock_buffer.data.borrow();
+    msg!("{:?}", &buffer[TIME_LOCK_HEADER_SIZE..]);
+
+    Ok(())
+}
+
+/// Moves the unlock slot of the buffer to `new_unlock_slot`, which can only push it further. An unlocked buffer can't
+/// be locked again.
+pub fn process_extend(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_unlock_slot: u64,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+    ctx.check_authority()?;
+
+    let mut buffer_header =
+        read_time_lock_header(program_id, ctx.time_lock_buffer, ctx.authority.key)?;
+
+    let slot = Clock::get()?.slot;
+
+    if slot >= buffer_header.unlock_slot {
+        msg!(
+            "Time lock buffer unlocked at slot {}, current slot {}",
+            buffer_header.unlock_slot,
+            slot
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    if new_unlock_slot <= buffer_header.unlock_slot {
+        msg!(
+            "New unlock slot {} must be after the unlock slot {}",
+            new_unlock_slot,
+            buffer_header.unlock_slot
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    buffer_header.unlock_slot = new_unlock_slot;
+    ctx.time_lock_buffer.data.borrow_mut()[..TIME_LOCK_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Unlock slot: {}", new_unlock_slot);
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshSerialize;
+
+use crate::{
+    error::EchoError,
+    processor::vending_machine_echo::{assert_admin, read_vending_machine_header},
+    state::VENDING_MACHINE_BUFF_HEADER_SIZE,
+};
+
+/// Hands the administration of a paid vending machine to `new_admin`, which signs to prove it controls the key.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vending_machine_buffer = next_account_info(accounts_iter)?;
+    let admin = next_account_info(accounts_iter)?;
+    let new_admin = next_account_info(accounts_iter)?;
+
+    if !vending_machine_buffer.is_writable {
+        msg!("Vending machine buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !new_admin.is_signer {
+        msg!("New admin account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let mut buffer_header = read_vending_machine_header(program_id, vending_machine_buffer)?;
+
+    // the address of a permissionless machine is derived from its admin, it would no longer match
+    if buffer_header.free {
+        msg!("The admin of a permissionless vending machine can't be transferred");
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"vending_machine",
+            buffer_header.mint.as_ref(),
+            &buffer_header.price.to_le_bytes(),
+        ],
+        program_id,
+    );
+
+    if pda != *vending_machine_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or mint");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    assert_admin(&buffer_header, admin)?;
+
+    buffer_header.admin = *new_admin.key;
+
+    vending_machine_buffer.data.borrow_mut()[..VENDING_MACHINE_BUFF_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Admin: {}", new_admin.key);
+
+    Ok(())
+}
@@ -1,19 +1,35 @@
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
+    instruction::Instruction,
     msg,
-    program::invoke,
+    program::{invoke, invoke_signed, set_return_data, MAX_RETURN_DATA},
     program_error::ProgramError,
-    program_pack::Pack,
+    program_option::COption,
+    program_pack::{IsInitialized, Pack},
     pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::{create_account, transfer},
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+use spl_token::{
+    error::TokenError,
+    state::{Account as TokenAccount, AccountState, Mint},
 };
-use spl_token::state::{Account as TokenAccount, Mint};
 
-use borsh::BorshDeserialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 
 use crate::{
     error::EchoError,
-    state::{VendingMachineBufferHeader, VENDING_MACHINE_BUFF_HEADER_SIZE},
+    events::{EchoEvent, VendingMachineStatsEvent, VendingMachineWriteEvent},
+    pda::get_associated_token_address_with_program_id,
+    processor::{drain_treasury::validate_treasury, vending_whitelist::assert_whitelisted},
+    state::{
+        VendingMachineBuffer, VendingMachineBufferHeader, VendingUserRecord, PAYMENT_MODE_TREASURY,
+        VENDING_MACHINE_BUFF_HEADER_SIZE, VENDING_USER_RECORD_SIZE,
+    },
 };
 
 struct Context<'a, 'b: 'a> {
@@ -22,6 +38,9 @@ struct Context<'a, 'b: 'a> {
     user_token_account: &'a AccountInfo<'b>,
     vending_machine_mint: &'a AccountInfo<'b>,
     token_program: &'a AccountInfo<'b>,
+    /// The treasury in treasury mode, then the fee treasury and system program when a SOL fee is charged, then the
+    /// user record and system program when a cooldown is set, then the whitelist when it is enabled
+    remaining_accounts: &'a [AccountInfo<'b>],
 }
 
 impl<'a, 'b: 'a> Context<'a, 'b> {
@@ -34,6 +53,7 @@ impl<'a, 'b: 'a> Context<'a, 'b> {
             user_token_account: next_account_info(accounts_iter)?,
             vending_machine_mint: next_account_info(accounts_iter)?,
             token_program: next_account_info(accounts_iter)?,
+            remaining_accounts: accounts_iter.as_slice(),
         };
 
         if !ctx.vending_machine_buffer.is_writable {
@@ -51,34 +71,64 @@ impl<'a, 'b: 'a> Context<'a, 'b> {
             return Err(EchoError::MissingRequiredSignature.into());
         }
 
+        // rejected before any CPI, the one recorded in the header is checked once it is read
+        if !is_token_program(ctx.token_program.key) {
+            msg!(
+                "Invalid token program {}, expected {} or {}",
+                ctx.token_program.key,
+                spl_token::id(),
+                spl_token_2022::id()
+            );
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
         Ok(ctx)
     }
 }
 
-pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
-    let ctx = Context::parse(accounts)?;
+/// Context of a write to a permissionless vending machine, token accounts passed after `creator` are ignored.
+struct FreeContext<'a, 'b: 'a> {
+    vending_machine_buffer: &'a AccountInfo<'b>,
+    user: &'a AccountInfo<'b>,
+    creator: &'a AccountInfo<'b>,
+}
 
-    let _mint = Mint::unpack_unchecked(&ctx.vending_machine_mint.data.borrow()).map_err(|e| {
-        msg!("Invalid mint account");
-        return e;
-    })?;
-    let user_token_account = TokenAccount::unpack_unchecked(&ctx.user_token_account.data.borrow())
-        .map_err(|e| {
-            msg!("Invalid token account");
-            return e;
-        })?;
-
-    if user_token_account.owner != *ctx.user.key {
-        msg!("Invalid token account owner");
-        return Err(EchoError::InvalidAccountData.into());
+impl<'a, 'b: 'a> FreeContext<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            vending_machine_buffer: next_account_info(accounts_iter)?,
+            user: next_account_info(accounts_iter)?,
+            creator: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.vending_machine_buffer.is_writable {
+            msg!("Vending machine buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.user.is_signer {
+            msg!("User account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
     }
+}
 
-    if user_token_account.mint != *ctx.vending_machine_mint.key {
-        msg!("Invalid token account mint");
-        return Err(EchoError::InvalidAccountData.into());
+/// Reads the header of `vending_machine_buffer`, the PDA is checked by the caller as the seeds depend on the mode.
+pub(crate) fn read_vending_machine_header(
+    program_id: &Pubkey,
+    vending_machine_buffer: &AccountInfo,
+) -> Result<VendingMachineBufferHeader, ProgramError> {
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if vending_machine_buffer.owner != program_id {
+        msg!("Vending machine buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
     }
 
-    let buffer = &mut (*ctx.vending_machine_buffer.data).borrow_mut();
+    let buffer = vending_machine_buffer.data.borrow();
 
     // check the size of the account before trying to read it
     if buffer.len() < VENDING_MACHINE_BUFF_HEADER_SIZE {
@@ -86,58 +136,1040 @@ pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) ->
         return Err(EchoError::AccountNotInitialized.into());
     }
 
-    // in order to validate the PDA address, we first read it to access the buffer seed
     let buffer_header =
         VendingMachineBufferHeader::try_from_slice(&buffer[..VENDING_MACHINE_BUFF_HEADER_SIZE])?;
 
-    if user_token_account.amount < buffer_header.price {
+    // an account of the Echo Program with the size of a vending machine that was never initialized as one
+    if !buffer_header.is_initialized {
+        msg!("Vending machine buffer is not initialized");
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    Ok(buffer_header)
+}
+
+/// Fails if `vending_machine_buffer` already holds a vending machine, so that its header is never overwritten.
+pub(crate) fn assert_uninitialized(
+    program_id: &Pubkey,
+    vending_machine_buffer: &AccountInfo,
+) -> ProgramResult {
+    if vending_machine_buffer.owner != program_id {
+        return Ok(());
+    }
+
+    let buffer = vending_machine_buffer.data.borrow();
+    let buffer_header = buffer
+        .get(..VENDING_MACHINE_BUFF_HEADER_SIZE)
+        .and_then(|header| VendingMachineBufferHeader::try_from_slice(header).ok());
+
+    if matches!(buffer_header, Some(buffer_header) if buffer_header.is_initialized) {
+        msg!("Vending machine buffer is already initialized");
+        return Err(EchoError::AccountAlreadyInitialized.into());
+    }
+
+    Ok(())
+}
+
+/// Checks that `admin` is the signing admin of the vending machine, shared by every instruction managing it.
+pub(crate) fn assert_admin(
+    buffer_header: &VendingMachineBufferHeader,
+    admin: &AccountInfo,
+) -> ProgramResult {
+    if !admin.is_signer {
+        msg!("Admin account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    if buffer_header.admin != *admin.key {
+        msg!(
+            "Only the admin {} can manage the vending machine",
+            buffer_header.admin
+        );
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    Ok(())
+}
+
+/// Checks that the PDA derived from the header alone is `vending_machine_buffer`: the mint and price, or the admin that
+/// created a permissionless machine.
+pub(crate) fn assert_vending_machine_address(
+    program_id: &Pubkey,
+    vending_machine_buffer: &AccountInfo,
+    buffer_header: &VendingMachineBufferHeader,
+) -> ProgramResult {
+    // the admin of a permissionless vending machine can't be transferred, it is always the creator
+    let (pda, bump_seed) = match buffer_header.free {
+        true => {
+            Pubkey::find_program_address(&[b"free_vm", buffer_header.admin.as_ref()], program_id)
+        }
+        false => Pubkey::find_program_address(
+            &[
+                b"vending_machine",
+                buffer_header.mint.as_ref(),
+                &buffer_header.price.to_le_bytes(),
+            ],
+            program_id,
+        ),
+    };
+
+    if pda != *vending_machine_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(())
+}
+
+/// The `data_len` of the vending machine once `data` is written, at `offset` or from the start of the payload.
+fn next_data_len(
+    vending_machine_buffer: &AccountInfo,
+    buffer_header: &VendingMachineBufferHeader,
+    data: &[u8],
+    offset: Option<u64>,
+) -> u32 {
+    let payload_len = vending_machine_buffer.data_len() - VENDING_MACHINE_BUFF_HEADER_SIZE;
+
+    match offset {
+        Some(offset) => buffer_header
+            .data_len
+            .max((offset as usize + data.len()) as u32),
+        None => payload_len.min(data.len()) as u32,
+    }
+}
+
+/// Copies `data` past the header, zeroing what is left of the `previous_data_len`, and returns the number of bytes
+/// written. With an `offset`, `data` is copied at that offset of the payload instead, leaving the other bytes
+/// untouched, and must fit.
+fn write_payload(
+    vending_machine_buffer: &AccountInfo,
+    data: &[u8],
+    offset: Option<u64>,
+    previous_data_len: u32,
+) -> u64 {
+    let buffer = &mut (*vending_machine_buffer.data).borrow_mut();
+
+    // this is the 'rest' of the account's data (beyond the header info)
+    copy_payload(
+        &mut buffer[VENDING_MACHINE_BUFF_HEADER_SIZE..],
+        data,
+        offset,
+        previous_data_len,
+    )
+}
+
+/// The copy of `write_payload` on the payload bytes alone. Everything past `previous_data_len` is expected to be
+/// zero already, so only the bytes of the previous data that aren't overwritten are zeroed, instead of the whole
+/// payload on every write.
+///
+/// Only public for the property tests comparing it with the byte by byte write.
+#[doc(hidden)]
+pub fn copy_payload(
+    buffer_data: &mut [u8],
+    data: &[u8],
+    offset: Option<u64>,
+    previous_data_len: u32,
+) -> u64 {
+    if let Some(offset) = offset {
+        let offset = offset as usize;
+        buffer_data[offset..offset + data.len()].copy_from_slice(data);
+        return data.len() as u64;
+    }
+
+    let len = buffer_data.len().min(data.len());
+    buffer_data[..len].copy_from_slice(&data[..len]);
+
+    // past the previous data the payload is already zeroed
+    let previous_data_len = buffer_data.len().min(previous_data_len as usize);
+    if previous_data_len > len {
+        buffer_data[len..previous_data_len].fill(0);
+    }
+
+    len as u64
+}
+
+/// Checks that `data` written at `offset` fits in the payload of `vending_machine_buffer`.
+fn check_write_bounds(
+    vending_machine_buffer: &AccountInfo,
+    data: &[u8],
+    offset: u64,
+) -> ProgramResult {
+    let payload_len = (vending_machine_buffer.data_len() - VENDING_MACHINE_BUFF_HEADER_SIZE) as u64;
+
+    if offset > payload_len {
+        msg!(
+            "Offset {} is past the payload of {} bytes",
+            offset,
+            payload_len
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    if data.len() as u64 > payload_len - offset {
+        msg!(
+            "{} bytes at offset {} don't fit in the payload of {} bytes",
+            data.len(),
+            offset,
+            payload_len
+        );
+        return Err(EchoError::DataTooLarge.into());
+    }
+
+    Ok(())
+}
+
+/// The SPL Token-2022 program, its mints and token accounts start with the layout of the Token Program ones, followed
+/// by their extensions
+pub mod spl_token_2022 {
+    solana_program::declare_id!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+    /// Accounts with extensions have their type at the length of a token account, mints are zero padded up to it
+    pub const ACCOUNT_TYPE_OFFSET: usize = 165;
+    pub const ACCOUNT_TYPE_MINT: u8 = 1;
+    pub const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+    /// Length of a multisig, that no account with extensions can have so the two are never confused
+    pub const MULTISIG_LEN: usize = 355;
+
+    /// The base state of an account with extensions, `None` unless it is of `account_type`. The base of a mint must be
+    /// followed by zero padding up to the account type.
+    pub fn base_with_extensions(data: &[u8], base_len: usize, account_type: u8) -> Option<&[u8]> {
+        if data.len() <= ACCOUNT_TYPE_OFFSET
+            || data.len() == MULTISIG_LEN
+            || data[ACCOUNT_TYPE_OFFSET] != account_type
+            || data[base_len..ACCOUNT_TYPE_OFFSET]
+                .iter()
+                .any(|byte| *byte != 0)
+        {
+            return None;
+        }
+
+        Some(&data[..base_len])
+    }
+}
+
+/// Whether vending machines can be paid with the tokens of `token_program`.
+pub(crate) fn is_token_program(token_program: &Pubkey) -> bool {
+    *token_program == spl_token::id() || spl_token_2022::check_id(token_program)
+}
+
+/// The base state of an account of `token_program`. Accounts of the Token Program must have the exact length of `T`,
+/// Token-2022 ones can also carry extensions after an account type matching `account_type`, the extensions are
+/// ignored.
+fn base_state<T: Pack + IsInitialized>(
+    data: &[u8],
+    token_program: &Pubkey,
+    account_type: u8,
+) -> Result<T, ProgramError> {
+    if data.len() == T::LEN || !spl_token_2022::check_id(token_program) {
+        return T::unpack(data);
+    }
+
+    let base = spl_token_2022::base_with_extensions(data, T::LEN, account_type)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    T::unpack(base)
+}
+
+/// Unpacks `mint` as an initialized mint owned by `token_program`, an account that merely has the size of a mint is
+/// rejected.
+pub(crate) fn unpack_mint(
+    mint: &AccountInfo,
+    token_program: &Pubkey,
+) -> Result<Mint, ProgramError> {
+    if mint.owner != token_program {
+        msg!("Invalid mint {}, not owned by {}", mint.key, token_program);
+        return Err(EchoError::InvalidMint.into());
+    }
+
+    base_state(
+        &mint.data.borrow(),
+        token_program,
+        spl_token_2022::ACCOUNT_TYPE_MINT,
+    )
+    .map_err(|_| {
+        msg!("Invalid mint {}", mint.key);
+        EchoError::InvalidMint.into()
+    })
+}
+
+/// Unpacks `token_account` as an initialized token account owned by `token_program`, as the fields of an account
+/// owned by any other program could be spoofed.
+pub(crate) fn unpack_token_account(
+    token_account: &AccountInfo,
+    token_program: &Pubkey,
+) -> Result<TokenAccount, ProgramError> {
+    if token_account.owner != token_program {
+        msg!(
+            "Token account {} must be owned by {}",
+            token_account.key,
+            token_program
+        );
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    base_state(
+        &token_account.data.borrow(),
+        token_program,
+        spl_token_2022::ACCOUNT_TYPE_ACCOUNT,
+    )
+    .inspect_err(|_| {
+        msg!("Invalid token account {}", token_account.key);
+    })
+}
+
+/// `instruction` of the Token Program sent to `token_program` instead, both share the instruction encoding.
+pub(crate) fn token_instruction(
+    mut instruction: Instruction,
+    token_program: &Pubkey,
+) -> Instruction {
+    instruction.program_id = *token_program;
+    instruction
+}
+
+/// Transfers `sol_fee` lamports from the `user` to the fee treasury, `fee_accounts` being the fee treasury and the
+/// system program.
+fn pay_sol_fee<'a>(
+    program_id: &Pubkey,
+    user: &AccountInfo<'a>,
+    fee_accounts: &[AccountInfo<'a>],
+    sol_fee: u64,
+) -> ProgramResult {
+    let (fee_treasury, system_program) = match fee_accounts {
+        [fee_treasury, system_program, ..] => (fee_treasury, system_program),
+        _ => {
+            msg!("The fee treasury and system program must be passed to pay the SOL fee");
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+    };
+
+    validate_treasury(program_id, fee_treasury)?;
+
+    if *system_program.key != SYSTEM_PROGRAM_ID {
+        msg!("Invalid system program");
+        return Err(EchoError::InvalidProgramAddress.into());
+    }
+
+    if !user.is_writable {
+        msg!("User account must be writable to pay the SOL fee");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    invoke(
+        &transfer(user.key, fee_treasury.key, sol_fee),
+        &[user.clone(), fee_treasury.clone(), system_program.clone()],
+    )?;
+
+    msg!("Paid SOL fee of {} lamports", sol_fee);
+
+    Ok(())
+}
+
+/// Fails with `CooldownActive` when the `user` wrote less than `cooldown_slots` ago, otherwise records `slot` as their
+/// last write. `cooldown_accounts` are the user record and the system program, the record is created on first use.
+fn enforce_cooldown<'a>(
+    program_id: &Pubkey,
+    vending_machine_buffer: &AccountInfo<'a>,
+    user: &AccountInfo<'a>,
+    cooldown_accounts: &[AccountInfo<'a>],
+    cooldown_slots: u64,
+    slot: u64,
+) -> ProgramResult {
+    let (user_record, system_program) = match cooldown_accounts {
+        [user_record, system_program, ..] => (user_record, system_program),
+        _ => {
+            msg!("The user record and system program must be passed to track the cooldown");
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+    };
+
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"vending_user",
+            vending_machine_buffer.key.as_ref(),
+            user.key.as_ref(),
+        ],
+        program_id,
+    );
+
+    if pda != *user_record.key {
+        msg!("Invalid user record address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    if !user_record.is_writable {
+        msg!("User record account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if user_record.data_is_empty() {
+        if *system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        if !user.is_writable {
+            msg!("User account must be writable to pay for the user record");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        invoke_signed(
+            &create_account(
+                user.key,
+                user_record.key,
+                Rent::get()?.minimum_balance(VENDING_USER_RECORD_SIZE),
+                VENDING_USER_RECORD_SIZE as u64,
+                program_id,
+            ),
+            &[user.clone(), user_record.clone(), system_program.clone()],
+            &[&[
+                b"vending_user",
+                vending_machine_buffer.key.as_ref(),
+                user.key.as_ref(),
+                &[bump_seed],
+            ]],
+        )?;
+    } else {
+        if user_record.owner != program_id {
+            msg!("User record must be owned by the Echo Program");
+            return Err(EchoError::InvalidAccountOwner.into());
+        }
+
+        let record = VendingUserRecord::try_from_slice(&user_record.data.borrow())?;
+        let next_write_slot = record.last_write_slot.saturating_add(cooldown_slots);
+
+        if slot < next_write_slot {
+            msg!("Cooldown active until slot {}", next_write_slot);
+            return Err(EchoError::CooldownActive.into());
+        }
+    }
+
+    let record = VendingUserRecord {
+        bump_seed,
+        last_write_slot: slot,
+    };
+    user_record
+        .data
+        .borrow_mut()
+        .copy_from_slice(&record.try_to_vec().unwrap());
+
+    Ok(())
+}
+
+/// Tokens charged for writing `len` bytes, the base `price`, or the price of the first tier covering `len`, plus
+/// `price_per_byte` for each byte.
+fn write_price(
+    buffer_header: &VendingMachineBufferHeader,
+    len: usize,
+) -> Result<u64, ProgramError> {
+    let base_price = if buffer_header.tier_count == 0 {
+        buffer_header.price
+    } else {
+        buffer_header.tiers[..buffer_header.tier_count as usize]
+            .iter()
+            .find(|tier| len <= tier.max_len as usize)
+            .map(|tier| tier.price)
+            .ok_or_else(|| {
+                msg!("No price tier covers {} bytes", len);
+                EchoError::DataTooLarge
+            })?
+    };
+
+    buffer_header
+        .price_per_byte
+        .checked_mul(len as u64)
+        .and_then(|byte_price| byte_price.checked_add(base_price))
+        .ok_or_else(|| {
+            msg!("The price of {} bytes overflows", len);
+            EchoError::Overflow.into()
+        })
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    process_write(program_id, accounts, data, None)
+}
+
+/// Writes `data` at `offset` of the payload, paying like a full write of `data`.
+pub fn process_write_at(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    offset: u64,
+    data: Vec<u8>,
+) -> ProgramResult {
+    process_write(program_id, accounts, data, Some(offset))
+}
+
+fn process_write(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+    offset: Option<u64>,
+) -> ProgramResult {
+    let vending_machine_buffer = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    // in order to validate the PDA address, we first read it to access the price and the payment mode
+    let buffer_header = read_vending_machine_header(program_id, vending_machine_buffer)?;
+
+    // an out of range write fails before anything is paid
+    if let Some(offset) = offset {
+        check_write_bounds(vending_machine_buffer, &data, offset)?;
+    }
+
+    // checked before anything is paid
+    if buffer_header.paused {
+        msg!("The vending machine is paused");
+        return Err(EchoError::VendingMachinePaused.into());
+    }
+
+    if buffer_header.free {
+        return process_free(program_id, accounts, &buffer_header, data, offset);
+    }
+
+    let ctx = Context::parse(accounts)?;
+
+    // the mint recorded at initialization, checked before any other account is trusted
+    if *ctx.vending_machine_mint.key != buffer_header.mint {
+        msg!("The mint {} must be passed", buffer_header.mint);
+        return Err(EchoError::MintMismatch.into());
+    }
+
+    // expiring machines are checked before anything is paid
+    if buffer_header.expiry_slot > 0 {
+        let slot = Clock::get()?.slot;
+
+        if slot >= buffer_header.expiry_slot {
+            msg!(
+                "The vending machine expired at slot {}",
+                buffer_header.expiry_slot
+            );
+            return Err(EchoError::BufferExpired.into());
+        }
+    }
+
+    // so is the window of time boxed machines
+    if buffer_header.start_slot > 0 || buffer_header.end_slot > 0 {
+        let slot = Clock::get()?.slot;
+
+        if slot < buffer_header.start_slot {
+            msg!(
+                "The vending machine starts at slot {}",
+                buffer_header.start_slot
+            );
+            return Err(EchoError::VendingMachineNotStarted.into());
+        }
+
+        if buffer_header.end_slot > 0 && slot >= buffer_header.end_slot {
+            msg!(
+                "The vending machine ended at slot {}",
+                buffer_header.end_slot
+            );
+            return Err(EchoError::VendingMachineEnded.into());
+        }
+    }
+
+    // the token program the machine was initialized with, mixing in accounts of the other one fails
+    if *ctx.token_program.key != buffer_header.token_program {
+        msg!(
+            "Invalid token program {}, expected {}",
+            ctx.token_program.key,
+            buffer_header.token_program
+        );
+        return Err(EchoError::InvalidProgramAddress.into());
+    }
+
+    let mint = unpack_mint(ctx.vending_machine_mint, &buffer_header.token_program)?;
+
+    if !mint.is_initialized {
+        msg!("Mint {} is not initialized", ctx.vending_machine_mint.key);
+        return Err(EchoError::InvalidMint.into());
+    }
+
+    let user_token_account =
+        unpack_token_account(ctx.user_token_account, &buffer_header.token_program)?;
+
+    if user_token_account.state != AccountState::Initialized {
+        msg!("User token account is frozen");
+        return Err(EchoError::TokenAccountFrozen.into());
+    }
+
+    if buffer_header.require_ata
+        && *ctx.user_token_account.key
+            != get_associated_token_address_with_program_id(
+                &user_token_account.owner,
+                ctx.vending_machine_mint.key,
+                &buffer_header.token_program,
+            )
+    {
+        msg!("User token account must be the associated token account of its owner");
+        return Err(EchoError::NotAssociatedTokenAccount.into());
+    }
+
+    // a delegate of the token account can pay from its allowance, the Token Program accepts it as the authority
+    let available = if user_token_account.owner == *ctx.user.key {
+        user_token_account.amount
+    } else if user_token_account.delegate == COption::Some(*ctx.user.key) {
+        user_token_account.delegated_amount
+    } else {
+        msg!("Invalid token account owner or delegate");
+        return Err(EchoError::InvalidAccountData.into());
+    };
+
+    if user_token_account.mint != *ctx.vending_machine_mint.key {
+        msg!("Invalid token account mint");
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    let price = write_price(&buffer_header, data.len())?;
+
+    // the project controlling the mint doesn't burn its own tokens to update its machine, unless it opted out
+    let free_authority_write =
+        buffer_header.free_authority_writes && mint.mint_authority == COption::Some(*ctx.user.key);
+
+    if !free_authority_write && available < price {
         msg!("Token account has insufficient funds");
         return Err(EchoError::InsufficientFunds.into());
     }
 
-    // verify that the PDA account is the correct address
-    let pda = Pubkey::create_program_address(
+    // limited machines are checked before anything is paid, free writes aren't sales so they neither count as uses
+    // nor are stopped by a sold out machine
+    if !free_authority_write
+        && buffer_header.max_uses > 0
+        && buffer_header.uses >= buffer_header.max_uses
+    {
+        msg!(
+            "The vending machine sold out its {} uses",
+            buffer_header.max_uses
+        );
+        return Err(EchoError::VendingMachineSoldOut.into());
+    }
+    let uses = match free_authority_write {
+        true => buffer_header.uses,
+        false => buffer_header
+            .uses
+            .checked_add(1)
+            .ok_or(EchoError::Overflow)?,
+    };
+
+    // verify that the PDA account is the correct address, re-deriving the canonical bump
+    // instead of trusting the one stored in the header
+    let (pda, bump_seed) = Pubkey::find_program_address(
         &[
             b"vending_machine",
             ctx.vending_machine_mint.key.as_ref(),
             &buffer_header.price.to_le_bytes(),
-            &[buffer_header.bump_seed],
         ],
         program_id,
-    )?;
+    );
 
-    if pda != *ctx.vending_machine_buffer.key {
+    if pda != *ctx.vending_machine_buffer.key || bump_seed != buffer_header.bump_seed {
         msg!("Invalid account address or authority");
         return Err(EchoError::InvalidAccountAddress.into());
     }
 
-    // Burn the vending machine tokens to authorize the echo
-    invoke(
-        &spl_token::instruction::burn(
-            ctx.token_program.key,
-            ctx.user_token_account.key,
-            ctx.vending_machine_mint.key,
+    let (treasury, fee_accounts) = match buffer_header.payment_mode {
+        PAYMENT_MODE_TREASURY => (
+            ctx.remaining_accounts.first(),
+            ctx.remaining_accounts.get(1..).unwrap_or_default(),
+        ),
+        _ => (None, ctx.remaining_accounts),
+    };
+
+    let fee_account_count = if buffer_header.sol_fee > 0 { 2 } else { 0 };
+    let cooldown_accounts = fee_accounts.get(fee_account_count..).unwrap_or_default();
+    let cooldown_account_count = if buffer_header.cooldown_slots > 0 {
+        2
+    } else {
+        0
+    };
+
+    // nothing is paid by a user that isn't whitelisted
+    if buffer_header.whitelist_enabled {
+        assert_whitelisted(
+            program_id,
+            ctx.vending_machine_buffer,
+            cooldown_accounts.get(cooldown_account_count),
             ctx.user.key,
-            &[],
-            buffer_header.price,
-        )?,
+        )?;
+    }
+
+    // a user in their cooldown is rejected before anything is paid
+    if buffer_header.cooldown_slots > 0 {
+        enforce_cooldown(
+            program_id,
+            ctx.vending_machine_buffer,
+            ctx.user,
+            cooldown_accounts,
+            buffer_header.cooldown_slots,
+            Clock::get()?.slot,
+        )?;
+    }
+
+    // the SOL fee is paid first, a failed transfer aborts before any token is moved
+    if buffer_header.sol_fee > 0 {
+        pay_sol_fee(program_id, ctx.user, fee_accounts, buffer_header.sol_fee)?;
+    }
+
+    match buffer_header.payment_mode {
+        _ if free_authority_write => {
+            msg!("Free write by the mint authority {}", ctx.user.key);
+        }
+        PAYMENT_MODE_TREASURY => {
+            let treasury = treasury
+                .filter(|treasury| *treasury.key == buffer_header.treasury)
+                .ok_or_else(|| {
+                    msg!("The treasury {} must be passed", buffer_header.treasury);
+                    EchoError::InvalidAccountAddress
+                })?;
+
+            // Transfer the vending machine tokens to the treasury to authorize the echo
+            invoke(
+                &token_instruction(
+                    spl_token::instruction::transfer(
+                        &spl_token::id(),
+                        ctx.user_token_account.key,
+                        treasury.key,
+                        ctx.user.key,
+                        &[],
+                        price,
+                    )?,
+                    ctx.token_program.key,
+                ),
+                &[
+                    ctx.token_program.clone(),
+                    ctx.user_token_account.clone(),
+                    treasury.clone(),
+                    ctx.user.clone(),
+                ],
+            )?;
+        }
+        _ => {
+            // Burn the vending machine tokens to authorize the echo, the token program checks the decimals against
+            // the mint it burns from
+            invoke(
+                &token_instruction(
+                    spl_token::instruction::burn_checked(
+                        &spl_token::id(),
+                        ctx.user_token_account.key,
+                        ctx.vending_machine_mint.key,
+                        ctx.user.key,
+                        &[],
+                        price,
+                        mint.decimals,
+                    )?,
+                    ctx.token_program.key,
+                ),
+                &[
+                    ctx.token_program.clone(),
+                    ctx.user_token_account.clone(),
+                    ctx.vending_machine_mint.clone(),
+                    ctx.user.clone(),
+                ],
+            )
+            .map_err(|error| match error {
+                ProgramError::Custom(code) if code == TokenError::MintDecimalsMismatch as u32 => {
+                    msg!(
+                        "Burn rejected, mint {} doesn't have {} decimals",
+                        ctx.vending_machine_mint.key,
+                        mint.decimals
+                    );
+                    EchoError::MintDecimalsMismatch.into()
+                }
+                error => error,
+            })?;
+        }
+    }
+
+    let slot = Clock::get()?.slot;
+    let price = if free_authority_write { 0 } else { price };
+
+    // the totals only count payments that went through
+    let total_writes = match free_authority_write {
+        true => buffer_header.total_writes,
+        false => buffer_header
+            .total_writes
+            .checked_add(1)
+            .ok_or(EchoError::Overflow)?,
+    };
+    let total_burned = match buffer_header.payment_mode {
+        PAYMENT_MODE_TREASURY => buffer_header.total_burned,
+        _ => buffer_header
+            .total_burned
+            .checked_add(price)
+            .ok_or(EchoError::Overflow)?,
+    };
+
+    // the header is updated before the data so readers never see new data with a stale writer
+    let previous_data_len = buffer_header.data_len;
+    let buffer_header = VendingMachineBufferHeader {
+        uses,
+        last_writer: *ctx.user.key,
+        last_write_slot: slot,
+        total_writes,
+        total_burned,
+        data_len: next_data_len(ctx.vending_machine_buffer, &buffer_header, &data, offset),
+        ..buffer_header
+    };
+    ctx.vending_machine_buffer.data.borrow_mut()[..VENDING_MACHINE_BUFF_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    let len = write_payload(ctx.vending_machine_buffer, &data, offset, previous_data_len);
+
+    EchoEvent::VendingMachineWrite(VendingMachineWriteEvent {
+        buffer: *ctx.vending_machine_buffer.key,
+        user: *ctx.user.key,
+        len,
+        price,
+        slot,
+    })
+    .emit();
+    EchoEvent::VendingMachineStats(VendingMachineStatsEvent {
+        buffer: *ctx.vending_machine_buffer.key,
+        total_writes,
+        total_burned,
+    })
+    .emit();
+
+    Ok(())
+}
+
+/// Writes to a permissionless vending machine without any burn, `creator` is only needed to validate the PDA.
+fn process_free(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    buffer_header: &VendingMachineBufferHeader,
+    data: Vec<u8>,
+    offset: Option<u64>,
+) -> ProgramResult {
+    let ctx = FreeContext::parse(accounts)?;
+    let previous_data_len = buffer_header.data_len;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"free_vm", ctx.creator.key.as_ref()], program_id);
+
+    if pda != *ctx.vending_machine_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid account address or creator");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let buffer_header = VendingMachineBufferHeader {
+        data_len: next_data_len(ctx.vending_machine_buffer, buffer_header, &data, offset),
+        ..buffer_header.clone()
+    };
+    ctx.vending_machine_buffer.data.borrow_mut()[..VENDING_MACHINE_BUFF_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    let len = write_payload(ctx.vending_machine_buffer, &data, offset, previous_data_len);
+
+    EchoEvent::VendingMachineWrite(VendingMachineWriteEvent {
+        buffer: *ctx.vending_machine_buffer.key,
+        user: *ctx.user.key,
+        len,
+        price: 0,
+        slot: Clock::get()?.slot,
+    })
+    .emit();
+
+    Ok(())
+}
+
+/// Sets the `paused` flag of the vending machine, only its admin can pause or resume it.
+pub fn process_set_paused(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    paused: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vending_machine_buffer = next_account_info(accounts_iter)?;
+    // the seed keeps its place in the accounts, the address is derived from the header alone
+    let _seed = next_account_info(accounts_iter)?;
+    let admin = next_account_info(accounts_iter)?;
+
+    if !vending_machine_buffer.is_writable {
+        msg!("Vending machine buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    if !admin.is_signer {
+        msg!("Admin account must be signer");
+        return Err(EchoError::MissingRequiredSignature.into());
+    }
+
+    let mut buffer_header = read_vending_machine_header(program_id, vending_machine_buffer)?;
+
+    assert_vending_machine_address(program_id, vending_machine_buffer, &buffer_header)?;
+
+    assert_admin(&buffer_header, admin)?;
+
+    buffer_header.paused = paused;
+
+    vending_machine_buffer.data.borrow_mut()[..VENDING_MACHINE_BUFF_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Paused: {}", paused);
+
+    Ok(())
+}
+
+/// Closes the cooldown record of `user`, sending its lamports to the `user`. Either the `user` or the admin of the
+/// vending machine signs, the record is found by re-deriving its PDA from both keys.
+pub fn process_close_user_record(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vending_machine_buffer = next_account_info(accounts_iter)?;
+    let user_record = next_account_info(accounts_iter)?;
+    let user = next_account_info(accounts_iter)?;
+    let admin = accounts_iter.next();
+
+    if !user_record.is_writable || !user.is_writable {
+        msg!("User record and user accounts must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    let buffer_header = read_vending_machine_header(program_id, vending_machine_buffer)?;
+    assert_vending_machine_address(program_id, vending_machine_buffer, &buffer_header)?;
+
+    if !user.is_signer {
+        match admin {
+            Some(admin) => assert_admin(&buffer_header, admin)?,
+            None => {
+                msg!("User or admin account must be signer");
+                return Err(EchoError::MissingRequiredSignature.into());
+            }
+        }
+    }
+
+    // the record data is never trusted, only the address derived from the machine and the user
+    let (pda, _) = Pubkey::find_program_address(
         &[
-            ctx.token_program.clone(),
-            ctx.user_token_account.clone(),
-            ctx.vending_machine_mint.clone(),
-            ctx.user.clone(),
+            b"vending_user",
+            vending_machine_buffer.key.as_ref(),
+            user.key.as_ref(),
         ],
-    )?;
+        program_id,
+    );
 
-    // this is the 'rest' of the account's data (beyond the header info)
-    let buffer_data = &mut buffer[VENDING_MACHINE_BUFF_HEADER_SIZE..];
+    if pda != *user_record.key {
+        msg!("Invalid user record address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
 
-    // loop over each byte in the rest of account's data
-    for index in 0..buffer_data.len() {
-        buffer_data[index] = match index < data.len() {
-            true => data[index],
-            false => 0,
-        };
+    if user_record.owner != program_id {
+        msg!("User record must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    // closing the record would otherwise let the user skip the rest of their cooldown
+    let record = VendingUserRecord::try_from_slice(&user_record.data.borrow())?;
+    let next_write_slot = record
+        .last_write_slot
+        .saturating_add(buffer_header.cooldown_slots);
+
+    if Clock::get()?.slot < next_write_slot {
+        msg!("Cooldown active until slot {}", next_write_slot);
+        return Err(EchoError::CooldownActive.into());
+    }
+
+    let lamports = user_record.lamports();
+    **user.lamports.borrow_mut() = user
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(EchoError::Overflow)?;
+    **user_record.lamports.borrow_mut() = 0;
+
+    // the runtime deletes the account once it has no lamports left, clear it in case it is funded again
+    user_record.data.borrow_mut().fill(0);
+
+    msg!("Closed user record, reclaimed {} lamports", lamports);
+
+    Ok(())
+}
+
+/// Replaces the window of paid writes with one that contains it, a bound of 0 is open on that side and can't be closed
+/// again.
+pub fn process_set_window(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    start_slot: u64,
+    end_slot: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vending_machine_buffer = next_account_info(accounts_iter)?;
+    let admin = next_account_info(accounts_iter)?;
+
+    if !vending_machine_buffer.is_writable {
+        msg!("Vending machine buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    let mut buffer_header = read_vending_machine_header(program_id, vending_machine_buffer)?;
+    assert_vending_machine_address(program_id, vending_machine_buffer, &buffer_header)?;
+    assert_admin(&buffer_header, admin)?;
+
+    // the window can only be extended, campaigns the users joined are never cut short
+    let starts_later =
+        start_slot > 0 && (buffer_header.start_slot == 0 || start_slot > buffer_header.start_slot);
+    let ends_earlier =
+        end_slot > 0 && (buffer_header.end_slot == 0 || end_slot < buffer_header.end_slot);
+
+    if starts_later || ends_earlier {
+        msg!(
+            "The window {}-{} can't be shortened to {}-{}",
+            buffer_header.start_slot,
+            buffer_header.end_slot,
+            start_slot,
+            end_slot
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    buffer_header.start_slot = start_slot;
+    buffer_header.end_slot = end_slot;
+
+    vending_machine_buffer.data.borrow_mut()[..VENDING_MACHINE_BUFF_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Start slot: {}", start_slot);
+    msg!("End slot: {}", end_slot);
+
+    Ok(())
+}
+
+/// Returns `len` bytes of the data at `offset` of the payload through the return data, without any signer or payment.
+pub fn process_read(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    offset: u64,
+    len: u64,
+) -> ProgramResult {
+    let vending_machine_buffer = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    let buffer_header = read_vending_machine_header(program_id, vending_machine_buffer)?;
+    assert_vending_machine_address(program_id, vending_machine_buffer, &buffer_header)?;
+
+    if len > MAX_RETURN_DATA as u64 {
+        msg!(
+            "Can't read {} bytes, at most {} are returned",
+            len,
+            MAX_RETURN_DATA
+        );
+        return Err(EchoError::DataTooLarge.into());
+    }
+
+    let buffer = vending_machine_buffer.data.borrow();
+    let payload = VendingMachineBuffer::unpack(&buffer)?.payload();
+
+    // only the data written so far can be read, not the zero padding past it
+    match offset.checked_add(len) {
+        Some(end) if end <= payload.len() as u64 => {
+            set_return_data(&payload[offset as usize..end as usize]);
+        }
+        _ => {
+            msg!(
+                "Can't read {} bytes at offset {} of {} bytes of data",
+                len,
+                offset,
+                payload.len()
+            );
+            return Err(EchoError::InvalidInstructionInput.into());
+        }
     }
 
     Ok(())
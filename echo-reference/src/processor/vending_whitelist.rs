@@ -0,0 +1,279 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::Sysvar,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    processor::{
+        set_echo::search,
+        vending_machine_echo::{
+            assert_admin, assert_vending_machine_address, read_vending_machine_header,
+        },
+    },
+    state::{
+        VendingWhitelistHeader, MAX_WHITELIST_MEMBERS, SET_MEMBER_SIZE,
+        VENDING_MACHINE_BUFF_HEADER_SIZE, VENDING_WHITELIST_HEADER_SIZE, VENDING_WHITELIST_SIZE,
+    },
+};
+
+/// Checks that `vending_whitelist` is the whitelist PDA of `vending_machine_buffer`, returns its bump seed.
+fn assert_whitelist_address(
+    program_id: &Pubkey,
+    vending_machine_buffer: &AccountInfo,
+    vending_whitelist: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[b"vending_whitelist", vending_machine_buffer.key.as_ref()],
+        program_id,
+    );
+
+    if pda != *vending_whitelist.key {
+        msg!("Invalid vending whitelist address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(bump_seed)
+}
+
+/// Reads the header of an existing `vending_whitelist`, its address is checked by the caller.
+fn read_whitelist_header(
+    program_id: &Pubkey,
+    vending_whitelist: &AccountInfo,
+) -> Result<VendingWhitelistHeader, ProgramError> {
+    if vending_whitelist.owner != program_id {
+        msg!("Vending whitelist must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = vending_whitelist.data.borrow();
+
+    if buffer.len() != VENDING_WHITELIST_SIZE {
+        msg!("Invalid vending whitelist size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let whitelist_header =
+        VendingWhitelistHeader::try_from_slice(&buffer[..VENDING_WHITELIST_HEADER_SIZE])?;
+
+    if whitelist_header.count as usize > MAX_WHITELIST_MEMBERS {
+        msg!(
+            "Invalid vending whitelist count, {}",
+            whitelist_header.count
+        );
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    Ok(whitelist_header)
+}
+
+/// Fails with `NotWhitelisted` unless `user` is a member of `vending_whitelist`, the whitelist of
+/// `vending_machine_buffer`. A whitelist that was never created has no members.
+pub(crate) fn assert_whitelisted(
+    program_id: &Pubkey,
+    vending_machine_buffer: &AccountInfo,
+    vending_whitelist: Option<&AccountInfo>,
+    user: &Pubkey,
+) -> ProgramResult {
+    let vending_whitelist = vending_whitelist.ok_or_else(|| {
+        msg!("The vending whitelist must be passed");
+        ProgramError::NotEnoughAccountKeys
+    })?;
+
+    assert_whitelist_address(program_id, vending_machine_buffer, vending_whitelist)?;
+
+    let is_member = !vending_whitelist.data_is_empty() && {
+        let whitelist_header = read_whitelist_header(program_id, vending_whitelist)?;
+        let buffer = vending_whitelist.data.borrow();
+        let members = &buffer[VENDING_WHITELIST_HEADER_SIZE
+            ..VENDING_WHITELIST_HEADER_SIZE + whitelist_header.count as usize * SET_MEMBER_SIZE];
+        search(members, user).is_ok()
+    };
+
+    if !is_member {
+        msg!("{} is not whitelisted", user);
+        return Err(EchoError::NotWhitelisted.into());
+    }
+
+    Ok(())
+}
+
+/// Inserts `member` into the whitelist of the vending machine, creating the whitelist on first use with the `admin`
+/// paying its rent.
+pub fn process_add(program_id: &Pubkey, accounts: &[AccountInfo], member: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vending_machine_buffer = next_account_info(accounts_iter)?;
+    let vending_whitelist = next_account_info(accounts_iter)?;
+    let admin = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !vending_whitelist.is_writable {
+        msg!("Vending whitelist account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    let buffer_header = read_vending_machine_header(program_id, vending_machine_buffer)?;
+    assert_vending_machine_address(program_id, vending_machine_buffer, &buffer_header)?;
+    assert_admin(&buffer_header, admin)?;
+
+    let bump_seed =
+        assert_whitelist_address(program_id, vending_machine_buffer, vending_whitelist)?;
+
+    if vending_whitelist.data_is_empty() {
+        if *system_program.key != SYSTEM_PROGRAM_ID {
+            msg!("Invalid system program");
+            return Err(EchoError::InvalidProgramAddress.into());
+        }
+
+        invoke_signed(
+            &create_account(
+                admin.key,
+                vending_whitelist.key,
+                Rent::get()?.minimum_balance(VENDING_WHITELIST_SIZE),
+                VENDING_WHITELIST_SIZE as u64,
+                program_id,
+            ),
+            &[
+                admin.clone(),
+                vending_whitelist.clone(),
+                system_program.clone(),
+            ],
+            &[&[
+                b"vending_whitelist",
+                vending_machine_buffer.key.as_ref(),
+                &[bump_seed],
+            ]],
+        )?;
+
+        let whitelist_header = VendingWhitelistHeader {
+            count: 0,
+            bump_seed,
+        };
+        vending_whitelist.data.borrow_mut()[..VENDING_WHITELIST_HEADER_SIZE]
+            .copy_from_slice(&whitelist_header.try_to_vec().unwrap());
+    }
+
+    let mut whitelist_header = read_whitelist_header(program_id, vending_whitelist)?;
+
+    if whitelist_header.count as usize == MAX_WHITELIST_MEMBERS {
+        msg!(
+            "Vending whitelist is full, capacity {}",
+            MAX_WHITELIST_MEMBERS
+        );
+        return Err(EchoError::SetFull.into());
+    }
+
+    let buffer = &mut (*vending_whitelist.data).borrow_mut();
+    let count = whitelist_header.count as usize;
+
+    let members = &mut buffer[VENDING_WHITELIST_HEADER_SIZE..];
+    let index = match search(&members[..count * SET_MEMBER_SIZE], &member) {
+        Ok(_) => {
+            msg!("{} is already whitelisted", member);
+            return Err(EchoError::DuplicateMember.into());
+        }
+        Err(index) => index,
+    };
+
+    // shift every member after the insertion point one slot to the right
+    members.copy_within(
+        index * SET_MEMBER_SIZE..count * SET_MEMBER_SIZE,
+        (index + 1) * SET_MEMBER_SIZE,
+    );
+    members[index * SET_MEMBER_SIZE..(index + 1) * SET_MEMBER_SIZE]
+        .copy_from_slice(member.as_ref());
+
+    whitelist_header.count += 1;
+    buffer[..VENDING_WHITELIST_HEADER_SIZE]
+        .copy_from_slice(&whitelist_header.try_to_vec().unwrap());
+
+    msg!("Whitelisted {}, count: {}", member, whitelist_header.count);
+
+    Ok(())
+}
+
+pub fn process_remove(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    member: Pubkey,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vending_machine_buffer = next_account_info(accounts_iter)?;
+    let vending_whitelist = next_account_info(accounts_iter)?;
+    let admin = next_account_info(accounts_iter)?;
+
+    if !vending_whitelist.is_writable {
+        msg!("Vending whitelist account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    let buffer_header = read_vending_machine_header(program_id, vending_machine_buffer)?;
+    assert_vending_machine_address(program_id, vending_machine_buffer, &buffer_header)?;
+    assert_admin(&buffer_header, admin)?;
+
+    assert_whitelist_address(program_id, vending_machine_buffer, vending_whitelist)?;
+    let mut whitelist_header = read_whitelist_header(program_id, vending_whitelist)?;
+
+    let buffer = &mut (*vending_whitelist.data).borrow_mut();
+    let count = whitelist_header.count as usize;
+
+    let members = &mut buffer[VENDING_WHITELIST_HEADER_SIZE..];
+    let index = search(&members[..count * SET_MEMBER_SIZE], &member).map_err(|_| {
+        msg!("{} is not whitelisted", member);
+        EchoError::MemberNotFound
+    })?;
+
+    // shift every member after the removed one a slot to the left and clear the freed slot
+    members.copy_within(
+        (index + 1) * SET_MEMBER_SIZE..count * SET_MEMBER_SIZE,
+        index * SET_MEMBER_SIZE,
+    );
+    members[(count - 1) * SET_MEMBER_SIZE..count * SET_MEMBER_SIZE].fill(0);
+
+    whitelist_header.count -= 1;
+    buffer[..VENDING_WHITELIST_HEADER_SIZE]
+        .copy_from_slice(&whitelist_header.try_to_vec().unwrap());
+
+    msg!("Removed {}, count: {}", member, whitelist_header.count);
+
+    Ok(())
+}
+
+/// Turns the whitelist check of paid writes on or off, the members are kept either way.
+pub fn process_set_enabled(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    enabled: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vending_machine_buffer = next_account_info(accounts_iter)?;
+    let admin = next_account_info(accounts_iter)?;
+
+    if !vending_machine_buffer.is_writable {
+        msg!("Vending machine buffer account must be writable");
+        return Err(EchoError::AccountMustBeWritable.into());
+    }
+
+    let mut buffer_header = read_vending_machine_header(program_id, vending_machine_buffer)?;
+    assert_vending_machine_address(program_id, vending_machine_buffer, &buffer_header)?;
+    assert_admin(&buffer_header, admin)?;
+
+    buffer_header.whitelist_enabled = enabled;
+
+    vending_machine_buffer.data.borrow_mut()[..VENDING_MACHINE_BUFF_HEADER_SIZE]
+        .copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Whitelist enabled: {}", enabled);
+
+    Ok(())
+}
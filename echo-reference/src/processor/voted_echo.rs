@@ -0,0 +1,193 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    keccak, msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::EchoError,
+    state::{VotedEchoHeader, VOTED_ECHO_HEADER_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    voted_buffer: &'a AccountInfo<'b>,
+    voter: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            voted_buffer: next_account_info(accounts_iter)?,
+            voter: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.voted_buffer.is_writable {
+            msg!("Voted buffer account must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.voter.is_signer {
+            msg!("Voter account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Reads the header of `voted_buffer` and returns it with the index of `voter` in the voter list.
+fn validate_voted_buffer(
+    program_id: &Pubkey,
+    voted_buffer: &AccountInfo,
+    voter: &Pubkey,
+) -> Result<(VotedEchoHeader, usize), ProgramError> {
+    // only accounts owned by the Echo Program can have been written by our initializer
+    if voted_buffer.owner != program_id {
+        msg!("Voted buffer must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let buffer = voted_buffer.data.borrow();
+
+    // check the size of the account before trying to read it
+    if buffer.len() < VOTED_ECHO_HEADER_SIZE {
+        msg!("Invalid voted buffer size, {}", buffer.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let buffer_header = VotedEchoHeader::try_from_slice(&buffer[..VOTED_ECHO_HEADER_SIZE])?;
+
+    // verify that the PDA account is the correct address
+    let (pda, bump_seed) =
+        Pubkey::find_program_address(&[b"voted", buffer_header.creator.as_ref()], program_id);
+
+    if pda != *voted_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Invalid voted buffer address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let voter_index = buffer_header.voter_index(voter).ok_or_else(|| {
+        msg!("{} is not a voter of this buffer", voter);
+        EchoError::UnauthorizedCaller
+    })?;
+
+    Ok((buffer_header, voter_index))
+}
+
+/// The payload and the staging region each take half of the data following the header.
+fn payload_size(buffer: &[u8]) -> usize {
+    (buffer.len() - VOTED_ECHO_HEADER_SIZE) / 2
+}
+
+/// Stages `data` for a vote, replacing any pending write and its votes.
+pub fn process_propose(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let (mut buffer_header, _) =
+        validate_voted_buffer(program_id, ctx.voted_buffer, ctx.voter.key)?;
+
+    let buffer = &mut (*ctx.voted_buffer.data).borrow_mut();
+    let payload_size = payload_size(buffer);
+
+    if data.is_empty() || data.len() > payload_size {
+        msg!(
+            "Invalid data length {}, must be between 1 and {}",
+            data.len(),
+            payload_size
+        );
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    let staging = &mut buffer[VOTED_ECHO_HEADER_SIZE + payload_size..];
+    staging[..data.len()].copy_from_slice(&data);
+    staging[data.len()..].fill(0);
+
+    buffer_header.pending_data_hash = keccak::hash(&data).to_bytes();
+    buffer_header.pending_len = data.len() as u32;
+    buffer_header.vote_bitmap = 0;
+    buffer[..VOTED_ECHO_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+
+    msg!("Proposed a write of {} bytes", data.len());
+
+    Ok(())
+}
+
+/// Clears the staging region and the pending write.
+fn reset_pending_write(buffer: &mut [u8], buffer_header: &mut VotedEchoHeader) {
+    let payload_size = payload_size(buffer);
+    buffer[VOTED_ECHO_HEADER_SIZE + payload_size..].fill(0);
+
+    buffer_header.pending_data_hash = [0; 32];
+    buffer_header.pending_len = 0;
+    buffer_header.vote_bitmap = 0;
+    buffer[..VOTED_ECHO_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+}
+
+/// Records the vote of the signer, applying the staged write once the quorum is reached.
+pub fn process_cast_vote(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let (mut buffer_header, voter_index) =
+        validate_voted_buffer(program_id, ctx.voted_buffer, ctx.voter.key)?;
+
+    if !buffer_header.has_pending_write() {
+        msg!("No write is pending");
+        return Err(EchoError::NoPendingWrite.into());
+    }
+
+    if buffer_header.vote_bitmap & (1 << voter_index) != 0 {
+        msg!("{} already voted", ctx.voter.key);
+        return Err(EchoError::AlreadyVoted.into());
+    }
+
+    buffer_header.vote_bitmap |= 1 << voter_index;
+    let votes = buffer_header.vote_bitmap.count_ones();
+
+    let buffer = &mut (*ctx.voted_buffer.data).borrow_mut();
+
+    if votes < buffer_header.quorum as u32 {
+        buffer[..VOTED_ECHO_HEADER_SIZE].copy_from_slice(&buffer_header.try_to_vec().unwrap());
+        msg!("{} of {} votes", votes, buffer_header.quorum);
+        return Ok(());
+    }
+
+    // the quorum is reached, move the staged data into the payload
+    let payload_size = payload_size(buffer);
+    let (payload, staging) = buffer[VOTED_ECHO_HEADER_SIZE..].split_at_mut(payload_size);
+    payload.copy_from_slice(staging);
+
+    reset_pending_write(buffer, &mut buffer_header);
+
+    msg!("Quorum reached with {} votes, write applied", votes);
+
+    Ok(())
+}
+
+/// Drops the pending write and its votes, any voter can abort.
+pub fn process_abort(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let (mut buffer_header, _) =
+        validate_voted_buffer(program_id, ctx.voted_buffer, ctx.voter.key)?;
+
+    if !buffer_header.has_pending_write() {
+        msg!("No write is pending");
+        return Err(EchoError::NoPendingWrite.into());
+    }
+
+    reset_pending_write(&mut ctx.voted_buffer.data.borrow_mut(), &mut buffer_header);
+
+    msg!("Aborted the pending write");
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::EchoError,
+    state::{FeeConfig, FEE_CONFIG_SIZE},
+};
+
+struct Context<'a, 'b: 'a> {
+    fee_config: &'a AccountInfo<'b>,
+    fee_vault: &'a AccountInfo<'b>,
+    admin: &'a AccountInfo<'b>,
+    destination: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            fee_config: next_account_info(accounts_iter)?,
+            fee_vault: next_account_info(accounts_iter)?,
+            admin: next_account_info(accounts_iter)?,
+            destination: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.fee_vault.is_writable || !ctx.destination.is_writable {
+            msg!("Fee vault and destination accounts must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.admin.is_signer {
+            msg!("Fee admin account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Reads the fee config after checking that it is the fee config PDA of the Echo Program.
+pub(crate) fn read_fee_config(
+    program_id: &Pubkey,
+    fee_config: &AccountInfo,
+) -> Result<FeeConfig, ProgramError> {
+    if fee_config.owner != program_id {
+        msg!("Fee config must be owned by the Echo Program");
+        return Err(EchoError::InvalidAccountOwner.into());
+    }
+
+    let data = fee_config.data.borrow();
+
+    if data.len() != FEE_CONFIG_SIZE {
+        msg!("Invalid fee config size, {}", data.len());
+        return Err(EchoError::AccountNotInitialized.into());
+    }
+
+    let config = FeeConfig::try_from_slice(&data)?;
+
+    // verify that the PDA account is the correct address
+    let (pda, _) = Pubkey::find_program_address(&[b"fee_config"], program_id);
+
+    if pda != *fee_config.key {
+        msg!("Invalid fee config address");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    Ok(config)
+}
+
+/// Moves `amount` lamports from the fee vault to `destination`, always leaving the vault rent exempt.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let config = read_fee_config(program_id, ctx.fee_config)?;
+
+    if config.admin != *ctx.admin.key {
+        msg!("Only the fee admin can withdraw fees");
+        return Err(EchoError::UnauthorizedCaller.into());
+    }
+
+    let vault_pda =
+        Pubkey::create_program_address(&[b"fee_vault", &[config.vault_bump_seed]], program_id)?;
+
+    if vault_pda != *ctx.fee_vault.key || ctx.fee_vault.owner != program_id {
+        msg!("Invalid fee vault");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let available = ctx
+        .fee_vault
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(ctx.fee_vault.data_len()));
+
+    if amount > available {
+        msg!(
+            "Cannot withdraw {} lamports, only {} available",
+            amount,
+            available
+        );
+        return Err(EchoError::InsufficientFunds.into());
+    }
+
+    **ctx.fee_vault.lamports.borrow_mut() -= amount;
+    **ctx.destination.lamports.borrow_mut() = ctx
+        .destination
+        .lamports()
+        .checked_add(amount)
+        .ok_or(EchoError::Overflow)?;
+
+    msg!("Withdrew {} lamports", amount);
+
+    Ok(())
+}
@@ -0,0 +1,163 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::EchoError,
+    processor::vending_machine_echo::{
+        assert_admin, read_vending_machine_header, token_instruction, unpack_mint,
+        unpack_token_account,
+    },
+};
+
+struct Context<'a, 'b: 'a> {
+    vending_machine_buffer: &'a AccountInfo<'b>,
+    vault: &'a AccountInfo<'b>,
+    vault_authority: &'a AccountInfo<'b>,
+    destination: &'a AccountInfo<'b>,
+    admin: &'a AccountInfo<'b>,
+    token_program: &'a AccountInfo<'b>,
+    vending_machine_mint: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b: 'a> Context<'a, 'b> {
+    pub fn parse(accounts: &'a [AccountInfo<'b>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+
+        let ctx = Self {
+            vending_machine_buffer: next_account_info(accounts_iter)?,
+            vault: next_account_info(accounts_iter)?,
+            vault_authority: next_account_info(accounts_iter)?,
+            destination: next_account_info(accounts_iter)?,
+            admin: next_account_info(accounts_iter)?,
+            token_program: next_account_info(accounts_iter)?,
+            vending_machine_mint: next_account_info(accounts_iter)?,
+        };
+
+        if !ctx.vault.is_writable || !ctx.destination.is_writable {
+            msg!("Vault and destination accounts must be writable");
+            return Err(EchoError::AccountMustBeWritable.into());
+        }
+
+        if !ctx.admin.is_signer {
+            msg!("Admin account must be signer");
+            return Err(EchoError::MissingRequiredSignature.into());
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Moves tokens out of a vault owned by the vault authority PDA of a paid vending machine, e.g. its treasury.
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let ctx = Context::parse(accounts)?;
+
+    let buffer_header = read_vending_machine_header(program_id, ctx.vending_machine_buffer)?;
+
+    if buffer_header.free {
+        msg!("Permissionless vending machines collect no proceeds");
+        return Err(EchoError::InvalidInstructionInput.into());
+    }
+
+    assert_admin(&buffer_header, ctx.admin)?;
+
+    // the token program the machine was initialized with, the proceeds were paid through it
+    if *ctx.token_program.key != buffer_header.token_program {
+        msg!(
+            "Invalid token program {}, expected {}",
+            ctx.token_program.key,
+            buffer_header.token_program
+        );
+        return Err(EchoError::InvalidProgramAddress.into());
+    }
+
+    if *ctx.vending_machine_mint.key != buffer_header.mint {
+        msg!(
+            "Invalid mint {}, expected {}",
+            ctx.vending_machine_mint.key,
+            buffer_header.mint
+        );
+        return Err(EchoError::MintMismatch.into());
+    }
+
+    let mint = unpack_mint(ctx.vending_machine_mint, &buffer_header.token_program)?;
+    let vault = unpack_token_account(ctx.vault, &buffer_header.token_program)?;
+    let destination = unpack_token_account(ctx.destination, &buffer_header.token_program)?;
+
+    // the buffer address commits to its mint, so re-deriving it from the vault mint proves they match
+    let (pda, bump_seed) = Pubkey::find_program_address(
+        &[
+            b"vending_machine",
+            vault.mint.as_ref(),
+            &buffer_header.price.to_le_bytes(),
+        ],
+        program_id,
+    );
+
+    if pda != *ctx.vending_machine_buffer.key || bump_seed != buffer_header.bump_seed {
+        msg!("Vault mint doesn't match the vending machine");
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    if destination.mint != vault.mint {
+        msg!("Invalid destination mint");
+        return Err(EchoError::InvalidAccountData.into());
+    }
+
+    let (vault_authority, vault_bump_seed) = Pubkey::find_program_address(
+        &[b"vending_vault", ctx.vending_machine_buffer.key.as_ref()],
+        program_id,
+    );
+
+    if *ctx.vault_authority.key != vault_authority || vault.owner != vault_authority {
+        msg!("Vault must be owned by the vault authority PDA");
+        return Err(EchoError::InvalidAccountAddress.into());
+    }
+
+    let amount = match amount {
+        0 => vault.amount,
+        amount => amount,
+    };
+
+    if vault.amount < amount {
+        msg!("Vault has insufficient funds");
+        return Err(EchoError::InsufficientFunds.into());
+    }
+
+    invoke_signed(
+        &token_instruction(
+            spl_token::instruction::transfer_checked(
+                &spl_token::id(),
+                ctx.vault.key,
+                ctx.vending_machine_mint.key,
+                ctx.destination.key,
+                ctx.vault_authority.key,
+                &[],
+                amount,
+                mint.decimals,
+            )?,
+            ctx.token_program.key,
+        ),
+        &[
+            ctx.token_program.clone(),
+            ctx.vault.clone(),
+            ctx.vending_machine_mint.clone(),
+            ctx.destination.clone(),
+            ctx.vault_authority.clone(),
+        ],
+        &[&[
+            b"vending_vault",
+            ctx.vending_machine_buffer.key.as_ref(),
+            &[vault_bump_seed],
+        ]],
+    )?;
+
+    msg!("Withdrew {} tokens", amount);
+
+    Ok(())
+}
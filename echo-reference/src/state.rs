@@ -1,18 +1,1013 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use std::mem::size_of;
+use solana_program::pubkey::Pubkey;
+use std::{
+    io::{self, Write},
+    mem::size_of,
+};
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+/// Maximum length in bytes of an authorized buffer's label
+pub const MAX_BUFFER_LABEL_LEN: usize = 32;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default, PartialEq)]
 pub struct AuthorizedBufferHeader {
     pub bump_seed: u8,
     pub buffer_seed: u64,
+    /// Number of payload bytes written so far, appends continue from here
+    pub cursor: u32,
+    /// Keccak hash of the data that `RevealAuthorizedEcho` has to provide
+    pub commitment: [u8; 32],
+    pub commit_slot: u64,
+    /// Whether `commitment` is waiting to be revealed
+    pub has_commitment: bool,
+    /// Zero-padded UTF-8 label to tell buffers apart, it isn't part of the PDA seeds
+    pub label: [u8; MAX_BUFFER_LABEL_LEN],
+    /// Whether `permanent_delegate` was set at initialization, it can't be changed afterwards
+    pub has_permanent_delegate: bool,
+    /// Emergency key that can clear or close the buffer in place of the authority, but never write to it
+    pub permanent_delegate: Pubkey,
+    /// Incremented by every write, append or clear of the payload, ordering the changes of the buffer
+    pub sequence: u64,
+    /// Lamports the authority pays into the fee vault on every `AuthorizedEcho`, no transfer happens when zero
+    pub write_fee_lamports: u64,
+    /// Slot after which anyone can close the buffer with `CrankCloseExpired`, 0 if it never expires
+    pub expiry_slot: u64,
+    /// Share of the buffer lamports paid to whoever cranks it closed, in basis points
+    pub crank_bounty_bps: u16,
+    /// Set by `AuthorizedEchoChunk` until `AuthorizedEchoFinalize`, the payload is incomplete in the meantime
+    pub write_in_progress: bool,
 }
 
-pub const AUTH_BUFF_HEADER_SIZE: usize = size_of::<u8>() + size_of::<u64>();
+impl AuthorizedBufferHeader {
+    /// The label without its zero padding, empty if it isn't valid UTF-8
+    pub fn label(&self) -> &str {
+        let len = self
+            .label
+            .iter()
+            .rposition(|byte| *byte != 0)
+            .map_or(0, |index| index + 1);
+        std::str::from_utf8(&self.label[..len]).unwrap_or("")
+    }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+    pub fn permanent_delegate(&self) -> Option<Pubkey> {
+        match self.has_permanent_delegate {
+            true => Some(self.permanent_delegate),
+            false => None,
+        }
+    }
+}
+
+pub const AUTH_BUFF_HEADER_SIZE: usize = size_of::<u8>()
+    + size_of::<u64>()
+    + size_of::<u32>()
+    + size_of::<[u8; 32]>()
+    + size_of::<u64>()
+    + size_of::<bool>()
+    + MAX_BUFFER_LABEL_LEN
+    + size_of::<bool>()
+    + size_of::<Pubkey>()
+    + size_of::<u64>()
+    + size_of::<u64>()
+    + size_of::<u64>()
+    + size_of::<u16>()
+    + size_of::<bool>();
+
+/// Bytes of the payload used by a hash-only write, the keccak hash of the data followed by its length as a u32
+pub const HASH_ONLY_RECORD_SIZE: usize = 32 + size_of::<u32>();
+
+/// Basis points of a whole, the upper bound of `crank_bounty_bps`
+pub const MAX_BPS: u16 = 10_000;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub struct VendingMachineBufferHeader {
     pub bump_seed: u8,
     pub price: u64,
+    /// Permissionless vending machines take no payment, writes skip the burn and need no token accounts
+    pub free: bool,
+    /// How the `price` is paid, `PAYMENT_MODE_BURN` or `PAYMENT_MODE_TREASURY`
+    pub payment_mode: u8,
+    /// Token account collecting the payments in treasury mode, unused when burning
+    pub treasury: Pubkey,
+    /// Key allowed to withdraw the proceeds, the mint authority or the creator of a permissionless vending machine
+    pub admin: Pubkey,
+    /// Set by `PauseVendingMachine`, writes fail until `ResumeVendingMachine`
+    pub paused: bool,
+    /// Charged for every byte of data on top of the `price`, 0 for a flat price
+    pub price_per_byte: u64,
+    /// Writes must be paid from the associated token account of the token account owner
+    pub require_ata: bool,
+    /// Owner of the mint, the Token Program or Token-2022, zeroed for permissionless vending machines
+    pub token_program: Pubkey,
+    /// Mint accepted by the vending machine, zeroed for permissionless vending machines
+    pub mint: Pubkey,
+    /// Paid writes so far, free writes of the mint authority aren't counted
+    pub uses: u64,
+    /// Paid writes accepted before the vending machine is sold out, 0 for unlimited
+    pub max_uses: u64,
+    /// Lamports paid to the fee treasury on every paid write on top of the token `price`, 0 for none
+    pub sol_fee: u64,
+    /// Number of `tiers` in use, 0 to always charge the `price`
+    pub tier_count: u8,
+    /// Price tiers sorted by strictly increasing `max_len`, a write pays the first one covering its length
+    pub tiers: [PriceTier; MAX_PRICE_TIERS],
+    /// User of the last write, paid or free, zeroed until then
+    pub last_writer: Pubkey,
+    /// Slot of the last write, paid or free, 0 until then
+    pub last_write_slot: u64,
+    /// Writes fail from this slot on and anyone can claim the buffer, 0 for a machine that never expires
+    pub expiry_slot: u64,
+    /// Paid writes so far, unlike `uses` it is kept for analytics only, free writes of the mint authority aren't
+    /// counted
+    pub total_writes: u64,
+    /// Tokens burned by all writes so far, payments transferred to a treasury aren't counted
+    pub total_burned: u64,
+    /// Length of the data past the header, the rest of the payload is zero padding
+    pub data_len: u32,
+    /// Set by the initializers, an account without it was never set up as a vending machine
+    pub is_initialized: bool,
+    /// Paid writes fail before this slot, 0 to accept them right away
+    pub start_slot: u64,
+    /// Paid writes fail from this slot on, 0 to accept them until the machine expires
+    pub end_slot: u64,
+    /// Slots a user waits between two paid writes, tracked in a `VendingUserRecord`, 0 for no cooldown
+    pub cooldown_slots: u64,
+    /// Paid writes are only accepted from members of the `VendingWhitelist` of the machine
+    pub whitelist_enabled: bool,
+    /// Writes signed by the mint authority of the machine's mint skip the token payment
+    pub free_authority_writes: bool,
+}
+
+impl VendingMachineBufferHeader {
+    pub fn stats(&self) -> VendingMachineStats {
+        VendingMachineStats {
+            total_writes: self.total_writes,
+            total_burned: self.total_burned,
+        }
+    }
+}
+
+/// Totals of a vending machine, read from its header without replaying its history
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VendingMachineStats {
+    pub total_writes: u64,
+    pub total_burned: u64,
+}
+
+/// The data of a vending machine account, its header followed by the payload
+pub struct VendingMachineBuffer<'a> {
+    pub header: VendingMachineBufferHeader,
+    data: &'a [u8],
+}
+
+impl<'a> VendingMachineBuffer<'a> {
+    pub fn unpack(buffer: &'a [u8]) -> io::Result<Self> {
+        if buffer.len() < VENDING_MACHINE_BUFF_HEADER_SIZE {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let (header, data) = buffer.split_at(VENDING_MACHINE_BUFF_HEADER_SIZE);
+        Ok(Self {
+            header: VendingMachineBufferHeader::try_from_slice(header)?,
+            data,
+        })
+    }
+
+    /// The data last written, without the zero padding of the payload
+    pub fn payload(&self) -> &'a [u8] {
+        &self.data[..self.data.len().min(self.header.data_len as usize)]
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct PriceTier {
+    /// Longest data accepted at this tier
+    pub max_len: u32,
+    /// Tokens charged instead of the vending machine `price`
+    pub price: u64,
+}
+
+pub const MAX_PRICE_TIERS: usize = 4;
+pub const PRICE_TIER_SIZE: usize = size_of::<u32>() + size_of::<u64>();
+
+/// One buffer created by `BatchInitialize`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct BatchInitConfig {
+    pub buffer_seed: u64,
+    pub buffer_size: usize,
+}
+
+/// The `price` is burned from the user's token account
+pub const PAYMENT_MODE_BURN: u8 = 0;
+/// The `price` is transferred from the user's token account to the `treasury`
+pub const PAYMENT_MODE_TREASURY: u8 = 1;
+
+pub const VENDING_MACHINE_BUFF_HEADER_SIZE: usize = size_of::<u8>()
+    + size_of::<u64>()
+    + size_of::<bool>()
+    + size_of::<u8>()
+    + size_of::<Pubkey>()
+    + size_of::<Pubkey>()
+    + size_of::<bool>()
+    + size_of::<u64>()
+    + size_of::<bool>()
+    + size_of::<Pubkey>()
+    + size_of::<Pubkey>()
+    + size_of::<u64>()
+    + size_of::<u64>()
+    + size_of::<u64>()
+    + size_of::<u8>()
+    + MAX_PRICE_TIERS * PRICE_TIER_SIZE
+    + size_of::<Pubkey>()
+    + size_of::<u64>()
+    + size_of::<u64>()
+    + size_of::<u64>()
+    + size_of::<u64>()
+    + size_of::<u32>()
+    + size_of::<bool>()
+    + size_of::<u64>()
+    + size_of::<u64>()
+    + size_of::<u64>()
+    + size_of::<bool>()
+    + size_of::<bool>();
+
+/// Paid writes of a user to a vending machine with a cooldown, PDA seeded by `"vending_user"`, the machine and the user
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct VendingUserRecord {
+    pub bump_seed: u8,
+    pub last_write_slot: u64,
+}
+
+pub const VENDING_USER_RECORD_SIZE: usize = size_of::<u8>() + size_of::<u64>();
+
+/// Users allowed to pay a vending machine with `whitelist_enabled`, PDA seeded by `"vending_whitelist"` and the
+/// machine. The header is followed by room for `MAX_WHITELIST_MEMBERS` sorted pubkeys.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct VendingWhitelistHeader {
+    pub count: u16,
+    pub bump_seed: u8,
+}
+
+pub const VENDING_WHITELIST_HEADER_SIZE: usize = size_of::<u16>() + size_of::<u8>();
+
+pub const MAX_WHITELIST_MEMBERS: usize = 32;
+
+pub const VENDING_WHITELIST_SIZE: usize =
+    VENDING_WHITELIST_HEADER_SIZE + MAX_WHITELIST_MEMBERS * size_of::<Pubkey>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ProgramControlledBufferHeader {
+    pub bump_seed: u8,
+    pub allowed_program: Pubkey,
+}
+
+pub const PROGRAM_CONTROLLED_BUFF_HEADER_SIZE: usize = size_of::<u8>() + size_of::<Pubkey>();
+
+/// Maximum number of bytes a conditional buffer can compare against
+pub const MAX_CONDITION_VALUE_LEN: usize = 32;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ConditionalBufferHeader {
+    pub bump_seed: u8,
+    pub authority: Pubkey,
+    pub condition_account: Pubkey,
+    pub condition_offset: u16,
+    pub condition_value_len: u8,
+    pub condition_value: [u8; MAX_CONDITION_VALUE_LEN],
+}
+
+impl ConditionalBufferHeader {
+    /// The bytes that must be found in `condition_account` for a write to go through
+    pub fn condition_value(&self) -> &[u8] {
+        &self.condition_value[..self.condition_value_len as usize]
+    }
+}
+
+pub const CONDITIONAL_BUFF_HEADER_SIZE: usize = size_of::<u8>()
+    + size_of::<Pubkey>()
+    + size_of::<Pubkey>()
+    + size_of::<u16>()
+    + size_of::<u8>()
+    + MAX_CONDITION_VALUE_LEN;
+
+/// Maximum length in bytes of a named authorized buffer's name
+pub const MAX_BUFFER_NAME_LEN: usize = 32;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct NamedAuthorizedBufferHeader {
+    pub bump_seed: u8,
+    pub name_len: u8,
+    pub name: [u8; MAX_BUFFER_NAME_LEN],
+}
+
+impl NamedAuthorizedBufferHeader {
+    /// The name used to seed the buffer's PDA
+    pub fn name(&self) -> &[u8] {
+        &self.name[..self.name_len as usize]
+    }
+}
+
+pub const NAMED_AUTH_BUFF_HEADER_SIZE: usize =
+    size_of::<u8>() + size_of::<u8>() + MAX_BUFFER_NAME_LEN;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct AggregatorBufferHeader {
+    pub bump_seed: u8,
+    pub authority: Pubkey,
+}
+
+pub const AGGREGATOR_BUFF_HEADER_SIZE: usize = size_of::<u8>() + size_of::<Pubkey>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SumHeader {
+    pub value: u64,
+    pub bump_seed: u8,
+}
+
+pub const SUM_HEADER_SIZE: usize = size_of::<u64>() + size_of::<u8>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct MaxHeader {
+    pub current_max: u64,
+    pub update_count: u64,
+    pub bump_seed: u8,
+}
+
+pub const MAX_ECHO_HEADER_SIZE: usize = size_of::<u64>() + size_of::<u64>() + size_of::<u8>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct MinHeader {
+    pub current_min: u64,
+    pub update_count: u64,
+    pub bump_seed: u8,
+}
+
+pub const MIN_ECHO_HEADER_SIZE: usize = size_of::<u64>() + size_of::<u64>() + size_of::<u8>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct HistogramHeader {
+    pub bump_seed: u8,
+    pub authority: Pubkey,
+    pub bucket_count: u16,
+    pub bucket_width: u64,
+}
+
+pub const HISTOGRAM_HEADER_SIZE: usize =
+    size_of::<u8>() + size_of::<Pubkey>() + size_of::<u16>() + size_of::<u64>();
+
+/// Each bucket is a little-endian u64 counter
+pub const HISTOGRAM_BUCKET_SIZE: usize = size_of::<u64>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SetHeader {
+    pub count: u16,
+    pub capacity: u16,
+    pub bump_seed: u8,
+}
+
+pub const SET_HEADER_SIZE: usize = size_of::<u16>() + size_of::<u16>() + size_of::<u8>();
+
+/// Members are stored as raw 32 byte pubkeys, sorted in ascending order
+pub const SET_MEMBER_SIZE: usize = size_of::<Pubkey>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct MapHeader {
+    pub count: u16,
+    pub capacity: u16,
+    pub bump_seed: u8,
+}
+
+pub const MAP_HEADER_SIZE: usize = size_of::<u16>() + size_of::<u16>() + size_of::<u8>();
+
+pub const MAP_KEY_SIZE: usize = size_of::<Pubkey>();
+
+/// Entries are a 32 byte pubkey key followed by a little-endian u64 value, sorted by key in ascending order
+pub const MAP_ENTRY_SIZE: usize = MAP_KEY_SIZE + size_of::<u64>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct BitmapHeader {
+    pub bit_count: u32,
+    pub bump_seed: u8,
+}
+
+pub const BITMAP_HEADER_SIZE: usize = size_of::<u32>() + size_of::<u8>();
+
+/// Number of bytes needed to store `bit_count` bits
+pub fn bitmap_len(bit_count: u32) -> usize {
+    (bit_count as usize).div_ceil(8)
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct AuthorityCounter {
+    /// `buffer_seed` of the next buffer created by `InitializeAuthorizedEchoAuto`
+    pub next_seed: u64,
+    pub bump_seed: u8,
+}
+
+pub const AUTHORITY_COUNTER_SIZE: usize = size_of::<u64>() + size_of::<u8>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LinkedBufferHeader {
+    /// Only set for the head of the chain, the other nodes aren't PDAs
+    pub bump_seed: u8,
+    pub authority: Pubkey,
+    pub next_buffer: Pubkey,
+    pub has_next: bool,
+}
+
+pub const LINKED_BUFF_HEADER_SIZE: usize =
+    size_of::<u8>() + size_of::<Pubkey>() + size_of::<Pubkey>() + size_of::<bool>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RegistryHeader {
+    pub count: u16,
+    pub capacity: u16,
+    pub bump_seed: u8,
+}
+
+pub const REGISTRY_HEADER_SIZE: usize = size_of::<u16>() + size_of::<u16>() + size_of::<u8>();
+
+/// An authorized buffer listed in its authority's registry
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct RegistryEntry {
+    pub buffer_seed: u64,
+    pub buffer: Pubkey,
+}
+
+pub const REGISTRY_ENTRY_SIZE: usize = size_of::<u64>() + size_of::<Pubkey>();
+
+/// Lists the `(buffer_seed, buffer)` pairs stored in the data of a registry account
+pub fn registry_entries(data: &[u8]) -> std::io::Result<Vec<(u64, Pubkey)>> {
+    let header =
+        RegistryHeader::try_from_slice(data.get(..REGISTRY_HEADER_SIZE).unwrap_or_default())?;
+    let entries_len = header.count as usize * REGISTRY_ENTRY_SIZE;
+    let entries = data
+        .get(REGISTRY_HEADER_SIZE..REGISTRY_HEADER_SIZE + entries_len)
+        .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::UnexpectedEof))?;
+
+    entries
+        .chunks(REGISTRY_ENTRY_SIZE)
+        .map(|entry| {
+            RegistryEntry::try_from_slice(entry).map(|entry| (entry.buffer_seed, entry.buffer))
+        })
+        .collect()
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct IndexedHeader {
+    pub bump_seed: u8,
+    pub slot_count: u16,
+    pub key_size: u8,
+    pub value_size: u16,
+}
+
+impl IndexedHeader {
+    /// Size of a record, its key followed by its value
+    pub fn record_size(&self) -> usize {
+        self.key_size as usize + self.value_size as usize
+    }
+}
+
+pub const INDEXED_HEADER_SIZE: usize =
+    size_of::<u8>() + size_of::<u16>() + size_of::<u8>() + size_of::<u16>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct EpochHeader {
+    /// Epoch the data belongs to, it is zeroed by the first write of any later epoch
+    pub last_write_epoch: u64,
+    pub bump_seed: u8,
+}
+
+pub const EPOCH_HEADER_SIZE: usize = size_of::<u64>() + size_of::<u8>();
+
+/// Header of a read-only copy of an authorized buffer's payload
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SnapshotHeader {
+    pub bump_seed: u8,
+    pub source_buffer: Pubkey,
+    /// Slot the payload was copied in
+    pub slot: u64,
+    pub snapshot_index: u64,
+}
+
+pub const SNAPSHOT_HEADER_SIZE: usize =
+    size_of::<u8>() + size_of::<Pubkey>() + size_of::<u64>() + size_of::<u64>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SlotHeader {
+    /// Slot the data belongs to, it is zeroed by the first write of any later slot
+    pub last_write_slot: u64,
+    pub bump_seed: u8,
+}
+
+pub const SLOT_HEADER_SIZE: usize = size_of::<u64>() + size_of::<u8>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RandomHeader {
+    /// Slot of the last refresh, the data only changes once per slot
+    pub last_refresh_slot: u64,
+    pub bump_seed: u8,
+}
+
+pub const RANDOM_HEADER_SIZE: usize = size_of::<u64>() + size_of::<u8>();
+
+/// Bytes of the written-segments bitmap, one bit for each of the up to 256 segments
+pub const SEGMENT_BITMAP_LEN: usize = 32;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SegmentedHeader {
+    pub bump_seed: u8,
+    pub segment_size: u32,
+    pub segment_count: u8,
+    /// Bit `index % 8` of byte `index / 8` is set while segment `index` holds written data
+    pub bitmap: [u8; SEGMENT_BITMAP_LEN],
+}
+
+impl SegmentedHeader {
+    pub fn is_populated(&self, index: u8) -> bool {
+        self.bitmap[index as usize / 8] & (1 << (index % 8)) != 0
+    }
+
+    pub fn set_populated(&mut self, index: u8, populated: bool) {
+        let mask = 1 << (index % 8);
+        match populated {
+            true => self.bitmap[index as usize / 8] |= mask,
+            false => self.bitmap[index as usize / 8] &= !mask,
+        }
+    }
+
+    /// Indices of the written segments, in ascending order
+    pub fn populated_segments(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..self.segment_count).filter(move |index| self.is_populated(*index))
+    }
+
+    /// Range of segment `index` in the data following the header
+    pub fn segment_range(&self, index: u8) -> std::ops::Range<usize> {
+        let start = index as usize * self.segment_size as usize;
+        start..start + self.segment_size as usize
+    }
+}
+
+pub const SEGMENTED_HEADER_SIZE: usize =
+    size_of::<u8>() + size_of::<u32>() + size_of::<u8>() + SEGMENT_BITMAP_LEN;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct CoAuthorizedBufferHeader {
+    pub bump_seed: u8,
+    pub buffer_seed: u64,
+    /// The lower of the two authorities, both must sign every write
+    pub authority_a: Pubkey,
+    pub authority_b: Pubkey,
+}
+
+pub const CO_AUTH_BUFF_HEADER_SIZE: usize =
+    size_of::<u8>() + size_of::<u64>() + size_of::<Pubkey>() + size_of::<Pubkey>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct DecayHeader {
+    /// Slot the decay was last applied in
+    pub last_decay_slot: u64,
+    /// Each byte loses `decay_rate / 1000` of its value per slot
+    pub decay_rate: u8,
+    pub bump_seed: u8,
+}
+
+pub const DECAY_HEADER_SIZE: usize = size_of::<u64>() + size_of::<u8>() + size_of::<u8>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct AverageHeader {
+    pub sum: u128,
+    pub count: u64,
+    /// Number of samples after which `SubmitSample` fails until the average is reset
+    pub max_samples: u32,
+    pub bump_seed: u8,
+}
+
+pub const AVERAGE_HEADER_SIZE: usize =
+    size_of::<u128>() + size_of::<u64>() + size_of::<u32>() + size_of::<u8>();
+
+/// Program wide settings of the fee vault collecting the write fees of authorized buffers
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct FeeConfig {
+    /// Only key allowed to withdraw from the fee vault
+    pub admin: Pubkey,
+    pub bump_seed: u8,
+    pub vault_bump_seed: u8,
+}
+
+pub const FEE_CONFIG_SIZE: usize = size_of::<Pubkey>() + size_of::<u8>() + size_of::<u8>();
+
+pub const METADATA_NAME_LEN: usize = 32;
+pub const METADATA_DESCRIPTION_LEN: usize = 128;
+pub const METADATA_URI_LEN: usize = 200;
+
+/// Zero-padded URI of a `BufferMetadata`. Borsh only implements arrays of a few fixed sizes, so this wraps the array
+/// and serializes it the same way, as its raw bytes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetadataUri(pub [u8; METADATA_URI_LEN]);
+
+impl BorshSerialize for MetadataUri {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.0)
+    }
+}
+
+impl BorshDeserialize for MetadataUri {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        if buf.len() < METADATA_URI_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Unexpected length of input",
+            ));
+        }
+
+        let mut uri = [0; METADATA_URI_LEN];
+        uri.copy_from_slice(&buf[..METADATA_URI_LEN]);
+        *buf = &buf[METADATA_URI_LEN..];
+
+        Ok(Self(uri))
+    }
+}
+
+/// Human-readable annotations of an authorized buffer, stored in their own PDA
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct BufferMetadata {
+    pub bump_seed: u8,
+    /// Zero-padded UTF-8
+    pub name: [u8; METADATA_NAME_LEN],
+    /// Zero-padded UTF-8
+    pub description: [u8; METADATA_DESCRIPTION_LEN],
+    pub uri: MetadataUri,
+}
+
+pub const BUFFER_METADATA_SIZE: usize =
+    size_of::<u8>() + METADATA_NAME_LEN + METADATA_DESCRIPTION_LEN + METADATA_URI_LEN;
+
+/// Header of a vending machine paid in lamports, collected by a vault PDA instead of burning tokens
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SolVendingMachineHeader {
+    pub bump_seed: u8,
+    pub vault_bump_seed: u8,
+    /// Lamports paid to the vault on every write
+    pub price_lamports: u64,
+    /// Creator of the vending machine, the only key allowed to withdraw the proceeds
+    pub admin: Pubkey,
+}
+
+pub const SOL_VENDING_MACHINE_HEADER_SIZE: usize =
+    size_of::<u8>() + size_of::<u8>() + size_of::<u64>() + size_of::<Pubkey>();
+
+/// A multi slot buffer tracks its chunks in a single `u64` bitmask
+pub const MAX_MULTI_SLOT_CHUNKS: u16 = 64;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct MultiSlotHeader {
+    /// Size of the data following the header
+    pub total_size: u32,
+    /// Size of every chunk but the last one, which holds the rest of `total_size`
+    pub chunk_size: u16,
+    /// Number of distinct chunks written since the buffer was created or aborted
+    pub chunks_written: u16,
+    pub total_chunks: u16,
+    /// Set by `CommitMultiSlot` once every chunk is written, the data can't change anymore
+    pub committed: bool,
+    pub bump_seed: u8,
+    /// Bit `index` is set once chunk `index` is written
+    pub written_chunks: u64,
+}
+
+impl MultiSlotHeader {
+    pub fn is_written(&self, index: u16) -> bool {
+        self.written_chunks & (1 << index) != 0
+    }
+
+    /// Range of chunk `index` in the data following the header
+    pub fn chunk_range(&self, index: u16) -> std::ops::Range<usize> {
+        let start = index as usize * self.chunk_size as usize;
+        start..(start + self.chunk_size as usize).min(self.total_size as usize)
+    }
+}
+
+pub const MULTI_SLOT_HEADER_SIZE: usize = size_of::<u32>()
+    + size_of::<u16>()
+    + size_of::<u16>()
+    + size_of::<u16>()
+    + size_of::<bool>()
+    + size_of::<u8>()
+    + size_of::<u64>();
+
+/// A voted buffer tracks the votes in a single `u64` bitmap, 8 voters keep the header small
+pub const MAX_VOTERS: usize = 8;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct VotedEchoHeader {
+    /// The first `voter_count` entries are the voters, the rest is zeroed
+    pub voters: [Pubkey; MAX_VOTERS],
+    pub voter_count: u8,
+    /// Number of votes that applies the staged write
+    pub quorum: u8,
+    /// Bit `i` is set once `voters[i]` voted for the pending write
+    pub vote_bitmap: u64,
+    /// Keccak hash of the staged data, zeroed while no write is pending
+    pub pending_data_hash: [u8; 32],
+    /// Number of staged bytes
+    pub pending_len: u32,
+    /// Payer of the buffer, its address is derived from it
+    pub creator: Pubkey,
+    pub bump_seed: u8,
+}
+
+impl VotedEchoHeader {
+    /// Index of `key` in the voter list
+    pub fn voter_index(&self, key: &Pubkey) -> Option<usize> {
+        self.voters[..self.voter_count as usize]
+            .iter()
+            .position(|voter| voter == key)
+    }
+
+    pub fn has_pending_write(&self) -> bool {
+        self.pending_data_hash != [0; 32]
+    }
+}
+
+pub const VOTED_ECHO_HEADER_SIZE: usize = MAX_VOTERS * size_of::<Pubkey>()
+    + size_of::<u8>()
+    + size_of::<u8>()
+    + size_of::<u64>()
+    + 32
+    + size_of::<u32>()
+    + size_of::<Pubkey>()
+    + size_of::<u8>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct GaslessHeader {
+    pub bump_seed: u8,
+    /// Signs the writes off-chain, the buffer address is derived from it
+    pub authority: Pubkey,
+    /// Only key allowed to submit the signed writes, paying the transaction fees
+    pub relayer: Pubkey,
+    /// Part of every signed message and incremented by every write, so a signature can't be replayed
+    pub nonce: u64,
+}
+
+pub const GASLESS_HEADER_SIZE: usize =
+    size_of::<u8>() + size_of::<Pubkey>() + size_of::<Pubkey>() + size_of::<u64>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct OracleEchoHeader {
+    pub bump_seed: u8,
+    /// Only key allowed to write, the buffer address is derived from it
+    pub authority: Pubkey,
+    /// Price feed read by every write, a Pyth price account or any account holding an `i64` price
+    pub oracle: Pubkey,
+    /// Writes succeed while the oracle price is within `[min_price, max_price]`
+    pub min_price: i64,
+    pub max_price: i64,
+}
+
+pub const ORACLE_ECHO_HEADER_SIZE: usize = size_of::<u8>()
+    + size_of::<Pubkey>()
+    + size_of::<Pubkey>()
+    + size_of::<i64>()
+    + size_of::<i64>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct MemoEchoHeader {
+    pub bump_seed: u8,
+    /// Only key allowed to write, the buffer address is derived from it
+    pub authority: Pubkey,
+}
+
+pub const MEMO_ECHO_HEADER_SIZE: usize = size_of::<u8>() + size_of::<Pubkey>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SchemaEchoHeader {
+    pub bump_seed: u8,
+    /// Only key allowed to write, the buffer address is derived from it
+    pub authority: Pubkey,
+    /// SHA-256 of the Borsh schema descriptor every write must be declared with
+    pub schema_hash: [u8; 32],
+}
+
+pub const SCHEMA_ECHO_HEADER_SIZE: usize =
+    size_of::<u8>() + size_of::<Pubkey>() + size_of::<[u8; 32]>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct CompressedEchoHeader {
+    pub bump_seed: u8,
+    /// Only key allowed to update the leaf, the buffer address is derived from it
+    pub authority: Pubkey,
+    /// Identifies the off-chain Merkle tree the leaf belongs to, the buffer address is derived from it
+    pub tree_id: Pubkey,
+    /// Hash of the latest leaf proven to be in the tree, zeroed until the first update
+    pub leaf: [u8; 32],
+    /// Root the latest leaf was proven against
+    pub root: [u8; 32],
+    /// Position of the latest leaf in the tree
+    pub leaf_index: u32,
+}
+
+pub const COMPRESSED_ECHO_HEADER_SIZE: usize = size_of::<u8>()
+    + size_of::<Pubkey>()
+    + size_of::<Pubkey>()
+    + size_of::<[u8; 32]>()
+    + size_of::<[u8; 32]>()
+    + size_of::<u32>();
+
+/// Longest proof accepted, enough for any leaf index that fits in a u32
+pub const MAX_MERKLE_PROOF_LEN: usize = 32;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct AuctionHeader {
+    /// Last slot bids are accepted in, the auction can be settled after it
+    pub end_slot: u64,
+    /// Lamports held by the vault for the highest bid, 0 until the first bid
+    pub highest_bid: u64,
+    /// Key that gets write access once the auction is settled
+    pub highest_bidder: Pubkey,
+    pub settled: bool,
+    pub bump_seed: u8,
+    pub vault_bump_seed: u8,
+    /// Lowest bid accepted
+    pub reserve_price: u64,
+    /// Creator of the auction, receives the highest bid at settlement
+    pub creator: Pubkey,
+}
+
+pub const AUCTION_HEADER_SIZE: usize = size_of::<u64>()
+    + size_of::<u64>()
+    + size_of::<Pubkey>()
+    + size_of::<bool>()
+    + size_of::<u8>()
+    + size_of::<u8>()
+    + size_of::<u64>()
+    + size_of::<Pubkey>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct StakeGatedHeader {
+    pub bump_seed: u8,
+    /// Creator of the buffer, the buffer address is derived from it and the vote account
+    pub creator: Pubkey,
+    /// Writers need stake delegated to this vote account
+    pub vote_account: Pubkey,
+    /// Lamports of delegated stake needed to write
+    pub min_stake: u64,
+}
+
+pub const STAKE_GATED_HEADER_SIZE: usize =
+    size_of::<u8>() + size_of::<Pubkey>() + size_of::<Pubkey>() + size_of::<u64>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ReplicatedEchoHeader {
+    pub bump_seed: u8,
+    /// `REPLICA_ROLE_PRIMARY` or `REPLICA_ROLE_REPLICA`, part of the seeds so both buffers of a pair differ
+    pub role: u8,
+    pub primary_authority: Pubkey,
+    pub replica_authority: Pubkey,
+    /// Set by the authority of the buffer, replicated writes fail while either buffer of the pair is frozen
+    pub frozen: bool,
+}
+
+/// The buffer of a replicated pair owned by the `primary_authority`
+pub const REPLICA_ROLE_PRIMARY: u8 = 0;
+/// The buffer of a replicated pair owned by the `replica_authority`
+pub const REPLICA_ROLE_REPLICA: u8 = 1;
+
+pub const REPLICATED_ECHO_HEADER_SIZE: usize = size_of::<u8>()
+    + size_of::<u8>()
+    + size_of::<Pubkey>()
+    + size_of::<Pubkey>()
+    + size_of::<bool>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct FeeTreasury {
+    pub bump_seed: u8,
+    /// Only key allowed to drain the treasury
+    pub authority: Pubkey,
+}
+
+pub const FEE_TREASURY_SIZE: usize = size_of::<u8>() + size_of::<Pubkey>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct OwnershipEchoHeader {
+    pub bump_seed: u8,
+    /// Asset whose ownership the buffer records, the buffer address is derived from it
+    pub asset: Pubkey,
+    /// Only key allowed to approve a transfer, replaced by the new owner on every claim
+    pub current_owner: Pubkey,
+    /// Part of every approval message and incremented by every claim, so an approval can't be replayed
+    pub transfer_count: u64,
+}
+
+pub const OWNERSHIP_ECHO_HEADER_SIZE: usize =
+    size_of::<u8>() + size_of::<Pubkey>() + size_of::<Pubkey>() + size_of::<u64>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SubscriptionHeader {
+    /// Writes succeed before this slot, 0 until the first renewal
+    pub expires_at_slot: u64,
+    /// Tokens of `mint` burned for every renewed period
+    pub price_per_period: u64,
+    /// Slots added to `expires_at_slot` for every renewed period
+    pub period_slots: u64,
+    pub mint: Pubkey,
+    pub bump_seed: u8,
+    /// Only key allowed to write, the buffer address is derived from it and the `mint`
+    pub authority: Pubkey,
+}
+
+pub const SUBSCRIPTION_HEADER_SIZE: usize = size_of::<u64>()
+    + size_of::<u64>()
+    + size_of::<u64>()
+    + size_of::<Pubkey>()
+    + size_of::<u8>()
+    + size_of::<Pubkey>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct NftVendingMachineHeader {
+    pub bump_seed: u8,
+    /// Verified collection the burned NFTs must belong to, the buffer address is derived from it
+    pub collection: Pubkey,
+}
+
+pub const NFT_VENDING_MACHINE_HEADER_SIZE: usize = size_of::<u8>() + size_of::<Pubkey>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct TimeLockHeader {
+    /// Reads fail before this slot, the data stays readable forever after
+    pub unlock_slot: u64,
+    pub bump_seed: u8,
+}
+
+pub const TIME_LOCK_HEADER_SIZE: usize = size_of::<u64>() + size_of::<u8>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SharedBufferHeader {
+    pub bump_seed: u8,
+    /// Seed of the buffer address, shared by the programs of the group instead of an authority
+    pub group_seed: [u8; 32],
+    /// Only program whose cross program invocations can write to the buffer
+    pub allowed_program: Pubkey,
+}
+
+pub const SHARED_BUFF_HEADER_SIZE: usize =
+    size_of::<u8>() + size_of::<[u8; 32]>() + size_of::<Pubkey>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct DurableNonceHeader {
+    pub bump_seed: u8,
+    pub authority: Pubkey,
+    /// System nonce account advanced by every write, its current value must be signed in the write
+    pub nonce_account: Pubkey,
+}
+
+pub const DURABLE_NONCE_HEADER_SIZE: usize =
+    size_of::<u8>() + size_of::<Pubkey>() + size_of::<Pubkey>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct GovernanceHeader {
+    pub governance_mint: Pubkey,
+    /// Tokens of `governance_mint` a writer must hold, they are not burned by the write
+    pub min_tokens: u64,
+    pub bump_seed: u8,
+}
+
+pub const GOVERNANCE_HEADER_SIZE: usize = size_of::<Pubkey>() + size_of::<u64>() + size_of::<u8>();
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct CrossChainHeader {
+    pub bump_seed: u8,
+    /// Wormhole chain id of the only emitter whose messages are written
+    pub emitter_chain: u16,
+    /// Address of the emitter on its chain, left padded to 32 bytes like in the VAAs
+    pub emitter_address: [u8; 32],
+    /// Set by the first write, until then any sequence is accepted
+    pub has_sequence: bool,
+    /// Sequence of the last VAA written, only later messages of the emitter are accepted
+    pub last_sequence: u64,
+}
+
+pub const CROSS_CHAIN_HEADER_SIZE: usize = size_of::<u8>()
+    + size_of::<u16>()
+    + size_of::<[u8; 32]>()
+    + size_of::<bool>()
+    + size_of::<u64>();
+
+/// Header of a buffer whose data is revealed a bit more every slot, followed by the masked data and the revealed data
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct GradualReleaseHeader {
+    pub bump_seed: u8,
+    pub authority: Pubkey,
+    /// Slot of the initialization, nothing is revealed yet
+    pub reveal_start_slot: u64,
+    /// Everything is revealed from this slot on
+    pub reveal_end_slot: u64,
+    /// Length of the data, of both the masked and the revealed regions
+    pub total_bytes: u32,
+    /// Bytes of the revealed region written so far
+    pub revealed_bytes: u32,
 }
 
-pub const VENDING_MACHINE_BUFF_HEADER_SIZE: usize = size_of::<u8>() + size_of::<u64>();
+pub const GRADUAL_RELEASE_HEADER_SIZE: usize = size_of::<u8>()
+    + size_of::<Pubkey>()
+    + size_of::<u64>()
+    + size_of::<u64>()
+    + size_of::<u32>()
+    + size_of::<u32>();
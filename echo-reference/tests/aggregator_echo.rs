@@ -0,0 +1,162 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::AGGREGATOR_BUFF_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+const DATA_LEN: usize = 4;
+
+fn aggregator_address(program_id: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"aggregator", authority.as_ref()], program_id).0
+}
+
+fn aggregate_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    signer: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::AggregateWrite { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*signer, true),
+        ],
+    )
+}
+
+async fn initialize(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    authority: &Pubkey,
+) -> Pubkey {
+    let buffer = aggregator_address(program_id, authority);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeAggregatorEcho {
+                buffer_size: AGGREGATOR_BUFF_HEADER_SIZE + DATA_LEN,
+                authority: *authority,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    buffer
+}
+
+async fn aggregate(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    data: Vec<u8>,
+) {
+    let contributor = Keypair::new();
+    send(
+        context,
+        &[aggregate_ix(
+            program_id,
+            buffer,
+            &contributor.pubkey(),
+            data,
+        )],
+        &[&contributor],
+    )
+    .await
+    .unwrap();
+}
+
+async fn buffer_data(context: &mut ProgramTestContext, buffer: &Pubkey) -> Vec<u8> {
+    account_data(context, buffer).await[AGGREGATOR_BUFF_HEADER_SIZE..].to_vec()
+}
+
+#[tokio::test]
+async fn test_aggregate_write_is_commutative() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let first = initialize(&mut context, &program_id, &Pubkey::new_unique()).await;
+    let second = initialize(&mut context, &program_id, &Pubkey::new_unique()).await;
+    let a = vec![0b1010_1010, 1, 2, 3];
+    let b = vec![0b0101_0101, 7, 7, 7];
+
+    aggregate(&mut context, &program_id, &first, a.clone()).await;
+    aggregate(&mut context, &program_id, &first, b.clone()).await;
+    aggregate(&mut context, &program_id, &second, b.clone()).await;
+    aggregate(&mut context, &program_id, &second, a.clone()).await;
+
+    let expected: Vec<u8> = a.iter().zip(b.iter()).map(|(x, y)| x ^ y).collect();
+    assert_eq!(buffer_data(&mut context, &first).await, expected);
+    assert_eq!(buffer_data(&mut context, &second).await, expected);
+}
+
+#[tokio::test]
+async fn test_aggregate_write_is_self_inverse_and_wraps() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let buffer = initialize(&mut context, &program_id, &Pubkey::new_unique()).await;
+
+    aggregate(&mut context, &program_id, &buffer, vec![5, 6, 7, 8]).await;
+    let before = buffer_data(&mut context, &buffer).await;
+
+    // 6 bytes into a 4 byte buffer, the last two wrap onto the first two
+    aggregate(&mut context, &program_id, &buffer, vec![1, 1, 1, 1, 2, 2]).await;
+    assert_eq!(
+        buffer_data(&mut context, &buffer).await,
+        vec![5 ^ 1 ^ 2, 6 ^ 1 ^ 2, 7 ^ 1, 8 ^ 1]
+    );
+
+    aggregate(&mut context, &program_id, &buffer, vec![1, 1, 1, 1, 2, 2]).await;
+    assert_eq!(buffer_data(&mut context, &buffer).await, before);
+}
+
+#[tokio::test]
+async fn test_reset_aggregator_requires_authority() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = Keypair::new();
+    let buffer = initialize(&mut context, &program_id, &authority.pubkey()).await;
+    aggregate(&mut context, &program_id, &buffer, vec![9; DATA_LEN]).await;
+
+    let reset_ix = |signer: &Pubkey| {
+        Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::ResetAggregator,
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(*signer, true),
+            ],
+        )
+    };
+
+    let intruder = Keypair::new();
+    let result = send(&mut context, &[reset_ix(&intruder.pubkey())], &[&intruder]).await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+
+    send(
+        &mut context,
+        &[reset_ix(&authority.pubkey())],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(buffer_data(&mut context, &buffer).await, vec![0; DATA_LEN]);
+}
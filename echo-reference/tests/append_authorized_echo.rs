@@ -0,0 +1,142 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{AuthorizedBufferHeader, AUTH_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+    },
+    solana_program_test::tokio,
+    solana_sdk::signature::Signer,
+};
+
+fn append_authorized_echo_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::AppendAuthorizedEcho { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+fn cursor(data: &[u8]) -> u32 {
+    AuthorizedBufferHeader::try_from_slice(&data[..AUTH_BUFF_HEADER_SIZE])
+        .unwrap()
+        .cursor
+}
+
+#[tokio::test]
+async fn test_append_authorized_echo_appends_in_order() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) =
+        setup_authorized_buffer(&mut context, &program_id, AUTH_BUFF_HEADER_SIZE + 16).await;
+
+    for chunk in [vec![1; 4], vec![2; 4], vec![3; 8]] {
+        send(
+            &mut context,
+            &[append_authorized_echo_ix(
+                &program_id,
+                &buffer,
+                &authority.pubkey(),
+                chunk,
+            )],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+    }
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(cursor(&data), 16);
+    assert_eq!(
+        &data[AUTH_BUFF_HEADER_SIZE..AUTH_BUFF_HEADER_SIZE + 4],
+        &[1; 4]
+    );
+    assert_eq!(
+        &data[AUTH_BUFF_HEADER_SIZE + 4..AUTH_BUFF_HEADER_SIZE + 8],
+        &[2; 4]
+    );
+    assert_eq!(&data[AUTH_BUFF_HEADER_SIZE + 8..], &[3; 8]);
+}
+
+#[tokio::test]
+async fn test_append_authorized_echo_rejects_overflow() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) =
+        setup_authorized_buffer(&mut context, &program_id, AUTH_BUFF_HEADER_SIZE + 8).await;
+
+    send(
+        &mut context,
+        &[append_authorized_echo_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            vec![1; 6],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut context,
+        &[append_authorized_echo_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            vec![2; 3],
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::BufferFull);
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(cursor(&data), 6);
+    assert_eq!(&data[AUTH_BUFF_HEADER_SIZE..], &[1, 1, 1, 1, 1, 1, 0, 0]);
+}
+
+#[tokio::test]
+async fn test_overwrite_resets_cursor_before_append() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) =
+        setup_authorized_buffer(&mut context, &program_id, AUTH_BUFF_HEADER_SIZE + 16).await;
+
+    send(
+        &mut context,
+        &[
+            append_authorized_echo_ix(&program_id, &buffer, &authority.pubkey(), vec![1; 10]),
+            authorized_echo_ix(&program_id, &buffer, &authority.pubkey(), vec![2; 3]),
+            append_authorized_echo_ix(&program_id, &buffer, &authority.pubkey(), vec![3; 2]),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(cursor(&data), 5);
+    assert_eq!(
+        &data[AUTH_BUFF_HEADER_SIZE..AUTH_BUFF_HEADER_SIZE + 5],
+        &[2, 2, 2, 3, 3]
+    );
+    assert!(data[AUTH_BUFF_HEADER_SIZE + 5..].iter().all(|b| *b == 0));
+}
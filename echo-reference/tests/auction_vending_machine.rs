@@ -0,0 +1,369 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{AuctionHeader, AUCTION_HEADER_SIZE},
+    },
+    solana_program::{
+        clock::Clock,
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+const DURATION: u64 = 100;
+const RESERVE: u64 = 1_000_000;
+
+fn vault_address(program_id: &Pubkey, buffer: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"auction_vault", buffer.as_ref()], program_id).0
+}
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> (Keypair, Pubkey) {
+    let creator = funded_keypair(context).await;
+    let (buffer, _) =
+        Pubkey::find_program_address(&[b"auction", creator.pubkey().as_ref()], program_id);
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeAuctionVendingMachine {
+                buffer_size: AUCTION_HEADER_SIZE + 8,
+                auction_duration_slots: DURATION,
+                reserve_price: RESERVE,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(creator.pubkey(), true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[&creator],
+    )
+    .await
+    .unwrap();
+    (creator, buffer)
+}
+
+fn bid_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    bidder: &Pubkey,
+    previous_bidder: Option<&Pubkey>,
+    bid_lamports: u64,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*buffer, false),
+        AccountMeta::new(vault_address(program_id, buffer), false),
+        AccountMeta::new(*bidder, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some(previous_bidder) = previous_bidder {
+        accounts.push(AccountMeta::new(*previous_bidder, false));
+    }
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::BidOnVendingSlot { bid_lamports },
+        accounts,
+    )
+}
+
+fn settle_ix(program_id: &Pubkey, buffer: &Pubkey, creator: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::SettleAuction,
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new(vault_address(program_id, buffer), false),
+            AccountMeta::new(*creator, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+fn auction_echo_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    winner: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::AuctionEcho { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*winner, true),
+        ],
+    )
+}
+
+async fn balance(context: &mut ProgramTestContext, key: &Pubkey) -> u64 {
+    context.banks_client.get_balance(*key).await.unwrap()
+}
+
+async fn header(context: &mut ProgramTestContext, buffer: &Pubkey) -> AuctionHeader {
+    AuctionHeader::try_from_slice(&account_data(context, buffer).await[..AUCTION_HEADER_SIZE])
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_auction_overbid_refunds_previous_bidder() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (_creator, buffer) = setup(&mut context, &program_id).await;
+    let vault = vault_address(&program_id, &buffer);
+
+    let first = funded_keypair(&mut context).await;
+    let second = funded_keypair(&mut context).await;
+    let first_lamports = balance(&mut context, &first.pubkey()).await;
+
+    send(
+        &mut context,
+        &[bid_ix(&program_id, &buffer, &first.pubkey(), None, RESERVE)],
+        &[&first],
+    )
+    .await
+    .unwrap();
+    assert_eq!(balance(&mut context, &vault).await, RESERVE);
+    assert_eq!(
+        balance(&mut context, &first.pubkey()).await,
+        first_lamports - RESERVE
+    );
+
+    // the previous highest bidder has to be passed to be refunded
+    assert_echo_error(
+        send(
+            &mut context,
+            &[bid_ix(
+                &program_id,
+                &buffer,
+                &second.pubkey(),
+                Some(&second.pubkey()),
+                2 * RESERVE,
+            )],
+            &[&second],
+        )
+        .await,
+        EchoError::InvalidAccountAddress,
+    );
+
+    send(
+        &mut context,
+        &[bid_ix(
+            &program_id,
+            &buffer,
+            &second.pubkey(),
+            Some(&first.pubkey()),
+            2 * RESERVE,
+        )],
+        &[&second],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(balance(&mut context, &vault).await, 2 * RESERVE);
+    assert_eq!(balance(&mut context, &first.pubkey()).await, first_lamports);
+    let buffer_header = header(&mut context, &buffer).await;
+    assert_eq!(buffer_header.highest_bid, 2 * RESERVE);
+    assert_eq!(buffer_header.highest_bidder, second.pubkey());
+    assert!(!buffer_header.settled);
+}
+
+#[tokio::test]
+async fn test_auction_underbid_fails() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (_creator, buffer) = setup(&mut context, &program_id).await;
+
+    let first = funded_keypair(&mut context).await;
+    let second = funded_keypair(&mut context).await;
+
+    // below the reserve price
+    assert_echo_error(
+        send(
+            &mut context,
+            &[bid_ix(
+                &program_id,
+                &buffer,
+                &first.pubkey(),
+                None,
+                RESERVE - 1,
+            )],
+            &[&first],
+        )
+        .await,
+        EchoError::BidTooLow,
+    );
+
+    send(
+        &mut context,
+        &[bid_ix(
+            &program_id,
+            &buffer,
+            &first.pubkey(),
+            None,
+            2 * RESERVE,
+        )],
+        &[&first],
+    )
+    .await
+    .unwrap();
+
+    // matching the highest bid is not enough
+    for bid in [RESERVE, 2 * RESERVE] {
+        assert_echo_error(
+            send(
+                &mut context,
+                &[bid_ix(
+                    &program_id,
+                    &buffer,
+                    &second.pubkey(),
+                    Some(&first.pubkey()),
+                    bid,
+                )],
+                &[&second],
+            )
+            .await,
+            EchoError::BidTooLow,
+        );
+    }
+
+    let buffer_header = header(&mut context, &buffer).await;
+    assert_eq!(buffer_header.highest_bid, 2 * RESERVE);
+    assert_eq!(buffer_header.highest_bidder, first.pubkey());
+}
+
+#[tokio::test]
+async fn test_auction_settlement_grants_write_access() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (creator, buffer) = setup(&mut context, &program_id).await;
+    let vault = vault_address(&program_id, &buffer);
+
+    let winner = funded_keypair(&mut context).await;
+    send(
+        &mut context,
+        &[bid_ix(
+            &program_id,
+            &buffer,
+            &winner.pubkey(),
+            None,
+            RESERVE,
+        )],
+        &[&winner],
+    )
+    .await
+    .unwrap();
+
+    // neither settling nor writing before the end slot
+    assert_echo_error(
+        send(
+            &mut context,
+            &[settle_ix(&program_id, &buffer, &creator.pubkey())],
+            &[],
+        )
+        .await,
+        EchoError::AuctionNotEnded,
+    );
+    assert_echo_error(
+        send(
+            &mut context,
+            &[auction_echo_ix(
+                &program_id,
+                &buffer,
+                &winner.pubkey(),
+                vec![1; 4],
+            )],
+            &[&winner],
+        )
+        .await,
+        EchoError::AuctionNotEnded,
+    );
+
+    let end_slot = header(&mut context, &buffer).await.end_slot;
+    context.warp_to_slot(end_slot + 1).unwrap();
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    assert!(clock.slot > end_slot);
+
+    let late = funded_keypair(&mut context).await;
+    assert_echo_error(
+        send(
+            &mut context,
+            &[bid_ix(
+                &program_id,
+                &buffer,
+                &late.pubkey(),
+                Some(&winner.pubkey()),
+                2 * RESERVE,
+            )],
+            &[&late],
+        )
+        .await,
+        EchoError::AuctionEnded,
+    );
+
+    let creator_lamports = balance(&mut context, &creator.pubkey()).await;
+    send(
+        &mut context,
+        &[settle_ix(&program_id, &buffer, &creator.pubkey())],
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        balance(&mut context, &creator.pubkey()).await,
+        creator_lamports + RESERVE
+    );
+    assert_eq!(balance(&mut context, &vault).await, 0);
+    assert!(header(&mut context, &buffer).await.settled);
+
+    assert_echo_error(
+        send(
+            &mut context,
+            &[settle_ix(&program_id, &buffer, &creator.pubkey())],
+            &[],
+        )
+        .await,
+        EchoError::AuctionEnded,
+    );
+
+    // only the highest bidder can write
+    assert_echo_error(
+        send(
+            &mut context,
+            &[auction_echo_ix(
+                &program_id,
+                &buffer,
+                &late.pubkey(),
+                vec![1; 4],
+            )],
+            &[&late],
+        )
+        .await,
+        EchoError::UnauthorizedCaller,
+    );
+    send(
+        &mut context,
+        &[auction_echo_ix(
+            &program_id,
+            &buffer,
+            &winner.pubkey(),
+            vec![1; 4],
+        )],
+        &[&winner],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        &account_data(&mut context, &buffer).await[AUCTION_HEADER_SIZE..],
+        &[1, 1, 1, 1, 0, 0, 0, 0]
+    );
+}
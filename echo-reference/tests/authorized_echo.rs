@@ -0,0 +1,226 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshSerialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        state::{AuthorizedBufferHeader, AUTH_BUFF_HEADER_SIZE},
+    },
+    solana_program::{pubkey::Pubkey, system_instruction},
+    solana_program_test::tokio,
+    solana_sdk::{
+        account::Account,
+        signature::{Keypair, Signer},
+    },
+};
+
+fn spoofed_buffer(owner: &Pubkey, header: AuthorizedBufferHeader, buffer_size: usize) -> Account {
+    let mut data = header.try_to_vec().unwrap();
+    data.resize(buffer_size, 0);
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: *owner,
+        ..Account::default()
+    }
+}
+
+#[tokio::test]
+async fn test_authorized_echo_writes_and_zero_pads() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let (buffer, _) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+
+    send(
+        &mut context,
+        &[
+            initialize_authorized_echo_ix(
+                &program_id,
+                &authority.pubkey(),
+                0,
+                AUTH_BUFF_HEADER_SIZE + 32,
+            ),
+            authorized_echo_ix(&program_id, &buffer, &authority.pubkey(), vec![1; 16]),
+            authorized_echo_ix(&program_id, &buffer, &authority.pubkey(), vec![2; 4]),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[AUTH_BUFF_HEADER_SIZE..AUTH_BUFF_HEADER_SIZE + 4],
+        &[2; 4]
+    );
+    assert!(data[AUTH_BUFF_HEADER_SIZE + 4..].iter().all(|b| *b == 0));
+}
+
+#[tokio::test]
+async fn test_authorized_echo_rejects_buffer_owned_by_other_program() {
+    let (mut program_test, program_id) = program_test();
+    let authority = Keypair::new();
+    let (buffer, bump_seed) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+    program_test.add_account(
+        buffer,
+        spoofed_buffer(
+            &Pubkey::new_unique(),
+            AuthorizedBufferHeader {
+                bump_seed,
+                buffer_seed: 0,
+                ..AuthorizedBufferHeader::default()
+            },
+            AUTH_BUFF_HEADER_SIZE + 32,
+        ),
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let result = send(
+        &mut context,
+        &[authorized_echo_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            vec![1; 8],
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn test_authorized_echo_rejects_non_canonical_bump() {
+    let (mut program_test, program_id) = program_test();
+    let authority = Keypair::new();
+    let (_, canonical_bump) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+
+    // find the next valid (but non-canonical) bump below the canonical one
+    let (buffer, bump_seed) = (0..canonical_bump)
+        .rev()
+        .find_map(|bump| {
+            Pubkey::create_program_address(
+                &[
+                    b"authority",
+                    authority.pubkey().as_ref(),
+                    &0u64.to_le_bytes(),
+                    &[bump],
+                ],
+                &program_id,
+            )
+            .ok()
+            .map(|address| (address, bump))
+        })
+        .unwrap();
+    program_test.add_account(
+        buffer,
+        spoofed_buffer(
+            &program_id,
+            AuthorizedBufferHeader {
+                bump_seed,
+                buffer_seed: 0,
+                ..AuthorizedBufferHeader::default()
+            },
+            AUTH_BUFF_HEADER_SIZE + 32,
+        ),
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let result = send(
+        &mut context,
+        &[authorized_echo_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            vec![1; 8],
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn test_initialize_authorized_echo_buffer_size() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+
+    // a buffer with no room past the header
+    let result = send(
+        &mut context,
+        &[initialize_authorized_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            0,
+            AUTH_BUFF_HEADER_SIZE,
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+
+    send(
+        &mut context,
+        &[initialize_authorized_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            0,
+            AUTH_BUFF_HEADER_SIZE + 1,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let (buffer, _) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let account = context
+        .banks_client
+        .get_account(buffer)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.data.len(), AUTH_BUFF_HEADER_SIZE + 1);
+    assert_eq!(
+        account.lamports,
+        rent.minimum_balance(AUTH_BUFF_HEADER_SIZE + 1)
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_authorized_echo_rejects_underfunded_payer() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let authority = Keypair::new();
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[system_instruction::transfer(
+            &payer,
+            &authority.pubkey(),
+            rent.minimum_balance(AUTH_BUFF_HEADER_SIZE + 32) - 1,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut context,
+        &[initialize_authorized_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            0,
+            AUTH_BUFF_HEADER_SIZE + 32,
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InsufficientFunds);
+}
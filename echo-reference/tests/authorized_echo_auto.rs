@@ -0,0 +1,139 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{AuthorityCounter, AuthorizedBufferHeader, AUTH_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::tokio,
+    solana_sdk::signature::Signer,
+};
+
+fn counter_address(program_id: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"counter", authority.as_ref()], program_id).0
+}
+
+fn initialize_auto_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    buffer_seed: u64,
+    buffer_size: usize,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::InitializeAuthorizedEchoAuto { buffer_size },
+        vec![
+            AccountMeta::new(
+                authorized_buffer_address(program_id, authority, buffer_seed).0,
+                false,
+            ),
+            AccountMeta::new(counter_address(program_id, authority), false),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_auto_initialized_buffers_use_sequential_seeds() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+
+    send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::InitializeAuthorityCounter,
+            vec![
+                AccountMeta::new(counter_address(&program_id, &authority.pubkey()), false),
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    for seed in 0..3u64 {
+        send(
+            &mut context,
+            &[initialize_auto_ix(
+                &program_id,
+                &authority.pubkey(),
+                seed,
+                AUTH_BUFF_HEADER_SIZE + 32,
+            )],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+    }
+
+    for seed in 0..3u64 {
+        let buffer = authorized_buffer_address(&program_id, &authority.pubkey(), seed).0;
+        let data = account_data(&mut context, &buffer).await;
+        let header =
+            AuthorizedBufferHeader::try_from_slice(&data[..AUTH_BUFF_HEADER_SIZE]).unwrap();
+        assert_eq!(header.buffer_seed, seed);
+
+        send(
+            &mut context,
+            &[authorized_echo_ix(
+                &program_id,
+                &buffer,
+                &authority.pubkey(),
+                vec![seed as u8 + 1; 4],
+            )],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+        let data = account_data(&mut context, &buffer).await;
+        assert_eq!(
+            &data[AUTH_BUFF_HEADER_SIZE..AUTH_BUFF_HEADER_SIZE + 4],
+            &[seed as u8 + 1; 4]
+        );
+    }
+
+    let counter = AuthorityCounter::try_from_slice(
+        &account_data(
+            &mut context,
+            &counter_address(&program_id, &authority.pubkey()),
+        )
+        .await,
+    )
+    .unwrap();
+    assert_eq!(counter.next_seed, 3);
+}
+
+#[tokio::test]
+async fn test_auto_initialize_requires_counter() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+
+    let result = send(
+        &mut context,
+        &[initialize_auto_ix(
+            &program_id,
+            &authority.pubkey(),
+            0,
+            AUTH_BUFF_HEADER_SIZE + 32,
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountOwner);
+}
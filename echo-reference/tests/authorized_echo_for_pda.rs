@@ -0,0 +1,120 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::AUTH_BUFF_HEADER_SIZE},
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{processor, tokio},
+    solana_sdk::signature::Signer,
+};
+
+const FIXTURE_AUTHORITY_SEED: &[u8] = b"echo_authority";
+
+/// Stands in for a governance program: signs `AuthorizedEcho` with its PDA and forwards its instruction data.
+/// Accounts: [echo_program, authorized_buffer, authority_pda]
+fn fixture_process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (_, bump_seed) = Pubkey::find_program_address(&[FIXTURE_AUTHORITY_SEED], program_id);
+    let instruction = Instruction::new_with_borsh(
+        *accounts[0].key,
+        &EchoInstruction::AuthorizedEcho {
+            data: instruction_data.to_vec(),
+        },
+        vec![
+            AccountMeta::new(*accounts[1].key, false),
+            AccountMeta::new_readonly(*accounts[2].key, true),
+        ],
+    );
+    invoke_signed(
+        &instruction,
+        &accounts[1..],
+        &[&[FIXTURE_AUTHORITY_SEED, &[bump_seed]]],
+    )
+}
+
+#[tokio::test]
+async fn test_pda_authority_writes_through_cpi() {
+    let (mut program_test, program_id) = program_test();
+    let fixture_program_id = Pubkey::new_unique();
+    program_test.add_program(
+        "echo_authority_fixture",
+        fixture_program_id,
+        processor!(fixture_process),
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let (authority, _) =
+        Pubkey::find_program_address(&[FIXTURE_AUTHORITY_SEED], &fixture_program_id);
+    let (buffer, _) = authorized_buffer_address(&program_id, &authority, 0);
+
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::InitializeAuthorizedEchoForPda {
+                buffer_seed: 0,
+                buffer_size: AUTH_BUFF_HEADER_SIZE + 32,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(authority, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    send(
+        &mut context,
+        &[Instruction {
+            program_id: fixture_program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(program_id, false),
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(authority, false),
+            ],
+            data: vec![4; 8],
+        }],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[AUTH_BUFF_HEADER_SIZE..AUTH_BUFF_HEADER_SIZE + 8],
+        &[4; 8]
+    );
+
+    // without the fixture program nobody can sign for the PDA
+    let result = send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::AuthorizedEcho { data: vec![5; 8] },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(authority, false),
+            ],
+        )],
+        &[],
+    )
+    .await;
+    assert_echo_error(result, EchoError::MissingRequiredSignature);
+}
@@ -0,0 +1,140 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::AverageHeader},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        signature::{Keypair, Signer},
+        transport::TransportError,
+    },
+};
+
+struct Average {
+    program_id: Pubkey,
+    authority: Keypair,
+    account: Pubkey,
+}
+
+impl Average {
+    async fn new(context: &mut ProgramTestContext, program_id: Pubkey, max_samples: u32) -> Self {
+        let authority = Keypair::new();
+        let account =
+            Pubkey::find_program_address(&[b"average", authority.pubkey().as_ref()], &program_id).0;
+        let payer = context.payer.pubkey();
+        send(
+            context,
+            &[Instruction::new_with_borsh(
+                program_id,
+                &EchoInstruction::InitializeAverageEcho {
+                    max_samples,
+                    authority: authority.pubkey(),
+                },
+                vec![
+                    AccountMeta::new(account, false),
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        Self {
+            program_id,
+            authority,
+            account,
+        }
+    }
+
+    /// Submits `value` signed only by the payer, the authority doesn't sign.
+    async fn submit(
+        &self,
+        context: &mut ProgramTestContext,
+        value: u64,
+    ) -> Result<(), TransportError> {
+        let ix = Instruction::new_with_borsh(
+            self.program_id,
+            &EchoInstruction::SubmitSample { value },
+            vec![
+                AccountMeta::new(self.account, false),
+                AccountMeta::new_readonly(self.authority.pubkey(), false),
+            ],
+        );
+        send(context, &[ix], &[]).await
+    }
+
+    async fn header(&self, context: &mut ProgramTestContext) -> AverageHeader {
+        AverageHeader::try_from_slice(&account_data(context, &self.account).await).unwrap()
+    }
+}
+
+#[tokio::test]
+async fn test_average_of_known_samples() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let average = Average::new(&mut context, program_id, 10).await;
+
+    for value in [2, 4, 9, u64::MAX] {
+        average.submit(&mut context, value).await.unwrap();
+    }
+
+    let header = average.header(&mut context).await;
+    assert_eq!(header.count, 4);
+    assert_eq!(header.sum, 15 + u64::MAX as u128);
+    assert_eq!(
+        header.sum / header.count as u128,
+        (15 + u64::MAX as u128) / 4
+    );
+
+    let ix = Instruction::new_with_borsh(
+        program_id,
+        &EchoInstruction::ReadAverage,
+        vec![
+            AccountMeta::new_readonly(average.account, false),
+            AccountMeta::new_readonly(average.authority.pubkey(), false),
+        ],
+    );
+    send(&mut context, &[ix], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_average_max_samples_boundary() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let average = Average::new(&mut context, program_id, 2).await;
+
+    average.submit(&mut context, 1).await.unwrap();
+    average.submit(&mut context, 2).await.unwrap();
+    let result = average.submit(&mut context, 3).await;
+    assert_echo_error(result, EchoError::MaxWritesExceeded);
+
+    // only the authority can reset
+    let ix = |authority_signs| {
+        Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::ResetAverage,
+            vec![
+                AccountMeta::new(average.account, false),
+                AccountMeta::new_readonly(average.authority.pubkey(), authority_signs),
+            ],
+        )
+    };
+    let result = send(&mut context, &[ix(false)], &[]).await;
+    assert_echo_error(result, EchoError::MissingRequiredSignature);
+    send(&mut context, &[ix(true)], &[&average.authority])
+        .await
+        .unwrap();
+
+    let header = average.header(&mut context).await;
+    assert_eq!((header.sum, header.count), (0, 0));
+    average.submit(&mut context, 3).await.unwrap();
+}
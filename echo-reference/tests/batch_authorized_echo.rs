@@ -0,0 +1,167 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::AUTH_BUFF_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+    },
+    solana_program_test::tokio,
+    solana_sdk::signature::Signer,
+};
+
+fn batch_authorized_echo_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    buffers: &[Pubkey],
+    writes: Vec<Vec<u8>>,
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(*authority, true)];
+    accounts.extend(
+        buffers
+            .iter()
+            .map(|buffer| AccountMeta::new(*buffer, false)),
+    );
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::BatchAuthorizedEcho { writes },
+        accounts,
+    )
+}
+
+#[tokio::test]
+async fn test_batch_authorized_echo_mixed_buffer_sizes() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+
+    let sizes = [AUTH_BUFF_HEADER_SIZE + 4, AUTH_BUFF_HEADER_SIZE + 64, 512];
+    let mut buffers = vec![];
+    for (seed, size) in sizes.iter().enumerate() {
+        send(
+            &mut context,
+            &[initialize_authorized_echo_ix(
+                &program_id,
+                &authority.pubkey(),
+                seed as u64,
+                *size,
+            )],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+        buffers.push(authorized_buffer_address(&program_id, &authority.pubkey(), seed as u64).0);
+    }
+
+    let writes = vec![vec![1; 16], vec![2; 16], vec![3; 16]];
+    send(
+        &mut context,
+        &[batch_authorized_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            &buffers,
+            writes,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    // the smallest buffer only has room for the first 4 bytes
+    let data = account_data(&mut context, &buffers[0]).await;
+    assert_eq!(&data[AUTH_BUFF_HEADER_SIZE..], &[1; 4]);
+    for (buffer, value) in buffers[1..].iter().zip([2u8, 3u8]) {
+        let data = account_data(&mut context, buffer).await;
+        assert_eq!(
+            &data[AUTH_BUFF_HEADER_SIZE..AUTH_BUFF_HEADER_SIZE + 16],
+            &[value; 16]
+        );
+        assert!(data[AUTH_BUFF_HEADER_SIZE + 16..].iter().all(|b| *b == 0));
+    }
+}
+
+#[tokio::test]
+async fn test_batch_authorized_echo_fails_atomically_on_wrong_authority() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let other_authority = funded_keypair(&mut context).await;
+
+    send(
+        &mut context,
+        &[
+            initialize_authorized_echo_ix(
+                &program_id,
+                &authority.pubkey(),
+                0,
+                AUTH_BUFF_HEADER_SIZE + 64,
+            ),
+            initialize_authorized_echo_ix(
+                &program_id,
+                &other_authority.pubkey(),
+                0,
+                AUTH_BUFF_HEADER_SIZE + 64,
+            ),
+        ],
+        &[&authority, &other_authority],
+    )
+    .await
+    .unwrap();
+    let buffers = [
+        authorized_buffer_address(&program_id, &authority.pubkey(), 0).0,
+        authorized_buffer_address(&program_id, &other_authority.pubkey(), 0).0,
+    ];
+
+    let result = send(
+        &mut context,
+        &[batch_authorized_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            &buffers,
+            vec![vec![1; 8], vec![2; 8]],
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+
+    let data = account_data(&mut context, &buffers[0]).await;
+    assert!(data[AUTH_BUFF_HEADER_SIZE..].iter().all(|b| *b == 0));
+}
+
+#[tokio::test]
+async fn test_batch_authorized_echo_rejects_length_mismatch() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+
+    send(
+        &mut context,
+        &[initialize_authorized_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            0,
+            AUTH_BUFF_HEADER_SIZE + 64,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let buffer = authorized_buffer_address(&program_id, &authority.pubkey(), 0).0;
+
+    let result = send(
+        &mut context,
+        &[batch_authorized_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            &[buffer],
+            vec![vec![1; 8], vec![2; 8]],
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+}
@@ -0,0 +1,169 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{AuthorizedBufferHeader, BatchInitConfig, AUTH_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::tokio,
+    solana_sdk::signature::Signer,
+};
+
+fn batch_initialize_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    configs: Vec<BatchInitConfig>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(configs.iter().map(|config| {
+        AccountMeta::new(
+            authorized_buffer_address(program_id, authority, config.buffer_seed).0,
+            false,
+        )
+    }));
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::BatchInitialize { configs },
+        accounts,
+    )
+}
+
+#[tokio::test]
+async fn test_batch_initialize_creates_every_buffer() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+
+    let configs = vec![
+        BatchInitConfig {
+            buffer_seed: 0,
+            buffer_size: AUTH_BUFF_HEADER_SIZE + 4,
+        },
+        BatchInitConfig {
+            buffer_seed: 7,
+            buffer_size: AUTH_BUFF_HEADER_SIZE + 64,
+        },
+        BatchInitConfig {
+            buffer_seed: 42,
+            buffer_size: 512,
+        },
+    ];
+    send(
+        &mut context,
+        &[batch_initialize_ix(
+            &program_id,
+            &authority.pubkey(),
+            configs.clone(),
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    for config in configs {
+        let (buffer, bump_seed) =
+            authorized_buffer_address(&program_id, &authority.pubkey(), config.buffer_seed);
+        let data = account_data(&mut context, &buffer).await;
+        assert_eq!(data.len(), config.buffer_size);
+        assert_eq!(
+            AuthorizedBufferHeader::try_from_slice(&data[..AUTH_BUFF_HEADER_SIZE]).unwrap(),
+            AuthorizedBufferHeader {
+                bump_seed,
+                buffer_seed: config.buffer_seed,
+                ..AuthorizedBufferHeader::default()
+            }
+        );
+        assert!(data[AUTH_BUFF_HEADER_SIZE..].iter().all(|byte| *byte == 0));
+    }
+
+    // the buffers can be written like any authorized buffer
+    send(
+        &mut context,
+        &[authorized_echo_ix(
+            &program_id,
+            &authorized_buffer_address(&program_id, &authority.pubkey(), 7).0,
+            &authority.pubkey(),
+            vec![1; 4],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_batch_initialize_fails_as_a_whole() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+
+    send(
+        &mut context,
+        &[initialize_authorized_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            1,
+            AUTH_BUFF_HEADER_SIZE + 8,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    // seed 1 already exists, so seed 0 isn't created either
+    let configs = (0..2)
+        .map(|buffer_seed| BatchInitConfig {
+            buffer_seed,
+            buffer_size: AUTH_BUFF_HEADER_SIZE + 8,
+        })
+        .collect();
+    let result = send(
+        &mut context,
+        &[batch_initialize_ix(
+            &program_id,
+            &authority.pubkey(),
+            configs,
+        )],
+        &[&authority],
+    )
+    .await;
+    assert!(result.is_err());
+    let buffer = authorized_buffer_address(&program_id, &authority.pubkey(), 0).0;
+    assert!(context
+        .banks_client
+        .get_account(buffer)
+        .await
+        .unwrap()
+        .is_none());
+
+    let configs = (10..16)
+        .map(|buffer_seed| BatchInitConfig {
+            buffer_seed,
+            buffer_size: AUTH_BUFF_HEADER_SIZE + 8,
+        })
+        .collect();
+    let result = send(
+        &mut context,
+        &[batch_initialize_ix(
+            &program_id,
+            &authority.pubkey(),
+            configs,
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+}
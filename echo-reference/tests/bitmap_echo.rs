@@ -0,0 +1,154 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::BITMAP_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+struct Bitmap {
+    program_id: Pubkey,
+    authority: Keypair,
+    account: Pubkey,
+}
+
+impl Bitmap {
+    async fn new(context: &mut ProgramTestContext, program_id: Pubkey, bit_count: u32) -> Self {
+        let authority = Keypair::new();
+        let account =
+            Pubkey::find_program_address(&[b"bitmap", authority.pubkey().as_ref()], &program_id).0;
+        let payer = context.payer.pubkey();
+        send(
+            context,
+            &[Instruction::new_with_borsh(
+                program_id,
+                &EchoInstruction::InitializeBitmapEcho {
+                    bit_count,
+                    authority: authority.pubkey(),
+                },
+                vec![
+                    AccountMeta::new(account, false),
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        Self {
+            program_id,
+            authority,
+            account,
+        }
+    }
+
+    fn ix(&self, instruction: EchoInstruction) -> Instruction {
+        Instruction::new_with_borsh(
+            self.program_id,
+            &instruction,
+            vec![
+                AccountMeta::new(self.account, false),
+                AccountMeta::new_readonly(self.authority.pubkey(), true),
+            ],
+        )
+    }
+
+    async fn bits(&self, context: &mut ProgramTestContext) -> Vec<u8> {
+        account_data(context, &self.account).await[BITMAP_HEADER_SIZE..].to_vec()
+    }
+}
+
+#[tokio::test]
+async fn test_bitmap_set_and_clear() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let bitmap = Bitmap::new(&mut context, program_id, 10).await;
+    assert_eq!(bitmap.bits(&mut context).await, vec![0, 0]);
+
+    send(
+        &mut context,
+        &[
+            bitmap.ix(EchoInstruction::SetBit { index: 0 }),
+            bitmap.ix(EchoInstruction::SetBit { index: 3 }),
+            bitmap.ix(EchoInstruction::SetBit { index: 9 }),
+        ],
+        &[&bitmap.authority],
+    )
+    .await
+    .unwrap();
+    // only bits 8 and 9 of the second byte belong to the bitmap, the padding bits stay clear
+    assert_eq!(
+        bitmap.bits(&mut context).await,
+        vec![0b0000_1001, 0b0000_0010]
+    );
+
+    send(
+        &mut context,
+        &[
+            bitmap.ix(EchoInstruction::ClearBit { index: 0 }),
+            bitmap.ix(EchoInstruction::ClearBit { index: 1 }),
+            bitmap.ix(EchoInstruction::QueryBit { index: 3 }),
+        ],
+        &[&bitmap.authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        bitmap.bits(&mut context).await,
+        vec![0b0000_1000, 0b0000_0010]
+    );
+}
+
+#[tokio::test]
+async fn test_bitmap_rejects_out_of_range_index() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let bitmap = Bitmap::new(&mut context, program_id, 10).await;
+
+    for instruction in [
+        EchoInstruction::SetBit { index: 10 },
+        EchoInstruction::ClearBit { index: 15 },
+        EchoInstruction::SetBit { index: u32::MAX },
+    ] {
+        let result = send(
+            &mut context,
+            &[bitmap.ix(instruction)],
+            &[&bitmap.authority],
+        )
+        .await;
+        assert_echo_error(result, EchoError::InvalidInstructionInput);
+    }
+    assert_eq!(bitmap.bits(&mut context).await, vec![0, 0]);
+}
+
+#[tokio::test]
+async fn test_bitmap_rejects_non_authority() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let bitmap = Bitmap::new(&mut context, program_id, 8).await;
+
+    let intruder = Keypair::new();
+    let result = send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::SetBit { index: 0 },
+            vec![
+                AccountMeta::new(bitmap.account, false),
+                AccountMeta::new_readonly(intruder.pubkey(), true),
+            ],
+        )],
+        &[&intruder],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+}
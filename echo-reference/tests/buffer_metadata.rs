@@ -0,0 +1,242 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{
+            BufferMetadata, MetadataUri, AUTH_BUFF_HEADER_SIZE, BUFFER_METADATA_SIZE,
+            METADATA_URI_LEN,
+        },
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::Signer,
+};
+
+fn metadata_address(program_id: &Pubkey, buffer: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"metadata", buffer.as_ref()], program_id).0
+}
+
+fn padded<const N: usize>(value: &str) -> [u8; N] {
+    let mut field = [0; N];
+    field[..value.len()].copy_from_slice(value.as_bytes());
+    field
+}
+
+fn set_metadata_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    name: &str,
+    description: &str,
+    uri: &str,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::SetBufferMetadata {
+            name: padded(name),
+            description: padded(description),
+            uri: MetadataUri(padded::<METADATA_URI_LEN>(uri)),
+        },
+        vec![
+            AccountMeta::new(metadata_address(program_id, buffer), false),
+            AccountMeta::new_readonly(*buffer, false),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+fn close_metadata_ix(program_id: &Pubkey, buffer: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::CloseBufferMetadata,
+        vec![
+            AccountMeta::new(metadata_address(program_id, buffer), false),
+            AccountMeta::new_readonly(*buffer, false),
+            AccountMeta::new(*authority, true),
+        ],
+    )
+}
+
+async fn read_metadata(context: &mut ProgramTestContext, metadata: &Pubkey) -> BufferMetadata {
+    let data = account_data(context, metadata).await;
+    assert_eq!(data.len(), BUFFER_METADATA_SIZE);
+    BufferMetadata::try_from_slice(&data).unwrap()
+}
+
+#[tokio::test]
+async fn test_set_and_overwrite_buffer_metadata() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) =
+        setup_authorized_buffer(&mut context, &program_id, AUTH_BUFF_HEADER_SIZE + 8).await;
+    let metadata = metadata_address(&program_id, &buffer);
+
+    send(
+        &mut context,
+        &[set_metadata_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            "prices",
+            "Latest SOL/USD price",
+            "https://example.com/prices.json",
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let buffer_metadata = read_metadata(&mut context, &metadata).await;
+    assert_eq!(buffer_metadata.name, padded("prices"));
+    assert_eq!(buffer_metadata.description, padded("Latest SOL/USD price"));
+    assert_eq!(
+        buffer_metadata.uri,
+        MetadataUri(padded("https://example.com/prices.json"))
+    );
+
+    // the second call overwrites every field, shorter values don't leave stale bytes behind
+    send(
+        &mut context,
+        &[set_metadata_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            "fx",
+            "EUR/USD",
+            "ipfs://fx",
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let buffer_metadata = read_metadata(&mut context, &metadata).await;
+    assert_eq!(buffer_metadata.name, padded("fx"));
+    assert_eq!(buffer_metadata.description, padded("EUR/USD"));
+    assert_eq!(buffer_metadata.uri, MetadataUri(padded("ipfs://fx")));
+
+    send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::GetBufferMetadata,
+            vec![
+                AccountMeta::new_readonly(metadata, false),
+                AccountMeta::new_readonly(buffer, false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_set_buffer_metadata_requires_authority() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (_, buffer) =
+        setup_authorized_buffer(&mut context, &program_id, AUTH_BUFF_HEADER_SIZE + 8).await;
+
+    let intruder = funded_keypair(&mut context).await;
+    let result = send(
+        &mut context,
+        &[set_metadata_ix(
+            &program_id,
+            &buffer,
+            &intruder.pubkey(),
+            "spoofed",
+            "",
+            "",
+        )],
+        &[&intruder],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn test_close_buffer_metadata() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) =
+        setup_authorized_buffer(&mut context, &program_id, AUTH_BUFF_HEADER_SIZE + 8).await;
+    let metadata = metadata_address(&program_id, &buffer);
+
+    send(
+        &mut context,
+        &[set_metadata_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            "prices",
+            "",
+            "",
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let metadata_lamports = context.banks_client.get_balance(metadata).await.unwrap();
+    let authority_lamports = context
+        .banks_client
+        .get_balance(authority.pubkey())
+        .await
+        .unwrap();
+
+    // the transaction fee is paid by the context payer, so the authority gets exactly the rent back
+    send(
+        &mut context,
+        &[close_metadata_ix(&program_id, &buffer, &authority.pubkey())],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(authority.pubkey())
+            .await
+            .unwrap(),
+        authority_lamports + metadata_lamports
+    );
+    assert!(context
+        .banks_client
+        .get_account(metadata)
+        .await
+        .unwrap()
+        .is_none());
+
+    // closed metadata can be set again from scratch
+    send(
+        &mut context,
+        &[set_metadata_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            "again",
+            "",
+            "",
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        read_metadata(&mut context, &metadata).await.name,
+        padded("again")
+    );
+}
@@ -0,0 +1,247 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{AuthorizedBufferHeader, AUTH_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        keccak,
+        pubkey::Pubkey,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+const PAYLOAD_LEN: usize = 6 * 1024;
+const CHUNK_LEN: usize = 768;
+
+fn chunked_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    instruction: EchoInstruction,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &instruction,
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+async fn write_chunk(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Keypair,
+    payload: &[u8],
+    index: usize,
+) {
+    let offset = index * CHUNK_LEN;
+    send(
+        context,
+        &[chunked_ix(
+            program_id,
+            buffer,
+            &authority.pubkey(),
+            EchoInstruction::AuthorizedEchoChunk {
+                offset: offset as u32,
+                data: payload[offset..offset + CHUNK_LEN].to_vec(),
+            },
+        )],
+        &[authority],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_chunked_write_of_large_payload() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let (buffer, _) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+    send(
+        &mut context,
+        &[initialize_authorized_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            0,
+            AUTH_BUFF_HEADER_SIZE + PAYLOAD_LEN + 16,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let payload: Vec<u8> = (0..PAYLOAD_LEN).map(|i| (i % 251) as u8 + 1).collect();
+    let hash = keccak::hash(&payload).to_bytes();
+    let finalize = EchoInstruction::AuthorizedEchoFinalize {
+        total_len: PAYLOAD_LEN as u32,
+        hash,
+    };
+
+    // upload every chunk but the fourth
+    for index in (0..PAYLOAD_LEN / CHUNK_LEN).filter(|index| *index != 3) {
+        write_chunk(
+            &mut context,
+            &program_id,
+            &buffer,
+            &authority,
+            &payload,
+            index,
+        )
+        .await;
+    }
+
+    let data = account_data(&mut context, &buffer).await;
+    let header = AuthorizedBufferHeader::try_from_slice(&data[..AUTH_BUFF_HEADER_SIZE]).unwrap();
+    assert!(header.write_in_progress);
+    assert_eq!(header.sequence, 0);
+
+    let result = send(
+        &mut context,
+        &[chunked_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            finalize.clone(),
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::HashMismatch);
+
+    write_chunk(&mut context, &program_id, &buffer, &authority, &payload, 3).await;
+    send(
+        &mut context,
+        &[chunked_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            finalize,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    let header = AuthorizedBufferHeader::try_from_slice(&data[..AUTH_BUFF_HEADER_SIZE]).unwrap();
+    assert!(!header.write_in_progress);
+    assert_eq!(header.cursor, PAYLOAD_LEN as u32);
+    assert_eq!(header.sequence, 1);
+    assert_eq!(
+        &data[AUTH_BUFF_HEADER_SIZE..AUTH_BUFF_HEADER_SIZE + PAYLOAD_LEN],
+        payload.as_slice()
+    );
+    assert_eq!(&data[AUTH_BUFF_HEADER_SIZE + PAYLOAD_LEN..], &[0; 16]);
+}
+
+#[tokio::test]
+async fn test_append_rejected_while_chunked_write_in_progress() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let (buffer, _) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+    send(
+        &mut context,
+        &[
+            initialize_authorized_echo_ix(
+                &program_id,
+                &authority.pubkey(),
+                0,
+                AUTH_BUFF_HEADER_SIZE + 32,
+            ),
+            chunked_ix(
+                &program_id,
+                &buffer,
+                &authority.pubkey(),
+                EchoInstruction::AuthorizedEchoChunk {
+                    offset: 16,
+                    data: vec![1; 16],
+                },
+            ),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut context,
+        &[chunked_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            EchoInstruction::AppendAuthorizedEcho { data: vec![2; 4] },
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::WriteInProgress);
+}
+
+#[tokio::test]
+async fn test_clear_abandons_chunked_write() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) =
+        setup_authorized_buffer(&mut context, &program_id, AUTH_BUFF_HEADER_SIZE + 32).await;
+    send(
+        &mut context,
+        &[
+            chunked_ix(
+                &program_id,
+                &buffer,
+                &authority.pubkey(),
+                EchoInstruction::AuthorizedEchoChunk {
+                    offset: 16,
+                    data: vec![1; 16],
+                },
+            ),
+            chunked_ix(
+                &program_id,
+                &buffer,
+                &authority.pubkey(),
+                EchoInstruction::ClearAuthorizedBuffer,
+            ),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    let header = AuthorizedBufferHeader::try_from_slice(&data[..AUTH_BUFF_HEADER_SIZE]).unwrap();
+    assert!(!header.write_in_progress);
+
+    // the cleared buffer takes regular writes again
+    send(
+        &mut context,
+        &[chunked_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            EchoInstruction::AppendAuthorizedEcho { data: vec![2; 4] },
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[AUTH_BUFF_HEADER_SIZE..AUTH_BUFF_HEADER_SIZE + 4],
+        &[2; 4]
+    );
+    assert!(data[AUTH_BUFF_HEADER_SIZE + 4..].iter().all(|b| *b == 0));
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        state::{AuthorizedBufferHeader, AUTH_BUFF_HEADER_SIZE},
+    },
+    solana_program_test::tokio,
+    solana_sdk::signature::Signer,
+};
+
+#[tokio::test]
+async fn test_clear_authorized_buffer_zero_fills_payload() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let (buffer, bump_seed) = authorized_buffer_address(&program_id, &authority.pubkey(), 7);
+
+    send(
+        &mut context,
+        &[
+            initialize_authorized_echo_ix(
+                &program_id,
+                &authority.pubkey(),
+                7,
+                AUTH_BUFF_HEADER_SIZE + 64,
+            ),
+            authorized_echo_ix(&program_id, &buffer, &authority.pubkey(), vec![5; 40]),
+            clear_authorized_buffer_ix(&program_id, &buffer, &authority.pubkey()),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(data.len(), AUTH_BUFF_HEADER_SIZE + 64);
+    assert!(data[AUTH_BUFF_HEADER_SIZE..].iter().all(|b| *b == 0));
+
+    let header = AuthorizedBufferHeader::try_from_slice(&data[..AUTH_BUFF_HEADER_SIZE]).unwrap();
+    assert_eq!(header.bump_seed, bump_seed);
+    assert_eq!(header.buffer_seed, 7);
+    assert_eq!(header.cursor, 0);
+}
+
+#[tokio::test]
+async fn test_clear_authorized_buffer_rejects_non_authority() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let intruder = funded_keypair(&mut context).await;
+    let (buffer, _) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+
+    send(
+        &mut context,
+        &[
+            initialize_authorized_echo_ix(
+                &program_id,
+                &authority.pubkey(),
+                0,
+                AUTH_BUFF_HEADER_SIZE + 32,
+            ),
+            authorized_echo_ix(&program_id, &buffer, &authority.pubkey(), vec![5; 8]),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut context,
+        &[clear_authorized_buffer_ix(
+            &program_id,
+            &buffer,
+            &intruder.pubkey(),
+        )],
+        &[&intruder],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[AUTH_BUFF_HEADER_SIZE..AUTH_BUFF_HEADER_SIZE + 8],
+        &[5; 8]
+    );
+}
@@ -0,0 +1,165 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::CO_AUTH_BUFF_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+fn co_authorized_buffer_address(
+    program_id: &Pubkey,
+    first: &Pubkey,
+    second: &Pubkey,
+    buffer_seed: u64,
+) -> Pubkey {
+    let (a, b) = if first <= second {
+        (first, second)
+    } else {
+        (second, first)
+    };
+    Pubkey::find_program_address(
+        &[
+            b"co_authority",
+            a.as_ref(),
+            b.as_ref(),
+            &buffer_seed.to_le_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+) -> (Keypair, Keypair, Pubkey) {
+    let first = funded_keypair(context).await;
+    let second = Keypair::new();
+    let buffer = co_authorized_buffer_address(program_id, &first.pubkey(), &second.pubkey(), 0);
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeCoAuthorizedEcho {
+                buffer_seed: 0,
+                buffer_size: CO_AUTH_BUFF_HEADER_SIZE + 8,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(first.pubkey(), true),
+                AccountMeta::new_readonly(second.pubkey(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[&first],
+    )
+    .await
+    .unwrap();
+    (first, second, buffer)
+}
+
+fn co_authorized_echo_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    first: &Pubkey,
+    second: &Pubkey,
+    second_signs: bool,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::CoAuthorizedEcho { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*first, true),
+            AccountMeta::new_readonly(*second, second_signs),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_co_authorized_echo_with_both_signatures_in_any_order() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (first, second, buffer) = setup(&mut context, &program_id).await;
+
+    send(
+        &mut context,
+        &[co_authorized_echo_ix(
+            &program_id,
+            &buffer,
+            &first.pubkey(),
+            &second.pubkey(),
+            true,
+            vec![1; 8],
+        )],
+        &[&first, &second],
+    )
+    .await
+    .unwrap();
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[CO_AUTH_BUFF_HEADER_SIZE..], &[1; 8]);
+
+    send(
+        &mut context,
+        &[co_authorized_echo_ix(
+            &program_id,
+            &buffer,
+            &second.pubkey(),
+            &first.pubkey(),
+            true,
+            vec![2; 4],
+        )],
+        &[&first, &second],
+    )
+    .await
+    .unwrap();
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[CO_AUTH_BUFF_HEADER_SIZE..], &[2, 2, 2, 2, 0, 0, 0, 0]);
+}
+
+#[tokio::test]
+async fn test_co_authorized_echo_rejects_single_signature() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (first, second, buffer) = setup(&mut context, &program_id).await;
+
+    let result = send(
+        &mut context,
+        &[co_authorized_echo_ix(
+            &program_id,
+            &buffer,
+            &first.pubkey(),
+            &second.pubkey(),
+            false,
+            vec![1; 8],
+        )],
+        &[&first],
+    )
+    .await;
+    assert_echo_error(result, EchoError::MissingRequiredSignature);
+
+    // the same authority twice doesn't count as both
+    let result = send(
+        &mut context,
+        &[co_authorized_echo_ix(
+            &program_id,
+            &buffer,
+            &first.pubkey(),
+            &first.pubkey(),
+            true,
+            vec![1; 8],
+        )],
+        &[&first],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+}
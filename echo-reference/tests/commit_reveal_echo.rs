@@ -0,0 +1,135 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::AUTH_BUFF_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        keccak,
+        pubkey::Pubkey,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+fn commit_reveal_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    instruction: EchoInstruction,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &instruction,
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+async fn commit(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Keypair,
+    data: &[u8],
+) {
+    let hash = keccak::hash(data).to_bytes();
+    send(
+        context,
+        &[commit_reveal_ix(
+            program_id,
+            buffer,
+            &authority.pubkey(),
+            EchoInstruction::CommitAuthorizedEcho { hash },
+        )],
+        &[authority],
+    )
+    .await
+    .unwrap();
+}
+
+async fn reveal(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Keypair,
+    data: &[u8],
+) -> Result<(), solana_sdk::transport::TransportError> {
+    send(
+        context,
+        &[commit_reveal_ix(
+            program_id,
+            buffer,
+            &authority.pubkey(),
+            EchoInstruction::RevealAuthorizedEcho {
+                data: data.to_vec(),
+            },
+        )],
+        &[authority],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_commit_then_reveal() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) =
+        setup_authorized_buffer(&mut context, &program_id, AUTH_BUFF_HEADER_SIZE + 16).await;
+
+    commit(&mut context, &program_id, &buffer, &authority, b"first").await;
+    // an unrevealed commitment can be replaced
+    commit(&mut context, &program_id, &buffer, &authority, b"hello").await;
+
+    let result = reveal(&mut context, &program_id, &buffer, &authority, b"hello").await;
+    assert_echo_error(result, EchoError::RevealTooEarly);
+
+    let slot = context.banks_client.get_root_slot().await.unwrap();
+    context.warp_to_slot(slot + 2).unwrap();
+    reveal(&mut context, &program_id, &buffer, &authority, b"hello")
+        .await
+        .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[AUTH_BUFF_HEADER_SIZE..AUTH_BUFF_HEADER_SIZE + 5],
+        b"hello"
+    );
+
+    // the commitment was consumed by the reveal
+    let result = reveal(&mut context, &program_id, &buffer, &authority, b"hello").await;
+    assert_echo_error(result, EchoError::CommitmentMismatch);
+}
+
+#[tokio::test]
+async fn test_reveal_with_wrong_preimage() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) =
+        setup_authorized_buffer(&mut context, &program_id, AUTH_BUFF_HEADER_SIZE + 16).await;
+
+    commit(&mut context, &program_id, &buffer, &authority, b"hello").await;
+    let slot = context.banks_client.get_root_slot().await.unwrap();
+    context.warp_to_slot(slot + 2).unwrap();
+
+    let result = reveal(&mut context, &program_id, &buffer, &authority, b"jello").await;
+    assert_echo_error(result, EchoError::CommitmentMismatch);
+
+    let data = account_data(&mut context, &buffer).await;
+    assert!(data[AUTH_BUFF_HEADER_SIZE..].iter().all(|b| *b == 0));
+}
+
+#[tokio::test]
+async fn test_reveal_before_commit() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) =
+        setup_authorized_buffer(&mut context, &program_id, AUTH_BUFF_HEADER_SIZE + 16).await;
+
+    let result = reveal(&mut context, &program_id, &buffer, &authority, b"hello").await;
+    assert_echo_error(result, EchoError::CommitmentMismatch);
+}
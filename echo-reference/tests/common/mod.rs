@@ -0,0 +1,495 @@
+#![allow(dead_code)]
+
+use {
+    echo::{error::EchoError, instruction::EchoInstruction},
+    solana_program::{
+        instruction::{AccountMeta, Instruction, InstructionError},
+        program_pack::Pack,
+        pubkey::Pubkey,
+        system_instruction, system_program,
+    },
+    solana_program_test::{ProgramTest, ProgramTestContext},
+    solana_sdk::{
+        account::Account,
+        signature::{Keypair, Signer},
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    },
+    spl_token::state::{Account as TokenAccount, Mint},
+    std::{
+        sync::Mutex,
+        thread::{self, ThreadId},
+    },
+};
+
+/// Program logs recorded by `LogCapture`, next to the thread of the bank that emitted them.
+static LOGS: Mutex<Vec<(ThreadId, String)>> = Mutex::new(Vec::new());
+
+/// Records the program logs the runtime emits as `log` records, as `BanksClient` 1.9.1 doesn't return them.
+struct LogCapture;
+
+impl log::Log for LogCapture {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target() == "solana_program_runtime::stable_log"
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            LOGS.lock()
+                .unwrap()
+                .push((thread::current().id(), record.args().to_string()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+pub fn program_test() -> (ProgramTest, Pubkey) {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::default();
+    program_test.add_program("echo", program_id, None);
+    (program_test, program_id)
+}
+
+/// `program_test` recording the program logs for `send_with_logs`. The capture takes the place of the logger of
+/// `ProgramTest`, so every test of a target using `send_with_logs` must start from here.
+pub fn program_test_with_logs() -> (ProgramTest, Pubkey) {
+    // fails without harm when another test of the target installed it first
+    let _ = log::set_boxed_logger(Box::new(LogCapture));
+    let program_test = program_test();
+    // `ProgramTest` sets the max level of its own filter
+    log::set_max_level(log::LevelFilter::Debug);
+    program_test
+}
+
+pub fn add_funded_account(program_test: &mut ProgramTest, key: &Pubkey) {
+    program_test.add_account(
+        *key,
+        Account {
+            lamports: 100_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+}
+
+/// A new keypair funded by the context payer, for accounts that pay for their own buffers.
+pub async fn funded_keypair(context: &mut ProgramTestContext) -> Keypair {
+    let keypair = Keypair::new();
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[system_instruction::transfer(
+            &payer,
+            &keypair.pubkey(),
+            10_000_000_000,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    keypair
+}
+
+/// A new zeroed, rent-exempt account of `size` bytes assigned to `owner`.
+pub async fn create_owned_account(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    size: usize,
+) -> Keypair {
+    let account = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[system_instruction::create_account(
+            &payer,
+            &account.pubkey(),
+            rent.minimum_balance(size),
+            size as u64,
+            owner,
+        )],
+        &[&account],
+    )
+    .await
+    .unwrap();
+    account
+}
+
+/// Signs with the context payer plus `signers` and processes the transaction.
+pub async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransportError> {
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+/// Like `send`, also returning the program logs of the transaction, whose first instruction invokes `program_id`.
+pub async fn send_with_logs(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> (Result<(), TransportError>, Vec<String>) {
+    let start = LOGS.lock().unwrap().len();
+    let result = send(context, instructions, signers).await;
+
+    // the tests of the target run in parallel, each with a bank thread of its own
+    let logs = LOGS.lock().unwrap();
+    let invoke = format!("Program {} invoke [1]", program_id);
+    let bank_thread = logs[start..]
+        .iter()
+        .find(|(_, log)| *log == invoke)
+        .map(|(thread, _)| *thread);
+    let logs = logs[start..]
+        .iter()
+        .filter(|(thread, _)| Some(*thread) == bank_thread)
+        .map(|(_, log)| log.clone())
+        .collect();
+    (result, logs)
+}
+
+pub fn assert_echo_error(result: Result<(), TransportError>, expected: EchoError) {
+    match result {
+        Err(TransportError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        ))) => assert_eq!(code, expected as u32, "expected {:?}", expected),
+        other => panic!("expected {:?}, got {:?}", expected, other),
+    }
+}
+
+pub async fn account_data(context: &mut ProgramTestContext, key: &Pubkey) -> Vec<u8> {
+    context
+        .banks_client
+        .get_account(*key)
+        .await
+        .unwrap()
+        .expect("account not found")
+        .data
+}
+
+pub fn authorized_buffer_address(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    seed: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"authority", authority.as_ref(), &seed.to_le_bytes()],
+        program_id,
+    )
+}
+
+pub fn vending_machine_address(program_id: &Pubkey, mint: &Pubkey, price: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"vending_machine", mint.as_ref(), &price.to_le_bytes()],
+        program_id,
+    )
+}
+
+pub fn initialize_authorized_echo_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    buffer_seed: u64,
+    buffer_size: usize,
+) -> Instruction {
+    initialize_labeled_authorized_echo_ix(program_id, authority, buffer_seed, buffer_size, "")
+}
+
+/// Initializes the authorized buffer of seed 0 for a new funded authority, returns the authority and the buffer.
+pub async fn setup_authorized_buffer(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    buffer_size: usize,
+) -> (Keypair, Pubkey) {
+    let authority = funded_keypair(context).await;
+    send(
+        context,
+        &[initialize_authorized_echo_ix(
+            program_id,
+            &authority.pubkey(),
+            0,
+            buffer_size,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let (buffer, _) = authorized_buffer_address(program_id, &authority.pubkey(), 0);
+    (authority, buffer)
+}
+
+pub fn initialize_labeled_authorized_echo_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    buffer_seed: u64,
+    buffer_size: usize,
+    label: &str,
+) -> Instruction {
+    let (buffer, _) = authorized_buffer_address(program_id, authority, buffer_seed);
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::InitializeAuthorizedEcho {
+            buffer_seed,
+            buffer_size,
+            label: label.to_string(),
+            permanent_delegate: None,
+            write_fee_lamports: 0,
+            expiry_slot: 0,
+            crank_bounty_bps: 0,
+        },
+        vec![
+            AccountMeta::new(buffer, false),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn authorized_echo_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::AuthorizedEcho { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+pub fn clear_authorized_buffer_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::ClearAuthorizedBuffer,
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+pub fn initialize_vending_machine_echo_ix(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    mint_authority: &Pubkey,
+    payer: &Pubkey,
+    price: u64,
+    buffer_size: usize,
+) -> Instruction {
+    let (buffer, _) = vending_machine_address(program_id, mint, price);
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::InitializeVendingMachineEcho {
+            price,
+            buffer_size,
+            payment_mode: 0,
+            price_per_byte: 0,
+            require_ata: false,
+            max_uses: 0,
+            tiers: vec![],
+            cooldown_slots: 0,
+            free_authority_writes: false,
+        },
+        vec![
+            AccountMeta::new(buffer, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*mint_authority, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn vending_machine_echo_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    user: &Pubkey,
+    user_token_account: &Pubkey,
+    mint: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::VendingMachineEcho { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(*user_token_account, false),
+            AccountMeta::new(*mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+pub async fn create_mint(
+    context: &mut ProgramTestContext,
+    mint_authority: &Pubkey,
+    decimals: u8,
+) -> Keypair {
+    let mint = Keypair::new();
+    initialize_mint(context, &mint, mint_authority, decimals).await;
+    mint
+}
+
+pub async fn initialize_mint(
+    context: &mut ProgramTestContext,
+    mint: &Keypair,
+    mint_authority: &Pubkey,
+    decimals: u8,
+) {
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &context.payer.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(Mint::LEN),
+            Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint(
+            &spl_token::id(),
+            &mint.pubkey(),
+            mint_authority,
+            None,
+            decimals,
+        )
+        .unwrap(),
+    ];
+    send(context, &instructions, &[mint]).await.unwrap();
+}
+
+pub async fn create_token_account(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> Keypair {
+    let account = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &context.payer.pubkey(),
+            &account.pubkey(),
+            rent.minimum_balance(TokenAccount::LEN),
+            TokenAccount::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_account(
+            &spl_token::id(),
+            &account.pubkey(),
+            mint,
+            owner,
+        )
+        .unwrap(),
+    ];
+    send(context, &instructions, &[&account]).await.unwrap();
+    account
+}
+
+pub async fn mint_tokens(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    account: &Pubkey,
+    mint_authority: &Keypair,
+    amount: u64,
+) {
+    let instruction = spl_token::instruction::mint_to(
+        &spl_token::id(),
+        mint,
+        account,
+        &mint_authority.pubkey(),
+        &[],
+        amount,
+    )
+    .unwrap();
+    send(context, &[instruction], &[mint_authority])
+        .await
+        .unwrap();
+}
+
+pub async fn token_balance(context: &mut ProgramTestContext, account: &Pubkey) -> u64 {
+    TokenAccount::unpack(&account_data(context, account).await)
+        .unwrap()
+        .amount
+}
+
+/// A vending machine ready to be written to: mint, funded user token account and buffer.
+pub struct VendingMachine {
+    pub mint_authority: Keypair,
+    pub mint: Pubkey,
+    pub user: Keypair,
+    pub user_token_account: Pubkey,
+    pub buffer: Pubkey,
+    pub price: u64,
+}
+
+pub async fn setup_vending_machine(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    price: u64,
+    buffer_size: usize,
+    user_tokens: u64,
+) -> VendingMachine {
+    let mint_authority = Keypair::new();
+    let mint = create_mint(context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let user = Keypair::new();
+    let user_token_account = create_token_account(context, &mint, &user.pubkey())
+        .await
+        .pubkey();
+    if user_tokens > 0 {
+        mint_tokens(
+            context,
+            &mint,
+            &user_token_account,
+            &mint_authority,
+            user_tokens,
+        )
+        .await;
+    }
+
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[initialize_vending_machine_echo_ix(
+            program_id,
+            &mint,
+            &mint_authority.pubkey(),
+            &payer,
+            price,
+            buffer_size,
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+
+    VendingMachine {
+        mint_authority,
+        mint,
+        user,
+        user_token_account,
+        buffer: vending_machine_address(program_id, &mint, price).0,
+        price,
+    }
+}
@@ -0,0 +1,180 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{CompressedEchoHeader, COMPRESSED_ECHO_HEADER_SIZE},
+    },
+    solana_program::{
+        hash::{hash, hashv},
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Levels of an 8-leaf SHA-256 Merkle tree, from the leaves up to the root.
+fn merkle_tree(leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![leaves];
+    while levels.last().unwrap().len() > 1 {
+        let level = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| hashv(&[&pair[0], &pair[1]]).to_bytes())
+            .collect();
+        levels.push(level);
+    }
+    levels
+}
+
+fn merkle_proof(levels: &[Vec<[u8; 32]>], index: usize) -> Vec<[u8; 32]> {
+    levels[..levels.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(depth, level)| level[(index >> depth) ^ 1])
+        .collect()
+}
+
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    tree_id: &Pubkey,
+) -> (Keypair, Pubkey) {
+    let authority = Keypair::new();
+    let (buffer, _) = Pubkey::find_program_address(
+        &[b"compressed", tree_id.as_ref(), authority.pubkey().as_ref()],
+        program_id,
+    );
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeCompressedEcho {
+                tree_id: *tree_id,
+                authority: authority.pubkey(),
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    (authority, buffer)
+}
+
+fn update_compressed_echo_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    new_leaf: [u8; 32],
+    proof: Vec<[u8; 32]>,
+    proof_index: u32,
+    root: [u8; 32],
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::UpdateCompressedEcho {
+            new_leaf,
+            proof,
+            proof_index,
+            root,
+        },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_update_compressed_echo_with_valid_proof() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let tree_id = Pubkey::new_unique();
+    let (authority, buffer) = setup(&mut context, &program_id, &tree_id).await;
+
+    let leaves: Vec<[u8; 32]> = (0u8..8).map(|i| hash(&[i]).to_bytes()).collect();
+    let levels = merkle_tree(leaves.clone());
+    let root = levels.last().unwrap()[0];
+
+    send(
+        &mut context,
+        &[update_compressed_echo_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            leaves[5],
+            merkle_proof(&levels, 5),
+            5,
+            root,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(data.len(), COMPRESSED_ECHO_HEADER_SIZE);
+    let header = CompressedEchoHeader::try_from_slice(&data).unwrap();
+    assert_eq!(header.tree_id, tree_id);
+    assert_eq!(header.leaf, leaves[5]);
+    assert_eq!(header.root, root);
+    assert_eq!(header.leaf_index, 5);
+}
+
+#[tokio::test]
+async fn test_update_compressed_echo_rejects_invalid_proofs() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let tree_id = Pubkey::new_unique();
+    let (authority, buffer) = setup(&mut context, &program_id, &tree_id).await;
+
+    let leaves: Vec<[u8; 32]> = (0u8..8).map(|i| hash(&[i]).to_bytes()).collect();
+    let levels = merkle_tree(leaves.clone());
+    let root = levels.last().unwrap()[0];
+    let mut tampered_proof = merkle_proof(&levels, 5);
+    tampered_proof[1][0] ^= 1;
+
+    for (leaf, proof, index) in [
+        // a leaf that isn't in the tree
+        (hash(b"other").to_bytes(), merkle_proof(&levels, 5), 5),
+        // the right proof for another index
+        (leaves[5], merkle_proof(&levels, 5), 4),
+        // an index past the 8 leaves of the tree
+        (leaves[5], merkle_proof(&levels, 5), 13),
+        (leaves[5], tampered_proof, 5),
+    ] {
+        let result = send(
+            &mut context,
+            &[update_compressed_echo_ix(
+                &program_id,
+                &buffer,
+                &authority.pubkey(),
+                leaf,
+                proof,
+                index,
+                root,
+            )],
+            &[&authority],
+        )
+        .await;
+        assert_echo_error(result, EchoError::InvalidMerkleProof);
+    }
+
+    let data = account_data(&mut context, &buffer).await;
+    let header = CompressedEchoHeader::try_from_slice(&data).unwrap();
+    assert_eq!(header.leaf, [0; 32]);
+}
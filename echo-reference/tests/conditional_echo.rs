@@ -0,0 +1,118 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::CONDITIONAL_BUFF_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::tokio,
+    solana_sdk::{
+        account::Account,
+        signature::{Keypair, Signer},
+    },
+};
+
+fn conditional_buffer_address(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    condition_account: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"conditional",
+            authority.as_ref(),
+            condition_account.as_ref(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+fn condition_account(data: Vec<u8>) -> Account {
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: Pubkey::new_unique(),
+        ..Account::default()
+    }
+}
+
+/// Initializes a buffer watching `[4..8]` of `condition` for `[1, 2, 3, 4]` and attempts a write.
+async fn write_with_condition_data(
+    condition_data: Vec<u8>,
+) -> (Vec<u8>, Result<(), solana_sdk::transport::TransportError>) {
+    let (mut program_test, program_id) = program_test();
+    let condition = Pubkey::new_unique();
+    program_test.add_account(condition, condition_account(condition_data));
+    let mut context = program_test.start_with_context().await;
+    let authority = Keypair::new();
+    let buffer = conditional_buffer_address(&program_id, &authority.pubkey(), &condition);
+    let payer = context.payer.pubkey();
+
+    send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::InitializeConditionalEcho {
+                buffer_size: CONDITIONAL_BUFF_HEADER_SIZE + 16,
+                authority: authority.pubkey(),
+                condition_offset: 4,
+                condition_value: vec![1, 2, 3, 4],
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(condition, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::ConditionalWrite { data: vec![7; 8] },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(authority.pubkey(), true),
+                AccountMeta::new_readonly(condition, false),
+            ],
+        )],
+        &[&authority],
+    )
+    .await;
+
+    (account_data(&mut context, &buffer).await, result)
+}
+
+#[tokio::test]
+async fn test_conditional_write_succeeds_when_condition_matches() {
+    let (data, result) = write_with_condition_data(vec![0, 0, 0, 0, 1, 2, 3, 4, 0]).await;
+    result.unwrap();
+    assert_eq!(
+        &data[CONDITIONAL_BUFF_HEADER_SIZE..CONDITIONAL_BUFF_HEADER_SIZE + 8],
+        &[7; 8]
+    );
+}
+
+#[tokio::test]
+async fn test_conditional_write_blocked_when_condition_differs() {
+    let (data, result) = write_with_condition_data(vec![0, 0, 0, 0, 1, 2, 3, 5, 0]).await;
+    assert_echo_error(result, EchoError::ConditionNotMet);
+    assert!(data[CONDITIONAL_BUFF_HEADER_SIZE..].iter().all(|b| *b == 0));
+}
+
+#[tokio::test]
+async fn test_conditional_write_blocked_when_condition_account_too_short() {
+    let (_, result) = write_with_condition_data(vec![0, 0, 0, 0, 1, 2]).await;
+    assert_echo_error(result, EchoError::ConditionNotMet);
+}
@@ -0,0 +1,194 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::AUTH_BUFF_HEADER_SIZE},
+    solana_program::{
+        clock::Clock,
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+const CRANK_BOUNTY_BPS: u16 = 100;
+
+/// Creates a buffer of `authority` expiring `expires_in` slots from now, or never when `None`.
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    authority: &Keypair,
+    buffer_seed: u64,
+    expires_in: Option<u64>,
+) -> (Pubkey, u64) {
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let expiry_slot = expires_in.map_or(0, |slots| clock.slot + slots);
+    let (buffer, _) = authorized_buffer_address(program_id, &authority.pubkey(), buffer_seed);
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeAuthorizedEcho {
+                buffer_seed,
+                buffer_size: AUTH_BUFF_HEADER_SIZE + 32,
+                label: String::new(),
+                permanent_delegate: None,
+                write_fee_lamports: 0,
+                expiry_slot,
+                crank_bounty_bps: CRANK_BOUNTY_BPS,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[authority],
+    )
+    .await
+    .unwrap();
+    (buffer, expiry_slot)
+}
+
+fn crank_close_expired_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    cranker: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::CrankCloseExpired,
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new(*authority, false),
+            AccountMeta::new(*cranker, true),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_crank_before_expiry_fails() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let cranker = funded_keypair(&mut context).await;
+    let (buffer, expiry_slot) = setup(&mut context, &program_id, &authority, 0, Some(50)).await;
+
+    let result = send(
+        &mut context,
+        &[crank_close_expired_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &cranker.pubkey(),
+        )],
+        &[&cranker],
+    )
+    .await;
+    assert_echo_error(result, EchoError::BufferNotExpired);
+
+    // the expiry slot itself is still within the lifetime of the buffer
+    context.warp_to_slot(expiry_slot).unwrap();
+    let result = send(
+        &mut context,
+        &[crank_close_expired_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &cranker.pubkey(),
+        )],
+        &[&cranker],
+    )
+    .await;
+    assert_echo_error(result, EchoError::BufferNotExpired);
+}
+
+#[tokio::test]
+async fn test_crank_after_expiry_splits_lamports() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let cranker = funded_keypair(&mut context).await;
+    let (buffer, expiry_slot) = setup(&mut context, &program_id, &authority, 0, Some(10)).await;
+
+    context.warp_to_slot(expiry_slot + 1).unwrap();
+
+    let buffer_lamports = context.banks_client.get_balance(buffer).await.unwrap();
+    let authority_lamports = context
+        .banks_client
+        .get_balance(authority.pubkey())
+        .await
+        .unwrap();
+    let cranker_lamports = context
+        .banks_client
+        .get_balance(cranker.pubkey())
+        .await
+        .unwrap();
+
+    send(
+        &mut context,
+        &[crank_close_expired_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &cranker.pubkey(),
+        )],
+        &[&cranker],
+    )
+    .await
+    .unwrap();
+
+    let bounty = buffer_lamports * CRANK_BOUNTY_BPS as u64 / 10_000;
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(cranker.pubkey())
+            .await
+            .unwrap(),
+        cranker_lamports + bounty
+    );
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(authority.pubkey())
+            .await
+            .unwrap(),
+        authority_lamports + buffer_lamports - bounty
+    );
+    assert!(context
+        .banks_client
+        .get_account(buffer)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_buffer_without_expiry_is_never_crankable() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let cranker = funded_keypair(&mut context).await;
+    let (buffer, _) = setup(&mut context, &program_id, &authority, 1, None).await;
+
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    context.warp_to_slot(clock.slot + 1_000).unwrap();
+
+    let result = send(
+        &mut context,
+        &[crank_close_expired_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &cranker.pubkey(),
+        )],
+        &[&cranker],
+    )
+    .await;
+    assert_echo_error(result, EchoError::BufferNotExpired);
+}
@@ -0,0 +1,258 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        processor::cross_chain_echo::wormhole,
+        state::{CrossChainHeader, CROSS_CHAIN_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTest, ProgramTestContext},
+    solana_sdk::{account::Account, signature::Signer},
+};
+
+const EMITTER_CHAIN: u16 = 2;
+const EMITTER_ADDRESS: [u8; 32] = [7; 32];
+
+/// The data of a posted VAA account of the Wormhole bridge carrying `payload`.
+fn posted_vaa_data(
+    emitter_chain: u16,
+    emitter_address: [u8; 32],
+    sequence: u64,
+    payload: &[u8],
+) -> Vec<u8> {
+    let mut data = wormhole::POSTED_VAA_MAGIC.to_vec();
+    data.push(1); // vaa_version
+    data.push(1); // consistency_level
+    data.extend_from_slice(&1_000u32.to_le_bytes()); // vaa_time
+    data.extend_from_slice(Pubkey::new_unique().as_ref()); // vaa_signature_account
+    data.extend_from_slice(&1_001u32.to_le_bytes()); // submission_time
+    data.extend_from_slice(&3u32.to_le_bytes()); // nonce
+    assert_eq!(data.len(), wormhole::SEQUENCE_OFFSET);
+    data.extend_from_slice(&sequence.to_le_bytes());
+    assert_eq!(data.len(), wormhole::EMITTER_CHAIN_OFFSET);
+    data.extend_from_slice(&emitter_chain.to_le_bytes());
+    data.extend_from_slice(&emitter_address);
+    data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    data.extend_from_slice(payload);
+    data
+}
+
+fn add_posted_vaa(program_test: &mut ProgramTest, owner: Pubkey, data: Vec<u8>) -> Pubkey {
+    let posted_vaa = Pubkey::new_unique();
+    program_test.add_account(
+        posted_vaa,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner,
+            ..Account::default()
+        },
+    );
+    posted_vaa
+}
+
+async fn initialize_cross_chain_echo(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+) -> Pubkey {
+    let buffer = Pubkey::find_program_address(
+        &[
+            b"cross_chain",
+            &EMITTER_CHAIN.to_le_bytes(),
+            EMITTER_ADDRESS.as_ref(),
+        ],
+        program_id,
+    )
+    .0;
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeCrossChainEcho {
+                buffer_size: CROSS_CHAIN_HEADER_SIZE + 8,
+                emitter_chain: EMITTER_CHAIN,
+                emitter_address: EMITTER_ADDRESS,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    buffer
+}
+
+fn cross_chain_write_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    posted_vaa: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::CrossChainWrite { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*posted_vaa, false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_cross_chain_write_copies_vaa_payload() {
+    let (mut program_test, program_id) = program_test();
+    let posted_vaa = add_posted_vaa(
+        &mut program_test,
+        wormhole::id(),
+        posted_vaa_data(EMITTER_CHAIN, EMITTER_ADDRESS, 42, &[9, 8, 7]),
+    );
+    let mut context = program_test.start_with_context().await;
+    let buffer = initialize_cross_chain_echo(&mut context, &program_id).await;
+
+    send(
+        &mut context,
+        &[cross_chain_write_ix(
+            &program_id,
+            &buffer,
+            &posted_vaa,
+            vec![9, 8, 7],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[CROSS_CHAIN_HEADER_SIZE..], &[9, 8, 7, 0, 0, 0, 0, 0]);
+
+    // the data must be the payload of the VAA
+    let result = send(
+        &mut context,
+        &[cross_chain_write_ix(
+            &program_id,
+            &buffer,
+            &posted_vaa,
+            vec![1, 2, 3],
+        )],
+        &[],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidVAA);
+}
+
+#[tokio::test]
+async fn test_cross_chain_write_rejects_other_emitters() {
+    let (mut program_test, program_id) = program_test();
+    let other_chain = add_posted_vaa(
+        &mut program_test,
+        wormhole::id(),
+        posted_vaa_data(EMITTER_CHAIN + 1, EMITTER_ADDRESS, 42, &[9; 3]),
+    );
+    let other_address = add_posted_vaa(
+        &mut program_test,
+        wormhole::id(),
+        posted_vaa_data(EMITTER_CHAIN, [8; 32], 42, &[9; 3]),
+    );
+    // the same bytes in an account the bridge doesn't own
+    let forged = add_posted_vaa(
+        &mut program_test,
+        Pubkey::new_unique(),
+        posted_vaa_data(EMITTER_CHAIN, EMITTER_ADDRESS, 42, &[9; 3]),
+    );
+    let mut context = program_test.start_with_context().await;
+    let buffer = initialize_cross_chain_echo(&mut context, &program_id).await;
+
+    for posted_vaa in [other_chain, other_address, forged] {
+        let result = send(
+            &mut context,
+            &[cross_chain_write_ix(
+                &program_id,
+                &buffer,
+                &posted_vaa,
+                vec![9; 3],
+            )],
+            &[],
+        )
+        .await;
+        assert_echo_error(result, EchoError::InvalidVAA);
+    }
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[CROSS_CHAIN_HEADER_SIZE..], &[0; 8]);
+}
+
+#[tokio::test]
+async fn test_cross_chain_write_rejects_replayed_vaas() {
+    let (mut program_test, program_id) = program_test();
+    let first = add_posted_vaa(
+        &mut program_test,
+        wormhole::id(),
+        posted_vaa_data(EMITTER_CHAIN, EMITTER_ADDRESS, 0, &[1; 3]),
+    );
+    let older = add_posted_vaa(
+        &mut program_test,
+        wormhole::id(),
+        posted_vaa_data(EMITTER_CHAIN, EMITTER_ADDRESS, 3, &[3; 3]),
+    );
+    let latest = add_posted_vaa(
+        &mut program_test,
+        wormhole::id(),
+        posted_vaa_data(EMITTER_CHAIN, EMITTER_ADDRESS, 5, &[5; 3]),
+    );
+    let mut context = program_test.start_with_context().await;
+    let buffer = initialize_cross_chain_echo(&mut context, &program_id).await;
+
+    // the first message of an emitter has sequence 0
+    for (posted_vaa, payload) in [(first, vec![1; 3]), (latest, vec![5; 3])] {
+        send(
+            &mut context,
+            &[cross_chain_write_ix(
+                &program_id,
+                &buffer,
+                &posted_vaa,
+                payload,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    // neither an older message nor the last one can be written again, past a new blockhash so the transaction of the
+    // last one isn't just deduplicated
+    context.warp_to_slot(100).unwrap();
+    for (posted_vaa, payload) in [(older, vec![3; 3]), (latest, vec![5; 3])] {
+        let result = send(
+            &mut context,
+            &[cross_chain_write_ix(
+                &program_id,
+                &buffer,
+                &posted_vaa,
+                payload,
+            )],
+            &[],
+        )
+        .await;
+        assert_echo_error(result, EchoError::InvalidVAA);
+    }
+
+    let data = account_data(&mut context, &buffer).await;
+    let buffer_header = CrossChainHeader::try_from_slice(&data[..CROSS_CHAIN_HEADER_SIZE]).unwrap();
+    assert_eq!(buffer_header.last_sequence, 5);
+    assert_eq!(&data[CROSS_CHAIN_HEADER_SIZE..], &[5, 5, 5, 0, 0, 0, 0, 0]);
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{instruction::EchoInstruction, state::DECAY_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        clock::Clock,
+        signature::{Keypair, Signer},
+    },
+};
+
+/// Creates a decay buffer of 8 bytes and fills it with 200s.
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    decay_rate: u8,
+) -> (Keypair, Pubkey) {
+    let authority = Keypair::new();
+    let buffer =
+        Pubkey::find_program_address(&[b"decay", authority.pubkey().as_ref()], program_id).0;
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[
+            Instruction::new_with_borsh(
+                *program_id,
+                &EchoInstruction::InitializeDecayEcho {
+                    buffer_size: DECAY_HEADER_SIZE + 8,
+                    decay_rate_per_1000_slots: decay_rate,
+                    authority: authority.pubkey(),
+                },
+                vec![
+                    AccountMeta::new(buffer, false),
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            ),
+            Instruction::new_with_borsh(
+                *program_id,
+                &EchoInstruction::WriteDecayEcho { data: vec![200; 8] },
+                vec![
+                    AccountMeta::new(buffer, false),
+                    AccountMeta::new_readonly(authority.pubkey(), true),
+                ],
+            ),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    (authority, buffer)
+}
+
+/// Warps `slots` ahead and refreshes the decay of `buffer`.
+async fn refresh_after(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    buffer: &Pubkey,
+    slots: u64,
+) {
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    context.warp_to_slot(clock.slot + slots).unwrap();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::RefreshDecay,
+            vec![
+                AccountMeta::new(*buffer, false),
+                AccountMeta::new_readonly(*authority, false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_high_decay_rate_drives_bytes_to_zero() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id, 255).await;
+
+    refresh_after(&mut context, &program_id, &authority.pubkey(), &buffer, 100).await;
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[DECAY_HEADER_SIZE..], &[0; 8]);
+}
+
+#[tokio::test]
+async fn test_zero_decay_rate_leaves_bytes_unchanged() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id, 0).await;
+
+    refresh_after(&mut context, &program_id, &authority.pubkey(), &buffer, 100).await;
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[DECAY_HEADER_SIZE..], &[200; 8]);
+}
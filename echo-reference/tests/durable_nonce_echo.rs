@@ -0,0 +1,196 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::DURABLE_NONCE_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        nonce::state::{State, Versions},
+        pubkey::Pubkey,
+        system_instruction, system_program, sysvar,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        account_utils::StateMut,
+        clock::Clock,
+        signature::{Keypair, Signer},
+    },
+};
+
+/// Creates a nonce account of `authority` and a durable nonce buffer bound to it.
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> (Keypair, Pubkey, Pubkey) {
+    let authority = Keypair::new();
+    let nonce_account = Keypair::new();
+    let buffer =
+        Pubkey::find_program_address(&[b"durable_nonce", authority.pubkey().as_ref()], program_id)
+            .0;
+    let payer = context.payer.pubkey();
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    let mut instructions = system_instruction::create_nonce_account(
+        &payer,
+        &nonce_account.pubkey(),
+        &authority.pubkey(),
+        rent.minimum_balance(State::size()),
+    );
+    instructions.push(Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::InitializeDurableNonceEcho {
+            buffer_size: DURABLE_NONCE_HEADER_SIZE + 8,
+            authority: authority.pubkey(),
+            nonce_account: nonce_account.pubkey(),
+        },
+        vec![
+            AccountMeta::new(buffer, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    ));
+    send(context, &instructions, &[&nonce_account])
+        .await
+        .unwrap();
+
+    // the nonce can only be advanced once the blockhash it stores is no longer the latest one
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    context.warp_to_slot(clock.slot + 2).unwrap();
+
+    (authority, nonce_account.pubkey(), buffer)
+}
+
+async fn nonce_value(context: &mut ProgramTestContext, nonce_account: &Pubkey) -> [u8; 32] {
+    let account = context
+        .banks_client
+        .get_account(*nonce_account)
+        .await
+        .unwrap()
+        .expect("account not found");
+    let versions: Versions = account.state().unwrap();
+    match versions.convert_to_current() {
+        State::Initialized(data) => data.blockhash.to_bytes(),
+        State::Uninitialized => panic!("nonce account is not initialized"),
+    }
+}
+
+fn durable_nonce_write_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    nonce_account: &Pubkey,
+    data: Vec<u8>,
+    nonce_value: [u8; 32],
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::DurableNonceWrite { data, nonce_value },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*nonce_account, false),
+            #[allow(deprecated)]
+            AccountMeta::new_readonly(sysvar::recent_blockhashes::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_durable_nonce_write_advances_nonce() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, nonce_account, buffer) = setup(&mut context, &program_id).await;
+
+    let nonce = nonce_value(&mut context, &nonce_account).await;
+    send(
+        &mut context,
+        &[durable_nonce_write_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &nonce_account,
+            vec![7; 4],
+            nonce,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[DURABLE_NONCE_HEADER_SIZE..],
+        &[7, 7, 7, 7, 0, 0, 0, 0]
+    );
+    assert_ne!(nonce_value(&mut context, &nonce_account).await, nonce);
+
+    // the signed nonce value was used up by the first write
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    context.warp_to_slot(clock.slot + 2).unwrap();
+    let result = send(
+        &mut context,
+        &[durable_nonce_write_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &nonce_account,
+            vec![9; 4],
+            nonce,
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidNonce);
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[DURABLE_NONCE_HEADER_SIZE..],
+        &[7, 7, 7, 7, 0, 0, 0, 0]
+    );
+
+    // the advanced value is accepted
+    let nonce = nonce_value(&mut context, &nonce_account).await;
+    send(
+        &mut context,
+        &[durable_nonce_write_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &nonce_account,
+            vec![9; 4],
+            nonce,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[DURABLE_NONCE_HEADER_SIZE..],
+        &[9, 9, 9, 9, 0, 0, 0, 0]
+    );
+}
+
+#[tokio::test]
+async fn test_durable_nonce_write_rejects_other_nonce_account() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, _, buffer) = setup(&mut context, &program_id).await;
+
+    // an initialized nonce account that the buffer is not bound to
+    let (_, other_nonce_account, _) = setup(&mut context, &program_id).await;
+    let nonce = nonce_value(&mut context, &other_nonce_account).await;
+    let result = send(
+        &mut context,
+        &[durable_nonce_write_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &other_nonce_account,
+            vec![7; 4],
+            nonce,
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+}
@@ -0,0 +1,148 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{instruction::EchoInstruction, state::EPOCH_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        clock::Clock,
+        signature::{Keypair, Signer},
+    },
+};
+
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    buffer_size: usize,
+) -> (Keypair, Pubkey) {
+    let authority = Keypair::new();
+    let buffer =
+        Pubkey::find_program_address(&[b"epoch", authority.pubkey().as_ref()], program_id).0;
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeEpochEcho {
+                buffer_size,
+                authority: authority.pubkey(),
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    (authority, buffer)
+}
+
+fn epoch_echo_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::EpochEcho { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// Moves the bank to the first slot of the next epoch.
+async fn warp_to_next_epoch(context: &mut ProgramTestContext) {
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let next_epoch_slot = context
+        .genesis_config()
+        .epoch_schedule
+        .get_first_slot_in_epoch(clock.epoch + 1);
+    context.warp_to_slot(next_epoch_slot).unwrap();
+}
+
+#[tokio::test]
+async fn test_epoch_echo_keeps_data_within_an_epoch() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id, EPOCH_HEADER_SIZE + 8).await;
+
+    send(
+        &mut context,
+        &[
+            epoch_echo_ix(&program_id, &buffer, &authority.pubkey(), vec![1; 8]),
+            epoch_echo_ix(&program_id, &buffer, &authority.pubkey(), vec![2; 2]),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[EPOCH_HEADER_SIZE..], &[2, 2, 1, 1, 1, 1, 1, 1]);
+}
+
+#[tokio::test]
+async fn test_epoch_echo_resets_stale_data_on_next_write() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id, EPOCH_HEADER_SIZE + 8).await;
+
+    send(
+        &mut context,
+        &[epoch_echo_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            vec![1; 8],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    warp_to_next_epoch(&mut context).await;
+
+    // reading a stale buffer succeeds without touching it
+    let read_ix = Instruction::new_with_borsh(
+        program_id,
+        &EchoInstruction::ReadEpochEcho,
+        vec![
+            AccountMeta::new_readonly(buffer, false),
+            AccountMeta::new_readonly(authority.pubkey(), false),
+        ],
+    );
+    send(&mut context, &[read_ix], &[]).await.unwrap();
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[EPOCH_HEADER_SIZE..], &[1; 8]);
+
+    send(
+        &mut context,
+        &[epoch_echo_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            vec![2; 2],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[..8], &clock.epoch.to_le_bytes());
+    assert_eq!(&data[EPOCH_HEADER_SIZE..], &[2, 2, 0, 0, 0, 0, 0, 0]);
+}
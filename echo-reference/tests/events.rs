@@ -0,0 +1,82 @@
+#![cfg(all(feature = "test-bpf", feature = "client"))]
+
+mod common;
+
+use {
+    common::*,
+    echo::{
+        events::{parse_events, EchoEvent, WriteEvent},
+        state::AUTH_BUFF_HEADER_SIZE,
+    },
+    solana_program_test::tokio,
+    solana_sdk::signature::Signer,
+};
+
+#[tokio::test]
+async fn test_authorized_echo_emits_write_event() {
+    let (program_test, program_id) = program_test_with_logs();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let (buffer, _) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+
+    send(
+        &mut context,
+        &[initialize_authorized_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            0,
+            AUTH_BUFF_HEADER_SIZE + 64,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let (result, logs) = send_with_logs(
+        &mut context,
+        &program_id,
+        &[authorized_echo_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            vec![1; 10],
+        )],
+        &[&authority],
+    )
+    .await;
+    result.unwrap();
+
+    let events = parse_events(&logs);
+    assert_eq!(events.len(), 1);
+    match &events[0] {
+        EchoEvent::Write(WriteEvent {
+            buffer: event_buffer,
+            authority: event_authority,
+            len,
+            ..
+        }) => {
+            assert_eq!(*event_buffer, buffer);
+            assert_eq!(*event_authority, authority.pubkey());
+            assert_eq!(*len, 10);
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_events_skips_unrelated_logs() {
+    let event = EchoEvent::Write(WriteEvent {
+        buffer: Default::default(),
+        authority: Default::default(),
+        len: 3,
+        slot: 42,
+        sequence: 7,
+    });
+    let encoded = base64::encode(borsh::BorshSerialize::try_to_vec(&event).unwrap());
+    let logs = vec![
+        "Program log: Instruction: AuthorizedEcho".to_string(),
+        format!("Program log: ECHO_EVENT:{}", encoded),
+        "Program log: ECHO_EVENT:not-base64!".to_string(),
+    ];
+    assert_eq!(parse_events(&logs), vec![event]);
+}
@@ -0,0 +1,279 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{VendingMachineBufferHeader, VENDING_MACHINE_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_instruction, system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        signature::{Keypair, Signer},
+        transport::TransportError,
+    },
+};
+
+const PRICE: u64 = 5;
+const EXPIRY_SLOT: u64 = 100;
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> VendingMachine {
+    let mint_authority = Keypair::new();
+    let mint = create_mint(context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let user = Keypair::new();
+    let user_token_account = create_token_account(context, &mint, &user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(context, &mint, &user_token_account, &mint_authority, 20).await;
+
+    let (buffer, _) = vending_machine_address(program_id, &mint, PRICE);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[initialize_expiring_ix(
+            program_id,
+            &buffer,
+            &mint,
+            &mint,
+            &mint_authority.pubkey(),
+            &payer,
+            EXPIRY_SLOT,
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+
+    VendingMachine {
+        mint_authority,
+        mint,
+        user,
+        user_token_account,
+        buffer,
+        price: PRICE,
+    }
+}
+
+fn initialize_expiring_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    mint: &Pubkey,
+    mint_account: &Pubkey,
+    mint_authority: &Pubkey,
+    payer: &Pubkey,
+    expiry_slot: u64,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::InitializeExpiringVendingMachine {
+            buffer_size: VENDING_MACHINE_BUFF_HEADER_SIZE + 16,
+            price: PRICE,
+            mint: *mint,
+            expiry_slot,
+        },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*mint_account, false),
+            AccountMeta::new_readonly(*mint_authority, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+fn claim_ix(program_id: &Pubkey, buffer: &Pubkey, destination: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::ClaimExpiredVendingMachine {
+            destination: *destination,
+        },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new(*destination, false),
+        ],
+    )
+}
+
+async fn write(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    vm: &VendingMachine,
+    data: Vec<u8>,
+) -> Result<(), TransportError> {
+    send(
+        context,
+        &[vending_machine_echo_ix(
+            program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            &vm.user_token_account,
+            &vm.mint,
+            data,
+        )],
+        &[&vm.user],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_expiring_vending_machine_writes_until_expiry() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup(&mut context, &program_id).await;
+
+    let data = account_data(&mut context, &vm.buffer).await;
+    let buffer_header =
+        VendingMachineBufferHeader::try_from_slice(&data[..VENDING_MACHINE_BUFF_HEADER_SIZE])
+            .unwrap();
+    assert_eq!(buffer_header.expiry_slot, EXPIRY_SLOT);
+
+    context.warp_to_slot(EXPIRY_SLOT - 1).unwrap();
+    write(&mut context, &program_id, &vm, vec![1; 4])
+        .await
+        .unwrap();
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        20 - PRICE
+    );
+
+    // nothing is paid once expired
+    context.warp_to_slot(EXPIRY_SLOT).unwrap();
+    let result = write(&mut context, &program_id, &vm, vec![2; 4]).await;
+    assert_echo_error(result, EchoError::BufferExpired);
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        20 - PRICE
+    );
+}
+
+#[tokio::test]
+async fn test_claim_expired_vending_machine() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup(&mut context, &program_id).await;
+    write(&mut context, &program_id, &vm, vec![1; 4])
+        .await
+        .unwrap();
+
+    // anyone can claim, but not before the expiry
+    let destination = Pubkey::new_unique();
+    let result = send(
+        &mut context,
+        &[claim_ix(&program_id, &vm.buffer, &destination)],
+        &[],
+    )
+    .await;
+    assert_echo_error(result, EchoError::BufferNotExpired);
+
+    context.warp_to_slot(EXPIRY_SLOT).unwrap();
+    let lamports = context.banks_client.get_balance(vm.buffer).await.unwrap();
+    send(
+        &mut context,
+        &[claim_ix(&program_id, &vm.buffer, &destination)],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        context.banks_client.get_balance(destination).await.unwrap(),
+        lamports
+    );
+    assert!(context
+        .banks_client
+        .get_account(vm.buffer)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_claim_zeroes_buffer_data() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup(&mut context, &program_id).await;
+    write(&mut context, &program_id, &vm, vec![1; 4])
+        .await
+        .unwrap();
+
+    // funding the buffer again in the same transaction keeps it alive, showing its data
+    context.warp_to_slot(EXPIRY_SLOT).unwrap();
+    let destination = Pubkey::new_unique();
+    let payer = context.payer.pubkey();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    send(
+        &mut context,
+        &[
+            claim_ix(&program_id, &vm.buffer, &destination),
+            system_instruction::transfer(
+                &payer,
+                &vm.buffer,
+                rent.minimum_balance(VENDING_MACHINE_BUFF_HEADER_SIZE + 16),
+            ),
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &vm.buffer).await;
+    assert!(data.iter().all(|byte| *byte == 0));
+}
+
+#[tokio::test]
+async fn test_initialize_expiring_vending_machine_rejects_invalid_input() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let mint_authority = Keypair::new();
+    let mint = create_mint(&mut context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let (buffer, _) = vending_machine_address(&program_id, &mint, PRICE);
+    let payer = context.payer.pubkey();
+
+    // the mint in the instruction must be the mint account
+    let other_mint = Pubkey::new_unique();
+    let result = send(
+        &mut context,
+        &[initialize_expiring_ix(
+            &program_id,
+            &buffer,
+            &other_mint,
+            &mint,
+            &mint_authority.pubkey(),
+            &payer,
+            EXPIRY_SLOT,
+        )],
+        &[&mint_authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::MintMismatch);
+
+    // already expired
+    context.warp_to_slot(EXPIRY_SLOT).unwrap();
+    let result = send(
+        &mut context,
+        &[initialize_expiring_ix(
+            &program_id,
+            &buffer,
+            &mint,
+            &mint,
+            &mint_authority.pubkey(),
+            &payer,
+            EXPIRY_SLOT,
+        )],
+        &[&mint_authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+}
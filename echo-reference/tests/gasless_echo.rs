@@ -0,0 +1,350 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        processor::gasless_echo::gasless_message,
+        state::{GaslessHeader, GASLESS_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_instruction, system_program, sysvar,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        ed25519_instruction::new_ed25519_instruction,
+        signature::{Keypair, Signer},
+    },
+};
+
+const BUFFER_SIZE: usize = GASLESS_HEADER_SIZE + 16;
+
+struct Gasless {
+    authority: Keypair,
+    relayer: Keypair,
+    buffer: Pubkey,
+}
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> Gasless {
+    let authority = Keypair::new();
+    let relayer = Keypair::new();
+    let (buffer, _) =
+        Pubkey::find_program_address(&[b"gasless", authority.pubkey().as_ref()], program_id);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeGaslessEcho {
+                buffer_size: BUFFER_SIZE,
+                authority: authority.pubkey(),
+                relayer: relayer.pubkey(),
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    Gasless {
+        authority,
+        relayer,
+        buffer,
+    }
+}
+
+/// The Ed25519 verification of `authority` signing the write, followed by the relayed write itself.
+fn relayed_write_ixs(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Keypair,
+    relayer: &Pubkey,
+    nonce: u64,
+    data: Vec<u8>,
+) -> [Instruction; 2] {
+    let message = gasless_message(buffer, nonce, &data);
+    let user_signature = authority.sign_message(&message).into();
+    let signer = ed25519_dalek::Keypair::from_bytes(&authority.to_bytes()).unwrap();
+    [
+        new_ed25519_instruction(&signer, &message),
+        gasless_echo_ix(program_id, buffer, relayer, data, user_signature),
+    ]
+}
+
+fn gasless_echo_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    relayer: &Pubkey,
+    data: Vec<u8>,
+    user_signature: [u8; 64],
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::GaslessEcho {
+            data,
+            user_signature,
+        },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*relayer, true),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        ],
+    )
+}
+
+async fn header(context: &mut ProgramTestContext, buffer: &Pubkey) -> GaslessHeader {
+    let data = account_data(context, buffer).await;
+    GaslessHeader::try_from_slice(&data[..GASLESS_HEADER_SIZE]).unwrap()
+}
+
+#[tokio::test]
+async fn test_relayed_write_signed_by_authority() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let gasless = setup(&mut context, &program_id).await;
+
+    // the authority never signs the transaction, only the relayer does
+    send(
+        &mut context,
+        &relayed_write_ixs(
+            &program_id,
+            &gasless.buffer,
+            &gasless.authority,
+            &gasless.relayer.pubkey(),
+            0,
+            vec![1; 20],
+        ),
+        &[&gasless.relayer],
+    )
+    .await
+    .unwrap();
+    send(
+        &mut context,
+        &relayed_write_ixs(
+            &program_id,
+            &gasless.buffer,
+            &gasless.authority,
+            &gasless.relayer.pubkey(),
+            1,
+            vec![2; 4],
+        ),
+        &[&gasless.relayer],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &gasless.buffer).await;
+    assert_eq!(
+        &data[GASLESS_HEADER_SIZE..],
+        &[2, 2, 2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(header(&mut context, &gasless.buffer).await.nonce, 2);
+}
+
+#[tokio::test]
+async fn test_relayed_write_rejects_other_relayer() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let gasless = setup(&mut context, &program_id).await;
+    let other_relayer = Keypair::new();
+
+    let result = send(
+        &mut context,
+        &relayed_write_ixs(
+            &program_id,
+            &gasless.buffer,
+            &gasless.authority,
+            &other_relayer.pubkey(),
+            0,
+            vec![1; 4],
+        ),
+        &[&other_relayer],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+}
+
+#[tokio::test]
+async fn test_relayed_write_rejects_bad_signatures() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let gasless = setup(&mut context, &program_id).await;
+    let relayer = gasless.relayer.pubkey();
+
+    // signed by someone other than the authority
+    let result = send(
+        &mut context,
+        &relayed_write_ixs(
+            &program_id,
+            &gasless.buffer,
+            &Keypair::new(),
+            &relayer,
+            0,
+            vec![1; 4],
+        ),
+        &[&gasless.relayer],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidSignature);
+
+    // the relayer swaps the signed data
+    let [verify_ix, _] = relayed_write_ixs(
+        &program_id,
+        &gasless.buffer,
+        &gasless.authority,
+        &relayer,
+        0,
+        vec![1; 4],
+    );
+    let message = gasless_message(&gasless.buffer, 0, &[1; 4]);
+    let user_signature = gasless.authority.sign_message(&message).into();
+    let result = send(
+        &mut context,
+        &[
+            verify_ix,
+            gasless_echo_ix(
+                &program_id,
+                &gasless.buffer,
+                &relayer,
+                vec![9; 4],
+                user_signature,
+            ),
+        ],
+        &[&gasless.relayer],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidSignature);
+
+    // no Ed25519 verification at all
+    let result = send(
+        &mut context,
+        &[gasless_echo_ix(
+            &program_id,
+            &gasless.buffer,
+            &relayer,
+            vec![1; 4],
+            user_signature,
+        )],
+        &[&gasless.relayer],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidSignature);
+
+    // a signature for the current nonce is accepted once, replaying it fails
+    send(
+        &mut context,
+        &relayed_write_ixs(
+            &program_id,
+            &gasless.buffer,
+            &gasless.authority,
+            &relayer,
+            0,
+            vec![1; 4],
+        ),
+        &[&gasless.relayer],
+    )
+    .await
+    .unwrap();
+    // a transfer first keeps the runtime from deduplicating the replayed transaction
+    let [verify_ix, write_ix] = relayed_write_ixs(
+        &program_id,
+        &gasless.buffer,
+        &gasless.authority,
+        &relayer,
+        0,
+        vec![1; 4],
+    );
+    let payer = context.payer.pubkey();
+    let result = send(
+        &mut context,
+        &[
+            system_instruction::transfer(&payer, &relayer, 1),
+            verify_ix,
+            write_ix,
+        ],
+        &[&gasless.relayer],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidSignature);
+}
+
+#[tokio::test]
+async fn test_update_relayer() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let gasless = setup(&mut context, &program_id).await;
+    let new_relayer = Keypair::new();
+
+    let update_relayer_ix = |authority: &Pubkey| {
+        Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::UpdateRelayer {
+                new_relayer: new_relayer.pubkey(),
+            },
+            vec![
+                AccountMeta::new(gasless.buffer, false),
+                AccountMeta::new_readonly(*authority, true),
+            ],
+        )
+    };
+
+    let result = send(
+        &mut context,
+        &[update_relayer_ix(&gasless.relayer.pubkey())],
+        &[&gasless.relayer],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    send(
+        &mut context,
+        &[update_relayer_ix(&gasless.authority.pubkey())],
+        &[&gasless.authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        header(&mut context, &gasless.buffer).await.relayer,
+        new_relayer.pubkey()
+    );
+
+    let result = send(
+        &mut context,
+        &relayed_write_ixs(
+            &program_id,
+            &gasless.buffer,
+            &gasless.authority,
+            &gasless.relayer.pubkey(),
+            0,
+            vec![1; 4],
+        ),
+        &[&gasless.relayer],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    send(
+        &mut context,
+        &relayed_write_ixs(
+            &program_id,
+            &gasless.buffer,
+            &gasless.authority,
+            &new_relayer.pubkey(),
+            0,
+            vec![1; 4],
+        ),
+        &[&new_relayer],
+    )
+    .await
+    .unwrap();
+}
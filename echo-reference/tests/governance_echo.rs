@@ -0,0 +1,243 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::GOVERNANCE_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+struct Governance {
+    mint_authority: Keypair,
+    mint: Pubkey,
+    holder: Keypair,
+    holder_token_account: Pubkey,
+    buffer: Pubkey,
+}
+
+/// Creates a governance buffer requiring `min_tokens` and a holder with `holder_tokens` of its mint.
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    min_tokens: u64,
+    holder_tokens: u64,
+) -> Governance {
+    let mint_authority = Keypair::new();
+    let mint = create_mint(context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let holder = Keypair::new();
+    let holder_token_account = create_token_account(context, &mint, &holder.pubkey())
+        .await
+        .pubkey();
+    if holder_tokens > 0 {
+        mint_tokens(
+            context,
+            &mint,
+            &holder_token_account,
+            &mint_authority,
+            holder_tokens,
+        )
+        .await;
+    }
+
+    let buffer = Pubkey::find_program_address(&[b"governance", mint.as_ref()], program_id).0;
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeGovernanceEcho {
+                buffer_size: GOVERNANCE_HEADER_SIZE + 8,
+                governance_mint: mint,
+                min_tokens,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    Governance {
+        mint_authority,
+        mint,
+        holder,
+        holder_token_account,
+        buffer,
+    }
+}
+
+fn governance_write_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    holder: &Pubkey,
+    holder_token_account: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::GovernanceWrite { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*holder, true),
+            AccountMeta::new_readonly(*holder_token_account, false),
+        ],
+    )
+}
+
+fn update_governance_threshold_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    mint: &Pubkey,
+    mint_authority: &Pubkey,
+    new_min: u64,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::UpdateGovernanceThreshold { new_min },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*mint_authority, true),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_governance_write_keeps_tokens() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let governance = setup(&mut context, &program_id, 10, 10).await;
+
+    for data in [vec![7; 4], vec![9; 2]] {
+        send(
+            &mut context,
+            &[governance_write_ix(
+                &program_id,
+                &governance.buffer,
+                &governance.holder.pubkey(),
+                &governance.holder_token_account,
+                data,
+            )],
+            &[&governance.holder],
+        )
+        .await
+        .unwrap();
+    }
+
+    let data = account_data(&mut context, &governance.buffer).await;
+    assert_eq!(&data[GOVERNANCE_HEADER_SIZE..], &[9, 9, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(
+        token_balance(&mut context, &governance.holder_token_account).await,
+        10
+    );
+}
+
+#[tokio::test]
+async fn test_governance_write_rejects_insufficient_balance() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let governance = setup(&mut context, &program_id, 10, 9).await;
+
+    let result = send(
+        &mut context,
+        &[governance_write_ix(
+            &program_id,
+            &governance.buffer,
+            &governance.holder.pubkey(),
+            &governance.holder_token_account,
+            vec![7; 4],
+        )],
+        &[&governance.holder],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InsufficientFunds);
+
+    // the token account of a large holder can't be borrowed by someone else
+    let other = Keypair::new();
+    mint_tokens(
+        &mut context,
+        &governance.mint,
+        &governance.holder_token_account,
+        &governance.mint_authority,
+        1,
+    )
+    .await;
+    let result = send(
+        &mut context,
+        &[governance_write_ix(
+            &program_id,
+            &governance.buffer,
+            &other.pubkey(),
+            &governance.holder_token_account,
+            vec![7; 4],
+        )],
+        &[&other],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+}
+
+#[tokio::test]
+async fn test_update_governance_threshold() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let governance = setup(&mut context, &program_id, 10, 5).await;
+
+    // only the mint authority can change the threshold
+    let result = send(
+        &mut context,
+        &[update_governance_threshold_ix(
+            &program_id,
+            &governance.buffer,
+            &governance.mint,
+            &governance.holder.pubkey(),
+            5,
+        )],
+        &[&governance.holder],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidMintAuthority);
+
+    send(
+        &mut context,
+        &[update_governance_threshold_ix(
+            &program_id,
+            &governance.buffer,
+            &governance.mint,
+            &governance.mint_authority.pubkey(),
+            5,
+        )],
+        &[&governance.mint_authority],
+    )
+    .await
+    .unwrap();
+
+    send(
+        &mut context,
+        &[governance_write_ix(
+            &program_id,
+            &governance.buffer,
+            &governance.holder.pubkey(),
+            &governance.holder_token_account,
+            vec![7; 4],
+        )],
+        &[&governance.holder],
+    )
+    .await
+    .unwrap();
+    let data = account_data(&mut context, &governance.buffer).await;
+    assert_eq!(&data[GOVERNANCE_HEADER_SIZE..], &[7, 7, 7, 7, 0, 0, 0, 0]);
+}
@@ -0,0 +1,212 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{GradualReleaseHeader, GRADUAL_RELEASE_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        signature::{Keypair, Signer},
+        transport::TransportError,
+    },
+};
+
+const TOTAL_SLOTS: u64 = 40;
+const START_SLOT: u64 = 100;
+
+fn release_data() -> Vec<u8> {
+    (1..=40).collect()
+}
+
+async fn initialize_gradual_release_echo(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    authority: &Pubkey,
+) -> Pubkey {
+    let buffer =
+        Pubkey::find_program_address(&[b"gradual_release", authority.as_ref()], program_id).0;
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeGradualReleaseEcho {
+                total_slots: TOTAL_SLOTS,
+                data: release_data(),
+                authority: *authority,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    buffer
+}
+
+async fn reveal(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Keypair,
+    current_slot: u64,
+) -> Result<(), TransportError> {
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::RevealGradual { current_slot },
+            vec![
+                AccountMeta::new(*buffer, false),
+                AccountMeta::new_readonly(authority.pubkey(), true),
+            ],
+        )],
+        &[authority],
+    )
+    .await
+}
+
+/// The header and the revealed region of the buffer.
+async fn revealed(
+    context: &mut ProgramTestContext,
+    buffer: &Pubkey,
+) -> (GradualReleaseHeader, Vec<u8>) {
+    let data = account_data(context, buffer).await;
+    let buffer_header =
+        GradualReleaseHeader::try_from_slice(&data[..GRADUAL_RELEASE_HEADER_SIZE]).unwrap();
+    let revealed =
+        data[GRADUAL_RELEASE_HEADER_SIZE + buffer_header.total_bytes as usize..].to_vec();
+    (buffer_header, revealed)
+}
+
+#[tokio::test]
+async fn test_gradual_release_reveals_linearly() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = Keypair::new();
+
+    context.warp_to_slot(START_SLOT).unwrap();
+    let buffer =
+        initialize_gradual_release_echo(&mut context, &program_id, &authority.pubkey()).await;
+
+    let data = account_data(&mut context, &buffer).await;
+    let (buffer_header, revealed_data) = revealed(&mut context, &buffer).await;
+    assert_eq!(buffer_header.reveal_start_slot, START_SLOT);
+    assert_eq!(buffer_header.reveal_end_slot, START_SLOT + TOTAL_SLOTS);
+    assert_eq!(buffer_header.total_bytes, 40);
+    assert_eq!(revealed_data, vec![0; 40]);
+    // the stored data is masked
+    assert_ne!(
+        &data[GRADUAL_RELEASE_HEADER_SIZE..GRADUAL_RELEASE_HEADER_SIZE + 40],
+        release_data().as_slice()
+    );
+
+    // (slot, revealed bytes): nothing at the start, a quarter, half, then everything from the end on
+    for (slot, bytes) in [
+        (START_SLOT, 0),
+        (START_SLOT + 10, 10),
+        (START_SLOT + 20, 20),
+        (START_SLOT + 30, 30),
+        (START_SLOT + TOTAL_SLOTS, 40),
+        (START_SLOT + TOTAL_SLOTS + 10, 40),
+    ] {
+        context.warp_to_slot(slot).unwrap();
+        reveal(&mut context, &program_id, &buffer, &authority, slot)
+            .await
+            .unwrap();
+
+        let (buffer_header, revealed_data) = revealed(&mut context, &buffer).await;
+        assert_eq!(buffer_header.revealed_bytes, bytes as u32);
+        assert_eq!(&revealed_data[..bytes], &release_data()[..bytes]);
+        assert!(revealed_data[bytes..].iter().all(|byte| *byte == 0));
+    }
+}
+
+#[tokio::test]
+async fn test_gradual_release_never_reveals_early() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = Keypair::new();
+
+    context.warp_to_slot(START_SLOT).unwrap();
+    let buffer =
+        initialize_gradual_release_echo(&mut context, &program_id, &authority.pubkey()).await;
+
+    // the slot of the instruction can't be ahead of the clock
+    context.warp_to_slot(START_SLOT + 20).unwrap();
+    let result = reveal(
+        &mut context,
+        &program_id,
+        &buffer,
+        &authority,
+        START_SLOT + TOTAL_SLOTS,
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+
+    reveal(
+        &mut context,
+        &program_id,
+        &buffer,
+        &authority,
+        START_SLOT + 20,
+    )
+    .await
+    .unwrap();
+
+    // an older slot doesn't hide what was revealed
+    reveal(
+        &mut context,
+        &program_id,
+        &buffer,
+        &authority,
+        START_SLOT + 5,
+    )
+    .await
+    .unwrap();
+    let (buffer_header, revealed_data) = revealed(&mut context, &buffer).await;
+    assert_eq!(buffer_header.revealed_bytes, 20);
+    assert_eq!(&revealed_data[..20], &release_data()[..20]);
+}
+
+#[tokio::test]
+async fn test_gradual_release_only_authority_reveals() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = Keypair::new();
+
+    context.warp_to_slot(START_SLOT).unwrap();
+    let buffer =
+        initialize_gradual_release_echo(&mut context, &program_id, &authority.pubkey()).await;
+
+    context.warp_to_slot(START_SLOT + TOTAL_SLOTS).unwrap();
+    let other = Keypair::new();
+    let result = reveal(
+        &mut context,
+        &program_id,
+        &buffer,
+        &other,
+        START_SLOT + TOTAL_SLOTS,
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    let (buffer_header, revealed_data) = revealed(&mut context, &buffer).await;
+    assert_eq!(buffer_header.revealed_bytes, 0);
+    assert_eq!(revealed_data, vec![0; 40]);
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{AUTH_BUFF_HEADER_SIZE, HASH_ONLY_RECORD_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        keccak,
+        pubkey::Pubkey,
+    },
+    solana_program_test::tokio,
+    solana_sdk::signature::Signer,
+};
+
+fn verify_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::VerifyAuthorizedEcho { data },
+        vec![
+            AccountMeta::new_readonly(*buffer, false),
+            AccountMeta::new_readonly(*authority, false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_hash_only_store_and_verify() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup_authorized_buffer(
+        &mut context,
+        &program_id,
+        AUTH_BUFF_HEADER_SIZE + HASH_ONLY_RECORD_SIZE,
+    )
+    .await;
+
+    // far larger than the payload of the buffer
+    let message = vec![7; 512];
+    send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::AuthorizedEchoHashOnly {
+                data: message.clone(),
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(authority.pubkey(), true),
+            ],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[AUTH_BUFF_HEADER_SIZE..AUTH_BUFF_HEADER_SIZE + 32],
+        &keccak::hash(&message).to_bytes()
+    );
+    assert_eq!(
+        &data[AUTH_BUFF_HEADER_SIZE + 32..],
+        &(message.len() as u32).to_le_bytes()
+    );
+
+    send(
+        &mut context,
+        &[verify_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            message.clone(),
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let mut tampered = message;
+    tampered[100] = 8;
+    let result = send(
+        &mut context,
+        &[verify_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            tampered,
+        )],
+        &[],
+    )
+    .await;
+    assert_echo_error(result, EchoError::HashMismatch);
+}
@@ -0,0 +1,138 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshSerialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{HistogramHeader, HISTOGRAM_BUCKET_SIZE, HISTOGRAM_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{account::Account, signature::Signer},
+};
+
+fn histogram_address(program_id: &Pubkey, authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"histogram", authority.as_ref()], program_id)
+}
+
+fn record_ix(program_id: &Pubkey, histogram: &Pubkey, value: u64) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::RecordHistogramValue { value },
+        vec![AccountMeta::new(*histogram, false)],
+    )
+}
+
+async fn buckets(context: &mut ProgramTestContext, histogram: &Pubkey) -> Vec<u64> {
+    account_data(context, histogram).await[HISTOGRAM_HEADER_SIZE..]
+        .chunks(HISTOGRAM_BUCKET_SIZE)
+        .map(|bucket| u64::from_le_bytes(bucket.try_into().unwrap()))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_histogram_bucket_boundaries_and_clamp() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let histogram = histogram_address(&program_id, &authority.pubkey()).0;
+
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::InitializeHistogramEcho {
+                bucket_count: 3,
+                bucket_width: 10,
+                authority: authority.pubkey(),
+            },
+            vec![
+                AccountMeta::new(histogram, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    for value in [0, 9, 10, 19, 20, 29, 30, u64::MAX] {
+        send(
+            &mut context,
+            &[record_ix(&program_id, &histogram, value)],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+    // the last bucket also counts everything past its upper bound
+    assert_eq!(buckets(&mut context, &histogram).await, vec![2, 2, 4]);
+
+    send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::ResetHistogram,
+            vec![
+                AccountMeta::new(histogram, false),
+                AccountMeta::new_readonly(authority.pubkey(), true),
+            ],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(buckets(&mut context, &histogram).await, vec![0, 0, 0]);
+}
+
+#[tokio::test]
+async fn test_histogram_bucket_overflow() {
+    let (mut program_test, program_id) = program_test();
+    let authority = Pubkey::new_unique();
+    let (histogram, bump_seed) = histogram_address(&program_id, &authority);
+
+    // start with the second bucket already saturated
+    let mut data = HistogramHeader {
+        bump_seed,
+        authority,
+        bucket_count: 2,
+        bucket_width: 100,
+    }
+    .try_to_vec()
+    .unwrap();
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&u64::MAX.to_le_bytes());
+    program_test.add_account(
+        histogram,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let result = send(
+        &mut context,
+        &[record_ix(&program_id, &histogram, 150)],
+        &[],
+    )
+    .await;
+    assert_echo_error(result, EchoError::Overflow);
+
+    send(&mut context, &[record_ix(&program_id, &histogram, 50)], &[])
+        .await
+        .unwrap();
+    assert_eq!(buckets(&mut context, &histogram).await, vec![1, u64::MAX]);
+}
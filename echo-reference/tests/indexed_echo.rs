@@ -0,0 +1,241 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::INDEXED_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+const KEY_SIZE: u8 = 4;
+const VALUE_SIZE: u16 = 2;
+const RECORD_SIZE: usize = KEY_SIZE as usize + VALUE_SIZE as usize;
+
+struct Indexed {
+    program_id: Pubkey,
+    authority: Keypair,
+    account: Pubkey,
+}
+
+impl Indexed {
+    async fn new(context: &mut ProgramTestContext, program_id: Pubkey, slot_count: u16) -> Self {
+        let authority = Keypair::new();
+        let account =
+            Pubkey::find_program_address(&[b"indexed", authority.pubkey().as_ref()], &program_id).0;
+        let payer = context.payer.pubkey();
+        send(
+            context,
+            &[Instruction::new_with_borsh(
+                program_id,
+                &EchoInstruction::InitializeIndexedEcho {
+                    slot_count,
+                    key_size: KEY_SIZE,
+                    value_size: VALUE_SIZE,
+                    authority: authority.pubkey(),
+                },
+                vec![
+                    AccountMeta::new(account, false),
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        Self {
+            program_id,
+            authority,
+            account,
+        }
+    }
+
+    async fn mutate(
+        &self,
+        context: &mut ProgramTestContext,
+        instruction: EchoInstruction,
+    ) -> Result<(), solana_sdk::transport::TransportError> {
+        let ix = Instruction::new_with_borsh(
+            self.program_id,
+            &instruction,
+            vec![
+                AccountMeta::new(self.account, false),
+                AccountMeta::new_readonly(self.authority.pubkey(), true),
+            ],
+        );
+        send(context, &[ix], &[&self.authority]).await
+    }
+
+    async fn get(
+        &self,
+        context: &mut ProgramTestContext,
+        key: Vec<u8>,
+    ) -> Result<(), solana_sdk::transport::TransportError> {
+        let ix = Instruction::new_with_borsh(
+            self.program_id,
+            &EchoInstruction::GetIndexedValue { key },
+            vec![
+                AccountMeta::new_readonly(self.account, false),
+                AccountMeta::new_readonly(self.authority.pubkey(), false),
+            ],
+        );
+        send(context, &[ix], &[]).await
+    }
+
+    async fn records(&self, context: &mut ProgramTestContext) -> Vec<Vec<u8>> {
+        let data = account_data(context, &self.account).await;
+        data[INDEXED_HEADER_SIZE..]
+            .chunks(RECORD_SIZE)
+            .map(|record| record.to_vec())
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn test_indexed_put_upserts_and_deletes() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let indexed = Indexed::new(&mut context, program_id, 2).await;
+
+    indexed
+        .mutate(
+            &mut context,
+            EchoInstruction::PutIndexedValue {
+                key: vec![1; 4],
+                value: vec![7, 7],
+            },
+        )
+        .await
+        .unwrap();
+    indexed
+        .mutate(
+            &mut context,
+            EchoInstruction::PutIndexedValue {
+                key: vec![2; 4],
+                value: vec![8, 8],
+            },
+        )
+        .await
+        .unwrap();
+    // updating an existing key keeps its slot
+    indexed
+        .mutate(
+            &mut context,
+            EchoInstruction::PutIndexedValue {
+                key: vec![1; 4],
+                value: vec![9, 9],
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        indexed.records(&mut context).await,
+        vec![vec![1, 1, 1, 1, 9, 9], vec![2, 2, 2, 2, 8, 8]]
+    );
+    indexed.get(&mut context, vec![2; 4]).await.unwrap();
+
+    indexed
+        .mutate(
+            &mut context,
+            EchoInstruction::DeleteIndexedValue { key: vec![1; 4] },
+        )
+        .await
+        .unwrap();
+    assert_eq!(indexed.records(&mut context).await[0], vec![0; RECORD_SIZE]);
+    let result = indexed.get(&mut context, vec![1; 4]).await;
+    assert_echo_error(result, EchoError::MemberNotFound);
+
+    // the freed slot is reused by the next insertion
+    indexed
+        .mutate(
+            &mut context,
+            EchoInstruction::PutIndexedValue {
+                key: vec![3; 4],
+                value: vec![1, 2],
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        indexed.records(&mut context).await[0],
+        vec![3, 3, 3, 3, 1, 2]
+    );
+}
+
+#[tokio::test]
+async fn test_indexed_rejects_full_and_bad_lengths() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let indexed = Indexed::new(&mut context, program_id, 1).await;
+
+    indexed
+        .mutate(
+            &mut context,
+            EchoInstruction::PutIndexedValue {
+                key: vec![1; 4],
+                value: vec![1, 1],
+            },
+        )
+        .await
+        .unwrap();
+    let result = indexed
+        .mutate(
+            &mut context,
+            EchoInstruction::PutIndexedValue {
+                key: vec![2; 4],
+                value: vec![1, 1],
+            },
+        )
+        .await;
+    assert_echo_error(result, EchoError::SetFull);
+
+    let result = indexed
+        .mutate(
+            &mut context,
+            EchoInstruction::PutIndexedValue {
+                key: vec![1; 3],
+                value: vec![1, 1],
+            },
+        )
+        .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+
+    let result = indexed
+        .mutate(
+            &mut context,
+            EchoInstruction::PutIndexedValue {
+                key: vec![1; 4],
+                value: vec![1],
+            },
+        )
+        .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+}
+
+#[tokio::test]
+async fn test_indexed_requires_authority_signature() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let indexed = Indexed::new(&mut context, program_id, 1).await;
+
+    let ix = Instruction::new_with_borsh(
+        program_id,
+        &EchoInstruction::PutIndexedValue {
+            key: vec![1; 4],
+            value: vec![1, 1],
+        },
+        vec![
+            AccountMeta::new(indexed.account, false),
+            AccountMeta::new_readonly(indexed.authority.pubkey(), false),
+        ],
+    );
+    let result = send(&mut context, &[ix], &[]).await;
+    assert_echo_error(result, EchoError::MissingRequiredSignature);
+}
@@ -0,0 +1,173 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{AuthorizedBufferHeader, AUTH_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::Signer,
+};
+
+fn set_label_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    label: &str,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::SetLabel {
+            label: label.to_string(),
+        },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+async fn header(context: &mut ProgramTestContext, buffer: &Pubkey) -> AuthorizedBufferHeader {
+    let data = account_data(context, buffer).await;
+    AuthorizedBufferHeader::try_from_slice(&data[..AUTH_BUFF_HEADER_SIZE]).unwrap()
+}
+
+#[tokio::test]
+async fn test_label_set_at_init_and_relabeled() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let (buffer, _) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+
+    send(
+        &mut context,
+        &[
+            initialize_labeled_authorized_echo_ix(
+                &program_id,
+                &authority.pubkey(),
+                0,
+                AUTH_BUFF_HEADER_SIZE + 8,
+                "prices",
+            ),
+            authorized_echo_ix(&program_id, &buffer, &authority.pubkey(), vec![1; 8]),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(header(&mut context, &buffer).await.label(), "prices");
+
+    let label = "é".repeat(16);
+    send(
+        &mut context,
+        &[set_label_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &label,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(header(&mut context, &buffer).await.label(), label);
+
+    // relabeling leaves the payload alone
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[AUTH_BUFF_HEADER_SIZE..], &[1; 8]);
+}
+
+#[tokio::test]
+async fn test_label_too_long() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let (buffer, _) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+
+    let result = send(
+        &mut context,
+        &[initialize_labeled_authorized_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            0,
+            AUTH_BUFF_HEADER_SIZE + 8,
+            &"a".repeat(33),
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+
+    send(
+        &mut context,
+        &[initialize_authorized_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            0,
+            AUTH_BUFF_HEADER_SIZE + 8,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(header(&mut context, &buffer).await.label(), "");
+
+    let result = send(
+        &mut context,
+        &[set_label_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &"a".repeat(33),
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+}
+
+#[tokio::test]
+async fn test_set_label_rejects_non_authority() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let intruder = funded_keypair(&mut context).await;
+    let (buffer, _) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+
+    send(
+        &mut context,
+        &[initialize_labeled_authorized_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            0,
+            AUTH_BUFF_HEADER_SIZE + 8,
+            "mine",
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut context,
+        &[set_label_ix(
+            &program_id,
+            &buffer,
+            &intruder.pubkey(),
+            "theirs",
+        )],
+        &[&intruder],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+    assert_eq!(header(&mut context, &buffer).await.label(), "mine");
+}
@@ -0,0 +1,186 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{LinkedBufferHeader, LINKED_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+const NODE_SIZE: usize = LINKED_BUFF_HEADER_SIZE + 16;
+
+async fn initialize_head(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    authority: &Pubkey,
+) -> Pubkey {
+    let head = Pubkey::find_program_address(&[b"linked", authority.as_ref()], program_id).0;
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeLinkedEcho {
+                buffer_size: NODE_SIZE,
+                authority: *authority,
+            },
+            vec![
+                AccountMeta::new(head, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    head
+}
+
+fn append_ix(
+    program_id: &Pubkey,
+    tail: &Pubkey,
+    new_buffer: &Pubkey,
+    authority: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::AppendLinkedEcho {
+            new_buffer: *new_buffer,
+        },
+        vec![
+            AccountMeta::new(*tail, false),
+            AccountMeta::new(*new_buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+fn traverse_ix(program_id: &Pubkey, chain: &[Pubkey]) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::TraverseLinkedEcho,
+        chain
+            .iter()
+            .map(|buffer| AccountMeta::new_readonly(*buffer, false))
+            .collect(),
+    )
+}
+
+async fn header(context: &mut ProgramTestContext, buffer: &Pubkey) -> LinkedBufferHeader {
+    let data = account_data(context, buffer).await;
+    LinkedBufferHeader::try_from_slice(&data[..LINKED_BUFF_HEADER_SIZE]).unwrap()
+}
+
+#[tokio::test]
+async fn test_linked_echo_single_node() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = Keypair::new();
+    let head = initialize_head(&mut context, &program_id, &authority.pubkey()).await;
+
+    let head_header = header(&mut context, &head).await;
+    assert_eq!(head_header.authority, authority.pubkey());
+    assert!(!head_header.has_next);
+
+    send(&mut context, &[traverse_ix(&program_id, &[head])], &[])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_linked_echo_two_nodes() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = Keypair::new();
+    let head = initialize_head(&mut context, &program_id, &authority.pubkey()).await;
+    let second = create_owned_account(&mut context, &program_id, NODE_SIZE)
+        .await
+        .pubkey();
+
+    send(
+        &mut context,
+        &[append_ix(&program_id, &head, &second, &authority.pubkey())],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let head_header = header(&mut context, &head).await;
+    assert!(head_header.has_next);
+    assert_eq!(head_header.next_buffer, second);
+    let second_header = header(&mut context, &second).await;
+    assert_eq!(second_header.authority, authority.pubkey());
+    assert!(!second_header.has_next);
+
+    send(
+        &mut context,
+        &[traverse_ix(&program_id, &[head, second])],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    // the chain continues past the head, so the traversal needs the second buffer too
+    let result = send(&mut context, &[traverse_ix(&program_id, &[head])], &[]).await;
+    assert!(result.is_err());
+
+    // the head is no longer the tail
+    let third = create_owned_account(&mut context, &program_id, NODE_SIZE)
+        .await
+        .pubkey();
+    let result = send(
+        &mut context,
+        &[append_ix(&program_id, &head, &third, &authority.pubkey())],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountData);
+}
+
+#[tokio::test]
+async fn test_linked_echo_rejects_cycles() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = Keypair::new();
+    let head = initialize_head(&mut context, &program_id, &authority.pubkey()).await;
+
+    let result = send(
+        &mut context,
+        &[append_ix(&program_id, &head, &head, &authority.pubkey())],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+
+    // linking back to an existing node fails as it isn't zeroed
+    let second = create_owned_account(&mut context, &program_id, NODE_SIZE)
+        .await
+        .pubkey();
+    send(
+        &mut context,
+        &[append_ix(&program_id, &head, &second, &authority.pubkey())],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let result = send(
+        &mut context,
+        &[append_ix(&program_id, &second, &head, &authority.pubkey())],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::AccountHasNonZeroData);
+}
@@ -0,0 +1,198 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{MapHeader, MAP_ENTRY_SIZE, MAP_HEADER_SIZE, MAP_KEY_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+struct Map {
+    program_id: Pubkey,
+    authority: Keypair,
+    account: Pubkey,
+}
+
+impl Map {
+    async fn new(context: &mut ProgramTestContext, program_id: Pubkey, capacity: u16) -> Self {
+        let authority = Keypair::new();
+        let account =
+            Pubkey::find_program_address(&[b"map", authority.pubkey().as_ref()], &program_id).0;
+        let payer = context.payer.pubkey();
+        send(
+            context,
+            &[Instruction::new_with_borsh(
+                program_id,
+                &EchoInstruction::InitializeMapEcho {
+                    capacity,
+                    authority: authority.pubkey(),
+                },
+                vec![
+                    AccountMeta::new(account, false),
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        Self {
+            program_id,
+            authority,
+            account,
+        }
+    }
+
+    async fn mutate(
+        &self,
+        context: &mut ProgramTestContext,
+        instruction: EchoInstruction,
+    ) -> Result<(), solana_sdk::transport::TransportError> {
+        let ix = Instruction::new_with_borsh(
+            self.program_id,
+            &instruction,
+            vec![
+                AccountMeta::new(self.account, false),
+                AccountMeta::new_readonly(self.authority.pubkey(), true),
+            ],
+        );
+        send(context, &[ix], &[&self.authority]).await
+    }
+
+    async fn get(
+        &self,
+        context: &mut ProgramTestContext,
+        key: Pubkey,
+    ) -> Result<(), solana_sdk::transport::TransportError> {
+        let ix = Instruction::new_with_borsh(
+            self.program_id,
+            &EchoInstruction::GetMapEntry { key },
+            vec![
+                AccountMeta::new_readonly(self.account, false),
+                AccountMeta::new_readonly(self.authority.pubkey(), false),
+            ],
+        );
+        send(context, &[ix], &[]).await
+    }
+
+    async fn entries(&self, context: &mut ProgramTestContext) -> Vec<(Pubkey, u64)> {
+        let data = account_data(context, &self.account).await;
+        let header = MapHeader::try_from_slice(&data[..MAP_HEADER_SIZE]).unwrap();
+        data[MAP_HEADER_SIZE..MAP_HEADER_SIZE + header.count as usize * MAP_ENTRY_SIZE]
+            .chunks(MAP_ENTRY_SIZE)
+            .map(|entry| {
+                (
+                    Pubkey::new(&entry[..MAP_KEY_SIZE]),
+                    u64::from_le_bytes(entry[MAP_KEY_SIZE..].try_into().unwrap()),
+                )
+            })
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn test_map_upsert_keeps_entries_sorted() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let map = Map::new(&mut context, program_id, 3).await;
+
+    let keys: Vec<Pubkey> = (1..=3).map(|i| Pubkey::new_from_array([i; 32])).collect();
+    for (key, value) in [(keys[2], 30), (keys[0], 10), (keys[1], 20), (keys[0], 11)] {
+        map.mutate(&mut context, EchoInstruction::PutMapEntry { key, value })
+            .await
+            .unwrap();
+    }
+    assert_eq!(
+        map.entries(&mut context).await,
+        vec![(keys[0], 11), (keys[1], 20), (keys[2], 30)]
+    );
+    map.get(&mut context, keys[1]).await.unwrap();
+
+    // updating an existing key still works on a full map
+    map.mutate(
+        &mut context,
+        EchoInstruction::PutMapEntry {
+            key: keys[2],
+            value: 31,
+        },
+    )
+    .await
+    .unwrap();
+    let result = map
+        .mutate(
+            &mut context,
+            EchoInstruction::PutMapEntry {
+                key: Pubkey::new_unique(),
+                value: 1,
+            },
+        )
+        .await;
+    assert_echo_error(result, EchoError::MapFull);
+    assert_eq!(map.entries(&mut context).await[2], (keys[2], 31));
+}
+
+#[tokio::test]
+async fn test_map_delete_and_not_found() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let map = Map::new(&mut context, program_id, 4).await;
+
+    let keys: Vec<Pubkey> = (1..=3).map(|i| Pubkey::new_from_array([i; 32])).collect();
+    for key in &keys {
+        map.mutate(
+            &mut context,
+            EchoInstruction::PutMapEntry {
+                key: *key,
+                value: 7,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    map.mutate(
+        &mut context,
+        EchoInstruction::DeleteMapEntry { key: keys[0] },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        map.entries(&mut context).await,
+        vec![(keys[1], 7), (keys[2], 7)]
+    );
+
+    let result = map
+        .mutate(
+            &mut context,
+            EchoInstruction::DeleteMapEntry { key: keys[0] },
+        )
+        .await;
+    assert_echo_error(result, EchoError::MemberNotFound);
+    let result = map.get(&mut context, keys[0]).await;
+    assert_echo_error(result, EchoError::MemberNotFound);
+
+    let intruder = Keypair::new();
+    let ix = Instruction::new_with_borsh(
+        program_id,
+        &EchoInstruction::DeleteMapEntry { key: keys[1] },
+        vec![
+            AccountMeta::new(map.account, false),
+            AccountMeta::new_readonly(intruder.pubkey(), true),
+        ],
+    );
+    let result = send(&mut context, &[ix], &[&intruder]).await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+}
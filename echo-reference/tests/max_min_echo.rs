@@ -0,0 +1,162 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{MaxHeader, MinHeader},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+struct Tracker {
+    program_id: Pubkey,
+    authority: Keypair,
+    account: Pubkey,
+}
+
+impl Tracker {
+    async fn new(
+        context: &mut ProgramTestContext,
+        program_id: Pubkey,
+        seed: &[u8],
+        initialize: fn(Pubkey) -> EchoInstruction,
+    ) -> Self {
+        let authority = Keypair::new();
+        let account =
+            Pubkey::find_program_address(&[seed, authority.pubkey().as_ref()], &program_id).0;
+        let payer = context.payer.pubkey();
+        send(
+            context,
+            &[Instruction::new_with_borsh(
+                program_id,
+                &initialize(authority.pubkey()),
+                vec![
+                    AccountMeta::new(account, false),
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        Self {
+            program_id,
+            authority,
+            account,
+        }
+    }
+
+    /// Sends an update signed only by the payer, the authority doesn't sign.
+    async fn update(&self, context: &mut ProgramTestContext, instruction: EchoInstruction) {
+        let ix = Instruction::new_with_borsh(
+            self.program_id,
+            &instruction,
+            vec![
+                AccountMeta::new(self.account, false),
+                AccountMeta::new_readonly(self.authority.pubkey(), false),
+            ],
+        );
+        send(context, &[ix], &[]).await.unwrap();
+    }
+
+    async fn reset(
+        &self,
+        context: &mut ProgramTestContext,
+        signer: &Keypair,
+        instruction: EchoInstruction,
+    ) -> Result<(), solana_sdk::transport::TransportError> {
+        let ix = Instruction::new_with_borsh(
+            self.program_id,
+            &instruction,
+            vec![
+                AccountMeta::new(self.account, false),
+                AccountMeta::new_readonly(signer.pubkey(), true),
+            ],
+        );
+        send(context, &[ix], &[signer]).await
+    }
+}
+
+async fn max_header(context: &mut ProgramTestContext, tracker: &Tracker) -> MaxHeader {
+    MaxHeader::try_from_slice(&account_data(context, &tracker.account).await).unwrap()
+}
+
+async fn min_header(context: &mut ProgramTestContext, tracker: &Tracker) -> MinHeader {
+    MinHeader::try_from_slice(&account_data(context, &tracker.account).await).unwrap()
+}
+
+#[tokio::test]
+async fn test_max_ignores_lower_candidates() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let max = Tracker::new(&mut context, program_id, b"max", |authority| {
+        EchoInstruction::InitializeMaxEcho { authority }
+    })
+    .await;
+
+    for (candidate, expected) in [(5, 5), (3, 5), (9, 9)] {
+        max.update(&mut context, EchoInstruction::UpdateMax { candidate })
+            .await;
+        assert_eq!(max_header(&mut context, &max).await.current_max, expected);
+    }
+    assert_eq!(max_header(&mut context, &max).await.update_count, 3);
+
+    let result = max
+        .reset(
+            &mut context,
+            &Keypair::new(),
+            EchoInstruction::ResetMax { new_value: 1 },
+        )
+        .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+
+    max.reset(
+        &mut context,
+        &max.authority,
+        EchoInstruction::ResetMax { new_value: 1 },
+    )
+    .await
+    .unwrap();
+    let header = max_header(&mut context, &max).await;
+    assert_eq!(header.current_max, 1);
+    assert_eq!(header.update_count, 3);
+}
+
+#[tokio::test]
+async fn test_min_ignores_higher_candidates() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let min = Tracker::new(&mut context, program_id, b"min", |authority| {
+        EchoInstruction::InitializeMinEcho { authority }
+    })
+    .await;
+    assert_eq!(min_header(&mut context, &min).await.current_min, u64::MAX);
+
+    for (candidate, expected) in [(5, 5), (8, 5), (2, 2)] {
+        min.update(&mut context, EchoInstruction::UpdateMin { candidate })
+            .await;
+        assert_eq!(min_header(&mut context, &min).await.current_min, expected);
+    }
+    assert_eq!(min_header(&mut context, &min).await.update_count, 3);
+
+    min.reset(
+        &mut context,
+        &min.authority,
+        EchoInstruction::ResetMin { new_value: 100 },
+    )
+    .await
+    .unwrap();
+    assert_eq!(min_header(&mut context, &min).await.current_min, 100);
+}
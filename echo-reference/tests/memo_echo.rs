@@ -0,0 +1,121 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{
+        error::EchoError, instruction::EchoInstruction, processor::memo_echo::spl_memo,
+        state::MEMO_ECHO_HEADER_SIZE,
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> (Keypair, Pubkey) {
+    let authority = Keypair::new();
+    let (buffer, _) =
+        Pubkey::find_program_address(&[b"memo", authority.pubkey().as_ref()], program_id);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeMemoEcho {
+                buffer_size: MEMO_ECHO_HEADER_SIZE + 8,
+                authority: authority.pubkey(),
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    (authority, buffer)
+}
+
+fn memo_echo_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    memo_program: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::MemoEcho { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(*memo_program, false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_memo_echo_logs_hex_memo() {
+    let (program_test, program_id) = program_test_with_logs();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id).await;
+
+    let (result, logs) = send_with_logs(
+        &mut context,
+        &program_id,
+        &[memo_echo_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &spl_memo::id(),
+            vec![0xde, 0xad, 0xbe, 0xef],
+        )],
+        &[&authority],
+    )
+    .await;
+    result.unwrap();
+
+    // the memo program is invoked from the echo program, as an inner instruction
+    let memo_invoke = format!("Program {} invoke [2]", spl_memo::id());
+    let position = logs
+        .iter()
+        .position(|log| *log == memo_invoke)
+        .expect("memo CPI not found");
+    assert!(logs[position..]
+        .iter()
+        .any(|log| log.starts_with("Program log: Memo") && log.contains("deadbeef")));
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[MEMO_ECHO_HEADER_SIZE..],
+        &[0xde, 0xad, 0xbe, 0xef, 0, 0, 0, 0]
+    );
+}
+
+#[tokio::test]
+async fn test_memo_echo_rejects_other_memo_program() {
+    let (program_test, program_id) = program_test_with_logs();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id).await;
+
+    let result = send(
+        &mut context,
+        &[memo_echo_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &system_program::id(),
+            vec![1; 4],
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidProgramAddress);
+}
@@ -0,0 +1,181 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{AuthorizedBufferHeader, AUTH_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+fn merge_buffers_ix(
+    program_id: &Pubkey,
+    source: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::MergeBuffers,
+        vec![
+            AccountMeta::new(*source, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// Creates the buffer of `authority` at `seed` and writes `data` to it.
+async fn written_buffer(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    authority: &Keypair,
+    seed: u64,
+    buffer_size: usize,
+    data: Vec<u8>,
+) -> Pubkey {
+    let buffer = authorized_buffer_address(program_id, &authority.pubkey(), seed).0;
+    send(
+        context,
+        &[
+            initialize_authorized_echo_ix(program_id, &authority.pubkey(), seed, buffer_size),
+            authorized_echo_ix(program_id, &buffer, &authority.pubkey(), data),
+        ],
+        &[authority],
+    )
+    .await
+    .unwrap();
+    buffer
+}
+
+#[tokio::test]
+async fn test_merge_buffers_appends_and_closes_source() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+
+    let source = written_buffer(
+        &mut context,
+        &program_id,
+        &authority,
+        0,
+        AUTH_BUFF_HEADER_SIZE + 3,
+        vec![1, 2, 3],
+    )
+    .await;
+    let destination = written_buffer(
+        &mut context,
+        &program_id,
+        &authority,
+        1,
+        AUTH_BUFF_HEADER_SIZE + 8,
+        vec![9, 9],
+    )
+    .await;
+    let source_lamports = context.banks_client.get_balance(source).await.unwrap();
+    let destination_lamports = context.banks_client.get_balance(destination).await.unwrap();
+
+    send(
+        &mut context,
+        &[merge_buffers_ix(
+            &program_id,
+            &source,
+            &destination,
+            &authority.pubkey(),
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &destination).await;
+    assert_eq!(&data[AUTH_BUFF_HEADER_SIZE..], &[9, 9, 1, 2, 3, 0, 0, 0]);
+    let header = AuthorizedBufferHeader::try_from_slice(&data[..AUTH_BUFF_HEADER_SIZE]).unwrap();
+    assert_eq!(header.cursor, 5);
+    assert_eq!(
+        context.banks_client.get_balance(destination).await.unwrap(),
+        destination_lamports + source_lamports
+    );
+    assert!(context
+        .banks_client
+        .get_account(source)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_merge_buffers_rejects_small_destination() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+
+    let source = written_buffer(
+        &mut context,
+        &program_id,
+        &authority,
+        0,
+        AUTH_BUFF_HEADER_SIZE + 3,
+        vec![1, 2, 3],
+    )
+    .await;
+    let destination = written_buffer(
+        &mut context,
+        &program_id,
+        &authority,
+        1,
+        AUTH_BUFF_HEADER_SIZE + 4,
+        vec![9, 9],
+    )
+    .await;
+
+    let result = send(
+        &mut context,
+        &[merge_buffers_ix(
+            &program_id,
+            &source,
+            &destination,
+            &authority.pubkey(),
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+
+    // both buffers must belong to the authority
+    let other = funded_keypair(&mut context).await;
+    let other_destination = written_buffer(
+        &mut context,
+        &program_id,
+        &other,
+        0,
+        AUTH_BUFF_HEADER_SIZE + 8,
+        vec![],
+    )
+    .await;
+    let result = send(
+        &mut context,
+        &[merge_buffers_ix(
+            &program_id,
+            &source,
+            &other_destination,
+            &authority.pubkey(),
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+
+    let data = account_data(&mut context, &source).await;
+    assert_eq!(&data[AUTH_BUFF_HEADER_SIZE..], &[1, 2, 3]);
+}
@@ -0,0 +1,197 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{MultiSlotHeader, MULTI_SLOT_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+const TOTAL_SIZE: usize = 100;
+const CHUNK_SIZE: usize = 32;
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> (Keypair, Pubkey) {
+    let authority = Keypair::new();
+    let (buffer, _) =
+        Pubkey::find_program_address(&[b"multi_slot", authority.pubkey().as_ref()], program_id);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeMultiSlotEcho {
+                total_size: TOTAL_SIZE as u32,
+                chunk_size: CHUNK_SIZE as u16,
+                authority: authority.pubkey(),
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    (authority, buffer)
+}
+
+fn multi_slot_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    instruction: EchoInstruction,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &instruction,
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+fn chunk(payload: &[u8], chunk_index: u16) -> EchoInstruction {
+    let start = chunk_index as usize * CHUNK_SIZE;
+    EchoInstruction::WriteMultiSlotChunk {
+        chunk_index,
+        data: payload[start..(start + CHUNK_SIZE).min(TOTAL_SIZE)].to_vec(),
+    }
+}
+
+async fn read_header(context: &mut ProgramTestContext, buffer: &Pubkey) -> MultiSlotHeader {
+    let data = account_data(context, buffer).await;
+    MultiSlotHeader::try_from_slice(&data[..MULTI_SLOT_HEADER_SIZE]).unwrap()
+}
+
+#[tokio::test]
+async fn test_out_of_order_and_duplicate_chunks() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id).await;
+    let payload: Vec<u8> = (1..=TOTAL_SIZE as u8).collect();
+    let ix = |instruction| multi_slot_ix(&program_id, &buffer, &authority.pubkey(), instruction);
+
+    // the last, shorter chunk first, and chunk 1 twice
+    send(
+        &mut context,
+        &[
+            ix(chunk(&payload, 3)),
+            ix(chunk(&payload, 1)),
+            ix(chunk(&payload, 1)),
+            ix(chunk(&payload, 0)),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let header = read_header(&mut context, &buffer).await;
+    assert_eq!(header.total_chunks, 4);
+    assert_eq!(header.chunks_written, 3);
+    assert_eq!(header.written_chunks, 0b1011);
+    assert!(!header.committed);
+
+    send(
+        &mut context,
+        &[ix(chunk(&payload, 2)), ix(EchoInstruction::CommitMultiSlot)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let header = read_header(&mut context, &buffer).await;
+    assert_eq!(header.chunks_written, 4);
+    assert!(header.committed);
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[MULTI_SLOT_HEADER_SIZE..], payload.as_slice());
+
+    let result = send(&mut context, &[ix(chunk(&payload, 0))], &[&authority]).await;
+    assert_echo_error(result, EchoError::MultiSlotCommitted);
+}
+
+#[tokio::test]
+async fn test_commit_before_all_chunks() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id).await;
+    let payload = vec![7; TOTAL_SIZE];
+    let ix = |instruction| multi_slot_ix(&program_id, &buffer, &authority.pubkey(), instruction);
+
+    send(
+        &mut context,
+        &[
+            ix(chunk(&payload, 0)),
+            ix(chunk(&payload, 1)),
+            ix(chunk(&payload, 3)),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut context,
+        &[ix(EchoInstruction::CommitMultiSlot)],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::MultiSlotIncomplete);
+    assert!(!read_header(&mut context, &buffer).await.committed);
+
+    let result = send(
+        &mut context,
+        &[ix(EchoInstruction::WriteMultiSlotChunk {
+            chunk_index: 4,
+            data: vec![1],
+        })],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+}
+
+#[tokio::test]
+async fn test_abort_multi_slot() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id).await;
+    let payload = vec![7; TOTAL_SIZE];
+    let ix = |instruction| multi_slot_ix(&program_id, &buffer, &authority.pubkey(), instruction);
+
+    send(
+        &mut context,
+        &[
+            ix(chunk(&payload, 0)),
+            ix(chunk(&payload, 1)),
+            ix(chunk(&payload, 2)),
+            ix(chunk(&payload, 3)),
+            ix(EchoInstruction::CommitMultiSlot),
+            ix(EchoInstruction::AbortMultiSlot),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let header = read_header(&mut context, &buffer).await;
+    assert_eq!(header.chunks_written, 0);
+    assert_eq!(header.written_chunks, 0);
+    assert!(!header.committed);
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[MULTI_SLOT_HEADER_SIZE..], &[0; TOTAL_SIZE]);
+}
@@ -0,0 +1,181 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        pda::find_named_authorized_echo_address,
+        state::{AUTH_BUFF_HEADER_SIZE, NAMED_AUTH_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::tokio,
+    solana_sdk::signature::Signer,
+};
+
+fn initialize_named_ix(program_id: &Pubkey, authority: &Pubkey, name: &str) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::InitializeNamedAuthorizedEcho {
+            name: name.to_string(),
+            buffer_size: NAMED_AUTH_BUFF_HEADER_SIZE + 32,
+        },
+        vec![
+            // names longer than a seed can't be derived client side at all
+            AccountMeta::new(
+                Pubkey::try_find_program_address(
+                    &[b"authority", authority.as_ref(), name.as_bytes()],
+                    program_id,
+                )
+                .map(|(address, _)| address)
+                .unwrap_or_else(Pubkey::new_unique),
+                false,
+            ),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+fn named_echo_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::NamedAuthorizedEcho { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_named_authorized_echo_same_name_different_authorities() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let alice = funded_keypair(&mut context).await;
+    let bob = funded_keypair(&mut context).await;
+
+    let (alice_buffer, _) =
+        find_named_authorized_echo_address(&program_id, &alice.pubkey(), "announcements");
+    let (bob_buffer, _) =
+        find_named_authorized_echo_address(&program_id, &bob.pubkey(), "announcements");
+    assert_ne!(alice_buffer, bob_buffer);
+
+    send(
+        &mut context,
+        &[
+            initialize_named_ix(&program_id, &alice.pubkey(), "announcements"),
+            initialize_named_ix(&program_id, &bob.pubkey(), "announcements"),
+            named_echo_ix(&program_id, &alice_buffer, &alice.pubkey(), vec![1; 4]),
+            named_echo_ix(&program_id, &bob_buffer, &bob.pubkey(), vec![2; 4]),
+        ],
+        &[&alice, &bob],
+    )
+    .await
+    .unwrap();
+
+    let alice_data = account_data(&mut context, &alice_buffer).await;
+    let bob_data = account_data(&mut context, &bob_buffer).await;
+    assert_eq!(&alice_data[2..2 + "announcements".len()], b"announcements");
+    assert_eq!(
+        &alice_data[NAMED_AUTH_BUFF_HEADER_SIZE..NAMED_AUTH_BUFF_HEADER_SIZE + 4],
+        &[1; 4]
+    );
+    assert_eq!(
+        &bob_data[NAMED_AUTH_BUFF_HEADER_SIZE..NAMED_AUTH_BUFF_HEADER_SIZE + 4],
+        &[2; 4]
+    );
+
+    // bob can't write alice's buffer
+    let result = send(
+        &mut context,
+        &[named_echo_ix(
+            &program_id,
+            &alice_buffer,
+            &bob.pubkey(),
+            vec![3; 4],
+        )],
+        &[&bob],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn test_named_authorized_echo_rejects_invalid_names() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+
+    for name in ["   ", &"x".repeat(33)] {
+        let result = send(
+            &mut context,
+            &[initialize_named_ix(&program_id, &authority.pubkey(), name)],
+            &[&authority],
+        )
+        .await;
+        assert_echo_error(result, EchoError::InvalidInstructionInput);
+    }
+}
+
+#[tokio::test]
+async fn test_named_authorized_echo_rejects_names_clashing_with_seeds() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+
+    // an 8 byte name has the address of the buffer seed made of the same bytes
+    let buffer_seed = u64::from_le_bytes(*b"announce");
+    let (seeded_buffer, _) =
+        authorized_buffer_address(&program_id, &authority.pubkey(), buffer_seed);
+    let (named_buffer, _) =
+        find_named_authorized_echo_address(&program_id, &authority.pubkey(), "announce");
+    assert_eq!(seeded_buffer, named_buffer);
+
+    send(
+        &mut context,
+        &[initialize_authorized_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            buffer_seed,
+            AUTH_BUFF_HEADER_SIZE + 32,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    for name in ["announce", "configV2"] {
+        let result = send(
+            &mut context,
+            &[initialize_named_ix(&program_id, &authority.pubkey(), name)],
+            &[&authority],
+        )
+        .await;
+        assert_echo_error(result, EchoError::InvalidInstructionInput);
+    }
+
+    // any other length can't clash
+    send(
+        &mut context,
+        &[initialize_named_ix(
+            &program_id,
+            &authority.pubkey(),
+            "announcements",
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+}
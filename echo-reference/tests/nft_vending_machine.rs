@@ -0,0 +1,267 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshSerialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        processor::nft_vending_machine_echo::mpl_token_metadata::{
+            self, Collection, Data, Metadata,
+        },
+        state::NFT_VENDING_MACHINE_HEADER_SIZE,
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_pack::Pack,
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTest, ProgramTestContext},
+    solana_sdk::{
+        account::Account,
+        signature::{Keypair, Signer},
+    },
+    spl_token::state::Mint,
+};
+
+struct Nft {
+    mint: Keypair,
+    metadata: Pubkey,
+}
+
+/// A mock Metaplex metadata account for a new NFT mint, with trailing bytes like the real accounts.
+fn add_nft(program_test: &mut ProgramTest, collection: Option<Collection>, owner: Pubkey) -> Nft {
+    let mint = Keypair::new();
+    let (metadata, _) = mpl_token_metadata::find_metadata_address(&mint.pubkey());
+    let mut data = Metadata {
+        key: mpl_token_metadata::KEY_METADATA_V1,
+        update_authority: Pubkey::new_unique(),
+        mint: mint.pubkey(),
+        data: Data {
+            name: "Echo #1".to_string(),
+            symbol: "ECHO".to_string(),
+            uri: "https://example.com/1.json".to_string(),
+            seller_fee_basis_points: 0,
+            creators: None,
+        },
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: None,
+        collection,
+    }
+    .try_to_vec()
+    .unwrap();
+    data.resize(679, 0);
+    program_test.add_account(
+        metadata,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner,
+            ..Account::default()
+        },
+    );
+    Nft { mint, metadata }
+}
+
+/// Mints the NFT to a token account of a new user.
+async fn mint_nft(context: &mut ProgramTestContext, nft: &Nft) -> (Keypair, Pubkey) {
+    let mint_authority = Keypair::new();
+    initialize_mint(context, &nft.mint, &mint_authority.pubkey(), 0).await;
+    let user = Keypair::new();
+    let user_token_account = create_token_account(context, &nft.mint.pubkey(), &user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(
+        context,
+        &nft.mint.pubkey(),
+        &user_token_account,
+        &mint_authority,
+        1,
+    )
+    .await;
+    (user, user_token_account)
+}
+
+async fn initialize_nft_vending_machine(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    collection: &Pubkey,
+) -> Pubkey {
+    let (buffer, _) = Pubkey::find_program_address(&[b"nft_vm", collection.as_ref()], program_id);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeNftVendingMachine {
+                buffer_size: NFT_VENDING_MACHINE_HEADER_SIZE + 16,
+                collection: *collection,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    buffer
+}
+
+fn nft_vending_machine_echo_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    user: &Pubkey,
+    user_token_account: &Pubkey,
+    nft: &Nft,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::NftVendingMachineEcho { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(*user_token_account, false),
+            AccountMeta::new(nft.mint.pubkey(), false),
+            AccountMeta::new_readonly(nft.metadata, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+fn verified(key: Pubkey) -> Option<Collection> {
+    Some(Collection {
+        verified: true,
+        key,
+    })
+}
+
+#[tokio::test]
+async fn test_nft_vending_machine_burns_nft_and_writes() {
+    let (mut program_test, program_id) = program_test();
+    let collection = Pubkey::new_unique();
+    let nft = add_nft(
+        &mut program_test,
+        verified(collection),
+        mpl_token_metadata::id(),
+    );
+    let mut context = program_test.start_with_context().await;
+    let (user, user_token_account) = mint_nft(&mut context, &nft).await;
+    let buffer = initialize_nft_vending_machine(&mut context, &program_id, &collection).await;
+
+    send(
+        &mut context,
+        &[nft_vending_machine_echo_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            &user_token_account,
+            &nft,
+            vec![1; 4],
+        )],
+        &[&user],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(token_balance(&mut context, &user_token_account).await, 0);
+    let mint = Mint::unpack(&account_data(&mut context, &nft.mint.pubkey()).await).unwrap();
+    assert_eq!(mint.supply, 0);
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[NFT_VENDING_MACHINE_HEADER_SIZE..],
+        &[1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+
+    // the burned NFT can't pay for another write
+    let result = send(
+        &mut context,
+        &[nft_vending_machine_echo_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            &user_token_account,
+            &nft,
+            vec![2; 4],
+        )],
+        &[&user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidMint);
+}
+
+#[tokio::test]
+async fn test_nft_vending_machine_rejects_other_collections() {
+    let (mut program_test, program_id) = program_test();
+    let collection = Pubkey::new_unique();
+    let nfts = [
+        // unverified member of the collection
+        add_nft(
+            &mut program_test,
+            Some(Collection {
+                verified: false,
+                key: collection,
+            }),
+            mpl_token_metadata::id(),
+        ),
+        // verified member of another collection
+        add_nft(
+            &mut program_test,
+            verified(Pubkey::new_unique()),
+            mpl_token_metadata::id(),
+        ),
+        // no collection
+        add_nft(&mut program_test, None, mpl_token_metadata::id()),
+    ];
+    let spoofed = add_nft(
+        &mut program_test,
+        verified(collection),
+        Pubkey::new_unique(),
+    );
+    let mut context = program_test.start_with_context().await;
+    let buffer = initialize_nft_vending_machine(&mut context, &program_id, &collection).await;
+
+    for nft in &nfts {
+        let (user, user_token_account) = mint_nft(&mut context, nft).await;
+        let result = send(
+            &mut context,
+            &[nft_vending_machine_echo_ix(
+                &program_id,
+                &buffer,
+                &user.pubkey(),
+                &user_token_account,
+                nft,
+                vec![1; 4],
+            )],
+            &[&user],
+        )
+        .await;
+        assert_echo_error(result, EchoError::InvalidCollection);
+        assert_eq!(token_balance(&mut context, &user_token_account).await, 1);
+    }
+
+    // metadata not owned by the Token Metadata Program
+    let (user, user_token_account) = mint_nft(&mut context, &spoofed).await;
+    let result = send(
+        &mut context,
+        &[nft_vending_machine_echo_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            &user_token_account,
+            &spoofed,
+            vec![1; 4],
+        )],
+        &[&user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountOwner);
+}
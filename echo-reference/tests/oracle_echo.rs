@@ -0,0 +1,206 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::ORACLE_ECHO_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        account::Account,
+        signature::{Keypair, Signer},
+    },
+};
+
+const PRICE_OFFSET: u16 = 208;
+
+/// Price feed holding `price` at `PRICE_OFFSET`, like the aggregate price of a Pyth price account.
+fn mock_oracle(price: i64) -> Account {
+    let mut data = vec![0xAA; 240];
+    data[PRICE_OFFSET as usize..PRICE_OFFSET as usize + 8].copy_from_slice(&price.to_le_bytes());
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: Pubkey::new_unique(),
+        ..Account::default()
+    }
+}
+
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    oracle: &Pubkey,
+) -> (Keypair, Pubkey) {
+    let authority = Keypair::new();
+    let (buffer, _) =
+        Pubkey::find_program_address(&[b"oracle", authority.pubkey().as_ref()], program_id);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeOracleEcho {
+                buffer_size: ORACLE_ECHO_HEADER_SIZE + 8,
+                authority: authority.pubkey(),
+                oracle_pubkey: *oracle,
+                min_price: 100,
+                max_price: 200,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    (authority, buffer)
+}
+
+fn oracle_gated_write_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    oracle: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::OracleGatedWrite {
+            data,
+            oracle_offset: PRICE_OFFSET,
+        },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(*oracle, false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_oracle_gated_write_within_range() {
+    let (mut program_test, program_id) = program_test();
+    let oracle = Pubkey::new_unique();
+    program_test.add_account(oracle, mock_oracle(150));
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id, &oracle).await;
+
+    send(
+        &mut context,
+        &[oracle_gated_write_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &oracle,
+            vec![7; 4],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[ORACLE_ECHO_HEADER_SIZE..], &[7, 7, 7, 7, 0, 0, 0, 0]);
+}
+
+#[tokio::test]
+async fn test_oracle_gated_write_outside_range() {
+    let (mut program_test, program_id) = program_test();
+    let low_oracle = Pubkey::new_unique();
+    let high_oracle = Pubkey::new_unique();
+    program_test.add_account(low_oracle, mock_oracle(99));
+    program_test.add_account(high_oracle, mock_oracle(201));
+    let mut context = program_test.start_with_context().await;
+
+    for oracle in [low_oracle, high_oracle] {
+        let (authority, buffer) = setup(&mut context, &program_id, &oracle).await;
+        let result = send(
+            &mut context,
+            &[oracle_gated_write_ix(
+                &program_id,
+                &buffer,
+                &authority.pubkey(),
+                &oracle,
+                vec![7; 4],
+            )],
+            &[&authority],
+        )
+        .await;
+        assert_echo_error(result, EchoError::ConditionNotMet);
+    }
+}
+
+#[tokio::test]
+async fn test_oracle_gated_write_rejects_other_oracle() {
+    let (mut program_test, program_id) = program_test();
+    let oracle = Pubkey::new_unique();
+    let other_oracle = Pubkey::new_unique();
+    program_test.add_account(oracle, mock_oracle(50));
+    program_test.add_account(other_oracle, mock_oracle(150));
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id, &oracle).await;
+
+    let result = send(
+        &mut context,
+        &[oracle_gated_write_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &other_oracle,
+            vec![7; 4],
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn test_update_oracle_gate() {
+    let (mut program_test, program_id) = program_test();
+    let oracle = Pubkey::new_unique();
+    program_test.add_account(oracle, mock_oracle(250));
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id, &oracle).await;
+    let update_gate_ix = |new_min: i64, new_max: i64| {
+        Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::UpdateOracleGate { new_min, new_max },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(authority.pubkey(), true),
+            ],
+        )
+    };
+    let write_ix = || {
+        oracle_gated_write_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &oracle,
+            vec![7; 4],
+        )
+    };
+
+    let result = send(&mut context, &[write_ix()], &[&authority]).await;
+    assert_echo_error(result, EchoError::ConditionNotMet);
+
+    let result = send(&mut context, &[update_gate_ix(300, 200)], &[&authority]).await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+
+    send(
+        &mut context,
+        &[update_gate_ix(200, 300), write_ix()],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+}
@@ -0,0 +1,354 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        processor::ownership_echo::ownership_message,
+        state::{OwnershipEchoHeader, OWNERSHIP_ECHO_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_instruction, system_program, sysvar,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        ed25519_instruction::new_ed25519_instruction,
+        signature::{Keypair, Signer},
+    },
+};
+
+const BUFFER_SIZE: usize = OWNERSHIP_ECHO_HEADER_SIZE + 16;
+
+fn initialize_ownership_echo_ix(
+    program_id: &Pubkey,
+    asset: &Pubkey,
+    owner: &Pubkey,
+    asset_token_account: &Pubkey,
+) -> Instruction {
+    let (buffer, _) = Pubkey::find_program_address(&[b"ownership", asset.as_ref()], program_id);
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::InitializeOwnershipEcho {
+            buffer_size: BUFFER_SIZE,
+            asset: *asset,
+        },
+        vec![
+            AccountMeta::new(buffer, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(*asset_token_account, false),
+        ],
+    )
+}
+
+/// A mint of a single token, held by a new funded keypair.
+async fn create_asset(context: &mut ProgramTestContext) -> (Pubkey, Keypair, Pubkey) {
+    let mint_authority = Keypair::new();
+    let asset = create_mint(context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let holder = funded_keypair(context).await;
+    let holder_token_account = create_token_account(context, &asset, &holder.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(context, &asset, &holder_token_account, &mint_authority, 1).await;
+    (asset, holder, holder_token_account)
+}
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> (Pubkey, Keypair) {
+    let (asset, owner, owner_token_account) = create_asset(context).await;
+    let (buffer, _) = Pubkey::find_program_address(&[b"ownership", asset.as_ref()], program_id);
+    send(
+        context,
+        &[initialize_ownership_echo_ix(
+            program_id,
+            &asset,
+            &owner.pubkey(),
+            &owner_token_account,
+        )],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+    (buffer, owner)
+}
+
+/// The Ed25519 verification of `signer` approving the transfer to `new_owner`, followed by the claim itself.
+fn claim_ixs(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    signer: &Keypair,
+    transfer_count: u64,
+    new_owner: &Pubkey,
+    data: Vec<u8>,
+) -> [Instruction; 2] {
+    let message = ownership_message(buffer, transfer_count, new_owner, &data);
+    let signer = ed25519_dalek::Keypair::from_bytes(&signer.to_bytes()).unwrap();
+    [
+        new_ed25519_instruction(&signer, &message),
+        Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::ClaimOwnership { data },
+            vec![
+                AccountMeta::new(*buffer, false),
+                AccountMeta::new_readonly(*new_owner, true),
+                AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            ],
+        ),
+    ]
+}
+
+async fn header(context: &mut ProgramTestContext, buffer: &Pubkey) -> OwnershipEchoHeader {
+    let data = account_data(context, buffer).await;
+    OwnershipEchoHeader::try_from_slice(&data[..OWNERSHIP_ECHO_HEADER_SIZE]).unwrap()
+}
+
+#[tokio::test]
+async fn test_ownership_transfer_flow() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (buffer, owner) = setup(&mut context, &program_id).await;
+    assert_eq!(
+        header(&mut context, &buffer).await.current_owner,
+        owner.pubkey()
+    );
+
+    // the current owner approves off-chain, only the new owner signs the transaction
+    let new_owner = Keypair::new();
+    send(
+        &mut context,
+        &claim_ixs(
+            &program_id,
+            &buffer,
+            &owner,
+            0,
+            &new_owner.pubkey(),
+            vec![1; 20],
+        ),
+        &[&new_owner],
+    )
+    .await
+    .unwrap();
+
+    let buffer_header = header(&mut context, &buffer).await;
+    assert_eq!(buffer_header.current_owner, new_owner.pubkey());
+    assert_eq!(buffer_header.transfer_count, 1);
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[OWNERSHIP_ECHO_HEADER_SIZE..], &[1; 16]);
+
+    // the new owner approves the next transfer
+    let third_owner = Keypair::new();
+    send(
+        &mut context,
+        &claim_ixs(
+            &program_id,
+            &buffer,
+            &new_owner,
+            1,
+            &third_owner.pubkey(),
+            vec![2; 4],
+        ),
+        &[&third_owner],
+    )
+    .await
+    .unwrap();
+
+    let buffer_header = header(&mut context, &buffer).await;
+    assert_eq!(buffer_header.current_owner, third_owner.pubkey());
+    assert_eq!(buffer_header.transfer_count, 2);
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[OWNERSHIP_ECHO_HEADER_SIZE..],
+        &[2, 2, 2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
+
+#[tokio::test]
+async fn test_claim_ownership_rejects_invalid_approvals() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (buffer, owner) = setup(&mut context, &program_id).await;
+    let new_owner = Keypair::new();
+
+    // approved by someone other than the current owner
+    let result = send(
+        &mut context,
+        &claim_ixs(
+            &program_id,
+            &buffer,
+            &new_owner,
+            0,
+            &new_owner.pubkey(),
+            vec![1; 4],
+        ),
+        &[&new_owner],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidSignature);
+
+    // approved for another new owner
+    let other = Keypair::new();
+    let [ed25519_ix, _] = claim_ixs(&program_id, &buffer, &owner, 0, &other.pubkey(), vec![1; 4]);
+    let [_, claim_ix] = claim_ixs(
+        &program_id,
+        &buffer,
+        &owner,
+        0,
+        &new_owner.pubkey(),
+        vec![1; 4],
+    );
+    let result = send(&mut context, &[ed25519_ix, claim_ix.clone()], &[&new_owner]).await;
+    assert_echo_error(result, EchoError::InvalidSignature);
+
+    // without the Ed25519 instruction
+    let result = send(&mut context, &[claim_ix], &[&new_owner]).await;
+    assert_echo_error(result, EchoError::InvalidSignature);
+
+    assert_eq!(
+        header(&mut context, &buffer).await.current_owner,
+        owner.pubkey()
+    );
+}
+
+#[tokio::test]
+async fn test_claim_ownership_rejects_replayed_approval() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (buffer, owner) = setup(&mut context, &program_id).await;
+    let new_owner = Keypair::new();
+
+    // ownership goes to the new owner and back
+    send(
+        &mut context,
+        &claim_ixs(
+            &program_id,
+            &buffer,
+            &owner,
+            0,
+            &new_owner.pubkey(),
+            vec![1],
+        ),
+        &[&new_owner],
+    )
+    .await
+    .unwrap();
+    send(
+        &mut context,
+        &claim_ixs(
+            &program_id,
+            &buffer,
+            &new_owner,
+            1,
+            &owner.pubkey(),
+            vec![2],
+        ),
+        &[&owner],
+    )
+    .await
+    .unwrap();
+
+    // the first approval no longer matches the transfer count, a transfer first keeps the runtime from
+    // deduplicating the replayed transaction
+    let [verify_ix, claim_ix] = claim_ixs(
+        &program_id,
+        &buffer,
+        &owner,
+        0,
+        &new_owner.pubkey(),
+        vec![1],
+    );
+    let payer = context.payer.pubkey();
+    let result = send(
+        &mut context,
+        &[
+            system_instruction::transfer(&payer, &new_owner.pubkey(), 1),
+            verify_ix,
+            claim_ix,
+        ],
+        &[&new_owner],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidSignature);
+    assert_eq!(
+        header(&mut context, &buffer).await.current_owner,
+        owner.pubkey()
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_ownership_echo_requires_holding_the_asset() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (asset, holder, holder_token_account) = create_asset(&mut context).await;
+    let (_, _, other_asset_token_account) = create_asset(&mut context).await;
+
+    // the token account of the holder can't be borrowed to squat the record
+    let squatter = funded_keypair(&mut context).await;
+    let result = send(
+        &mut context,
+        &[initialize_ownership_echo_ix(
+            &program_id,
+            &asset,
+            &squatter.pubkey(),
+            &holder_token_account,
+        )],
+        &[&squatter],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    // nor can an empty token account of the asset
+    let empty_token_account = create_token_account(&mut context, &asset, &squatter.pubkey())
+        .await
+        .pubkey();
+    let result = send(
+        &mut context,
+        &[initialize_ownership_echo_ix(
+            &program_id,
+            &asset,
+            &squatter.pubkey(),
+            &empty_token_account,
+        )],
+        &[&squatter],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    // nor a token account of another asset
+    let result = send(
+        &mut context,
+        &[initialize_ownership_echo_ix(
+            &program_id,
+            &asset,
+            &holder.pubkey(),
+            &other_asset_token_account,
+        )],
+        &[&holder],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountData);
+
+    send(
+        &mut context,
+        &[initialize_ownership_echo_ix(
+            &program_id,
+            &asset,
+            &holder.pubkey(),
+            &holder_token_account,
+        )],
+        &[&holder],
+    )
+    .await
+    .unwrap();
+    let buffer = Pubkey::find_program_address(&[b"ownership", asset.as_ref()], &program_id).0;
+    assert_eq!(
+        header(&mut context, &buffer).await.current_owner,
+        holder.pubkey()
+    );
+}
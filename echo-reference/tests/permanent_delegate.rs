@@ -0,0 +1,321 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{registry_entries, AuthorizedBufferHeader, AUTH_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Creates a buffer with `delegate` as its permanent delegate and writes some data to it.
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    delegate: &Pubkey,
+) -> (Keypair, Pubkey) {
+    let authority = funded_keypair(context).await;
+    let (buffer, _) = authorized_buffer_address(program_id, &authority.pubkey(), 0);
+    send(
+        context,
+        &[
+            Instruction::new_with_borsh(
+                *program_id,
+                &EchoInstruction::InitializeAuthorizedEcho {
+                    buffer_seed: 0,
+                    buffer_size: AUTH_BUFF_HEADER_SIZE + 16,
+                    label: String::new(),
+                    permanent_delegate: Some(*delegate),
+                    write_fee_lamports: 0,
+                    expiry_slot: 0,
+                    crank_bounty_bps: 0,
+                },
+                vec![
+                    AccountMeta::new(buffer, false),
+                    AccountMeta::new(authority.pubkey(), true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            ),
+            authorized_echo_ix(program_id, &buffer, &authority.pubkey(), vec![3; 16]),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    (authority, buffer)
+}
+
+/// `instruction` with the authority passed unsigned and `delegate` signing after it.
+fn delegated_ix(
+    program_id: &Pubkey,
+    instruction: EchoInstruction,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    delegate: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &instruction,
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new(*authority, false),
+            AccountMeta::new_readonly(*delegate, true),
+        ],
+    )
+}
+
+/// `CloseAuthorizedEcho` signed by `delegate`, with the Echo Program id in place of a missing `registry`.
+fn delegated_close_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    registry: Option<&Pubkey>,
+    delegate: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::CloseAuthorizedEcho,
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new(*authority, false),
+            AccountMeta::new(*registry.unwrap_or(program_id), false),
+            AccountMeta::new_readonly(*delegate, true),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_permanent_delegate_can_clear() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let delegate = Keypair::new();
+    let (authority, buffer) = setup(&mut context, &program_id, &delegate.pubkey()).await;
+
+    send(
+        &mut context,
+        &[delegated_ix(
+            &program_id,
+            EchoInstruction::ClearAuthorizedBuffer,
+            &buffer,
+            &authority.pubkey(),
+            &delegate.pubkey(),
+        )],
+        &[&delegate],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    assert!(data[AUTH_BUFF_HEADER_SIZE..].iter().all(|b| *b == 0));
+    let header = AuthorizedBufferHeader::try_from_slice(&data[..AUTH_BUFF_HEADER_SIZE]).unwrap();
+    assert_eq!(header.permanent_delegate(), Some(delegate.pubkey()));
+}
+
+#[tokio::test]
+async fn test_permanent_delegate_cannot_write() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let delegate = Keypair::new();
+    let (authority, buffer) = setup(&mut context, &program_id, &delegate.pubkey()).await;
+
+    let result = send(
+        &mut context,
+        &[delegated_ix(
+            &program_id,
+            EchoInstruction::AuthorizedEcho { data: vec![9; 16] },
+            &buffer,
+            &authority.pubkey(),
+            &delegate.pubkey(),
+        )],
+        &[&delegate],
+    )
+    .await;
+    assert_echo_error(result, EchoError::MissingRequiredSignature);
+
+    // signing in place of the authority doesn't match the buffer address either
+    let result = send(
+        &mut context,
+        &[authorized_echo_ix(
+            &program_id,
+            &buffer,
+            &delegate.pubkey(),
+            vec![9; 16],
+        )],
+        &[&delegate],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[AUTH_BUFF_HEADER_SIZE..], &[3; 16]);
+}
+
+#[tokio::test]
+async fn test_permanent_delegate_close_pays_the_authority() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let delegate = Keypair::new();
+    let (authority, buffer) = setup(&mut context, &program_id, &delegate.pubkey()).await;
+
+    let buffer_lamports = context.banks_client.get_balance(buffer).await.unwrap();
+    let authority_lamports = context
+        .banks_client
+        .get_balance(authority.pubkey())
+        .await
+        .unwrap();
+
+    send(
+        &mut context,
+        &[delegated_close_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            None,
+            &delegate.pubkey(),
+        )],
+        &[&delegate],
+    )
+    .await
+    .unwrap();
+
+    assert!(context
+        .banks_client
+        .get_account(buffer)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(authority.pubkey())
+            .await
+            .unwrap(),
+        authority_lamports + buffer_lamports
+    );
+}
+
+#[tokio::test]
+async fn test_clear_rejects_signer_other_than_the_delegate() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let delegate = Keypair::new();
+    let intruder = Keypair::new();
+    let (authority, buffer) = setup(&mut context, &program_id, &delegate.pubkey()).await;
+
+    let result = send(
+        &mut context,
+        &[delegated_ix(
+            &program_id,
+            EchoInstruction::ClearAuthorizedBuffer,
+            &buffer,
+            &authority.pubkey(),
+            &intruder.pubkey(),
+        )],
+        &[&intruder],
+    )
+    .await;
+    assert_echo_error(result, EchoError::MissingRequiredSignature);
+}
+
+#[tokio::test]
+async fn test_permanent_delegate_close_removes_the_buffer_from_the_registry() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let delegate = Keypair::new();
+    let (authority, buffer) = setup(&mut context, &program_id, &delegate.pubkey()).await;
+    let (registry, _) =
+        Pubkey::find_program_address(&[b"registry", authority.pubkey().as_ref()], &program_id);
+    let (registered_buffer, _) = authorized_buffer_address(&program_id, &authority.pubkey(), 1);
+    send(
+        &mut context,
+        &[
+            Instruction::new_with_borsh(
+                program_id,
+                &EchoInstruction::InitializeRegistry { capacity: 1 },
+                vec![
+                    AccountMeta::new(registry, false),
+                    AccountMeta::new(authority.pubkey(), true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            ),
+            Instruction::new_with_borsh(
+                program_id,
+                &EchoInstruction::InitializeAuthorizedEcho {
+                    buffer_seed: 1,
+                    buffer_size: AUTH_BUFF_HEADER_SIZE + 16,
+                    label: String::new(),
+                    permanent_delegate: Some(delegate.pubkey()),
+                    write_fee_lamports: 0,
+                    expiry_slot: 0,
+                    crank_bounty_bps: 0,
+                },
+                vec![
+                    AccountMeta::new(registered_buffer, false),
+                    AccountMeta::new(authority.pubkey(), true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                    AccountMeta::new(registry, false),
+                ],
+            ),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    // the delegate isn't looked up in place of the registry, which would skip the removal
+    let result = send(
+        &mut context,
+        &[delegated_ix(
+            &program_id,
+            EchoInstruction::CloseAuthorizedEcho,
+            &registered_buffer,
+            &authority.pubkey(),
+            &delegate.pubkey(),
+        )],
+        &[&delegate],
+    )
+    .await;
+    assert_echo_error(result, EchoError::MissingRequiredSignature);
+
+    send(
+        &mut context,
+        &[delegated_close_ix(
+            &program_id,
+            &registered_buffer,
+            &authority.pubkey(),
+            Some(&registry),
+            &delegate.pubkey(),
+        )],
+        &[&delegate],
+    )
+    .await
+    .unwrap();
+
+    assert!(context
+        .banks_client
+        .get_account(registered_buffer)
+        .await
+        .unwrap()
+        .is_none());
+    let data = account_data(&mut context, &registry).await;
+    assert!(registry_entries(&data).unwrap().is_empty());
+
+    // the unregistered buffer is still there
+    assert!(context
+        .banks_client
+        .get_account(buffer)
+        .await
+        .unwrap()
+        .is_some());
+}
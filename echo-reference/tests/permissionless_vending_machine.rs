@@ -0,0 +1,164 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{VendingMachineBufferHeader, VENDING_MACHINE_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+fn free_vm_address(program_id: &Pubkey, creator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"free_vm", creator.as_ref()], program_id).0
+}
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> Pubkey {
+    let payer = context.payer.pubkey();
+    let buffer = free_vm_address(program_id, &payer);
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializePermissionlessVendingMachine {
+                buffer_size: VENDING_MACHINE_BUFF_HEADER_SIZE + 8,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    buffer
+}
+
+fn free_echo_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    user: &Pubkey,
+    creator: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::VendingMachineEcho { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new_readonly(*creator, false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_free_vending_machine_writes_without_burn() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let buffer = setup(&mut context, &program_id).await;
+    let creator = context.payer.pubkey();
+
+    let data = account_data(&mut context, &buffer).await;
+    let header =
+        VendingMachineBufferHeader::try_from_slice(&data[..VENDING_MACHINE_BUFF_HEADER_SIZE])
+            .unwrap();
+    assert!(header.free);
+    assert_eq!(header.price, 0);
+
+    for i in 0..3u8 {
+        let user = Keypair::new();
+        send(
+            &mut context,
+            &[free_echo_ix(
+                &program_id,
+                &buffer,
+                &user.pubkey(),
+                &creator,
+                vec![i + 1; 4],
+            )],
+            &[&user],
+        )
+        .await
+        .unwrap();
+    }
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[VENDING_MACHINE_BUFF_HEADER_SIZE..],
+        &[3, 3, 3, 3, 0, 0, 0, 0]
+    );
+}
+
+#[tokio::test]
+async fn test_free_vending_machine_ignores_token_accounts() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let buffer = setup(&mut context, &program_id).await;
+    let creator = context.payer.pubkey();
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+
+    let mut instruction = free_echo_ix(
+        &program_id,
+        &buffer,
+        &vm.user.pubkey(),
+        &creator,
+        vec![7; 8],
+    );
+    instruction.accounts.extend([
+        AccountMeta::new(vm.user_token_account, false),
+        AccountMeta::new(vm.mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ]);
+    send(&mut context, &[instruction], &[&vm.user])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        10
+    );
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[VENDING_MACHINE_BUFF_HEADER_SIZE..], &[7; 8]);
+}
+
+#[tokio::test]
+async fn test_free_vending_machine_rejects_wrong_creator() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let buffer = setup(&mut context, &program_id).await;
+    let user = Keypair::new();
+
+    let result = send(
+        &mut context,
+        &[free_echo_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            &Pubkey::new_unique(),
+            vec![7; 8],
+        )],
+        &[&user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+}
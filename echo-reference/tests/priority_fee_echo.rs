@@ -0,0 +1,233 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{FEE_TREASURY_SIZE, VENDING_MACHINE_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+const PRICE: u64 = 2;
+const SOL_FEE: u64 = 1_000_000;
+
+fn treasury_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"treasury", program_id.as_ref()], program_id).0
+}
+
+async fn initialize_treasury(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    authority: &Pubkey,
+) {
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeTreasury {
+                authority: *authority,
+            },
+            vec![
+                AccountMeta::new(treasury_address(program_id), false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+}
+
+/// A priority fee vending machine with a funded user, which pays the SOL fee from its own lamports.
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> VendingMachine {
+    let mint_authority = Keypair::new();
+    let mint = create_mint(context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let user = funded_keypair(context).await;
+    let user_token_account = create_token_account(context, &mint, &user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(context, &mint, &user_token_account, &mint_authority, 10).await;
+
+    let (buffer, _) = vending_machine_address(program_id, &mint, PRICE);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializePriorityFeeEcho {
+                price: PRICE,
+                buffer_size: VENDING_MACHINE_BUFF_HEADER_SIZE + 8,
+                sol_fee: SOL_FEE,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(mint_authority.pubkey(), true),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+
+    VendingMachine {
+        mint_authority,
+        mint,
+        user,
+        user_token_account,
+        buffer,
+        price: PRICE,
+    }
+}
+
+fn priority_fee_echo_ix(program_id: &Pubkey, vm: &VendingMachine, data: Vec<u8>) -> Instruction {
+    let mut instruction = vending_machine_echo_ix(
+        program_id,
+        &vm.buffer,
+        &vm.user.pubkey(),
+        &vm.user_token_account,
+        &vm.mint,
+        data,
+    );
+    instruction.accounts[1] = AccountMeta::new(vm.user.pubkey(), true);
+    instruction.accounts.extend([
+        AccountMeta::new(treasury_address(program_id), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]);
+    instruction
+}
+
+fn drain_treasury_ix(program_id: &Pubkey, authority: &Pubkey, destination: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::DrainTreasury {
+            destination: *destination,
+        },
+        vec![
+            AccountMeta::new(treasury_address(program_id), false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*destination, false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_priority_fees_accumulate_and_drain() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = Keypair::new();
+    initialize_treasury(&mut context, &program_id, &authority.pubkey()).await;
+    let vm = setup(&mut context, &program_id).await;
+    let treasury = treasury_address(&program_id);
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let user_lamports = context
+        .banks_client
+        .get_balance(vm.user.pubkey())
+        .await
+        .unwrap();
+
+    for data in [vec![1; 8], vec![2; 8], vec![3; 8]] {
+        send(
+            &mut context,
+            &[priority_fee_echo_ix(&program_id, &vm, data)],
+            &[&vm.user],
+        )
+        .await
+        .unwrap();
+    }
+
+    assert_eq!(
+        context.banks_client.get_balance(treasury).await.unwrap(),
+        rent.minimum_balance(FEE_TREASURY_SIZE) + 3 * SOL_FEE
+    );
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(vm.user.pubkey())
+            .await
+            .unwrap(),
+        user_lamports - 3 * SOL_FEE
+    );
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        10 - 3 * PRICE
+    );
+
+    // only the treasury authority can drain the fees
+    let destination = Keypair::new().pubkey();
+    let result = send(
+        &mut context,
+        &[drain_treasury_ix(
+            &program_id,
+            &vm.user.pubkey(),
+            &destination,
+        )],
+        &[&vm.user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    send(
+        &mut context,
+        &[drain_treasury_ix(
+            &program_id,
+            &authority.pubkey(),
+            &destination,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        context.banks_client.get_balance(destination).await.unwrap(),
+        3 * SOL_FEE
+    );
+    assert_eq!(
+        context.banks_client.get_balance(treasury).await.unwrap(),
+        rent.minimum_balance(FEE_TREASURY_SIZE)
+    );
+}
+
+#[tokio::test]
+async fn test_priority_fee_required_before_the_burn() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup(&mut context, &program_id).await;
+
+    // the treasury doesn't exist yet
+    let result = send(
+        &mut context,
+        &[priority_fee_echo_ix(&program_id, &vm, vec![1; 8])],
+        &[&vm.user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::FeeTreasuryNotInitialized);
+
+    // without the fee accounts
+    let authority = Keypair::new();
+    initialize_treasury(&mut context, &program_id, &authority.pubkey()).await;
+    let mut write = priority_fee_echo_ix(&program_id, &vm, vec![1; 8]);
+    write.accounts.truncate(5);
+    assert!(send(&mut context, &[write], &[&vm.user]).await.is_err());
+
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        10
+    );
+}
@@ -0,0 +1,157 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{
+        error::EchoError, instruction::EchoInstruction, state::PROGRAM_CONTROLLED_BUFF_HEADER_SIZE,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction},
+        program::invoke,
+        pubkey::Pubkey,
+        system_program, sysvar,
+    },
+    solana_program_test::{processor, tokio},
+    solana_sdk::signature::Signer,
+};
+
+/// Forwards its instruction data as a `ProgramControlledEcho` write.
+/// Accounts: [echo_program, program_controlled_buffer, instructions_sysvar]
+fn mock_caller_process(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = Instruction::new_with_borsh(
+        *accounts[0].key,
+        &EchoInstruction::ProgramControlledEcho {
+            data: instruction_data.to_vec(),
+        },
+        vec![
+            AccountMeta::new(*accounts[1].key, false),
+            AccountMeta::new_readonly(*accounts[2].key, false),
+        ],
+    );
+    invoke(&instruction, &accounts[1..])
+}
+
+fn program_controlled_buffer_address(program_id: &Pubkey, allowed_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"program_controlled", allowed_program.as_ref()],
+        program_id,
+    )
+    .0
+}
+
+fn initialize_program_controlled_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    allowed_program: &Pubkey,
+    buffer_size: usize,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::InitializeProgramControlled {
+            buffer_size,
+            allowed_program: *allowed_program,
+        },
+        vec![
+            AccountMeta::new(
+                program_controlled_buffer_address(program_id, allowed_program),
+                false,
+            ),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_program_controlled_echo_via_cpi() {
+    let (mut program_test, program_id) = program_test();
+    let mock_program_id = Pubkey::new_unique();
+    program_test.add_program(
+        "mock_caller",
+        mock_program_id,
+        processor!(mock_caller_process),
+    );
+    let mut context = program_test.start_with_context().await;
+    let buffer = program_controlled_buffer_address(&program_id, &mock_program_id);
+
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[initialize_program_controlled_ix(
+            &program_id,
+            &payer,
+            &mock_program_id,
+            64,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    send(
+        &mut context,
+        &[Instruction {
+            program_id: mock_program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(program_id, false),
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            ],
+            data: vec![9; 4],
+        }],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[PROGRAM_CONTROLLED_BUFF_HEADER_SIZE..PROGRAM_CONTROLLED_BUFF_HEADER_SIZE + 4],
+        &[9; 4]
+    );
+}
+
+#[tokio::test]
+async fn test_program_controlled_echo_rejects_direct_call() {
+    let (program_test, program_id) = program_test();
+    let allowed_program = Pubkey::new_unique();
+    let mut context = program_test.start_with_context().await;
+    let buffer = program_controlled_buffer_address(&program_id, &allowed_program);
+
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[initialize_program_controlled_ix(
+            &program_id,
+            &payer,
+            &allowed_program,
+            64,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::ProgramControlledEcho { data: vec![9; 4] },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+}
@@ -0,0 +1,206 @@
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    echo::state::{
+        AuthorizedBufferHeader, PriceTier, VendingMachineBuffer, VendingMachineBufferHeader,
+        AUTH_BUFF_HEADER_SIZE, MAX_BUFFER_LABEL_LEN, VENDING_MACHINE_BUFF_HEADER_SIZE,
+    },
+    proptest::prelude::*,
+    solana_program::pubkey::Pubkey,
+};
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+fn price_tier() -> impl Strategy<Value = PriceTier> {
+    (any::<u32>(), any::<u64>()).prop_map(|(max_len, price)| PriceTier { max_len, price })
+}
+
+fn vending_machine_header() -> impl Strategy<Value = VendingMachineBufferHeader> {
+    (
+        (
+            any::<u8>(),
+            any::<u64>(),
+            any::<bool>(),
+            any::<u8>(),
+            pubkey(),
+            pubkey(),
+            any::<bool>(),
+        ),
+        (
+            any::<u64>(),
+            any::<bool>(),
+            pubkey(),
+            pubkey(),
+            any::<u64>(),
+            any::<u64>(),
+            any::<u64>(),
+            any::<u8>(),
+            proptest::array::uniform4(price_tier()),
+        ),
+        // proptest implements `Strategy` for tuples of up to 12 elements
+        (
+            pubkey(),
+            any::<u64>(),
+            any::<u64>(),
+            any::<u64>(),
+            any::<u64>(),
+            any::<u32>(),
+            any::<bool>(),
+            any::<u64>(),
+            any::<u64>(),
+            any::<u64>(),
+            any::<bool>(),
+            any::<bool>(),
+        ),
+    )
+        .prop_map(
+            |(
+                (bump_seed, price, free, payment_mode, treasury, admin, paused),
+                (
+                    price_per_byte,
+                    require_ata,
+                    token_program,
+                    mint,
+                    uses,
+                    max_uses,
+                    sol_fee,
+                    tier_count,
+                    tiers,
+                ),
+                (
+                    last_writer,
+                    last_write_slot,
+                    expiry_slot,
+                    total_writes,
+                    total_burned,
+                    data_len,
+                    is_initialized,
+                    start_slot,
+                    end_slot,
+                    cooldown_slots,
+                    whitelist_enabled,
+                    free_authority_writes,
+                ),
+            )| VendingMachineBufferHeader {
+                bump_seed,
+                price,
+                free,
+                payment_mode,
+                treasury,
+                admin,
+                paused,
+                price_per_byte,
+                require_ata,
+                token_program,
+                mint,
+                uses,
+                max_uses,
+                sol_fee,
+                tier_count,
+                tiers,
+                last_writer,
+                last_write_slot,
+                expiry_slot,
+                total_writes,
+                total_burned,
+                data_len,
+                is_initialized,
+                start_slot,
+                end_slot,
+                cooldown_slots,
+                whitelist_enabled,
+                free_authority_writes,
+            },
+        )
+}
+
+fn authorized_header() -> impl Strategy<Value = AuthorizedBufferHeader> {
+    (
+        (
+            any::<u8>(),
+            any::<u64>(),
+            any::<u32>(),
+            any::<[u8; 32]>(),
+            any::<u64>(),
+            any::<bool>(),
+            any::<[u8; MAX_BUFFER_LABEL_LEN]>(),
+        ),
+        (
+            any::<bool>(),
+            pubkey(),
+            any::<u64>(),
+            any::<u64>(),
+            any::<u64>(),
+            any::<u16>(),
+            any::<bool>(),
+        ),
+    )
+        .prop_map(
+            |(
+                (bump_seed, buffer_seed, cursor, commitment, commit_slot, has_commitment, label),
+                (
+                    has_permanent_delegate,
+                    permanent_delegate,
+                    sequence,
+                    write_fee_lamports,
+                    expiry_slot,
+                    crank_bounty_bps,
+                    write_in_progress,
+                ),
+            )| AuthorizedBufferHeader {
+                bump_seed,
+                buffer_seed,
+                cursor,
+                commitment,
+                commit_slot,
+                has_commitment,
+                label,
+                has_permanent_delegate,
+                permanent_delegate,
+                sequence,
+                write_fee_lamports,
+                expiry_slot,
+                crank_bounty_bps,
+                write_in_progress,
+            },
+        )
+}
+
+proptest! {
+    #[test]
+    fn test_vending_machine_header_round_trip(header in vending_machine_header()) {
+        let bytes = header.try_to_vec().unwrap();
+        prop_assert_eq!(bytes.len(), VENDING_MACHINE_BUFF_HEADER_SIZE);
+        prop_assert_eq!(VendingMachineBufferHeader::try_from_slice(&bytes).unwrap(), header);
+    }
+
+    #[test]
+    fn test_vending_machine_header_mint_offset(header in vending_machine_header()) {
+        // getProgramAccounts memcmp filters find the machines of a mint at this offset
+        let bytes = header.try_to_vec().unwrap();
+        prop_assert_eq!(&bytes[117..149], header.mint.as_ref());
+    }
+
+    #[test]
+    fn test_vending_machine_payload(
+        header in vending_machine_header(),
+        payload in proptest::collection::vec(any::<u8>(), 0..64),
+        padding in 0usize..64,
+    ) {
+        let header = VendingMachineBufferHeader { data_len: payload.len() as u32, ..header };
+        let mut bytes = header.try_to_vec().unwrap();
+        bytes.extend_from_slice(&payload);
+        bytes.resize(bytes.len() + padding, 0);
+        let buffer = VendingMachineBuffer::unpack(&bytes).unwrap();
+        prop_assert_eq!(&buffer.header, &header);
+        prop_assert_eq!(buffer.payload(), &payload[..]);
+    }
+
+    #[test]
+    fn test_authorized_header_round_trip(header in authorized_header()) {
+        let bytes = header.try_to_vec().unwrap();
+        prop_assert_eq!(bytes.len(), AUTH_BUFF_HEADER_SIZE);
+        prop_assert_eq!(AuthorizedBufferHeader::try_from_slice(&bytes).unwrap(), header);
+    }
+}
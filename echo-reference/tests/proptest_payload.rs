@@ -0,0 +1,58 @@
+use {echo::processor::vending_machine_echo::copy_payload, proptest::prelude::*};
+
+/// The write of the first vending machines, every byte of the payload past `data` was zeroed on each write.
+fn copy_payload_byte_by_byte(buffer_data: &mut [u8], data: &[u8]) -> u64 {
+    for index in 0..buffer_data.len() {
+        buffer_data[index] = match index < data.len() {
+            true => data[index],
+            false => 0,
+        };
+    }
+
+    buffer_data.len().min(data.len()) as u64
+}
+
+/// A payload of `len` bytes holding random data up to `data_len` and zeros after it, as left by the previous write.
+fn previous_payload() -> impl Strategy<Value = (Vec<u8>, u32)> {
+    (0usize..1024)
+        .prop_flat_map(|len| (proptest::collection::vec(any::<u8>(), len), 0..=len))
+        .prop_map(|(mut payload, data_len)| {
+            payload[data_len..].fill(0);
+            (payload, data_len as u32)
+        })
+}
+
+proptest! {
+    #[test]
+    fn test_copy_payload_matches_byte_by_byte_write(
+        (payload, data_len) in previous_payload(),
+        data in proptest::collection::vec(any::<u8>(), 0..1280),
+    ) {
+        let mut expected = payload.clone();
+        let expected_len = copy_payload_byte_by_byte(&mut expected, &data);
+
+        let mut actual = payload;
+        let len = copy_payload(&mut actual, &data, None, data_len);
+
+        prop_assert_eq!(len, expected_len);
+        prop_assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_copy_payload_at_offset_keeps_other_bytes(
+        (payload, data_len) in previous_payload(),
+        data in proptest::collection::vec(any::<u8>(), 0..64),
+        offset in any::<prop::sample::Index>(),
+    ) {
+        prop_assume!(data.len() <= payload.len());
+        let offset = offset.index(payload.len() - data.len() + 1);
+
+        let mut actual = payload.clone();
+        let len = copy_payload(&mut actual, &data, Some(offset as u64), data_len);
+
+        prop_assert_eq!(len, data.len() as u64);
+        prop_assert_eq!(&actual[..offset], &payload[..offset]);
+        prop_assert_eq!(&actual[offset..offset + data.len()], &data[..]);
+        prop_assert_eq!(&actual[offset + data.len()..], &payload[offset + data.len()..]);
+    }
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{instruction::EchoInstruction, state::RANDOM_HEADER_SIZE},
+    solana_program::{
+        clock::Clock,
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program, sysvar,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> (Keypair, Pubkey) {
+    let authority = Keypair::new();
+    let (buffer, _) =
+        Pubkey::find_program_address(&[b"random", authority.pubkey().as_ref()], program_id);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeRandomEcho {
+                buffer_size: RANDOM_HEADER_SIZE + 64,
+                authority: authority.pubkey(),
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    (authority, buffer)
+}
+
+fn refresh_random_ix(program_id: &Pubkey, buffer: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::RefreshRandom,
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(sysvar::slot_hashes::id(), false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_refresh_random_is_idempotent_within_a_slot() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id).await;
+
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    context.warp_to_slot(clock.slot + 2).unwrap();
+
+    // both refreshes run in the same slot, XORing twice would zero the data again
+    send(
+        &mut context,
+        &[
+            refresh_random_ix(&program_id, &buffer, &authority.pubkey()),
+            refresh_random_ix(&program_id, &buffer, &authority.pubkey()),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    assert!(data[RANDOM_HEADER_SIZE..].iter().any(|byte| *byte != 0));
+}
+
+#[tokio::test]
+async fn test_refresh_random_changes_with_the_slot() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id).await;
+
+    let mut previous = account_data(&mut context, &buffer).await;
+    for _ in 0..3 {
+        let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+        context.warp_to_slot(clock.slot + 2).unwrap();
+
+        send(
+            &mut context,
+            &[refresh_random_ix(&program_id, &buffer, &authority.pubkey())],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+
+        let data = account_data(&mut context, &buffer).await;
+        assert_ne!(&data[RANDOM_HEADER_SIZE..], &previous[RANDOM_HEADER_SIZE..]);
+        previous = data;
+    }
+}
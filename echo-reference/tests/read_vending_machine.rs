@@ -0,0 +1,293 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{
+            PriceTier, VendingMachineBufferHeader, MAX_PRICE_TIERS,
+            VENDING_MACHINE_BUFF_HEADER_SIZE,
+        },
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction},
+        program::{get_return_data, invoke},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    solana_program_test::{processor, tokio, ProgramTestContext},
+    solana_sdk::{account::Account, signature::Signer},
+};
+
+/// Reads the vending machine through CPI and fails unless the returned bytes are the ones at `offset` of the payload.
+/// Accounts: [echo_program, vending_machine_buffer]
+fn fixture_process(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (offset, len) = <(u64, u64)>::try_from_slice(instruction_data)?;
+    let instruction = Instruction::new_with_borsh(
+        *accounts[0].key,
+        &EchoInstruction::ReadVendingMachineEcho { offset, len },
+        vec![AccountMeta::new_readonly(*accounts[1].key, false)],
+    );
+    invoke(&instruction, &accounts[1..])?;
+
+    let (returned_by, return_data) = get_return_data().ok_or(ProgramError::InvalidAccountData)?;
+    let start = VENDING_MACHINE_BUFF_HEADER_SIZE + offset as usize;
+    let expected = &accounts[1].data.borrow()[start..start + len as usize];
+    if returned_by != *accounts[0].key || return_data != expected {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+fn read_ix(
+    fixture_program_id: &Pubkey,
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    offset: u64,
+    len: u64,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *fixture_program_id,
+        &(offset, len),
+        vec![
+            AccountMeta::new_readonly(*program_id, false),
+            AccountMeta::new_readonly(*buffer, false),
+        ],
+    )
+}
+
+/// Pays for a write of `data`, at `offset` of the payload if there is one.
+async fn write(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    vm: &VendingMachine,
+    offset: Option<u64>,
+    data: Vec<u8>,
+) {
+    let mut instruction = vending_machine_echo_ix(
+        program_id,
+        &vm.buffer,
+        &vm.user.pubkey(),
+        &vm.user_token_account,
+        &vm.mint,
+        vec![],
+    );
+    instruction.data = match offset {
+        Some(offset) => EchoInstruction::VendingMachineEchoWriteAt { offset, data },
+        None => EchoInstruction::VendingMachineEcho { data },
+    }
+    .try_to_vec()
+    .unwrap();
+    send(context, &[instruction], &[&vm.user]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_read_vending_machine_returns_data() {
+    let (mut program_test, program_id) = program_test();
+    let fixture_program_id = Pubkey::new_unique();
+    program_test.add_program(
+        "echo_read_fixture",
+        fixture_program_id,
+        processor!(fixture_process),
+    );
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 32,
+        20,
+    )
+    .await;
+    write(&mut context, &program_id, &vm, None, (0..16).collect()).await;
+    let data = account_data(&mut context, &vm.buffer).await;
+    assert_eq!(
+        &data[VENDING_MACHINE_BUFF_HEADER_SIZE..VENDING_MACHINE_BUFF_HEADER_SIZE + 16],
+        &(0..16).collect::<Vec<u8>>()[..]
+    );
+
+    // nobody but the fee payer signs the reads
+    send(
+        &mut context,
+        &[
+            read_ix(&fixture_program_id, &program_id, &vm.buffer, 4, 8),
+            read_ix(&fixture_program_id, &program_id, &vm.buffer, 0, 16),
+            read_ix(&fixture_program_id, &program_id, &vm.buffer, 16, 0),
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    // reading is free
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        15
+    );
+
+    // only the data written so far can be read, not the padding past it
+    let result = send(
+        &mut context,
+        &[read_ix(&fixture_program_id, &program_id, &vm.buffer, 10, 8)],
+        &[],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+    let result = send(
+        &mut context,
+        &[read_ix(
+            &fixture_program_id,
+            &program_id,
+            &vm.buffer,
+            u64::MAX,
+            1,
+        )],
+        &[],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+
+    // a shorter write shrinks what can be read
+    write(&mut context, &program_id, &vm, None, vec![9; 4]).await;
+    let result = send(
+        &mut context,
+        &[read_ix(&fixture_program_id, &program_id, &vm.buffer, 0, 8)],
+        &[],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+    send(
+        &mut context,
+        &[read_ix(&fixture_program_id, &program_id, &vm.buffer, 0, 4)],
+        &[],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_read_vending_machine_rejects_large_reads() {
+    let (mut program_test, program_id) = program_test();
+    let fixture_program_id = Pubkey::new_unique();
+    program_test.add_program(
+        "echo_read_fixture",
+        fixture_program_id,
+        processor!(fixture_process),
+    );
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 1200,
+        20,
+    )
+    .await;
+
+    // a transaction can't carry the whole data, it is written in parts
+    for offset in [0, 400, 800] {
+        write(&mut context, &program_id, &vm, Some(offset), vec![1; 400]).await;
+    }
+
+    send(
+        &mut context,
+        &[read_ix(
+            &fixture_program_id,
+            &program_id,
+            &vm.buffer,
+            100,
+            1024,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    let result = send(
+        &mut context,
+        &[read_ix(
+            &fixture_program_id,
+            &program_id,
+            &vm.buffer,
+            0,
+            1025,
+        )],
+        &[],
+    )
+    .await;
+    assert_echo_error(result, EchoError::DataTooLarge);
+}
+
+#[tokio::test]
+async fn test_read_vending_machine_rejects_spoofed_buffer() {
+    let (mut program_test, program_id) = program_test();
+    let fixture_program_id = Pubkey::new_unique();
+    program_test.add_program(
+        "echo_read_fixture",
+        fixture_program_id,
+        processor!(fixture_process),
+    );
+
+    // a buffer of the Echo Program whose header doesn't derive its address
+    let buffer = Pubkey::new_unique();
+    let mut data = VendingMachineBufferHeader {
+        bump_seed: 255,
+        price: 5,
+        free: false,
+        payment_mode: 0,
+        treasury: Pubkey::default(),
+        admin: Pubkey::default(),
+        paused: false,
+        price_per_byte: 0,
+        require_ata: false,
+        token_program: spl_token::id(),
+        mint: Pubkey::new_unique(),
+        uses: 0,
+        max_uses: 0,
+        sol_fee: 0,
+        tier_count: 0,
+        tiers: [PriceTier::default(); MAX_PRICE_TIERS],
+        last_writer: Pubkey::default(),
+        last_write_slot: 0,
+        expiry_slot: 0,
+        total_writes: 0,
+        total_burned: 0,
+        data_len: 8,
+        is_initialized: true,
+        start_slot: 0,
+        end_slot: 0,
+        cooldown_slots: 0,
+        whitelist_enabled: false,
+        free_authority_writes: false,
+    }
+    .try_to_vec()
+    .unwrap();
+    data.resize(VENDING_MACHINE_BUFF_HEADER_SIZE + 8, 1);
+    program_test.add_account(
+        buffer,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let result = send(
+        &mut context,
+        &[read_ix(&fixture_program_id, &program_id, &buffer, 0, 8)],
+        &[],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+}
@@ -0,0 +1,179 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{registry_entries, AUTH_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+fn registry_address(program_id: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"registry", authority.as_ref()], program_id).0
+}
+
+fn initialize_registered_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    buffer_seed: u64,
+) -> Instruction {
+    let mut ix = initialize_authorized_echo_ix(
+        program_id,
+        authority,
+        buffer_seed,
+        AUTH_BUFF_HEADER_SIZE + 64,
+    );
+    ix.accounts.push(AccountMeta::new(
+        registry_address(program_id, authority),
+        false,
+    ));
+    ix
+}
+
+fn close_ix(program_id: &Pubkey, authority: &Pubkey, buffer_seed: u64) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::CloseAuthorizedEcho,
+        vec![
+            AccountMeta::new(
+                authorized_buffer_address(program_id, authority, buffer_seed).0,
+                false,
+            ),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(registry_address(program_id, authority), false),
+        ],
+    )
+}
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey, capacity: u16) -> Keypair {
+    let authority = funded_keypair(context).await;
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeRegistry { capacity },
+            vec![
+                AccountMeta::new(registry_address(program_id, &authority.pubkey()), false),
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    authority
+}
+
+async fn entries(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    authority: &Pubkey,
+) -> Vec<(u64, Pubkey)> {
+    let data = account_data(context, &registry_address(program_id, authority)).await;
+    registry_entries(&data).unwrap()
+}
+
+#[tokio::test]
+async fn test_registry_full() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = setup(&mut context, &program_id, 2).await;
+
+    send(
+        &mut context,
+        &[
+            initialize_registered_ix(&program_id, &authority.pubkey(), 4),
+            initialize_registered_ix(&program_id, &authority.pubkey(), 9),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let buffer = |seed| authorized_buffer_address(&program_id, &authority.pubkey(), seed).0;
+    assert_eq!(
+        entries(&mut context, &program_id, &authority.pubkey()).await,
+        vec![(4, buffer(4)), (9, buffer(9))]
+    );
+
+    let result = send(
+        &mut context,
+        &[initialize_registered_ix(
+            &program_id,
+            &authority.pubkey(),
+            5,
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::RegistryFull);
+
+    // the failed registration rolls back the buffer creation too
+    let account = context.banks_client.get_account(buffer(5)).await.unwrap();
+    assert!(account.is_none());
+}
+
+#[tokio::test]
+async fn test_close_removes_registry_entry() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = setup(&mut context, &program_id, 3).await;
+    let buffer = |seed| authorized_buffer_address(&program_id, &authority.pubkey(), seed).0;
+
+    send(
+        &mut context,
+        &[
+            initialize_registered_ix(&program_id, &authority.pubkey(), 0),
+            initialize_registered_ix(&program_id, &authority.pubkey(), 1),
+            initialize_registered_ix(&program_id, &authority.pubkey(), 2),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let balance_before = context
+        .banks_client
+        .get_balance(authority.pubkey())
+        .await
+        .unwrap();
+    send(
+        &mut context,
+        &[close_ix(&program_id, &authority.pubkey(), 1)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        entries(&mut context, &program_id, &authority.pubkey()).await,
+        vec![(0, buffer(0)), (2, buffer(2))]
+    );
+    let account = context.banks_client.get_account(buffer(1)).await.unwrap();
+    assert!(account.is_none());
+    let balance_after = context
+        .banks_client
+        .get_balance(authority.pubkey())
+        .await
+        .unwrap();
+    assert!(balance_after > balance_before);
+
+    // a closed buffer can't be closed again
+    let result = send(
+        &mut context,
+        &[close_ix(&program_id, &authority.pubkey(), 1)],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountOwner);
+}
@@ -0,0 +1,248 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{REPLICATED_ECHO_HEADER_SIZE, REPLICA_ROLE_PRIMARY, REPLICA_ROLE_REPLICA},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+const PAYLOAD_SIZE: usize = 8;
+
+struct Pair {
+    primary_authority: Keypair,
+    replica_authority: Keypair,
+    primary: Pubkey,
+    replica: Pubkey,
+}
+
+fn replicated_address(program_id: &Pubkey, primary: &Pubkey, replica: &Pubkey, role: u8) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"replicated", primary.as_ref(), replica.as_ref(), &[role]],
+        program_id,
+    )
+    .0
+}
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> Pair {
+    let primary_authority = Keypair::new();
+    let replica_authority = Keypair::new();
+    let primary = replicated_address(
+        program_id,
+        &primary_authority.pubkey(),
+        &replica_authority.pubkey(),
+        REPLICA_ROLE_PRIMARY,
+    );
+    let replica = replicated_address(
+        program_id,
+        &primary_authority.pubkey(),
+        &replica_authority.pubkey(),
+        REPLICA_ROLE_REPLICA,
+    );
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeReplicatedEcho {
+                primary_authority: primary_authority.pubkey(),
+                replica_authority: replica_authority.pubkey(),
+                buffer_size: REPLICATED_ECHO_HEADER_SIZE + PAYLOAD_SIZE,
+            },
+            vec![
+                AccountMeta::new(primary, false),
+                AccountMeta::new(replica, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    Pair {
+        primary_authority,
+        replica_authority,
+        primary,
+        replica,
+    }
+}
+
+fn replicated_write_ix(program_id: &Pubkey, pair: &Pair, data: Vec<u8>) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::ReplicatedWrite { data },
+        vec![
+            AccountMeta::new(pair.primary, false),
+            AccountMeta::new(pair.replica, false),
+            AccountMeta::new_readonly(pair.primary_authority.pubkey(), true),
+            AccountMeta::new_readonly(pair.replica_authority.pubkey(), true),
+        ],
+    )
+}
+
+fn set_frozen_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    frozen: bool,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::SetReplicaFrozen { frozen },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+async fn payloads(context: &mut ProgramTestContext, pair: &Pair) -> (Vec<u8>, Vec<u8>) {
+    (
+        account_data(context, &pair.primary).await[REPLICATED_ECHO_HEADER_SIZE..].to_vec(),
+        account_data(context, &pair.replica).await[REPLICATED_ECHO_HEADER_SIZE..].to_vec(),
+    )
+}
+
+#[tokio::test]
+async fn test_replicated_write_to_both_buffers() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let pair = setup(&mut context, &program_id).await;
+
+    send(
+        &mut context,
+        &[replicated_write_ix(&program_id, &pair, vec![1, 2, 3])],
+        &[&pair.primary_authority, &pair.replica_authority],
+    )
+    .await
+    .unwrap();
+
+    let (primary, replica) = payloads(&mut context, &pair).await;
+    assert_eq!(primary, [1, 2, 3, 0, 0, 0, 0, 0]);
+    assert_eq!(primary, replica);
+
+    // data larger than the payloads writes nothing
+    let result = send(
+        &mut context,
+        &[replicated_write_ix(
+            &program_id,
+            &pair,
+            vec![4; PAYLOAD_SIZE + 1],
+        )],
+        &[&pair.primary_authority, &pair.replica_authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::BufferFull);
+    assert_eq!(payloads(&mut context, &pair).await.0, primary);
+}
+
+#[tokio::test]
+async fn test_frozen_replica_blocks_replicated_write() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let pair = setup(&mut context, &program_id).await;
+
+    send(
+        &mut context,
+        &[replicated_write_ix(&program_id, &pair, vec![1; 4])],
+        &[&pair.primary_authority, &pair.replica_authority],
+    )
+    .await
+    .unwrap();
+
+    // only the replica authority can freeze the replica
+    let result = send(
+        &mut context,
+        &[set_frozen_ix(
+            &program_id,
+            &pair.replica,
+            &pair.primary_authority.pubkey(),
+            true,
+        )],
+        &[&pair.primary_authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    send(
+        &mut context,
+        &[set_frozen_ix(
+            &program_id,
+            &pair.replica,
+            &pair.replica_authority.pubkey(),
+            true,
+        )],
+        &[&pair.replica_authority],
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut context,
+        &[replicated_write_ix(&program_id, &pair, vec![2; 4])],
+        &[&pair.primary_authority, &pair.replica_authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::BufferFrozen);
+
+    // the primary isn't written either
+    let (primary, replica) = payloads(&mut context, &pair).await;
+    assert_eq!(primary, [1, 1, 1, 1, 0, 0, 0, 0]);
+    assert_eq!(primary, replica);
+
+    send(
+        &mut context,
+        &[
+            set_frozen_ix(
+                &program_id,
+                &pair.replica,
+                &pair.replica_authority.pubkey(),
+                false,
+            ),
+            replicated_write_ix(&program_id, &pair, vec![2; 4]),
+        ],
+        &[&pair.primary_authority, &pair.replica_authority],
+    )
+    .await
+    .unwrap();
+    let (primary, replica) = payloads(&mut context, &pair).await;
+    assert_eq!(primary, [2, 2, 2, 2, 0, 0, 0, 0]);
+    assert_eq!(primary, replica);
+}
+
+#[tokio::test]
+async fn test_replicated_write_requires_both_authorities() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let pair = setup(&mut context, &program_id).await;
+
+    // the buffers passed in the wrong order
+    let mut write = replicated_write_ix(&program_id, &pair, vec![1; 4]);
+    write.accounts.swap(0, 1);
+    let result = send(
+        &mut context,
+        &[write],
+        &[&pair.primary_authority, &pair.replica_authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountData);
+
+    // another key signing for the replica authority
+    let other = Keypair::new();
+    let mut write = replicated_write_ix(&program_id, &pair, vec![1; 4]);
+    write.accounts[3] = AccountMeta::new_readonly(other.pubkey(), true);
+    let result = send(&mut context, &[write], &[&pair.primary_authority, &other]).await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+}
@@ -0,0 +1,125 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{SchemaEchoHeader, SCHEMA_ECHO_HEADER_SIZE},
+    },
+    solana_program::{
+        hash::hash,
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+const SCHEMA: &str = "struct Reading { sensor: u32, value: i64 }";
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> (Keypair, Pubkey) {
+    let authority = Keypair::new();
+    let (buffer, _) =
+        Pubkey::find_program_address(&[b"schema", authority.pubkey().as_ref()], program_id);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeSchemaEcho {
+                schema_hash: hash(SCHEMA.as_bytes()).to_bytes(),
+                buffer_size: SCHEMA_ECHO_HEADER_SIZE + 16,
+                authority: authority.pubkey(),
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    (authority, buffer)
+}
+
+fn schema_write_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    data: Vec<u8>,
+    schema_descriptor: &str,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::SchemaWrite {
+            data,
+            schema_descriptor: schema_descriptor.as_bytes().to_vec(),
+        },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_schema_write_with_matching_descriptor() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id).await;
+    let reading = (7u32, -42i64).try_to_vec().unwrap();
+
+    send(
+        &mut context,
+        &[schema_write_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            reading.clone(),
+            SCHEMA,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    let header = SchemaEchoHeader::try_from_slice(&data[..SCHEMA_ECHO_HEADER_SIZE]).unwrap();
+    assert_eq!(header.schema_hash, hash(SCHEMA.as_bytes()).to_bytes());
+    assert_eq!(
+        &data[SCHEMA_ECHO_HEADER_SIZE..SCHEMA_ECHO_HEADER_SIZE + 12],
+        reading.as_slice()
+    );
+    assert_eq!(&data[SCHEMA_ECHO_HEADER_SIZE + 12..], &[0; 4]);
+}
+
+#[tokio::test]
+async fn test_schema_write_rejects_other_descriptor() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id).await;
+
+    let result = send(
+        &mut context,
+        &[schema_write_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            vec![1; 12],
+            "struct Reading { sensor: u32, value: u64 }",
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidSchemaDescriptor);
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[SCHEMA_ECHO_HEADER_SIZE..], &[0; 16]);
+}
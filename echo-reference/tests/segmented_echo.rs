@@ -0,0 +1,168 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{SegmentedHeader, SEGMENTED_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+const SEGMENT_SIZE: usize = 16;
+const SEGMENT_COUNT: u8 = 32;
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> (Keypair, Pubkey) {
+    let authority = Keypair::new();
+    let (buffer, _) =
+        Pubkey::find_program_address(&[b"segmented", authority.pubkey().as_ref()], program_id);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeSegmentedEcho {
+                segment_size: SEGMENT_SIZE as u32,
+                segment_count: SEGMENT_COUNT,
+                authority: authority.pubkey(),
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    (authority, buffer)
+}
+
+fn segmented_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    instruction: EchoInstruction,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &instruction,
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+fn segment(data: &[u8], index: usize) -> &[u8] {
+    let start = SEGMENTED_HEADER_SIZE + index * SEGMENT_SIZE;
+    &data[start..start + SEGMENT_SIZE]
+}
+
+#[tokio::test]
+async fn test_segment_bitmap_after_writes_and_clears() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id).await;
+
+    let write = |index: u8, data: Vec<u8>| {
+        segmented_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            EchoInstruction::AuthorizedEchoSegment { index, data },
+        )
+    };
+    send(
+        &mut context,
+        &[
+            write(0, vec![1; 4]),
+            write(9, vec![2; 40]),
+            write(31, vec![3; 16]),
+            write(17, vec![4; 8]),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    send(
+        &mut context,
+        &[
+            segmented_ix(
+                &program_id,
+                &buffer,
+                &authority.pubkey(),
+                EchoInstruction::ClearSegment { index: 17 },
+            ),
+            write(0, vec![5; 2]),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    let header = SegmentedHeader::try_from_slice(&data[..SEGMENTED_HEADER_SIZE]).unwrap();
+    assert_eq!(
+        header.populated_segments().collect::<Vec<_>>(),
+        vec![0, 9, 31]
+    );
+
+    // writes replace the whole segment, truncating or zero-padding the data
+    assert_eq!(
+        segment(&data, 0),
+        &[5, 5, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(segment(&data, 9), &[2; SEGMENT_SIZE]);
+    assert_eq!(segment(&data, 17), &[0; SEGMENT_SIZE]);
+    assert_eq!(segment(&data, 31), &[3; SEGMENT_SIZE]);
+}
+
+#[tokio::test]
+async fn test_segment_index_out_of_range() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id).await;
+
+    let result = send(
+        &mut context,
+        &[segmented_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            EchoInstruction::AuthorizedEchoSegment {
+                index: SEGMENT_COUNT,
+                data: vec![1; 4],
+            },
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::SegmentOutOfRange);
+
+    let result = send(
+        &mut context,
+        &[segmented_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            EchoInstruction::ClearSegment {
+                index: SEGMENT_COUNT,
+            },
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::SegmentOutOfRange);
+}
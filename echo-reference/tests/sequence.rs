@@ -0,0 +1,115 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    common::*,
+    echo::{
+        instruction::EchoInstruction,
+        state::{AuthorizedBufferHeader, AUTH_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction},
+        program::{get_return_data, invoke},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    solana_program_test::{processor, tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+/// Forwards its instruction data to the Echo Program and fails unless the returned sequence matches the header.
+/// Accounts: [echo_program, authorized_buffer, authority]
+fn fixture_process(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = Instruction {
+        program_id: *accounts[0].key,
+        accounts: vec![
+            AccountMeta::new(*accounts[1].key, false),
+            AccountMeta::new_readonly(*accounts[2].key, true),
+        ],
+        data: instruction_data.to_vec(),
+    };
+    invoke(&instruction, &accounts[1..])?;
+
+    let (returned_by, return_data) = get_return_data().ok_or(ProgramError::InvalidAccountData)?;
+    let header = AuthorizedBufferHeader::try_from_slice(
+        &accounts[1].data.borrow()[..AUTH_BUFF_HEADER_SIZE],
+    )?;
+    if returned_by != *accounts[0].key || return_data != header.sequence.to_le_bytes() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+async fn sequence(context: &mut ProgramTestContext, buffer: &Pubkey) -> u64 {
+    let data = account_data(context, buffer).await;
+    AuthorizedBufferHeader::try_from_slice(&data[..AUTH_BUFF_HEADER_SIZE])
+        .unwrap()
+        .sequence
+}
+
+#[tokio::test]
+async fn test_sequence_increases_on_every_mutation_and_is_returned() {
+    let (mut program_test, program_id) = program_test();
+    let fixture_program_id = Pubkey::new_unique();
+    program_test.add_program(
+        "echo_sequence_fixture",
+        fixture_program_id,
+        processor!(fixture_process),
+    );
+    let mut context = program_test.start_with_context().await;
+    let authority: Keypair = funded_keypair(&mut context).await;
+    let (buffer, _) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+
+    send(
+        &mut context,
+        &[initialize_authorized_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            0,
+            AUTH_BUFF_HEADER_SIZE + 32,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(sequence(&mut context, &buffer).await, 0);
+
+    let mutations = [
+        EchoInstruction::AuthorizedEcho { data: vec![1; 8] },
+        EchoInstruction::AppendAuthorizedEcho { data: vec![2; 4] },
+        EchoInstruction::ClearAuthorizedBuffer,
+        EchoInstruction::AppendAuthorizedEcho { data: vec![3; 4] },
+        EchoInstruction::AuthorizedEcho { data: vec![4; 8] },
+    ];
+    let mut last_sequence = 0;
+    for mutation in mutations {
+        send(
+            &mut context,
+            &[Instruction::new_with_bytes(
+                fixture_program_id,
+                &mutation.try_to_vec().unwrap(),
+                vec![
+                    AccountMeta::new_readonly(program_id, false),
+                    AccountMeta::new(buffer, false),
+                    AccountMeta::new_readonly(authority.pubkey(), true),
+                ],
+            )],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+
+        let current_sequence = sequence(&mut context, &buffer).await;
+        assert!(current_sequence > last_sequence);
+        last_sequence = current_sequence;
+    }
+    assert_eq!(last_sequence, 5);
+}
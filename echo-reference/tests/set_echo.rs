@@ -0,0 +1,205 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{SetHeader, SET_HEADER_SIZE, SET_MEMBER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+struct Set {
+    program_id: Pubkey,
+    authority: Keypair,
+    account: Pubkey,
+}
+
+impl Set {
+    async fn new(context: &mut ProgramTestContext, program_id: Pubkey, capacity: u16) -> Self {
+        let authority = Keypair::new();
+        let account =
+            Pubkey::find_program_address(&[b"set", authority.pubkey().as_ref()], &program_id).0;
+        let payer = context.payer.pubkey();
+        send(
+            context,
+            &[Instruction::new_with_borsh(
+                program_id,
+                &EchoInstruction::InitializeSetEcho {
+                    capacity,
+                    authority: authority.pubkey(),
+                },
+                vec![
+                    AccountMeta::new(account, false),
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        Self {
+            program_id,
+            authority,
+            account,
+        }
+    }
+
+    async fn mutate(
+        &self,
+        context: &mut ProgramTestContext,
+        instruction: EchoInstruction,
+    ) -> Result<(), solana_sdk::transport::TransportError> {
+        let ix = Instruction::new_with_borsh(
+            self.program_id,
+            &instruction,
+            vec![
+                AccountMeta::new(self.account, false),
+                AccountMeta::new_readonly(self.authority.pubkey(), true),
+            ],
+        );
+        send(context, &[ix], &[&self.authority]).await
+    }
+
+    async fn members(&self, context: &mut ProgramTestContext) -> Vec<Pubkey> {
+        let data = account_data(context, &self.account).await;
+        let header = SetHeader::try_from_slice(&data[..SET_HEADER_SIZE]).unwrap();
+        data[SET_HEADER_SIZE..SET_HEADER_SIZE + header.count as usize * SET_MEMBER_SIZE]
+            .chunks(SET_MEMBER_SIZE)
+            .map(Pubkey::new)
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn test_set_add_keeps_members_sorted() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let set = Set::new(&mut context, program_id, 3).await;
+
+    let members = [
+        Pubkey::new_from_array([3; 32]),
+        Pubkey::new_from_array([1; 32]),
+        Pubkey::new_from_array([2; 32]),
+    ];
+    for member in members {
+        set.mutate(&mut context, EchoInstruction::AddToSet { member })
+            .await
+            .unwrap();
+    }
+    let mut sorted = members.to_vec();
+    sorted.sort();
+    assert_eq!(set.members(&mut context).await, sorted);
+
+    let ix = Instruction::new_with_borsh(
+        program_id,
+        &EchoInstruction::ContainsInSet { member: members[0] },
+        vec![
+            AccountMeta::new_readonly(set.account, false),
+            AccountMeta::new_readonly(set.authority.pubkey(), false),
+        ],
+    );
+    send(&mut context, &[ix], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_set_rejects_duplicate_and_full() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let set = Set::new(&mut context, program_id, 2).await;
+
+    let member = Pubkey::new_unique();
+    set.mutate(&mut context, EchoInstruction::AddToSet { member })
+        .await
+        .unwrap();
+
+    // a second signer keeps the duplicate transaction distinct from the first add
+    let ix = Instruction::new_with_borsh(
+        program_id,
+        &EchoInstruction::AddToSet { member },
+        vec![
+            AccountMeta::new(set.account, false),
+            AccountMeta::new_readonly(set.authority.pubkey(), true),
+            AccountMeta::new_readonly(context.payer.pubkey(), true),
+        ],
+    );
+    let result = send(&mut context, &[ix], &[&set.authority]).await;
+    assert_echo_error(result, EchoError::DuplicateMember);
+
+    set.mutate(
+        &mut context,
+        EchoInstruction::AddToSet {
+            member: Pubkey::new_unique(),
+        },
+    )
+    .await
+    .unwrap();
+    let result = set
+        .mutate(
+            &mut context,
+            EchoInstruction::AddToSet {
+                member: Pubkey::new_unique(),
+            },
+        )
+        .await;
+    assert_echo_error(result, EchoError::SetFull);
+    assert_eq!(set.members(&mut context).await.len(), 2);
+}
+
+#[tokio::test]
+async fn test_set_remove() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let set = Set::new(&mut context, program_id, 4).await;
+
+    let members: Vec<Pubkey> = (1..=3).map(|i| Pubkey::new_from_array([i; 32])).collect();
+    for member in &members {
+        set.mutate(&mut context, EchoInstruction::AddToSet { member: *member })
+            .await
+            .unwrap();
+    }
+
+    set.mutate(
+        &mut context,
+        EchoInstruction::RemoveFromSet { member: members[1] },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        set.members(&mut context).await,
+        vec![members[0], members[2]]
+    );
+
+    let result = set
+        .mutate(
+            &mut context,
+            EchoInstruction::RemoveFromSet {
+                member: Pubkey::new_unique(),
+            },
+        )
+        .await;
+    assert_echo_error(result, EchoError::MemberNotFound);
+
+    let intruder = Keypair::new();
+    let ix = Instruction::new_with_borsh(
+        program_id,
+        &EchoInstruction::RemoveFromSet { member: members[0] },
+        vec![
+            AccountMeta::new(set.account, false),
+            AccountMeta::new_readonly(intruder.pubkey(), true),
+        ],
+    );
+    let result = send(&mut context, &[ix], &[&intruder]).await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+}
@@ -0,0 +1,168 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::SHARED_BUFF_HEADER_SIZE},
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction},
+        program::invoke,
+        pubkey::Pubkey,
+        system_program, sysvar,
+    },
+    solana_program_test::{processor, tokio, ProgramTestContext},
+    solana_sdk::signature::Signer,
+};
+
+const GROUP_SEED: [u8; 32] = [7; 32];
+
+/// Forwards its instruction data as a `WriteShared` write.
+/// Accounts: [echo_program, shared_buffer, instructions_sysvar]
+fn mock_caller_process(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = Instruction::new_with_borsh(
+        *accounts[0].key,
+        &EchoInstruction::WriteShared {
+            data: instruction_data.to_vec(),
+        },
+        vec![
+            AccountMeta::new(*accounts[1].key, false),
+            AccountMeta::new_readonly(*accounts[2].key, false),
+        ],
+    );
+    invoke(&instruction, &accounts[1..])
+}
+
+fn shared_buffer_address(program_id: &Pubkey, group_seed: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[b"shared", group_seed.as_ref()], program_id).0
+}
+
+async fn initialize_shared_echo(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    allowed_program: &Pubkey,
+) -> Pubkey {
+    let buffer = shared_buffer_address(program_id, &GROUP_SEED);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeSharedEcho {
+                buffer_size: SHARED_BUFF_HEADER_SIZE + 8,
+                group_seed: GROUP_SEED,
+                allowed_program: *allowed_program,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    buffer
+}
+
+#[tokio::test]
+async fn test_shared_echo_via_cpi() {
+    let (mut program_test, program_id) = program_test();
+    let mock_program_id = Pubkey::new_unique();
+    program_test.add_program(
+        "mock_caller",
+        mock_program_id,
+        processor!(mock_caller_process),
+    );
+    let mut context = program_test.start_with_context().await;
+    let buffer = initialize_shared_echo(&mut context, &program_id, &mock_program_id).await;
+
+    for data in [vec![9; 8], vec![3; 2]] {
+        send(
+            &mut context,
+            &[Instruction {
+                program_id: mock_program_id,
+                accounts: vec![
+                    AccountMeta::new_readonly(program_id, false),
+                    AccountMeta::new(buffer, false),
+                    AccountMeta::new_readonly(sysvar::instructions::id(), false),
+                ],
+                data,
+            }],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[1..33], &GROUP_SEED);
+    assert_eq!(&data[33..SHARED_BUFF_HEADER_SIZE], mock_program_id.as_ref());
+    assert_eq!(&data[SHARED_BUFF_HEADER_SIZE..], &[3, 3, 0, 0, 0, 0, 0, 0]);
+}
+
+#[tokio::test]
+async fn test_shared_echo_rejects_direct_call() {
+    let (mut program_test, program_id) = program_test();
+    let mock_program_id = Pubkey::new_unique();
+    program_test.add_program(
+        "mock_caller",
+        mock_program_id,
+        processor!(mock_caller_process),
+    );
+    let mut context = program_test.start_with_context().await;
+    let buffer = initialize_shared_echo(&mut context, &program_id, &mock_program_id).await;
+
+    let result = send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::WriteShared { data: vec![9; 4] },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+}
+
+#[tokio::test]
+async fn test_shared_echo_rejects_other_program() {
+    let (mut program_test, program_id) = program_test();
+    let mock_program_id = Pubkey::new_unique();
+    program_test.add_program(
+        "mock_caller",
+        mock_program_id,
+        processor!(mock_caller_process),
+    );
+    let mut context = program_test.start_with_context().await;
+
+    // the group only lets another program write
+    let buffer = initialize_shared_echo(&mut context, &program_id, &Pubkey::new_unique()).await;
+
+    let result = send(
+        &mut context,
+        &[Instruction {
+            program_id: mock_program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(program_id, false),
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            ],
+            data: vec![9; 4],
+        }],
+        &[],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+}
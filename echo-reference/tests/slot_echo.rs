@@ -0,0 +1,135 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{instruction::EchoInstruction, state::SLOT_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        clock::Clock,
+        signature::{Keypair, Signer},
+    },
+};
+
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    buffer_size: usize,
+) -> (Keypair, Pubkey) {
+    let authority = Keypair::new();
+    let buffer =
+        Pubkey::find_program_address(&[b"slot", authority.pubkey().as_ref()], program_id).0;
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeSlotEcho {
+                buffer_size,
+                authority: authority.pubkey(),
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    (authority, buffer)
+}
+
+fn slot_echo_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::SlotEcho { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// Moves the bank one slot ahead.
+async fn warp_to_next_slot(context: &mut ProgramTestContext) {
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    context.warp_to_slot(clock.slot + 1).unwrap();
+}
+
+#[tokio::test]
+async fn test_slot_echo_keeps_data_within_a_slot() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id, SLOT_HEADER_SIZE + 8).await;
+
+    send(
+        &mut context,
+        &[
+            slot_echo_ix(&program_id, &buffer, &authority.pubkey(), vec![1; 8]),
+            slot_echo_ix(&program_id, &buffer, &authority.pubkey(), vec![2; 2]),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[SLOT_HEADER_SIZE..], &[2, 2, 1, 1, 1, 1, 1, 1]);
+}
+
+#[tokio::test]
+async fn test_slot_echo_resets_stale_data_on_next_write() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (authority, buffer) = setup(&mut context, &program_id, SLOT_HEADER_SIZE + 8).await;
+
+    send(
+        &mut context,
+        &[slot_echo_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            vec![1; 8],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    warp_to_next_slot(&mut context).await;
+
+    // stale data stays in the account until the next write
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[SLOT_HEADER_SIZE..], &[1; 8]);
+
+    send(
+        &mut context,
+        &[slot_echo_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            vec![2; 2],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[..8], &clock.slot.to_le_bytes());
+    assert_eq!(&data[SLOT_HEADER_SIZE..], &[2, 2, 0, 0, 0, 0, 0, 0]);
+}
@@ -0,0 +1,265 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{SnapshotHeader, AUTH_BUFF_HEADER_SIZE, SNAPSHOT_HEADER_SIZE},
+    },
+    solana_program::{
+        clock::Clock,
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+fn snapshot_address(program_id: &Pubkey, buffer: &Pubkey, snapshot_index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"snapshot", buffer.as_ref(), &snapshot_index.to_le_bytes()],
+        program_id,
+    )
+    .0
+}
+
+fn slot_snapshot_address(program_id: &Pubkey, buffer: &Pubkey, slot: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"slot_snapshot", buffer.as_ref(), &slot.to_le_bytes()],
+        program_id,
+    )
+    .0
+}
+
+fn snapshot_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    payer: &Pubkey,
+    snapshot_index: u64,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::SnapshotAuthorizedEcho { snapshot_index },
+        vec![
+            AccountMeta::new_readonly(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(snapshot_address(program_id, buffer, snapshot_index), false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Takes a `SnapshotBuffer` of `buffer` at `slot`, returns the snapshot address.
+async fn snapshot_at_slot(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Keypair,
+    slot: u64,
+) -> Pubkey {
+    context.warp_to_slot(slot).unwrap();
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let snapshot = slot_snapshot_address(program_id, buffer, clock.slot);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::SnapshotBuffer,
+            vec![
+                AccountMeta::new_readonly(*buffer, false),
+                AccountMeta::new_readonly(authority.pubkey(), true),
+                AccountMeta::new(snapshot, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[authority],
+    )
+    .await
+    .unwrap();
+    snapshot
+}
+
+#[tokio::test]
+async fn test_snapshot_keeps_previous_contents() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let (buffer, _) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+    let payer = context.payer.pubkey();
+
+    send(
+        &mut context,
+        &[
+            initialize_authorized_echo_ix(
+                &program_id,
+                &authority.pubkey(),
+                0,
+                AUTH_BUFF_HEADER_SIZE + 32,
+            ),
+            authorized_echo_ix(&program_id, &buffer, &authority.pubkey(), vec![1; 8]),
+            snapshot_ix(&program_id, &buffer, &authority.pubkey(), &payer, 0),
+            authorized_echo_ix(&program_id, &buffer, &authority.pubkey(), vec![2; 8]),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let payload_len = 32;
+    let data = account_data(&mut context, &snapshot_address(&program_id, &buffer, 0)).await;
+    assert_eq!(data.len(), SNAPSHOT_HEADER_SIZE + payload_len);
+    let header = SnapshotHeader::try_from_slice(&data[..SNAPSHOT_HEADER_SIZE]).unwrap();
+    assert_eq!(header.source_buffer, buffer);
+    assert_eq!(header.snapshot_index, 0);
+    assert_eq!(
+        &data[SNAPSHOT_HEADER_SIZE..SNAPSHOT_HEADER_SIZE + 8],
+        &[1; 8]
+    );
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[AUTH_BUFF_HEADER_SIZE..AUTH_BUFF_HEADER_SIZE + 8],
+        &[2; 8]
+    );
+}
+
+#[tokio::test]
+async fn test_snapshot_rejects_existing_index() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let (buffer, _) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+    let payer = context.payer.pubkey();
+
+    send(
+        &mut context,
+        &[
+            initialize_authorized_echo_ix(
+                &program_id,
+                &authority.pubkey(),
+                0,
+                AUTH_BUFF_HEADER_SIZE + 32,
+            ),
+            snapshot_ix(&program_id, &buffer, &authority.pubkey(), &payer, 3),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    // the authority pays this time so the transaction differs from the first snapshot
+    let result = send(
+        &mut context,
+        &[snapshot_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &authority.pubkey(),
+            3,
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::SnapshotAlreadyExists);
+}
+
+#[tokio::test]
+async fn test_snapshot_buffer_at_different_slots() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+    let (buffer, _) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+
+    send(
+        &mut context,
+        &[
+            initialize_authorized_echo_ix(
+                &program_id,
+                &authority.pubkey(),
+                0,
+                AUTH_BUFF_HEADER_SIZE + 4,
+            ),
+            authorized_echo_ix(&program_id, &buffer, &authority.pubkey(), vec![1; 4]),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let first = snapshot_at_slot(&mut context, &program_id, &buffer, &authority, 50).await;
+
+    send(
+        &mut context,
+        &[authorized_echo_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            vec![2; 4],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let second = snapshot_at_slot(&mut context, &program_id, &buffer, &authority, 100).await;
+    assert_ne!(first, second);
+
+    for (snapshot, payload, slot) in [(first, [1; 4], 50), (second, [2; 4], 100)] {
+        let data = account_data(&mut context, &snapshot).await;
+        let header = SnapshotHeader::try_from_slice(&data[..SNAPSHOT_HEADER_SIZE]).unwrap();
+        assert_eq!(header.source_buffer, buffer);
+        assert_eq!(header.slot, slot);
+        assert_eq!(header.snapshot_index, slot);
+        assert_eq!(&data[SNAPSHOT_HEADER_SIZE..], &payload);
+    }
+
+    // both are snapshots of the buffer, the buffer itself isn't
+    let list_ix = |snapshots: &[Pubkey]| {
+        let mut accounts = vec![AccountMeta::new_readonly(buffer, false)];
+        accounts.extend(
+            snapshots
+                .iter()
+                .map(|snapshot| AccountMeta::new_readonly(*snapshot, false)),
+        );
+        Instruction::new_with_borsh(program_id, &EchoInstruction::ListSnapshots, accounts)
+    };
+    send(&mut context, &[list_ix(&[first, second])], &[])
+        .await
+        .unwrap();
+    let result = send(&mut context, &[list_ix(&[first, buffer])], &[]).await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+
+    // the snapshot taken at index 50 doesn't clash with the one taken at slot 50
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[snapshot_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            &payer,
+            50,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let indexed = snapshot_address(&program_id, &buffer, 50);
+    assert_ne!(indexed, first);
+    let data = account_data(&mut context, &indexed).await;
+    let header = SnapshotHeader::try_from_slice(&data[..SNAPSHOT_HEADER_SIZE]).unwrap();
+    assert_eq!(header.snapshot_index, 50);
+    assert_eq!(header.slot, 100);
+    assert_eq!(&data[SNAPSHOT_HEADER_SIZE..], &[2; 4]);
+
+    send(&mut context, &[list_ix(&[first, second, indexed])], &[])
+        .await
+        .unwrap();
+}
@@ -0,0 +1,216 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{
+        error::EchoError, instruction::EchoInstruction, state::SOL_VENDING_MACHINE_HEADER_SIZE,
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_instruction, system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+const PRICE: u64 = 1_000_000;
+
+fn vault_address(program_id: &Pubkey, buffer: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"sol_vending_machine_vault", buffer.as_ref()], program_id).0
+}
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> (Keypair, Pubkey) {
+    let admin = funded_keypair(context).await;
+    let (buffer, _) = Pubkey::find_program_address(
+        &[
+            b"sol_vending_machine",
+            admin.pubkey().as_ref(),
+            &PRICE.to_le_bytes(),
+        ],
+        program_id,
+    );
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeSolVendingMachine {
+                price_lamports: PRICE,
+                buffer_size: SOL_VENDING_MACHINE_HEADER_SIZE + 16,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(admin.pubkey(), true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    (admin, buffer)
+}
+
+fn sol_vending_machine_echo_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    user: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::SolVendingMachineEcho { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new(*user, true),
+            AccountMeta::new(vault_address(program_id, buffer), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+fn withdraw_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    admin: &Pubkey,
+    destination: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::WithdrawSolVendingProceeds,
+        vec![
+            AccountMeta::new_readonly(*buffer, false),
+            AccountMeta::new(vault_address(program_id, buffer), false),
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_sol_vending_machine_payment_and_withdrawal() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (admin, buffer) = setup(&mut context, &program_id).await;
+    let vault = vault_address(&program_id, &buffer);
+
+    let user = funded_keypair(&mut context).await;
+    let user_lamports = context
+        .banks_client
+        .get_balance(user.pubkey())
+        .await
+        .unwrap();
+    for data in [vec![1; 8], vec![2; 4]] {
+        send(
+            &mut context,
+            &[sol_vending_machine_echo_ix(
+                &program_id,
+                &buffer,
+                &user.pubkey(),
+                data,
+            )],
+            &[&user],
+        )
+        .await
+        .unwrap();
+    }
+
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(user.pubkey())
+            .await
+            .unwrap(),
+        user_lamports - 2 * PRICE
+    );
+    assert_eq!(
+        context.banks_client.get_balance(vault).await.unwrap(),
+        2 * PRICE
+    );
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[SOL_VENDING_MACHINE_HEADER_SIZE..],
+        &[2, 2, 2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+
+    // only the admin can withdraw
+    let destination = Keypair::new().pubkey();
+    let result = send(
+        &mut context,
+        &[withdraw_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            &destination,
+        )],
+        &[&user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    send(
+        &mut context,
+        &[withdraw_ix(
+            &program_id,
+            &buffer,
+            &admin.pubkey(),
+            &destination,
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    assert_eq!(context.banks_client.get_balance(vault).await.unwrap(), 0);
+    assert_eq!(
+        context.banks_client.get_balance(destination).await.unwrap(),
+        2 * PRICE
+    );
+}
+
+#[tokio::test]
+async fn test_sol_vending_machine_rejects_under_funded_user() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (_, buffer) = setup(&mut context, &program_id).await;
+
+    let user = Keypair::new();
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[system_instruction::transfer(
+            &payer,
+            &user.pubkey(),
+            PRICE - 1,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut context,
+        &[sol_vending_machine_echo_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            vec![1; 8],
+        )],
+        &[&user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InsufficientFunds);
+
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[SOL_VENDING_MACHINE_HEADER_SIZE..], &[0; 16]);
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(vault_address(&program_id, &buffer))
+            .await
+            .unwrap(),
+        0
+    );
+}
@@ -0,0 +1,203 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::AUTH_BUFF_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+fn sponsor_address(program_id: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"sponsor", authority.as_ref()], program_id).0
+}
+
+async fn fund_sponsor(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    lamports: u64,
+) {
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::FundSponsor { lamports },
+            vec![
+                AccountMeta::new(sponsor_address(program_id, authority), false),
+                AccountMeta::new_readonly(*authority, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+}
+
+fn initialize_sponsored_echo_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    buffer_size: usize,
+) -> Instruction {
+    let (buffer, _) = authorized_buffer_address(program_id, authority, 0);
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::InitializeSponsoredEcho {
+            buffer_size,
+            authority: *authority,
+        },
+        vec![
+            AccountMeta::new(buffer, false),
+            AccountMeta::new(sponsor_address(program_id, authority), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+fn drain_sponsor_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    signer: &Pubkey,
+    destination: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::DrainSponsor {
+            authority: *authority,
+        },
+        vec![
+            AccountMeta::new(sponsor_address(program_id, authority), false),
+            AccountMeta::new_readonly(*signer, true),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_sponsor_pays_buffer_rent() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = Keypair::new();
+    let sponsor = sponsor_address(&program_id, &authority.pubkey());
+    fund_sponsor(
+        &mut context,
+        &program_id,
+        &authority.pubkey(),
+        1_000_000_000,
+    )
+    .await;
+
+    // only the transaction fee payer signs, the authority doesn't need any lamports
+    send(
+        &mut context,
+        &[initialize_sponsored_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            AUTH_BUFF_HEADER_SIZE + 8,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let (buffer, _) = authorized_buffer_address(&program_id, &authority.pubkey(), 0);
+    let buffer_lamports = context.banks_client.get_balance(buffer).await.unwrap();
+    assert_eq!(
+        context.banks_client.get_balance(sponsor).await.unwrap(),
+        1_000_000_000 - buffer_lamports
+    );
+
+    send(
+        &mut context,
+        &[authorized_echo_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            vec![1; 8],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[AUTH_BUFF_HEADER_SIZE..], &[1; 8]);
+}
+
+#[tokio::test]
+async fn test_unfunded_sponsor_cannot_pay_rent() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = Keypair::new();
+
+    let result = send(
+        &mut context,
+        &[initialize_sponsored_echo_ix(
+            &program_id,
+            &authority.pubkey(),
+            AUTH_BUFF_HEADER_SIZE + 8,
+        )],
+        &[],
+    )
+    .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_only_authority_drains_sponsor() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = Keypair::new();
+    let sponsor = sponsor_address(&program_id, &authority.pubkey());
+    fund_sponsor(
+        &mut context,
+        &program_id,
+        &authority.pubkey(),
+        1_000_000_000,
+    )
+    .await;
+
+    let intruder = Keypair::new();
+    let result = send(
+        &mut context,
+        &[drain_sponsor_ix(
+            &program_id,
+            &authority.pubkey(),
+            &intruder.pubkey(),
+            &intruder.pubkey(),
+        )],
+        &[&intruder],
+    )
+    .await;
+    assert_echo_error(result, EchoError::MissingRequiredSignature);
+
+    let destination = funded_keypair(&mut context).await.pubkey();
+    let destination_lamports = context.banks_client.get_balance(destination).await.unwrap();
+    send(
+        &mut context,
+        &[drain_sponsor_ix(
+            &program_id,
+            &authority.pubkey(),
+            &authority.pubkey(),
+            &destination,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(context.banks_client.get_balance(sponsor).await.unwrap(), 0);
+    assert_eq!(
+        context.banks_client.get_balance(destination).await.unwrap(),
+        destination_lamports + 1_000_000_000
+    );
+}
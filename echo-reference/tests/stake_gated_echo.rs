@@ -0,0 +1,255 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshSerialize,
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::STAKE_GATED_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        stake::{
+            self,
+            state::{Authorized, Delegation, Meta, Stake, StakeState},
+        },
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTest, ProgramTestContext},
+    solana_sdk::{
+        account::Account,
+        signature::{Keypair, Signer},
+    },
+};
+
+const MIN_STAKE: u64 = 5_000_000_000;
+const STAKE_ACCOUNT_SIZE: usize = 200;
+
+/// Adds a stake account with `staker` as its staker authority, delegating `stake` lamports to `vote_account`.
+fn add_stake_account(
+    program_test: &mut ProgramTest,
+    staker: &Pubkey,
+    vote_account: &Pubkey,
+    stake: u64,
+    deactivation_epoch: u64,
+) -> Pubkey {
+    let key = Pubkey::new_unique();
+    let stake_state = StakeState::Stake(
+        Meta {
+            authorized: Authorized {
+                staker: *staker,
+                withdrawer: *staker,
+            },
+            ..Meta::default()
+        },
+        Stake {
+            delegation: Delegation {
+                voter_pubkey: *vote_account,
+                stake,
+                deactivation_epoch,
+                ..Delegation::default()
+            },
+            credits_observed: 0,
+        },
+    );
+    let mut data = stake_state.try_to_vec().unwrap();
+    data.resize(STAKE_ACCOUNT_SIZE, 0);
+    program_test.add_account(
+        key,
+        Account {
+            lamports: stake + 10_000_000,
+            data,
+            owner: stake::program::id(),
+            ..Account::default()
+        },
+    );
+    key
+}
+
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    vote_account: &Pubkey,
+) -> Pubkey {
+    let creator = funded_keypair(context).await;
+    let (buffer, _) = Pubkey::find_program_address(
+        &[
+            b"stake_gated",
+            creator.pubkey().as_ref(),
+            vote_account.as_ref(),
+        ],
+        program_id,
+    );
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeStakeGatedEcho {
+                buffer_size: STAKE_GATED_HEADER_SIZE + 8,
+                vote_account: *vote_account,
+                min_stake: MIN_STAKE,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(creator.pubkey(), true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[&creator],
+    )
+    .await
+    .unwrap();
+    buffer
+}
+
+fn stake_gated_write_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    staker: &Pubkey,
+    stake_account: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::StakeGatedWrite { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*staker, true),
+            AccountMeta::new_readonly(*stake_account, false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_stake_gated_write_threshold() {
+    let (mut program_test, program_id) = program_test();
+    let vote_account = Pubkey::new_unique();
+    let staker = Keypair::new();
+    let enough = add_stake_account(
+        &mut program_test,
+        &staker.pubkey(),
+        &vote_account,
+        MIN_STAKE,
+        u64::MAX,
+    );
+    let too_little = add_stake_account(
+        &mut program_test,
+        &staker.pubkey(),
+        &vote_account,
+        MIN_STAKE - 1,
+        u64::MAX,
+    );
+    let deactivating = add_stake_account(
+        &mut program_test,
+        &staker.pubkey(),
+        &vote_account,
+        MIN_STAKE,
+        0,
+    );
+    let mut context = program_test.start_with_context().await;
+    let buffer = setup(&mut context, &program_id, &vote_account).await;
+
+    for stake_account in [too_little, deactivating] {
+        let result = send(
+            &mut context,
+            &[stake_gated_write_ix(
+                &program_id,
+                &buffer,
+                &staker.pubkey(),
+                &stake_account,
+                vec![1; 4],
+            )],
+            &[&staker],
+        )
+        .await;
+        assert_echo_error(result, EchoError::InsufficientFunds);
+    }
+
+    send(
+        &mut context,
+        &[stake_gated_write_ix(
+            &program_id,
+            &buffer,
+            &staker.pubkey(),
+            &enough,
+            vec![1; 4],
+        )],
+        &[&staker],
+    )
+    .await
+    .unwrap();
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[STAKE_GATED_HEADER_SIZE..], &[1, 1, 1, 1, 0, 0, 0, 0]);
+}
+
+#[tokio::test]
+async fn test_stake_gated_write_checks_delegation() {
+    let (mut program_test, program_id) = program_test();
+    let vote_account = Pubkey::new_unique();
+    let staker = Keypair::new();
+    let other_vote = add_stake_account(
+        &mut program_test,
+        &staker.pubkey(),
+        &Pubkey::new_unique(),
+        MIN_STAKE,
+        u64::MAX,
+    );
+    let stake_account = add_stake_account(
+        &mut program_test,
+        &staker.pubkey(),
+        &vote_account,
+        MIN_STAKE,
+        u64::MAX,
+    );
+    let mut context = program_test.start_with_context().await;
+    let buffer = setup(&mut context, &program_id, &vote_account).await;
+
+    // delegated to another validator
+    let result = send(
+        &mut context,
+        &[stake_gated_write_ix(
+            &program_id,
+            &buffer,
+            &staker.pubkey(),
+            &other_vote,
+            vec![1; 4],
+        )],
+        &[&staker],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountData);
+
+    // someone else's stake account
+    let other = Keypair::new();
+    let result = send(
+        &mut context,
+        &[stake_gated_write_ix(
+            &program_id,
+            &buffer,
+            &other.pubkey(),
+            &stake_account,
+            vec![1; 4],
+        )],
+        &[&other],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    // a copy of the delegation in an account the Stake Program doesn't own
+    let fake = create_owned_account(&mut context, &system_program::id(), STAKE_ACCOUNT_SIZE)
+        .await
+        .pubkey();
+    let result = send(
+        &mut context,
+        &[stake_gated_write_ix(
+            &program_id,
+            &buffer,
+            &staker.pubkey(),
+            &fake,
+            vec![1; 4],
+        )],
+        &[&staker],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountOwner);
+}
@@ -0,0 +1,263 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{SubscriptionHeader, SUBSCRIPTION_HEADER_SIZE},
+    },
+    solana_program::{
+        clock::Clock,
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        signature::{Keypair, Signer},
+        transport::TransportError,
+    },
+};
+
+const BUFFER_SIZE: usize = SUBSCRIPTION_HEADER_SIZE + 16;
+const PERIOD_SLOTS: u64 = 100;
+const PRICE_PER_PERIOD: u64 = 5;
+
+struct Subscription {
+    buffer: Pubkey,
+    authority: Keypair,
+    mint: Pubkey,
+    user: Keypair,
+    user_token_account: Pubkey,
+}
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> Subscription {
+    let mint_authority = Keypair::new();
+    let mint = create_mint(context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let user = Keypair::new();
+    let user_token_account = create_token_account(context, &mint, &user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(context, &mint, &user_token_account, &mint_authority, 50).await;
+
+    let authority = Keypair::new();
+    let (buffer, _) = Pubkey::find_program_address(
+        &[b"subscription", authority.pubkey().as_ref(), mint.as_ref()],
+        program_id,
+    );
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeSubscriptionEcho {
+                period_slots: PERIOD_SLOTS,
+                price_per_period: PRICE_PER_PERIOD,
+                buffer_size: BUFFER_SIZE,
+                authority: authority.pubkey(),
+                mint,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    Subscription {
+        buffer,
+        authority,
+        mint,
+        user,
+        user_token_account,
+    }
+}
+
+async fn renew(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    subscription: &Subscription,
+    periods: u8,
+) -> Result<(), TransportError> {
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::RenewSubscription { periods },
+            vec![
+                AccountMeta::new(subscription.buffer, false),
+                AccountMeta::new_readonly(subscription.user.pubkey(), true),
+                AccountMeta::new(subscription.user_token_account, false),
+                AccountMeta::new(subscription.mint, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+        )],
+        &[&subscription.user],
+    )
+    .await
+}
+
+async fn write(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    subscription: &Subscription,
+    data: Vec<u8>,
+) -> Result<(), TransportError> {
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::SubscriptionWrite { data },
+            vec![
+                AccountMeta::new(subscription.buffer, false),
+                AccountMeta::new_readonly(subscription.authority.pubkey(), true),
+            ],
+        )],
+        &[&subscription.authority],
+    )
+    .await
+}
+
+async fn header(context: &mut ProgramTestContext, buffer: &Pubkey) -> SubscriptionHeader {
+    let data = account_data(context, buffer).await;
+    SubscriptionHeader::try_from_slice(&data[..SUBSCRIPTION_HEADER_SIZE]).unwrap()
+}
+
+async fn current_slot(context: &mut ProgramTestContext) -> u64 {
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.slot
+}
+
+#[tokio::test]
+async fn test_subscription_renewal_allows_writes() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let subscription = setup(&mut context, &program_id).await;
+
+    // no period is paid yet
+    let result = write(&mut context, &program_id, &subscription, vec![1; 4]).await;
+    assert_echo_error(result, EchoError::SubscriptionExpired);
+
+    context.warp_to_slot(10).unwrap();
+    renew(&mut context, &program_id, &subscription, 1)
+        .await
+        .unwrap();
+    let slot = current_slot(&mut context).await;
+    assert_eq!(
+        header(&mut context, &subscription.buffer)
+            .await
+            .expires_at_slot,
+        slot + PERIOD_SLOTS
+    );
+    assert_eq!(
+        token_balance(&mut context, &subscription.user_token_account).await,
+        50 - PRICE_PER_PERIOD
+    );
+
+    write(&mut context, &program_id, &subscription, vec![2; 4])
+        .await
+        .unwrap();
+    let data = account_data(&mut context, &subscription.buffer).await;
+    assert_eq!(
+        &data[SUBSCRIPTION_HEADER_SIZE..],
+        &[2, 2, 2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+
+    // renewing an active subscription extends it from its expiry
+    renew(&mut context, &program_id, &subscription, 2)
+        .await
+        .unwrap();
+    assert_eq!(
+        header(&mut context, &subscription.buffer)
+            .await
+            .expires_at_slot,
+        slot + 3 * PERIOD_SLOTS
+    );
+}
+
+#[tokio::test]
+async fn test_subscription_write_after_expiry() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let subscription = setup(&mut context, &program_id).await;
+
+    renew(&mut context, &program_id, &subscription, 1)
+        .await
+        .unwrap();
+    let expires_at_slot = header(&mut context, &subscription.buffer)
+        .await
+        .expires_at_slot;
+
+    context.warp_to_slot(expires_at_slot - 1).unwrap();
+    write(&mut context, &program_id, &subscription, vec![1; 4])
+        .await
+        .unwrap();
+
+    context.warp_to_slot(expires_at_slot).unwrap();
+    let result = write(&mut context, &program_id, &subscription, vec![2; 4]).await;
+    assert_echo_error(result, EchoError::SubscriptionExpired);
+
+    // the lapsed slots are not paid for, the renewal starts from the current slot
+    context.warp_to_slot(expires_at_slot + 50).unwrap();
+    renew(&mut context, &program_id, &subscription, 1)
+        .await
+        .unwrap();
+    let slot = current_slot(&mut context).await;
+    assert_eq!(
+        header(&mut context, &subscription.buffer)
+            .await
+            .expires_at_slot,
+        slot + PERIOD_SLOTS
+    );
+    write(&mut context, &program_id, &subscription, vec![3; 4])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_subscription_renews_multiple_periods() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let subscription = setup(&mut context, &program_id).await;
+
+    renew(&mut context, &program_id, &subscription, 3)
+        .await
+        .unwrap();
+    let slot = current_slot(&mut context).await;
+    assert_eq!(
+        token_balance(&mut context, &subscription.user_token_account).await,
+        50 - 3 * PRICE_PER_PERIOD
+    );
+    let expires_at_slot = header(&mut context, &subscription.buffer)
+        .await
+        .expires_at_slot;
+    assert_eq!(expires_at_slot, slot + 3 * PERIOD_SLOTS);
+
+    // still active past the first period
+    context.warp_to_slot(slot + 2 * PERIOD_SLOTS).unwrap();
+    write(&mut context, &program_id, &subscription, vec![1; 4])
+        .await
+        .unwrap();
+
+    // more periods than the user can pay for
+    let result = renew(&mut context, &program_id, &subscription, 8).await;
+    assert!(result.is_err());
+    assert_eq!(
+        header(&mut context, &subscription.buffer)
+            .await
+            .expires_at_slot,
+        expires_at_slot
+    );
+
+    let result = renew(&mut context, &program_id, &subscription, 0).await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+}
@@ -0,0 +1,170 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::SumHeader},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+struct Sum {
+    program_id: Pubkey,
+    authority: Keypair,
+    account: Pubkey,
+}
+
+impl Sum {
+    async fn new(context: &mut ProgramTestContext, program_id: Pubkey, initial_value: u64) -> Self {
+        let authority = Keypair::new();
+        let account =
+            Pubkey::find_program_address(&[b"sum", authority.pubkey().as_ref()], &program_id).0;
+        let payer = context.payer.pubkey();
+        send(
+            context,
+            &[Instruction::new_with_borsh(
+                program_id,
+                &EchoInstruction::InitializeSumEcho {
+                    authority: authority.pubkey(),
+                    initial_value,
+                },
+                vec![
+                    AccountMeta::new(account, false),
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        Self {
+            program_id,
+            authority,
+            account,
+        }
+    }
+
+    async fn add(
+        &self,
+        context: &mut ProgramTestContext,
+        signer: &Keypair,
+        amount: u64,
+    ) -> Result<(), solana_sdk::transport::TransportError> {
+        let ix = Instruction::new_with_borsh(
+            self.program_id,
+            &EchoInstruction::AddToSum { amount },
+            vec![
+                AccountMeta::new(self.account, false),
+                AccountMeta::new_readonly(signer.pubkey(), true),
+                AccountMeta::new_readonly(self.authority.pubkey(), false),
+            ],
+        );
+        send(context, &[ix], &[signer]).await
+    }
+
+    async fn authority_op(
+        &self,
+        context: &mut ProgramTestContext,
+        signer: &Keypair,
+        instruction: EchoInstruction,
+    ) -> Result<(), solana_sdk::transport::TransportError> {
+        let ix = Instruction::new_with_borsh(
+            self.program_id,
+            &instruction,
+            vec![
+                AccountMeta::new(self.account, false),
+                AccountMeta::new_readonly(signer.pubkey(), true),
+            ],
+        );
+        send(context, &[ix], &[signer]).await
+    }
+
+    async fn value(&self, context: &mut ProgramTestContext) -> u64 {
+        SumHeader::try_from_slice(&account_data(context, &self.account).await)
+            .unwrap()
+            .value
+    }
+}
+
+#[tokio::test]
+async fn test_sum_mixed_add_and_subtract() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let sum = Sum::new(&mut context, program_id, 10).await;
+    let anyone = Keypair::new();
+
+    sum.add(&mut context, &anyone, 5).await.unwrap();
+    sum.authority_op(
+        &mut context,
+        &sum.authority,
+        EchoInstruction::SubtractFromSum { amount: 12 },
+    )
+    .await
+    .unwrap();
+    sum.add(&mut context, &sum.authority, 100).await.unwrap();
+    assert_eq!(sum.value(&mut context).await, 103);
+
+    sum.authority_op(
+        &mut context,
+        &sum.authority,
+        EchoInstruction::ResetSum { new_value: 7 },
+    )
+    .await
+    .unwrap();
+    assert_eq!(sum.value(&mut context).await, 7);
+}
+
+#[tokio::test]
+async fn test_sum_overflow() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let sum = Sum::new(&mut context, program_id, u64::MAX - 1).await;
+
+    sum.add(&mut context, &Keypair::new(), 1).await.unwrap();
+    let result = sum.add(&mut context, &Keypair::new(), 1).await;
+    assert_echo_error(result, EchoError::Overflow);
+    assert_eq!(sum.value(&mut context).await, u64::MAX);
+}
+
+#[tokio::test]
+async fn test_sum_underflow_and_authority_checks() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let sum = Sum::new(&mut context, program_id, 3).await;
+
+    let result = sum
+        .authority_op(
+            &mut context,
+            &sum.authority,
+            EchoInstruction::SubtractFromSum { amount: 4 },
+        )
+        .await;
+    assert_echo_error(result, EchoError::Underflow);
+
+    let intruder = Keypair::new();
+    let result = sum
+        .authority_op(
+            &mut context,
+            &intruder,
+            EchoInstruction::SubtractFromSum { amount: 1 },
+        )
+        .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+    let result = sum
+        .authority_op(
+            &mut context,
+            &intruder,
+            EchoInstruction::ResetSum { new_value: 0 },
+        )
+        .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+    assert_eq!(sum.value(&mut context).await, 3);
+}
@@ -0,0 +1,176 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{AuthorizedBufferHeader, AUTH_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+fn swap_buffers_ix(
+    program_id: &Pubkey,
+    buffer_a: &Pubkey,
+    buffer_b: &Pubkey,
+    authority: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::SwapBuffers,
+        vec![
+            AccountMeta::new(*buffer_a, false),
+            AccountMeta::new(*buffer_b, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// Creates the buffer of `authority` at `seed` and writes `data` to it.
+async fn written_buffer(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    authority: &Keypair,
+    seed: u64,
+    buffer_size: usize,
+    data: Vec<u8>,
+) -> Pubkey {
+    let buffer = authorized_buffer_address(program_id, &authority.pubkey(), seed).0;
+    send(
+        context,
+        &[
+            initialize_authorized_echo_ix(program_id, &authority.pubkey(), seed, buffer_size),
+            authorized_echo_ix(program_id, &buffer, &authority.pubkey(), data),
+        ],
+        &[authority],
+    )
+    .await
+    .unwrap();
+    buffer
+}
+
+#[tokio::test]
+async fn test_swap_buffers_exchanges_data() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+
+    let live = written_buffer(
+        &mut context,
+        &program_id,
+        &authority,
+        0,
+        AUTH_BUFF_HEADER_SIZE + 6,
+        vec![1, 2, 3, 4, 5, 6],
+    )
+    .await;
+    let staging = written_buffer(
+        &mut context,
+        &program_id,
+        &authority,
+        1,
+        AUTH_BUFF_HEADER_SIZE + 6,
+        vec![7, 8],
+    )
+    .await;
+
+    send(
+        &mut context,
+        &[swap_buffers_ix(
+            &program_id,
+            &live,
+            &staging,
+            &authority.pubkey(),
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let data = account_data(&mut context, &live).await;
+    assert_eq!(&data[AUTH_BUFF_HEADER_SIZE..], &[7, 8, 0, 0, 0, 0]);
+    let header = AuthorizedBufferHeader::try_from_slice(&data[..AUTH_BUFF_HEADER_SIZE]).unwrap();
+    assert_eq!(header.cursor, 2);
+    assert_eq!(header.buffer_seed, 0);
+
+    let data = account_data(&mut context, &staging).await;
+    assert_eq!(&data[AUTH_BUFF_HEADER_SIZE..], &[1, 2, 3, 4, 5, 6]);
+    let header = AuthorizedBufferHeader::try_from_slice(&data[..AUTH_BUFF_HEADER_SIZE]).unwrap();
+    assert_eq!(header.cursor, 6);
+    assert_eq!(header.buffer_seed, 1);
+}
+
+#[tokio::test]
+async fn test_swap_buffers_rejects_mismatched_sizes() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let authority = funded_keypair(&mut context).await;
+
+    let small = written_buffer(
+        &mut context,
+        &program_id,
+        &authority,
+        0,
+        AUTH_BUFF_HEADER_SIZE + 4,
+        vec![1; 4],
+    )
+    .await;
+    let large = written_buffer(
+        &mut context,
+        &program_id,
+        &authority,
+        1,
+        AUTH_BUFF_HEADER_SIZE + 8,
+        vec![2; 8],
+    )
+    .await;
+
+    let result = send(
+        &mut context,
+        &[swap_buffers_ix(
+            &program_id,
+            &small,
+            &large,
+            &authority.pubkey(),
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+
+    // the buffers of another authority can't be swapped in
+    let other = funded_keypair(&mut context).await;
+    let other_buffer = written_buffer(
+        &mut context,
+        &program_id,
+        &other,
+        0,
+        AUTH_BUFF_HEADER_SIZE + 4,
+        vec![3; 4],
+    )
+    .await;
+    let result = send(
+        &mut context,
+        &[swap_buffers_ix(
+            &program_id,
+            &small,
+            &other_buffer,
+            &authority.pubkey(),
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+
+    let data = account_data(&mut context, &small).await;
+    assert_eq!(&data[AUTH_BUFF_HEADER_SIZE..], &[1; 4]);
+}
@@ -0,0 +1,232 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::TIME_LOCK_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        clock::Clock,
+        signature::{Keypair, Signer},
+    },
+};
+
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    unlock_slot: u64,
+) -> (Keypair, Pubkey) {
+    let authority = Keypair::new();
+    let buffer =
+        Pubkey::find_program_address(&[b"time_lock", authority.pubkey().as_ref()], program_id).0;
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeTimeLockedEcho {
+                buffer_size: TIME_LOCK_HEADER_SIZE + 8,
+                authority: authority.pubkey(),
+                unlock_slot,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    (authority, buffer)
+}
+
+fn time_locked_write_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::TimeLockedWrite { data },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+fn time_locked_read_ix(program_id: &Pubkey, buffer: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::TimeLockedRead,
+        vec![
+            AccountMeta::new_readonly(*buffer, false),
+            AccountMeta::new_readonly(*authority, false),
+        ],
+    )
+}
+
+fn extend_time_lock_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    new_unlock_slot: u64,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::ExtendTimeLock { new_unlock_slot },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_time_locked_read_unlocks_at_slot() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let unlock_slot = clock.slot + 100;
+    let (authority, buffer) = setup(&mut context, &program_id, unlock_slot).await;
+
+    // the authority writes before the unlock slot, but nobody can read yet
+    send(
+        &mut context,
+        &[time_locked_write_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            vec![1; 8],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let result = send(
+        &mut context,
+        &[time_locked_read_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+        )],
+        &[],
+    )
+    .await;
+    assert_echo_error(result, EchoError::TimeLockNotExpired);
+
+    context.warp_to_slot(unlock_slot).unwrap();
+    send(
+        &mut context,
+        &[time_locked_read_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    // still readable long after
+    context.warp_to_slot(unlock_slot + 1_000).unwrap();
+    send(
+        &mut context,
+        &[time_locked_read_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[..8], &unlock_slot.to_le_bytes());
+    assert_eq!(&data[TIME_LOCK_HEADER_SIZE..], &[1; 8]);
+}
+
+#[tokio::test]
+async fn test_extend_time_lock() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let unlock_slot = clock.slot + 100;
+    let (authority, buffer) = setup(&mut context, &program_id, unlock_slot).await;
+
+    // the unlock slot can't be moved earlier
+    let result = send(
+        &mut context,
+        &[extend_time_lock_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            unlock_slot - 1,
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+
+    send(
+        &mut context,
+        &[extend_time_lock_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            unlock_slot + 100,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    context.warp_to_slot(unlock_slot).unwrap();
+    let result = send(
+        &mut context,
+        &[time_locked_read_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+        )],
+        &[],
+    )
+    .await;
+    assert_echo_error(result, EchoError::TimeLockNotExpired);
+
+    context.warp_to_slot(unlock_slot + 100).unwrap();
+    send(
+        &mut context,
+        &[time_locked_read_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    // an unlocked buffer can't be locked again
+    let result = send(
+        &mut context,
+        &[extend_time_lock_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            unlock_slot + 1_000,
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+}
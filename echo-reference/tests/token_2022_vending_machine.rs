@@ -0,0 +1,558 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{
+        error::EchoError, instruction::EchoInstruction,
+        processor::vending_machine_echo::spl_token_2022, state::VENDING_MACHINE_BUFF_HEADER_SIZE,
+    },
+    solana_program::program_option::COption,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_pack::Pack,
+        pubkey::Pubkey,
+        system_instruction, system_program,
+    },
+    solana_program_test::{tokio, ProgramTest, ProgramTestContext},
+    solana_sdk::{
+        account::Account,
+        signature::{Keypair, Signer},
+    },
+    spl_token::state::{Account as TokenAccount, Mint},
+};
+
+/// The program test with Token-2022 loaded from `tests/fixtures/spl_token_2022.so`, next to the bundled Token Program.
+fn program_test_with_token_2022() -> (ProgramTest, Pubkey) {
+    let (mut program_test, program_id) = program_test();
+    program_test.add_program("spl_token_2022", spl_token_2022::id(), None);
+    (program_test, program_id)
+}
+
+/// Token Program instruction sent to Token-2022, both share the instruction encoding.
+fn token_2022_ix(mut instruction: Instruction) -> Instruction {
+    instruction.program_id = spl_token_2022::id();
+    instruction
+}
+
+async fn create_token_2022_mint(
+    context: &mut ProgramTestContext,
+    mint_authority: &Pubkey,
+) -> Pubkey {
+    let mint = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                rent.minimum_balance(Mint::LEN),
+                Mint::LEN as u64,
+                &spl_token_2022::id(),
+            ),
+            token_2022_ix(
+                spl_token::instruction::initialize_mint(
+                    &spl_token::id(),
+                    &mint.pubkey(),
+                    mint_authority,
+                    None,
+                    0,
+                )
+                .unwrap(),
+            ),
+        ],
+        &[&mint],
+    )
+    .await
+    .unwrap();
+    mint.pubkey()
+}
+
+async fn create_token_2022_account(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    mint_authority: &Keypair,
+    amount: u64,
+) -> Pubkey {
+    let account = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[
+            system_instruction::create_account(
+                &payer,
+                &account.pubkey(),
+                rent.minimum_balance(TokenAccount::LEN),
+                TokenAccount::LEN as u64,
+                &spl_token_2022::id(),
+            ),
+            token_2022_ix(
+                spl_token::instruction::initialize_account(
+                    &spl_token::id(),
+                    &account.pubkey(),
+                    mint,
+                    owner,
+                )
+                .unwrap(),
+            ),
+            token_2022_ix(
+                spl_token::instruction::mint_to(
+                    &spl_token::id(),
+                    mint,
+                    &account.pubkey(),
+                    &mint_authority.pubkey(),
+                    &[],
+                    amount,
+                )
+                .unwrap(),
+            ),
+        ],
+        &[&account, mint_authority],
+    )
+    .await
+    .unwrap();
+    account.pubkey()
+}
+
+fn vending_machine_echo_2022_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    user: &Pubkey,
+    user_token_account: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    treasury: Option<&Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*buffer, false),
+        AccountMeta::new_readonly(*user, true),
+        AccountMeta::new(*user_token_account, false),
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*token_program, false),
+    ];
+    accounts.extend(treasury.map(|treasury| AccountMeta::new(*treasury, false)));
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::VendingMachineEcho { data: vec![7; 8] },
+        accounts,
+    )
+}
+
+#[tokio::test]
+async fn test_token_2022_vending_machine_burns_and_writes() {
+    let (program_test, program_id) = program_test_with_token_2022();
+    let mut context = program_test.start_with_context().await;
+    let mint_authority = Keypair::new();
+    let mint = create_token_2022_mint(&mut context, &mint_authority.pubkey()).await;
+    let user = Keypair::new();
+    let user_token_account =
+        create_token_2022_account(&mut context, &mint, &user.pubkey(), &mint_authority, 10).await;
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[initialize_vending_machine_echo_ix(
+            &program_id,
+            &mint,
+            &mint_authority.pubkey(),
+            &payer,
+            5,
+            VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+    let (buffer, _) = vending_machine_address(&program_id, &mint, 5);
+
+    send(
+        &mut context,
+        &[vending_machine_echo_2022_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            &user_token_account,
+            &mint,
+            &spl_token_2022::id(),
+            None,
+        )],
+        &[&user],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(token_balance(&mut context, &user_token_account).await, 5);
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[VENDING_MACHINE_BUFF_HEADER_SIZE..VENDING_MACHINE_BUFF_HEADER_SIZE + 8],
+        &[7; 8]
+    );
+}
+
+#[tokio::test]
+async fn test_token_2022_vending_machine_transfers_to_treasury() {
+    let (program_test, program_id) = program_test_with_token_2022();
+    let mut context = program_test.start_with_context().await;
+    let mint_authority = Keypair::new();
+    let mint = create_token_2022_mint(&mut context, &mint_authority.pubkey()).await;
+    let user = Keypair::new();
+    let user_token_account =
+        create_token_2022_account(&mut context, &mint, &user.pubkey(), &mint_authority, 10).await;
+    let treasury = create_token_2022_account(
+        &mut context,
+        &mint,
+        &mint_authority.pubkey(),
+        &mint_authority,
+        0,
+    )
+    .await;
+    let (buffer, _) = vending_machine_address(&program_id, &mint, 3);
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::InitializeVendingMachineEcho {
+                price: 3,
+                buffer_size: VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+                payment_mode: 1,
+                price_per_byte: 0,
+                require_ata: false,
+                max_uses: 0,
+                tiers: vec![],
+                cooldown_slots: 0,
+                free_authority_writes: false,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(mint_authority.pubkey(), true),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(treasury, false),
+            ],
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+
+    send(
+        &mut context,
+        &[vending_machine_echo_2022_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            &user_token_account,
+            &mint,
+            &spl_token_2022::id(),
+            Some(&treasury),
+        )],
+        &[&user],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(token_balance(&mut context, &user_token_account).await, 7);
+    assert_eq!(token_balance(&mut context, &treasury).await, 3);
+}
+
+#[tokio::test]
+async fn test_token_2022_vending_machine_proceeds_are_withdrawn() {
+    let (program_test, program_id) = program_test_with_token_2022();
+    let mut context = program_test.start_with_context().await;
+    let mint_authority = Keypair::new();
+    let mint = create_token_2022_mint(&mut context, &mint_authority.pubkey()).await;
+    let user = Keypair::new();
+    let user_token_account =
+        create_token_2022_account(&mut context, &mint, &user.pubkey(), &mint_authority, 10).await;
+    let (buffer, _) = vending_machine_address(&program_id, &mint, 3);
+    let (vault_authority, _) =
+        Pubkey::find_program_address(&[b"vending_vault", buffer.as_ref()], &program_id);
+    let vault =
+        create_token_2022_account(&mut context, &mint, &vault_authority, &mint_authority, 0).await;
+    let destination = create_token_2022_account(
+        &mut context,
+        &mint,
+        &mint_authority.pubkey(),
+        &mint_authority,
+        0,
+    )
+    .await;
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[
+            Instruction::new_with_borsh(
+                program_id,
+                &EchoInstruction::InitializeVendingMachineEcho {
+                    price: 3,
+                    buffer_size: VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+                    payment_mode: 1,
+                    price_per_byte: 0,
+                    require_ata: false,
+                    max_uses: 0,
+                    tiers: vec![],
+                    cooldown_slots: 0,
+                    free_authority_writes: false,
+                },
+                vec![
+                    AccountMeta::new(buffer, false),
+                    AccountMeta::new_readonly(mint, false),
+                    AccountMeta::new_readonly(mint_authority.pubkey(), true),
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                    AccountMeta::new_readonly(vault, false),
+                ],
+            ),
+            vending_machine_echo_2022_ix(
+                &program_id,
+                &buffer,
+                &user.pubkey(),
+                &user_token_account,
+                &mint,
+                &spl_token_2022::id(),
+                Some(&vault),
+            ),
+        ],
+        &[&mint_authority, &user],
+    )
+    .await
+    .unwrap();
+    assert_eq!(token_balance(&mut context, &vault).await, 3);
+
+    let withdraw_ix = |token_program: Pubkey| {
+        Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::WithdrawVendingMachineProceeds { amount: 0 },
+            vec![
+                AccountMeta::new_readonly(buffer, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(vault_authority, false),
+                AccountMeta::new(destination, false),
+                AccountMeta::new_readonly(mint_authority.pubkey(), true),
+                AccountMeta::new_readonly(token_program, false),
+                AccountMeta::new_readonly(mint, false),
+            ],
+        )
+    };
+
+    // the proceeds were paid through Token-2022, they can't be moved with the Token Program
+    let result = send(
+        &mut context,
+        &[withdraw_ix(spl_token::id())],
+        &[&mint_authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidProgramAddress);
+
+    // once withdrawn the treasury is empty and the machine can be closed
+    send(
+        &mut context,
+        &[
+            withdraw_ix(spl_token_2022::id()),
+            Instruction::new_with_borsh(
+                program_id,
+                &EchoInstruction::CloseVendingMachine,
+                vec![
+                    AccountMeta::new(buffer, false),
+                    AccountMeta::new_readonly(mint_authority.pubkey(), true),
+                    AccountMeta::new(payer, false),
+                    AccountMeta::new_readonly(mint, false),
+                    AccountMeta::new_readonly(vault, false),
+                ],
+            ),
+        ],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(token_balance(&mut context, &vault).await, 0);
+    assert_eq!(token_balance(&mut context, &destination).await, 3);
+    assert!(context
+        .banks_client
+        .get_account(buffer)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_vending_machine_rejects_mixed_token_programs() {
+    let (program_test, program_id) = program_test_with_token_2022();
+    let mut context = program_test.start_with_context().await;
+
+    // a Token-2022 machine written to through the Token Program
+    let mint_authority = Keypair::new();
+    let mint = create_token_2022_mint(&mut context, &mint_authority.pubkey()).await;
+    let user = Keypair::new();
+    let user_token_account =
+        create_token_2022_account(&mut context, &mint, &user.pubkey(), &mint_authority, 10).await;
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[initialize_vending_machine_echo_ix(
+            &program_id,
+            &mint,
+            &mint_authority.pubkey(),
+            &payer,
+            5,
+            VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+    let (buffer, _) = vending_machine_address(&program_id, &mint, 5);
+    let result = send(
+        &mut context,
+        &[vending_machine_echo_2022_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            &user_token_account,
+            &mint,
+            &spl_token::id(),
+            None,
+        )],
+        &[&user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidProgramAddress);
+
+    // a Token-2022 account passed to a legacy machine
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+    let result = send(
+        &mut context,
+        &[vending_machine_echo_ix(
+            &program_id,
+            &vm.buffer,
+            &user.pubkey(),
+            &user_token_account,
+            &vm.mint,
+            vec![7; 8],
+        )],
+        &[&user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountOwner);
+    assert_eq!(token_balance(&mut context, &user_token_account).await, 10);
+}
+
+/// The data of an initialized mint of `mint_authority`, padded to `len` with the `account_type` at its offset when
+/// `len` leaves room for one.
+fn mint_data(mint_authority: &Pubkey, len: usize, account_type: u8) -> Vec<u8> {
+    let mut data = vec![0; len];
+    Mint {
+        mint_authority: COption::Some(*mint_authority),
+        supply: 0,
+        decimals: 0,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut data[..Mint::LEN]);
+    if len > spl_token_2022::ACCOUNT_TYPE_OFFSET {
+        data[spl_token_2022::ACCOUNT_TYPE_OFFSET] = account_type;
+    }
+    data
+}
+
+fn add_mint(program_test: &mut ProgramTest, owner: Pubkey, data: Vec<u8>) -> Pubkey {
+    let mint = Pubkey::new_unique();
+    program_test.add_account(
+        mint,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner,
+            ..Account::default()
+        },
+    );
+    mint
+}
+
+#[tokio::test]
+async fn test_initialize_vending_machine_checks_the_token_2022_account_type() {
+    let (mut program_test, program_id) = program_test_with_token_2022();
+    let mint_authority = Keypair::new();
+    let extended_len = spl_token_2022::ACCOUNT_TYPE_OFFSET + 5;
+    let with_extensions = add_mint(
+        &mut program_test,
+        spl_token_2022::id(),
+        mint_data(
+            &mint_authority.pubkey(),
+            extended_len,
+            spl_token_2022::ACCOUNT_TYPE_MINT,
+        ),
+    );
+    // a token account with extensions, even with the bytes of a mint first
+    let token_account = add_mint(
+        &mut program_test,
+        spl_token_2022::id(),
+        mint_data(
+            &mint_authority.pubkey(),
+            extended_len,
+            spl_token_2022::ACCOUNT_TYPE_ACCOUNT,
+        ),
+    );
+    let multisig = add_mint(
+        &mut program_test,
+        spl_token_2022::id(),
+        mint_data(
+            &mint_authority.pubkey(),
+            spl_token_2022::MULTISIG_LEN,
+            spl_token_2022::ACCOUNT_TYPE_MINT,
+        ),
+    );
+    // the Token Program has no extensions, its mints have the exact length of one
+    let legacy_padded = add_mint(
+        &mut program_test,
+        spl_token::id(),
+        mint_data(&mint_authority.pubkey(), TokenAccount::LEN, 0),
+    );
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+
+    for mint in [token_account, multisig, legacy_padded] {
+        let result = send(
+            &mut context,
+            &[initialize_vending_machine_echo_ix(
+                &program_id,
+                &mint,
+                &mint_authority.pubkey(),
+                &payer,
+                5,
+                VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+            )],
+            &[&mint_authority],
+        )
+        .await;
+        assert_echo_error(result, EchoError::InvalidMint);
+    }
+
+    send(
+        &mut context,
+        &[initialize_vending_machine_echo_ix(
+            &program_id,
+            &with_extensions,
+            &mint_authority.pubkey(),
+            &payer,
+            5,
+            VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+}
@@ -0,0 +1,334 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{VendingUserRecord, VENDING_MACHINE_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        signature::{Keypair, Signer},
+        transport::TransportError,
+    },
+};
+
+const PRICE: u64 = 5;
+const COOLDOWN_SLOTS: u64 = 10;
+
+struct Cooldown {
+    mint: Pubkey,
+    buffer: Pubkey,
+    users: Vec<(Keypair, Pubkey)>,
+}
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> Cooldown {
+    let mint_authority = Keypair::new();
+    let mint = create_mint(context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+
+    let mut users = vec![];
+    for _ in 0..2 {
+        let user = funded_keypair(context).await;
+        let user_token_account = create_token_account(context, &mint, &user.pubkey())
+            .await
+            .pubkey();
+        mint_tokens(context, &mint, &user_token_account, &mint_authority, 20).await;
+        users.push((user, user_token_account));
+    }
+
+    let (buffer, _) = vending_machine_address(program_id, &mint, PRICE);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeVendingMachineEcho {
+                price: PRICE,
+                buffer_size: VENDING_MACHINE_BUFF_HEADER_SIZE + 16,
+                payment_mode: 0,
+                price_per_byte: 0,
+                require_ata: false,
+                max_uses: 0,
+                tiers: vec![],
+                cooldown_slots: COOLDOWN_SLOTS,
+                free_authority_writes: false,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(mint_authority.pubkey(), true),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+
+    Cooldown {
+        mint,
+        buffer,
+        users,
+    }
+}
+
+fn user_record_address(program_id: &Pubkey, buffer: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"vending_user", buffer.as_ref(), user.as_ref()],
+        program_id,
+    )
+    .0
+}
+
+async fn write(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    cooldown: &Cooldown,
+    user_index: usize,
+    data: Vec<u8>,
+) -> Result<(), TransportError> {
+    let (user, user_token_account) = &cooldown.users[user_index];
+    let mut instruction = vending_machine_echo_ix(
+        program_id,
+        &cooldown.buffer,
+        &user.pubkey(),
+        user_token_account,
+        &cooldown.mint,
+        data,
+    );
+    // the user pays for their record on their first write
+    instruction.accounts[1] = AccountMeta::new(user.pubkey(), true);
+    instruction.accounts.extend([
+        AccountMeta::new(
+            user_record_address(program_id, &cooldown.buffer, &user.pubkey()),
+            false,
+        ),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]);
+    send(context, &[instruction], &[user]).await
+}
+
+fn close_user_record_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    user_record: &Pubkey,
+    user: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::CloseVendingUserRecord,
+        vec![
+            AccountMeta::new_readonly(*buffer, false),
+            AccountMeta::new(*user_record, false),
+            AccountMeta::new(*user, true),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_vending_machine_cooldown_per_user() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let cooldown = setup(&mut context, &program_id).await;
+
+    context.warp_to_slot(100).unwrap();
+    write(&mut context, &program_id, &cooldown, 0, vec![1; 4])
+        .await
+        .unwrap();
+
+    let user = cooldown.users[0].0.pubkey();
+    let data = account_data(
+        &mut context,
+        &user_record_address(&program_id, &cooldown.buffer, &user),
+    )
+    .await;
+    assert_eq!(
+        VendingUserRecord::try_from_slice(&data)
+            .unwrap()
+            .last_write_slot,
+        100
+    );
+
+    // nothing is burned by a write within the cooldown
+    let result = write(&mut context, &program_id, &cooldown, 0, vec![2; 4]).await;
+    assert_echo_error(result, EchoError::CooldownActive);
+    assert_eq!(
+        token_balance(&mut context, &cooldown.users[0].1).await,
+        20 - PRICE
+    );
+
+    // the cooldown is per user
+    write(&mut context, &program_id, &cooldown, 1, vec![3; 4])
+        .await
+        .unwrap();
+    assert_eq!(
+        token_balance(&mut context, &cooldown.users[1].1).await,
+        20 - PRICE
+    );
+
+    context.warp_to_slot(100 + COOLDOWN_SLOTS).unwrap();
+    write(&mut context, &program_id, &cooldown, 0, vec![4; 4])
+        .await
+        .unwrap();
+    assert_eq!(
+        token_balance(&mut context, &cooldown.users[0].1).await,
+        20 - 2 * PRICE
+    );
+
+    let data = account_data(&mut context, &cooldown.buffer).await;
+    assert_eq!(
+        &data[VENDING_MACHINE_BUFF_HEADER_SIZE..],
+        &[4, 4, 4, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
+
+#[tokio::test]
+async fn test_vending_machine_cooldown_rejects_wrong_user_record() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let cooldown = setup(&mut context, &program_id).await;
+
+    // the record of another user can't be used to dodge the cooldown
+    let (user, user_token_account) = &cooldown.users[0];
+    let other = cooldown.users[1].0.pubkey();
+    let mut instruction = vending_machine_echo_ix(
+        &program_id,
+        &cooldown.buffer,
+        &user.pubkey(),
+        user_token_account,
+        &cooldown.mint,
+        vec![1; 4],
+    );
+    instruction.accounts[1] = AccountMeta::new(user.pubkey(), true);
+    instruction.accounts.extend([
+        AccountMeta::new(
+            user_record_address(&program_id, &cooldown.buffer, &other),
+            false,
+        ),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]);
+    let result = send(&mut context, &[instruction], &[user]).await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+}
+
+#[tokio::test]
+async fn test_close_vending_user_record() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let cooldown = setup(&mut context, &program_id).await;
+    let (user, _) = &cooldown.users[0];
+    let user_record = user_record_address(&program_id, &cooldown.buffer, &user.pubkey());
+
+    context.warp_to_slot(100).unwrap();
+    write(&mut context, &program_id, &cooldown, 0, vec![1; 4])
+        .await
+        .unwrap();
+
+    // the cooldown can't be skipped by closing the record
+    let result = send(
+        &mut context,
+        &[close_user_record_ix(
+            &program_id,
+            &cooldown.buffer,
+            &user_record,
+            &user.pubkey(),
+        )],
+        &[user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::CooldownActive);
+
+    context.warp_to_slot(100 + COOLDOWN_SLOTS).unwrap();
+    let record_lamports = context.banks_client.get_balance(user_record).await.unwrap();
+    let user_lamports = context
+        .banks_client
+        .get_balance(user.pubkey())
+        .await
+        .unwrap();
+    send(
+        &mut context,
+        &[close_user_record_ix(
+            &program_id,
+            &cooldown.buffer,
+            &user_record,
+            &user.pubkey(),
+        )],
+        &[user],
+    )
+    .await
+    .unwrap();
+    assert!(context
+        .banks_client
+        .get_account(user_record)
+        .await
+        .unwrap()
+        .is_none());
+    // the user also paid the transaction fee
+    assert!(
+        context
+            .banks_client
+            .get_balance(user.pubkey())
+            .await
+            .unwrap()
+            > user_lamports
+    );
+    assert!(record_lamports > 0);
+
+    // the next write creates a new record
+    write(&mut context, &program_id, &cooldown, 0, vec![2; 4])
+        .await
+        .unwrap();
+    let data = account_data(&mut context, &user_record).await;
+    assert_eq!(
+        VendingUserRecord::try_from_slice(&data)
+            .unwrap()
+            .last_write_slot,
+        100 + COOLDOWN_SLOTS
+    );
+}
+
+#[tokio::test]
+async fn test_close_vending_user_record_of_someone_else() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let cooldown = setup(&mut context, &program_id).await;
+
+    write(&mut context, &program_id, &cooldown, 0, vec![1; 4])
+        .await
+        .unwrap();
+    context.warp_to_slot(100).unwrap();
+
+    // the second user can't take the rent of the record of the first one
+    let record = user_record_address(&program_id, &cooldown.buffer, &cooldown.users[0].0.pubkey());
+    let (other, _) = &cooldown.users[1];
+    let result = send(
+        &mut context,
+        &[close_user_record_ix(
+            &program_id,
+            &cooldown.buffer,
+            &record,
+            &other.pubkey(),
+        )],
+        &[other],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+    assert!(context
+        .banks_client
+        .get_account(record)
+        .await
+        .unwrap()
+        .is_some());
+}
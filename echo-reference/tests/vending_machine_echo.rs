@@ -0,0 +1,2719 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        pda::{get_associated_token_address, spl_associated_token_account},
+        state::{
+            PriceTier, VendingMachineBuffer, VendingMachineBufferHeader, VendingMachineStats,
+            MAX_PRICE_TIERS, PAYMENT_MODE_TREASURY, VENDING_MACHINE_BUFF_HEADER_SIZE,
+        },
+    },
+    solana_program::{
+        clock::Clock,
+        instruction::{AccountMeta, Instruction},
+        program_pack::Pack,
+        pubkey::Pubkey,
+        system_instruction, system_program, sysvar,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        account::Account,
+        compute_budget::ComputeBudgetInstruction,
+        signature::{Keypair, Signer},
+    },
+    spl_token::state::{Account as TokenAccount, AccountState, Mint},
+};
+
+#[tokio::test]
+async fn test_vending_machine_echo_burns_and_writes() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+
+    send(
+        &mut context,
+        &[vending_machine_echo_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            &vm.user_token_account,
+            &vm.mint,
+            vec![7; 8],
+        )],
+        &[&vm.user],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(token_balance(&mut context, &vm.user_token_account).await, 5);
+    let data = account_data(&mut context, &vm.buffer).await;
+    assert_eq!(
+        &data[VENDING_MACHINE_BUFF_HEADER_SIZE..VENDING_MACHINE_BUFF_HEADER_SIZE + 8],
+        &[7; 8]
+    );
+}
+
+/// Compute units of a write of `data`, the smallest budget the write succeeds with. Every probe that succeeds writes
+/// `data` again and pays the price, so writing the same `data` beforehand keeps the cost of every probe the same.
+async fn vending_machine_echo_cost(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    vm: &VendingMachine,
+    data: Vec<u8>,
+) -> u32 {
+    let (mut low, mut high) = (0, 200_000);
+    while high - low > 1 {
+        let units = low + (high - low) / 2;
+        let result = send(
+            context,
+            &[
+                ComputeBudgetInstruction::request_units(units),
+                vending_machine_echo_ix(
+                    program_id,
+                    &vm.buffer,
+                    &vm.user.pubkey(),
+                    &vm.user_token_account,
+                    &vm.mint,
+                    data.clone(),
+                ),
+            ],
+            &[&vm.user],
+        )
+        .await;
+        match result {
+            Ok(()) => high = units,
+            Err(_) => low = units,
+        }
+    }
+    high
+}
+
+#[tokio::test]
+async fn test_vending_machine_echo_cost_does_not_grow_with_buffer_size() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+
+    let mut costs = vec![];
+    for payload_len in [64, 10_240] {
+        let vm = setup_vending_machine(
+            &mut context,
+            &program_id,
+            1,
+            VENDING_MACHINE_BUFF_HEADER_SIZE + payload_len,
+            100,
+        )
+        .await;
+        send(
+            &mut context,
+            &[vending_machine_echo_ix(
+                &program_id,
+                &vm.buffer,
+                &vm.user.pubkey(),
+                &vm.user_token_account,
+                &vm.mint,
+                vec![8; 8],
+            )],
+            &[&vm.user],
+        )
+        .await
+        .unwrap();
+
+        costs.push(vending_machine_echo_cost(&mut context, &program_id, &vm, vec![8; 8]).await);
+
+        let data = account_data(&mut context, &vm.buffer).await;
+        assert_eq!(
+            &data[VENDING_MACHINE_BUFF_HEADER_SIZE..VENDING_MACHINE_BUFF_HEADER_SIZE + 8],
+            &[8; 8]
+        );
+        assert!(data[VENDING_MACHINE_BUFF_HEADER_SIZE + 8..]
+            .iter()
+            .all(|byte| *byte == 0));
+    }
+
+    // a short write only touches the bytes it copies and the previous data, zeroing the whole 10KB payload byte by
+    // byte on every write cost several compute units per byte
+    // a cost at either bound means the requested budget wasn't applied
+    assert!(costs.iter().all(|cost| (2..200_000).contains(cost)));
+    let delta = costs[1] as i64 - costs[0] as i64;
+    println!(
+        "8 byte write: {} CU on a 64 byte payload, {} CU on a 10KB payload, delta {} CU",
+        costs[0], costs[1], delta
+    );
+    assert!(delta < 1_000, "delta of {} CU", delta);
+}
+
+#[tokio::test]
+async fn test_vending_machine_echo_rejects_buffer_owned_by_other_program() {
+    let (mut program_test, program_id) = program_test();
+    // the mint is created after startup, so spoof the buffer for a pre-chosen mint address
+    let mint = Keypair::new();
+    let price = 5;
+    let (buffer, bump_seed) = vending_machine_address(&program_id, &mint.pubkey(), price);
+    let mut data = VendingMachineBufferHeader {
+        bump_seed,
+        price,
+        free: false,
+        payment_mode: 0,
+        treasury: Pubkey::default(),
+        admin: Pubkey::default(),
+        paused: false,
+        price_per_byte: 0,
+        require_ata: false,
+        token_program: spl_token::id(),
+        mint: mint.pubkey(),
+        uses: 0,
+        max_uses: 0,
+        sol_fee: 0,
+        tier_count: 0,
+        tiers: [PriceTier::default(); MAX_PRICE_TIERS],
+        last_writer: Pubkey::default(),
+        last_write_slot: 0,
+        expiry_slot: 0,
+        total_writes: 0,
+        total_burned: 0,
+        data_len: 0,
+        is_initialized: true,
+        start_slot: 0,
+        end_slot: 0,
+        cooldown_slots: 0,
+        whitelist_enabled: false,
+        free_authority_writes: false,
+    }
+    .try_to_vec()
+    .unwrap();
+    data.resize(VENDING_MACHINE_BUFF_HEADER_SIZE + 128, 0);
+    program_test.add_account(
+        buffer,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: Pubkey::new_unique(),
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let mint_authority = Keypair::new();
+    initialize_mint(&mut context, &mint, &mint_authority.pubkey(), 0).await;
+    let user = Keypair::new();
+    let user_token_account = create_token_account(&mut context, &mint.pubkey(), &user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(
+        &mut context,
+        &mint.pubkey(),
+        &user_token_account,
+        &mint_authority,
+        10,
+    )
+    .await;
+
+    let result = send(
+        &mut context,
+        &[vending_machine_echo_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            &user_token_account,
+            &mint.pubkey(),
+            vec![7; 8],
+        )],
+        &[&user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountOwner);
+    assert_eq!(token_balance(&mut context, &user_token_account).await, 10);
+}
+
+#[tokio::test]
+async fn test_vending_machine_echo_rejects_uninitialized_buffer() {
+    let (mut program_test, program_id) = program_test();
+    // an account of the Echo Program at the vending machine address that never went through the initializer
+    let mint = Keypair::new();
+    let (buffer, _) = vending_machine_address(&program_id, &mint.pubkey(), 5);
+    program_test.add_account(
+        buffer,
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![0; VENDING_MACHINE_BUFF_HEADER_SIZE + 128],
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let mint_authority = Keypair::new();
+    initialize_mint(&mut context, &mint, &mint_authority.pubkey(), 0).await;
+    let user = Keypair::new();
+    let user_token_account = create_token_account(&mut context, &mint.pubkey(), &user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(
+        &mut context,
+        &mint.pubkey(),
+        &user_token_account,
+        &mint_authority,
+        10,
+    )
+    .await;
+
+    let result = send(
+        &mut context,
+        &[vending_machine_echo_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            &user_token_account,
+            &mint.pubkey(),
+            vec![7; 8],
+        )],
+        &[&user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::AccountNotInitialized);
+    assert_eq!(token_balance(&mut context, &user_token_account).await, 10);
+}
+
+#[tokio::test]
+async fn test_vending_machine_echo_rejects_unknown_token_program() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 8,
+        10,
+    )
+    .await;
+
+    let mut instruction = vending_machine_echo_ix(
+        &program_id,
+        &vm.buffer,
+        &vm.user.pubkey(),
+        &vm.user_token_account,
+        &vm.mint,
+        vec![7; 8],
+    );
+    instruction.accounts[4] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+    let result = send(&mut context, &[instruction], &[&vm.user]).await;
+    assert_echo_error(result, EchoError::InvalidProgramAddress);
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        10
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_vending_machine_twice_fails() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+    send(
+        &mut context,
+        &[vending_machine_echo_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            &vm.user_token_account,
+            &vm.mint,
+            vec![7; 8],
+        )],
+        &[&vm.user],
+    )
+    .await
+    .unwrap();
+    let before = account_data(&mut context, &vm.buffer).await;
+
+    let payer = context.payer.pubkey();
+    let result = send(
+        &mut context,
+        &[initialize_vending_machine_echo_ix(
+            &program_id,
+            &vm.mint,
+            &vm.mint_authority.pubkey(),
+            &payer,
+            5,
+            VENDING_MACHINE_BUFF_HEADER_SIZE + 64,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::AccountAlreadyInitialized);
+
+    // the stats and payload of the first initialization are kept
+    assert_eq!(account_data(&mut context, &vm.buffer).await, before);
+}
+
+fn close_vending_machine_buffer_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    mint: &Pubkey,
+    mint_authority: &Pubkey,
+    destination: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::CloseVendingMachineBuffer,
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*mint_authority, true),
+            AccountMeta::new(*destination, false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_close_vending_machine_buffer_moves_lamports() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+
+    send(
+        &mut context,
+        &[vending_machine_echo_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            &vm.user_token_account,
+            &vm.mint,
+            vec![7; 8],
+        )],
+        &[&vm.user],
+    )
+    .await
+    .unwrap();
+
+    let destination = funded_keypair(&mut context).await.pubkey();
+    let destination_lamports = context.banks_client.get_balance(destination).await.unwrap();
+    let buffer_lamports = context.banks_client.get_balance(vm.buffer).await.unwrap();
+
+    // the user holds tokens but isn't the mint authority
+    let result = send(
+        &mut context,
+        &[close_vending_machine_buffer_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint,
+            &vm.user.pubkey(),
+            &destination,
+        )],
+        &[&vm.user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    send(
+        &mut context,
+        &[close_vending_machine_buffer_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint,
+            &vm.mint_authority.pubkey(),
+            &destination,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        context.banks_client.get_balance(destination).await.unwrap(),
+        destination_lamports + buffer_lamports
+    );
+    assert!(context
+        .banks_client
+        .get_account(vm.buffer)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_initialize_vending_machine_requires_mint_authority() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let mint_authority = Keypair::new();
+    let mint = create_mint(&mut context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+
+    // an attacker signing as the mint authority of a mint they don't control
+    let attacker = Keypair::new();
+    let payer = context.payer.pubkey();
+    let result = send(
+        &mut context,
+        &[initialize_vending_machine_echo_ix(
+            &program_id,
+            &mint,
+            &attacker.pubkey(),
+            &payer,
+            1,
+            VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        )],
+        &[&attacker],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidMintAuthority);
+}
+
+fn initialize_treasury_vending_machine_ix(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    mint_authority: &Pubkey,
+    payer: &Pubkey,
+    treasury: &Pubkey,
+    price: u64,
+) -> Instruction {
+    let (buffer, _) = vending_machine_address(program_id, mint, price);
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::InitializeVendingMachineEcho {
+            price,
+            buffer_size: VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+            payment_mode: PAYMENT_MODE_TREASURY,
+            price_per_byte: 0,
+            require_ata: false,
+            max_uses: 0,
+            tiers: vec![],
+            cooldown_slots: 0,
+            free_authority_writes: false,
+        },
+        vec![
+            AccountMeta::new(buffer, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*mint_authority, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(*treasury, false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_vending_machine_echo_transfers_to_treasury() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let mint_authority = Keypair::new();
+    let mint = create_mint(&mut context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let treasury = create_token_account(&mut context, &mint, &mint_authority.pubkey())
+        .await
+        .pubkey();
+    let user = Keypair::new();
+    let user_token_account = create_token_account(&mut context, &mint, &user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(
+        &mut context,
+        &mint,
+        &user_token_account,
+        &mint_authority,
+        10,
+    )
+    .await;
+
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[initialize_treasury_vending_machine_ix(
+            &program_id,
+            &mint,
+            &mint_authority.pubkey(),
+            &payer,
+            &treasury,
+            3,
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+    let (buffer, _) = vending_machine_address(&program_id, &mint, 3);
+
+    let mut write = vending_machine_echo_ix(
+        &program_id,
+        &buffer,
+        &user.pubkey(),
+        &user_token_account,
+        &mint,
+        vec![7; 8],
+    );
+    write.accounts.push(AccountMeta::new(treasury, false));
+    send(&mut context, &[write], &[&user]).await.unwrap();
+
+    assert_eq!(token_balance(&mut context, &user_token_account).await, 7);
+    assert_eq!(token_balance(&mut context, &treasury).await, 3);
+    let supply = Mint::unpack(&account_data(&mut context, &mint).await)
+        .unwrap()
+        .supply;
+    assert_eq!(supply, 10);
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[VENDING_MACHINE_BUFF_HEADER_SIZE..VENDING_MACHINE_BUFF_HEADER_SIZE + 8],
+        &[7; 8]
+    );
+
+    // the write must pay the treasury recorded at initialization
+    let other = create_token_account(&mut context, &mint, &user.pubkey())
+        .await
+        .pubkey();
+    let mut write = vending_machine_echo_ix(
+        &program_id,
+        &buffer,
+        &user.pubkey(),
+        &user_token_account,
+        &mint,
+        vec![8; 8],
+    );
+    write.accounts.push(AccountMeta::new(other, false));
+    let result = send(&mut context, &[write], &[&user]).await;
+    assert_echo_error(result, EchoError::InvalidAccountAddress);
+    assert_eq!(token_balance(&mut context, &user_token_account).await, 7);
+}
+
+#[tokio::test]
+async fn test_treasury_must_hold_the_vending_machine_mint() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let mint_authority = Keypair::new();
+    let mint = create_mint(&mut context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let other_mint = create_mint(&mut context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let treasury = create_token_account(&mut context, &other_mint, &mint_authority.pubkey())
+        .await
+        .pubkey();
+
+    let payer = context.payer.pubkey();
+    let result = send(
+        &mut context,
+        &[initialize_treasury_vending_machine_ix(
+            &program_id,
+            &mint,
+            &mint_authority.pubkey(),
+            &payer,
+            &treasury,
+            3,
+        )],
+        &[&mint_authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountData);
+}
+
+fn withdraw_proceeds_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    mint: &Pubkey,
+    vault: &Pubkey,
+    destination: &Pubkey,
+    admin: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let (vault_authority, _) =
+        Pubkey::find_program_address(&[b"vending_vault", buffer.as_ref()], program_id);
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::WithdrawVendingMachineProceeds { amount },
+        vec![
+            AccountMeta::new_readonly(*buffer, false),
+            AccountMeta::new(*vault, false),
+            AccountMeta::new_readonly(vault_authority, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*mint, false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_withdraw_vending_machine_proceeds() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let mint_authority = Keypair::new();
+    let mint = create_mint(&mut context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let (buffer, _) = vending_machine_address(&program_id, &mint, 3);
+    let (vault_authority, _) =
+        Pubkey::find_program_address(&[b"vending_vault", buffer.as_ref()], &program_id);
+
+    // the treasury is the vault, so the payments can be withdrawn
+    let vault = create_token_account(&mut context, &mint, &vault_authority)
+        .await
+        .pubkey();
+    let user = Keypair::new();
+    let user_token_account = create_token_account(&mut context, &mint, &user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(
+        &mut context,
+        &mint,
+        &user_token_account,
+        &mint_authority,
+        10,
+    )
+    .await;
+
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[initialize_treasury_vending_machine_ix(
+            &program_id,
+            &mint,
+            &mint_authority.pubkey(),
+            &payer,
+            &vault,
+            3,
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+
+    for data in [vec![1; 8], vec![2; 8]] {
+        let mut write = vending_machine_echo_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            &user_token_account,
+            &mint,
+            data,
+        );
+        write.accounts.push(AccountMeta::new(vault, false));
+        send(&mut context, &[write], &[&user]).await.unwrap();
+    }
+    assert_eq!(token_balance(&mut context, &vault).await, 6);
+
+    let destination = create_token_account(&mut context, &mint, &mint_authority.pubkey())
+        .await
+        .pubkey();
+
+    // the user paid into the vault but isn't the admin
+    let result = send(
+        &mut context,
+        &[withdraw_proceeds_ix(
+            &program_id,
+            &buffer,
+            &mint,
+            &vault,
+            &destination,
+            &user.pubkey(),
+            0,
+        )],
+        &[&user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    send(
+        &mut context,
+        &[withdraw_proceeds_ix(
+            &program_id,
+            &buffer,
+            &mint,
+            &vault,
+            &destination,
+            &mint_authority.pubkey(),
+            2,
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(token_balance(&mut context, &vault).await, 4);
+    assert_eq!(token_balance(&mut context, &destination).await, 2);
+
+    // an amount of 0 withdraws the whole balance
+    send(
+        &mut context,
+        &[withdraw_proceeds_ix(
+            &program_id,
+            &buffer,
+            &mint,
+            &vault,
+            &destination,
+            &mint_authority.pubkey(),
+            0,
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(token_balance(&mut context, &vault).await, 0);
+    assert_eq!(token_balance(&mut context, &destination).await, 6);
+}
+
+#[tokio::test]
+async fn test_withdraw_vending_machine_proceeds_rejects_mismatched_mints() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        3,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        0,
+    )
+    .await;
+    let (vault_authority, _) =
+        Pubkey::find_program_address(&[b"vending_vault", vm.buffer.as_ref()], &program_id);
+    let vault = create_token_account(&mut context, &vm.mint, &vault_authority)
+        .await
+        .pubkey();
+
+    let other_mint = create_mint(&mut context, &vm.mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let other_vault = create_token_account(&mut context, &other_mint, &vault_authority)
+        .await
+        .pubkey();
+    let other_destination =
+        create_token_account(&mut context, &other_mint, &vm.mint_authority.pubkey())
+            .await
+            .pubkey();
+    let destination = create_token_account(&mut context, &vm.mint, &vm.mint_authority.pubkey())
+        .await
+        .pubkey();
+
+    for (vault, destination) in [(other_vault, other_destination), (vault, other_destination)] {
+        let result = send(
+            &mut context,
+            &[withdraw_proceeds_ix(
+                &program_id,
+                &vm.buffer,
+                &vm.mint,
+                &vault,
+                &destination,
+                &vm.mint_authority.pubkey(),
+                0,
+            )],
+            &[&vm.mint_authority],
+        )
+        .await;
+        assert_echo_error(result, EchoError::InvalidAccountData);
+    }
+
+    // the mint is checked against the header, whatever the vault holds
+    let result = send(
+        &mut context,
+        &[withdraw_proceeds_ix(
+            &program_id,
+            &vm.buffer,
+            &other_mint,
+            &other_vault,
+            &other_destination,
+            &vm.mint_authority.pubkey(),
+            0,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::MintMismatch);
+
+    send(
+        &mut context,
+        &[withdraw_proceeds_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint,
+            &vault,
+            &destination,
+            &vm.mint_authority.pubkey(),
+            0,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await
+    .unwrap();
+}
+
+fn set_paused_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    mint: &Pubkey,
+    admin: &Pubkey,
+    paused: bool,
+) -> Instruction {
+    let instruction = match paused {
+        true => EchoInstruction::PauseVendingMachine,
+        false => EchoInstruction::ResumeVendingMachine,
+    };
+    Instruction::new_with_borsh(
+        *program_id,
+        &instruction,
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*admin, true),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_pause_and_resume_vending_machine() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+    let write = |data: Vec<u8>| {
+        vending_machine_echo_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            &vm.user_token_account,
+            &vm.mint,
+            data,
+        )
+    };
+
+    // the user isn't the admin
+    let result = send(
+        &mut context,
+        &[set_paused_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint,
+            &vm.user.pubkey(),
+            true,
+        )],
+        &[&vm.user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    send(
+        &mut context,
+        &[set_paused_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint,
+            &vm.mint_authority.pubkey(),
+            true,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await
+    .unwrap();
+
+    let result = send(&mut context, &[write(vec![7; 8])], &[&vm.user]).await;
+    assert_echo_error(result, EchoError::VendingMachinePaused);
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        10
+    );
+    let data = account_data(&mut context, &vm.buffer).await;
+    assert_eq!(&data[VENDING_MACHINE_BUFF_HEADER_SIZE..], &[0; 128]);
+
+    send(
+        &mut context,
+        &[set_paused_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint,
+            &vm.mint_authority.pubkey(),
+            false,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await
+    .unwrap();
+
+    send(&mut context, &[write(vec![7; 8])], &[&vm.user])
+        .await
+        .unwrap();
+    assert_eq!(token_balance(&mut context, &vm.user_token_account).await, 5);
+    let data = account_data(&mut context, &vm.buffer).await;
+    assert_eq!(
+        &data[VENDING_MACHINE_BUFF_HEADER_SIZE..VENDING_MACHINE_BUFF_HEADER_SIZE + 8],
+        &[7; 8]
+    );
+}
+
+/// A vending machine charging `price_per_byte` on top of `price`, with a user holding `user_tokens`.
+async fn setup_per_byte_vending_machine(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    price: u64,
+    price_per_byte: u64,
+    buffer_size: usize,
+    user_tokens: u64,
+) -> VendingMachine {
+    let mint_authority = Keypair::new();
+    let mint = create_mint(context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let user = Keypair::new();
+    let user_token_account = create_token_account(context, &mint, &user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(
+        context,
+        &mint,
+        &user_token_account,
+        &mint_authority,
+        user_tokens,
+    )
+    .await;
+
+    let (buffer, _) = vending_machine_address(program_id, &mint, price);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeVendingMachineEcho {
+                price,
+                buffer_size,
+                payment_mode: 0,
+                price_per_byte,
+                require_ata: false,
+                max_uses: 0,
+                tiers: vec![],
+                cooldown_slots: 0,
+                free_authority_writes: false,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(mint_authority.pubkey(), true),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+
+    VendingMachine {
+        mint_authority,
+        mint,
+        user,
+        user_token_account,
+        buffer,
+        price,
+    }
+}
+
+#[tokio::test]
+async fn test_per_byte_pricing() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_per_byte_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        2,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 600,
+        2_000,
+    )
+    .await;
+    let write = |data: Vec<u8>| {
+        vending_machine_echo_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            &vm.user_token_account,
+            &vm.mint,
+            data,
+        )
+    };
+
+    // an empty payload only pays the base price
+    send(&mut context, &[write(vec![])], &[&vm.user])
+        .await
+        .unwrap();
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        1_995
+    );
+
+    send(&mut context, &[write(vec![7; 600])], &[&vm.user])
+        .await
+        .unwrap();
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        1_995 - (5 + 2 * 600)
+    );
+    let data = account_data(&mut context, &vm.buffer).await;
+    assert_eq!(&data[VENDING_MACHINE_BUFF_HEADER_SIZE..], &[7; 600]);
+
+    // the remaining 790 tokens don't cover another 600 bytes
+    let result = send(&mut context, &[write(vec![8; 600])], &[&vm.user]).await;
+    assert_echo_error(result, EchoError::InsufficientFunds);
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        790
+    );
+}
+
+#[tokio::test]
+async fn test_per_byte_pricing_overflow() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_per_byte_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        u64::MAX / 2,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+
+    let result = send(
+        &mut context,
+        &[vending_machine_echo_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            &vm.user_token_account,
+            &vm.mint,
+            vec![7; 3],
+        )],
+        &[&vm.user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::Overflow);
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        10
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_vending_machine_rejects_invalid_mints() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let mint_authority = Keypair::new();
+    let uninitialized_mint = create_owned_account(&mut context, &spl_token::id(), Mint::LEN).await;
+    let system_owned_mint =
+        create_owned_account(&mut context, &system_program::id(), Mint::LEN).await;
+
+    for mint in [uninitialized_mint.pubkey(), system_owned_mint.pubkey()] {
+        let payer = context.payer.pubkey();
+        let result = send(
+            &mut context,
+            &[initialize_vending_machine_echo_ix(
+                &program_id,
+                &mint,
+                &mint_authority.pubkey(),
+                &payer,
+                5,
+                VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+            )],
+            &[&mint_authority],
+        )
+        .await;
+        assert_echo_error(result, EchoError::InvalidMint);
+    }
+}
+
+#[tokio::test]
+async fn test_vending_machine_echo_rejects_invalid_mints() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+    let uninitialized_mint = create_owned_account(&mut context, &spl_token::id(), Mint::LEN).await;
+    let system_owned_mint =
+        create_owned_account(&mut context, &system_program::id(), Mint::LEN).await;
+
+    for mint in [uninitialized_mint.pubkey(), system_owned_mint.pubkey()] {
+        let result = send(
+            &mut context,
+            &[vending_machine_echo_ix(
+                &program_id,
+                &vm.buffer,
+                &vm.user.pubkey(),
+                &vm.user_token_account,
+                &mint,
+                vec![7; 8],
+            )],
+            &[&vm.user],
+        )
+        .await;
+        // the stored mint is compared before the passed one is unpacked
+        assert_echo_error(result, EchoError::MintMismatch);
+    }
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        10
+    );
+}
+
+#[tokio::test]
+async fn test_vending_machine_echo_rejects_frozen_token_account() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let mint_authority = Keypair::new();
+    let mint = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                rent.minimum_balance(Mint::LEN),
+                Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &mint_authority.pubkey(),
+                Some(&mint_authority.pubkey()),
+                0,
+            )
+            .unwrap(),
+        ],
+        &[&mint],
+    )
+    .await
+    .unwrap();
+    let user = Keypair::new();
+    let user_token_account = create_token_account(&mut context, &mint.pubkey(), &user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(
+        &mut context,
+        &mint.pubkey(),
+        &user_token_account,
+        &mint_authority,
+        10,
+    )
+    .await;
+    send(
+        &mut context,
+        &[
+            initialize_vending_machine_echo_ix(
+                &program_id,
+                &mint.pubkey(),
+                &mint_authority.pubkey(),
+                &payer,
+                5,
+                VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+            ),
+            spl_token::instruction::freeze_account(
+                &spl_token::id(),
+                &user_token_account,
+                &mint.pubkey(),
+                &mint_authority.pubkey(),
+                &[],
+            )
+            .unwrap(),
+        ],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+
+    let (buffer, _) = vending_machine_address(&program_id, &mint.pubkey(), 5);
+    let result = send(
+        &mut context,
+        &[vending_machine_echo_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            &user_token_account,
+            &mint.pubkey(),
+            vec![7; 8],
+        )],
+        &[&user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::TokenAccountFrozen);
+    assert_eq!(token_balance(&mut context, &user_token_account).await, 10);
+}
+
+#[tokio::test]
+async fn test_vending_machine_echo_rejects_fake_token_account() {
+    let (mut program_test, program_id) = program_test();
+    // the mint is created after startup, so spoof the token account for a pre-chosen mint address
+    let mint = Keypair::new();
+    let user = Keypair::new();
+    let fake_token_account = Pubkey::new_unique();
+    let mut data = vec![0; TokenAccount::LEN];
+    TokenAccount {
+        mint: mint.pubkey(),
+        owner: user.pubkey(),
+        amount: 100,
+        state: AccountState::Initialized,
+        ..TokenAccount::default()
+    }
+    .pack_into_slice(&mut data);
+    program_test.add_account(
+        fake_token_account,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: system_program::id(),
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let mint_authority = Keypair::new();
+    initialize_mint(&mut context, &mint, &mint_authority.pubkey(), 0).await;
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[initialize_vending_machine_echo_ix(
+            &program_id,
+            &mint.pubkey(),
+            &mint_authority.pubkey(),
+            &payer,
+            5,
+            VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+
+    let (buffer, _) = vending_machine_address(&program_id, &mint.pubkey(), 5);
+    let result = send(
+        &mut context,
+        &[vending_machine_echo_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            &fake_token_account,
+            &mint.pubkey(),
+            vec![7; 8],
+        )],
+        &[&user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn test_vending_machine_echo_burns_through_delegate() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        20,
+    )
+    .await;
+    let delegate = Keypair::new();
+    send(
+        &mut context,
+        &[spl_token::instruction::approve(
+            &spl_token::id(),
+            &vm.user_token_account,
+            &delegate.pubkey(),
+            &vm.user.pubkey(),
+            &[],
+            7,
+        )
+        .unwrap()],
+        &[&vm.user],
+    )
+    .await
+    .unwrap();
+    let write = |signer: &Pubkey, data: Vec<u8>| {
+        vending_machine_echo_ix(
+            &program_id,
+            &vm.buffer,
+            signer,
+            &vm.user_token_account,
+            &vm.mint,
+            data,
+        )
+    };
+
+    // the owner still pays from the full balance
+    send(
+        &mut context,
+        &[write(&vm.user.pubkey(), vec![1; 8])],
+        &[&vm.user],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        15
+    );
+
+    send(
+        &mut context,
+        &[write(&delegate.pubkey(), vec![2; 8])],
+        &[&delegate],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        10
+    );
+    let data = account_data(&mut context, &vm.buffer).await;
+    assert_eq!(
+        &data[VENDING_MACHINE_BUFF_HEADER_SIZE..VENDING_MACHINE_BUFF_HEADER_SIZE + 8],
+        &[2; 8]
+    );
+
+    // 2 tokens of allowance left, even though the account holds 10
+    let result = send(
+        &mut context,
+        &[write(&delegate.pubkey(), vec![3; 8])],
+        &[&delegate],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InsufficientFunds);
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        10
+    );
+}
+
+fn create_associated_token_account_ix(
+    payer: &Pubkey,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: spl_associated_token_account::id(),
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(get_associated_token_address(wallet, mint), false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: vec![],
+    }
+}
+
+#[tokio::test]
+async fn test_vending_machine_echo_requires_associated_token_account() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let mint_authority = Keypair::new();
+    let mint = create_mint(&mut context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let other_mint = create_mint(&mut context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let user = Keypair::new();
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[
+            create_associated_token_account_ix(&payer, &user.pubkey(), &mint),
+            create_associated_token_account_ix(&payer, &user.pubkey(), &other_mint),
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+    let associated_token_account = get_associated_token_address(&user.pubkey(), &mint);
+    let other_mint_associated_token_account =
+        get_associated_token_address(&user.pubkey(), &other_mint);
+    let secondary_token_account = create_token_account(&mut context, &mint, &user.pubkey())
+        .await
+        .pubkey();
+    for (account, mint) in [
+        (associated_token_account, mint),
+        (secondary_token_account, mint),
+        (other_mint_associated_token_account, other_mint),
+    ] {
+        mint_tokens(&mut context, &mint, &account, &mint_authority, 10).await;
+    }
+
+    let (buffer, _) = vending_machine_address(&program_id, &mint, 5);
+    send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::InitializeVendingMachineEcho {
+                price: 5,
+                buffer_size: VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+                payment_mode: 0,
+                price_per_byte: 0,
+                require_ata: true,
+                max_uses: 0,
+                tiers: vec![],
+                cooldown_slots: 0,
+                free_authority_writes: false,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(mint_authority.pubkey(), true),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+    let write = |user_token_account: &Pubkey| {
+        vending_machine_echo_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            user_token_account,
+            &mint,
+            vec![7; 8],
+        )
+    };
+
+    for user_token_account in [secondary_token_account, other_mint_associated_token_account] {
+        let result = send(&mut context, &[write(&user_token_account)], &[&user]).await;
+        assert_echo_error(result, EchoError::NotAssociatedTokenAccount);
+        assert_eq!(token_balance(&mut context, &user_token_account).await, 10);
+    }
+
+    send(&mut context, &[write(&associated_token_account)], &[&user])
+        .await
+        .unwrap();
+    assert_eq!(
+        token_balance(&mut context, &associated_token_account).await,
+        5
+    );
+}
+
+fn close_vending_machine_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    admin: &Pubkey,
+    recipient: &Pubkey,
+    mint: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::CloseVendingMachine,
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(*recipient, false),
+            AccountMeta::new_readonly(*mint, false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_close_vending_machine_by_admin() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+
+    let recipient = funded_keypair(&mut context).await.pubkey();
+    let recipient_lamports = context.banks_client.get_balance(recipient).await.unwrap();
+    let buffer_lamports = context.banks_client.get_balance(vm.buffer).await.unwrap();
+
+    send(
+        &mut context,
+        &[close_vending_machine_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint_authority.pubkey(),
+            &recipient,
+            &vm.mint,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        context.banks_client.get_balance(recipient).await.unwrap(),
+        recipient_lamports + buffer_lamports
+    );
+    assert!(context
+        .banks_client
+        .get_account(vm.buffer)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_close_vending_machine_rejects_non_admin() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+
+    let recipient = Keypair::new().pubkey();
+    let result = send(
+        &mut context,
+        &[close_vending_machine_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            &recipient,
+            &vm.mint,
+        )],
+        &[&vm.user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+    assert_eq!(
+        account_data(&mut context, &vm.buffer).await.len(),
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128
+    );
+}
+
+#[tokio::test]
+async fn test_close_vending_machine_rejects_other_mint() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+    let other_mint = create_mint(&mut context, &vm.mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+
+    let result = send(
+        &mut context,
+        &[close_vending_machine_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint_authority.pubkey(),
+            &Keypair::new().pubkey(),
+            &other_mint,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::MintMismatch);
+}
+
+#[tokio::test]
+async fn test_close_vending_machine_requires_empty_treasury() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let mint_authority = Keypair::new();
+    let mint = create_mint(&mut context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let (buffer, _) = vending_machine_address(&program_id, &mint, 3);
+    let (vault_authority, _) =
+        Pubkey::find_program_address(&[b"vending_vault", buffer.as_ref()], &program_id);
+    let vault = create_token_account(&mut context, &mint, &vault_authority)
+        .await
+        .pubkey();
+    let user = Keypair::new();
+    let user_token_account = create_token_account(&mut context, &mint, &user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(
+        &mut context,
+        &mint,
+        &user_token_account,
+        &mint_authority,
+        10,
+    )
+    .await;
+
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[initialize_treasury_vending_machine_ix(
+            &program_id,
+            &mint,
+            &mint_authority.pubkey(),
+            &payer,
+            &vault,
+            3,
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+
+    let mut write = vending_machine_echo_ix(
+        &program_id,
+        &buffer,
+        &user.pubkey(),
+        &user_token_account,
+        &mint,
+        vec![1; 8],
+    );
+    write.accounts.push(AccountMeta::new(vault, false));
+    send(&mut context, &[write], &[&user]).await.unwrap();
+
+    let recipient = Keypair::new().pubkey();
+    let mut close = close_vending_machine_ix(
+        &program_id,
+        &buffer,
+        &mint_authority.pubkey(),
+        &recipient,
+        &mint,
+    );
+    close.accounts.push(AccountMeta::new_readonly(vault, false));
+    let result = send(&mut context, &[close.clone()], &[&mint_authority]).await;
+    assert_echo_error(result, EchoError::VaultNotEmpty);
+
+    // once the proceeds are withdrawn the machine can be closed
+    let destination = create_token_account(&mut context, &mint, &mint_authority.pubkey())
+        .await
+        .pubkey();
+    send(
+        &mut context,
+        &[
+            withdraw_proceeds_ix(
+                &program_id,
+                &buffer,
+                &mint,
+                &vault,
+                &destination,
+                &mint_authority.pubkey(),
+                0,
+            ),
+            close,
+        ],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(token_balance(&mut context, &destination).await, 3);
+    assert!(context
+        .banks_client
+        .get_account(buffer)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_vending_machine_header_stores_mint() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+
+    let data = account_data(&mut context, &vm.buffer).await;
+    let buffer_header =
+        VendingMachineBufferHeader::try_from_slice(&data[..VENDING_MACHINE_BUFF_HEADER_SIZE])
+            .unwrap();
+    assert_eq!(buffer_header.mint, vm.mint);
+    assert_eq!(
+        buffer_header.try_to_vec().unwrap(),
+        data[..VENDING_MACHINE_BUFF_HEADER_SIZE]
+    );
+}
+
+#[tokio::test]
+async fn test_vending_machine_echo_rejects_mismatched_mint() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+
+    // a funded token account of another mint
+    let other_mint_authority = Keypair::new();
+    let other_mint = create_mint(&mut context, &other_mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let other_token_account = create_token_account(&mut context, &other_mint, &vm.user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(
+        &mut context,
+        &other_mint,
+        &other_token_account,
+        &other_mint_authority,
+        10,
+    )
+    .await;
+
+    let result = send(
+        &mut context,
+        &[vending_machine_echo_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            &other_token_account,
+            &other_mint,
+            vec![7; 8],
+        )],
+        &[&vm.user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::MintMismatch);
+    assert_eq!(token_balance(&mut context, &other_token_account).await, 10);
+}
+
+#[tokio::test]
+async fn test_vending_machine_sells_out_after_max_uses() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let mint_authority = Keypair::new();
+    let mint = create_mint(&mut context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let user = Keypair::new();
+    let user_token_account = create_token_account(&mut context, &mint, &user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(
+        &mut context,
+        &mint,
+        &user_token_account,
+        &mint_authority,
+        10,
+    )
+    .await;
+
+    let payer = context.payer.pubkey();
+    let mut initialize = initialize_vending_machine_echo_ix(
+        &program_id,
+        &mint,
+        &mint_authority.pubkey(),
+        &payer,
+        2,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+    );
+    initialize.data = EchoInstruction::InitializeVendingMachineEcho {
+        price: 2,
+        buffer_size: VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        payment_mode: 0,
+        price_per_byte: 0,
+        require_ata: false,
+        max_uses: 3,
+        tiers: vec![],
+        cooldown_slots: 0,
+        free_authority_writes: false,
+    }
+    .try_to_vec()
+    .unwrap();
+    send(&mut context, &[initialize], &[&mint_authority])
+        .await
+        .unwrap();
+    let (buffer, _) = vending_machine_address(&program_id, &mint, 2);
+
+    for i in 1..=3u8 {
+        send(
+            &mut context,
+            &[vending_machine_echo_ix(
+                &program_id,
+                &buffer,
+                &user.pubkey(),
+                &user_token_account,
+                &mint,
+                vec![i; 8],
+            )],
+            &[&user],
+        )
+        .await
+        .unwrap();
+        let data = account_data(&mut context, &buffer).await;
+        let buffer_header =
+            VendingMachineBufferHeader::try_from_slice(&data[..VENDING_MACHINE_BUFF_HEADER_SIZE])
+                .unwrap();
+        assert_eq!(buffer_header.uses, i as u64);
+    }
+    assert_eq!(token_balance(&mut context, &user_token_account).await, 4);
+
+    let result = send(
+        &mut context,
+        &[vending_machine_echo_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            &user_token_account,
+            &mint,
+            vec![4; 8],
+        )],
+        &[&user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::VendingMachineSoldOut);
+    assert_eq!(token_balance(&mut context, &user_token_account).await, 4);
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(
+        &data[VENDING_MACHINE_BUFF_HEADER_SIZE..VENDING_MACHINE_BUFF_HEADER_SIZE + 8],
+        &[3; 8]
+    );
+}
+
+fn initialize_tiered_vending_machine_ix(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    mint_authority: &Pubkey,
+    payer: &Pubkey,
+    tiers: Vec<PriceTier>,
+) -> Instruction {
+    let mut instruction = initialize_vending_machine_echo_ix(
+        program_id,
+        mint,
+        mint_authority,
+        payer,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 256,
+    );
+    instruction.data = EchoInstruction::InitializeVendingMachineEcho {
+        price: 5,
+        buffer_size: VENDING_MACHINE_BUFF_HEADER_SIZE + 256,
+        payment_mode: 0,
+        price_per_byte: 0,
+        require_ata: false,
+        max_uses: 0,
+        tiers,
+        cooldown_slots: 0,
+        free_authority_writes: false,
+    }
+    .try_to_vec()
+    .unwrap();
+    instruction
+}
+
+#[tokio::test]
+async fn test_tiered_pricing_boundaries() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let mint_authority = Keypair::new();
+    let mint = create_mint(&mut context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let user = Keypair::new();
+    let user_token_account = create_token_account(&mut context, &mint, &user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(
+        &mut context,
+        &mint,
+        &user_token_account,
+        &mint_authority,
+        100,
+    )
+    .await;
+
+    let payer = context.payer.pubkey();
+    send(
+        &mut context,
+        &[initialize_tiered_vending_machine_ix(
+            &program_id,
+            &mint,
+            &mint_authority.pubkey(),
+            &payer,
+            vec![
+                PriceTier {
+                    max_len: 8,
+                    price: 1,
+                },
+                PriceTier {
+                    max_len: 16,
+                    price: 3,
+                },
+            ],
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+    let (buffer, _) = vending_machine_address(&program_id, &mint, 5);
+
+    let mut balance = 100;
+    for (len, tier_price) in [(0, 1), (8, 1), (9, 3), (16, 3)] {
+        send(
+            &mut context,
+            &[vending_machine_echo_ix(
+                &program_id,
+                &buffer,
+                &user.pubkey(),
+                &user_token_account,
+                &mint,
+                vec![1; len],
+            )],
+            &[&user],
+        )
+        .await
+        .unwrap();
+        balance -= tier_price;
+        assert_eq!(
+            token_balance(&mut context, &user_token_account).await,
+            balance,
+            "{} bytes",
+            len
+        );
+    }
+
+    // longer than the last tier
+    let result = send(
+        &mut context,
+        &[vending_machine_echo_ix(
+            &program_id,
+            &buffer,
+            &user.pubkey(),
+            &user_token_account,
+            &mint,
+            vec![1; 17],
+        )],
+        &[&user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::DataTooLarge);
+    assert_eq!(
+        token_balance(&mut context, &user_token_account).await,
+        balance
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_vending_machine_rejects_unsorted_tiers() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let mint_authority = Keypair::new();
+    let mint = create_mint(&mut context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let payer = context.payer.pubkey();
+
+    let tier = |max_len, price| PriceTier { max_len, price };
+    for tiers in [
+        vec![tier(16, 1), tier(8, 3)],
+        vec![tier(8, 1), tier(8, 3)],
+        vec![tier(1, 1), tier(2, 1), tier(3, 1), tier(4, 1), tier(5, 1)],
+    ] {
+        let result = send(
+            &mut context,
+            &[initialize_tiered_vending_machine_ix(
+                &program_id,
+                &mint,
+                &mint_authority.pubkey(),
+                &payer,
+                tiers,
+            )],
+            &[&mint_authority],
+        )
+        .await;
+        assert_echo_error(result, EchoError::InvalidInstructionInput);
+    }
+}
+
+async fn vending_machine_header(
+    context: &mut ProgramTestContext,
+    buffer: &Pubkey,
+) -> VendingMachineBufferHeader {
+    let data = account_data(context, buffer).await;
+    VendingMachineBufferHeader::try_from_slice(&data[..VENDING_MACHINE_BUFF_HEADER_SIZE]).unwrap()
+}
+
+#[tokio::test]
+async fn test_vending_machine_records_last_writer() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+
+    let buffer_header = vending_machine_header(&mut context, &vm.buffer).await;
+    assert_eq!(buffer_header.last_writer, Pubkey::default());
+    assert_eq!(buffer_header.last_write_slot, 0);
+
+    // a second user holding tokens of the same mint
+    let other_user = Keypair::new();
+    let other_token_account = create_token_account(&mut context, &vm.mint, &other_user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(
+        &mut context,
+        &vm.mint,
+        &other_token_account,
+        &vm.mint_authority,
+        10,
+    )
+    .await;
+
+    for (slot, user, user_token_account) in [
+        (10, &vm.user, vm.user_token_account),
+        (20, &other_user, other_token_account),
+    ] {
+        context.warp_to_slot(slot).unwrap();
+        send(
+            &mut context,
+            &[vending_machine_echo_ix(
+                &program_id,
+                &vm.buffer,
+                &user.pubkey(),
+                &user_token_account,
+                &vm.mint,
+                vec![7; 8],
+            )],
+            &[user],
+        )
+        .await
+        .unwrap();
+
+        let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+        let buffer_header = vending_machine_header(&mut context, &vm.buffer).await;
+        assert_eq!(buffer_header.last_writer, user.pubkey());
+        assert_eq!(buffer_header.last_write_slot, clock.slot);
+    }
+}
+
+#[tokio::test]
+async fn test_vending_machine_burn_checked_decimals() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+
+    for decimals in [0, 9] {
+        let one_token = 10u64.pow(decimals as u32);
+        let price = 2 * one_token;
+        let mint_authority = Keypair::new();
+        let mint = create_mint(&mut context, &mint_authority.pubkey(), decimals)
+            .await
+            .pubkey();
+        let user = Keypair::new();
+        let user_token_account = create_token_account(&mut context, &mint, &user.pubkey())
+            .await
+            .pubkey();
+        mint_tokens(
+            &mut context,
+            &mint,
+            &user_token_account,
+            &mint_authority,
+            5 * one_token,
+        )
+        .await;
+        let payer = context.payer.pubkey();
+        send(
+            &mut context,
+            &[initialize_vending_machine_echo_ix(
+                &program_id,
+                &mint,
+                &mint_authority.pubkey(),
+                &payer,
+                price,
+                VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+            )],
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+        let (buffer, _) = vending_machine_address(&program_id, &mint, price);
+
+        send(
+            &mut context,
+            &[vending_machine_echo_ix(
+                &program_id,
+                &buffer,
+                &user.pubkey(),
+                &user_token_account,
+                &mint,
+                vec![7; 8],
+            )],
+            &[&user],
+        )
+        .await
+        .unwrap();
+
+        // two whole tokens are burned whatever the decimals
+        let balance = token_balance(&mut context, &user_token_account).await;
+        assert_eq!(spl_token::amount_to_ui_amount(balance, decimals), 3.0);
+        let supply = Mint::unpack(&account_data(&mut context, &mint).await)
+            .unwrap()
+            .supply;
+        assert_eq!(spl_token::amount_to_ui_amount(supply, decimals), 3.0);
+    }
+}
+
+fn transfer_admin_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    admin: &Pubkey,
+    new_admin: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::TransferVendingMachineAdmin {},
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(*new_admin, true),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_vending_machine_admin_is_initializer() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+
+    let buffer_header = vending_machine_header(&mut context, &vm.buffer).await;
+    assert_eq!(buffer_header.admin, vm.mint_authority.pubkey());
+}
+
+#[tokio::test]
+async fn test_transfer_vending_machine_admin() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        10,
+    )
+    .await;
+    let new_admin = Keypair::new();
+
+    // only the admin can rotate it
+    let result = send(
+        &mut context,
+        &[transfer_admin_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            &new_admin.pubkey(),
+        )],
+        &[&vm.user, &new_admin],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    send(
+        &mut context,
+        &[transfer_admin_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint_authority.pubkey(),
+            &new_admin.pubkey(),
+        )],
+        &[&vm.mint_authority, &new_admin],
+    )
+    .await
+    .unwrap();
+    let buffer_header = vending_machine_header(&mut context, &vm.buffer).await;
+    assert_eq!(buffer_header.admin, new_admin.pubkey());
+
+    // the previous admin lost its rights, the new one manages the machine
+    let result = send(
+        &mut context,
+        &[set_paused_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint,
+            &vm.mint_authority.pubkey(),
+            true,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+    send(
+        &mut context,
+        &[set_paused_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint,
+            &new_admin.pubkey(),
+            true,
+        )],
+        &[&new_admin],
+    )
+    .await
+    .unwrap();
+    assert!(
+        vending_machine_header(&mut context, &vm.buffer)
+            .await
+            .paused
+    );
+}
+
+#[tokio::test]
+async fn test_vending_machine_totals() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 128,
+        20,
+    )
+    .await;
+
+    for byte in 1..=3 {
+        send(
+            &mut context,
+            &[vending_machine_echo_ix(
+                &program_id,
+                &vm.buffer,
+                &vm.user.pubkey(),
+                &vm.user_token_account,
+                &vm.mint,
+                vec![byte; 8],
+            )],
+            &[&vm.user],
+        )
+        .await
+        .unwrap();
+    }
+
+    let stats = vending_machine_header(&mut context, &vm.buffer)
+        .await
+        .stats();
+    assert_eq!(
+        stats,
+        VendingMachineStats {
+            total_writes: 3,
+            total_burned: 15,
+        }
+    );
+}
+
+fn vending_machine_echo_write_at_ix(
+    program_id: &Pubkey,
+    vm: &VendingMachine,
+    offset: u64,
+    data: Vec<u8>,
+) -> Instruction {
+    let mut instruction = vending_machine_echo_ix(
+        program_id,
+        &vm.buffer,
+        &vm.user.pubkey(),
+        &vm.user_token_account,
+        &vm.mint,
+        vec![],
+    );
+    instruction.data = EchoInstruction::VendingMachineEchoWriteAt { offset, data }
+        .try_to_vec()
+        .unwrap();
+    instruction
+}
+
+#[tokio::test]
+async fn test_vending_machine_write_at_keeps_untouched_bytes() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 32,
+        20,
+    )
+    .await;
+    send(
+        &mut context,
+        &[vending_machine_echo_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            &vm.user_token_account,
+            &vm.mint,
+            vec![1; 32],
+        )],
+        &[&vm.user],
+    )
+    .await
+    .unwrap();
+
+    send(
+        &mut context,
+        &[vending_machine_echo_write_at_ix(
+            &program_id,
+            &vm,
+            10,
+            vec![2; 4],
+        )],
+        &[&vm.user],
+    )
+    .await
+    .unwrap();
+
+    // burned once for the write at the offset
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        10
+    );
+    let data = account_data(&mut context, &vm.buffer).await;
+    let payload = &data[VENDING_MACHINE_BUFF_HEADER_SIZE..];
+    assert_eq!(&payload[..10], &[1; 10]);
+    assert_eq!(&payload[10..14], &[2; 4]);
+    assert_eq!(&payload[14..], &[1; 18]);
+}
+
+#[tokio::test]
+async fn test_vending_machine_write_at_out_of_range() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 32,
+        20,
+    )
+    .await;
+
+    let result = send(
+        &mut context,
+        &[vending_machine_echo_write_at_ix(
+            &program_id,
+            &vm,
+            33,
+            vec![2; 1],
+        )],
+        &[&vm.user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+
+    let result = send(
+        &mut context,
+        &[vending_machine_echo_write_at_ix(
+            &program_id,
+            &vm,
+            30,
+            vec![2; 4],
+        )],
+        &[&vm.user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::DataTooLarge);
+
+    // nothing was burned nor written
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        20
+    );
+    let data = account_data(&mut context, &vm.buffer).await;
+    assert_eq!(&data[VENDING_MACHINE_BUFF_HEADER_SIZE..], &[0; 32]);
+
+    // a write ending at the end of the payload fits
+    send(
+        &mut context,
+        &[vending_machine_echo_write_at_ix(
+            &program_id,
+            &vm,
+            28,
+            vec![2; 4],
+        )],
+        &[&vm.user],
+    )
+    .await
+    .unwrap();
+    let data = account_data(&mut context, &vm.buffer).await;
+    assert_eq!(&data[VENDING_MACHINE_BUFF_HEADER_SIZE + 28..], &[2; 4]);
+}
+
+#[tokio::test]
+async fn test_vending_machine_shrinking_write_updates_data_len() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 32,
+        20,
+    )
+    .await;
+
+    for data in [vec![1; 16], vec![2; 4]] {
+        send(
+            &mut context,
+            &[vending_machine_echo_ix(
+                &program_id,
+                &vm.buffer,
+                &vm.user.pubkey(),
+                &vm.user_token_account,
+                &vm.mint,
+                data,
+            )],
+            &[&vm.user],
+        )
+        .await
+        .unwrap();
+    }
+
+    let data = account_data(&mut context, &vm.buffer).await;
+    let buffer = VendingMachineBuffer::unpack(&data).unwrap();
+    assert_eq!(buffer.header.data_len, 4);
+    assert_eq!(buffer.payload(), &[2; 4]);
+    // the bytes of the longer write are zeroed
+    assert_eq!(&data[VENDING_MACHINE_BUFF_HEADER_SIZE + 4..], &[0; 28]);
+
+    // an offset write past the data extends it
+    send(
+        &mut context,
+        &[vending_machine_echo_write_at_ix(
+            &program_id,
+            &vm,
+            8,
+            vec![3; 2],
+        )],
+        &[&vm.user],
+    )
+    .await
+    .unwrap();
+    let data = account_data(&mut context, &vm.buffer).await;
+    let buffer = VendingMachineBuffer::unpack(&data).unwrap();
+    assert_eq!(buffer.payload(), &[2, 2, 2, 2, 0, 0, 0, 0, 3, 3]);
+}
+
+fn resize_vending_machine_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    admin: &Pubkey,
+    payer: &Pubkey,
+    new_size: u64,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::ResizeVendingMachine { new_size },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_resize_vending_machine_grows_and_shrinks() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 32,
+        10,
+    )
+    .await;
+    send(
+        &mut context,
+        &[vending_machine_echo_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            &vm.user_token_account,
+            &vm.mint,
+            vec![7; 8],
+        )],
+        &[&vm.user],
+    )
+    .await
+    .unwrap();
+
+    let payer = context.payer.pubkey();
+    let new_size = VENDING_MACHINE_BUFF_HEADER_SIZE + 64;
+    send(
+        &mut context,
+        &[resize_vending_machine_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint_authority.pubkey(),
+            &payer,
+            new_size as u64,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await
+    .unwrap();
+
+    let account = context
+        .banks_client
+        .get_account(vm.buffer)
+        .await
+        .unwrap()
+        .unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(account.data.len(), new_size);
+    assert!(account.lamports >= rent.minimum_balance(new_size));
+    let buffer = VendingMachineBuffer::unpack(&account.data).unwrap();
+    assert_eq!(buffer.payload(), &[7; 8]);
+    assert_eq!(
+        &account.data[VENDING_MACHINE_BUFF_HEADER_SIZE + 8..],
+        &[0; 56]
+    );
+
+    // shrinking down to the data written so far keeps it
+    send(
+        &mut context,
+        &[resize_vending_machine_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint_authority.pubkey(),
+            &payer,
+            VENDING_MACHINE_BUFF_HEADER_SIZE as u64 + 8,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await
+    .unwrap();
+    let data = account_data(&mut context, &vm.buffer).await;
+    assert_eq!(&data[VENDING_MACHINE_BUFF_HEADER_SIZE..], &[7; 8]);
+
+    let result = send(
+        &mut context,
+        &[resize_vending_machine_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint_authority.pubkey(),
+            &payer,
+            VENDING_MACHINE_BUFF_HEADER_SIZE as u64 + 4,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+}
+
+#[tokio::test]
+async fn test_resize_vending_machine_rejects_over_limit_growth() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup_vending_machine(
+        &mut context,
+        &program_id,
+        5,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 32,
+        10,
+    )
+    .await;
+    let payer = context.payer.pubkey();
+    let size = (VENDING_MACHINE_BUFF_HEADER_SIZE + 32) as u64;
+
+    let result = send(
+        &mut context,
+        &[resize_vending_machine_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint_authority.pubkey(),
+            &payer,
+            size + 10 * 1024 + 1,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::DataTooLarge);
+
+    // only the admin can resize
+    let result = send(
+        &mut context,
+        &[resize_vending_machine_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            &payer,
+            size + 10 * 1024,
+        )],
+        &[&vm.user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    send(
+        &mut context,
+        &[resize_vending_machine_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.mint_authority.pubkey(),
+            &payer,
+            size + 10 * 1024,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await
+    .unwrap();
+    let data = account_data(&mut context, &vm.buffer).await;
+    assert_eq!(data.len() as u64, size + 10 * 1024);
+}
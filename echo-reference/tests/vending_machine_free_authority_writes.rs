@@ -0,0 +1,311 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{VendingMachineBufferHeader, VENDING_MACHINE_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        signature::{Keypair, Signer},
+        transport::TransportError,
+    },
+};
+
+const PRICE: u64 = 5;
+
+struct FreeAuthority {
+    vm: VendingMachine,
+    authority_token_account: Pubkey,
+}
+
+/// Creates a vending machine whose mint authority holds no tokens, next to a user holding 20 of them.
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    free_authority_writes: bool,
+    max_uses: u64,
+) -> FreeAuthority {
+    let mint_authority = Keypair::new();
+    let mint = create_mint(context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let authority_token_account = create_token_account(context, &mint, &mint_authority.pubkey())
+        .await
+        .pubkey();
+    let user = Keypair::new();
+    let user_token_account = create_token_account(context, &mint, &user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(context, &mint, &user_token_account, &mint_authority, 20).await;
+
+    let (buffer, _) = vending_machine_address(program_id, &mint, PRICE);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeVendingMachineEcho {
+                price: PRICE,
+                buffer_size: VENDING_MACHINE_BUFF_HEADER_SIZE + 16,
+                payment_mode: 0,
+                price_per_byte: 0,
+                require_ata: false,
+                max_uses,
+                tiers: vec![],
+                cooldown_slots: 0,
+                free_authority_writes,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(mint_authority.pubkey(), true),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+
+    FreeAuthority {
+        vm: VendingMachine {
+            mint_authority,
+            mint,
+            user,
+            user_token_account,
+            buffer,
+            price: PRICE,
+        },
+        authority_token_account,
+    }
+}
+
+async fn write(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    vm: &VendingMachine,
+    writer: &Keypair,
+    writer_token_account: &Pubkey,
+    data: Vec<u8>,
+) -> Result<(), TransportError> {
+    send(
+        context,
+        &[vending_machine_echo_ix(
+            program_id,
+            &vm.buffer,
+            &writer.pubkey(),
+            writer_token_account,
+            &vm.mint,
+            data,
+        )],
+        &[writer],
+    )
+    .await
+}
+
+async fn buffer_header(
+    context: &mut ProgramTestContext,
+    buffer: &Pubkey,
+) -> VendingMachineBufferHeader {
+    let data = account_data(context, buffer).await;
+    VendingMachineBufferHeader::try_from_slice(&data[..VENDING_MACHINE_BUFF_HEADER_SIZE]).unwrap()
+}
+
+#[tokio::test]
+async fn test_mint_authority_writes_for_free() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let free_authority = setup(&mut context, &program_id, true, 0).await;
+    let vm = &free_authority.vm;
+    assert!(
+        buffer_header(&mut context, &vm.buffer)
+            .await
+            .free_authority_writes
+    );
+
+    // nothing is burned from the empty token account of the authority
+    write(
+        &mut context,
+        &program_id,
+        vm,
+        &vm.mint_authority,
+        &free_authority.authority_token_account,
+        vec![1; 4],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        token_balance(&mut context, &free_authority.authority_token_account).await,
+        0
+    );
+
+    let data = account_data(&mut context, &vm.buffer).await;
+    assert_eq!(
+        &data[VENDING_MACHINE_BUFF_HEADER_SIZE..],
+        &[1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+    let buffer_header = buffer_header(&mut context, &vm.buffer).await;
+    assert_eq!(buffer_header.last_writer, vm.mint_authority.pubkey());
+    assert_eq!(buffer_header.uses, 0);
+    assert_eq!(buffer_header.total_writes, 0);
+    assert_eq!(buffer_header.total_burned, 0);
+}
+
+#[tokio::test]
+async fn test_free_writes_do_not_count_as_uses() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let free_authority = setup(&mut context, &program_id, true, 1).await;
+    let vm = &free_authority.vm;
+
+    write(
+        &mut context,
+        &program_id,
+        vm,
+        &vm.mint_authority,
+        &free_authority.authority_token_account,
+        vec![1; 4],
+    )
+    .await
+    .unwrap();
+    // the only use is still left for a sale
+    write(
+        &mut context,
+        &program_id,
+        vm,
+        &vm.user,
+        &vm.user_token_account,
+        vec![2; 4],
+    )
+    .await
+    .unwrap();
+    let header = buffer_header(&mut context, &vm.buffer).await;
+    assert_eq!(header.uses, 1);
+    assert_eq!(header.total_writes, 1);
+
+    let result = write(
+        &mut context,
+        &program_id,
+        vm,
+        &vm.user,
+        &vm.user_token_account,
+        vec![3; 4],
+    )
+    .await;
+    assert_echo_error(result, EchoError::VendingMachineSoldOut);
+
+    // the mint authority still updates the sold out machine
+    write(
+        &mut context,
+        &program_id,
+        vm,
+        &vm.mint_authority,
+        &free_authority.authority_token_account,
+        vec![4; 4],
+    )
+    .await
+    .unwrap();
+    let data = account_data(&mut context, &vm.buffer).await;
+    assert_eq!(
+        &data[VENDING_MACHINE_BUFF_HEADER_SIZE..VENDING_MACHINE_BUFF_HEADER_SIZE + 4],
+        &[4; 4]
+    );
+    assert_eq!(buffer_header(&mut context, &vm.buffer).await.uses, 1);
+}
+
+#[tokio::test]
+async fn test_regular_user_still_pays() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let free_authority = setup(&mut context, &program_id, true, 0).await;
+    let vm = &free_authority.vm;
+
+    write(
+        &mut context,
+        &program_id,
+        vm,
+        &vm.user,
+        &vm.user_token_account,
+        vec![2; 4],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        20 - PRICE
+    );
+    assert_eq!(
+        buffer_header(&mut context, &vm.buffer).await.total_burned,
+        PRICE
+    );
+
+    // paying from the token account of the authority doesn't make the user the authority
+    let result = write(
+        &mut context,
+        &program_id,
+        vm,
+        &vm.user,
+        &free_authority.authority_token_account,
+        vec![3; 4],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidAccountData);
+}
+
+#[tokio::test]
+async fn test_opted_out_machine_charges_the_mint_authority() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let free_authority = setup(&mut context, &program_id, false, 0).await;
+    let vm = &free_authority.vm;
+
+    let result = write(
+        &mut context,
+        &program_id,
+        vm,
+        &vm.mint_authority,
+        &free_authority.authority_token_account,
+        vec![1; 4],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InsufficientFunds);
+
+    mint_tokens(
+        &mut context,
+        &vm.mint,
+        &free_authority.authority_token_account,
+        &vm.mint_authority,
+        10,
+    )
+    .await;
+    write(
+        &mut context,
+        &program_id,
+        vm,
+        &vm.mint_authority,
+        &free_authority.authority_token_account,
+        vec![1; 4],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        token_balance(&mut context, &free_authority.authority_token_account).await,
+        10 - PRICE
+    );
+    assert_eq!(
+        buffer_header(&mut context, &vm.buffer).await.total_burned,
+        PRICE
+    );
+}
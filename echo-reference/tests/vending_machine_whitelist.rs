@@ -0,0 +1,297 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{
+        error::EchoError, instruction::EchoInstruction, state::VENDING_MACHINE_BUFF_HEADER_SIZE,
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_instruction, system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{signature::Signer, transport::TransportError},
+};
+
+const PRICE: u64 = 5;
+
+fn whitelist_address(program_id: &Pubkey, buffer: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vending_whitelist", buffer.as_ref()], program_id).0
+}
+
+/// A vending machine whose admin can pay for the whitelist.
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> VendingMachine {
+    let vm = setup_vending_machine(
+        context,
+        program_id,
+        PRICE,
+        VENDING_MACHINE_BUFF_HEADER_SIZE + 8,
+        20,
+    )
+    .await;
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[system_instruction::transfer(
+            &payer,
+            &vm.mint_authority.pubkey(),
+            1_000_000_000,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    vm
+}
+
+async fn send_admin(
+    context: &mut ProgramTestContext,
+    vm: &VendingMachine,
+    instruction: Instruction,
+) -> Result<(), TransportError> {
+    send(context, &[instruction], &[&vm.mint_authority]).await
+}
+
+fn add_to_whitelist_ix(program_id: &Pubkey, vm: &VendingMachine, member: Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::AddToWhitelist { member },
+        vec![
+            AccountMeta::new_readonly(vm.buffer, false),
+            AccountMeta::new(whitelist_address(program_id, &vm.buffer), false),
+            AccountMeta::new(vm.mint_authority.pubkey(), true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+fn remove_from_whitelist_ix(
+    program_id: &Pubkey,
+    vm: &VendingMachine,
+    member: Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::RemoveFromWhitelist { member },
+        vec![
+            AccountMeta::new_readonly(vm.buffer, false),
+            AccountMeta::new(whitelist_address(program_id, &vm.buffer), false),
+            AccountMeta::new_readonly(vm.mint_authority.pubkey(), true),
+        ],
+    )
+}
+
+fn set_whitelist_enabled_ix(
+    program_id: &Pubkey,
+    vm: &VendingMachine,
+    enabled: bool,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::SetWhitelistEnabled { enabled },
+        vec![
+            AccountMeta::new(vm.buffer, false),
+            AccountMeta::new_readonly(vm.mint_authority.pubkey(), true),
+        ],
+    )
+}
+
+async fn write(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    vm: &VendingMachine,
+    data: Vec<u8>,
+) -> Result<(), TransportError> {
+    let mut instruction = vending_machine_echo_ix(
+        program_id,
+        &vm.buffer,
+        &vm.user.pubkey(),
+        &vm.user_token_account,
+        &vm.mint,
+        data,
+    );
+    instruction.accounts.push(AccountMeta::new_readonly(
+        whitelist_address(program_id, &vm.buffer),
+        false,
+    ));
+    send(context, &[instruction], &[&vm.user]).await
+}
+
+#[tokio::test]
+async fn test_whitelisted_user_writes() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup(&mut context, &program_id).await;
+
+    send_admin(
+        &mut context,
+        &vm,
+        set_whitelist_enabled_ix(&program_id, &vm, true),
+    )
+    .await
+    .unwrap();
+
+    // nothing is burned before the user is whitelisted
+    let result = write(&mut context, &program_id, &vm, vec![1; 4]).await;
+    assert_echo_error(result, EchoError::NotWhitelisted);
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        20
+    );
+
+    send_admin(
+        &mut context,
+        &vm,
+        add_to_whitelist_ix(&program_id, &vm, Pubkey::new_unique()),
+    )
+    .await
+    .unwrap();
+    send_admin(
+        &mut context,
+        &vm,
+        add_to_whitelist_ix(&program_id, &vm, vm.user.pubkey()),
+    )
+    .await
+    .unwrap();
+
+    write(&mut context, &program_id, &vm, vec![2; 4])
+        .await
+        .unwrap();
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        20 - PRICE
+    );
+
+    // members can't be added twice
+    let result = send_admin(
+        &mut context,
+        &vm,
+        add_to_whitelist_ix(&program_id, &vm, vm.user.pubkey()),
+    )
+    .await;
+    assert_echo_error(result, EchoError::DuplicateMember);
+}
+
+#[tokio::test]
+async fn test_removed_user_fails() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup(&mut context, &program_id).await;
+
+    send_admin(
+        &mut context,
+        &vm,
+        add_to_whitelist_ix(&program_id, &vm, vm.user.pubkey()),
+    )
+    .await
+    .unwrap();
+    send_admin(
+        &mut context,
+        &vm,
+        set_whitelist_enabled_ix(&program_id, &vm, true),
+    )
+    .await
+    .unwrap();
+    write(&mut context, &program_id, &vm, vec![1; 4])
+        .await
+        .unwrap();
+
+    // only the admin manages the whitelist
+    let result = send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::RemoveFromWhitelist {
+                member: vm.user.pubkey(),
+            },
+            vec![
+                AccountMeta::new_readonly(vm.buffer, false),
+                AccountMeta::new(whitelist_address(&program_id, &vm.buffer), false),
+                AccountMeta::new_readonly(vm.user.pubkey(), true),
+            ],
+        )],
+        &[&vm.user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    send_admin(
+        &mut context,
+        &vm,
+        remove_from_whitelist_ix(&program_id, &vm, vm.user.pubkey()),
+    )
+    .await
+    .unwrap();
+    let result = write(&mut context, &program_id, &vm, vec![2; 4]).await;
+    assert_echo_error(result, EchoError::NotWhitelisted);
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        20 - PRICE
+    );
+
+    let result = send_admin(
+        &mut context,
+        &vm,
+        remove_from_whitelist_ix(&program_id, &vm, vm.user.pubkey()),
+    )
+    .await;
+    assert_echo_error(result, EchoError::MemberNotFound);
+}
+
+#[tokio::test]
+async fn test_disabled_whitelist_is_ignored() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup(&mut context, &program_id).await;
+
+    // the user isn't a member, the list is only checked once enabled
+    send_admin(
+        &mut context,
+        &vm,
+        add_to_whitelist_ix(&program_id, &vm, Pubkey::new_unique()),
+    )
+    .await
+    .unwrap();
+    send(
+        &mut context,
+        &[vending_machine_echo_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            &vm.user_token_account,
+            &vm.mint,
+            vec![1; 4],
+        )],
+        &[&vm.user],
+    )
+    .await
+    .unwrap();
+
+    send_admin(
+        &mut context,
+        &vm,
+        set_whitelist_enabled_ix(&program_id, &vm, true),
+    )
+    .await
+    .unwrap();
+    let result = write(&mut context, &program_id, &vm, vec![2; 4]).await;
+    assert_echo_error(result, EchoError::NotWhitelisted);
+
+    send_admin(
+        &mut context,
+        &vm,
+        set_whitelist_enabled_ix(&program_id, &vm, false),
+    )
+    .await
+    .unwrap();
+    write(&mut context, &program_id, &vm, vec![3; 4])
+        .await
+        .unwrap();
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        20 - 2 * PRICE
+    );
+}
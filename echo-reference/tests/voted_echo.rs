@@ -0,0 +1,250 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{VotedEchoHeader, VOTED_ECHO_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+const BUFFER_SIZE: usize = 8;
+
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    quorum: u8,
+) -> (Vec<Keypair>, Pubkey) {
+    let voters: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+    let payer = context.payer.pubkey();
+    let (buffer, _) = Pubkey::find_program_address(&[b"voted", payer.as_ref()], program_id);
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeVotedEcho {
+                buffer_size: BUFFER_SIZE,
+                voters: voters.iter().map(|voter| voter.pubkey()).collect(),
+                quorum,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    (voters, buffer)
+}
+
+fn voted_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    voter: &Pubkey,
+    instruction: EchoInstruction,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &instruction,
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*voter, true),
+        ],
+    )
+}
+
+async fn payload(context: &mut ProgramTestContext, buffer: &Pubkey) -> Vec<u8> {
+    account_data(context, buffer).await[VOTED_ECHO_HEADER_SIZE..][..BUFFER_SIZE].to_vec()
+}
+
+#[tokio::test]
+async fn test_write_applied_when_quorum_exactly_met() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (voters, buffer) = setup(&mut context, &program_id, 2).await;
+
+    send(
+        &mut context,
+        &[
+            voted_ix(
+                &program_id,
+                &buffer,
+                &voters[2].pubkey(),
+                EchoInstruction::ProposeVotedWrite {
+                    data: vec![1, 2, 3],
+                },
+            ),
+            voted_ix(
+                &program_id,
+                &buffer,
+                &voters[0].pubkey(),
+                EchoInstruction::CastVote,
+            ),
+        ],
+        &[&voters[2], &voters[0]],
+    )
+    .await
+    .unwrap();
+
+    // one vote out of two, the payload is untouched
+    assert_eq!(payload(&mut context, &buffer).await, vec![0; BUFFER_SIZE]);
+
+    send(
+        &mut context,
+        &[voted_ix(
+            &program_id,
+            &buffer,
+            &voters[1].pubkey(),
+            EchoInstruction::CastVote,
+        )],
+        &[&voters[1]],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        payload(&mut context, &buffer).await,
+        vec![1, 2, 3, 0, 0, 0, 0, 0]
+    );
+    let data = account_data(&mut context, &buffer).await;
+    let header = VotedEchoHeader::try_from_slice(&data[..VOTED_ECHO_HEADER_SIZE]).unwrap();
+    assert!(!header.has_pending_write());
+    assert_eq!(header.vote_bitmap, 0);
+    assert_eq!(
+        &data[VOTED_ECHO_HEADER_SIZE + BUFFER_SIZE..],
+        &[0; BUFFER_SIZE]
+    );
+}
+
+#[tokio::test]
+async fn test_write_not_applied_without_quorum() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (voters, buffer) = setup(&mut context, &program_id, 3).await;
+
+    send(
+        &mut context,
+        &[
+            voted_ix(
+                &program_id,
+                &buffer,
+                &voters[0].pubkey(),
+                EchoInstruction::ProposeVotedWrite { data: vec![9; 4] },
+            ),
+            voted_ix(
+                &program_id,
+                &buffer,
+                &voters[0].pubkey(),
+                EchoInstruction::CastVote,
+            ),
+            voted_ix(
+                &program_id,
+                &buffer,
+                &voters[1].pubkey(),
+                EchoInstruction::CastVote,
+            ),
+        ],
+        &[&voters[0], &voters[1]],
+    )
+    .await
+    .unwrap();
+    assert_eq!(payload(&mut context, &buffer).await, vec![0; BUFFER_SIZE]);
+
+    // an outsider can neither vote nor abort
+    let outsider = Keypair::new();
+    let result = send(
+        &mut context,
+        &[voted_ix(
+            &program_id,
+            &buffer,
+            &outsider.pubkey(),
+            EchoInstruction::CastVote,
+        )],
+        &[&outsider],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    send(
+        &mut context,
+        &[voted_ix(
+            &program_id,
+            &buffer,
+            &voters[2].pubkey(),
+            EchoInstruction::AbortVotedWrite,
+        )],
+        &[&voters[2]],
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut context,
+        &[voted_ix(
+            &program_id,
+            &buffer,
+            &voters[2].pubkey(),
+            EchoInstruction::CastVote,
+        )],
+        &[&voters[2]],
+    )
+    .await;
+    assert_echo_error(result, EchoError::NoPendingWrite);
+    assert_eq!(payload(&mut context, &buffer).await, vec![0; BUFFER_SIZE]);
+}
+
+#[tokio::test]
+async fn test_double_vote_rejected() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let (voters, buffer) = setup(&mut context, &program_id, 2).await;
+
+    send(
+        &mut context,
+        &[
+            voted_ix(
+                &program_id,
+                &buffer,
+                &voters[0].pubkey(),
+                EchoInstruction::ProposeVotedWrite { data: vec![5; 8] },
+            ),
+            voted_ix(
+                &program_id,
+                &buffer,
+                &voters[0].pubkey(),
+                EchoInstruction::CastVote,
+            ),
+        ],
+        &[&voters[0]],
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut context,
+        &[voted_ix(
+            &program_id,
+            &buffer,
+            &voters[0].pubkey(),
+            EchoInstruction::CastVote,
+        )],
+        &[&voters[0]],
+    )
+    .await;
+    assert_echo_error(result, EchoError::AlreadyVoted);
+    assert_eq!(payload(&mut context, &buffer).await, vec![0; BUFFER_SIZE]);
+}
@@ -0,0 +1,234 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::*,
+    echo::{
+        error::EchoError,
+        instruction::EchoInstruction,
+        state::{VendingMachineBufferHeader, VENDING_MACHINE_BUFF_HEADER_SIZE},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::{
+        signature::{Keypair, Signer},
+        transport::TransportError,
+    },
+};
+
+const PRICE: u64 = 5;
+const START_SLOT: u64 = 50;
+const END_SLOT: u64 = 100;
+
+async fn setup(context: &mut ProgramTestContext, program_id: &Pubkey) -> VendingMachine {
+    let mint_authority = Keypair::new();
+    let mint = create_mint(context, &mint_authority.pubkey(), 0)
+        .await
+        .pubkey();
+    let user = Keypair::new();
+    let user_token_account = create_token_account(context, &mint, &user.pubkey())
+        .await
+        .pubkey();
+    mint_tokens(context, &mint, &user_token_account, &mint_authority, 20).await;
+
+    let (buffer, _) = vending_machine_address(program_id, &mint, PRICE);
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &EchoInstruction::InitializeWindowedVendingMachine {
+                buffer_size: VENDING_MACHINE_BUFF_HEADER_SIZE + 16,
+                price: PRICE,
+                start_slot: START_SLOT,
+                end_slot: END_SLOT,
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(mint_authority.pubkey(), true),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+
+    VendingMachine {
+        mint_authority,
+        mint,
+        user,
+        user_token_account,
+        buffer,
+        price: PRICE,
+    }
+}
+
+fn set_window_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    admin: &Pubkey,
+    start_slot: u64,
+    end_slot: u64,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::SetVendingWindow {
+            start_slot,
+            end_slot,
+        },
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new_readonly(*admin, true),
+        ],
+    )
+}
+
+async fn write(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    vm: &VendingMachine,
+    data: Vec<u8>,
+) -> Result<(), TransportError> {
+    send(
+        context,
+        &[vending_machine_echo_ix(
+            program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            &vm.user_token_account,
+            &vm.mint,
+            data,
+        )],
+        &[&vm.user],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_windowed_vending_machine_writes_within_window() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup(&mut context, &program_id).await;
+
+    let data = account_data(&mut context, &vm.buffer).await;
+    let buffer_header =
+        VendingMachineBufferHeader::try_from_slice(&data[..VENDING_MACHINE_BUFF_HEADER_SIZE])
+            .unwrap();
+    assert_eq!(buffer_header.start_slot, START_SLOT);
+    assert_eq!(buffer_header.end_slot, END_SLOT);
+
+    // nothing is paid before the start
+    context.warp_to_slot(START_SLOT - 1).unwrap();
+    let result = write(&mut context, &program_id, &vm, vec![1; 4]).await;
+    assert_echo_error(result, EchoError::VendingMachineNotStarted);
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        20
+    );
+
+    context.warp_to_slot(START_SLOT).unwrap();
+    write(&mut context, &program_id, &vm, vec![1; 4])
+        .await
+        .unwrap();
+    context.warp_to_slot(END_SLOT - 1).unwrap();
+    write(&mut context, &program_id, &vm, vec![2; 4])
+        .await
+        .unwrap();
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        20 - 2 * PRICE
+    );
+
+    // nor after the end
+    context.warp_to_slot(END_SLOT).unwrap();
+    let result = write(&mut context, &program_id, &vm, vec![3; 4]).await;
+    assert_echo_error(result, EchoError::VendingMachineEnded);
+    assert_eq!(
+        token_balance(&mut context, &vm.user_token_account).await,
+        20 - 2 * PRICE
+    );
+}
+
+#[tokio::test]
+async fn test_set_vending_window_only_extends() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let vm = setup(&mut context, &program_id).await;
+    let admin = vm.mint_authority.pubkey();
+
+    for (start_slot, end_slot) in [(START_SLOT + 1, END_SLOT), (START_SLOT, END_SLOT - 1)] {
+        let result = send(
+            &mut context,
+            &[set_window_ix(
+                &program_id,
+                &vm.buffer,
+                &admin,
+                start_slot,
+                end_slot,
+            )],
+            &[&vm.mint_authority],
+        )
+        .await;
+        assert_echo_error(result, EchoError::InvalidInstructionInput);
+    }
+
+    // only the admin can extend it
+    let result = send(
+        &mut context,
+        &[set_window_ix(
+            &program_id,
+            &vm.buffer,
+            &vm.user.pubkey(),
+            0,
+            END_SLOT * 2,
+        )],
+        &[&vm.user],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+
+    send(
+        &mut context,
+        &[set_window_ix(
+            &program_id,
+            &vm.buffer,
+            &admin,
+            0,
+            END_SLOT * 2,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await
+    .unwrap();
+    write(&mut context, &program_id, &vm, vec![1; 4])
+        .await
+        .unwrap();
+    context.warp_to_slot(END_SLOT).unwrap();
+    write(&mut context, &program_id, &vm, vec![2; 4])
+        .await
+        .unwrap();
+
+    // an open start can't be closed again
+    let result = send(
+        &mut context,
+        &[set_window_ix(
+            &program_id,
+            &vm.buffer,
+            &admin,
+            1,
+            END_SLOT * 2,
+        )],
+        &[&vm.mint_authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InvalidInstructionInput);
+}
@@ -0,0 +1,342 @@
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use {
+    common::*,
+    echo::{error::EchoError, instruction::EchoInstruction, state::AUTH_BUFF_HEADER_SIZE},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        keccak,
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{tokio, ProgramTestContext},
+    solana_sdk::signature::{Keypair, Signer},
+};
+
+const WRITE_FEE: u64 = 5_000;
+
+fn fee_addresses(program_id: &Pubkey) -> (Pubkey, Pubkey) {
+    let (fee_config, _) = Pubkey::find_program_address(&[b"fee_config"], program_id);
+    let (fee_vault, _) = Pubkey::find_program_address(&[b"fee_vault"], program_id);
+    (fee_config, fee_vault)
+}
+
+/// Creates the fee config with `admin` and a buffer charging `WRITE_FEE` on every write.
+async fn setup(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    admin: &Pubkey,
+) -> (Keypair, Pubkey) {
+    let (fee_config, fee_vault) = fee_addresses(program_id);
+    let payer = context.payer.pubkey();
+    let authority = funded_keypair(context).await;
+    let (buffer, _) = authorized_buffer_address(program_id, &authority.pubkey(), 0);
+    send(
+        context,
+        &[
+            Instruction::new_with_borsh(
+                *program_id,
+                &EchoInstruction::InitializeFeeConfig { admin: *admin },
+                vec![
+                    AccountMeta::new(fee_config, false),
+                    AccountMeta::new(fee_vault, false),
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            ),
+            Instruction::new_with_borsh(
+                *program_id,
+                &EchoInstruction::InitializeAuthorizedEcho {
+                    buffer_seed: 0,
+                    buffer_size: AUTH_BUFF_HEADER_SIZE + 8,
+                    label: String::new(),
+                    permanent_delegate: None,
+                    write_fee_lamports: WRITE_FEE,
+                    expiry_slot: 0,
+                    crank_bounty_bps: 0,
+                },
+                vec![
+                    AccountMeta::new(buffer, false),
+                    AccountMeta::new(authority.pubkey(), true),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            ),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    (authority, buffer)
+}
+
+fn paid_write_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    paid_ix(
+        program_id,
+        buffer,
+        authority,
+        EchoInstruction::AuthorizedEcho { data },
+    )
+}
+
+fn paid_ix(
+    program_id: &Pubkey,
+    buffer: &Pubkey,
+    authority: &Pubkey,
+    instruction: EchoInstruction,
+) -> Instruction {
+    let (_, fee_vault) = fee_addresses(program_id);
+    Instruction::new_with_borsh(
+        *program_id,
+        &instruction,
+        vec![
+            AccountMeta::new(*buffer, false),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(fee_vault, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+fn withdraw_fees_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let (fee_config, fee_vault) = fee_addresses(program_id);
+    Instruction::new_with_borsh(
+        *program_id,
+        &EchoInstruction::WithdrawFees { amount },
+        vec![
+            AccountMeta::new_readonly(fee_config, false),
+            AccountMeta::new(fee_vault, false),
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(*destination, false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_writes_pay_fee_into_vault() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let admin = Keypair::new();
+    let (authority, buffer) = setup(&mut context, &program_id, &admin.pubkey()).await;
+    let (_, fee_vault) = fee_addresses(&program_id);
+
+    let initial_vault = context.banks_client.get_balance(fee_vault).await.unwrap();
+    let initial_authority = context
+        .banks_client
+        .get_balance(authority.pubkey())
+        .await
+        .unwrap();
+
+    for i in 0..3u8 {
+        send(
+            &mut context,
+            &[paid_write_ix(
+                &program_id,
+                &buffer,
+                &authority.pubkey(),
+                vec![i + 1; 8],
+            )],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+    }
+
+    assert_eq!(
+        context.banks_client.get_balance(fee_vault).await.unwrap(),
+        initial_vault + 3 * WRITE_FEE
+    );
+    // the transaction fees are paid by the context payer
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(authority.pubkey())
+            .await
+            .unwrap(),
+        initial_authority - 3 * WRITE_FEE
+    );
+    let data = account_data(&mut context, &buffer).await;
+    assert_eq!(&data[AUTH_BUFF_HEADER_SIZE..], &[3; 8]);
+
+    // the fee can't be skipped by leaving out the vault
+    let result = send(
+        &mut context,
+        &[authorized_echo_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            vec![9; 8],
+        )],
+        &[&authority],
+    )
+    .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_every_write_path_pays_fee() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let admin = Keypair::new();
+    let (authority, buffer) = setup(&mut context, &program_id, &admin.pubkey()).await;
+    let (_, fee_vault) = fee_addresses(&program_id);
+    let initial_vault = context.banks_client.get_balance(fee_vault).await.unwrap();
+
+    let data = vec![7; 4];
+    send(
+        &mut context,
+        &[Instruction::new_with_borsh(
+            program_id,
+            &EchoInstruction::CommitAuthorizedEcho {
+                hash: keccak::hash(&data).to_bytes(),
+            },
+            vec![
+                AccountMeta::new(buffer, false),
+                AccountMeta::new_readonly(authority.pubkey(), true),
+            ],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let slot = context.banks_client.get_root_slot().await.unwrap();
+    context.warp_to_slot(slot + 2).unwrap();
+
+    let writes = [
+        EchoInstruction::RevealAuthorizedEcho { data },
+        EchoInstruction::AppendAuthorizedEcho { data: vec![1; 4] },
+        EchoInstruction::AuthorizedEchoChunk {
+            offset: 0,
+            data: vec![2; 4],
+        },
+    ];
+    for write in writes {
+        // the fee can't be skipped by leaving out the vault
+        let result = send(
+            &mut context,
+            &[Instruction::new_with_borsh(
+                program_id,
+                &write,
+                vec![
+                    AccountMeta::new(buffer, false),
+                    AccountMeta::new(authority.pubkey(), true),
+                ],
+            )],
+            &[&authority],
+        )
+        .await;
+        assert!(result.is_err());
+
+        send(
+            &mut context,
+            &[paid_ix(&program_id, &buffer, &authority.pubkey(), write)],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+    }
+
+    assert_eq!(
+        context.banks_client.get_balance(fee_vault).await.unwrap(),
+        initial_vault + 3 * WRITE_FEE
+    );
+}
+
+#[tokio::test]
+async fn test_admin_withdraws_fees() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let admin = Keypair::new();
+    let (authority, buffer) = setup(&mut context, &program_id, &admin.pubkey()).await;
+    let (_, fee_vault) = fee_addresses(&program_id);
+
+    send(
+        &mut context,
+        &[
+            paid_write_ix(&program_id, &buffer, &authority.pubkey(), vec![1; 8]),
+            paid_write_ix(&program_id, &buffer, &authority.pubkey(), vec![2; 8]),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let destination = funded_keypair(&mut context).await.pubkey();
+    let initial_destination = context.banks_client.get_balance(destination).await.unwrap();
+    send(
+        &mut context,
+        &[withdraw_fees_ix(
+            &program_id,
+            &admin.pubkey(),
+            &destination,
+            2 * WRITE_FEE,
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        context.banks_client.get_balance(destination).await.unwrap(),
+        initial_destination + 2 * WRITE_FEE
+    );
+
+    // the rent exempt minimum stays in the vault
+    let result = send(
+        &mut context,
+        &[withdraw_fees_ix(
+            &program_id,
+            &admin.pubkey(),
+            &destination,
+            1,
+        )],
+        &[&admin],
+    )
+    .await;
+    assert_echo_error(result, EchoError::InsufficientFunds);
+    assert!(context.banks_client.get_balance(fee_vault).await.unwrap() > 0);
+}
+
+#[tokio::test]
+async fn test_non_admin_cannot_withdraw_fees() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let admin = Keypair::new();
+    let (authority, buffer) = setup(&mut context, &program_id, &admin.pubkey()).await;
+
+    send(
+        &mut context,
+        &[paid_write_ix(
+            &program_id,
+            &buffer,
+            &authority.pubkey(),
+            vec![1; 8],
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut context,
+        &[withdraw_fees_ix(
+            &program_id,
+            &authority.pubkey(),
+            &authority.pubkey(),
+            WRITE_FEE,
+        )],
+        &[&authority],
+    )
+    .await;
+    assert_echo_error(result, EchoError::UnauthorizedCaller);
+}